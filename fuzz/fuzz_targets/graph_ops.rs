@@ -0,0 +1,50 @@
+//! Applies the operations encoded in the input to a directed and an
+//! undirected graph and checks the structural invariants and the contents
+//! against a set based model after every operation. Every three bytes of
+//! input encode an operation and its two keys.
+#![no_main]
+use fastgraph::collections::*;
+use libfuzzer_sys::fuzz_target;
+use std::collections::BTreeSet;
+
+fn run<G: Graph<u8, u8, u16>>(data: &[u8]) {
+	let mut g = G::new();
+	let mut nodes = BTreeSet::new();
+	let mut edges = BTreeSet::new();
+	for op in data.chunks_exact(3) {
+		let (s, t) = (op[1] % 16, op[2] % 16);
+		match op[0] % 4 {
+			0 => {
+				assert_eq!(g.add_node(s, t), nodes.insert(s));
+			}
+			1 => {
+				edges.retain(|(a, b)| *a != s && *b != s);
+				assert_eq!(g.del_node(s), nodes.remove(&s));
+			}
+			2 => {
+				let known = nodes.contains(&s) && nodes.contains(&t);
+				if known {
+					edges.insert((s, t));
+				}
+				assert_eq!(g.add_edge(s, t, s as u16 * 16 + t as u16), known);
+			}
+			_ => {
+				edges.remove(&(s, t));
+				assert_eq!(g.del_edge(s, t), nodes.contains(&s) && nodes.contains(&t));
+			}
+		}
+		if let Err(violation) = g.validate() {
+			panic!("{}", violation);
+		}
+	}
+	assert_eq!(g.node_count(), nodes.len());
+	assert_eq!(g.edge_count(), edges.len());
+	for (s, t) in edges {
+		assert_eq!(g.get_edge(s, t).unwrap().load(), s as u16 * 16 + t as u16);
+	}
+}
+
+fuzz_target!(|data: &[u8]| {
+	run::<Digraph<u8, u8, u16>>(data);
+	run::<Ungraph<u8, u8, u16>>(data);
+});
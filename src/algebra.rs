@@ -0,0 +1,368 @@
+//=============================================================================
+// LINEAR ALGEBRA
+//=============================================================================
+
+//! Sparse matrix operations over semirings in the style of GraphBLAS. The
+//! adjacency structure of a frozen graph is turned into a sparse matrix whose
+//! entries are computed from the edges, and graph algorithms are expressed as
+//! products of matrices and vectors where addition and multiplication are
+//! replaced by the operations of a semiring. With `OrAnd` a product of the
+//! adjacency matrix and a frontier finds the next level of a breadth first
+//! search, with `MinPlus` it relaxes shortest path distances and with
+//! `PlusTimes` it counts walks. Every product is computed in parallel over
+//! the rows of the result.
+//!
+use crate::core::*;
+use crate::csr::Csr;
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Addition and multiplication with a zero that is the identity of addition.
+/// Entries missing from a sparse matrix are zero.
+pub trait Semiring: Sync + Send {
+	type Value: Clone + PartialEq + Sync + Send;
+
+	/// Identity of addition.
+	fn zero(&self) -> Self::Value;
+
+	fn add(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+
+	fn mul(&self, a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+/// Ordinary addition and multiplication of numbers.
+#[derive(Clone, Copy, Debug)]
+pub struct PlusTimes;
+
+impl Semiring for PlusTimes {
+	type Value = f64;
+
+	fn zero(&self) -> f64 {
+		0.0
+	}
+
+	fn add(&self, a: &f64, b: &f64) -> f64 {
+		a + b
+	}
+
+	fn mul(&self, a: &f64, b: &f64) -> f64 {
+		a * b
+	}
+}
+
+/// Minimum as addition and addition as multiplication, also known as the
+/// tropical semiring, for shortest paths.
+#[derive(Clone, Copy, Debug)]
+pub struct MinPlus;
+
+impl Semiring for MinPlus {
+	type Value = f64;
+
+	fn zero(&self) -> f64 {
+		f64::INFINITY
+	}
+
+	fn add(&self, a: &f64, b: &f64) -> f64 {
+		a.min(*b)
+	}
+
+	fn mul(&self, a: &f64, b: &f64) -> f64 {
+		a + b
+	}
+}
+
+/// Logical or as addition and logical and as multiplication, for
+/// reachability.
+#[derive(Clone, Copy, Debug)]
+pub struct OrAnd;
+
+impl Semiring for OrAnd {
+	type Value = bool;
+
+	fn zero(&self) -> bool {
+		false
+	}
+
+	fn add(&self, a: &bool, b: &bool) -> bool {
+		*a || *b
+	}
+
+	fn mul(&self, a: &bool, b: &bool) -> bool {
+		*a && *b
+	}
+}
+
+//=============================================================================
+
+/// Sparse matrix in compressed sparse row form. The columns of every row are
+/// sorted and unique.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix<V> {
+	rows: usize,
+	cols: usize,
+	offsets: Vec<usize>,
+	columns: Vec<usize>,
+	values: Vec<V>,
+}
+
+impl<V: Clone + PartialEq + Sync + Send> Matrix<V> {
+	/// Build a matrix from a list of row, column and value triplets. Values
+	/// at the same position are added with the semiring.
+	pub fn from_triplets<S>(rows: usize, cols: usize, semiring: &S, mut triplets: Vec<(usize, usize, V)>) -> Self
+	where
+		S: Semiring<Value = V>,
+	{
+		triplets.sort_by_key(|(i, j, _)| (*i, *j));
+		let mut entries: Vec<Vec<(usize, V)>> = vec![Vec::new(); rows];
+		for (i, j, v) in triplets {
+			match entries[i].last_mut() {
+				Some((last, acc)) if *last == j => { *acc = semiring.add(acc, &v) }
+				_ => { entries[i].push((j, v)) }
+			}
+		}
+		Matrix::from_rows(rows, cols, entries)
+	}
+
+	/// Adjacency matrix of a frozen graph where the entry at row `i` and
+	/// column `j` is computed from the edge between the nodes at indices `i`
+	/// and `j` with the `weight` closure. Undirected edges give symmetric
+	/// entries. Values of edges between the same nodes are added.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::algebra::*;
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 0..3 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(1, 2, Empty);
+	///
+	/// let csr = g.freeze();
+	/// let a = Matrix::adjacency(&csr, &OrAnd, |_| true);
+	/// let mut frontier = vec![false; 3];
+	/// frontier[csr.index(&0).unwrap()] = true;
+	///
+	/// // Two steps from node 0 reach node 2.
+	/// let reached = a.vxm(&OrAnd, &a.vxm(&OrAnd, &frontier));
+	/// assert!(reached[csr.index(&2).unwrap()]);
+	/// ```
+	pub fn adjacency<K, N, E, S, F>(csr: &Csr<K, N, E>, semiring: &S, weight: F) -> Self
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		S: Semiring<Value = V>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> V,
+	{
+		let n = csr.node_count();
+		let mut triplets = Vec::new();
+		for i in 0..n {
+			for (j, e) in csr.neighbors(i).iter().zip(csr.edge_ids(i).iter()) {
+				triplets.push((i, *j, weight(csr.edge(*e))));
+			}
+		}
+		Matrix::from_triplets(n, n, semiring, triplets)
+	}
+
+	/// Amount of rows.
+	pub fn rows(&self) -> usize {
+		self.rows
+	}
+
+	/// Amount of columns.
+	pub fn cols(&self) -> usize {
+		self.cols
+	}
+
+	/// Amount of stored entries.
+	pub fn nnz(&self) -> usize {
+		self.columns.len()
+	}
+
+	/// Columns and values of the entries of a row.
+	pub fn row(&self, i: usize) -> (&[usize], &[V]) {
+		let range = self.offsets[i]..self.offsets[i + 1];
+		(&self.columns[range.clone()], &self.values[range])
+	}
+
+	/// Entry at the given row and column, or None if it isn't stored.
+	pub fn get(&self, i: usize, j: usize) -> Option<&V> {
+		let (columns, values) = self.row(i);
+		columns.binary_search(&j).ok().map(|k| &values[k])
+	}
+
+	/// Matrix with rows and columns swapped.
+	pub fn transpose(&self) -> Self {
+		let mut offsets = vec![0; self.cols + 1];
+		for j in self.columns.iter() {
+			offsets[j + 1] += 1;
+		}
+		for j in 0..self.cols {
+			offsets[j + 1] += offsets[j];
+		}
+		let mut next = offsets.clone();
+		let mut slots: Vec<Option<(usize, V)>> = vec![None; self.columns.len()];
+		for i in 0..self.rows {
+			let (columns, values) = self.row(i);
+			for (j, v) in columns.iter().zip(values.iter()) {
+				slots[next[*j]] = Some((i, v.clone()));
+				next[*j] += 1;
+			}
+		}
+		let (columns, values) = slots.into_iter().flatten().unzip();
+		Matrix { rows: self.cols, cols: self.rows, offsets, columns, values }
+	}
+
+	/// Product of the matrix and a column vector, `y[i] = ⊕_j A[i][j] ⊗ x[j]`.
+	/// With an adjacency matrix this pulls values from the neighbors of every
+	/// node. Panics if the vector doesn't have an entry for every column.
+	pub fn mxv<S>(&self, semiring: &S, x: &[V]) -> Vec<V>
+	where
+		S: Semiring<Value = V>,
+	{
+		assert!(x.len() == self.cols, "mxv of a {}x{} matrix and a vector of length {}", self.rows, self.cols, x.len());
+		(0..self.rows)
+			.into_par_iter()
+			.map(|i| {
+				let (columns, values) = self.row(i);
+				columns.iter().zip(values.iter()).fold(semiring.zero(), |acc, (j, v)| {
+					semiring.add(&acc, &semiring.mul(v, &x[*j]))
+				})
+			})
+			.collect()
+	}
+
+	/// Product of a row vector and the matrix, `y[j] = ⊕_i x[i] ⊗ A[i][j]`.
+	/// With an adjacency matrix this pushes values to the neighbors of every
+	/// node. Computed from the transpose, so reuse `transpose().mxv` when
+	/// multiplying many vectors. Panics if the vector doesn't have an entry
+	/// for every row.
+	pub fn vxm<S>(&self, semiring: &S, x: &[V]) -> Vec<V>
+	where
+		S: Semiring<Value = V>,
+	{
+		assert!(x.len() == self.rows, "vxm of a vector of length {} and a {}x{} matrix", x.len(), self.rows, self.cols);
+		let t = self.transpose();
+		(0..t.rows)
+			.into_par_iter()
+			.map(|j| {
+				let (rows, values) = t.row(j);
+				rows.iter().zip(values.iter()).fold(semiring.zero(), |acc, (i, v)| {
+					semiring.add(&acc, &semiring.mul(&x[*i], v))
+				})
+			})
+			.collect()
+	}
+
+	/// Product of two matrices, `C[i][j] = ⊕_k A[i][k] ⊗ B[k][j]`. Entries of
+	/// the result that are zero aren't stored. Panics if this matrix doesn't
+	/// have as many columns as the other has rows.
+	pub fn mxm<S>(&self, semiring: &S, other: &Matrix<V>) -> Matrix<V>
+	where
+		S: Semiring<Value = V>,
+	{
+		assert!(self.cols == other.rows, "mxm of a {}x{} and a {}x{} matrix", self.rows, self.cols, other.rows, other.cols);
+		let rows: Vec<Vec<(usize, V)>> = (0..self.rows)
+			.into_par_iter()
+			.map(|i| {
+				let mut products = Vec::new();
+				let (columns, values) = self.row(i);
+				for (k, a) in columns.iter().zip(values.iter()) {
+					let (other_columns, other_values) = other.row(*k);
+					for (j, b) in other_columns.iter().zip(other_values.iter()) {
+						products.push((*j, semiring.mul(a, b)));
+					}
+				}
+				products.sort_by_key(|(j, _)| *j);
+				let mut row: Vec<(usize, V)> = Vec::new();
+				for (j, v) in products {
+					match row.last_mut() {
+						Some((last, acc)) if *last == j => { *acc = semiring.add(acc, &v) }
+						_ => { row.push((j, v)) }
+					}
+				}
+				row.retain(|(_, v)| *v != semiring.zero());
+				row
+			})
+			.collect();
+		Matrix::from_rows(self.rows, other.cols, rows)
+	}
+
+	/// Product of two matrices computed only at the positions stored in the
+	/// mask, for example `A ⊗ A` masked by `A` to count triangles. Each entry
+	/// is the dot product of a row of this matrix and a column of the other.
+	/// Panics if the matrices can't be multiplied or the mask doesn't have
+	/// the shape of the product.
+	pub fn mxm_masked<S, M>(&self, semiring: &S, other: &Matrix<V>, mask: &Matrix<M>) -> Matrix<V>
+	where
+		S: Semiring<Value = V>,
+		M: Clone + PartialEq + Sync + Send,
+	{
+		assert!(self.cols == other.rows, "mxm_masked of a {}x{} and a {}x{} matrix", self.rows, self.cols, other.rows, other.cols);
+		assert!(mask.rows == self.rows && mask.cols == other.cols, "mxm_masked of a {}x{} product with a {}x{} mask", self.rows, other.cols, mask.rows, mask.cols);
+		let t = other.transpose();
+		let rows: Vec<Vec<(usize, V)>> = (0..mask.rows)
+			.into_par_iter()
+			.map(|i| {
+				let (a_columns, a_values) = self.row(i);
+				let mut row = Vec::new();
+				for j in mask.row(i).0.iter() {
+					let (b_rows, b_values) = t.row(*j);
+					let (mut x, mut y) = (0, 0);
+					let mut acc = semiring.zero();
+					while x < a_columns.len() && y < b_rows.len() {
+						match a_columns[x].cmp(&b_rows[y]) {
+							std::cmp::Ordering::Less => { x += 1 }
+							std::cmp::Ordering::Greater => { y += 1 }
+							std::cmp::Ordering::Equal => {
+								acc = semiring.add(&acc, &semiring.mul(&a_values[x], &b_values[y]));
+								x += 1;
+								y += 1;
+							}
+						}
+					}
+					if acc != semiring.zero() {
+						row.push((*j, acc));
+					}
+				}
+				row
+			})
+			.collect();
+		Matrix::from_rows(mask.rows, other.cols, rows)
+	}
+
+	/// Sum of all stored entries with the semiring.
+	pub fn reduce<S>(&self, semiring: &S) -> V
+	where
+		S: Semiring<Value = V>,
+	{
+		self.values
+			.par_iter()
+			.fold(|| semiring.zero(), |acc, v| semiring.add(&acc, v))
+			.reduce(|| semiring.zero(), |a, b| semiring.add(&a, &b))
+	}
+
+	fn from_rows(rows: usize, cols: usize, entries: Vec<Vec<(usize, V)>>) -> Self {
+		let mut res = Matrix { rows, cols, offsets: Vec::with_capacity(rows + 1), columns: Vec::new(), values: Vec::new() };
+		res.offsets.push(0);
+		for row in entries {
+			for (j, v) in row {
+				res.columns.push(j);
+				res.values.push(v);
+			}
+			res.offsets.push(res.columns.len());
+		}
+		res
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,320 @@
+//=============================================================================
+// ANONYMIZATION
+//=============================================================================
+
+//! Transforms that make a graph safer to share by hiding who the nodes are
+//! while keeping the structure useful for analysis. Degree preserving edge
+//! swaps rewire the graph while every node keeps its degree, noise edges
+//! blur the neighborhoods an attacker could recognize, and stripping
+//! replaces the keys with random ids and drops or coarsens the data. Every
+//! transform keeps the graph simple, without loops or parallel edges, and
+//! the randomness comes from the generator of the caller. The degree
+//! anonymity of a graph, the smallest amount of nodes sharing a degree,
+//! measures how well the degrees alone hide the nodes.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use rand::{seq::SliceRandom, Rng};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// # Swap Edges
+///
+/// Rewire the graph with `swaps` degree preserving double edge swaps. A swap
+/// picks two edges `a -> b` and `c -> d` at random and replaces them with
+/// `a -> d` and `c -> b`, carrying the data of each edge along with its
+/// source. In a directed graph every node keeps its inbound and outbound
+/// degree. Swaps that would create a loop or a parallel edge are rejected,
+/// and after ten times as many attempts as swaps the rewiring gives up.
+/// Returns the amount of swaps made.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::anonymize::*;
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..20 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..20 {
+///     g.add_edge(i, (i + 1) % 20, Empty);
+/// }
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// assert!(swap_edges(&mut g, 50, &mut rng) > 0);
+/// assert!(g.edge_count() == 20);
+/// assert!(degree_anonymity(&g) == 20);
+/// ```
+pub fn swap_edges<K, N, E, G, R>(graph: &mut G, swaps: usize, rng: &mut R) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	R: Rng + ?Sized,
+{
+	let mut edges = Edges::new(graph);
+	if edges.list.len() < 2 {
+		return 0;
+	}
+	let mut res = 0;
+	for _ in 0..swaps.saturating_mul(10) {
+		if res == swaps {
+			break;
+		}
+		let i = rng.gen_range(0..edges.list.len());
+		let j = rng.gen_range(0..edges.list.len());
+		let ((a, b), (c, d)) = (edges.list[i].clone(), edges.list[j].clone());
+		// An undirected edge can be swapped in either orientation.
+		let (c, d) = match !G::directed() && rng.gen_bool(0.5) {
+			true => { (d, c) }
+			false => { (c, d) }
+		};
+		if i == j || a == d || c == b || edges.contains(&a, &d) || edges.contains(&c, &b) {
+			continue;
+		}
+		let (ab, cd) = match (graph.get_edge(a.clone(), b.clone()), edges.get(graph, &c, &d)) {
+			(Some(ab), Some(cd)) => { (ab.load(), cd.load()) }
+			_ => { continue }
+		};
+		edges.remove(graph, &a, &b);
+		edges.remove(graph, &c, &d);
+		graph.add_edge(a.clone(), d.clone(), ab);
+		graph.add_edge(c.clone(), b.clone(), cd);
+		edges.insert(i, a, d);
+		edges.insert(j, c, b);
+		res += 1;
+	}
+	res
+}
+
+/// # Inject Edges
+///
+/// Add `count` noise edges between random pairs of distinct nodes that
+/// aren't adjacent, with data given by the `edge` closure. Gives up after
+/// ten times as many attempts as edges. Returns the amount of edges added.
+pub fn inject_edges<K, N, E, G, F, R>(graph: &mut G, count: usize, mut edge: F, rng: &mut R) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: FnMut (&K, &K) -> E,
+	R: Rng + ?Sized,
+{
+	let keys: Vec<K> = graph.nodes().iter().map(|n| n.key().clone()).collect();
+	let mut edges = Edges::new(graph);
+	if keys.len() < 2 {
+		return 0;
+	}
+	let mut res = 0;
+	for _ in 0..count.saturating_mul(10) {
+		if res == count {
+			break;
+		}
+		let (a, b) = (keys.choose(rng).unwrap(), keys.choose(rng).unwrap());
+		if a == b || edges.contains(a, b) {
+			continue;
+		}
+		graph.add_edge(a.clone(), b.clone(), edge(a, b));
+		edges.insert(edges.list.len(), a.clone(), b.clone());
+		res += 1;
+	}
+	res
+}
+
+/// # Drop Edges
+///
+/// Delete `count` edges picked uniformly at random, or every edge if the
+/// graph has fewer. Returns the amount of edges deleted.
+pub fn drop_edges<K, N, E, G, R>(graph: &mut G, count: usize, rng: &mut R) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	R: Rng + ?Sized,
+{
+	let mut edges = Edges::new(graph).list;
+	let count = count.min(edges.len());
+	let (dropped, _) = edges.partial_shuffle(rng, count);
+	for (a, b) in dropped.iter() {
+		graph.del_edge(a.clone(), b.clone());
+	}
+	count
+}
+
+/// # Strip Attributes
+///
+/// Copy of the graph with the keys replaced by a random permutation of the
+/// ids from zero to the amount of nodes and the data of every node and edge
+/// transformed by the closures, which usually drop it or keep a coarse
+/// part of it. No mapping from the ids back to the keys is kept. The type
+/// of the new graph is chosen by the caller.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::anonymize::*;
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // People with their ages and how often they call each other.
+/// let mut g = Ungraph::<&str, u32, u32>::new();
+/// g.add_node("ann", 34);
+/// g.add_node("bob", 57);
+/// g.add_edge("ann", "bob", 12);
+///
+/// // Keep the decade of the age and drop the calls.
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let h: Ungraph<usize, u32, Empty> = strip_attributes(&g, |age| age / 10 * 10, |_| Empty, &mut rng);
+/// assert!(h.node_count() == 2 && h.edge_count() == 1);
+/// assert!(h.get_node(0).is_some() && h.get_node(1).is_some());
+/// ```
+pub fn strip_attributes<K, N, E, G, N2, E2, G2, FN, FE, R>(graph: &G, node: FN, edge: FE, rng: &mut R) -> G2
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	N2: Clone + Debug + Display + Sync + Send,
+	E2: Clone + Debug + Display + Sync + Send,
+	G2: Graph<usize, N2, E2>,
+	FN: Fn (N) -> N2,
+	FE: Fn (E) -> E2,
+	R: Rng + ?Sized,
+{
+	let nodes = graph.nodes();
+	let mut ids: Vec<usize> = (0..nodes.len()).collect();
+	ids.shuffle(rng);
+	let id: HashMap<K, usize> = nodes.iter().map(|n| n.key().clone()).zip(ids.iter().copied()).collect();
+	let mut g = G2::new();
+	// Nodes are added in the order of their ids so that the order of the
+	// new graph doesn't reveal the order of the old one.
+	let mut order: Vec<usize> = (0..nodes.len()).collect();
+	order.sort_by_key(|v| ids[*v]);
+	for v in order {
+		g.add_node(ids[v], node(nodes[v].load()));
+	}
+	for n in nodes.iter() {
+		for e in n.outbound().iter() {
+			g.add_edge(id[n.key()], id[e.target().key()], edge(e.load()));
+		}
+	}
+	g
+}
+
+/// # Degree Anonymity
+///
+/// The smallest amount of nodes sharing a degree, which makes the graph
+/// k-degree anonymous for this k. In a directed graph nodes share a degree
+/// if both their inbound and their outbound degree are equal. Zero for an
+/// empty graph.
+pub fn degree_anonymity<K, N, E, G>(graph: &G) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+	for node in graph.nodes() {
+		let degree = match G::directed() {
+			true => { (node.inbound().len(), node.outbound().len()) }
+			false => { (0, node.inbound().len() + node.outbound().len()) }
+		};
+		*counts.entry(degree).or_insert(0) += 1;
+	}
+	counts.values().copied().min().unwrap_or(0)
+}
+
+//=============================================================================
+
+// The edges of a graph as a list that can be sampled from together with a
+// set for checking adjacency, which holds both orientations of undirected
+// edges.
+struct Edges<K> {
+	list: Vec<(K, K)>,
+	set: HashSet<(K, K)>,
+	directed: bool,
+}
+
+impl<K> Edges<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	fn new<N, E, G>(graph: &G) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		let mut edges = Edges { list: Vec::new(), set: HashSet::new(), directed: G::directed() };
+		for node in graph.nodes() {
+			for edge in node.outbound().iter() {
+				let target = edge.target().key().clone();
+				edges.insert(edges.list.len(), node.key().clone(), target);
+			}
+		}
+		edges
+	}
+
+	fn contains(&self, a: &K, b: &K) -> bool {
+		self.set.contains(&(a.clone(), b.clone()))
+	}
+
+	// The edge in the orientation it's stored in the graph.
+	fn get<N, E, G>(&self, graph: &G, a: &K, b: &K) -> Option<Arc<Edge<K, N, E>>>
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		graph.get_edge(a.clone(), b.clone()).or_else(|| match self.directed {
+			true => { None }
+			false => { graph.get_edge(b.clone(), a.clone()) }
+		})
+	}
+
+	// Delete an edge from the graph and the set. Its place in the list is
+	// reused by the next insert.
+	fn remove<N, E, G>(&mut self, graph: &mut G, a: &K, b: &K)
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		match graph.get_edge(a.clone(), b.clone()).is_some() || self.directed {
+			true => { graph.del_edge(a.clone(), b.clone()) }
+			false => { graph.del_edge(b.clone(), a.clone()) }
+		};
+		self.set.remove(&(a.clone(), b.clone()));
+		if !self.directed {
+			self.set.remove(&(b.clone(), a.clone()));
+		}
+	}
+
+	fn insert(&mut self, i: usize, a: K, b: K) {
+		self.set.insert((a.clone(), b.clone()));
+		if !self.directed {
+			self.set.insert((b.clone(), a.clone()));
+		}
+		match i < self.list.len() {
+			true => { self.list[i] = (a, b) }
+			false => { self.list.push((a, b)) }
+		}
+	}
+}
+
+//=============================================================================
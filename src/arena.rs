@@ -0,0 +1,371 @@
+//=============================================================================
+// ARENA GRAPH
+//=============================================================================
+
+//! Mutable graph that owns its nodes and edges in two slabs and hands out
+//! lightweight ids instead of atomic references. Adding a node or an edge
+//! writes into a slot of a flat array rather than allocating, which makes
+//! bulk loading cheaper and keeps the data of neighboring nodes close
+//! together in memory. Slots of deleted nodes and edges are reused, and every
+//! id carries the generation of its slot so that ids of deleted items are
+//! never confused with the items that took their place.
+//!
+//! The arena is single threaded and accessed through `&self` and
+//! `&mut self`. Convert to and from the reference based graphs with
+//! `from_graph` and `to_graph` to use the algorithms of the crate.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+/// Id of a node in an `Arena`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId {
+	index: u32,
+	generation: u32,
+}
+
+/// Id of an edge in an `Arena`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId {
+	index: u32,
+	generation: u32,
+}
+
+impl NodeId {
+	/// Index of the slot of the node. Indices are below the capacity of the
+	/// arena and can be used to index arrays of node data.
+	pub fn index(&self) -> usize {
+		self.index as usize
+	}
+}
+
+impl EdgeId {
+	/// Index of the slot of the edge.
+	pub fn index(&self) -> usize {
+		self.index as usize
+	}
+}
+
+struct NodeSlot<K, N> {
+	key: K,
+	data: N,
+	outbound: Vec<EdgeId>,
+	inbound: Vec<EdgeId>,
+}
+
+struct EdgeSlot<E> {
+	source: NodeId,
+	target: NodeId,
+	data: E,
+}
+
+// A slab of slots where vacant slots are kept in a free list and every slot
+// counts how many times it has been vacated.
+struct Slab<T> {
+	slots: Vec<(u32, Option<T>)>,
+	free: Vec<u32>,
+	len: usize,
+}
+
+impl<T> Slab<T> {
+	fn with_capacity(capacity: usize) -> Self {
+		Slab { slots: Vec::with_capacity(capacity), free: Vec::new(), len: 0 }
+	}
+
+	fn insert(&mut self, item: T) -> (u32, u32) {
+		self.len += 1;
+		match self.free.pop() {
+			Some(index) => {
+				let slot = &mut self.slots[index as usize];
+				slot.1 = Some(item);
+				(index, slot.0)
+			}
+			None => {
+				self.slots.push((0, Some(item)));
+				(self.slots.len() as u32 - 1, 0)
+			}
+		}
+	}
+
+	fn remove(&mut self, index: u32, generation: u32) -> Option<T> {
+		let slot = self.slots.get_mut(index as usize)?;
+		if slot.0 != generation || slot.1.is_none() {
+			return None;
+		}
+		slot.0 = slot.0.wrapping_add(1);
+		self.free.push(index);
+		self.len -= 1;
+		slot.1.take()
+	}
+
+	fn get(&self, index: u32, generation: u32) -> Option<&T> {
+		match self.slots.get(index as usize) {
+			Some((g, Some(item))) if *g == generation => { Some(item) }
+			_ => { None }
+		}
+	}
+
+	fn get_mut(&mut self, index: u32, generation: u32) -> Option<&mut T> {
+		match self.slots.get_mut(index as usize) {
+			Some((g, Some(item))) if *g == generation => { Some(item) }
+			_ => { None }
+		}
+	}
+
+	fn iter(&self) -> impl Iterator<Item = (u32, u32, &T)> {
+		self.slots
+			.iter()
+			.enumerate()
+			.filter_map(|(i, (g, item))| item.as_ref().map(|item| (i as u32, *g, item)))
+	}
+}
+
+//=============================================================================
+
+/// # Arena
+///
+/// Graph stored in slabs and addressed by ids. An undirected arena lists
+/// every edge as outbound from its source and inbound to its target, like
+/// `Ungraph`, and follows edges both ways when exploring. Parallel edges are
+/// allowed.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::arena::*;
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Direction;
+///
+/// let mut a = Arena::<&str, u32, f64>::new(true);
+/// let x = a.add_node("x", 1).unwrap();
+/// let y = a.add_node("y", 2).unwrap();
+/// let e = a.add_edge(x, y, 0.5).unwrap();
+///
+/// assert!(a.neighbors(x, Direction::Outbound).collect::<Vec<_>>() == vec![y]);
+/// *a.node_mut(y).unwrap() += 10;
+/// assert!(a.del_node(x) == Some(1));
+/// assert!(a.edge(e).is_none() && a.edge_count() == 0);
+///
+/// let g: Digraph<&str, u32, f64> = a.to_graph();
+/// assert!(g.get_node("y").unwrap().load() == 12);
+/// ```
+pub struct Arena<K, N, E> {
+	directed: bool,
+	nodes: Slab<NodeSlot<K, N>>,
+	edges: Slab<EdgeSlot<E>>,
+	index: HashMap<K, NodeId>,
+}
+
+impl<K, N, E> Arena<K, N, E>
+where
+	K: Hash + Eq + Clone,
+{
+	/// Create an empty arena for a directed or an undirected graph.
+	pub fn new(directed: bool) -> Self {
+		Self::with_capacity(directed, 0, 0)
+	}
+
+	/// Create an empty arena with room for the given amount of nodes and
+	/// edges before it needs to grow.
+	pub fn with_capacity(directed: bool, nodes: usize, edges: usize) -> Self {
+		Arena {
+			directed,
+			nodes: Slab::with_capacity(nodes),
+			edges: Slab::with_capacity(edges),
+			index: HashMap::with_capacity(nodes),
+		}
+	}
+
+	/// True if the graph is directed.
+	pub fn directed(&self) -> bool {
+		self.directed
+	}
+
+	/// Amount of nodes in the graph.
+	pub fn node_count(&self) -> usize {
+		self.nodes.len
+	}
+
+	/// Amount of edges in the graph.
+	pub fn edge_count(&self) -> usize {
+		self.edges.len
+	}
+
+	/// Add a node, or return None if a node with the key already exists.
+	pub fn add_node(&mut self, key: K, data: N) -> Option<NodeId> {
+		if self.index.contains_key(&key) {
+			return None;
+		}
+		let slot = NodeSlot { key: key.clone(), data, outbound: Vec::new(), inbound: Vec::new() };
+		let (index, generation) = self.nodes.insert(slot);
+		let id = NodeId { index, generation };
+		self.index.insert(key, id);
+		Some(id)
+	}
+
+	/// Delete a node and its edges and return its data.
+	pub fn del_node(&mut self, id: NodeId) -> Option<N> {
+		let slot = self.nodes.get(id.index, id.generation)?;
+		let mut incident: Vec<EdgeId> = slot.outbound.iter().chain(slot.inbound.iter()).cloned().collect();
+		incident.sort();
+		incident.dedup();
+		for e in incident {
+			self.del_edge(e);
+		}
+		let slot = self.nodes.remove(id.index, id.generation)?;
+		self.index.remove(&slot.key);
+		Some(slot.data)
+	}
+
+	/// Id of the node with the key.
+	pub fn node_id(&self, key: &K) -> Option<NodeId> {
+		self.index.get(key).copied()
+	}
+
+	/// Key of the node.
+	pub fn key(&self, id: NodeId) -> Option<&K> {
+		self.nodes.get(id.index, id.generation).map(|n| &n.key)
+	}
+
+	/// Data of the node.
+	pub fn node(&self, id: NodeId) -> Option<&N> {
+		self.nodes.get(id.index, id.generation).map(|n| &n.data)
+	}
+
+	/// Mutable data of the node.
+	pub fn node_mut(&mut self, id: NodeId) -> Option<&mut N> {
+		self.nodes.get_mut(id.index, id.generation).map(|n| &mut n.data)
+	}
+
+	/// Ids of all nodes in slot order.
+	pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+		self.nodes.iter().map(|(index, generation, _)| NodeId { index, generation })
+	}
+
+	/// Add an edge between existing nodes, or return None if either node
+	/// doesn't exist.
+	pub fn add_edge(&mut self, source: NodeId, target: NodeId, data: E) -> Option<EdgeId> {
+		self.nodes.get(source.index, source.generation)?;
+		self.nodes.get(target.index, target.generation)?;
+		let (index, generation) = self.edges.insert(EdgeSlot { source, target, data });
+		let id = EdgeId { index, generation };
+		self.nodes.get_mut(source.index, source.generation)?.outbound.push(id);
+		self.nodes.get_mut(target.index, target.generation)?.inbound.push(id);
+		Some(id)
+	}
+
+	/// Delete an edge and return its data.
+	pub fn del_edge(&mut self, id: EdgeId) -> Option<E> {
+		let slot = self.edges.remove(id.index, id.generation)?;
+		if let Some(s) = self.nodes.get_mut(slot.source.index, slot.source.generation) {
+			if let Some(i) = s.outbound.iter().position(|e| *e == id) {
+				s.outbound.swap_remove(i);
+			}
+		}
+		if let Some(t) = self.nodes.get_mut(slot.target.index, slot.target.generation) {
+			if let Some(i) = t.inbound.iter().position(|e| *e == id) {
+				t.inbound.swap_remove(i);
+			}
+		}
+		Some(slot.data)
+	}
+
+	/// Source, target and data of the edge.
+	pub fn edge(&self, id: EdgeId) -> Option<(NodeId, NodeId, &E)> {
+		self.edges.get(id.index, id.generation).map(|e| (e.source, e.target, &e.data))
+	}
+
+	/// Mutable data of the edge.
+	pub fn edge_mut(&mut self, id: EdgeId) -> Option<&mut E> {
+		self.edges.get_mut(id.index, id.generation).map(|e| &mut e.data)
+	}
+
+	/// Find an edge from the source to the target. Undirected edges are also
+	/// found from the target to the source.
+	pub fn find_edge(&self, source: NodeId, target: NodeId) -> Option<EdgeId> {
+		let s = self.nodes.get(source.index, source.generation)?;
+		let forward = s.outbound.iter().find(|e| self.edges.get(e.index, e.generation).map(|e| e.target) == Some(target));
+		let backward = || s.inbound.iter().find(|e| self.edges.get(e.index, e.generation).map(|e| e.source) == Some(target));
+		match (forward, self.directed) {
+			(Some(e), _) => { Some(*e) }
+			(None, false) => { backward().copied() }
+			(None, true) => { None }
+		}
+	}
+
+	/// Ids of the edges leaving the node, which is empty if the node doesn't
+	/// exist.
+	pub fn outbound(&self, id: NodeId) -> &[EdgeId] {
+		self.nodes.get(id.index, id.generation).map(|n| n.outbound.as_slice()).unwrap_or(&[])
+	}
+
+	/// Ids of the edges entering the node, which is empty if the node doesn't
+	/// exist.
+	pub fn inbound(&self, id: NodeId) -> &[EdgeId] {
+		self.nodes.get(id.index, id.generation).map(|n| n.inbound.as_slice()).unwrap_or(&[])
+	}
+
+	/// Neighbors of the node along edges in the given direction.
+	pub fn neighbors(&self, id: NodeId, direction: Direction) -> impl Iterator<Item = NodeId> + '_ {
+		let outbound: &[EdgeId] = if direction != Direction::Inbound { self.outbound(id) } else { &[] };
+		let inbound: &[EdgeId] = if direction != Direction::Outbound { self.inbound(id) } else { &[] };
+		outbound
+			.iter()
+			.filter_map(move |e| self.edges.get(e.index, e.generation).map(|e| e.target))
+			.chain(inbound.iter().filter_map(move |e| self.edges.get(e.index, e.generation).map(|e| e.source)))
+	}
+
+	/// Direction in which edges are followed when exploring the graph.
+	pub fn direction(&self) -> Direction {
+		match self.directed {
+			true => { Direction::Outbound }
+			false => { Direction::Both }
+		}
+	}
+}
+
+impl<K, N, E> Arena<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Copy a reference based graph into an arena.
+	pub fn from_graph<G: Graph<K, N, E>>(graph: &G) -> Self {
+		let nodes = graph.nodes();
+		let mut res = Arena::with_capacity(G::directed(), nodes.len(), graph.edge_count());
+		for node in nodes.iter() {
+			res.add_node(node.key().clone(), node.load());
+		}
+		for node in nodes.iter() {
+			for edge in node.outbound().iter() {
+				if let (Some(s), Some(t)) = (res.node_id(node.key()), res.node_id(edge.target().key())) {
+					res.add_edge(s, t, edge.load());
+				}
+			}
+		}
+		res
+	}
+
+	/// Copy the arena into a reference based graph. Parallel edges are
+	/// merged into one the way `Graph::add_edge` merges them.
+	pub fn to_graph<G: Graph<K, N, E>>(&self) -> G {
+		let mut g = G::new();
+		for (_, _, node) in self.nodes.iter() {
+			g.add_node(node.key.clone(), node.data.clone());
+		}
+		for (_, _, edge) in self.edges.iter() {
+			if let (Some(s), Some(t)) = (self.key(edge.source), self.key(edge.target)) {
+				g.add_edge(s.clone(), t.clone(), edge.data.clone());
+			}
+		}
+		g
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,208 @@
+//=============================================================================
+// CACHE
+//=============================================================================
+
+//! Memoization of expensive computations keyed by the revision of the graph.
+//! A memoized result is returned for as long as the graph stays at the
+//! revision it was computed from and is recomputed on the first call after
+//! the graph changes. Memos are shared between threads, so a cache can live
+//! next to a `Shared` graph and serve many readers.
+//!
+use crate::centrality::{pagerank, NodeScores};
+use crate::collections::Graph;
+use crate::components::{connected_components, strongly_connected_components};
+use parking_lot::Mutex;
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// A memoized result of a computation with parameters of type `P`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::cache::Memo;
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use std::sync::Arc;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// g.add_node(1, Empty);
+///
+/// let memo = Memo::new();
+/// let a = memo.get(&g, |g| g.edge_count());
+/// let b = memo.get(&g, |_| unreachable!());
+/// assert!(Arc::ptr_eq(&a, &b));
+///
+/// g.add_edge(1, 1, Empty);
+/// assert!(*memo.get(&g, |g| g.edge_count()) == 1);
+/// ```
+pub struct Memo<T, P = ()> {
+	entry: Mutex<Option<(u64, P, Arc<T>)>>,
+}
+
+impl<T, P: PartialEq> Memo<T, P> {
+	pub fn new() -> Self {
+		Memo { entry: Mutex::new(None) }
+	}
+
+	/// Get the result computed from the graph with the given parameters, or
+	/// compute it if the graph or the parameters have changed. Concurrent
+	/// callers wait for the computation in progress instead of repeating it.
+	/// Results from graphs that don't have a revision are never memoized.
+	pub fn get_with<K, N, E, G, F>(&self, graph: &G, params: P, compute: F) -> Arc<T>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		F: FnOnce (&G, &P) -> T,
+	{
+		let revision = match graph.revision() {
+			Some(revision) => { revision }
+			None => { return Arc::new(compute(graph, &params)) }
+		};
+		let mut entry = self.entry.lock();
+		if let Some((r, p, result)) = entry.as_ref() {
+			if *r == revision && *p == params {
+				return result.clone();
+			}
+		}
+		let result = Arc::new(compute(graph, &params));
+		*entry = Some((revision, params, result.clone()));
+		result
+	}
+
+	/// True if a result for the current revision of the graph is memoized.
+	pub fn is_fresh<K, N, E, G>(&self, graph: &G) -> bool
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		match (self.entry.lock().as_ref(), graph.revision()) {
+			(Some((r, _, _)), Some(revision)) => { *r == revision }
+			_ => { false }
+		}
+	}
+
+	/// Drop the memoized result.
+	pub fn clear(&self) {
+		*self.entry.lock() = None;
+	}
+}
+
+impl<T> Memo<T, ()> {
+	/// Get the result computed from the graph, or compute it if the graph
+	/// has changed.
+	pub fn get<K, N, E, G, F>(&self, graph: &G, compute: F) -> Arc<T>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		F: FnOnce (&G) -> T,
+	{
+		self.get_with(graph, (), |graph, _| compute(graph))
+	}
+}
+
+impl<T, P: PartialEq> Default for Memo<T, P> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+//=============================================================================
+
+// Parameters of PageRank: damping, maximum iterations and tolerance.
+type PagerankParams = (f64, usize, f64);
+
+/// Memoized results of the common whole graph computations of one graph.
+/// Memoized scores hold references to nodes, which keeps deleted nodes alive
+/// until the scores are recomputed or the cache is cleared.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::cache::Cache;
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, Empty);
+///
+/// let cache = Cache::new();
+/// assert!(cache.components(&g).len() == 3);
+/// assert!(cache.components(&g).len() == 3);
+///
+/// g.add_edge(2, 3, Empty);
+/// assert!(cache.components(&g).len() == 2);
+/// ```
+pub struct Cache<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	components: Memo<Vec<Vec<K>>>,
+	strongly_connected_components: Memo<Vec<Vec<K>>>,
+	pagerank: Memo<NodeScores<K, N, E>, PagerankParams>,
+}
+
+impl<K, N, E> Cache<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	pub fn new() -> Self {
+		Cache {
+			components: Memo::new(),
+			strongly_connected_components: Memo::new(),
+			pagerank: Memo::new(),
+		}
+	}
+
+	/// Memoized `components::connected_components`.
+	pub fn components<G: Graph<K, N, E>>(&self, graph: &G) -> Arc<Vec<Vec<K>>> {
+		self.components.get(graph, |graph| connected_components(graph))
+	}
+
+	/// Memoized `components::strongly_connected_components`.
+	pub fn strongly_connected_components<G: Graph<K, N, E>>(&self, graph: &G) -> Arc<Vec<Vec<K>>> {
+		self.strongly_connected_components.get(graph, |graph| strongly_connected_components(graph))
+	}
+
+	/// Memoized `centrality::pagerank`. Calls with different parameters
+	/// recompute the scores.
+	pub fn pagerank<G: Graph<K, N, E>>(&self, graph: &G, damping: f64, max_iterations: usize, tolerance: f64) -> Arc<NodeScores<K, N, E>> {
+		self.pagerank.get_with(graph, (damping, max_iterations, tolerance), |graph, &(d, m, t)| pagerank(graph, d, m, t))
+	}
+
+	/// Drop every memoized result.
+	pub fn clear(&self) {
+		self.components.clear();
+		self.strongly_connected_components.clear();
+		self.pagerank.clear();
+	}
+}
+
+impl<K, N, E> Default for Cache<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,677 @@
+//=============================================================================
+// CENTRALITY
+//=============================================================================
+
+//! Centrality measures rank the nodes or edges of a graph by importance.
+//! The computations are parallelized over the nodes of the graph.
+//!
+use crate::checkpoint::Checkpoint;
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::sketch::hash;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::{HashMap, VecDeque},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Nodes paired with a score.
+pub type NodeScores<K, N, E> = Vec<(Arc<Node<K, N, E>>, f64)>;
+
+/// Edges paired with a score.
+pub type EdgeScores<K, N, E> = Vec<(Arc<Edge<K, N, E>>, f64)>;
+
+/// Hub and authority scores computed by the HITS algorithm together with
+/// the amount of iterations it took to converge.
+pub struct Hits<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	pub hubs: NodeScores<K, N, E>,
+	pub authorities: NodeScores<K, N, E>,
+	pub iterations: usize,
+}
+
+/// How an iterative algorithm converged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Convergence {
+	/// Residual of every iteration run, in order, where the residual of an
+	/// iteration is the total change of the values in it.
+	pub residuals: Vec<f64>,
+	/// Whether the last residual fell below the tolerance before the
+	/// iterations ran out.
+	pub converged: bool,
+}
+
+impl Convergence {
+	/// Amount of iterations run.
+	pub fn iterations(&self) -> usize {
+		self.residuals.len()
+	}
+}
+
+/// Progress of an edge betweenness computation, see
+/// `edge_betweenness_resume`. Its units of work are the source nodes and its
+/// values the partial scores of the edges by the keys of their source and
+/// target.
+pub type BetweennessCheckpoint<K> = Checkpoint<(K, K, f64)>;
+
+/// How an iterative algorithm updates the values of the nodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Update {
+	/// Every iteration computes the new values from the values of the
+	/// previous iteration, in parallel over the nodes.
+	Synchronous,
+	/// Gauss-Seidel iteration, which updates the values in place one node at
+	/// a time so that later nodes already see the new values of earlier
+	/// ones. It often needs fewer iterations but runs on one thread. Nodes
+	/// are visited in an order given by their keys, shuffled by the seed in
+	/// every iteration if there is one, so a graph with the same nodes and
+	/// edges gives the same results in every run.
+	Asynchronous { seed: Option<u64> },
+}
+
+//=============================================================================
+
+/// # Edge Betweenness
+///
+/// Compute the edge betweenness centrality of every edge in the graph ie. the
+/// amount of shortest paths between pairs of nodes that pass through the
+/// edge. Paths are unweighted and follow the direction of the graph. In an
+/// undirected graph each pair of nodes is counted once.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// g.add_node(1, Empty);
+/// g.add_node(2, Empty);
+/// g.add_node(3, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 3, Empty);
+///
+/// for (_, score) in edge_betweenness(&g) {
+///     assert!(score == 2.0);
+/// }
+/// ```
+pub fn edge_betweenness<K, N, E, G>(graph: &G) -> EdgeScores<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let removed = vec![false; indexed.edges.len()];
+	let sources: Vec<usize> = (0..indexed.nodes.len()).collect();
+	let mut scores = brandes(&indexed.adjacency, &removed, &sources);
+	if !G::directed() {
+		for score in scores.iter_mut() {
+			*score /= 2.0;
+		}
+	}
+	indexed.edges.into_iter().zip(scores).collect()
+}
+
+/// # Resumable Edge Betweenness
+///
+/// Compute edge betweenness like `edge_betweenness` a batch of at most
+/// `batch` source nodes at a time, recording the progress in the
+/// checkpoint. Returns the scores once every source has been processed and
+/// None before that. Sources are processed in an order that is the same in
+/// every run, so a checkpoint saved to disk can be resumed by another
+/// process as long as the graph hasn't changed. A checkpoint of a graph
+/// with a different amount of nodes is started over. A batch of zero is
+/// taken as one.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 3, Empty);
+///
+/// let mut checkpoint = BetweennessCheckpoint::new();
+/// assert!(edge_betweenness_resume(&g, &mut checkpoint, 3).is_none());
+/// assert!(checkpoint.done == 3);
+///
+/// // Every pair of nodes counts every edge on the path between them.
+/// let scores = edge_betweenness_resume(&g, &mut checkpoint, 3).unwrap();
+/// assert!(checkpoint.finished());
+/// assert!(scores.iter().map(|(_, score)| score).sum::<f64>() == 10.0);
+/// ```
+pub fn edge_betweenness_resume<K, N, E, G>(
+	graph: &G,
+	checkpoint: &mut BetweennessCheckpoint<K>,
+	batch: usize,
+) -> Option<EdgeScores<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let n = indexed.nodes.len();
+	let range = checkpoint.next(n, batch);
+	let position: HashMap<(K, K), usize> = indexed.edges
+		.iter()
+		.enumerate()
+		.map(|(i, e)| ((e.source().key().clone(), e.target().key().clone()), i))
+		.collect();
+	let mut scores = vec![0.0; indexed.edges.len()];
+	for (s, t, score) in checkpoint.values.drain(..) {
+		if let Some(i) = position.get(&(s, t)) {
+			scores[*i] += score;
+		}
+	}
+	let order = stable_order(&indexed);
+	let _span = trace_span!("edge_betweenness_resume", from = range.start, to = range.end);
+	let removed = vec![false; indexed.edges.len()];
+	let partial = brandes(&indexed.adjacency, &removed, &order[range.clone()]);
+	for (score, x) in scores.iter_mut().zip(partial) {
+		*score += x;
+	}
+	checkpoint.done = range.end;
+	if range.end < n {
+		checkpoint.values = indexed.edges
+			.iter()
+			.zip(scores)
+			.filter(|(_, score)| *score != 0.0)
+			.map(|(e, score)| (e.source().key().clone(), e.target().key().clone(), score))
+			.collect();
+		return None;
+	}
+	if !G::directed() {
+		for score in scores.iter_mut() {
+			*score /= 2.0;
+		}
+	}
+	Some(indexed.edges.into_iter().zip(scores).collect())
+}
+
+// Brandes' algorithm for edge betweenness over an adjacency list of pairs of
+// neighbor and edge index, summed over the given source nodes. Edges marked
+// as removed are ignored. Sources are processed in parallel, each with its
+// own accumulator.
+pub(crate) fn brandes(adjacency: &[Vec<(usize, usize)>], removed: &[bool], sources: &[usize]) -> Vec<f64> {
+	let n = adjacency.len();
+	let m = removed.len();
+	sources
+		.into_par_iter()
+		.map(|s| {
+			let s = *s;
+			let mut scores = vec![0.0; m];
+			let mut sigma = vec![0.0; n];
+			let mut dist = vec![usize::MAX; n];
+			let mut delta = vec![0.0; n];
+			let mut preds: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+			let mut order = Vec::new();
+			let mut queue = VecDeque::new();
+			sigma[s] = 1.0;
+			dist[s] = 0;
+			queue.push_back(s);
+			while let Some(v) = queue.pop_front() {
+				order.push(v);
+				for (w, e) in adjacency[v].iter() {
+					if removed[*e] {
+						continue;
+					}
+					if dist[*w] == usize::MAX {
+						dist[*w] = dist[v] + 1;
+						queue.push_back(*w);
+					}
+					if dist[*w] == dist[v] + 1 {
+						sigma[*w] += sigma[v];
+						preds[*w].push((v, *e));
+					}
+				}
+			}
+			for w in order.into_iter().rev() {
+				for (v, e) in preds[w].iter() {
+					let c = sigma[*v] / sigma[w] * (1.0 + delta[w]);
+					scores[*e] += c;
+					delta[*v] += c;
+				}
+			}
+			scores
+		})
+		.reduce(
+			|| vec![0.0; m],
+			|mut a, b| {
+				for (x, y) in a.iter_mut().zip(b) {
+					*x += y;
+				}
+				a
+			},
+		)
+}
+
+//=============================================================================
+
+/// # HITS
+///
+/// Compute hub and authority scores with the HITS algorithm. A good hub
+/// points to many good authorities and a good authority is pointed to by
+/// many good hubs. Scores are updated in parallel and normalized to sum to
+/// one. Iteration stops after `max_iterations` or once the total change of
+/// the scores in an iteration falls below `tolerance`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// g.add_node(1, Empty);
+/// g.add_node(2, Empty);
+/// g.add_node(3, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(1, 3, Empty);
+///
+/// let hits = hits(&g, 100, 1e-9);
+///
+/// for (node, hub) in hits.hubs {
+///     if node.key() == &1 {
+///         assert!(hub == 1.0);
+///     }
+/// }
+/// ```
+pub fn hits<K, N, E, G>(graph: &G, max_iterations: usize, tolerance: f64) -> Hits<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let n = indexed.nodes.len();
+	let outbound: Vec<Vec<usize>> = indexed.adjacency
+		.iter()
+		.map(|adjacent| adjacent.iter().map(|(w, _)| *w).collect())
+		.collect();
+	let mut inbound = vec![Vec::new(); n];
+	for (u, adjacent) in outbound.iter().enumerate() {
+		for w in adjacent.iter() {
+			inbound[*w].push(u);
+		}
+	}
+	let mut hubs = vec![1.0 / n as f64; n];
+	let mut authorities = vec![1.0 / n as f64; n];
+	let mut iterations = 0;
+	let _span = trace_span!("hits", nodes = n);
+	while iterations < max_iterations {
+		iterations += 1;
+		let new_authorities = normalize(propagate(&inbound, &hubs));
+		let new_hubs = normalize(propagate(&outbound, &new_authorities));
+		let delta = difference(&hubs, &new_hubs) + difference(&authorities, &new_authorities);
+		trace_event!("iteration", iteration = iterations, delta = delta);
+		hubs = new_hubs;
+		authorities = new_authorities;
+		if delta < tolerance {
+			break;
+		}
+	}
+	Hits {
+		hubs: indexed.nodes.iter().cloned().zip(hubs).collect(),
+		authorities: indexed.nodes.into_iter().zip(authorities).collect(),
+		iterations,
+	}
+}
+
+// Sum the scores of the adjacent nodes of every node in parallel.
+fn propagate(adjacency: &[Vec<usize>], scores: &[f64]) -> Vec<f64> {
+	adjacency
+		.into_par_iter()
+		.map(|adjacent| adjacent.iter().map(|w| scores[*w]).sum())
+		.collect()
+}
+
+// Scale scores to sum to one. A vector of zeros is left as is.
+fn normalize(mut scores: Vec<f64>) -> Vec<f64> {
+	let sum: f64 = scores.iter().sum();
+	if sum > 0.0 {
+		for score in scores.iter_mut() {
+			*score /= sum;
+		}
+	}
+	scores
+}
+
+// Indices of the nodes ordered by a hash of their keys that is the same in
+// every run, rather than by the order of the nodes in the graph.
+pub(crate) fn stable_order<K, N, E>(indexed: &Indexed<K, N, E>) -> Vec<usize>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut order: Vec<usize> = (0..indexed.nodes.len()).collect();
+	order.sort_by_cached_key(|i| hash(indexed.nodes[*i].key()));
+	order
+}
+
+// L1 distance between two score vectors.
+fn difference(a: &[f64], b: &[f64]) -> f64 {
+	a.iter().zip(b).map(|(x, y)| (x - y).abs()).sum()
+}
+
+//=============================================================================
+
+/// # PageRank
+///
+/// Compute the PageRank of every node in the graph with power iteration. At
+/// each step the random surfer follows an edge with probability `damping`
+/// and jumps to a random node otherwise. Scores sum to one. Iteration stops
+/// after `max_iterations` or once the total change of the scores in an
+/// iteration falls below `tolerance`. For very large graphs, and to see the
+/// change of every iteration, use `Csr::par_pagerank` instead.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// g.add_node(1, Empty);
+/// g.add_node(2, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 1, Empty);
+///
+/// for (_, score) in pagerank(&g, 0.85, 100, 1e-9) {
+///     assert!((score - 0.5).abs() < 1e-9);
+/// }
+/// ```
+pub fn pagerank<K, N, E, G>(graph: &G, damping: f64, max_iterations: usize, tolerance: f64) -> NodeScores<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let n = indexed.nodes.len();
+	let teleport = vec![1.0 / n as f64; n];
+	let (scores, _) = power_iteration(&indexed.adjacency, &teleport, damping, max_iterations, tolerance, Update::Synchronous, Vec::new());
+	indexed.nodes.into_iter().zip(scores).collect()
+}
+
+/// PageRank like `pagerank` where the scores are updated as given by
+/// `update`. Returns the scores together with the residual of every
+/// iteration, which is the total change of the scores in it.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// for (s, t) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 0)] {
+///     g.add_edge(s, t, Empty);
+/// }
+///
+/// let (jacobi, a) = pagerank_with(&g, 0.85, 200, 1e-12, Update::Synchronous);
+/// let (seidel, b) = pagerank_with(&g, 0.85, 200, 1e-12, Update::Asynchronous { seed: Some(7) });
+/// assert!(a.converged && b.converged);
+/// for ((_, x), (_, y)) in jacobi.iter().zip(seidel.iter()) {
+///     assert!((x - y).abs() < 1e-9);
+/// }
+/// ```
+pub fn pagerank_with<K, N, E, G>(
+	graph: &G,
+	damping: f64,
+	max_iterations: usize,
+	tolerance: f64,
+	update: Update,
+) -> (NodeScores<K, N, E>, Convergence)
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let n = indexed.nodes.len();
+	let teleport = vec![1.0 / n as f64; n];
+	let order = stable_order(&indexed);
+	let (scores, convergence) = power_iteration(&indexed.adjacency, &teleport, damping, max_iterations, tolerance, update, order);
+	(indexed.nodes.into_iter().zip(scores).collect(), convergence)
+}
+
+/// # Personalized PageRank
+///
+/// PageRank where the random surfer restarts from one of the `seeds` instead
+/// of a random node, also known as random walk with restart. The scores
+/// measure how related each node is to the seed nodes. Computed exactly with
+/// power iteration over the whole graph. Returns None if none of the seeds
+/// can be found.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// g.add_node(1, Empty);
+/// g.add_node(2, Empty);
+/// g.add_node(3, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 1, Empty);
+///
+/// for (node, score) in personalized_pagerank(&g, &[1], 0.85, 100, 1e-9).unwrap() {
+///     if node.key() == &3 {
+///         assert!(score == 0.0);
+///     }
+/// }
+/// ```
+pub fn personalized_pagerank<K, N, E, G>(
+	graph: &G,
+	seeds: &[K],
+	damping: f64,
+	max_iterations: usize,
+	tolerance: f64,
+) -> Option<NodeScores<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let mut teleport = vec![0.0; indexed.nodes.len()];
+	let found: Vec<usize> = indexed.nodes
+		.iter()
+		.enumerate()
+		.filter(|(_, node)| seeds.contains(node.key()))
+		.map(|(i, _)| i)
+		.collect();
+	if found.is_empty() {
+		return None;
+	}
+	for i in found.iter() {
+		teleport[*i] = 1.0 / found.len() as f64;
+	}
+	let (scores, _) = power_iteration(&indexed.adjacency, &teleport, damping, max_iterations, tolerance, Update::Synchronous, Vec::new());
+	Some(indexed.nodes.into_iter().zip(scores).collect())
+}
+
+/// # Approximate Personalized PageRank
+///
+/// Approximate personalized PageRank with local push operations. Residual
+/// probability mass starts at the seeds and is pushed to neighbors until the
+/// residual of every node is below `epsilon` times its degree. Only nodes
+/// near the seeds are ever visited, which makes this suitable for queries on
+/// very large graphs. Returns the nodes that received a score, or None if
+/// none of the seeds can be found.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// g.add_node(1, Empty);
+/// g.add_node(2, Empty);
+/// g.add_node(3, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 1, Empty);
+///
+/// let scores = approximate_personalized_pagerank(&g, &[1], 0.85, 1e-6).unwrap();
+///
+/// assert!(scores.len() == 2);
+/// ```
+pub fn approximate_personalized_pagerank<K, N, E, G>(
+	graph: &G,
+	seeds: &[K],
+	damping: f64,
+	epsilon: f64,
+) -> Option<NodeScores<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let seeds: Vec<Arc<Node<K, N, E>>> = seeds.iter().filter_map(|k| graph.get_node(k.clone())).collect();
+	if seeds.is_empty() {
+		return None;
+	}
+	let restart = 1.0 / seeds.len() as f64;
+	let mut nodes: HashMap<K, Arc<Node<K, N, E>>> = HashMap::new();
+	let mut scores: HashMap<K, f64> = HashMap::new();
+	let mut residual: HashMap<K, f64> = HashMap::new();
+	let mut queue = VecDeque::new();
+	for seed in seeds.iter() {
+		nodes.insert(seed.key().clone(), seed.clone());
+		residual.insert(seed.key().clone(), restart);
+		queue.push_back(seed.clone());
+	}
+	while let Some(node) = queue.pop_front() {
+		let r = residual.insert(node.key().clone(), 0.0).unwrap_or(0.0);
+		let adjacent = node.neighbors(G::direction());
+		if r == 0.0 || r < epsilon * adjacent.len() as f64 {
+			*residual.get_mut(node.key()).unwrap() += r;
+			continue;
+		}
+		*scores.entry(node.key().clone()).or_insert(0.0) += (1.0 - damping) * r;
+		// Mass at a node without neighbors restarts from the seeds.
+		let targets = match adjacent.is_empty() {
+			true => { seeds.clone() }
+			false => { adjacent }
+		};
+		let share = damping * r / targets.len() as f64;
+		for next in targets {
+			let old = residual.entry(next.key().clone()).or_insert(0.0);
+			*old += share;
+			let degree = next.neighbors(G::direction()).len().max(1);
+			if *old >= epsilon * degree as f64 && *old - share < epsilon * degree as f64 {
+				queue.push_back(next.clone());
+			}
+			nodes.entry(next.key().clone()).or_insert(next);
+		}
+	}
+	Some(scores.into_iter().map(|(k, score)| (nodes[&k].clone(), score)).collect())
+}
+
+// Power iteration of PageRank with the given teleport distribution. Each
+// iteration pulls the scores of the inbound neighbors of every node, in
+// parallel or in place one node at a time in the given order. Mass at nodes
+// without outbound edges is teleported.
+fn power_iteration(
+	adjacency: &[Vec<(usize, usize)>],
+	teleport: &[f64],
+	damping: f64,
+	max_iterations: usize,
+	tolerance: f64,
+	update: Update,
+	mut order: Vec<usize>,
+) -> (Vec<f64>, Convergence) {
+	let n = adjacency.len();
+	let mut inbound = vec![Vec::new(); n];
+	for (u, adjacent) in adjacency.iter().enumerate() {
+		for (w, _) in adjacent.iter() {
+			inbound[*w].push(u);
+		}
+	}
+	let degree: Vec<f64> = adjacency.iter().map(|adjacent| adjacent.len() as f64).collect();
+	let mut scores = teleport.to_vec();
+	let mut convergence = Convergence::default();
+	let mut rng = match update {
+		Update::Asynchronous { seed: Some(seed) } => { Some(StdRng::seed_from_u64(seed)) }
+		_ => { None }
+	};
+	let _span = trace_span!("power_iteration", nodes = n, damping = damping);
+	for iteration in 0..max_iterations {
+		let mut dangling: f64 = (0..n).filter(|u| degree[*u] == 0.0).map(|u| scores[u]).sum();
+		let delta = match update {
+			Update::Synchronous => {
+				let new_scores: Vec<f64> = (0..n)
+					.into_par_iter()
+					.map(|v| {
+						let pulled: f64 = inbound[v].iter().map(|u| scores[*u] / degree[*u]).sum();
+						(1.0 - damping) * teleport[v] + damping * (pulled + dangling * teleport[v])
+					})
+					.collect();
+				let delta = difference(&scores, &new_scores);
+				scores = new_scores;
+				delta
+			}
+			Update::Asynchronous { .. } => {
+				if let Some(rng) = rng.as_mut() {
+					order.shuffle(rng);
+				}
+				let mut delta = 0.0;
+				for v in order.iter().copied() {
+					let pulled: f64 = inbound[v].iter().map(|u| scores[*u] / degree[*u]).sum();
+					let score = (1.0 - damping) * teleport[v] + damping * (pulled + dangling * teleport[v]);
+					if degree[v] == 0.0 {
+						dangling += score - scores[v];
+					}
+					delta += (score - scores[v]).abs();
+					scores[v] = score;
+				}
+				delta
+			}
+		};
+		trace_event!("iteration", iteration = iteration, delta = delta);
+		convergence.residuals.push(delta);
+		if delta < tolerance {
+			convergence.converged = true;
+			break;
+		}
+	}
+	(scores, convergence)
+}
+
+//=============================================================================
@@ -0,0 +1,64 @@
+//=============================================================================
+// CHECKPOINT
+//=============================================================================
+
+//! Progress of long computations that are split into independent units of
+//! work, such as one shortest path search per source node or one random
+//! walk, so that they can be stopped and resumed later. A computation
+//! processes a batch of units at a time in an order that is the same in
+//! every run and records the partial results in its checkpoint, until it
+//! returns the results of every unit. The next call starts a finished
+//! checkpoint over. With the `serde` feature a checkpoint can be saved
+//! between runs, so that a computation over a large graph survives
+//! restarts.
+//!
+//! Resumable computations are `edge_betweenness_resume` in `centrality`,
+//! `all_pairs_resume` in `shortest_path` and `walks_resume` of the walk
+//! generators in `embedding`.
+//!
+use std::ops::Range;
+
+//=============================================================================
+
+/// Progress of a computation that can be stopped and resumed later.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Checkpoint<T> {
+	/// Amount of units of work in the computation, None before it starts.
+	pub total: Option<usize>,
+	/// Amount of units of work processed so far.
+	pub done: usize,
+	/// Partial results of the units processed so far.
+	pub values: Vec<T>,
+}
+
+impl<T> Checkpoint<T> {
+	/// A checkpoint of a computation that hasn't started.
+	pub fn new() -> Self {
+		Checkpoint { total: None, done: 0, values: Vec::new() }
+	}
+
+	/// Whether every unit of work has been processed.
+	pub fn finished(&self) -> bool {
+		self.total == Some(self.done)
+	}
+
+	// Start the computation over if it has finished or the checkpoint was
+	// recorded for a different amount of units, and return the range of units
+	// of the next batch of at most `batch` units. A batch of zero is taken as
+	// one so that every call makes progress.
+	pub(crate) fn next(&mut self, total: usize, batch: usize) -> Range<usize> {
+		if self.total != Some(total) || self.done >= total {
+			*self = Checkpoint { total: Some(total), done: 0, values: Vec::new() };
+		}
+		self.done..total.min(self.done.saturating_add(batch.max(1)))
+	}
+}
+
+impl<T> Default for Checkpoint<T> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+//=============================================================================
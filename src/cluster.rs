@@ -0,0 +1,371 @@
+//=============================================================================
+// CLUSTER
+//=============================================================================
+
+//! Pregel computations over a graph split into shards, with one worker for
+//! every shard, for example in a separate process or on a separate machine
+//! so that no worker needs to hold the whole graph. Every worker computes
+//! the nodes its shard owns like `pregel` does and sends the messages
+//! addressed to nodes of other shards to their workers at the end of every
+//! superstep over a `Transport`. The workers run their supersteps in
+//! lockstep, and the computation ends for all of them once none of them
+//! sends a message. `Channels` connects workers that are threads of one
+//! process and `Tcp` workers anywhere on a network.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::cluster::*;
+//! use fastgraph::collections::*;
+//! use fastgraph::distributed::*;
+//!
+//! let mut g = Digraph::<u32, f64, f64>::new();
+//! for i in 0..4 {
+//!     g.add_node(i, f64::INFINITY);
+//! }
+//! g.add_edge(0, 1, 1.0);
+//! g.add_edge(1, 2, 1.0);
+//! g.add_edge(2, 3, 1.0);
+//!
+//! let shards = split(&g, &[vec![0, 1], vec![2, 3]]);
+//! let workers: Vec<_> = shards
+//!     .into_iter()
+//!     .zip(channels(2))
+//!     .map(|(shard, mut transport)| {
+//!         std::thread::spawn(move || {
+//!             let local: Digraph<u32, f64, f64> = shard.graph();
+//!             pregel_worker(&shard, &local, &mut transport, 100, |superstep, node, messages: Vec<f64>| {
+//!                 let start = if superstep == 0 && *node.key() == 0 { 0.0 } else { f64::INFINITY };
+//!                 let best = messages.into_iter().fold(start, f64::min);
+//!                 if best >= node.load() {
+//!                     return Vec::new();
+//!                 }
+//!                 node.store(best);
+//!                 node.outbound().iter().map(|e| (*e.target().key(), best + e.load())).collect()
+//!             }).unwrap();
+//!             (shard, local)
+//!         })
+//!     })
+//!     .collect();
+//! for worker in workers {
+//!     let (shard, local) = worker.join().unwrap();
+//!     gather(&g, &shard, &local);
+//! }
+//!
+//! assert!(g.get_node(3).unwrap().load() == 3.0);
+//! ```
+use crate::core::*;
+use crate::collections::Graph;
+use crate::distributed::Shard;
+use crate::indexed::Indexed;
+use crate::io::Error;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+	convert::TryFrom,
+	fmt::{Debug, Display},
+	hash::Hash,
+	io::{Read, Write},
+	net::{SocketAddr, TcpListener, TcpStream},
+	sync::{
+		mpsc::{channel, Receiver, Sender},
+		Arc,
+	},
+	time::{Duration, Instant},
+};
+
+//=============================================================================
+
+/// Messages sent by one worker to another in a superstep, together with
+/// the total amount of messages the sender sent in it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Batch<K, M> {
+	/// Index of the shard of the sending worker.
+	pub from: usize,
+	/// Superstep in which the messages were sent.
+	pub superstep: usize,
+	/// Amount of messages the sender sent to all workers in the superstep,
+	/// including the ones in this batch.
+	pub sent: usize,
+	/// Messages of this batch, each with the key of the node it's addressed
+	/// to.
+	pub messages: Vec<(K, M)>,
+}
+
+/// Delivery of batches between the workers of a computation. Every worker
+/// has its own transport, and batches sent by one worker to another must
+/// arrive in the order they were sent.
+pub trait Transport<K, M> {
+	/// Send a batch to the worker of the shard with the given index.
+	fn send(&mut self, to: usize, batch: Batch<K, M>) -> Result<(), Error>;
+
+	/// Wait for the next batch sent to this worker by any other worker.
+	fn receive(&mut self) -> Result<Batch<K, M>, Error>;
+}
+
+//=============================================================================
+
+/// # Pregel Worker
+///
+/// Run the supersteps of the worker of a shard over the local graph built
+/// from it with `Shard::graph`, until no worker sends messages or
+/// `max_supersteps` supersteps have been run. Every worker of the
+/// computation must be running at the same time with the same
+/// `max_supersteps`. The closure is run for the nodes the shard owns as in
+/// `pregel`, and the state it stores in the nodes can be written back into
+/// the whole graph with `gather`. Messages can be sent to the owned nodes
+/// and the ghost nodes of the shard, and messages to other keys are
+/// dropped. Returns the amount of supersteps run, or the error of the
+/// transport if a batch couldn't be sent or received.
+pub fn pregel_worker<K, N, E, G, M, F, T>(
+	shard: &Shard<K, N, E>,
+	graph: &G,
+	transport: &mut T,
+	max_supersteps: usize,
+	compute: F,
+) -> Result<usize, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	M: Send,
+	F: Fn(usize, &Arc<Node<K, N, E>>, Vec<M>) -> Vec<(K, M)> + Sync + Send,
+	T: Transport<K, M>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let owners = shard.owners();
+	let owned: Vec<bool> = indexed.nodes
+		.iter()
+		.map(|node| owners.get(node.key()) == Some(&shard.index))
+		.collect();
+	let mut inboxes: Vec<Vec<M>> = indexed.nodes.iter().map(|_| Vec::new()).collect();
+	let mut early: Vec<Batch<K, M>> = Vec::new();
+	let mut superstep = 0;
+	let _span = trace_span!("pregel_worker", shard = shard.index, nodes = indexed.nodes.len());
+	while superstep < max_supersteps {
+		let outgoing: Vec<(K, M)> = std::mem::take(&mut inboxes)
+			.into_par_iter()
+			.enumerate()
+			.filter(|(i, messages)| owned[*i] && (superstep == 0 || !messages.is_empty()))
+			.flat_map_iter(|(i, messages)| compute(superstep, &indexed.nodes[i], messages))
+			.collect();
+		let own = outgoing.len();
+		let mut sent = own;
+		inboxes = indexed.nodes.iter().map(|_| Vec::new()).collect();
+		let mut remote: Vec<Vec<(K, M)>> = (0..shard.count).map(|_| Vec::new()).collect();
+		for (key, message) in outgoing {
+			match owners.get(&key) {
+				Some(owner) if *owner != shard.index => { remote[*owner].push((key, message)) }
+				Some(_) => {
+					if let Some(i) = indexed.index.get(&key) {
+						inboxes[*i].push(message);
+					}
+				}
+				None => {}
+			}
+		}
+		for (to, messages) in remote.into_iter().enumerate() {
+			if to != shard.index {
+				transport.send(to, Batch { from: shard.index, superstep, sent: own, messages })?;
+			}
+		}
+		// Batches of the next superstep can arrive from workers that are
+		// already done with this one, and are kept for later.
+		let mut received = 0;
+		let mut pending = std::mem::take(&mut early);
+		while received + 1 < shard.count {
+			let batch = match pending.pop() {
+				Some(batch) => { batch }
+				None => { transport.receive()? }
+			};
+			if batch.superstep != superstep {
+				early.push(batch);
+				continue;
+			}
+			received += 1;
+			sent += batch.sent;
+			for (key, message) in batch.messages {
+				if let Some(i) = indexed.index.get(&key) {
+					inboxes[*i].push(message);
+				}
+			}
+		}
+		early.extend(pending);
+		trace_event!("superstep", superstep = superstep, messages = sent);
+		superstep += 1;
+		if sent == 0 {
+			break;
+		}
+	}
+	Ok(superstep)
+}
+
+//=============================================================================
+
+/// Transport between workers that are threads of one process.
+pub struct Channels<K, M> {
+	senders: Vec<Option<Sender<Batch<K, M>>>>,
+	receiver: Receiver<Batch<K, M>>,
+}
+
+/// Create connected transports for the given amount of workers, one for
+/// every shard in the order of the shards.
+pub fn channels<K, M>(count: usize) -> Vec<Channels<K, M>> {
+	let (senders, receivers): (Vec<_>, Vec<_>) = (0..count).map(|_| channel()).unzip();
+	receivers
+		.into_iter()
+		.enumerate()
+		.map(|(i, receiver)| Channels {
+			senders: senders
+				.iter()
+				.enumerate()
+				.map(|(j, sender)| if i == j { None } else { Some(sender.clone()) })
+				.collect(),
+			receiver,
+		})
+		.collect()
+}
+
+impl<K, M> Transport<K, M> for Channels<K, M> {
+	fn send(&mut self, to: usize, batch: Batch<K, M>) -> Result<(), Error> {
+		match self.senders.get(to).and_then(|sender| sender.as_ref()) {
+			Some(sender) => { sender.send(batch).map_err(|_| disconnected(to)) }
+			None => { Err(Error::Format(format!("no worker {} to send to", to))) }
+		}
+	}
+
+	fn receive(&mut self) -> Result<Batch<K, M>, Error> {
+		self.receiver.recv().map_err(|_| Error::Io(std::io::ErrorKind::UnexpectedEof.into()))
+	}
+}
+
+//=============================================================================
+
+/// Largest batch in bytes that `Tcp` sends or accepts.
+pub const MAX_FRAME_SIZE: usize = 1 << 28;
+
+/// Transport between workers over TCP. Every batch is sent as a little
+/// endian `u32` length followed by the batch in JSON, and a thread for every
+/// other worker reads the batches it sends. Batches larger than
+/// `MAX_FRAME_SIZE` are an error on both ends.
+pub struct Tcp<K, M> {
+	streams: Vec<Option<TcpStream>>,
+	receiver: Receiver<Result<Batch<K, M>, Error>>,
+}
+
+impl<K, M> Tcp<K, M>
+where
+	K: Serialize + DeserializeOwned + Send + 'static,
+	M: Serialize + DeserializeOwned + Send + 'static,
+{
+	/// Connect the worker of the shard with the given index to the workers
+	/// listening on the other addresses, which are listed in the order of
+	/// the shards. The worker connects to the workers before it, retrying
+	/// until the timeout runs out, and accepts connections from the workers
+	/// after it on its own listener.
+	pub fn connect(index: usize, listener: TcpListener, addresses: &[SocketAddr], timeout: Duration) -> Result<Self, Error> {
+		let count = addresses.len();
+		let mut streams: Vec<Option<TcpStream>> = (0..count).map(|_| None).collect();
+		let deadline = Instant::now() + timeout;
+		for (j, address) in addresses.iter().enumerate().take(index) {
+			let mut stream = loop {
+				match TcpStream::connect(address) {
+					Ok(stream) => { break stream }
+					Err(err) => {
+						if Instant::now() >= deadline {
+							return Err(Error::Io(err));
+						}
+						std::thread::sleep(Duration::from_millis(10));
+					}
+				}
+			};
+			stream.write_all(&(index as u32).to_le_bytes())?;
+			streams[j] = Some(stream);
+		}
+		for _ in index + 1..count {
+			let (mut stream, _) = listener.accept()?;
+			let mut handshake = [0; 4];
+			stream.read_exact(&mut handshake)?;
+			let j = u32::from_le_bytes(handshake) as usize;
+			if j <= index || j >= count || streams[j].is_some() {
+				return Err(Error::Format(format!("unexpected worker {}", j)));
+			}
+			streams[j] = Some(stream);
+		}
+		let (sender, receiver) = channel();
+		for stream in streams.iter().flatten() {
+			stream.set_nodelay(true)?;
+			let mut reader = stream.try_clone()?;
+			let sender = sender.clone();
+			std::thread::spawn(move || loop {
+				let batch = read_frame(&mut reader);
+				let done = batch.is_err();
+				if sender.send(batch).is_err() || done {
+					break;
+				}
+			});
+		}
+		Ok(Tcp { streams, receiver })
+	}
+}
+
+impl<K, M> Transport<K, M> for Tcp<K, M>
+where
+	K: Serialize,
+	M: Serialize,
+{
+	fn send(&mut self, to: usize, batch: Batch<K, M>) -> Result<(), Error> {
+		let stream = match self.streams.get_mut(to).and_then(|stream| stream.as_mut()) {
+			Some(stream) => { stream }
+			None => { return Err(Error::Format(format!("no worker {} to send to", to))) }
+		};
+		let bytes = serde_json::to_vec(&batch).map_err(|err| Error::Format(err.to_string()))?;
+		let length = match u32::try_from(bytes.len()) {
+			Ok(length) if bytes.len() <= MAX_FRAME_SIZE => { length }
+			_ => { return Err(too_large(bytes.len())) }
+		};
+		let mut frame = length.to_le_bytes().to_vec();
+		frame.extend(bytes);
+		stream.write_all(&frame).map_err(Error::Io)
+	}
+
+	fn receive(&mut self) -> Result<Batch<K, M>, Error> {
+		match self.receiver.recv() {
+			Ok(batch) => { batch }
+			Err(_) => { Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into())) }
+		}
+	}
+}
+
+// Read one length prefixed batch. The buffer grows as the bytes arrive, so
+// a bad length can't allocate more than what was actually sent.
+fn read_frame<K, M, R>(reader: &mut R) -> Result<Batch<K, M>, Error>
+where
+	K: DeserializeOwned,
+	M: DeserializeOwned,
+	R: Read,
+{
+	let mut length = [0; 4];
+	reader.read_exact(&mut length)?;
+	let length = u32::from_le_bytes(length) as usize;
+	if length > MAX_FRAME_SIZE {
+		return Err(too_large(length));
+	}
+	let mut bytes = Vec::new();
+	reader.take(length as u64).read_to_end(&mut bytes)?;
+	if bytes.len() != length {
+		return Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into()));
+	}
+	serde_json::from_slice(&bytes).map_err(|err| Error::Format(err.to_string()))
+}
+
+fn too_large(length: usize) -> Error {
+	Error::Format(format!("batch of {} bytes is larger than {} bytes", length, MAX_FRAME_SIZE))
+}
+
+fn disconnected(to: usize) -> Error {
+	Error::Io(std::io::Error::new(std::io::ErrorKind::BrokenPipe, format!("worker {} is gone", to)))
+}
+
+//=============================================================================
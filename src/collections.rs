@@ -6,11 +6,24 @@
 //! easily out of thier own desired container type or use one of the templates.
 //!
 use crate::core::*;
+use crate::community::{GroupStats, Groups};
+use crate::csr::Csr;
+use crate::events::*;
+use crate::indexed::Indexed;
+use crate::printer::Printer;
+use crate::schema::{Schema, Violation};
+use crate::shortest_path::*;
+use crate::typed::{Metapath, Tagged};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::{iter::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator}, slice::ParallelSlice};
 use std::{
-	collections::HashMap,
+	cell::RefCell,
+	collections::{BinaryHeap, HashMap, HashSet, VecDeque, hash_map::{DefaultHasher, Entry}},
+	convert::TryFrom,
     fmt::{Debug, Display},
-    hash::Hash,
-	sync::{Arc, Weak}
+    hash::{Hash, Hasher},
+	sync::{Arc, Weak, atomic::{AtomicUsize, Ordering}}
 };
 
 /// This trait can be used to easily create a graph from a desired container type.
@@ -28,9 +41,22 @@ where
 	/// Direction of the graph.
 	fn directed() -> bool;
 
+	/// Direction in which edges are followed when exploring the graph.
+	fn direction() -> Direction {
+		match Self::directed() {
+			true => { Direction::Outbound }
+			false => { Direction::Both }
+		}
+	}
+
 	/// Add a node to the graph.
     fn add_node(&mut self, key: K, data: N) -> bool;
 
+	/// Delete a node and all of its edges from the graph. Graphs with
+	/// subscribers send a `DelEdge` for each of the edges before the
+	/// `DelNode`. Returns false if the node can't be found.
+	fn del_node(&mut self, key: K) -> bool;
+
 	/// Get an atomic reference to a node. If node can't
 	/// be found, returns None.
 	fn get_node(&self, node: K) -> Option<Arc<Node<K, N, E>>>;
@@ -41,6 +67,120 @@ where
 	/// Count the nodes in the graph.
 	fn node_count(&self) -> usize;
 
+	/// Subscribers notified of mutations made through the trait. Graphs that
+	/// don't support subscriptions return None.
+	fn subscribers(&self) -> Option<&Subscribers<K, N, E>> {
+		None
+	}
+
+	/// Subscribe to the mutations of the graph. Every node and edge added
+	/// and every edge deleted afterwards is sent to the returned receiver.
+	/// If the graph doesn't support subscriptions the channel is closed
+	/// immediately.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::events::Event;
+	///
+	/// let mut g = Digraph::<u32, u32, f64>::new();
+	/// let feed = g.subscribe();
+	/// g.add_node(1, 10);
+	/// g.add_node(2, 20);
+	/// g.add_edge(1, 2, 0.5);
+	///
+	/// assert!(feed.try_iter().count() == 3);
+	/// ```
+	fn subscribe(&self) -> std::sync::mpsc::Receiver<Event<K, N, E>> {
+		match self.subscribers() {
+			Some(subscribers) => { subscribers.subscribe() }
+			None => { std::sync::mpsc::channel().1 }
+		}
+	}
+
+	/// Revision of the graph. The revision changes with every mutation made
+	/// through the trait and is unique across graphs, so a result computed
+	/// from a graph stays valid for as long as the revision stays the same.
+	/// Like events, data stored directly to nodes and edges doesn't change
+	/// the revision. Graphs that don't support subscriptions return None.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// g.add_node(1, Empty);
+	/// let revision = g.revision();
+	/// assert!(g.get_node(1).is_some() && g.revision() == revision);
+	///
+	/// g.add_edge(1, 1, Empty);
+	/// assert!(g.revision() != revision);
+	/// ```
+	fn revision(&self) -> Option<u64> {
+		self.subscribers().map(|subscribers| subscribers.revision())
+	}
+
+	/// Collect atomic references to all nodes in the graph.
+	fn nodes(&self) -> Vec<Arc<Node<K, N, E>>> {
+		let res = RefCell::new(Vec::with_capacity(self.node_count()));
+		self.iter_nodes(&|n| res.borrow_mut().push(n));
+		res.into_inner()
+	}
+
+	/// Copy the data of every node into a flat list of keys paired with their
+	/// data, loaded in parallel. The order is the order of `nodes`, which stays
+	/// the same between calls as long as no nodes are added or removed, so the
+	/// data can be processed as an array and written back with
+	/// `scatter_node_data`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, f64, Empty>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, i as f64);
+	/// }
+	///
+	/// let (keys, mut values): (Vec<u32>, Vec<f64>) = g.gather_node_data().into_iter().unzip();
+	/// for v in values.iter_mut() {
+	///     *v *= 2.0;
+	/// }
+	/// assert!(g.scatter_node_data(keys.into_iter().zip(values).collect()) == 4);
+	/// assert!(g.get_node(3).unwrap().load() == 6.0);
+	/// ```
+	fn gather_node_data(&self) -> Vec<(K, N)> {
+		self.nodes()
+			.into_par_iter()
+			.map(|node| (node.key().clone(), node.load()))
+			.collect()
+	}
+
+	/// Store new data to the nodes with the given keys in parallel. Keys that
+	/// can't be found are skipped. If any node is updated the graph moves to
+	/// a new revision, without sending an event. Returns the amount of nodes
+	/// updated.
+	fn scatter_node_data(&self, updates: Vec<(K, N)>) -> usize
+	where
+		Self: Sync,
+	{
+		let count = updates
+			.into_par_iter()
+			.filter_map(|(key, data)| self.get_node(key).map(|node| node.store(data)))
+			.count();
+		if count > 0 {
+			if let Some(subscribers) = self.subscribers() {
+				subscribers.touch();
+			}
+		}
+		count
+	}
+
 	// ========================================================================
 
 	/// Add a new edge to the graph.
@@ -51,7 +191,24 @@ where
 			Some(src) => {
 				match t {
 					Some(trg) => {
-						connect(&src, &trg, data);
+						let copy = match self.subscribers() {
+							Some(subscribers) if subscribers.active() => { Some(data.clone()) }
+							_ => { None }
+						};
+						if connect(&src, &trg, data) {
+							if let Some(subscribers) = self.subscribers() {
+								match copy {
+									Some(data) => {
+										subscribers.send(|| Event::AddEdge {
+											source: src.key().clone(),
+											target: trg.key().clone(),
+											data,
+										});
+									}
+									None => { subscribers.touch() }
+								}
+							}
+						}
 						true
 					}
 					None => { false }
@@ -61,6 +218,56 @@ where
 		}
 	}
 
+	/// Add a new edge to the graph, or if the edge already exists, merge the
+	/// data into its data with the `merge` closure. Returns false if either
+	/// node can't be found. Merging doesn't send an event, as there is no
+	/// event for changed edge data, but moves the graph to a new revision.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, u64>::new();
+	/// g.add_node(1, Empty);
+	/// g.add_node(2, Empty);
+	///
+	/// // Count repeated interactions.
+	/// for _ in 0..3 {
+	///     g.add_edge_with(1, 2, 1, |count, x| *count += x);
+	/// }
+	/// assert!(g.get_edge(1, 2).unwrap().load() == 3);
+	/// assert!(g.edge_count() == 1);
+	/// ```
+	fn add_edge_with<F>(&mut self, source: K, target: K, data: E, merge: F) -> bool
+	where
+		F: FnOnce (&mut E, E),
+	{
+		let (src, trg) = match (self.get_node(source), self.get_node(target)) {
+			(Some(src), Some(trg)) => { (src, trg) }
+			_ => { return false }
+		};
+		let copy = match self.subscribers() {
+			Some(subscribers) if subscribers.active() => { Some(data.clone()) }
+			_ => { None }
+		};
+		let added = connect_or_merge(&src, &trg, data, merge);
+		if let Some(subscribers) = self.subscribers() {
+			match (added, copy) {
+				(true, Some(data)) => {
+					subscribers.send(|| Event::AddEdge {
+						source: src.key().clone(),
+						target: trg.key().clone(),
+						data,
+					});
+				}
+				_ => { subscribers.touch() }
+			}
+		}
+		true
+	}
+
 	/// Delete an edge from the graph.
 	fn del_edge(&mut self, source: K, target: K) -> bool {
 		let s = self.get_node(source);
@@ -69,7 +276,14 @@ where
 			Some(src) => {
 				match t {
 					Some(trg) => {
-						disconnect(&src, &trg);
+						if disconnect(&src, &trg) {
+							if let Some(subscribers) = self.subscribers() {
+								subscribers.send(|| Event::DelEdge {
+									source: src.key().clone(),
+									target: trg.key().clone(),
+								});
+							}
+						}
 						true
 					}
 					None => { false }
@@ -79,6 +293,126 @@ where
 		}
 	}
 
+	/// Reverse the edge from the source to the target so that it goes from
+	/// the target to the source with the same data. Returns false if either
+	/// node or the edge doesn't exist, or if the reversed edge already
+	/// exists. Subscribers see the reversal as a deleted and an added edge.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, f64>::new();
+	/// g.add_node(1, Empty);
+	/// g.add_node(2, Empty);
+	/// g.add_edge(1, 2, 0.5);
+	///
+	/// assert!(g.reverse_edge(1, 2));
+	/// assert!(g.get_edge(1, 2).is_none());
+	/// assert!(g.get_edge(2, 1).unwrap().load() == 0.5);
+	/// assert!(!g.reverse_edge(1, 2));
+	/// ```
+	fn reverse_edge(&mut self, source: K, target: K) -> bool {
+		let (src, trg) = match (self.get_node(source), self.get_node(target)) {
+			(Some(src), Some(trg)) => { (src, trg) }
+			_ => { return false }
+		};
+		if !reverse(&src, &trg) {
+			return false;
+		}
+		if let Some(subscribers) = self.subscribers() {
+			match Arc::ptr_eq(&src, &trg) {
+				true => {}
+				false => {
+					subscribers.send(|| Event::DelEdge {
+						source: src.key().clone(),
+						target: trg.key().clone(),
+					});
+					subscribers.send(|| Event::AddEdge {
+						source: trg.key().clone(),
+						target: src.key().clone(),
+						data: trg.find_outbound(&src).map(|e| e.load()).unwrap(),
+					});
+				}
+			}
+		}
+		true
+	}
+
+	/// Reverse every edge accepted by the predicate and return the number of
+	/// edges reversed. Edges are selected before any is reversed, and if
+	/// both edges between two nodes are selected their data is swapped, so
+	/// they are reversed too. Other selected edges are skipped if their
+	/// reversed edge exists.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, f64>::new();
+	/// for i in 1..=3 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(1, 2, -1.0);
+	/// g.add_edge(2, 3, 1.0);
+	/// g.add_edge(3, 1, -2.0);
+	///
+	/// // Point every edge of negative weight the other way.
+	/// assert!(g.reverse_edges_where(|e| e.load() < 0.0) == 2);
+	/// assert!(g.get_edge(2, 1).is_some() && g.get_edge(1, 3).is_some());
+	/// assert!(g.get_edge(2, 3).is_some());
+	/// ```
+	fn reverse_edges_where<F>(&mut self, predicate: F) -> usize
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> bool + Sync + Send,
+	{
+		let selected: Mutex<Vec<(K, K)>> = Mutex::new(Vec::new());
+		self.iter_nodes(&|n| {
+			for e in n.outbound().iter() {
+				if predicate(e) {
+					selected.lock().push((n.key().clone(), e.target().key().clone()));
+				}
+			}
+		});
+		let selected = selected.into_inner();
+		let pairs: HashSet<&(K, K)> = selected.iter().collect();
+		let mut swapped: HashSet<(K, K)> = HashSet::new();
+		let mut count = 0;
+		for (s, t) in selected.iter() {
+			if s == t || !pairs.contains(&(t.clone(), s.clone())) {
+				if self.reverse_edge(s.clone(), t.clone()) {
+					count += 1;
+				}
+				continue;
+			}
+			// Swapping the data of both edges reverses them at once.
+			if swapped.contains(&(s.clone(), t.clone())) {
+				continue;
+			}
+			if let (Some(a), Some(b)) = (self.get_edge(s.clone(), t.clone()), self.get_edge(t.clone(), s.clone())) {
+				let data = a.load();
+				a.store(b.load());
+				b.store(data);
+				// Announce it like two calls to `reverse_edge`, deleting both
+				// edges before adding them back so a mirror can replay it.
+				if let Some(subscribers) = self.subscribers() {
+					for (source, target) in [(s, t), (t, s)].iter() {
+						subscribers.send(|| Event::DelEdge { source: (*source).clone(), target: (*target).clone() });
+					}
+					subscribers.send(|| Event::AddEdge { source: t.clone(), target: s.clone(), data: b.load() });
+					subscribers.send(|| Event::AddEdge { source: s.clone(), target: t.clone(), data: a.load() });
+				}
+				swapped.insert((t.clone(), s.clone()));
+				count += 2;
+			}
+		}
+		count
+	}
+
 	/// Get an edge if it exists.
 	fn get_edge(&self, source: K, target: K) -> Option<Arc<Edge<K, N, E>>> {
 		let s = self.get_node(source);
@@ -102,12 +436,1019 @@ where
 		r.load(std::sync::atomic::Ordering::Relaxed)
     }
 
+	/// True if both graphs have the same direction, the same nodes with
+	/// equal data and the same edges with equal data. Edges of undirected
+	/// graphs match in either orientation.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	///
+	/// let mut a = Ungraph::<u32, u32, u32>::new();
+	/// let mut b = Ungraph::<u32, u32, u32>::new();
+	/// for g in [&mut a, &mut b] {
+	///     g.add_node(1, 10);
+	///     g.add_node(2, 20);
+	/// }
+	/// a.add_edge(1, 2, 5);
+	/// b.add_edge(2, 1, 5);
+	///
+	/// assert!(a.structural_eq(&b));
+	/// assert!(a.structural_hash() == b.structural_hash());
+	/// ```
+	fn structural_eq<G>(&self, other: &G) -> bool
+	where
+		G: Graph<K, N, E>,
+		N: PartialEq,
+		E: PartialEq,
+	{
+		if Self::directed() != G::directed()
+		|| self.node_count() != other.node_count()
+		|| self.edge_count() != other.edge_count() {
+			return false;
+		}
+		for node in self.nodes() {
+			match other.get_node(node.key().clone()) {
+				Some(n) if n.load() == node.load() => {}
+				_ => { return false }
+			}
+			for edge in node.outbound().iter() {
+				let target = edge.target().key().clone();
+				let found = other.get_edge(node.key().clone(), target.clone())
+					.or_else(|| match Self::directed() {
+						true => { None }
+						false => { other.get_edge(target, node.key().clone()) }
+					});
+				match found {
+					Some(e) if e.load() == edge.load() => {}
+					_ => { return false }
+				}
+			}
+		}
+		true
+	}
+
+	/// Hash of the content of the graph which doesn't depend on the order in
+	/// which nodes and edges were added. Graphs that are structurally equal
+	/// have the same hash. The hash is stable within a build of the library
+	/// but not across Rust versions, so it shouldn't be persisted.
+	fn structural_hash(&self) -> u64
+	where
+		K: Hash,
+		N: Hash,
+		E: Hash,
+	{
+		let hash = |value: &dyn Fn(&mut DefaultHasher)| {
+			let mut hasher = DefaultHasher::new();
+			value(&mut hasher);
+			hasher.finish()
+		};
+		// Sums of element hashes are independent of element order.
+		let mut nodes = 0u64;
+		let mut edges = 0u64;
+		for node in self.nodes() {
+			let source = hash(&|h| node.key().hash(h));
+			nodes = nodes.wrapping_add(hash(&|h| (source, node.load()).hash(h)));
+			for edge in node.outbound().iter() {
+				let target = hash(&|h| edge.target().key().hash(h));
+				let (a, b) = match Self::directed() {
+					true => { (source, target) }
+					false => { (source.min(target), source.max(target)) }
+				};
+				edges = edges.wrapping_add(hash(&|h| (a, b, edge.load()).hash(h)));
+			}
+		}
+		hash(&|h| (Self::directed(), self.node_count(), nodes, edges).hash(h))
+	}
+
+	/// Keep only the nodes for which the predicate returns true. The edges of
+	/// the removed nodes are detached in a single pass over the remaining
+	/// nodes instead of one by one. Subscribers get a deletion event for every
+	/// edge of the removed nodes before the deletion events of the nodes
+	/// themselves. Returns the amount of nodes removed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 0..10 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// for i in 1..10 {
+	///     g.add_edge(0, i, Empty);
+	/// }
+	///
+	/// assert!(g.retain_nodes(|node| node.key() % 2 == 0) == 5);
+	/// assert!(g.edge_count() == 4);
+	/// ```
+	fn retain_nodes<F>(&mut self, predicate: F) -> usize
+	where
+		F: Fn (&Arc<Node<K, N, E>>) -> bool,
+	{
+		let (kept, removed): (Vec<_>, Vec<_>) = self.nodes().into_iter().partition(|n| predicate(n));
+		if removed.is_empty() {
+			return 0;
+		}
+		let keys: HashSet<K> = removed.iter().map(|n| n.key().clone()).collect();
+		let mut detached = Vec::new();
+		for node in kept.iter() {
+			node.outbound_mut().retain(|e| {
+				let keep = !keys.contains(e.target().key());
+				if !keep {
+					detached.push((node.key().clone(), e.target().key().clone()));
+				}
+				keep
+			});
+			node.inbound_mut().retain(|e| match e.upgrade() {
+				Some(e) => {
+					let keep = !keys.contains(e.source().key());
+					if !keep {
+						detached.push((e.source().key().clone(), node.key().clone()));
+					}
+					keep
+				}
+				None => { false }
+			});
+		}
+		// Edges between two removed nodes, loops included, are only
+		// listed by their sources.
+		for node in removed.iter() {
+			for edge in node.outbound().iter() {
+				if keys.contains(edge.target().key()) {
+					detached.push((node.key().clone(), edge.target().key().clone()));
+				}
+			}
+		}
+		if let Some(subscribers) = self.subscribers() {
+			for (source, target) in detached.iter() {
+				subscribers.send(|| Event::DelEdge { source: source.clone(), target: target.clone() });
+			}
+		}
+		for node in removed.iter() {
+			node.outbound_mut().clear();
+			node.inbound_mut().clear();
+			self.del_node(node.key().clone());
+		}
+		removed.len()
+	}
+
+	/// # Prune by Degree
+	///
+	/// Delete the nodes with a degree less than `k` in rounds until every
+	/// remaining node has a degree of at least `k`, which leaves the k-core of
+	/// the graph. The degree counts both inbound and outbound edges. Deleting
+	/// the nodes of a round lowers the degree of their neighbors, which are
+	/// the only candidates of the next round. Returns the keys deleted in
+	/// each round.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..5 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// // A triangle with a tail 2 - 3 - 4.
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 0, Empty);
+	/// g.add_edge(2, 3, Empty);
+	/// g.add_edge(3, 4, Empty);
+	///
+	/// let rounds = g.prune_degree_lt(2);
+	/// assert!(rounds == vec![vec![4], vec![3]]);
+	/// assert!(g.node_count() == 3);
+	/// ```
+	fn prune_degree_lt(&mut self, k: usize) -> Vec<Vec<K>>
+	where
+		Self: Sized,
+	{
+		prune_rounds(self, usize::MAX, |node| node.outbound().len() + node.inbound().len() < k)
+	}
+
+	/// # Trim Leaves
+	///
+	/// Delete the leaves of the graph for at most the given amount of rounds
+	/// or until no leaves are left. In an undirected graph the leaves are the
+	/// nodes with at most one edge. In a directed graph they are the nodes
+	/// without inbound or without outbound edges, which can't be on a cycle,
+	/// so trimming without a limit leaves only the nodes on or between
+	/// cycles. Returns the keys deleted in each round.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 0..5 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// // The cycle 0 -> 1 -> 0 depends on 2, which depends on 3 and 4.
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(1, 0, Empty);
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 3, Empty);
+	/// g.add_edge(2, 4, Empty);
+	///
+	/// let mut rounds = g.trim_leaves(1);
+	/// rounds[0].sort();
+	/// assert!(rounds == vec![vec![3, 4]]);
+	/// assert!(g.trim_leaves(usize::MAX) == vec![vec![2]]);
+	/// assert!(g.node_count() == 2);
+	/// ```
+	fn trim_leaves(&mut self, rounds: usize) -> Vec<Vec<K>>
+	where
+		Self: Sized,
+	{
+		match Self::directed() {
+			true => { prune_rounds(self, rounds, |node| node.outbound().is_empty() || node.inbound().is_empty()) }
+			false => { prune_rounds(self, rounds, |node| node.outbound().len() + node.inbound().len() <= 1) }
+		}
+	}
+
+	/// # Rewire
+	///
+	/// Randomize the graph with `iterations` double edge swaps that preserve
+	/// the degree of every node, and in a directed graph both its inbound and
+	/// outbound degree, while keeping the graph free of loops and parallel
+	/// edges. With about ten swaps per edge the graph is a sample of the null
+	/// model of graphs with the same degree sequence, against which the
+	/// motifs or the clustering of the original graph can be tested for
+	/// significance. Returns the amount of swaps made, which is lower if the
+	/// graph has too few valid swaps. See `anonymize::swap_edges`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use rand::{rngs::StdRng, SeedableRng};
+	///
+	/// // Two triangles joined by an edge.
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..6 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+	///     g.add_edge(a, b, Empty);
+	/// }
+	///
+	/// let degrees = |g: &Ungraph<u32, Empty, Empty>| {
+	///     let mut d: Vec<usize> = g.nodes().iter().map(|n| n.outbound().len() + n.inbound().len()).collect();
+	///     d.sort();
+	///     d
+	/// };
+	/// let before = degrees(&g);
+	/// let mut rng = StdRng::seed_from_u64(3);
+	/// assert!(g.rewire(20, &mut rng) > 0);
+	/// assert!(degrees(&g) == before && g.edge_count() == 7);
+	/// ```
+	fn rewire<R>(&mut self, iterations: usize, rng: &mut R) -> usize
+	where
+		Self: Sized,
+		R: Rng + ?Sized,
+	{
+		crate::anonymize::swap_edges(self, iterations, rng)
+	}
+
+	/// Keep only the edges for which the predicate returns true, in a single
+	/// pass over the nodes. Returns the amount of edges removed.
+	fn retain_edges<F>(&mut self, predicate: F) -> usize
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> bool,
+	{
+		let nodes = self.nodes();
+		let mut removed = Vec::new();
+		for node in nodes.iter() {
+			node.outbound_mut().retain(|e| {
+				let keep = predicate(e);
+				if !keep {
+					removed.push((node.key().clone(), e.target().key().clone()));
+				}
+				keep
+			});
+		}
+		for node in nodes.iter() {
+			node.inbound_mut().retain(|e| e.strong_count() > 0);
+		}
+		if let Some(subscribers) = self.subscribers() {
+			for (source, target) in removed.iter() {
+				subscribers.send(|| Event::DelEdge { source: source.clone(), target: target.clone() });
+			}
+		}
+		removed.len()
+	}
+
+	/// Run a closure that gets the graph for reading together with a queue of
+	/// mutations and apply the queued mutations once the closure returns. This
+	/// allows a visitor to modify the graph it is walking without running into
+	/// borrow conflicts. Returns the amount of mutations applied.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, Empty);
+	///
+	/// // Remove every isolated node while iterating.
+	/// let removed = g.deferred(|g, queue| {
+	///     g.iter_nodes(&|node| {
+	///         if node.degree() == 0 && node.inbound().is_empty() {
+	///             let key = *node.key();
+	///             queue.defer(move |g| { g.del_node(key); });
+	///         }
+	///     });
+	/// });
+	/// assert!(removed == 2);
+	/// assert!(g.node_count() == 2);
+	/// ```
+	fn deferred<F>(&mut self, f: F) -> usize
+	where
+		Self: Sized,
+		F: FnOnce (&Self, &Deferred<Self>),
+	{
+		let queue = Deferred::new();
+		f(self, &queue);
+		queue.apply(self)
+	}
+
+	/// Freeze the current structure of the graph into compressed sparse row
+	/// form for fast parallel scans. See `Csr`.
+	fn freeze(&self) -> Csr<K, N, E>
+	where
+		Self: Sized,
+	{
+		Csr::new(self)
+	}
+
+	/// Check the graph against a schema and return every violation. See
+	/// `Schema`.
+	fn check_schema<T, R>(&self, schema: &Schema<N, E, T, R>) -> Vec<Violation<K, T, R>>
+	where
+		Self: Sized,
+		T: Hash + Eq + Clone + Debug,
+		R: Hash + Eq + Clone + Debug,
+	{
+		schema.check(self)
+	}
+
+	/// The nodes a metapath leads to from a node with the amount of paths
+	/// that match to every one of them. None if the node isn't in the graph.
+	/// See `Metapath`.
+	fn follow_metapath<TN, TE>(&self, start: K, metapath: &Metapath<TN, TE>) -> Option<HashMap<K, usize>>
+	where
+		Self: Sized,
+		N: Tagged<TN>,
+		E: Tagged<TE>,
+		TN: PartialEq,
+		TE: PartialEq,
+	{
+		metapath.ends(self, start)
+	}
+
+	/// Check the structural invariants of the graph: every node is stored
+	/// under its own key, every edge is unique between its endpoints, has
+	/// both endpoints in the graph and is referenced exactly once by the
+	/// inbound list of its target, and every inbound reference belongs to
+	/// an outbound edge of its source. Inbound references to edges that no
+	/// longer exist are allowed, see `compact`. Returns a description of the
+	/// first violation found.
+	fn validate(&self) -> Result<(), String> {
+		let in_graph = |node: &Arc<Node<K, N, E>>| {
+			self.get_node(node.key().clone()).is_some_and(|n| Arc::ptr_eq(&n, node))
+		};
+		for node in self.nodes() {
+			if !in_graph(&node) {
+				return Err(format!("node {} is not stored under its key", node.key()));
+			}
+			let outbound = node.outbound().clone();
+			for (i, edge) in outbound.iter().enumerate() {
+				let (source, target) = match edge.endpoints() {
+					Some(endpoints) => endpoints,
+					None => return Err(format!("edge of node {} has a dropped endpoint", node.key())),
+				};
+				if !Arc::ptr_eq(&source, &node) {
+					return Err(format!("edge {} -> {} is stored at node {}", source.key(), target.key(), node.key()));
+				}
+				if !in_graph(&target) {
+					return Err(format!("edge {} -> {} leads out of the graph", source.key(), target.key()));
+				}
+				let same_target = |e: &Arc<Edge<K, N, E>>| e.endpoints().is_some_and(|(_, t)| Arc::ptr_eq(&t, &target));
+				if outbound[..i].iter().any(same_target) {
+					return Err(format!("edge {} -> {} is duplicated", source.key(), target.key()));
+				}
+				let refs = target.inbound().iter().filter(|e| e.as_ptr() == Arc::as_ptr(edge)).count();
+				if refs != 1 {
+					return Err(format!("edge {} -> {} has {} inbound references", source.key(), target.key(), refs));
+				}
+			}
+			let inbound: Vec<_> = node.inbound().iter().filter_map(|e| e.upgrade()).collect();
+			for edge in inbound {
+				let valid = match edge.endpoints() {
+					Some((source, target)) => {
+						Arc::ptr_eq(&target, &node) && source.outbound().iter().any(|e| Arc::ptr_eq(e, &edge))
+					}
+					None => { false }
+				};
+				if !valid {
+					return Err(format!("inbound edge of node {} is not an outbound edge of its source", node.key()));
+				}
+			}
+		}
+		Ok(())
+	}
+
 	/// Approximate the size of the graph.
 	fn size_of(&self) -> usize {
 		(self.node_count() * std::mem::size_of::<Node<K, N, E>>())
 		+ (self.edge_count() * std::mem::size_of::<Edge<K, N, E>>())
 	}
 
+	/// # Clear
+	///
+	/// Delete every node and edge and release the memory held by the graph.
+	/// Returns the amount of nodes deleted. Edges only hold weak references
+	/// to their nodes and to nothing else, so the graph owns every node and
+	/// edge exactly once and they are freed as soon as the graph lets go of
+	/// them. Nodes still referenced from outside the graph are left without
+	/// edges.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use std::sync::Arc;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// g.add_node(1, Empty);
+	/// g.add_node(2, Empty);
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 1, Empty);
+	/// let node = Arc::downgrade(&g.get_node(1).unwrap());
+	/// let edge = Arc::downgrade(&g.get_edge(1, 2).unwrap());
+	///
+	/// assert!(g.clear() == 2 && g.node_count() == 0);
+	/// assert!(node.upgrade().is_none() && edge.upgrade().is_none());
+	/// ```
+	fn clear(&mut self) -> usize {
+		let keys: Vec<K> = self.nodes().iter().map(|n| n.key().clone()).collect();
+		let count = keys.into_iter().filter(|k| self.del_node(k.clone())).count();
+		self.compact();
+		count
+	}
+
+	/// Release excess capacity of the node table and the adjacency lists,
+	/// for example after heavy deletions, and drop inbound references to
+	/// edges that no longer exist. Nodes are addressed by their keys, so
+	/// nothing is moved or renumbered. Returns the amount of bytes
+	/// reclaimed.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 0..100 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// for i in 1..100 {
+	///     g.add_edge(0, i, Empty);
+	/// }
+	/// for i in 2..100 {
+	///     g.del_node(i);
+	/// }
+	///
+	/// assert!(g.compact() > 0);
+	/// assert!(g.compact() == 0);
+	/// assert!(g.get_edge(0, 1).is_some());
+	/// ```
+	fn compact(&mut self) -> usize {
+		let r = AtomicUsize::new(0);
+		self.iter_nodes(&|n| {
+			r.fetch_add(n.shrink_to_fit(), Ordering::Relaxed);
+		});
+		r.into_inner()
+	}
+
+	/// # Build
+	///
+	/// Materialize a graph defined by a successor function, such as the
+	/// states and transitions of a state machine. The given nodes are added
+	/// first and then expanded in breadth first order: the successor
+	/// function is called once for every node in the graph and returns the
+	/// keys of its successors paired with the data of the edges leading to
+	/// them. Successors that aren't in the graph yet are added until the
+	/// graph has `max_nodes` nodes, after which edges to new nodes are
+	/// dropped. Nodes get the default data.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// // States of a counter modulo 12 stepping by 3 or 4.
+	/// let g = Digraph::<u32, Empty, u32>::build([0], 100, |k| [((k + 3) % 12, 3), ((k + 4) % 12, 4)]);
+	/// assert!(g.node_count() == 12 && g.edge_count() == 24);
+	///
+	/// // The naturals, bounded to the first ten.
+	/// let h = Digraph::<u32, Empty, Empty>::build(0.., 10, |k| Some((k + 1, Empty)));
+	/// assert!(h.node_count() == 10 && h.edge_count() == 9);
+	/// ```
+	fn build<I, F, S>(nodes: I, max_nodes: usize, mut successors: F) -> Self
+	where
+		Self: Sized,
+		N: Default,
+		I: IntoIterator<Item = K>,
+		F: FnMut (&K) -> S,
+		S: IntoIterator<Item = (K, E)>,
+	{
+		let mut g = Self::new();
+		let mut queue = VecDeque::new();
+		for key in nodes {
+			if g.node_count() >= max_nodes {
+				break;
+			}
+			if g.add_node(key.clone(), N::default()) {
+				queue.push_back(key);
+			}
+		}
+		while let Some(key) = queue.pop_front() {
+			for (next, data) in successors(&key) {
+				if g.get_node(next.clone()).is_none() {
+					if g.node_count() >= max_nodes {
+						continue;
+					}
+					g.add_node(next.clone(), N::default());
+					queue.push_back(next.clone());
+				}
+				g.add_edge(key.clone(), next, data);
+			}
+		}
+		g
+	}
+
+	/// Induced subgraph of all nodes within `k` hops of the `center` node.
+	/// Hops are counted along edges in the given direction. If the center
+	/// node can't be found, returns None.
+	fn ego(&self, center: K, k: usize, direction: Direction) -> Option<Self>
+	where
+		Self: Sized,
+	{
+		let center = self.get_node(center)?;
+		let mut visited = HashSet::new();
+		let mut members = Vec::new();
+		let mut queue = VecDeque::new();
+		visited.insert(center.key().clone());
+		queue.push_back((center, 0));
+		while let Some((node, depth)) = queue.pop_front() {
+			if depth < k {
+				for next in node.neighbors(direction) {
+					if visited.insert(next.key().clone()) {
+						queue.push_back((next, depth + 1));
+					}
+				}
+			}
+			members.push(node);
+		}
+		let mut g = Self::new();
+		for node in members.iter() {
+			g.add_node(node.key().clone(), node.load());
+		}
+		for node in members.iter() {
+			for edge in node.outbound().iter() {
+				if visited.contains(edge.target().key()) {
+					g.add_edge(node.key().clone(), edge.target().key().clone(), edge.load());
+				}
+			}
+		}
+		Some(g)
+	}
+
+	/// # Map
+	///
+	/// Copy of the graph with the same nodes and edges where the data of
+	/// every node and edge has been transformed by the closures. The type of
+	/// the new graph is chosen by the caller.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	///
+	/// let mut g = Digraph::<u32, String, String>::new();
+	/// g.add_node(1, "1.5".to_string());
+	/// g.add_node(2, "2.5".to_string());
+	/// g.add_edge(1, 2, "10".to_string());
+	///
+	/// let h: Digraph<u32, f64, u64> = g.map(|_, n| n.parse().unwrap(), |e| e.parse().unwrap());
+	/// assert!(h.get_node(2).unwrap().load() == 2.5);
+	/// assert!(h.get_edge(1, 2).unwrap().load() == 10);
+	/// ```
+	fn map<N2, E2, G2, FN, FE>(&self, node: FN, edge: FE) -> G2
+	where
+		N2: Clone + Debug + Display + Sync + Send,
+		E2: Clone + Debug + Display + Sync + Send,
+		G2: Graph<K, N2, E2>,
+		FN: Fn (&K, N) -> N2,
+		FE: Fn (E) -> E2,
+	{
+		self.filter_map(|k, n| Some(node(k, n)), |e| Some(edge(e)))
+	}
+
+	/// Like `map`, but nodes and edges for which the closures return None
+	/// are left out. Leaving out a node also leaves out its edges.
+	fn filter_map<N2, E2, G2, FN, FE>(&self, node: FN, edge: FE) -> G2
+	where
+		N2: Clone + Debug + Display + Sync + Send,
+		E2: Clone + Debug + Display + Sync + Send,
+		G2: Graph<K, N2, E2>,
+		FN: Fn (&K, N) -> Option<N2>,
+		FE: Fn (E) -> Option<E2>,
+	{
+		let nodes = self.nodes();
+		let mut g = G2::new();
+		for n in nodes.iter() {
+			if let Some(data) = node(n.key(), n.load()) {
+				g.add_node(n.key().clone(), data);
+			}
+		}
+		for n in nodes.iter() {
+			for e in n.outbound().iter() {
+				if let Some(data) = edge(e.load()) {
+					g.add_edge(n.key().clone(), e.target().key().clone(), data);
+				}
+			}
+		}
+		g
+	}
+
+	/// # Map Keys
+	///
+	/// Copy of the graph with every key replaced by the key given by the
+	/// closure, for example to turn string keys into integers. If two keys
+	/// are mapped to the same new key, returns the first such pair of old
+	/// keys as an error. The type of the new graph is chosen by the caller.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<String, Empty, Empty>::new();
+	/// g.add_node("10".to_string(), Empty);
+	/// g.add_node("20".to_string(), Empty);
+	/// g.add_node("020".to_string(), Empty);
+	/// g.add_edge("10".to_string(), "20".to_string(), Empty);
+	///
+	/// let h: Result<Digraph<u32, Empty, Empty>, _> = g.map_keys(|k| k.parse().unwrap());
+	/// assert!(h.is_err());
+	///
+	/// g.del_node("020".to_string());
+	/// let h: Digraph<u32, Empty, Empty> = g.map_keys(|k| k.parse().unwrap()).unwrap();
+	/// assert!(h.get_edge(10, 20).is_some());
+	/// ```
+	fn map_keys<K2, G2, F>(&self, key: F) -> Result<G2, (K, K)>
+	where
+		K2: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		G2: Graph<K2, N, E>,
+		F: Fn (&K) -> K2,
+	{
+		let nodes = self.nodes();
+		let mut keys: HashMap<K2, K> = HashMap::with_capacity(nodes.len());
+		let mut g = G2::new();
+		for n in nodes.iter() {
+			let k = key(n.key());
+			if let Some(previous) = keys.get(&k) {
+				return Err((previous.clone(), n.key().clone()));
+			}
+			keys.insert(k.clone(), n.key().clone());
+			g.add_node(k, n.load());
+		}
+		for n in nodes.iter() {
+			for e in n.outbound().iter() {
+				g.add_edge(key(n.key()), key(e.target().key()), e.load());
+			}
+		}
+		Ok(g)
+	}
+
+	/// # Complement
+	///
+	/// Graph with the same nodes and an edge between every pair of distinct
+	/// nodes that aren't connected in this graph. In an undirected graph a
+	/// pair is connected by an edge in either orientation. Node data is given
+	/// by the `node` closure and edge data by the `edge` closure from the keys
+	/// of the endpoints. The type of the new graph is chosen by the caller.
+	/// The complement of a sparse graph has close to n² edges.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(2, 1, Empty);
+	///
+	/// let h: Ungraph<u32, Empty, Empty> = g.complement(|_, n| n, |_, _| Empty);
+	/// assert!(h.edge_count() == 4);
+	/// assert!(h.get_edge(0, 1).is_none() && h.get_edge(1, 0).is_none());
+	/// ```
+	fn complement<N2, E2, G2, FN, FE>(&self, node: FN, edge: FE) -> G2
+	where
+		N2: Clone + Debug + Display + Sync + Send,
+		E2: Clone + Debug + Display + Sync + Send,
+		G2: Graph<K, N2, E2>,
+		FN: Fn (&K, N) -> N2,
+		FE: Fn (&K, &K) -> E2,
+	{
+		let indexed = Indexed::new(self.nodes(), Self::direction());
+		let mut g = G2::new();
+		for n in indexed.nodes.iter() {
+			g.add_node(n.key().clone(), node(n.key(), n.load()));
+		}
+		let n = indexed.nodes.len();
+		let mut adjacent = vec![false; n];
+		for u in 0..n {
+			for &(w, _) in indexed.adjacency[u].iter() {
+				adjacent[w] = true;
+			}
+			// Each unordered pair once in an undirected graph.
+			let first = if Self::directed() { 0 } else { u + 1 };
+			for (w, &connected) in adjacent.iter().enumerate().skip(first) {
+				if w != u && !connected {
+					let (s, t) = (indexed.nodes[u].key(), indexed.nodes[w].key());
+					g.add_edge(s.clone(), t.clone(), edge(s, t));
+				}
+			}
+			for &(w, _) in indexed.adjacency[u].iter() {
+				adjacent[w] = false;
+			}
+		}
+		g
+	}
+
+	/// # Line Graph
+	///
+	/// Graph with a node for every edge of this graph. In a directed graph
+	/// there is an edge from the node of an edge u -> v to the node of every
+	/// edge v -> w. In an undirected graph the nodes of two edges are
+	/// connected if the edges share an endpoint. The `node` closure gives the
+	/// key and the data of the node of an edge. Keys should be unique, since
+	/// edges given a key that is already taken are left out. The `edge`
+	/// closure gives the data of an edge from the node the two edges share.
+	/// The type of the new graph is chosen by the caller.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// // A star with three edges.
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// for i in 1..4 {
+	///     g.add_edge(0, i, Empty);
+	/// }
+	///
+	/// // Coloring the nodes of the line graph colors the edges of the star.
+	/// let h: Ungraph<u32, Empty, u32> = g.line_graph(
+	///     |e| (*e.target().key(), Empty),
+	///     |shared| *shared.key(),
+	/// );
+	/// assert!(h.node_count() == 3);
+	/// assert!(h.edge_count() == 3);
+	/// ```
+	fn line_graph<K2, N2, E2, G2, FN, FE>(&self, node: FN, edge: FE) -> G2
+	where
+		K2: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N2: Clone + Debug + Display + Sync + Send,
+		E2: Clone + Debug + Display + Sync + Send,
+		G2: Graph<K2, N2, E2>,
+		FN: Fn (&Arc<Edge<K, N, E>>) -> (K2, N2),
+		FE: Fn (&Arc<Node<K, N, E>>) -> E2,
+	{
+		let indexed = Indexed::new(self.nodes(), Self::direction());
+		let mut g = G2::new();
+		let mut keys = Vec::with_capacity(indexed.edges.len());
+		for e in indexed.edges.iter() {
+			let (key, data) = node(e);
+			g.add_node(key.clone(), data);
+			keys.push(key);
+		}
+		match Self::directed() {
+			true => {
+				// Edges u -> v by the target v.
+				let mut inbound = vec![Vec::new(); indexed.nodes.len()];
+				for adjacent in indexed.adjacency.iter() {
+					for &(w, e) in adjacent.iter() {
+						inbound[w].push(e);
+					}
+				}
+				for (v, adjacent) in indexed.adjacency.iter().enumerate() {
+					for &e in inbound[v].iter() {
+						for &(_, f) in adjacent.iter() {
+							g.add_edge(keys[e].clone(), keys[f].clone(), edge(&indexed.nodes[v]));
+						}
+					}
+				}
+			}
+			false => {
+				for (v, adjacent) in indexed.adjacency.iter().enumerate() {
+					for (i, &(_, e)) in adjacent.iter().enumerate() {
+						for &(_, f) in adjacent[i + 1..].iter() {
+							g.add_edge(keys[e].clone(), keys[f].clone(), edge(&indexed.nodes[v]));
+						}
+					}
+				}
+			}
+		}
+		g
+	}
+
+	/// All nodes reachable from the `center` node within a total edge cost of
+	/// `max_cost`, paired with their distance. Edges are followed in their
+	/// direction in a directed graph and both ways in an undirected graph.
+	/// If the center node can't be found, returns None.
+	fn ball<F>(&self, center: K, max_cost: f64, cost: F) -> Option<Distances<K, N, E>>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let center = self.get_node(center)?;
+		Some(dijkstra_ball(&center, max_cost, Self::direction(), cost))
+	}
+
+	/// Shortest path between two nodes with edge costs given by the `cost`
+	/// closure. Returns the total cost and the edges along the path, or None
+	/// if either node can't be found or the target can't be reached.
+	fn shortest_path<F>(&self, source: K, target: K, cost: F) -> Option<(f64, Path<K, N, E>)>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let s = self.get_node(source)?;
+		let t = self.get_node(target)?;
+		dijkstra(&s, &t, Self::direction(), cost)
+	}
+
+	/// Iterator over the simple paths from the source to the target with at
+	/// most `max_len` edges, ending after `max_count` paths. See
+	/// `shortest_path::simple_paths`. Yields nothing if either node can't be
+	/// found.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// // Every way around the square.
+	/// for i in 0..4 {
+	///     g.add_edge(i, (i + 1) % 4, Empty);
+	/// }
+	///
+	/// let lengths: Vec<usize> = g.all_simple_paths(0, 2, 10, 10).map(|p| p.len()).collect();
+	/// assert!(lengths == [2, 2]);
+	/// assert!(g.all_simple_paths(0, 9, 10, 10).next().is_none());
+	/// ```
+	fn all_simple_paths(&self, source: K, target: K, max_len: usize, max_count: usize) -> SimplePaths<K, N, E> {
+		match (self.get_node(source), self.get_node(target.clone())) {
+			(Some(s), Some(t)) => { simple_paths(&s, &t, Self::direction(), max_len, max_count) }
+			_ => { SimplePaths::empty(target, Self::direction(), max_len, max_count) }
+		}
+	}
+
+	/// Answer many independent shortest path queries in parallel. Every query
+	/// is a `shortest_path` search that keeps its own distances and visited
+	/// set and leaves the nodes untouched, so the queries share nothing but
+	/// the graph, which can be read by other threads at the same time.
+	/// Results are in the order of the pairs.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, f64>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, 1.0);
+	/// g.add_edge(1, 2, 1.0);
+	/// g.add_edge(0, 2, 3.0);
+	///
+	/// let res = g.par_shortest_paths(&[(0, 2), (2, 0), (1, 2), (0, 9)], |e| e.load());
+	/// let costs: Vec<Option<f64>> = res.into_iter().map(|r| r.map(|(c, _)| c)).collect();
+	/// assert!(costs == vec![Some(2.0), None, Some(1.0), None]);
+	/// ```
+	fn par_shortest_paths<F>(&self, pairs: &[(K, K)], cost: F) -> Routes<K, N, E>
+	where
+		Self: Sync,
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64 + Sync + Send,
+	{
+		pairs
+			.into_par_iter()
+			.map(|(source, target)| self.shortest_path(source.clone(), target.clone(), &cost))
+			.collect()
+	}
+
+	/// Shortest path between two nodes where passing through a node costs a
+	/// penalty depending on the incoming and the outgoing edge, given by the
+	/// `turn` closure. A turn for which the closure returns None is
+	/// forbidden. See `dijkstra_turns`.
+	fn shortest_path_turns<F, T>(&self, source: K, target: K, cost: F, turn: T) -> Option<(f64, Path<K, N, E>)>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+		T: Fn (&Arc<Edge<K, N, E>>, &Arc<Edge<K, N, E>>) -> Option<f64>,
+	{
+		let s = self.get_node(source)?;
+		let t = self.get_node(target)?;
+		dijkstra_turns(&s, &t, Self::direction(), cost, turn)
+	}
+
+	/// Shortest path between two nodes where entering a node costs as well,
+	/// given by the `region` closure. Nodes with an infinite cost can't be
+	/// entered. See `dijkstra_regions`.
+	fn shortest_path_regions<F, R>(&self, source: K, target: K, cost: F, region: R) -> Option<(f64, Path<K, N, E>)>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+		R: Fn (&Arc<Node<K, N, E>>) -> f64,
+	{
+		let s = self.get_node(source)?;
+		let t = self.get_node(target)?;
+		dijkstra_regions(&s, &t, Self::direction(), cost, region)
+	}
+
+	/// The edge to follow from every node toward the nearest of the target
+	/// nodes with its cost. Keys that can't be found are ignored. See
+	/// `flow_field`.
+	fn flow_field<F>(&self, targets: &[K], cost: F) -> FlowField<K, N, E>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let targets: Vec<Arc<Node<K, N, E>>> = targets.iter().filter_map(|k| self.get_node(k.clone())).collect();
+		flow_field(&targets, Self::direction(), cost)
+	}
+
+	/// Pareto optimal paths between two nodes with several cost criteria for
+	/// every edge given by the `cost` closure. Returns an empty list if either
+	/// node can't be found or the target can't be reached. See
+	/// `pareto_paths`.
+	fn pareto_paths<F, const D: usize>(&self, source: K, target: K, cost: F) -> Vec<([f64; D], Path<K, N, E>)>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> [f64; D],
+	{
+		match (self.get_node(source), self.get_node(target)) {
+			(Some(s), Some(t)) => { pareto_paths(&s, &t, Self::direction(), cost) }
+			_ => Vec::new(),
+		}
+	}
+
+	/// Path between two nodes that arrives the earliest when leaving at the
+	/// `departure` time, with travel times given by the `duration` closure
+	/// from the edge and the time it's entered. Returns the arrival time and
+	/// the edges along the path, or None if either node can't be found or the
+	/// target can't be reached. See `earliest_arrival`.
+	fn earliest_arrival<F>(&self, source: K, target: K, departure: f64, duration: F) -> Option<(f64, Path<K, N, E>)>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>, f64) -> f64,
+	{
+		let s = self.get_node(source)?;
+		let t = self.get_node(target)?;
+		earliest_arrival(&s, &t, Self::direction(), departure, duration)
+	}
+
 	/// Depth first traversal of the graph.
 	fn depth_first<F>(&self, source: K, explorer: F) -> Option<Vec<Weak<Edge<K, N, E>>>>
 	where
@@ -156,6 +1497,608 @@ where
 		}
 	}
 
+	/// Fold over the nodes reachable from the `root` node in breadth first
+	/// order. The closure gets the accumulator, the node and the edge the
+	/// node was first reached through, which is None for the root. Edges are
+	/// followed in their direction in a directed graph and both ways in an
+	/// undirected graph. Returns None if the root can't be found.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, u32>::new();
+	/// for i in 1..5 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(1, 2, 10);
+	/// g.add_edge(1, 3, 20);
+	/// g.add_edge(3, 4, 30);
+	///
+	/// let total = g.fold_bfs(1, 0, |acc, _, edge| acc + edge.map_or(0, |e| e.load()));
+	/// assert!(total == Some(60));
+	/// ```
+	fn fold_bfs<T, F>(&self, root: K, init: T, mut f: F) -> Option<T>
+	where
+		F: FnMut (T, &Arc<Node<K, N, E>>, Option<&Arc<Edge<K, N, E>>>) -> T,
+	{
+		let root = self.get_node(root)?;
+		let mut acc = f(init, &root, None);
+		let mut visited = HashSet::new();
+		let mut queue = VecDeque::new();
+		visited.insert(root.key().clone());
+		queue.push_back(root);
+		while let Some(node) = queue.pop_front() {
+			for (edge, next) in node.incident(Self::direction()) {
+				if visited.insert(next.key().clone()) {
+					acc = f(acc, &next, Some(&edge));
+					queue.push_back(next);
+				}
+			}
+		}
+		Some(acc)
+	}
+
+	/// Fold over the nodes reachable from the `root` node in depth first
+	/// preorder. See `fold_bfs`.
+	fn fold_dfs<T, F>(&self, root: K, init: T, mut f: F) -> Option<T>
+	where
+		F: FnMut (T, &Arc<Node<K, N, E>>, Option<&Arc<Edge<K, N, E>>>) -> T,
+	{
+		let root = self.get_node(root)?;
+		let mut acc = init;
+		let mut visited = HashSet::new();
+		let mut stack = vec![(root, None)];
+		while let Some((node, edge)) = stack.pop() {
+			if !visited.insert(node.key().clone()) {
+				continue;
+			}
+			acc = f(acc, &node, edge.as_ref());
+			// Neighbors are pushed in reverse so that the first one is
+			// visited first.
+			for (edge, next) in node.incident(Self::direction()).into_iter().rev() {
+				if !visited.contains(next.key()) {
+					stack.push((next, Some(edge)));
+				}
+			}
+		}
+		Some(acc)
+	}
+
+	/// Parallel fold over the nodes reachable from the `root` node for folds
+	/// that don't depend on the order of the nodes. Every thread folds part of
+	/// the nodes starting from `identity` and the partial results are combined
+	/// with `reduce`. The nodes and edges passed to `fold` are the same as in
+	/// `fold_bfs`. Returns None if the root can't be found.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Ungraph::<u32, u32, Empty>::new();
+	/// for i in 1..101 {
+	///     g.add_node(i, i);
+	/// }
+	/// for i in 1..100 {
+	///     g.add_edge(i, i + 1, Empty);
+	/// }
+	///
+	/// let sum = g.par_fold_bfs(50, || 0, |acc, node, _| acc + node.load(), |a, b| a + b);
+	/// assert!(sum == Some(5050));
+	/// ```
+	fn par_fold_bfs<T, I, F, R>(&self, root: K, identity: I, fold: F, reduce: R) -> Option<T>
+	where
+		T: Send,
+		I: Fn () -> T + Sync + Send,
+		F: Fn (T, &Arc<Node<K, N, E>>, Option<&Arc<Edge<K, N, E>>>) -> T + Sync + Send,
+		R: Fn (T, T) -> T + Sync + Send,
+	{
+		let visited = self.fold_bfs(root, Vec::new(), |mut acc, node, edge| {
+			acc.push((node.clone(), edge.cloned()));
+			acc
+		})?;
+		Some(visited
+			.into_par_iter()
+			.fold(&identity, |acc, (node, edge)| fold(acc, &node, edge.as_ref()))
+			.reduce(&identity, reduce))
+	}
+
+	/// Fold the nodes reachable from the `root` node bottom up in parallel.
+	/// The closure gets a node and the values computed for its children, and
+	/// the value of the root is returned. Children are the neighbors in the
+	/// direction of a directed graph and the nodes of a depth first spanning
+	/// tree in an undirected graph. See `core::parallel_post_order_fold`.
+	/// Returns None if the root can't be found.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// // Depth of a binary tree.
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 1..16 {
+	///     g.add_node(i, Empty);
+	///     if i > 1 {
+	///         g.add_edge(i / 2, i, Empty);
+	///     }
+	/// }
+	///
+	/// let depth = g.par_fold_postorder(1, |_, children: Vec<u32>| {
+	///     1 + children.into_iter().max().unwrap_or(0)
+	/// });
+	/// assert!(depth == Some(4));
+	/// ```
+	fn par_fold_postorder<T, F>(&self, root: K, f: F) -> Option<T>
+	where
+		T: Clone + Send,
+		F: Fn (&Arc<Node<K, N, E>>, Vec<T>) -> T + Sync + Send,
+	{
+		let root = self.get_node(root)?;
+		Some(parallel_post_order_fold(&root, Self::direction(), f))
+	}
+
+	/// # Topological Execution
+	///
+	/// Run a task for every node as soon as the tasks of all of its
+	/// predecessors have finished, with the tasks of independent nodes running
+	/// in parallel on rayon. The task gets the key and the data of the node.
+	/// Edges are followed from their source to their target, also in an
+	/// undirected graph. The graph is checked for cycles before any task
+	/// runs, and if it has one, the keys of the nodes on a cycle are returned
+	/// in order as the error. Otherwise returns the result of every task.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use std::sync::Mutex;
+	///
+	/// // Build steps that depend on each other.
+	/// let mut g = Digraph::<&str, Empty, Empty>::new();
+	/// for step in ["fetch", "compile", "test", "docs", "release"] {
+	///     g.add_node(step, Empty);
+	/// }
+	/// g.add_edge("fetch", "compile", Empty);
+	/// g.add_edge("compile", "test", Empty);
+	/// g.add_edge("fetch", "docs", Empty);
+	/// g.add_edge("test", "release", Empty);
+	/// g.add_edge("docs", "release", Empty);
+	///
+	/// let log = Mutex::new(Vec::new());
+	/// let results = g.execute_topological(|step, _| log.lock().unwrap().push(*step)).unwrap();
+	/// let log = log.into_inner().unwrap();
+	/// assert!(results.len() == 5);
+	/// assert!(log[0] == "fetch" && log[4] == "release");
+	///
+	/// g.add_edge("release", "fetch", Empty);
+	/// let cycle = g.execute_topological(|_, _| ()).unwrap_err();
+	/// assert!(cycle.contains(&"fetch") && cycle.contains(&"release"));
+	/// ```
+	fn execute_topological<T, F>(&self, task: F) -> Result<HashMap<K, T>, Vec<K>>
+	where
+		T: Send,
+		F: Fn (&K, N) -> T + Sync + Send,
+	{
+		let indexed = Indexed::new(self.nodes(), Direction::Outbound);
+		let n = indexed.nodes.len();
+		let mut indegree = vec![0; n];
+		for adjacent in indexed.adjacency.iter() {
+			for &(w, _) in adjacent.iter() {
+				indegree[w] += 1;
+			}
+		}
+		// Nodes left with predecessors by Kahn's algorithm are on a cycle or
+		// depend on one.
+		let mut remaining = indegree.clone();
+		let mut ready: Vec<usize> = (0..n).filter(|v| indegree[*v] == 0).collect();
+		let roots = ready.clone();
+		while let Some(v) = ready.pop() {
+			for &(w, _) in indexed.adjacency[v].iter() {
+				remaining[w] -= 1;
+				if remaining[w] == 0 {
+					ready.push(w);
+				}
+			}
+		}
+		if remaining.iter().any(|r| *r > 0) {
+			return Err(cycle(&indexed.adjacency, &remaining).into_iter().map(|v| indexed.key(v)).collect());
+		}
+		let schedule = Schedule {
+			indexed: &indexed,
+			pending: indegree.into_iter().map(AtomicUsize::new).collect(),
+			results: (0..n).map(|_| Mutex::new(None)).collect(),
+			task: &task,
+		};
+		let _span = trace_span!("execute_topological", nodes = n);
+		rayon::scope(|scope| {
+			for v in roots {
+				let schedule = &schedule;
+				scope.spawn(move |scope| schedule.run(scope, v));
+			}
+		});
+		let results = schedule.results;
+		Ok(indexed.nodes.iter().zip(results).filter_map(|(node, r)| Some((node.key().clone(), r.into_inner()?))).collect())
+	}
+
+	/// Combine values extracted from the edges of every node in the given
+	/// direction, in parallel over the nodes. Returns every node paired with
+	/// its aggregate, see `Node::aggregate`.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::*;
+	///
+	/// let mut g = Digraph::<u32, Empty, f64>::new();
+	/// for i in 1..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(1, 2, 3.0);
+	/// g.add_edge(1, 3, 1.0);
+	/// g.add_edge(2, 3, 2.0);
+	///
+	/// for (node, inflow) in g.aggregate_edges(Direction::Inbound, Aggregate::Sum, |e| e.load()) {
+	///     let outflow = node.aggregate_out(Aggregate::Sum, |e| e.load()).unwrap();
+	///     if node.key() == &2 {
+	///         assert!(inflow.unwrap() - outflow == 1.0);
+	///     }
+	/// }
+	/// ```
+	fn aggregate_edges<F>(&self, direction: Direction, aggregate: Aggregate, value: F) -> Aggregates<K, N, E>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64 + Sync + Send,
+	{
+		self.nodes()
+			.into_par_iter()
+			.map(|node| {
+				let res = node.aggregate(direction, aggregate, &value);
+				(node, res)
+			})
+			.collect()
+	}
+
+	/// Group the nodes by the group the `key` closure gives each of them and
+	/// count the nodes and edges of every group and the edges between every
+	/// two groups, in parallel over the nodes. Unlike `community::summarize`
+	/// no summary graph is built, and the counts answer questions like how
+	/// many edges go between two departments. Degrees are the amount of
+	/// inbound and outbound edges of a node.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, &str, Empty>::new();
+	/// g.add_node(1, "sales");
+	/// g.add_node(2, "sales");
+	/// g.add_node(3, "research");
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 3, Empty);
+	/// g.add_edge(3, 1, Empty);
+	///
+	/// let groups = g.group_by_nodes(|n| n.load());
+	/// let sales = groups.groups["sales"];
+	/// assert!(sales.nodes == 2 && sales.internal == 1 && sales.external == 2);
+	/// assert!(groups.between[&("sales", "research")] == 1);
+	/// assert!(groups.between[&("research", "sales")] == 1);
+	/// ```
+	fn group_by_nodes<C, F>(&self, key: F) -> Groups<C>
+	where
+		C: Hash + Eq + Clone + Sync + Send,
+		F: Fn (&Arc<Node<K, N, E>>) -> C + Sync + Send,
+	{
+		let nodes = self.nodes();
+		let group: HashMap<K, C> = nodes.par_iter().map(|n| (n.key().clone(), key(n))).collect();
+		let directed = Self::directed();
+		let (groups, between) = nodes
+			.par_iter()
+			.fold(
+				|| (HashMap::new(), HashMap::new()),
+				|(mut groups, mut between): (HashMap<C, GroupStats>, HashMap<(C, C), usize>), node| {
+					let c = &group[node.key()];
+					let degree = node.outbound().len() + node.inbound().len();
+					groups.entry(c.clone()).or_insert_with(GroupStats::empty).add(&GroupStats {
+						nodes: 1,
+						min_degree: degree,
+						max_degree: degree,
+						..GroupStats::default()
+					});
+					for edge in node.outbound().iter() {
+						let d = match group.get(edge.target().key()) {
+							Some(d) => { d }
+							None => { continue }
+						};
+						if c == d {
+							groups.get_mut(c).unwrap().internal += 1;
+							continue;
+						}
+						groups.get_mut(c).unwrap().external += 1;
+						groups.entry(d.clone()).or_insert_with(GroupStats::empty).external += 1;
+						*between.entry((c.clone(), d.clone())).or_insert(0) += 1;
+						if !directed {
+							*between.entry((d.clone(), c.clone())).or_insert(0) += 1;
+						}
+					}
+					(groups, between)
+				},
+			)
+			.reduce(
+				|| (HashMap::new(), HashMap::new()),
+				|(mut groups, mut between), (other_groups, other_between)| {
+					for (c, other) in other_groups {
+						groups.entry(c).or_insert_with(GroupStats::empty).add(&other);
+					}
+					for (pair, count) in other_between {
+						*between.entry(pair).or_insert(0) += count;
+					}
+					(groups, between)
+				},
+			);
+		Groups { groups, between }
+	}
+
+	/// Edges that join a node of one set to a node of the other, in either
+	/// direction, found in parallel over the nodes of the first set. Every
+	/// such edge is returned once, also if the sets overlap. Keys that
+	/// aren't in the graph are ignored.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 1..5 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(1, 3, Empty);
+	/// g.add_edge(4, 2, Empty);
+	/// g.add_edge(1, 2, Empty);
+	///
+	/// assert!(g.cut_edges(&[1, 2], &[3, 4]).len() == 2);
+	/// assert!(g.cut_edges(&[1], &[2, 3]).len() == 2);
+	/// ```
+	fn cut_edges(&self, a: &[K], b: &[K]) -> Vec<Arc<Edge<K, N, E>>> {
+		let in_a: HashSet<&K> = a.iter().collect();
+		let in_b: HashSet<&K> = b.iter().collect();
+		let members: Vec<Arc<Node<K, N, E>>> = in_a.iter().filter_map(|k| self.get_node((*k).clone())).collect();
+		members
+			.par_iter()
+			.flat_map_iter(|node| {
+				let x = node.key();
+				// Outbound edges are cut if they enter the other set, and
+				// inbound edges if they leave a node that is only in b.
+				let outbound = node.outbound().iter().filter(|e| {
+					let y = e.target();
+					in_b.contains(y.key()) || (in_b.contains(x) && in_a.contains(y.key()))
+				}).cloned().collect::<Vec<_>>();
+				let inbound = node.inbound().iter().filter_map(|e| e.upgrade()).filter(|e| {
+					let y = e.source();
+					in_b.contains(y.key()) && !in_a.contains(y.key())
+				}).collect::<Vec<_>>();
+				outbound.into_iter().chain(inbound)
+			})
+			.collect()
+	}
+
+	/// Edges with one endpoint in the set and the other outside of it, found
+	/// in parallel over the nodes of the set. Keys that aren't in the graph
+	/// are ignored.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 1..5 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 3, Empty);
+	/// g.add_edge(3, 4, Empty);
+	///
+	/// assert!(g.boundary(&[2, 3]).len() == 2);
+	/// assert!(g.boundary(&[1, 2, 3, 4]).is_empty());
+	/// ```
+	fn boundary(&self, set: &[K]) -> Vec<Arc<Edge<K, N, E>>> {
+		let inside: HashSet<&K> = set.iter().collect();
+		let members: Vec<Arc<Node<K, N, E>>> = inside.iter().filter_map(|k| self.get_node((*k).clone())).collect();
+		members
+			.par_iter()
+			.flat_map_iter(|node| {
+				let outbound = node.outbound().iter().filter(|e| !inside.contains(e.target().key())).cloned().collect::<Vec<_>>();
+				let inbound = node.inbound().iter().filter_map(|e| e.upgrade()).filter(|e| !inside.contains(e.source().key())).collect::<Vec<_>>();
+				outbound.into_iter().chain(inbound)
+			})
+			.collect()
+	}
+
+	/// Total weight given by the `weight` closure of the edges on the
+	/// boundary of the set, see `boundary`.
+	fn cut_size<F>(&self, set: &[K], weight: F) -> f64
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64 + Sync + Send,
+	{
+		self.boundary(set).par_iter().map(weight).sum()
+	}
+
+	/// # Conductance
+	///
+	/// The cut size of the set divided by the volume of the set or of the
+	/// rest of the graph, whichever is smaller. The volume of a set of nodes
+	/// is the total weight of the edges of its nodes where loops count twice,
+	/// so a set with a low conductance is well separated from the rest of
+	/// the graph. Computed in parallel over the nodes. Returns None if either
+	/// volume is zero.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// // Two triangles joined by one edge.
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 1..7 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// for (s, t) in [(1, 2), (2, 3), (3, 1), (4, 5), (5, 6), (6, 4), (3, 4)] {
+	///     g.add_edge(s, t, Empty);
+	/// }
+	///
+	/// assert!(g.conductance(&[1, 2, 3], |_| 1.0) == Some(1.0 / 7.0));
+	/// assert!(g.conductance(&[1, 2], |_| 1.0) == Some(2.0 / 4.0));
+	/// assert!(g.conductance(&[1, 2, 3, 4, 5, 6], |_| 1.0).is_none());
+	/// ```
+	fn conductance<F>(&self, set: &[K], weight: F) -> Option<f64>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64 + Sync + Send,
+	{
+		let inside: HashSet<&K> = set.iter().collect();
+		let (volume, total) = self
+			.nodes()
+			.par_iter()
+			.map(|node| {
+				let degree: f64 = node.outbound().iter().map(&weight).sum::<f64>()
+					+ node.inbound().iter().filter_map(|e| e.upgrade()).map(|e| weight(&e)).sum::<f64>();
+				match inside.contains(node.key()) {
+					true => { (degree, degree) }
+					false => { (0.0, degree) }
+				}
+			})
+			.reduce(|| (0.0, 0.0), |a, b| (a.0 + b.0, a.1 + b.1));
+		let smaller = volume.min(total - volume);
+		match smaller > 0.0 {
+			true => { Some(self.cut_size(set, &weight) / smaller) }
+			false => { None }
+		}
+	}
+
+	/// Sort the outbound and inbound edges of every node by the weight given
+	/// by the `weight` closure, lightest first, in parallel over the nodes.
+	/// Traversals then follow the cheapest edges of a node first. Weights are
+	/// compared with `f64::total_cmp` and edges of equal weight keep their
+	/// order.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, f64>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, 5.0);
+	/// g.add_edge(0, 2, 1.0);
+	/// g.add_edge(0, 3, 3.0);
+	/// g.sort_all_edges_by_weight(|e| e.load());
+	///
+	/// let order = g.fold_dfs(0, Vec::new(), |mut acc, node, _| {
+	///     acc.push(*node.key());
+	///     acc
+	/// });
+	/// assert!(order == Some(vec![0, 2, 3, 1]));
+	/// ```
+	fn sort_all_edges_by_weight<F>(&self, weight: F)
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64 + Sync + Send,
+	{
+		self.nodes().into_par_iter().for_each(|node| {
+			node.sort_outbound_by(|a, b| weight(a).total_cmp(&weight(b)));
+			node.sort_inbound_by(|a, b| weight(a).total_cmp(&weight(b)));
+		});
+	}
+
+	/// Sample `k` edges without replacement with probabilities proportional to
+	/// their weight given by the `weight` closure, using weighted reservoir
+	/// sampling. Every edge draws a random key from its weight and the edges
+	/// with the largest keys are kept. The nodes are split into chunks that
+	/// are sampled in parallel and merged, each with its own generator seeded
+	/// from `rng`, so the sample only depends on the state of `rng`. Edges
+	/// with a weight that isn't positive are never sampled. Returns fewer
+	/// than `k` edges if there aren't enough edges to sample.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use rand::{rngs::StdRng, SeedableRng};
+	///
+	/// let mut g = Digraph::<u32, Empty, f64>::new();
+	/// for i in 0..100 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// for i in 1..100 {
+	///     g.add_edge(0, i, (i % 2) as f64);
+	/// }
+	///
+	/// let sample = g.sample_edges(10, |e| e.load(), &mut StdRng::seed_from_u64(7));
+	/// assert!(sample.len() == 10);
+	/// assert!(sample.iter().all(|e| e.load() == 1.0));
+	/// ```
+	fn sample_edges<F, R>(&self, k: usize, weight: F, rng: &mut R) -> Vec<Arc<Edge<K, N, E>>>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64 + Sync + Send,
+		R: Rng + ?Sized,
+	{
+		const CHUNK: usize = 1024;
+		if k == 0 {
+			return Vec::new();
+		}
+		let seed: u64 = rng.gen();
+		let nodes = self.nodes();
+		let keep = |mut heap: BinaryHeap<MinCost<Arc<Edge<K, N, E>>>>, key: f64, edge: Arc<Edge<K, N, E>>| {
+			if heap.len() < k {
+				heap.push(MinCost { cost: key, item: edge });
+			} else if heap.peek().is_some_and(|min| key > min.cost) {
+				heap.pop();
+				heap.push(MinCost { cost: key, item: edge });
+			}
+			heap
+		};
+		let heap = nodes
+			.par_chunks(CHUNK)
+			.enumerate()
+			.map(|(i, chunk)| {
+				let mut rng = StdRng::seed_from_u64(seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+				let mut heap = BinaryHeap::new();
+				for node in chunk.iter() {
+					for edge in node.outbound().iter() {
+						let w = weight(edge);
+						if w > 0.0 && w.is_finite() {
+							// Equivalent to keeping the largest u^(1 / w) but
+							// without underflow for small weights.
+							let u: f64 = rng.gen_range(f64::MIN_POSITIVE..1.0);
+							heap = keep(heap, u.ln() / w, edge.clone());
+						}
+					}
+				}
+				heap
+			})
+			.reduce(BinaryHeap::new, |a, b| {
+				b.into_iter().fold(a, |heap, MinCost { cost, item }| keep(heap, cost, item))
+			});
+		let mut res = heap.into_vec();
+		res.sort_by(|a, b| b.cost.partial_cmp(&a.cost).unwrap_or(std::cmp::Ordering::Equal));
+		res.into_iter().map(|m| m.item).collect()
+	}
+
 	/// Print graph nodes.
 	fn print_nodes(&self) {
 		self.iter_nodes(&| node | {
@@ -165,11 +2108,10 @@ where
 
 	/// Print graph edges.
 	fn print_edges(&self) {
-		let sign;
-		match Self::directed() {
-			true => { sign = "->"}
-			false => { sign = "--" }
-		}
+		let sign = match Self::directed() {
+			true => { "->" }
+			false => { "--" }
+		};
 		self.iter_nodes(&| node | {
 			for edge in node.outbound().iter() {
 				println!("	{} {} {} [label = \"{}\"]",
@@ -182,16 +2124,161 @@ where
 	}
 
 	/// Print graph in .dot format.
-	fn print_graph(&self) {
-		let name;
-		match Self::directed() {
-			true => { name = "digraph"}
-			false => { name = "graph" }
+	fn print_graph(&self)
+	where
+		Self: Sized,
+	{
+		println!("{}", self.display());
+	}
+
+	/// Formatter for the graph, which prints every node and edge with their
+	/// data as dot unless configured otherwise. See `Printer`.
+	fn display(&self) -> Printer<'_, K, N, E, Self>
+	where
+		Self: Sized,
+	{
+		Printer::new(self)
+	}
+}
+
+// Keys of every edge of the node, each edge once, for the `DelEdge` events
+// sent before the node is deleted.
+fn incident_edges<K, N, E>(node: &Arc<Node<K, N, E>>) -> Vec<(K, K)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut edges: Vec<(K, K)> = node.outbound().iter()
+		.map(|e| (node.key().clone(), e.target().key().clone()))
+		.collect();
+	for edge in node.inbound().iter().filter_map(|e| e.upgrade()) {
+		// Loops are already listed among the outbound edges.
+		if !Arc::ptr_eq(&edge.source(), node) {
+			edges.push((edge.source().key().clone(), node.key().clone()));
+		}
+	}
+	edges
+}
+
+// Delete the nodes for which the predicate returns true in rounds. The first
+// round checks every node and later rounds the neighbors of the nodes deleted
+// in the previous round.
+fn prune_rounds<K, N, E, G, F>(graph: &mut G, rounds: usize, remove: F) -> Vec<Vec<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn (&Arc<Node<K, N, E>>) -> bool,
+{
+	let mut res = Vec::new();
+	let mut candidates = graph.nodes();
+	while res.len() < rounds {
+		let mut removed: HashSet<K> = HashSet::new();
+		let mut round = Vec::new();
+		for node in candidates.iter() {
+			if remove(node) && removed.insert(node.key().clone()) {
+				round.push(node.clone());
+			}
+		}
+		if round.is_empty() {
+			break;
+		}
+		let mut next: HashMap<K, Arc<Node<K, N, E>>> = HashMap::new();
+		for node in round.iter() {
+			for edge in node.outbound().iter() {
+				let target = edge.target();
+				if !removed.contains(target.key()) {
+					next.insert(target.key().clone(), target);
+				}
+			}
+			for edge in node.inbound().iter().filter_map(|e| e.upgrade()) {
+				let source = edge.source();
+				if !removed.contains(source.key()) {
+					next.insert(source.key().clone(), source);
+				}
+			}
+		}
+		for node in round.iter() {
+			graph.del_node(node.key().clone());
+		}
+		res.push(round.iter().map(|node| node.key().clone()).collect());
+		candidates = next.into_values().collect();
+	}
+	res
+}
+
+// A cycle among the nodes with remaining predecessors, found by walking
+// predecessors until a node repeats. Every such node has a predecessor that
+// also remains.
+pub(crate) fn cycle(adjacency: &[Vec<(usize, usize)>], remaining: &[usize]) -> Vec<usize> {
+	let mut inbound = vec![Vec::new(); adjacency.len()];
+	for (u, adjacent) in adjacency.iter().enumerate() {
+		for &(w, _) in adjacent.iter() {
+			if remaining[u] > 0 {
+				inbound[w].push(u);
+			}
+		}
+	}
+	let mut position: Vec<Option<usize>> = vec![None; adjacency.len()];
+	let mut walk = Vec::new();
+	let mut v = match remaining.iter().position(|r| *r > 0) {
+		Some(v) => { v }
+		None => { return walk }
+	};
+	while position[v].is_none() {
+		position[v] = Some(walk.len());
+		walk.push(v);
+		v = inbound[v][0];
+	}
+	let mut res = walk.split_off(position[v].unwrap());
+	res.reverse();
+	res
+}
+
+// Shared state of the tasks of `execute_topological`.
+struct Schedule<'a, K, N, E, T, F>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	indexed: &'a Indexed<K, N, E>,
+	// Amount of unfinished predecessors of every node.
+	pending: Vec<AtomicUsize>,
+	results: Vec<Mutex<Option<T>>>,
+	task: &'a F,
+}
+
+impl<'a, K, N, E, T, F> Schedule<'a, K, N, E, T, F>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	T: Send,
+	F: Fn (&K, N) -> T + Sync + Send,
+{
+	// Run the task of a node and then the tasks of the successors it was the
+	// last predecessor of, continuing with one of them on this thread and
+	// spawning the rest.
+	fn run<'s>(&'s self, scope: &rayon::Scope<'s>, mut v: usize) {
+		loop {
+			let node = &self.indexed.nodes[v];
+			*self.results[v].lock() = Some((self.task)(node.key(), node.load()));
+			let mut next = None;
+			for &(w, _) in self.indexed.adjacency[v].iter() {
+				if self.pending[w].fetch_sub(1, Ordering::AcqRel) == 1 {
+					if let Some(u) = next.replace(w) {
+						scope.spawn(move |scope| self.run(scope, u));
+					}
+				}
+			}
+			match next {
+				Some(w) => { v = w }
+				None => { return }
+			}
 		}
-		println!("{} {{", name);
-		self.print_nodes();
-		self.print_edges();
-		println!("}}");
 	}
 }
 
@@ -204,6 +2291,7 @@ where
     E: Clone + Debug + Display + Sync + Send,
 {
     nodes: HashMap<K, Arc<Node<K, N, E>>>,
+	subscribers: Subscribers<K, N, E>,
 }
 
 impl<K, N, E> Graph<K, N, E> for Ungraph<K, N, E>
@@ -215,6 +2303,7 @@ where
 	fn new() -> Self {
         Self {
             nodes: HashMap::new(),
+			subscribers: Subscribers::new(),
         }
     }
 
@@ -223,22 +2312,39 @@ where
 	}
 
 	fn add_node(&mut self, key: K, data: N) -> bool {
-        if self.nodes.contains_key(&key) {
-            false
-        } else {
-            let node = Arc::new(Node::new(key.clone(), data));
-            self.nodes.insert(key, node);
-            true
-        }
+		match self.nodes.entry(key) {
+			Entry::Occupied(_) => { false }
+			Entry::Vacant(entry) => {
+				self.subscribers.send(|| Event::AddNode { key: entry.key().clone(), data: data.clone() });
+				let node = Arc::new(Node::new(entry.key().clone(), data));
+				entry.insert(node);
+				true
+			}
+		}
     }
 
-	fn get_node(&self, node: K) -> Option<Arc<Node<K, N, E>>> {
-		match self.nodes.get(&node) {
-			Some(n) => { Some(n.clone()) }
-			None => None
+	fn del_node(&mut self, key: K) -> bool {
+		match self.nodes.remove(&key) {
+			Some(node) => {
+				let edges = match self.subscribers.active() {
+					true => { incident_edges(&node) }
+					false => { Vec::new() }
+				};
+				isolate(&node);
+				for (source, target) in edges {
+					self.subscribers.send(|| Event::DelEdge { source, target });
+				}
+				self.subscribers.send(|| Event::DelNode { key });
+				true
+			}
+			None => { false }
 		}
 	}
 
+	fn get_node(&self, node: K) -> Option<Arc<Node<K, N, E>>> {
+		self.nodes.get(&node).cloned()
+	}
+
 	fn iter_nodes(&self, f: &dyn Fn (Arc<Node<K, N, E>>)) {
 		for (_, node) in self.nodes.iter() {
 			f(node.clone());
@@ -248,6 +2354,42 @@ where
 	fn node_count(&self) -> usize {
         self.nodes.len()
     }
+
+	fn subscribers(&self) -> Option<&Subscribers<K, N, E>> {
+		Some(&self.subscribers)
+	}
+
+	fn clear(&mut self) -> usize {
+		let count = self.nodes.len();
+		let nodes = std::mem::take(&mut self.nodes);
+		if self.subscribers.active() {
+			for node in nodes.values() {
+				for edge in node.outbound().iter() {
+					self.subscribers.send(|| Event::DelEdge {
+						source: node.key().clone(),
+						target: edge.target().key().clone(),
+					});
+				}
+			}
+		}
+		for (key, node) in nodes {
+			node.outbound_mut().clear();
+			node.inbound_mut().clear();
+			self.subscribers.send(|| Event::DelNode { key });
+		}
+		count
+	}
+
+	fn compact(&mut self) -> usize {
+		let before = self.nodes.capacity();
+		self.nodes.shrink_to_fit();
+		let mut reclaimed = (before - self.nodes.capacity())
+			* std::mem::size_of::<(K, Arc<Node<K, N, E>>)>();
+		for node in self.nodes.values() {
+			reclaimed += node.shrink_to_fit();
+		}
+		reclaimed
+	}
 }
 
 /// Directed graph with arbitrary edge values. Underlying container type is
@@ -259,6 +2401,7 @@ where
     E: Clone + Debug + Display + Sync + Send,
 {
     nodes: HashMap<K, Arc<Node<K, N, E>>>,
+	subscribers: Subscribers<K, N, E>,
 }
 
 impl<K, N, E> Graph<K, N, E> for Digraph<K, N, E>
@@ -270,6 +2413,7 @@ where
 	fn new() -> Self {
         Self {
             nodes: HashMap::new(),
+			subscribers: Subscribers::new(),
         }
     }
 
@@ -278,22 +2422,39 @@ where
 	}
 
 	fn add_node(&mut self, key: K, data: N) -> bool {
-        if self.nodes.contains_key(&key) {
-            false
-        } else {
-            let node = Arc::new(Node::new(key.clone(), data));
-            self.nodes.insert(key, node);
-            true
-        }
+		match self.nodes.entry(key) {
+			Entry::Occupied(_) => { false }
+			Entry::Vacant(entry) => {
+				self.subscribers.send(|| Event::AddNode { key: entry.key().clone(), data: data.clone() });
+				let node = Arc::new(Node::new(entry.key().clone(), data));
+				entry.insert(node);
+				true
+			}
+		}
     }
 
-	fn get_node(&self, node: K) -> Option<Arc<Node<K, N, E>>>  {
-		match self.nodes.get(&node) {
-			Some(n) => { Some(n.clone()) }
-			None => None
+	fn del_node(&mut self, key: K) -> bool {
+		match self.nodes.remove(&key) {
+			Some(node) => {
+				let edges = match self.subscribers.active() {
+					true => { incident_edges(&node) }
+					false => { Vec::new() }
+				};
+				isolate(&node);
+				for (source, target) in edges {
+					self.subscribers.send(|| Event::DelEdge { source, target });
+				}
+				self.subscribers.send(|| Event::DelNode { key });
+				true
+			}
+			None => { false }
 		}
 	}
 
+	fn get_node(&self, node: K) -> Option<Arc<Node<K, N, E>>>  {
+		self.nodes.get(&node).cloned()
+	}
+
 	fn iter_nodes(&self, f: &dyn Fn (Arc<Node<K, N, E>>)) {
 		for (_, node) in self.nodes.iter() {
 			f(node.clone());
@@ -303,4 +2464,309 @@ where
 	fn node_count(&self) -> usize {
         self.nodes.len()
     }
+
+	fn subscribers(&self) -> Option<&Subscribers<K, N, E>> {
+		Some(&self.subscribers)
+	}
+
+	fn clear(&mut self) -> usize {
+		let count = self.nodes.len();
+		let nodes = std::mem::take(&mut self.nodes);
+		if self.subscribers.active() {
+			for node in nodes.values() {
+				for edge in node.outbound().iter() {
+					self.subscribers.send(|| Event::DelEdge {
+						source: node.key().clone(),
+						target: edge.target().key().clone(),
+					});
+				}
+			}
+		}
+		for (key, node) in nodes {
+			node.outbound_mut().clear();
+			node.inbound_mut().clear();
+			self.subscribers.send(|| Event::DelNode { key });
+		}
+		count
+	}
+
+	fn compact(&mut self) -> usize {
+		let before = self.nodes.capacity();
+		self.nodes.shrink_to_fit();
+		let mut reclaimed = (before - self.nodes.capacity())
+			* std::mem::size_of::<(K, Arc<Node<K, N, E>>)>();
+		for node in self.nodes.values() {
+			reclaimed += node.shrink_to_fit();
+		}
+		reclaimed
+	}
+}
+
+/// A graph behind a read-write lock shared between threads. Any number of
+/// readers can hold a read guard at the same time while a write guard
+/// excludes everyone else, so a batch of queries under one read guard sees
+/// the graph in a single consistent state. Mutations made directly on nodes,
+/// for example with `connect` or `Node::store`, only take the node locks and
+/// should be made under a write guard.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+///
+/// let shared = Shared::new(Digraph::<u32, u32, f64>::new());
+/// {
+///     let mut g = shared.write();
+///     g.add_node(1, 10);
+///     g.add_node(2, 20);
+///     g.add_edge(1, 2, 0.5);
+/// }
+/// let g = shared.read();
+/// assert!(g.node_count() == 2 && g.edge_count() == 1);
+/// ```
+pub struct Shared<G> {
+	graph: RwLock<G>,
+}
+
+/// Shared access to a graph held by a reader.
+pub type ReadGuard<'a, G> = RwLockReadGuard<'a, G>;
+
+/// Exclusive access to a graph held by a writer.
+pub type WriteGuard<'a, G> = RwLockWriteGuard<'a, G>;
+
+impl<G> Shared<G> {
+	/// Put a graph behind a lock.
+	pub fn new(graph: G) -> Self {
+		Shared { graph: RwLock::new(graph) }
+	}
+
+	/// Wait until no writer holds the lock and return a read guard.
+	pub fn read(&self) -> ReadGuard<'_, G> {
+		self.graph.read()
+	}
+
+	/// Wait until no one holds the lock and return a write guard.
+	pub fn write(&self) -> WriteGuard<'_, G> {
+		self.graph.write()
+	}
+
+	/// Return a read guard if no writer holds the lock.
+	pub fn try_read(&self) -> Option<ReadGuard<'_, G>> {
+		self.graph.try_read()
+	}
+
+	/// Return a write guard if no one holds the lock.
+	pub fn try_write(&self) -> Option<WriteGuard<'_, G>> {
+		self.graph.try_write()
+	}
+
+	/// Take the graph out of the lock.
+	pub fn into_inner(self) -> G {
+		self.graph.into_inner()
+	}
+}
+
+impl<G> From<G> for Shared<G> {
+	fn from(graph: G) -> Self {
+		Shared::new(graph)
+	}
+}
+
+/// A mutation of a graph waiting in a `Deferred` queue.
+pub type Mutation<G> = Box<dyn FnOnce(&mut G) + Send>;
+
+/// A queue of mutations collected while the graph is borrowed, for example
+/// by a visitor during a traversal, and applied in order once the borrow has
+/// ended. The queue can be shared between threads. See `Graph::deferred`.
+pub struct Deferred<G> {
+	mutations: Mutex<Vec<Mutation<G>>>,
+}
+
+impl<G> Deferred<G> {
+	/// Create an empty queue.
+	pub fn new() -> Self {
+		Deferred { mutations: Mutex::new(Vec::new()) }
+	}
+
+	/// Queue a mutation.
+	pub fn defer<F>(&self, mutation: F)
+	where
+		F: FnOnce(&mut G) + Send + 'static,
+	{
+		self.mutations.lock().push(Box::new(mutation));
+	}
+
+	/// Amount of queued mutations.
+	pub fn len(&self) -> usize {
+		self.mutations.lock().len()
+	}
+
+	/// True if no mutations are queued.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Apply the queued mutations to the graph in the order they were queued
+	/// and return their amount.
+	pub fn apply(self, graph: &mut G) -> usize {
+		let mutations = self.mutations.into_inner();
+		let count = mutations.len();
+		for mutation in mutations {
+			mutation(graph);
+		}
+		count
+	}
+}
+
+impl<G> Default for Deferred<G> {
+	fn default() -> Self {
+		Deferred::new()
+	}
+}
+
+/// A graph keyed by strings that stores every distinct string once and keys
+/// the underlying graph by 32 bit ids instead, which makes the nodes smaller
+/// and hashing faster. Strings stay interned after their node is deleted, so
+/// adding the node again reuses its id. The nodes handed out are those of
+/// the underlying graph and report their id as their key, which `name` turns
+/// back into the string.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+///
+/// let mut g = InternedGraph::<u32, f64>::new();
+/// g.add_node("helsinki", 1);
+/// g.add_node("tallinn", 2);
+/// g.add_edge("helsinki", "tallinn", 80.0);
+///
+/// let node = g.get_node("tallinn").unwrap();
+/// assert!(g.name(*node.key()) == Some("tallinn"));
+/// assert!(g.get_edge("helsinki", "tallinn").unwrap().load() == 80.0);
+/// ```
+pub struct InternedGraph<N, E, G = Digraph<u32, N, E>> {
+	graph: G,
+	ids: HashMap<Arc<str>, u32>,
+	names: Vec<Arc<str>>,
+	data: std::marker::PhantomData<(N, E)>,
+}
+
+impl<N, E, G> InternedGraph<N, E, G>
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<u32, N, E>,
+{
+	/// Create an empty graph.
+	pub fn new() -> Self {
+		InternedGraph { graph: G::new(), ids: HashMap::new(), names: Vec::new(), data: std::marker::PhantomData }
+	}
+
+	/// Id of the string, interning it if it hasn't been seen before.
+	///
+	/// # Panics
+	///
+	/// Panics if every `u32` id has been given to a string already, since
+	/// ids must stay distinct.
+	pub fn intern(&mut self, name: &str) -> u32 {
+		if let Some(id) = self.ids.get(name) {
+			return *id;
+		}
+		let id = match u32::try_from(self.names.len()) {
+			Ok(id) => { id }
+			Err(_) => { panic!("every u32 id has been given to an interned string") }
+		};
+		let name: Arc<str> = Arc::from(name);
+		self.names.push(name.clone());
+		self.ids.insert(name, id);
+		id
+	}
+
+	/// Id of an interned string.
+	pub fn id(&self, name: &str) -> Option<u32> {
+		self.ids.get(name).copied()
+	}
+
+	/// String of an id.
+	pub fn name(&self, id: u32) -> Option<&str> {
+		self.names.get(id as usize).map(|n| n.as_ref())
+	}
+
+	/// Add a new node to the graph.
+	pub fn add_node(&mut self, name: &str, data: N) -> bool {
+		let id = self.intern(name);
+		self.graph.add_node(id, data)
+	}
+
+	/// Delete a node from the graph.
+	pub fn del_node(&mut self, name: &str) -> bool {
+		match self.id(name) {
+			Some(id) => { self.graph.del_node(id) }
+			None => { false }
+		}
+	}
+
+	/// Get a node from the graph.
+	pub fn get_node(&self, name: &str) -> Option<Arc<Node<u32, N, E>>> {
+		self.graph.get_node(self.id(name)?)
+	}
+
+	/// Add a new edge between existing nodes.
+	pub fn add_edge(&mut self, source: &str, target: &str, data: E) -> bool {
+		match (self.id(source), self.id(target)) {
+			(Some(s), Some(t)) => { self.graph.add_edge(s, t, data) }
+			_ => { false }
+		}
+	}
+
+	/// Delete an edge from the graph.
+	pub fn del_edge(&mut self, source: &str, target: &str) -> bool {
+		match (self.id(source), self.id(target)) {
+			(Some(s), Some(t)) => { self.graph.del_edge(s, t) }
+			_ => { false }
+		}
+	}
+
+	/// Get an edge from the graph.
+	pub fn get_edge(&self, source: &str, target: &str) -> Option<Arc<Edge<u32, N, E>>> {
+		self.graph.get_edge(self.id(source)?, self.id(target)?)
+	}
+
+	/// Amount of nodes in the graph.
+	pub fn node_count(&self) -> usize {
+		self.graph.node_count()
+	}
+
+	/// Amount of edges in the graph.
+	pub fn edge_count(&self) -> usize {
+		self.graph.edge_count()
+	}
+
+	/// The underlying graph keyed by ids, for running algorithms on.
+	pub fn graph(&self) -> &G {
+		&self.graph
+	}
+
+	/// Copy of the graph keyed by the strings.
+	pub fn to_string_keyed<G2>(&self) -> G2
+	where
+		G2: Graph<String, N, E>,
+	{
+		match self.graph.map_keys(|id| self.names[*id as usize].to_string()) {
+			Ok(g) => { g }
+			Err(_) => { unreachable!("interned strings are distinct") }
+		}
+	}
+}
+
+impl<N, E, G> Default for InternedGraph<N, E, G>
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<u32, N, E>,
+{
+	fn default() -> Self {
+		InternedGraph::new()
+	}
 }
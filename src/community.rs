@@ -0,0 +1,508 @@
+//=============================================================================
+// COMMUNITY DETECTION
+//=============================================================================
+
+//! Algorithms that partition the nodes of a graph into densely connected
+//! communities, and summarization of a graph into a graph of the groups of
+//! its nodes, which is small enough to draw when the graph itself isn't.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::centrality::{brandes, stable_order, Convergence, Update};
+use crate::indexed::Indexed;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display, Formatter},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// A hierarchy of partitions ordered from the coarsest to the finest. Each
+/// partition is a list of communities and each community a list of keys.
+pub type Dendrogram<K> = Vec<Vec<Vec<K>>>;
+
+/// Data of a node of a summary graph, which stands for a group of nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Supernode {
+	/// Amount of nodes in the group.
+	pub size: usize,
+	/// Total weight of the edges between the nodes of the group.
+	pub internal: f64,
+}
+
+impl Display for Supernode {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} nodes, {} internal", self.size, self.internal)
+	}
+}
+
+/// A summary graph and the group of each summarized node.
+#[derive(Clone, Debug)]
+pub struct Summary<K, C, S>
+where
+	K: Hash + Eq,
+{
+	/// Graph with a supernode for every group, keyed by the group, and an
+	/// edge between two supernodes with the total weight of the edges
+	/// between their groups.
+	pub graph: S,
+	/// Group of every summarized node by its key. Nodes left out of the
+	/// summary aren't in it.
+	pub groups: HashMap<K, C>,
+}
+
+/// Statistics of a group of nodes, see `Graph::group_by_nodes`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GroupStats {
+	/// Amount of nodes in the group.
+	pub nodes: usize,
+	/// Amount of edges between the nodes of the group, loops included.
+	pub internal: usize,
+	/// Amount of edges between the group and the other groups.
+	pub external: usize,
+	/// Least degree of a node of the group.
+	pub min_degree: usize,
+	/// Largest degree of a node of the group.
+	pub max_degree: usize,
+}
+
+impl GroupStats {
+	// Statistics that any other statistics can be added to.
+	pub(crate) fn empty() -> Self {
+		GroupStats { min_degree: usize::MAX, ..GroupStats::default() }
+	}
+
+	// Add the statistics of another part of the same group.
+	pub(crate) fn add(&mut self, other: &GroupStats) {
+		self.nodes += other.nodes;
+		self.internal += other.internal;
+		self.external += other.external;
+		self.min_degree = self.min_degree.min(other.min_degree);
+		self.max_degree = self.max_degree.max(other.max_degree);
+	}
+
+	/// Mean degree of the nodes of the group. Loops count twice.
+	pub fn mean_degree(&self) -> f64 {
+		(2 * self.internal + self.external) as f64 / self.nodes as f64
+	}
+
+	/// Amount of internal edges relative to the amount of possible edges
+	/// between distinct nodes of the group. Loops can push it above one.
+	pub fn density(&self, directed: bool) -> f64 {
+		let pairs = self.nodes * self.nodes.saturating_sub(1);
+		match (pairs, directed) {
+			(0, _) => { 0.0 }
+			(_, true) => { self.internal as f64 / pairs as f64 }
+			(_, false) => { 2.0 * self.internal as f64 / pairs as f64 }
+		}
+	}
+}
+
+/// Statistics of every group of nodes and the amount of edges between every
+/// two groups. In undirected graphs an edge between two groups is counted
+/// in both orders of the groups.
+#[derive(Clone, Debug)]
+pub struct Groups<C>
+where
+	C: Hash + Eq,
+{
+	/// Statistics of every group.
+	pub groups: HashMap<C, GroupStats>,
+	/// Amount of edges from the first group to the second, for every two
+	/// groups with edges between them.
+	pub between: HashMap<(C, C), usize>,
+}
+
+//=============================================================================
+
+/// # Girvan-Newman
+///
+/// Divisive clustering which repeatedly removes the edge with the highest
+/// edge betweenness from the graph. Each time the removal splits a community
+/// in two, the new partition is recorded in the dendrogram. The first level
+/// holds the connected components of the graph and the last level has every
+/// node in its own community. Edge direction is ignored.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::community::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 3, Empty);
+/// g.add_edge(3, 4, Empty);
+///
+/// let dendrogram = girvan_newman(&g);
+///
+/// assert!(dendrogram[0].len() == 1);
+/// assert!(dendrogram[1].len() == 2);
+/// assert!(dendrogram.last().unwrap().len() == 4);
+/// ```
+pub fn girvan_newman<K, N, E, G>(graph: &G) -> Dendrogram<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Both);
+	let mut removed = vec![false; indexed.edges.len()];
+	let sources: Vec<usize> = (0..indexed.nodes.len()).collect();
+	let mut labels = components(&indexed.adjacency, &removed);
+	let mut count = labels.iter().max().map_or(0, |c| c + 1);
+	let mut res = vec![partition(&indexed, &labels, count)];
+	let mut remaining = removed.len();
+	while remaining > 0 {
+		let scores = brandes(&indexed.adjacency, &removed, &sources);
+		let mut max = None;
+		for (e, score) in scores.iter().enumerate() {
+			if removed[e] {
+				continue;
+			}
+			match max {
+				Some((_, best)) if best >= *score => {}
+				_ => { max = Some((e, *score)) }
+			}
+		}
+		let (e, _) = max.unwrap();
+		removed[e] = true;
+		remaining -= 1;
+		let new_labels = components(&indexed.adjacency, &removed);
+		let new_count = new_labels.iter().max().map_or(0, |c| c + 1);
+		if new_count > count {
+			labels = new_labels;
+			count = new_count;
+			res.push(partition(&indexed, &labels, count));
+		}
+	}
+	res
+}
+
+/// # Label Propagation
+///
+/// Find communities by letting every node repeatedly take the label that is
+/// most common among its neighbors, starting with every node in its own
+/// community. A node keeps its label if it is one of the most common, and
+/// other ties go to the label of the node whose key comes first in an order
+/// that is the same in every run, or with an asynchronous update and a seed
+/// to a label picked at random. Iteration stops once no label
+/// changes or after `max_iterations`. Synchronous updates may oscillate
+/// between two labelings, which asynchronous updates don't. Edge direction
+/// is ignored. Returns the communities together with the amount of nodes
+/// that changed their label in every iteration.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::centrality::Update;
+/// use fastgraph::community::*;
+/// use fastgraph::core::Empty;
+///
+/// // Two cliques of four nodes joined by one edge.
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..8 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..8 {
+///     for j in i + 1..8 {
+///         if i / 4 == j / 4 {
+///             g.add_edge(i, j, Empty);
+///         }
+///     }
+/// }
+/// g.add_edge(3, 4, Empty);
+///
+/// let (communities, convergence) = label_propagation(&g, 20, Update::Asynchronous { seed: Some(1) });
+/// assert!(convergence.converged);
+/// assert!(communities.len() == 2 && communities.iter().all(|c| c.len() == 4));
+/// ```
+pub fn label_propagation<K, N, E, G>(graph: &G, max_iterations: usize, update: Update) -> (Vec<Vec<K>>, Convergence)
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Both);
+	let n = indexed.nodes.len();
+	// Labels are ranks in an order of the keys, so that ties are broken the
+	// same way in every run.
+	let mut order = stable_order(&indexed);
+	let mut labels = vec![0; n];
+	for (rank, v) in order.iter().enumerate() {
+		labels[*v] = rank;
+	}
+	let mut convergence = Convergence::default();
+	let mut rng = match update {
+		Update::Asynchronous { seed: Some(seed) } => { Some(StdRng::seed_from_u64(seed)) }
+		_ => { None }
+	};
+	let _span = trace_span!("label_propagation", nodes = n);
+	for _ in 0..max_iterations {
+		let changed = match update {
+			Update::Synchronous => {
+				let new_labels: Vec<usize> = (0..n)
+					.into_par_iter()
+					.map(|v| most_common(&indexed.adjacency[v], v, &labels, None))
+					.collect();
+				let changed = labels.iter().zip(new_labels.iter()).filter(|(a, b)| a != b).count();
+				labels = new_labels;
+				changed
+			}
+			Update::Asynchronous { .. } => {
+				if let Some(rng) = rng.as_mut() {
+					order.shuffle(rng);
+				}
+				let mut changed = 0;
+				for v in order.iter().copied() {
+					let label = most_common(&indexed.adjacency[v], v, &labels, rng.as_mut());
+					if label != labels[v] {
+						labels[v] = label;
+						changed += 1;
+					}
+				}
+				changed
+			}
+		};
+		convergence.residuals.push(changed as f64);
+		if changed == 0 {
+			convergence.converged = true;
+			break;
+		}
+	}
+	// Number the communities in the order of their first node.
+	let mut number: HashMap<usize, usize> = HashMap::new();
+	for label in labels.iter_mut() {
+		let next = number.len();
+		*label = *number.entry(*label).or_insert(next);
+	}
+	(partition(&indexed, &labels, number.len()), convergence)
+}
+
+// The label most common among the neighbors of a node, which is the label of
+// the node if it is one of the most common. Other ties go to a random label
+// with a random number generator and otherwise to the smallest.
+fn most_common(adjacent: &[(usize, usize)], v: usize, labels: &[usize], rng: Option<&mut StdRng>) -> usize {
+	let mut counts: HashMap<usize, usize> = HashMap::new();
+	for (w, _) in adjacent.iter().filter(|(w, _)| *w != v) {
+		*counts.entry(labels[*w]).or_insert(0) += 1;
+	}
+	let max = match counts.values().max() {
+		Some(max) => { *max }
+		None => { return labels[v] }
+	};
+	if counts.get(&labels[v]) == Some(&max) {
+		return labels[v];
+	}
+	let mut best: Vec<usize> = counts.into_iter().filter(|(_, c)| *c == max).map(|(l, _)| l).collect();
+	best.sort_unstable();
+	match rng {
+		Some(rng) => { best[rng.gen_range(0..best.len())] }
+		None => { best[0] }
+	}
+}
+
+//=============================================================================
+
+// Label the connected components of an adjacency list ignoring removed
+// edges. Labels are numbered in order of discovery.
+pub(crate) fn components(adjacency: &[Vec<(usize, usize)>], removed: &[bool]) -> Vec<usize> {
+	let mut labels = vec![usize::MAX; adjacency.len()];
+	let mut count = 0;
+	for s in 0..adjacency.len() {
+		if labels[s] != usize::MAX {
+			continue;
+		}
+		let mut stack = vec![s];
+		labels[s] = count;
+		while let Some(v) = stack.pop() {
+			for (w, e) in adjacency[v].iter() {
+				if !removed[*e] && labels[*w] == usize::MAX {
+					labels[*w] = count;
+					stack.push(*w);
+				}
+			}
+		}
+		count += 1;
+	}
+	labels
+}
+
+// Group node keys by component label.
+fn partition<K, N, E>(indexed: &Indexed<K, N, E>, labels: &[usize], count: usize) -> Vec<Vec<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut res = vec![Vec::new(); count];
+	for (i, label) in labels.iter().enumerate() {
+		res[*label].push(indexed.key(i));
+	}
+	res
+}
+
+//=============================================================================
+
+/// # Summarize
+///
+/// Summarize the graph by grouping its nodes into supernodes. The `group`
+/// closure gives the key of the supernode of each node. Edges between two
+/// groups are merged into a single edge whose weight is the sum of the
+/// weights given by the `weight` closure, and edges within a group are
+/// summed into the `internal` weight of the supernode. The type of the
+/// summary graph is chosen by the caller and should have the direction of
+/// the graph.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::community::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, f64>::new();
+/// for i in 0..6 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 2, 1.0);
+/// g.add_edge(2, 1, 2.0);
+/// g.add_edge(1, 4, 3.0);
+/// g.add_edge(3, 5, 4.0);
+///
+/// // Group the nodes by parity.
+/// let summary: Summary<u32, u32, Digraph<u32, Supernode, f64>> = summarize(&g, |n| n.key() % 2, |e| e.load());
+/// assert!(summary.graph.node_count() == 2);
+/// assert!(summary.graph.get_node(0).unwrap().load().internal == 1.0);
+/// assert!(summary.graph.get_edge(0, 1).unwrap().load() == 2.0);
+/// assert!(summary.graph.get_edge(1, 0).unwrap().load() == 3.0);
+/// assert!(summary.groups[&5] == 1);
+/// ```
+pub fn summarize<K, N, E, G, C, S, FG, FW>(graph: &G, group: FG, weight: FW) -> Summary<K, C, S>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	C: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	S: Graph<C, Supernode, f64>,
+	FG: Fn (&Arc<Node<K, N, E>>) -> C,
+	FW: Fn (&Arc<Edge<K, N, E>>) -> f64,
+{
+	summary(graph, |node| Some(group(node)), weight)
+}
+
+/// Summarize the graph by the communities of a partition, such as a level
+/// of a dendrogram, like `summarize`. The supernodes are keyed by the index
+/// of their community. Nodes which aren't in any community are left out
+/// along with their edges.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::community::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 3, Empty);
+/// g.add_edge(3, 4, Empty);
+///
+/// let partition = vec![vec![1, 2], vec![3, 4]];
+/// let summary: Summary<u32, usize, Ungraph<usize, Supernode, f64>> = summarize_partition(&g, &partition, |_| 1.0);
+/// assert!(summary.graph.edge_count() == 1);
+/// assert!(summary.graph.get_node(1).unwrap().load().size == 2);
+/// ```
+pub fn summarize_partition<K, N, E, G, S, F>(graph: &G, partition: &[Vec<K>], weight: F) -> Summary<K, usize, S>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	S: Graph<usize, Supernode, f64>,
+	F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+{
+	let mut community = HashMap::new();
+	for (c, members) in partition.iter().enumerate() {
+		for k in members.iter() {
+			community.insert(k.clone(), c);
+		}
+	}
+	summary(graph, |node| community.get(node.key()).copied(), weight)
+}
+
+// Summarize the nodes the closure gives a group for.
+fn summary<K, N, E, G, C, S, FG, FW>(graph: &G, group: FG, weight: FW) -> Summary<K, C, S>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	C: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	S: Graph<C, Supernode, f64>,
+	FG: Fn (&Arc<Node<K, N, E>>) -> Option<C>,
+	FW: Fn (&Arc<Edge<K, N, E>>) -> f64,
+{
+	let nodes = graph.nodes();
+	// Groups are numbered in order of appearance.
+	let mut index: HashMap<C, usize> = HashMap::new();
+	let mut supernodes: Vec<(C, Supernode)> = Vec::new();
+	let mut members: HashMap<K, usize> = HashMap::with_capacity(nodes.len());
+	for node in nodes.iter() {
+		if let Some(c) = group(node) {
+			let i = *index.entry(c.clone()).or_insert_with(|| {
+				supernodes.push((c, Supernode::default()));
+				supernodes.len() - 1
+			});
+			supernodes[i].1.size += 1;
+			members.insert(node.key().clone(), i);
+		}
+	}
+	let mut edges: HashMap<(usize, usize), f64> = HashMap::new();
+	for node in nodes.iter() {
+		let u = match members.get(node.key()) {
+			Some(u) => { *u }
+			None => { continue }
+		};
+		for edge in node.outbound().iter() {
+			let w = match members.get(edge.target().key()) {
+				Some(w) => { *w }
+				None => { continue }
+			};
+			if u == w {
+				supernodes[u].1.internal += weight(edge);
+				continue;
+			}
+			// Edges of an undirected graph are merged in either orientation.
+			let pair = match G::directed() || u < w {
+				true => { (u, w) }
+				false => { (w, u) }
+			};
+			*edges.entry(pair).or_insert(0.0) += weight(edge);
+		}
+	}
+	let mut res = S::new();
+	for (c, data) in supernodes.iter() {
+		res.add_node(c.clone(), *data);
+	}
+	for ((u, w), total) in edges {
+		res.add_edge(supernodes[u].0.clone(), supernodes[w].0.clone(), total);
+	}
+	let groups = members.into_iter().map(|(k, i)| (k, supernodes[i].0.clone())).collect();
+	Summary { graph: res, groups }
+}
+
+//=============================================================================
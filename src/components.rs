@@ -0,0 +1,160 @@
+//=============================================================================
+// COMPONENTS
+//=============================================================================
+
+//! Connected and strongly connected components with Tarjan's algorithm. The
+//! search is iterative so that long paths don't overflow the stack.
+//!
+use crate::collections::Graph;
+use crate::core::Direction;
+use crate::indexed::Indexed;
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+//=============================================================================
+
+/// # Strongly Connected Components
+///
+/// Partition the nodes into maximal sets where every node can reach every
+/// other. Components are returned in reverse topological order, so edges
+/// between components lead from a later component to an earlier one. In an
+/// undirected graph these are the connected components.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::components::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 1, Empty);
+/// g.add_edge(2, 3, Empty);
+/// g.add_edge(3, 4, Empty);
+/// g.add_edge(4, 3, Empty);
+///
+/// let mut components = strongly_connected_components(&g);
+/// for c in components.iter_mut() {
+///     c.sort();
+/// }
+/// assert!(components == vec![vec![3, 4], vec![1, 2]]);
+/// ```
+pub fn strongly_connected_components<K, N, E, G>(graph: &G) -> Vec<Vec<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	tarjan(&indexed.adjacency)
+		.into_iter()
+		.map(|c| c.into_iter().map(|v| indexed.key(v)).collect())
+		.collect()
+}
+
+/// # Connected Components
+///
+/// Partition the nodes into maximal sets connected by edges in either
+/// direction, ie. the weakly connected components of a directed graph.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::components::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 1..6 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(3, 2, Empty);
+/// g.add_edge(5, 4, Empty);
+///
+/// let mut components = connected_components(&g);
+/// for c in components.iter_mut() {
+///     c.sort();
+/// }
+/// components.sort();
+/// assert!(components == vec![vec![1, 2, 3], vec![4, 5]]);
+/// ```
+pub fn connected_components<K, N, E, G>(graph: &G) -> Vec<Vec<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Both);
+	tarjan(&indexed.adjacency)
+		.into_iter()
+		.map(|c| c.into_iter().map(|v| indexed.key(v)).collect())
+		.collect()
+}
+
+// Strongly connected components of an adjacency list in reverse topological
+// order.
+pub(crate) fn tarjan(adjacency: &[Vec<(usize, usize)>]) -> Vec<Vec<usize>> {
+	let n = adjacency.len();
+	let mut index = vec![usize::MAX; n];
+	let mut lowlink = vec![0; n];
+	let mut on_stack = vec![false; n];
+	let mut stack = Vec::new();
+	let mut next = vec![0; n];
+	let mut res = Vec::new();
+	let mut count = 0;
+	for root in 0..n {
+		if index[root] != usize::MAX {
+			continue;
+		}
+		let mut search = vec![root];
+		index[root] = count;
+		lowlink[root] = count;
+		count += 1;
+		stack.push(root);
+		on_stack[root] = true;
+		while let Some(&v) = search.last() {
+			if next[v] < adjacency[v].len() {
+				let w = adjacency[v][next[v]].0;
+				next[v] += 1;
+				if index[w] == usize::MAX {
+					index[w] = count;
+					lowlink[w] = count;
+					count += 1;
+					stack.push(w);
+					on_stack[w] = true;
+					search.push(w);
+				} else if on_stack[w] {
+					lowlink[v] = lowlink[v].min(index[w]);
+				}
+				continue;
+			}
+			search.pop();
+			if let Some(&u) = search.last() {
+				lowlink[u] = lowlink[u].min(lowlink[v]);
+			}
+			if lowlink[v] == index[v] {
+				let mut component = Vec::new();
+				while let Some(w) = stack.pop() {
+					on_stack[w] = false;
+					component.push(w);
+					if w == v {
+						break;
+					}
+				}
+				res.push(component);
+			}
+		}
+	}
+	res
+}
+
+//=============================================================================
@@ -0,0 +1,320 @@
+//=============================================================================
+// CONTRACTION HIERARCHIES
+//=============================================================================
+
+//! Preprocessed index for answering many point-to-point shortest path queries
+//! over a static graph. Nodes are contracted one by one in order of
+//! importance, adding shortcut edges that preserve shortest path distances.
+//! A query is then a bidirectional search that only moves upwards in the
+//! hierarchy and touches a tiny fraction of the graph.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::shortest_path::MinCost;
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, HashMap},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::{Arc, Weak},
+};
+
+// Maximum amount of nodes settled by a single witness search. Stopping early
+// only results in superfluous shortcuts, never in wrong distances.
+const WITNESS_LIMIT: usize = 500;
+
+type Arcs = Vec<HashMap<usize, f64>>;
+type ArcEdges<K, N, E> = HashMap<(usize, usize), Weak<Edge<K, N, E>>>;
+
+//=============================================================================
+
+/// Contraction hierarchy built from a graph and an edge cost closure. The
+/// index refers to the edges of the graph weakly and is invalidated by
+/// changes to the graph.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::contraction::*;
+/// use fastgraph::core::Empty;
+///
+/// let mut g = Digraph::<u32, Empty, f64>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 3, 1.0);
+/// g.add_edge(3, 4, 1.0);
+/// g.add_edge(1, 4, 5.0);
+///
+/// let ch = ContractionHierarchy::new(&g, |edge| edge.load());
+/// let (cost, path) = ch.shortest_path(&1, &4).unwrap();
+///
+/// assert!(cost == 3.0);
+/// assert!(path.len() == 3);
+/// ```
+pub struct ContractionHierarchy<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	index: HashMap<K, usize>,
+	upward: Vec<Vec<(usize, f64)>>,
+	downward: Vec<Vec<(usize, f64)>>,
+	middle: HashMap<(usize, usize), usize>,
+	edges: ArcEdges<K, N, E>,
+}
+
+impl<K, N, E> ContractionHierarchy<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Build the hierarchy for a graph with edge costs given by the `cost`
+	/// closure. Costs must be non-negative.
+	pub fn new<G, F>(graph: &G, cost: F) -> Self
+	where
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let nodes = graph.nodes();
+		let mut index = HashMap::new();
+		for (i, node) in nodes.iter().enumerate() {
+			index.insert(node.key().clone(), i);
+		}
+		let n = nodes.len();
+		let mut out: Arcs = vec![HashMap::new(); n];
+		let mut inc: Arcs = vec![HashMap::new(); n];
+		let mut edges = HashMap::new();
+		for (u, node) in nodes.iter().enumerate() {
+			for edge in node.outbound().iter() {
+				let w = index[edge.target().key()];
+				let c = cost(edge);
+				let mut add = |a: usize, b: usize| {
+					if a != b && c < *out[a].get(&b).unwrap_or(&f64::INFINITY) {
+						out[a].insert(b, c);
+						inc[b].insert(a, c);
+						edges.insert((a, b), Arc::downgrade(edge));
+					}
+				};
+				add(u, w);
+				if !G::directed() {
+					add(w, u);
+				}
+			}
+		}
+		let mut ch = ContractionHierarchy {
+			index,
+			upward: vec![Vec::new(); n],
+			downward: vec![Vec::new(); n],
+			middle: HashMap::new(),
+			edges,
+		};
+		ch.contract(out, inc);
+		ch
+	}
+
+	/// Shortest path between two nodes. Returns the total cost and the edges
+	/// along the path, or None if either node is not part of the index or
+	/// the target can't be reached.
+	pub fn shortest_path(&self, source: &K, target: &K) -> Option<(f64, Path<K, N, E>)> {
+		let s = *self.index.get(source)?;
+		let t = *self.index.get(target)?;
+		let forward = upward_search(&self.upward, s);
+		let backward = upward_search(&self.downward, t);
+		let mut best = f64::INFINITY;
+		let mut meet = None;
+		for (v, (df, _)) in forward.iter() {
+			if let Some((db, _)) = backward.get(v) {
+				if df + db < best {
+					best = df + db;
+					meet = Some(*v);
+				}
+			}
+		}
+		let meet = meet?;
+		let mut chain = vec![meet];
+		while let Some((_, Some(prev))) = forward.get(chain.last().unwrap()) {
+			chain.push(*prev);
+		}
+		chain.reverse();
+		let mut current = meet;
+		while let Some((_, Some(next))) = backward.get(&current) {
+			chain.push(*next);
+			current = *next;
+		}
+		let mut path = Vec::new();
+		for pair in chain.windows(2) {
+			self.unpack(pair[0], pair[1], &mut path);
+		}
+		Some((best, path))
+	}
+
+	/// Amount of shortcut edges added during preprocessing.
+	pub fn shortcut_count(&self) -> usize {
+		self.middle.len()
+	}
+
+	// Contracts all nodes in order of importance and builds the upward and
+	// downward search graphs.
+	fn contract(&mut self, mut out: Arcs, mut inc: Arcs) {
+		let n = out.len();
+		let mut contracted = vec![false; n];
+		let mut deleted = vec![0i64; n];
+		let mut rank = vec![0; n];
+		let mut all: Vec<(usize, usize, f64)> = Vec::new();
+		for (u, arcs) in out.iter().enumerate() {
+			for (w, c) in arcs.iter() {
+				all.push((u, *w, *c));
+			}
+		}
+		let mut queue = BinaryHeap::new();
+		for v in 0..n {
+			let p = priority(v, &out, &inc, &contracted, &deleted);
+			queue.push(Reverse((p, v)));
+		}
+		let mut next_rank = 0;
+		while let Some(Reverse((_, v))) = queue.pop() {
+			if contracted[v] {
+				continue;
+			}
+			let p = priority(v, &out, &inc, &contracted, &deleted);
+			if let Some(Reverse((top, _))) = queue.peek() {
+				if p > *top {
+					queue.push(Reverse((p, v)));
+					continue;
+				}
+			}
+			for (u, w, c) in shortcuts(v, &out, &inc, &contracted) {
+				out[u].insert(w, c);
+				inc[w].insert(u, c);
+				self.middle.insert((u, w), v);
+				all.push((u, w, c));
+			}
+			contracted[v] = true;
+			rank[v] = next_rank;
+			next_rank += 1;
+			for u in inc[v].keys().chain(out[v].keys()) {
+				deleted[*u] += 1;
+			}
+		}
+		for (u, w, c) in all {
+			// Arcs replaced by a cheaper shortcut are left out.
+			if out[u].get(&w) != Some(&c) {
+				continue;
+			}
+			if rank[u] < rank[w] {
+				self.upward[u].push((w, c));
+			} else {
+				self.downward[w].push((u, c));
+			}
+		}
+	}
+
+	// Expands the arc from `u` to `w` into the original edges it represents.
+	fn unpack(&self, u: usize, w: usize, path: &mut Path<K, N, E>) {
+		match self.middle.get(&(u, w)) {
+			Some(v) => {
+				self.unpack(u, *v, path);
+				self.unpack(*v, w, path);
+			}
+			None => { path.push(self.edges[&(u, w)].clone()) }
+		}
+	}
+}
+
+//=============================================================================
+
+// Shortcuts needed to preserve distances when `v` is contracted.
+fn shortcuts(v: usize, out: &Arcs, inc: &Arcs, contracted: &[bool]) -> Vec<(usize, usize, f64)> {
+	let mut res = Vec::new();
+	for (u, c1) in inc[v].iter() {
+		if contracted[*u] {
+			continue;
+		}
+		let limit = out[v]
+			.iter()
+			.filter(|(w, _)| !contracted[**w] && *w != u)
+			.map(|(_, c2)| c1 + c2)
+			.fold(0.0, f64::max);
+		let dist = witness_search(*u, v, limit, out, contracted);
+		for (w, c2) in out[v].iter() {
+			if contracted[*w] || w == u {
+				continue;
+			}
+			let c = c1 + c2;
+			if *dist.get(w).unwrap_or(&f64::INFINITY) > c {
+				res.push((*u, *w, c));
+			}
+		}
+	}
+	res
+}
+
+// Edge difference of contracting `v` combined with the amount of already
+// contracted neighbors, which spreads contraction evenly over the graph.
+fn priority(v: usize, out: &Arcs, inc: &Arcs, contracted: &[bool], deleted: &[i64]) -> i64 {
+	let degree = inc[v].keys().chain(out[v].keys()).filter(|u| !contracted[**u]).count();
+	shortcuts(v, out, inc, contracted).len() as i64 - degree as i64 + deleted[v]
+}
+
+// Dijkstra over the uncontracted graph from `source` that avoids `skip`
+// and gives up beyond `limit`.
+fn witness_search(source: usize, skip: usize, limit: f64, out: &Arcs, contracted: &[bool]) -> HashMap<usize, f64> {
+	let mut dist = HashMap::new();
+	let mut settled = 0;
+	let mut heap = BinaryHeap::new();
+	dist.insert(source, 0.0);
+	heap.push(MinCost { cost: 0.0, item: source });
+	while let Some(MinCost { cost: d, item: u }) = heap.pop() {
+		if d > limit || settled >= WITNESS_LIMIT {
+			break;
+		}
+		if d > dist[&u] {
+			continue;
+		}
+		settled += 1;
+		for (w, c) in out[u].iter() {
+			if *w == skip || contracted[*w] {
+				continue;
+			}
+			let nd = d + c;
+			if nd < *dist.get(w).unwrap_or(&f64::INFINITY) {
+				dist.insert(*w, nd);
+				heap.push(MinCost { cost: nd, item: *w });
+			}
+		}
+	}
+	dist
+}
+
+// Dijkstra over one half of the hierarchy. Returns the distance and the
+// predecessor of every reached node.
+fn upward_search(arcs: &[Vec<(usize, f64)>], source: usize) -> HashMap<usize, (f64, Option<usize>)> {
+	let mut dist: HashMap<usize, (f64, Option<usize>)> = HashMap::new();
+	let mut heap = BinaryHeap::new();
+	dist.insert(source, (0.0, None));
+	heap.push(MinCost { cost: 0.0, item: source });
+	while let Some(MinCost { cost: d, item: u }) = heap.pop() {
+		if d > dist[&u].0 {
+			continue;
+		}
+		for (w, c) in arcs[u].iter() {
+			let nd = d + c;
+			let shorter = match dist.get(w) {
+				Some((old, _)) => nd < *old,
+				None => true,
+			};
+			if shorter {
+				dist.insert(*w, (nd, Some(u)));
+				heap.push(MinCost { cost: nd, item: *w });
+			}
+		}
+	}
+	dist
+}
+
+//=============================================================================
@@ -9,10 +9,11 @@
 //! different graphs.
 //!
 use std::{
+	collections::HashMap,
 	fmt::{Debug, Display, Formatter},
     hash::Hash,
     sync::{
-		atomic::{AtomicBool, Ordering},
+		atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc, Weak,
     },
 };
@@ -32,9 +33,34 @@ pub enum Traverse {
     Finish,
 }
 
+/// The Direction enum selects which edges of a node are followed when
+/// exploring its neighborhood. Outbound follows edges to their targets,
+/// Inbound follows edges back to their sources and Both treats the graph
+/// as undirected.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Outbound,
+    Inbound,
+    Both,
+}
+
+/// The Aggregate enum selects how values extracted from the edges of a node
+/// are combined. Count ignores the values.
+///
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+    Mean,
+    Count,
+}
+
 /// Represents an empty parameter for either a node or an edge.
 ///
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Empty;
 
 impl std::fmt::Display for Empty {
@@ -44,6 +70,10 @@ impl std::fmt::Display for Empty {
 }
 
 pub type Frontier<K, N, E> = Vec<Weak<Edge<K, N, E>>>;
+pub type Path<K, N, E> = Vec<Weak<Edge<K, N, E>>>;
+pub type Incident<K, N, E> = Vec<(Arc<Edge<K, N, E>>, Arc<Node<K, N, E>>)>;
+pub type Endpoints<K, N, E> = (Arc<Node<K, N, E>>, Arc<Node<K, N, E>>);
+pub type Aggregates<K, N, E> = Vec<(Arc<Node<K, N, E>>, Option<f64>)>;
 
 pub trait Explorer<K, N, E>
 where
@@ -116,6 +146,12 @@ where
         self.target.upgrade().unwrap()
     }
 
+    /// Edge's source and target nodes if both still exist.
+    #[inline(always)]
+    pub fn endpoints(&self) -> Option<Endpoints<K, N, E>> {
+        Some((self.source.upgrade()?, self.target.upgrade()?))
+    }
+
     /// Load data from the edge.
     #[inline(always)]
     pub fn load(&self) -> E {
@@ -129,6 +165,24 @@ where
         *x = data;
     }
 
+    /// Call a closure with the data of the edge while it's locked and
+    /// return its result, which reads a part of the data without cloning
+    /// all of it. The closure must not access the data of this edge itself.
+    #[inline(always)]
+    pub fn with<T, F: FnOnce(&E) -> T>(&self, f: F) -> T {
+        f(&self.data.lock())
+    }
+
+    /// Reverse this edge between its endpoints, see `reverse`. The reversed
+    /// edge replaces this one in the adjacency lists of the nodes. Returns
+    /// false if this edge was already removed or reversed.
+    pub fn reverse(&self) -> bool {
+        match self.endpoints() {
+            Some((source, target)) => { reverse_if(&source, &target, |edge| std::ptr::eq(Arc::as_ptr(edge), self)) }
+            None => { false }
+        }
+    }
+
 	#[inline(always)]
     fn try_lock(&self) -> bool {
         self.lock.load(Ordering::Relaxed)
@@ -193,17 +247,17 @@ where
 /// Used for example to find the shortest path from the results of a breadth
 /// first straversal.
 ///
-pub fn backtrack_edges<K, N, E>(edges: &Vec<Weak<Edge<K, N, E>>>) -> Vec<Weak<Edge<K, N, E>>>
+pub fn backtrack_edges<K, N, E>(edges: &[Weak<Edge<K, N, E>>]) -> Vec<Weak<Edge<K, N, E>>>
 where
     K: Hash + Eq + Clone + Debug + Display + Sync + Send,
     N: Clone + Debug + Display + Sync + Send,
     E: Clone + Debug + Display + Sync + Send,
 {
     let mut res = Vec::new();
-    if edges.len() == 0 {
-        return res;
-    }
-    let w = edges.get(edges.len() - 1).unwrap();
+    let w = match edges.last() {
+        Some(w) => w,
+        None => return res,
+    };
     res.push(w.clone());
     let mut i = 0;
     for edge in edges.iter().rev() {
@@ -218,13 +272,13 @@ where
 }
 
 // Opens all locks in
-fn open_locks<K, N, E>(edges: &Vec<Weak<Edge<K, N, E>>>)
+fn open_locks<K, N, E>(edges: &[Weak<Edge<K, N, E>>])
 where
     K: Hash + Eq + Clone + Debug + Display + Sync + Send,
     N: Clone + Debug + Display + Sync + Send,
     E: Clone + Debug + Display + Sync + Send,
 {
-	if edges.len() < 1 {
+	if edges.is_empty() {
 		return ;
 	}
 	edges[0].upgrade().unwrap().source().open();
@@ -242,8 +296,177 @@ where
 //=============================================================================
 // TYPES
 
-type Outbound<K, N, E> = RwLock<Vec<Arc<Edge<K, N, E>>>>;
-type Inbound<K, N, E> = RwLock<Vec<Weak<Edge<K, N, E>>>>;
+/// List of the edges adjacent to a node. It dereferences to a slice of the
+/// edges and has the same type with or without the `smallvec` feature. With
+/// the feature the first four edges are stored inline in the node and the
+/// list moves to the heap only when it grows beyond that, which saves an
+/// allocation and a pointer chase per node in graphs where most nodes have a
+/// low degree.
+#[derive(Clone, Debug)]
+pub struct Adjacency<T> {
+    list: List<T>,
+}
+
+#[cfg(not(feature = "smallvec"))]
+type List<T> = Vec<T>;
+
+#[cfg(feature = "smallvec")]
+type List<T> = smallvec::SmallVec<[T; 4]>;
+
+impl<T> Adjacency<T> {
+    /// Create an empty list.
+    pub fn new() -> Self {
+        Self { list: List::new() }
+    }
+
+    /// Append an edge to the end of the list.
+    pub fn push(&mut self, value: T) {
+        self.list.push(value);
+    }
+
+    /// Insert an edge at the index, shifting the edges after it.
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.list.insert(index, value);
+    }
+
+    /// Remove the edge at the index, shifting the edges after it.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.list.remove(index)
+    }
+
+    /// Keep only the edges for which the predicate returns true, in order.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.list.retain(|value| predicate(value));
+    }
+
+    /// Remove every edge from the list.
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    /// Reserve capacity for at least `additional` more edges.
+    pub fn reserve(&mut self, additional: usize) {
+        self.list.reserve(additional);
+    }
+
+    /// Amount of edges the list can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.list.capacity()
+    }
+
+    /// Free the capacity that isn't used by the edges.
+    pub fn shrink_to_fit(&mut self) {
+        self.list.shrink_to_fit();
+    }
+
+    /// Whether the edges are stored on the heap instead of inline in the
+    /// node. Without the `smallvec` feature this is true once the list has
+    /// allocated.
+    pub fn spilled(&self) -> bool {
+        #[cfg(feature = "smallvec")]
+        let res = self.list.spilled();
+        #[cfg(not(feature = "smallvec"))]
+        let res = self.list.capacity() > 0;
+        res
+    }
+
+    // Capacity of the list allocated on the heap.
+    fn heap_capacity(&self) -> usize {
+        if self.spilled() { self.list.capacity() } else { 0 }
+    }
+}
+
+impl<T> Default for Adjacency<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> std::ops::Deref for Adjacency<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.list
+    }
+}
+
+impl<T> std::ops::DerefMut for Adjacency<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.list
+    }
+}
+
+impl<T> Extend<T> for Adjacency<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.list.extend(iter);
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Adjacency<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter()
+    }
+}
+
+type Outbound<K, N, E> = RwLock<Adjacency<Arc<Edge<K, N, E>>>>;
+type Inbound<K, N, E> = RwLock<Adjacency<Weak<Edge<K, N, E>>>>;
+
+/// Degree from which a node looks up its edges through a hash index from the
+/// key of the node on the other end to the edge instead of scanning the
+/// adjacency list. An index is built on the first lookup into a list of at
+/// least this length and kept up to date by `connect`, `disconnect` and
+/// `isolate`. Taking write access to a list with `outbound_mut` or
+/// `inbound_mut` drops its index, which is rebuilt on the next lookup.
+pub const INDEX_DEGREE: usize = 32;
+
+// Hash index of an adjacency list, None until the first lookup into a long
+// list.
+type EdgeIndex<K, N, E> = Mutex<Option<Box<HashMap<K, Weak<Edge<K, N, E>>>>>>;
+
+// Find the outbound edge to the node with the given key, through the index if
+// the list is long. The caller holds a lock on the list.
+fn find_outbound_in<K, N, E>(list: &Adjacency<Arc<Edge<K, N, E>>>, index: &EdgeIndex<K, N, E>, key: &K) -> Option<Arc<Edge<K, N, E>>>
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+{
+    if list.len() < INDEX_DEGREE {
+        return list.iter().find(|edge| edge.target().key() == key).cloned();
+    }
+    let mut index = index.lock();
+    let map = index.get_or_insert_with(|| {
+        Box::new(list.iter().map(|edge| (edge.target().key().clone(), Arc::downgrade(edge))).collect())
+    });
+    map.get(key).and_then(|edge| edge.upgrade())
+}
+
+// Find the inbound edge from the node with the given key. Edges that no
+// longer exist are skipped.
+fn find_inbound_in<K, N, E>(list: &Adjacency<Weak<Edge<K, N, E>>>, index: &EdgeIndex<K, N, E>, key: &K) -> Option<Weak<Edge<K, N, E>>>
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+{
+    if list.len() < INDEX_DEGREE {
+        return list
+            .iter()
+            .find(|edge| edge.upgrade().is_some_and(|edge| edge.source().key() == key))
+            .cloned();
+    }
+    let mut index = index.lock();
+    let map = index.get_or_insert_with(|| {
+        Box::new(list.iter().filter_map(|edge| edge.upgrade().map(|e| (e.source().key().clone(), edge.clone()))).collect())
+    });
+    map.get(key).filter(|edge| edge.strong_count() > 0).cloned()
+}
 
 //=============================================================================
 // STRUCT
@@ -262,6 +485,8 @@ where
     data: Mutex<N>,
     outbound: Outbound<K, N, E>,
     inbound: Inbound<K, N, E>,
+    outbound_index: EdgeIndex<K, N, E>,
+    inbound_index: EdgeIndex<K, N, E>,
     lock: AtomicBool,
 }
 
@@ -284,8 +509,10 @@ where
         Self {
             key,
             data: Mutex::new(data),
-            outbound: Outbound::new(Vec::new()),
-            inbound: Inbound::new(Vec::new()),
+            outbound: Outbound::new(Adjacency::new()),
+            inbound: Inbound::new(Adjacency::new()),
+            outbound_index: Mutex::new(None),
+            inbound_index: Mutex::new(None),
             lock: AtomicBool::new(OPEN),
         }
     }
@@ -304,6 +531,14 @@ where
         *self.data.lock() = data;
     }
 
+    /// Call a closure with the data of the node while it's locked and
+    /// return its result. See `Edge::with`.
+    ///
+    #[inline(always)]
+    pub fn with<T, F: FnOnce(&N) -> T>(&self, f: F) -> T {
+        f(&self.data.lock())
+    }
+
     /// Get node key.
     ///
     #[inline(always)]
@@ -322,59 +557,188 @@ where
     ///
     #[inline(always)]
     pub fn is_leaf(&self) -> bool {
-        self.outbound().len() == 0
+        self.outbound().is_empty()
     }
 
     /// Find an outbound node and return the corresponding edge if found.
+    /// Nodes with a degree of at least `INDEX_DEGREE` find the edge through
+    /// a hash index.
     ///
     #[inline(always)]
     pub fn find_outbound(&self, target: &Arc<Node<K, N, E>>) -> Option<Arc<Edge<K, N, E>>> {
-        for edge in self.outbound().iter() {
-            if edge.target() == *target {
-                return Some(edge.clone());
-            }
-        }
-        None
+        find_outbound_in(&self.outbound.read(), &self.outbound_index, target.key())
     }
 
-    /// Find an inbound node and return the corresponding edge if found.
+    /// Find an inbound node and return the corresponding edge if found. See
+    /// `find_outbound`.
     ///
     #[inline(always)]
     pub fn find_inbound(&self, source: &Arc<Node<K, N, E>>) -> Option<Weak<Edge<K, N, E>>> {
-        for edge in self.inbound().iter() {
-            if edge.upgrade().unwrap().source() == *source {
-                return Some(edge.clone());
+        find_inbound_in(&self.inbound.read(), &self.inbound_index, source.key())
+    }
+
+    /// Collect the edges incident to this node in the given direction, each
+    /// paired with the node on the other end of the edge.
+    ///
+    pub fn incident(&self, direction: Direction) -> Incident<K, N, E> {
+        let mut res = Vec::new();
+        if direction != Direction::Inbound {
+            for edge in self.outbound().iter() {
+                res.push((edge.clone(), edge.target()));
             }
         }
-        None
+        if direction != Direction::Outbound {
+            for edge in self.inbound().iter() {
+                if let Some(edge) = edge.upgrade() {
+                    let source = edge.source();
+                    res.push((edge, source));
+                }
+            }
+        }
+        res
+    }
+
+    /// Collect the nodes adjacent to this node in the given direction.
+    ///
+    pub fn neighbors(&self, direction: Direction) -> Vec<Arc<Node<K, N, E>>> {
+        self.incident(direction)
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect()
+    }
+
+    /// Combine values extracted from the edges of this node in the given
+    /// direction with the `value` closure, for example the weighted degree
+    /// with `Aggregate::Sum`. Sum and Count of a node without edges are zero
+    /// while Min, Max and Mean are None.
+    ///
+    pub fn aggregate<F>(&self, direction: Direction, aggregate: Aggregate, value: F) -> Option<f64>
+    where
+        F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+    {
+        let incident = self.incident(direction);
+        let values = incident.iter().map(|(edge, _)| value(edge));
+        match aggregate {
+            Aggregate::Sum => { Some(values.sum()) }
+            Aggregate::Min => { values.reduce(f64::min) }
+            Aggregate::Max => { values.reduce(f64::max) }
+            Aggregate::Mean => {
+                match incident.len() {
+                    0 => { None }
+                    n => { Some(values.sum::<f64>() / n as f64) }
+                }
+            }
+            Aggregate::Count => { Some(incident.len() as f64) }
+        }
+    }
+
+    /// Combine values extracted from the outbound edges of this node. See
+    /// `aggregate`.
+    ///
+    pub fn aggregate_out<F>(&self, aggregate: Aggregate, value: F) -> Option<f64>
+    where
+        F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+    {
+        self.aggregate(Direction::Outbound, aggregate, value)
+    }
+
+    /// Combine values extracted from the inbound edges of this node. See
+    /// `aggregate`.
+    ///
+    pub fn aggregate_in<F>(&self, aggregate: Aggregate, value: F) -> Option<f64>
+    where
+        F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+    {
+        self.aggregate(Direction::Inbound, aggregate, value)
     }
 
     /// Get read access to outbound edges of the node.
     ///
     #[inline(always)]
-    pub fn outbound(&self) -> RwLockReadGuard<Vec<Arc<Edge<K, N, E>>>> {
+    pub fn outbound(&self) -> RwLockReadGuard<'_, Adjacency<Arc<Edge<K, N, E>>>> {
         self.outbound.read()
     }
 
     /// Get read and write access to the outbound edges of the node. Will block other threads.
+    /// Drops the hash index of the edges, see `INDEX_DEGREE`.
     ///
     #[inline(always)]
-    pub fn outbound_mut(&self) -> RwLockWriteGuard<Vec<Arc<Edge<K, N, E>>>> {
-        self.outbound.write()
+    pub fn outbound_mut(&self) -> RwLockWriteGuard<'_, Adjacency<Arc<Edge<K, N, E>>>> {
+        let outbound = self.outbound.write();
+        *self.outbound_index.lock() = None;
+        outbound
     }
 
     /// Get read access to inbound edges of the node.
     ///
     #[inline(always)]
-    pub fn inbound(&self) -> RwLockReadGuard<Vec<Weak<Edge<K, N, E>>>> {
+    pub fn inbound(&self) -> RwLockReadGuard<'_, Adjacency<Weak<Edge<K, N, E>>>> {
         self.inbound.read()
     }
 
-    /// Get read and write access to the outbound edges of the node. Will block other threads.
+    /// Get read and write access to the inbound edges of the node. Will block other threads.
+    /// Drops the hash index of the edges, see `INDEX_DEGREE`.
     ///
     #[inline(always)]
-    pub fn inbound_mut(&self) -> RwLockWriteGuard<Vec<Weak<Edge<K, N, E>>>> {
-        self.inbound.write()
+    pub fn inbound_mut(&self) -> RwLockWriteGuard<'_, Adjacency<Weak<Edge<K, N, E>>>> {
+        let inbound = self.inbound.write();
+        *self.inbound_index.lock() = None;
+        inbound
+    }
+
+    /// Sort the outbound edges of the node with a comparator. Edges otherwise
+    /// stay in the order they were connected in, and traversals visit them in
+    /// this order. The sort is stable and keeps the hash index of the edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use fastgraph::core::*;
+    /// use std::sync::Arc;
+    ///
+    /// let hub = Arc::new(Node::<u32, Empty, f64>::new(0, Empty));
+    /// let leaves: Vec<_> = (1..4).map(|i| Arc::new(Node::new(i, Empty))).collect();
+    /// for (leaf, w) in leaves.iter().zip([3.0, 1.0, 2.0]) {
+    ///     connect(&hub, leaf, w);
+    /// }
+    /// hub.sort_outbound_by(|a, b| a.load().total_cmp(&b.load()));
+    ///
+    /// let order: Vec<f64> = hub.outbound().iter().map(|e| e.load()).collect();
+    /// assert!(order == vec![1.0, 2.0, 3.0]);
+    /// ```
+    pub fn sort_outbound_by<F>(&self, mut cmp: F)
+    where
+        F: FnMut(&Arc<Edge<K, N, E>>, &Arc<Edge<K, N, E>>) -> std::cmp::Ordering,
+    {
+        self.outbound.write().sort_by(|a, b| cmp(a, b));
+    }
+
+    /// Sort the inbound edges of the node with a comparator. Edges that no
+    /// longer exist are moved to the end. See `sort_outbound_by`.
+    ///
+    pub fn sort_inbound_by<F>(&self, mut cmp: F)
+    where
+        F: FnMut(&Arc<Edge<K, N, E>>, &Arc<Edge<K, N, E>>) -> std::cmp::Ordering,
+    {
+        self.inbound.write().sort_by(|a, b| match (a.upgrade(), b.upgrade()) {
+            (Some(a), Some(b)) => { cmp(&a, &b) }
+            (a, b) => { b.is_some().cmp(&a.is_some()) }
+        });
+    }
+
+    /// Release excess capacity held by the adjacency lists of the node and
+    /// drop inbound references to edges that no longer exist. Returns the
+    /// amount of bytes reclaimed.
+    ///
+    pub fn shrink_to_fit(&self) -> usize {
+        let mut outbound = self.outbound_mut();
+        let mut inbound = self.inbound_mut();
+        let before = outbound.heap_capacity() + inbound.heap_capacity();
+        inbound.retain(|edge| edge.upgrade().is_some());
+        outbound.shrink_to_fit();
+        inbound.shrink_to_fit();
+        let after = outbound.heap_capacity() + inbound.heap_capacity();
+        (before - after) * std::mem::size_of::<Weak<Edge<K, N, E>>>()
     }
 
 	//=============================================================================
@@ -506,8 +870,10 @@ where
         Node {
             key: self.key.clone(),
             data: Mutex::new(self.data.lock().clone()),
-            outbound: Outbound::new(Vec::new()),
-            inbound: Inbound::new(Vec::new()),
+            outbound: Outbound::new(Adjacency::new()),
+            inbound: Inbound::new(Adjacency::new()),
+            outbound_index: Mutex::new(None),
+            inbound_index: Mutex::new(None),
             lock: AtomicBool::new(OPEN),
         }
     }
@@ -547,62 +913,218 @@ where
 //=============================================================================
 // FUNCTION IMPLEMENTATIONS
 
-#[inline]
-fn overlaps<K, N, E>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>) -> bool
+/// Connect two nodes if no previous connection exists.
+///
+/// The outbound lock of the source and the inbound lock of the target are
+/// held together, so concurrent connects can't both add the same edge. Every
+/// function that holds two adjacency locks takes the outbound lock first and
+/// never holds more than one lock of each kind, so concurrent connects and
+/// disconnects between overlapping pairs of nodes can't deadlock. The only
+/// exception is `reverse`, which takes two outbound locks in the order of
+/// the addresses of the nodes before any inbound lock.
+pub fn connect<K, N, E>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>, data: E) -> bool
 where
     K: Hash + Eq + Clone + Debug + Display + Sync + Send,
     N: Clone + Debug + Display + Sync + Send,
     E: Clone + Debug + Display + Sync + Send,
 {
-    for edge in source.outbound().iter() {
-        if edge.target() == *target {
-            return true;
-        }
+    connect_or_merge(source, target, data, |_, _| {})
+}
+
+/// Connect two nodes, or if they are already connected, merge the data into
+/// the data of the existing edge with the `merge` closure, for example to sum
+/// the weights of repeated edges. Returns true if a new edge was added. The
+/// merge happens while the outbound lock of the source is held, so
+/// concurrent merges into the same edge are applied one at a time.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use std::sync::Arc;
+///
+/// let a = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let b = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+///
+/// assert!(connect_or_merge(&a, &b, 1.0, |w, x| *w += x));
+/// assert!(!connect_or_merge(&a, &b, 2.5, |w, x| *w += x));
+/// assert!(a.find_outbound(&b).unwrap().load() == 3.5);
+/// ```
+pub fn connect_or_merge<K, N, E, F>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>, data: E, merge: F) -> bool
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+    F: FnOnce (&mut E, E),
+{
+    let mut outbound = source.outbound.write();
+    if let Some(edge) = find_outbound_in(&outbound, &source.outbound_index, target.key()) {
+        merge(&mut edge.data.lock(), data);
+        return false;
     }
-    false
+    let new_edge = Arc::new(Edge::new(source, target, data));
+    let weak = Arc::downgrade(&new_edge);
+    target.inbound.write().push(weak.clone());
+    if let Some(index) = target.inbound_index.lock().as_mut() {
+        index.insert(source.key().clone(), weak.clone());
+    }
+    outbound.push(new_edge);
+    if let Some(index) = source.outbound_index.lock().as_mut() {
+        index.insert(target.key().clone(), weak);
+    }
+    true
 }
 
-/// Connect two nodes if no previous connection exists.
-pub fn connect<K, N, E>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>, data: E) -> bool
+/// Remove every edge incident to a node. Returns the number of edges
+/// removed. Locks are taken one at a time.
+pub fn isolate<K, N, E>(node: &Arc<Node<K, N, E>>) -> usize
 where
     K: Hash + Eq + Clone + Debug + Display + Sync + Send,
     N: Clone + Debug + Display + Sync + Send,
     E: Clone + Debug + Display + Sync + Send,
 {
-    if !overlaps(source, target) {
-        let new_edge = Arc::new(Edge::new(source, target, data));
-        source.outbound_mut().push(new_edge.clone());
-        target.inbound_mut().push(Arc::downgrade(&new_edge));
-        return true;
+    let outbound = std::mem::take(&mut *node.outbound_mut());
+    let mut removed = outbound.len();
+    for edge in outbound.iter() {
+        let target = edge.target();
+        let mut inbound = target.inbound.write();
+        if let Some(i) = inbound.iter().position(|e| e.as_ptr() == Arc::as_ptr(edge)) {
+            inbound.remove(i);
+            if let Some(index) = target.inbound_index.lock().as_mut() {
+                index.remove(node.key());
+            }
+        }
     }
-    false
+    let inbound = std::mem::take(&mut *node.inbound_mut());
+    for edge in inbound.iter().filter_map(|e| e.upgrade()) {
+        let source = edge.source();
+        let mut outbound = source.outbound.write();
+        if let Some(i) = outbound.iter().position(|e| Arc::ptr_eq(e, &edge)) {
+            outbound.remove(i);
+            if let Some(index) = source.outbound_index.lock().as_mut() {
+                index.remove(node.key());
+            }
+            removed += 1;
+        }
+    }
+    removed
 }
 
-/// Disconnect two nodes from each other if they share an edge.
+/// Disconnect two nodes from each other if they share an edge. Locks are
+/// taken as in `connect`.
 pub fn disconnect<K, N, E>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>) -> bool
 where
     K: Hash + Eq + Clone + Debug + Display + Sync + Send,
     N: Clone + Debug + Display + Sync + Send,
     E: Clone + Debug + Display + Sync + Send,
 {
-    let mut idx: (usize, usize) = (0, 0);
-    let mut flag = false;
-    for (i, edge) in source.outbound().iter().enumerate() {
-        if edge.target() == *target {
-            idx.0 = i;
-            flag = true;
+    let mut outbound = source.outbound.write();
+    let edge = match find_outbound_in(&outbound, &source.outbound_index, target.key()) {
+        Some(edge) => edge,
+        None => return false,
+    };
+    if let Some(i) = outbound.iter().position(|e| Arc::ptr_eq(e, &edge)) {
+        outbound.remove(i);
+    }
+    if let Some(index) = source.outbound_index.lock().as_mut() {
+        index.remove(target.key());
+    }
+    let mut inbound = target.inbound.write();
+    if let Some(i) = inbound.iter().position(|e| e.as_ptr() == Arc::as_ptr(&edge)) {
+        inbound.remove(i);
+    }
+    if let Some(index) = target.inbound_index.lock().as_mut() {
+        index.remove(source.key());
+    }
+    true
+}
+
+/// Reverse the edge from the source to the target so that it goes from the
+/// target to the source with the same data. Returns false if there is no
+/// such edge or if the target already has an edge to the source. A loop is
+/// its own reversal. The outbound locks of both nodes are held for the whole
+/// reversal, so a concurrent connect can't add the reversed edge halfway,
+/// and the inbound locks are taken one at a time after them.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use std::sync::Arc;
+///
+/// let a = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let b = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+/// connect(&a, &b, 0.5);
+///
+/// assert!(reverse(&a, &b));
+/// assert!(a.find_outbound(&b).is_none());
+/// assert!(b.find_outbound(&a).unwrap().load() == 0.5);
+/// assert!(a.inbound().len() == 1 && b.inbound().is_empty());
+/// ```
+pub fn reverse<K, N, E>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>) -> bool
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+{
+    reverse_if(source, target, |_| true)
+}
+
+// Reverse the edge from the source to the target if the predicate accepts
+// it.
+fn reverse_if<K, N, E, F>(source: &Arc<Node<K, N, E>>, target: &Arc<Node<K, N, E>>, accept: F) -> bool
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+    F: FnOnce (&Arc<Edge<K, N, E>>) -> bool,
+{
+    if Arc::ptr_eq(source, target) {
+        return source.find_outbound(target).is_some_and(|edge| accept(&edge));
+    }
+    let (mut outbound, mut reversed) = match Arc::as_ptr(source) < Arc::as_ptr(target) {
+        true => {
+            let outbound = source.outbound.write();
+            (outbound, target.outbound.write())
         }
+        false => {
+            let reversed = target.outbound.write();
+            (source.outbound.write(), reversed)
+        }
+    };
+    let edge = match find_outbound_in(&outbound, &source.outbound_index, target.key()) {
+        Some(edge) if accept(&edge) => edge,
+        _ => return false,
+    };
+    if find_outbound_in(&reversed, &target.outbound_index, source.key()).is_some() {
+        return false;
+    }
+    if let Some(i) = outbound.iter().position(|e| Arc::ptr_eq(e, &edge)) {
+        outbound.remove(i);
     }
-    for (i, edge) in target.inbound().iter().enumerate() {
-        if edge.upgrade().unwrap().source() == *source {
-            idx.1 = i;
+    if let Some(index) = source.outbound_index.lock().as_mut() {
+        index.remove(target.key());
+    }
+    {
+        let mut inbound = target.inbound.write();
+        if let Some(i) = inbound.iter().position(|e| e.as_ptr() == Arc::as_ptr(&edge)) {
+            inbound.remove(i);
+        }
+        if let Some(index) = target.inbound_index.lock().as_mut() {
+            index.remove(source.key());
         }
     }
-    if flag == true {
-        source.outbound_mut().remove(idx.0);
-        source.inbound_mut().remove(idx.1);
+    let new_edge = Arc::new(Edge::new(target, source, edge.load()));
+    let weak = Arc::downgrade(&new_edge);
+    source.inbound.write().push(weak.clone());
+    if let Some(index) = source.inbound_index.lock().as_mut() {
+        index.insert(target.key().clone(), weak.clone());
     }
-    flag
+    reversed.push(new_edge);
+    if let Some(index) = target.outbound_index.lock().as_mut() {
+        index.insert(source.key().clone(), weak);
+    }
+    true
 }
 
 //=============================================================================
@@ -642,21 +1164,19 @@ where
 /// connect(&n1, &n3, Empty);
 ///
 /// let edges = directed_breadth_traversal(&n1,
-/// 	| edge | {
-/// 		if n3 == edge.target() {
-///				Traverse::Finish
-///			} else {
-///				Traverse::Include
-///			}
-/// 	})
-/// 	.unwrap();
+///     | edge | {
+///         if n3 == edge.target() {
+///                Traverse::Finish
+///            } else {
+///                Traverse::Include
+///            }
+///     })
+///     .unwrap();
 ///
 /// let shortest_path = backtrack_edges(&edges);
 ///
 /// assert!(shortest_path.len() == 1);
 /// ```
-///
-
 pub fn directed_breadth_traversal<K, N, E, F>(
     source: &Arc<Node<K, N, E>>,
     explorer: F,
@@ -669,6 +1189,7 @@ where
 {
     let mut frontiers: Vec<Weak<Edge<K, N, E>>>;
     let mut bounds: (usize, usize) = (0, 0);
+    let _span = trace_span!("breadth_first");
     source.close();
     let initial = source.map_adjacent_dir(&explorer);
     match initial {
@@ -686,9 +1207,10 @@ where
             break;
         }
         let current_frontier = &frontiers[bounds.0..bounds.1];
+        trace_event!("frontier", discovered = bounds.0, frontier = current_frontier.len());
         bounds.0 = bounds.1;
         let mut new_segments = Vec::new();
-        for edge in current_frontier.into_iter() {
+        for edge in current_frontier.iter() {
             let node = edge.upgrade().unwrap().target();
             let haystack = node.map_adjacent_dir(&explorer);
             match haystack {
@@ -721,6 +1243,7 @@ where
 {
     let mut frontiers: Vec<Weak<Edge<K, N, E>>>;
     let mut bounds: (usize, usize) = (0, 0);
+    let _span = trace_span!("breadth_first");
     source.close();
     let initial = source.map_adjacent_undir(&explorer);
     match initial {
@@ -738,9 +1261,10 @@ where
             break;
         }
         let current_frontier = &frontiers[bounds.0..bounds.1];
+        trace_event!("frontier", discovered = bounds.0, frontier = current_frontier.len());
         bounds.0 = bounds.1;
         let mut new_segments = Vec::new();
-        for edge in current_frontier.into_iter() {
+        for edge in current_frontier.iter() {
             let node = edge.upgrade().unwrap().target();
             let haystack = node.map_adjacent_undir(&explorer);
             match haystack {
@@ -796,21 +1320,19 @@ where
 /// connect(&n1, &n3, Empty);
 ///
 /// let edges = parallel_directed_breadth_traversal(&n1,
-/// 	| edge | {
-/// 		if n3 == edge.target() {
-///				Traverse::Finish
-///			} else {
-///				Traverse::Include
-///			}
-/// 	})
-/// 	.unwrap();
+///     | edge | {
+///         if n3 == edge.target() {
+///                Traverse::Finish
+///            } else {
+///                Traverse::Include
+///            }
+///     })
+///     .unwrap();
 ///
 /// let shortest_path = backtrack_edges(&edges);
 ///
 /// assert!(shortest_path.len() == 1);
 /// ```
-///
-
 pub fn parallel_directed_breadth_traversal<K, N, E, F>(
     source: &Arc<Node<K, N, E>>,
     explorer: F,
@@ -824,6 +1346,7 @@ where
     let mut frontiers: Vec<Weak<Edge<K, N, E>>>;
     let mut bounds: (usize, usize) = (0, 0);
     let terminate: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    let _span = trace_span!("parallel_breadth_first");
     source.close();
     match source.map_adjacent_dir(&explorer) {
         Continue::No(segment) => {
@@ -840,6 +1363,7 @@ where
             break;
         }
         let current_frontier = &frontiers[bounds.0..bounds.1];
+        trace_event!("frontier", discovered = bounds.0, frontier = current_frontier.len());
         bounds.0 = bounds.1;
         let frontier_segments: Vec<_> = current_frontier
             .into_par_iter()
@@ -863,12 +1387,12 @@ where
         for mut segment in frontier_segments {
             frontiers.append(&mut segment);
         }
-        if terminate.load(Ordering::Relaxed) == true {
+        if terminate.load(Ordering::Relaxed) {
             break;
         }
     }
     open_locks(&frontiers);
-    if terminate.load(Ordering::Relaxed) == true {
+    if terminate.load(Ordering::Relaxed) {
         Some(frontiers)
     } else {
         None
@@ -888,6 +1412,7 @@ where
     let mut frontiers: Vec<Weak<Edge<K, N, E>>>;
     let mut bounds: (usize, usize) = (0, 0);
     let terminate: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    let _span = trace_span!("parallel_breadth_first");
     source.close();
     match source.map_adjacent_undir(&explorer) {
         Continue::No(segment) => {
@@ -904,6 +1429,7 @@ where
             break;
         }
         let current_frontier = &frontiers[bounds.0..bounds.1];
+        trace_event!("frontier", discovered = bounds.0, frontier = current_frontier.len());
         bounds.0 = bounds.1;
         let frontier_segments: Vec<_> = current_frontier
             .into_par_iter()
@@ -927,12 +1453,12 @@ where
         for mut segment in frontier_segments {
             frontiers.append(&mut segment);
         }
-        if terminate.load(Ordering::Relaxed) == true {
+        if terminate.load(Ordering::Relaxed) {
             break;
         }
     }
     open_locks(&frontiers);
-    if terminate.load(Ordering::Relaxed) == true {
+    if terminate.load(Ordering::Relaxed) {
         Some(frontiers)
     } else {
         None
@@ -974,21 +1500,19 @@ where
 /// connect(&n1, &n3, Empty);
 ///
 /// let edges = directed_depth_traversal(&n1,
-/// 	| edge | {
-/// 		if n3 == edge.target() {
-///				Traverse::Finish
-///			} else {
-///				Traverse::Include
-///			}
-/// 	})
-/// 	.unwrap();
+///     | edge | {
+///         if n3 == edge.target() {
+///                Traverse::Finish
+///            } else {
+///                Traverse::Include
+///            }
+///     })
+///     .unwrap();
 ///
 /// let shortest_path = backtrack_edges(&edges);
 ///
 /// assert!(shortest_path.len() == 2);
 /// ```
-///
-
 fn directed_depth_traversal_recursion<K, N, E, F>(
     source: &Arc<Node<K, N, E>>,
     results: &mut Vec<Weak<Edge<K, N, E>>>,
@@ -1115,3 +1639,181 @@ where
 }
 
 //=============================================================================
+
+// Shared state of a parallel post-order fold. Nodes are numbered in the
+// order they were discovered, the root being zero.
+struct PostOrder<K, N, E, T, F>
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+{
+    nodes: Vec<Arc<Node<K, N, E>>>,
+    children: Vec<Vec<usize>>,
+    parents: Vec<Vec<usize>>,
+    // Children of each node whose value hasn't been computed yet.
+    pending: Vec<AtomicUsize>,
+    // Parents of each node that haven't consumed its value yet.
+    consumers: Vec<AtomicUsize>,
+    values: Vec<Mutex<Option<T>>>,
+    f: F,
+}
+
+impl<K, N, E, T, F> PostOrder<K, N, E, T, F>
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+    T: Clone + Send,
+    F: Fn(&Arc<Node<K, N, E>>, Vec<T>) -> T + Sync + Send,
+{
+    // Number the nodes reachable from the source with an iterative depth
+    // first search. Edges to nodes that are still on the stack close a cycle
+    // and are left out. Edges to finished nodes share the node between
+    // parents, except when following both directions where they are the
+    // other side of an edge that was left out.
+    fn new(source: &Arc<Node<K, N, E>>, direction: Direction, f: F) -> Self {
+        let mut index = HashMap::new();
+        let mut nodes = vec![source.clone()];
+        let mut children = vec![Vec::new()];
+        let mut finished = vec![false];
+        index.insert(source.key().clone(), 0);
+        let mut stack = vec![(0, source.incident(direction).into_iter())];
+        while let Some((v, neighbors)) = stack.last_mut() {
+            let v = *v;
+            match neighbors.next() {
+                Some((_, next)) => {
+                    match index.get(next.key()) {
+                        Some(&w) => {
+                            if finished[w] && direction != Direction::Both {
+                                children[v].push(w);
+                            }
+                        }
+                        None => {
+                            let w = nodes.len();
+                            index.insert(next.key().clone(), w);
+                            children.push(Vec::new());
+                            finished.push(false);
+                            children[v].push(w);
+                            stack.push((w, next.incident(direction).into_iter()));
+                            nodes.push(next);
+                        }
+                    }
+                }
+                None => {
+                    finished[v] = true;
+                    stack.pop();
+                }
+            }
+        }
+        let mut parents = vec![Vec::new(); nodes.len()];
+        for (v, c) in children.iter().enumerate() {
+            for &w in c.iter() {
+                parents[w].push(v);
+            }
+        }
+        PostOrder {
+            pending: children.iter().map(|c| AtomicUsize::new(c.len())).collect(),
+            consumers: parents.iter().map(|p| AtomicUsize::new(p.len())).collect(),
+            values: nodes.iter().map(|_| Mutex::new(None)).collect(),
+            nodes,
+            children,
+            parents,
+            f,
+        }
+    }
+
+    // The last parent to consume a value takes it, the others clone it.
+    fn consume(&self, v: usize) -> T {
+        let mut value = self.values[v].lock();
+        match self.consumers[v].fetch_sub(1, Ordering::AcqRel) {
+            1 => value.take().unwrap(),
+            _ => value.as_ref().unwrap().clone(),
+        }
+    }
+
+    // Compute the value of a node whose children are done and continue with
+    // the parents that became ready. One parent is continued on this thread
+    // and the rest are spawned for other threads to steal.
+    fn run<'s>(&'s self, mut v: usize, scope: &rayon::Scope<'s>)
+    where
+        T: 's,
+    {
+        loop {
+            let inputs = self.children[v].iter().map(|&c| self.consume(c)).collect();
+            *self.values[v].lock() = Some((self.f)(&self.nodes[v], inputs));
+            let mut next = None;
+            for &p in self.parents[v].iter() {
+                if self.pending[p].fetch_sub(1, Ordering::AcqRel) == 1 {
+                    match next {
+                        None => next = Some(p),
+                        Some(_) => scope.spawn(move |scope| self.run(p, scope)),
+                    }
+                }
+            }
+            match next {
+                Some(p) => v = p,
+                None => return,
+            }
+        }
+    }
+}
+
+/// # Parallel Post-order Fold
+///
+/// Compute a value for every node reachable from the source from the values
+/// of its children, bottom up and in parallel, and return the value of the
+/// source. The closure gets the node and the values of its children in the
+/// order of its edges. Leaves are folded in parallel and every node is
+/// folded as soon as the last of its children is done, by whichever thread
+/// finished it, so the work is balanced by rayon's work stealing even in
+/// deep and uneven hierarchies. In a directed acyclic graph a node reached
+/// through many parents is folded once and its value is cloned for each.
+/// Edges that close a cycle are left out. Following both directions folds
+/// over a depth first spanning tree.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use std::sync::Arc;
+///
+/// let root = Arc::new(Node::<&str, u64, Empty>::new("/", 1));
+/// let etc = Arc::new(Node::new("/etc", 2));
+/// let usr = Arc::new(Node::new("/usr", 3));
+/// let bin = Arc::new(Node::new("/usr/bin", 4));
+/// connect(&root, &etc, Empty);
+/// connect(&root, &usr, Empty);
+/// connect(&usr, &bin, Empty);
+///
+/// let size = parallel_post_order_fold(&root, Direction::Outbound, |node, children: Vec<u64>| {
+///     node.load() + children.iter().sum::<u64>()
+/// });
+/// assert!(size == 10);
+/// ```
+pub fn parallel_post_order_fold<K, N, E, T, F>(
+    source: &Arc<Node<K, N, E>>,
+    direction: Direction,
+    f: F,
+) -> T
+where
+    K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+    N: Clone + Debug + Display + Sync + Send,
+    E: Clone + Debug + Display + Sync + Send,
+    T: Clone + Send,
+    F: Fn(&Arc<Node<K, N, E>>, Vec<T>) -> T + Sync + Send,
+{
+    let fold = PostOrder::new(source, direction, f);
+    let leaves: Vec<usize> = (0..fold.nodes.len())
+        .filter(|&v| fold.children[v].is_empty())
+        .collect();
+    rayon::scope(|scope| {
+        leaves
+            .into_par_iter()
+            .for_each(|v| fold.run(v, scope));
+    });
+    let root = fold.values[0].lock().take();
+    root.unwrap()
+}
+
+//=============================================================================
@@ -0,0 +1,311 @@
+//=============================================================================
+// FROZEN GRAPH
+//=============================================================================
+
+//! Frozen snapshot of a graph in compressed sparse row form. Nodes are
+//! numbered from zero and the neighbors of every node are stored in one flat
+//! array, which makes the snapshot cheap to scan in parallel and suitable for
+//! graphs with hundreds of millions of edges. The snapshot holds on to the
+//! nodes and edges of the graph, but changes to the structure of the graph
+//! after freezing aren't reflected in it.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::centrality::Convergence;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::{Arc, atomic::{AtomicUsize, Ordering}},
+};
+
+/// Graph in compressed sparse row form. The neighbors of node `i` are
+/// `targets[offsets[i]..offsets[i + 1]]`. A directed graph stores outbound
+/// neighbors and an undirected graph stores every edge in both directions.
+pub struct Csr<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	nodes: Vec<Arc<Node<K, N, E>>>,
+	index: HashMap<K, usize>,
+	offsets: Vec<usize>,
+	targets: Vec<usize>,
+	edge_ids: Vec<usize>,
+	edges: Vec<Arc<Edge<K, N, E>>>,
+	directed: bool,
+}
+
+impl<K, N, E> Csr<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Freeze the current structure of the graph.
+	pub fn new<G: Graph<K, N, E>>(graph: &G) -> Self {
+		let nodes = graph.nodes();
+		let directed = G::directed();
+		let mut index = HashMap::with_capacity(nodes.len());
+		for (i, node) in nodes.iter().enumerate() {
+			index.insert(node.key().clone(), i);
+		}
+		let mut edges = Vec::new();
+		let mut endpoints = Vec::new();
+		for (u, node) in nodes.iter().enumerate() {
+			for edge in node.outbound().iter() {
+				if let Some(w) = index.get(edge.target().key()) {
+					endpoints.push((u, *w));
+					edges.push(edge.clone());
+				}
+			}
+		}
+		let mut offsets = vec![0; nodes.len() + 1];
+		for (u, w) in endpoints.iter() {
+			offsets[u + 1] += 1;
+			if !directed && u != w {
+				offsets[w + 1] += 1;
+			}
+		}
+		for i in 0..nodes.len() {
+			offsets[i + 1] += offsets[i];
+		}
+		let mut next = offsets.clone();
+		let mut targets = vec![0; offsets[nodes.len()]];
+		let mut edge_ids = vec![0; offsets[nodes.len()]];
+		for (e, (u, w)) in endpoints.into_iter().enumerate() {
+			targets[next[u]] = w;
+			edge_ids[next[u]] = e;
+			next[u] += 1;
+			if !directed && u != w {
+				targets[next[w]] = u;
+				edge_ids[next[w]] = e;
+				next[w] += 1;
+			}
+		}
+		Csr { nodes, index, offsets, targets, edge_ids, edges, directed }
+	}
+
+	/// True if the frozen graph is directed.
+	pub fn directed(&self) -> bool {
+		self.directed
+	}
+
+	/// Amount of nodes.
+	pub fn node_count(&self) -> usize {
+		self.nodes.len()
+	}
+
+	/// Amount of edges. Undirected edges are counted once.
+	pub fn edge_count(&self) -> usize {
+		self.edges.len()
+	}
+
+	/// Index of the node with the given key.
+	pub fn index(&self, key: &K) -> Option<usize> {
+		self.index.get(key).copied()
+	}
+
+	/// Node at the given index.
+	pub fn node(&self, i: usize) -> &Arc<Node<K, N, E>> {
+		&self.nodes[i]
+	}
+
+	/// Key of the node at the given index.
+	pub fn key(&self, i: usize) -> &K {
+		self.nodes[i].key()
+	}
+
+	/// Indices of the neighbors of the node at the given index.
+	pub fn neighbors(&self, i: usize) -> &[usize] {
+		&self.targets[self.offsets[i]..self.offsets[i + 1]]
+	}
+
+	/// Indices of the edges to the neighbors of the node at the given index,
+	/// in the same order as `neighbors`.
+	pub fn edge_ids(&self, i: usize) -> &[usize] {
+		&self.edge_ids[self.offsets[i]..self.offsets[i + 1]]
+	}
+
+	/// Edge at the given index.
+	pub fn edge(&self, e: usize) -> &Arc<Edge<K, N, E>> {
+		&self.edges[e]
+	}
+
+	/// # Parallel Connected Components
+	///
+	/// Label the connected components of the graph, ignoring the direction of
+	/// the edges in a directed graph. The edges are scanned in parallel and
+	/// joined in a lock free union-find where the root of a component always
+	/// points to a root with a smaller index. Returns the label of every node
+	/// by index, which is the smallest index of a node in its component.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..6 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(3, 4, Empty);
+	///
+	/// let csr = g.freeze();
+	/// let labels = csr.par_connected_components();
+	/// let label = |key| labels[csr.index(&key).unwrap()];
+	///
+	/// assert!(label(0) == label(2));
+	/// assert!(label(3) == label(4));
+	/// assert!(label(0) != label(3) && label(5) != label(3));
+	/// ```
+	pub fn par_connected_components(&self) -> Vec<usize> {
+		let parent: Vec<AtomicUsize> = (0..self.nodes.len()).map(AtomicUsize::new).collect();
+		(0..self.nodes.len()).into_par_iter().for_each(|u| {
+			for w in self.neighbors(u) {
+				union(&parent, u, *w);
+			}
+		});
+		(0..self.nodes.len()).into_par_iter().map(|i| find(&parent, i)).collect()
+	}
+
+	/// # Parallel PageRank
+	///
+	/// Compute the PageRank of every node with power iteration like
+	/// `centrality::pagerank`, which suits graphs of hundreds of millions of
+	/// edges. Every node pulls the scores of its predecessors along the edges
+	/// of the transposed graph, so the nodes are updated in parallel without
+	/// locks or atomics, and the buffers of the scores are allocated once
+	/// and reused in every iteration. Iteration stops after
+	/// `max_iterations` or once the residual of an iteration falls below
+	/// `tolerance`. Returns the score of every node by index and the
+	/// residual of every iteration.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	///
+	/// let mut g = Digraph::<u32, Empty, Empty>::new();
+	/// for i in 0..3 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 0, Empty);
+	///
+	/// let (scores, convergence) = g.freeze().par_pagerank(0.85, 100, 1e-9);
+	/// assert!(scores.iter().all(|s| (s - 1.0 / 3.0).abs() < 1e-9));
+	/// assert!(convergence.converged && convergence.iterations() == 1);
+	/// ```
+	pub fn par_pagerank(&self, damping: f64, max_iterations: usize, tolerance: f64) -> (Vec<f64>, Convergence) {
+		let n = self.nodes.len();
+		let mut convergence = Convergence::default();
+		if n == 0 {
+			convergence.converged = true;
+			return (Vec::new(), convergence);
+		}
+		let teleport = 1.0 / n as f64;
+		// An undirected graph is its own transpose.
+		let transpose;
+		let (in_offsets, sources) = match self.directed {
+			true => {
+				transpose = self.transpose();
+				(&transpose.0, &transpose.1)
+			}
+			false => { (&self.offsets, &self.targets) }
+		};
+		let degree = |u: usize| self.offsets[u + 1] - self.offsets[u];
+		let mut scores = vec![teleport; n];
+		let mut shares = vec![0.0; n];
+		let mut new_scores = vec![0.0; n];
+		let _span = trace_span!("par_pagerank", nodes = n, edges = self.targets.len());
+		for iteration in 0..max_iterations {
+			shares.par_iter_mut().enumerate().for_each(|(u, share)| {
+				*share = match degree(u) {
+					0 => { 0.0 }
+					d => { scores[u] / d as f64 }
+				};
+			});
+			let dangling: f64 = (0..n).into_par_iter().filter(|u| degree(*u) == 0).map(|u| scores[u]).sum();
+			let base = (1.0 - damping) * teleport + damping * dangling * teleport;
+			new_scores.par_iter_mut().enumerate().for_each(|(v, score)| {
+				let pulled: f64 = sources[in_offsets[v]..in_offsets[v + 1]].iter().map(|u| shares[*u]).sum();
+				*score = base + damping * pulled;
+			});
+			let residual: f64 = scores.par_iter().zip(new_scores.par_iter()).map(|(x, y)| (x - y).abs()).sum();
+			trace_event!("iteration", iteration = iteration, residual = residual);
+			std::mem::swap(&mut scores, &mut new_scores);
+			convergence.residuals.push(residual);
+			if residual < tolerance {
+				convergence.converged = true;
+				break;
+			}
+		}
+		(scores, convergence)
+	}
+
+	// Offsets and sources of the transposed graph, in which the sources of
+	// the edges into node `i` are `sources[offsets[i]..offsets[i + 1]]`.
+	fn transpose(&self) -> (Vec<usize>, Vec<usize>) {
+		let n = self.nodes.len();
+		let mut offsets = vec![0; n + 1];
+		for w in self.targets.iter() {
+			offsets[w + 1] += 1;
+		}
+		for i in 0..n {
+			offsets[i + 1] += offsets[i];
+		}
+		let mut next = offsets.clone();
+		let mut sources = vec![0; self.targets.len()];
+		for u in 0..n {
+			for w in self.neighbors(u) {
+				sources[next[*w]] = u;
+				next[*w] += 1;
+			}
+		}
+		(offsets, sources)
+	}
+}
+
+//=============================================================================
+
+// Root of the node with path halving. Concurrent halving only ever replaces a
+// parent by one of its ancestors, so a lost update is harmless.
+fn find(parent: &[AtomicUsize], mut x: usize) -> usize {
+	loop {
+		let p = parent[x].load(Ordering::Acquire);
+		if p == x {
+			return x;
+		}
+		let grandparent = parent[p].load(Ordering::Acquire);
+		if grandparent != p {
+			let _ = parent[x].compare_exchange(p, grandparent, Ordering::AcqRel, Ordering::Acquire);
+		}
+		x = grandparent;
+	}
+}
+
+// Join the components of two nodes by linking the root with the larger index
+// under the other root. Linking fails if the root was linked by another
+// thread in the meantime, in which case the roots are looked up again.
+fn union(parent: &[AtomicUsize], u: usize, w: usize) {
+	loop {
+		let (a, b) = (find(parent, u), find(parent, w));
+		if a == b {
+			return;
+		}
+		let (high, low) = if a > b { (a, b) } else { (b, a) };
+		if parent[high].compare_exchange(high, low, Ordering::AcqRel, Ordering::Acquire).is_ok() {
+			return;
+		}
+	}
+}
+
+//=============================================================================
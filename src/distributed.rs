@@ -0,0 +1,318 @@
+//=============================================================================
+// DISTRIBUTED
+//=============================================================================
+
+//! Splitting a graph into shards that can be processed apart from each
+//! other, for example by different processes. Every node is owned by exactly
+//! one shard. A shard holds the nodes it owns, every edge with an owned
+//! endpoint and a halo of ghost nodes: copies of the nodes of other shards
+//! that are adjacent to its own nodes, each with the index of the shard that
+//! owns it. An algorithm run on the graph of a shard updates its owned
+//! nodes, and `gather` writes their data back into the whole graph, while
+//! `merge` reassembles the whole graph from the shards. With the `serde`
+//! feature every shard can be serialized on its own.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::distributed::*;
+//!
+//! let mut g = Digraph::<u32, u32, f64>::new();
+//! for i in 0..4 {
+//!     g.add_node(i, 0);
+//! }
+//! g.add_edge(0, 1, 1.0);
+//! g.add_edge(1, 2, 1.0);
+//! g.add_edge(2, 3, 1.0);
+//!
+//! let shards = split(&g, &[vec![0, 1], vec![2, 3]]);
+//! assert!(shards[0].ghosts == vec![(2, 0, 1)]);
+//!
+//! // Compute on every shard apart and write the results back.
+//! for shard in shards.iter() {
+//!     let local: Digraph<u32, u32, f64> = shard.graph();
+//!     for node in local.nodes() {
+//!         node.store(node.outbound().len() as u32);
+//!     }
+//!     gather(&g, shard, &local);
+//! }
+//! assert!(g.get_node(3).unwrap().load() == 0);
+//! assert!(g.get_node(1).unwrap().load() == 1);
+//! ```
+use crate::core::*;
+use crate::collections::Graph;
+use crate::multilevel::multilevel_partition;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+#[cfg(feature = "serde")]
+use crate::io::Error;
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "serde")]
+use std::io::{Read, Write};
+
+//=============================================================================
+
+/// The part of a graph owned by one of the shards it was split into.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shard<K, N, E> {
+	/// Index of this shard.
+	pub index: usize,
+	/// Amount of shards the graph was split into.
+	pub count: usize,
+	/// Whether the graph is directed.
+	pub directed: bool,
+	/// Keys and data of the nodes owned by this shard.
+	pub nodes: Vec<(K, N)>,
+	/// Keys and data of the nodes of other shards adjacent to the owned
+	/// nodes, with the index of the shard that owns them.
+	pub ghosts: Vec<(K, N, usize)>,
+	/// Source, target and data of every edge with an owned endpoint. Edges
+	/// between shards are in both of them.
+	pub edges: Vec<(K, K, E)>,
+}
+
+impl<K, N, E> Shard<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Build a graph of the owned nodes, the ghost nodes and the edges of
+	/// this shard.
+	pub fn graph<G>(&self) -> G
+	where
+		G: Graph<K, N, E>,
+	{
+		let mut res = G::new();
+		for (key, data) in self.nodes.iter() {
+			res.add_node(key.clone(), data.clone());
+		}
+		for (key, data, _) in self.ghosts.iter() {
+			res.add_node(key.clone(), data.clone());
+		}
+		for (source, target, data) in self.edges.iter() {
+			res.add_edge(source.clone(), target.clone(), data.clone());
+		}
+		res
+	}
+
+	/// Index of the shard that owns every node of this shard, owned and
+	/// ghost.
+	pub fn owners(&self) -> HashMap<K, usize> {
+		let mut res: HashMap<K, usize> = self.nodes.iter().map(|(key, _)| (key.clone(), self.index)).collect();
+		for (key, _, owner) in self.ghosts.iter() {
+			res.insert(key.clone(), *owner);
+		}
+		res
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<K, N, E> Shard<K, N, E>
+where
+	K: Serialize + DeserializeOwned,
+	N: Serialize + DeserializeOwned,
+	E: Serialize + DeserializeOwned,
+{
+	/// Write this shard as JSON.
+	pub fn write<W: Write>(&self, writer: W) -> Result<(), Error> {
+		serde_json::to_writer(writer, self).map_err(|err| Error::Format(err.to_string()))
+	}
+
+	/// Read a shard written by `write`.
+	pub fn read<R: Read>(reader: R) -> Result<Self, Error> {
+		serde_json::from_reader(reader).map_err(|err| Error::Format(err.to_string()))
+	}
+}
+
+//=============================================================================
+
+/// # Split
+///
+/// Split the graph into one shard for every part of the given keys, for
+/// example the parts found by `multilevel_partition`. Keys that can't be
+/// found or appear in an earlier part are ignored. Nodes that aren't in any
+/// part are left out together with their edges. Shards list their nodes
+/// and ghosts in the order of the parts and their edges in the order of
+/// their owned nodes.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::distributed::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..3 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, Empty);
+/// g.add_edge(1, 2, Empty);
+///
+/// let shards = split(&g, &[vec![1], vec![0, 2]]);
+/// assert!(shards[0].ghosts.len() == 2);
+/// assert!(shards[0].edges.len() == 2 && shards[1].edges.len() == 2);
+/// ```
+pub fn split<K, N, E, G>(graph: &G, parts: &[Vec<K>]) -> Vec<Shard<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let mut owner: HashMap<K, usize> = HashMap::new();
+	let mut owned = vec![Vec::new(); parts.len()];
+	for (i, part) in parts.iter().enumerate() {
+		for key in part.iter() {
+			if owner.contains_key(key) {
+				continue;
+			}
+			if let Some(node) = graph.get_node(key.clone()) {
+				owner.insert(key.clone(), i);
+				owned[i].push(node);
+			}
+		}
+	}
+	owned
+		.into_iter()
+		.enumerate()
+		.map(|(i, nodes)| {
+			let mut ghosts = Vec::new();
+			let mut halo = HashSet::new();
+			let mut edges = Vec::new();
+			let mut seen = HashSet::new();
+			for node in nodes.iter() {
+				for (edge, other) in node.incident(Direction::Both) {
+					let j = match owner.get(other.key()) {
+						Some(j) => { *j }
+						None => { continue }
+					};
+					let (source, target) = (edge.source(), edge.target());
+					if !seen.insert((source.key().clone(), target.key().clone())) {
+						continue;
+					}
+					if j != i && halo.insert(other.key().clone()) {
+						ghosts.push((other.key().clone(), other.load(), j));
+					}
+					edges.push((source.key().clone(), target.key().clone(), edge.load()));
+				}
+			}
+			Shard {
+				index: i,
+				count: parts.len(),
+				directed: G::directed(),
+				nodes: nodes.iter().map(|node| (node.key().clone(), node.load())).collect(),
+				ghosts,
+				edges,
+			}
+		})
+		.collect()
+}
+
+/// Split the graph into `count` shards of about the same amount of nodes
+/// that cut few edges, found with `multilevel_partition`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::distributed::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..20 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..19 {
+///     g.add_edge(i, i + 1, Empty);
+/// }
+///
+/// let shards = split_into(&g, 2);
+/// assert!(shards.len() == 2);
+/// assert!(shards.iter().map(|s| s.nodes.len()).sum::<usize>() == 20);
+/// ```
+pub fn split_into<K, N, E, G>(graph: &G, count: usize) -> Vec<Shard<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	split(graph, &multilevel_partition(graph, count, 0.05, |_| 1.0))
+}
+
+/// # Merge
+///
+/// Reassemble a graph from its shards, taking every node from the shard
+/// that owns it and every edge from the shard that owns its source.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::distributed::*;
+///
+/// let mut g = Digraph::<u32, u32, u32>::new();
+/// for i in 0..3 {
+///     g.add_node(i, i * 10);
+/// }
+/// g.add_edge(0, 1, 1);
+/// g.add_edge(2, 0, 2);
+///
+/// let h: Digraph<u32, u32, u32> = merge(&split(&g, &[vec![0], vec![1, 2]]));
+/// assert!(h.get_node(2).unwrap().load() == 20);
+/// assert!(h.get_edge(2, 0).unwrap().load() == 2);
+/// ```
+pub fn merge<K, N, E, G>(shards: &[Shard<K, N, E>]) -> G
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let mut res = G::new();
+	for shard in shards.iter() {
+		for (key, data) in shard.nodes.iter() {
+			res.add_node(key.clone(), data.clone());
+		}
+	}
+	for shard in shards.iter() {
+		let owned: HashSet<&K> = shard.nodes.iter().map(|(key, _)| key).collect();
+		for (source, target, data) in shard.edges.iter() {
+			if owned.contains(source) {
+				res.add_edge(source.clone(), target.clone(), data.clone());
+			}
+		}
+	}
+	res
+}
+
+/// Write the data of the nodes owned by the shard in its local graph, such
+/// as one built by `Shard::graph`, back into the whole graph. Nodes missing
+/// from either graph are skipped. Returns the amount of nodes written.
+pub fn gather<K, N, E, G, H>(graph: &G, shard: &Shard<K, N, E>, local: &H) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	H: Graph<K, N, E>,
+{
+	let mut res = 0;
+	for (key, _) in shard.nodes.iter() {
+		if let (Some(node), Some(result)) = (graph.get_node(key.clone()), local.get_node(key.clone())) {
+			node.store(result.load());
+			res += 1;
+		}
+	}
+	res
+}
+
+//=============================================================================
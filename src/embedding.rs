@@ -0,0 +1,389 @@
+//=============================================================================
+// EMBEDDING
+//=============================================================================
+
+//! Generation of random walk corpora for training node embeddings with
+//! external tools. Each walk is a sequence of node keys. Walks of
+//! heterogeneous graphs follow a metapath in the style of metapath2vec, so
+//! that for example authors are only reached through the papers they wrote.
+//!
+use crate::centrality::stable_order;
+use crate::checkpoint::Checkpoint;
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use crate::sketch::hash;
+use crate::typed::{follow, matches, Metapath, Tagged};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	fmt::{Debug, Display},
+	fs::File,
+	hash::Hash,
+	io::{BufWriter, Write},
+	path::Path,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// Parameters of node2vec style biased random walks. The return parameter
+/// `p` controls the likelihood of immediately revisiting the previous node
+/// and the in-out parameter `q` the likelihood of moving away from it. With
+/// both set to one the walks are uniform random walks.
+#[derive(Clone, Debug)]
+pub struct Node2Vec {
+	/// Amount of nodes in a walk, including the node it starts from. A walk
+	/// has at least its start node even if this is zero.
+	pub walk_length: usize,
+	/// Amount of walks starting from every node.
+	pub walks_per_node: usize,
+	/// Return parameter, the likelihood of revisiting the previous node is
+	/// proportional to `1 / p`.
+	pub p: f64,
+	/// In-out parameter, the likelihood of moving to a node that isn't
+	/// adjacent to the previous node is proportional to `1 / q`.
+	pub q: f64,
+	/// Seed of the random number generators of the walks.
+	pub seed: u64,
+}
+
+impl Default for Node2Vec {
+	fn default() -> Self {
+		Node2Vec {
+			walk_length: 80,
+			walks_per_node: 10,
+			p: 1.0,
+			q: 1.0,
+			seed: 0,
+		}
+	}
+}
+
+impl Node2Vec {
+	/// Generate `walks_per_node` walks starting from every node of the graph.
+	/// Walks are generated in parallel and are reproducible for a given seed:
+	/// nodes are visited in an order given by a hash of their keys, so a
+	/// graph with the same nodes and edges gives the same walks in every run.
+	/// A walk ends early at a node without neighbors.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use fastgraph::embedding::*;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// g.add_node(1, Empty);
+	/// g.add_node(2, Empty);
+	/// g.add_edge(1, 2, Empty);
+	///
+	/// let config = Node2Vec { walk_length: 3, walks_per_node: 2, ..Node2Vec::default() };
+	/// let walks = config.walks(&g);
+	///
+	/// assert!(walks.len() == 4);
+	/// assert!(walks.iter().all(|walk| walk.len() == 3));
+	/// ```
+	pub fn walks<K, N, E, G>(&self, graph: &G) -> Vec<Vec<K>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		self.walks_resume(graph, &mut Checkpoint::new(), usize::MAX).unwrap_or_default()
+	}
+
+	/// Generate the walks of `walks` a batch of at most `batch` walks at a
+	/// time, recording the walks generated so far in the checkpoint. Returns
+	/// the walks once all of them have been generated and None before that.
+	/// Since the walks are reproducible a checkpoint saved to disk can be
+	/// resumed by another process as long as the graph and the parameters
+	/// haven't changed. A checkpoint of a different amount of walks is
+	/// started over. A batch of zero is taken as one.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::checkpoint::Checkpoint;
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use fastgraph::embedding::*;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// g.add_node(1, Empty);
+	/// g.add_node(2, Empty);
+	/// g.add_edge(1, 2, Empty);
+	///
+	/// let config = Node2Vec { walk_length: 3, walks_per_node: 2, ..Node2Vec::default() };
+	/// let mut checkpoint = Checkpoint::new();
+	/// assert!(config.walks_resume(&g, &mut checkpoint, 3).is_none());
+	/// assert!(config.walks_resume(&g, &mut checkpoint, 3).unwrap() == config.walks(&g));
+	/// ```
+	pub fn walks_resume<K, N, E, G>(&self, graph: &G, checkpoint: &mut Checkpoint<Vec<K>>, batch: usize) -> Option<Vec<Vec<K>>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		let indexed = Indexed::new(graph.nodes(), G::direction());
+		// Walk over the positions of the nodes in the stable order, so that
+		// neither the start nodes nor the neighbors depend on the order of the
+		// nodes in the graph.
+		let order = stable_order(&indexed);
+		let mut rank = vec![0; order.len()];
+		for (r, v) in order.iter().enumerate() {
+			rank[*v] = r;
+		}
+		let adjacency: Vec<Vec<usize>> = order
+			.iter()
+			.map(|v| {
+				let mut adjacent: Vec<usize> = indexed.adjacency[*v].iter().map(|(w, _)| rank[*w]).collect();
+				adjacent.sort_unstable();
+				adjacent.dedup();
+				adjacent
+			})
+			.collect();
+		let n = adjacency.len();
+		let total = n * self.walks_per_node;
+		let range = checkpoint.next(total, batch);
+		let walks: Vec<Vec<K>> = range
+			.clone()
+			.into_par_iter()
+			.map(|i| {
+				self.walk(&adjacency, i % n, &mut rng(self.seed, i))
+					.into_iter()
+					.map(|r| indexed.key(order[r]))
+					.collect()
+			})
+			.collect();
+		resume(checkpoint, range.end, total, walks)
+	}
+
+	/// Generate the walks and write them to a file with one walk per line
+	/// and keys separated by spaces.
+	pub fn write_walks<K, N, E, G, P>(&self, graph: &G, path: P) -> std::io::Result<()>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		P: AsRef<Path>,
+	{
+		write(self.walks(graph), path)
+	}
+
+	// A single biased walk over sorted adjacency lists.
+	fn walk(&self, adjacency: &[Vec<usize>], start: usize, rng: &mut StdRng) -> Vec<usize> {
+		let mut walk = vec![start];
+		while walk.len() < self.walk_length {
+			let v = *walk.last().unwrap();
+			let adjacent = &adjacency[v];
+			if adjacent.is_empty() {
+				break;
+			}
+			let next = match walk.len() {
+				1 => { adjacent[rng.gen_range(0..adjacent.len())] }
+				_ => {
+					let t = walk[walk.len() - 2];
+					let weights: Vec<f64> = adjacent
+						.iter()
+						.map(|x| {
+							if *x == t {
+								1.0 / self.p
+							} else if adjacency[t].binary_search(x).is_ok() {
+								1.0
+							} else {
+								1.0 / self.q
+							}
+						})
+						.collect();
+					let mut pick = rng.gen::<f64>() * weights.iter().sum::<f64>();
+					let mut choice = adjacent[adjacent.len() - 1];
+					for (x, weight) in adjacent.iter().zip(weights) {
+						if pick < weight {
+							choice = *x;
+							break;
+						}
+						pick -= weight;
+					}
+					choice
+				}
+			};
+			walk.push(next);
+		}
+		walk
+	}
+}
+
+//=============================================================================
+
+/// Parameters of metapath2vec style random walks, which repeat the steps of
+/// a metapath and pick uniformly among the edges matching the next step. A
+/// metapath that ends with the tag it starts with, like author, paper,
+/// author, can be repeated for walks of any length.
+#[derive(Clone, Debug)]
+pub struct Metapath2Vec<TN, TE> {
+	/// Steps the walks repeat, starting from the nodes that match its start.
+	pub metapath: Metapath<TN, TE>,
+	/// Amount of nodes in a walk, including the node it starts from. A walk
+	/// has at least its start node even if this is zero.
+	pub walk_length: usize,
+	/// Amount of walks starting from every node matching the metapath.
+	pub walks_per_node: usize,
+	/// Seed of the random number generators of the walks.
+	pub seed: u64,
+}
+
+impl<TN, TE> Metapath2Vec<TN, TE>
+where
+	TN: PartialEq + Sync + Send,
+	TE: PartialEq + Sync + Send,
+{
+	/// Walks with the default length, amount and seed of `Node2Vec`.
+	pub fn new(metapath: Metapath<TN, TE>) -> Self {
+		let defaults = Node2Vec::default();
+		Metapath2Vec {
+			metapath,
+			walk_length: defaults.walk_length,
+			walks_per_node: defaults.walks_per_node,
+			seed: defaults.seed,
+		}
+	}
+
+	/// Generate `walks_per_node` walks starting from every node that matches
+	/// the start of the metapath. Walks are generated in parallel and are
+	/// reproducible for a given seed: start nodes and neighbors are taken in
+	/// an order given by a hash of their keys, so a graph with the same nodes and edges
+	/// gives the same walks in every run. A walk ends early at a node without
+	/// edges matching the next step.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::{Direction, Empty};
+	/// use fastgraph::embedding::*;
+	/// use fastgraph::typed::*;
+	///
+	/// let mut g = Digraph::<&str, Typed<&str, Empty>, Typed<&str, Empty>>::new();
+	/// g.add_node("ada", Typed::new("author", Empty));
+	/// g.add_node("bob", Typed::new("author", Empty));
+	/// g.add_node("p1", Typed::new("paper", Empty));
+	/// g.add_node("acme", Typed::new("company", Empty));
+	/// g.add_edge("ada", "p1", Typed::new("writes", Empty));
+	/// g.add_edge("bob", "p1", Typed::new("writes", Empty));
+	/// g.add_edge("ada", "acme", Typed::new("works_at", Empty));
+	///
+	/// let metapath = Metapath::new(Some("author"))
+	///     .step(Direction::Outbound, Some("writes"), Some("paper"))
+	///     .step(Direction::Inbound, Some("writes"), Some("author"));
+	/// let config = Metapath2Vec { walk_length: 5, walks_per_node: 2, ..Metapath2Vec::new(metapath) };
+	/// let walks = config.walks(&g);
+	///
+	/// assert!(walks.len() == 4);
+	/// assert!(walks.iter().all(|walk| walk.len() == 5 && walk[1] == "p1" && walk[3] == "p1" && !walk.contains(&"acme")));
+	/// ```
+	pub fn walks<K, N, E, G>(&self, graph: &G) -> Vec<Vec<K>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+		G: Graph<K, N, E>,
+	{
+		self.walks_resume(graph, &mut Checkpoint::new(), usize::MAX).unwrap_or_default()
+	}
+
+	/// Generate the walks of `walks` a batch of at most `batch` walks at a
+	/// time like `Node2Vec::walks_resume`.
+	pub fn walks_resume<K, N, E, G>(&self, graph: &G, checkpoint: &mut Checkpoint<Vec<K>>, batch: usize) -> Option<Vec<Vec<K>>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+		G: Graph<K, N, E>,
+	{
+		let mut starts: Vec<Arc<Node<K, N, E>>> = graph
+			.nodes()
+			.into_iter()
+			.filter(|n| n.with(|data| matches(&self.metapath.start, data.tags())))
+			.collect();
+		starts.sort_by_cached_key(|n| hash(n.key()));
+		let n = starts.len();
+		let total = n * self.walks_per_node;
+		let range = checkpoint.next(total, batch);
+		let walks: Vec<Vec<K>> = range
+			.clone()
+			.into_par_iter()
+			.map(|i| self.walk(&starts[i % n], G::directed(), &mut rng(self.seed, i)))
+			.collect();
+		resume(checkpoint, range.end, total, walks)
+	}
+
+	/// Generate the walks and write them to a file with one walk per line
+	/// and keys separated by spaces.
+	pub fn write_walks<K, N, E, G, P>(&self, graph: &G, path: P) -> std::io::Result<()>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+		G: Graph<K, N, E>,
+		P: AsRef<Path>,
+	{
+		write(self.walks(graph), path)
+	}
+
+	fn walk<K, N, E>(&self, start: &Arc<Node<K, N, E>>, directed: bool, rng: &mut StdRng) -> Vec<K>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+	{
+		let mut walk = vec![start.key().clone()];
+		let mut node = start.clone();
+		let steps = &self.metapath.steps;
+		while walk.len() < self.walk_length && !steps.is_empty() {
+			let mut adjacent = follow(&node, &steps[(walk.len() - 1) % steps.len()], directed);
+			if adjacent.is_empty() {
+				break;
+			}
+			// Neighbors in the stable order rather than the order of the edges.
+			adjacent.sort_by_cached_key(|n| hash(n.key()));
+			node = adjacent[rng.gen_range(0..adjacent.len())].clone();
+			walk.push(node.key().clone());
+		}
+		walk
+	}
+}
+
+//=============================================================================
+
+// Random number generator of the walk with the given index.
+fn rng(seed: u64, i: usize) -> StdRng {
+	StdRng::seed_from_u64(seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+// Record a batch of walks ending at `end` in the checkpoint and return every
+// walk once all `total` of them have been generated.
+fn resume<K>(checkpoint: &mut Checkpoint<Vec<K>>, end: usize, total: usize, walks: Vec<Vec<K>>) -> Option<Vec<Vec<K>>> {
+	checkpoint.values.extend(walks);
+	checkpoint.done = end;
+	match end < total {
+		true => { None }
+		false => { Some(std::mem::take(&mut checkpoint.values)) }
+	}
+}
+
+// Write walks with one walk per line and keys separated by spaces.
+fn write<K: Display, P: AsRef<Path>>(walks: Vec<Vec<K>>, path: P) -> std::io::Result<()> {
+	let mut out = BufWriter::new(File::create(path)?);
+	for walk in walks {
+		let line: Vec<String> = walk.iter().map(|k| k.to_string()).collect();
+		writeln!(out, "{}", line.join(" "))?;
+	}
+	out.flush()
+}
+
+//=============================================================================
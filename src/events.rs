@@ -0,0 +1,109 @@
+//=============================================================================
+// MUTATION EVENTS
+//=============================================================================
+
+//! Events describing the mutations made to a graph through the `Graph`
+//! trait. A graph that keeps a list of subscribers sends every event to each
+//! subscriber's channel, so other systems can mirror the graph as it changes.
+//! Every event also moves the graph to a new revision, which lets results
+//! computed from the graph be cached until it changes.
+//! Mutations made directly on nodes, for example with `connect` or
+//! `Node::store`, are not observed.
+//!
+use parking_lot::Mutex;
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		mpsc::{channel, Receiver, Sender},
+	},
+};
+
+// Source of revisions. Revisions are unique across all graphs so that a
+// revision identifies both the graph and its state.
+static REVISIONS: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+	REVISIONS.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A mutation of a graph.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event<K, N, E> {
+	AddNode { key: K, data: N },
+	/// A deleted node. Every edge deleted along with the node is sent as a
+	/// `DelEdge` before it, so the node has no edges left when this arrives.
+	DelNode { key: K },
+	AddEdge { source: K, target: K, data: E },
+	DelEdge { source: K, target: K },
+}
+
+/// Channels of the subscribers of a graph together with the revision of the
+/// graph. Subscribers whose receiver has been dropped are removed when the
+/// next event is sent.
+pub struct Subscribers<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	senders: Mutex<Vec<Sender<Event<K, N, E>>>>,
+	revision: AtomicU64,
+}
+
+impl<K, N, E> Subscribers<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	pub fn new() -> Self {
+		Subscribers { senders: Mutex::new(Vec::new()), revision: AtomicU64::new(next_revision()) }
+	}
+
+	/// Current revision of the graph.
+	pub fn revision(&self) -> u64 {
+		self.revision.load(Ordering::Acquire)
+	}
+
+	/// Move the graph to a new revision without sending an event.
+	pub fn touch(&self) {
+		self.revision.store(next_revision(), Ordering::Release);
+	}
+
+	/// Add a subscriber and return the receiving end of its channel.
+	pub fn subscribe(&self) -> Receiver<Event<K, N, E>> {
+		let (sender, receiver) = channel();
+		self.senders.lock().push(sender);
+		receiver
+	}
+
+	/// True if there is at least one subscriber.
+	pub fn active(&self) -> bool {
+		!self.senders.lock().is_empty()
+	}
+
+	/// Send an event to every subscriber and move the graph to a new
+	/// revision. The event is only built if there are subscribers.
+	pub fn send<F: FnOnce() -> Event<K, N, E>>(&self, event: F) {
+		self.touch();
+		let mut senders = self.senders.lock();
+		if senders.is_empty() {
+			return;
+		}
+		let event = event();
+		senders.retain(|s| s.send(event.clone()).is_ok());
+	}
+}
+
+impl<K, N, E> Default for Subscribers<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,802 @@
+//=============================================================================
+// MINIMUM COST FLOW
+//=============================================================================
+
+//! Minimum cost flows with lower and upper bounds on the flow of every edge.
+//! Flows are found with successive shortest augmenting paths where node
+//! potentials keep the reduced edge costs non-negative so that every path
+//! can be found with Dijkstra's algorithm. Lower bounds are satisfied first
+//! by routing the flow they force through the graph from a super source to a
+//! super sink. Edges with a negative cost start out saturated, so negative
+//! costs and even negative cycles are allowed. Maximum flows without costs
+//! are found over a `Residual` view that keeps the flow of every edge out of
+//! the graph. Every algorithm keeps the flow of an arc next to its bounds
+//! and derives the residual capacities from them, so no reverse arcs are
+//! stored.
+//!
+//! Flows are integral and follow edges from their source to their target,
+//! also in undirected graphs.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::shortest_path::MinCost;
+use std::{
+	collections::{BinaryHeap, VecDeque},
+	convert::TryFrom,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Edges paired with their flow.
+pub type EdgeFlows<K, N, E> = Vec<(Arc<Edge<K, N, E>>, i64)>;
+
+/// Bounds on the flow of an edge and the cost of a unit of flow on it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capacity {
+	pub lower: i64,
+	pub upper: i64,
+	pub cost: f64,
+}
+
+impl Capacity {
+	/// Capacity without a lower bound.
+	pub fn new(upper: i64, cost: f64) -> Self {
+		Capacity { lower: 0, upper, cost }
+	}
+}
+
+/// What the paths found by `disjoint_paths` may not share.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Disjoint {
+	/// Paths share no edges.
+	Edges,
+	/// Paths share no nodes other than the source and the target.
+	Nodes,
+}
+
+/// A flow given as the flow of every edge together with the amount of flow
+/// from the source to the target and the total cost.
+pub struct Flow<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	pub value: i64,
+	pub cost: f64,
+	pub edges: EdgeFlows<K, N, E>,
+}
+
+//=============================================================================
+
+/// # Minimum Cost Maximum Flow
+///
+/// Find the maximum flow from the source node to the target node that
+/// respects the capacity of every edge given by the `capacity` closure, and
+/// of all maximum flows the one with the lowest total cost. Returns None if
+/// either node can't be found or the lower bounds can't be satisfied, which
+/// includes bounds that force more flow through a node than fits in an
+/// `i64`. A flow value that doesn't fit saturates at `i64::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::flow::*;
+///
+/// let mut g = Digraph::<u32, Empty, f64>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 4, 1.0);
+/// g.add_edge(1, 3, 2.0);
+/// g.add_edge(3, 4, 2.0);
+///
+/// // Every edge fits two units and costs its data per unit.
+/// let flow = min_cost_max_flow(&g, &1, &4, |e| Capacity::new(2, e.load())).unwrap();
+///
+/// assert!(flow.value == 4);
+/// assert!(flow.cost == 12.0);
+/// ```
+pub fn min_cost_max_flow<K, N, E, G, F>(graph: &G, source: &K, target: &K, capacity: F) -> Option<Flow<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> Capacity,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let s = *indexed.index.get(source)?;
+	let t = *indexed.index.get(target)?;
+	let capacities: Vec<Capacity> = indexed.edges.iter().map(capacity).collect();
+	let mut network = Network::new(&indexed, &capacities)?;
+	// Flow forced by the lower bounds may return from the target to the
+	// source for free while the bounds are satisfied.
+	let unbounded = capacities.iter().fold(1, |total: i64, c| total.saturating_add(c.upper.max(0)));
+	let back = network.add_arc(t, s, unbounded, 0.0);
+	if !network.satisfy_bounds() {
+		return None;
+	}
+	// Fix the flow that returned so that it can't change any more.
+	network.lower[back] = network.flow[back];
+	network.upper[back] = network.flow[back];
+	let mut potential = network.bellman_ford(s);
+	network.augment(s, t, i64::MAX, &mut potential);
+	Some(network.flows(&indexed, &capacities, s))
+}
+
+/// # Minimum Cost Circulation
+///
+/// Find the flow with the lowest total cost in which the flow into every
+/// node equals the flow out of it and the flow of every edge respects its
+/// capacity given by the `capacity` closure. Lower bounds force flow around
+/// cycles and negative costs make cycles profitable. The value of the
+/// returned flow is zero. Returns None if the lower bounds can't be
+/// satisfied, which includes bounds that force more flow through a node
+/// than fits in an `i64`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::flow::*;
+///
+/// let mut g = Digraph::<u32, Empty, f64>::new();
+/// for i in 1..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 3, 1.0);
+/// g.add_edge(3, 1, -5.0);
+///
+/// let flow = min_cost_circulation(&g, |e| Capacity { lower: 0, upper: 3, cost: e.load() }).unwrap();
+///
+/// assert!(flow.cost == -9.0);
+/// ```
+pub fn min_cost_circulation<K, N, E, G, F>(graph: &G, capacity: F) -> Option<Flow<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> Capacity,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let capacities: Vec<Capacity> = indexed.edges.iter().map(capacity).collect();
+	let mut network = Network::new(&indexed, &capacities)?;
+	if !network.satisfy_bounds() {
+		return None;
+	}
+	Some(network.flows(&indexed, &capacities, 0))
+}
+
+/// # Disjoint Paths
+///
+/// Find up to `k` paths from the source node to the target node that share
+/// no edges, or with `Disjoint::Nodes` no nodes other than the source and the
+/// target. The paths are found as a maximum flow in which every edge, and in
+/// node mode every node, has a capacity of one, so the amount of paths found
+/// is the largest possible up to `k`. By Menger's theorem that is the least
+/// amount of edges or nodes whose removal separates the target from the
+/// source. Edges of undirected graphs may be used in either direction.
+/// Returns no paths if either node can't be found or both are the same node.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::flow::*;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 0..7 {
+///     g.add_node(i, Empty);
+/// }
+/// // Two routes from 0 to 6 that both pass through 3.
+/// for (s, t) in [(0, 1), (0, 2), (1, 3), (2, 3), (3, 4), (3, 5), (4, 6), (5, 6)] {
+///     g.add_edge(s, t, Empty);
+/// }
+///
+/// let paths = disjoint_paths(&g, &0, &6, 5, Disjoint::Edges);
+/// assert!(paths.len() == 2 && paths.iter().all(|p| p.len() == 4));
+/// assert!(disjoint_paths(&g, &0, &6, 1, Disjoint::Edges).len() == 1);
+/// assert!(disjoint_paths(&g, &0, &6, 5, Disjoint::Nodes).len() == 1);
+/// ```
+pub fn disjoint_paths<K, N, E, G>(graph: &G, source: &K, target: &K, k: usize, mode: Disjoint) -> Vec<Path<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let (s, t) = match (indexed.index.get(source), indexed.index.get(target)) {
+		(Some(&s), Some(&t)) if s != t => { (s, t) }
+		_ => { return Vec::new() }
+	};
+	let n = indexed.nodes.len();
+	// In node mode every node v is split into an entry 2v and an exit 2v + 1
+	// joined by an arc with a capacity of one.
+	let split = mode == Disjoint::Nodes;
+	let entry = |v: usize| if split { 2 * v } else { v };
+	let exit = |v: usize| if split { 2 * v + 1 } else { v };
+	let mut network = Network::empty(if split { 2 * n } else { n });
+	if split {
+		for v in 0..n {
+			network.add_arc(entry(v), exit(v), 1, 0.0);
+		}
+	}
+	// The arcs of every edge with the edge and the nodes the arc leaves and
+	// enters, one arc per direction in an undirected graph.
+	let mut arcs = Vec::with_capacity(indexed.edges.len());
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for &(w, e) in list.iter().filter(|(w, _)| *w != u) {
+			arcs.push((network.add_arc(exit(u), entry(w), 1, 0.0), e, u, w));
+			if !G::directed() {
+				arcs.push((network.add_arc(exit(w), entry(u), 1, 0.0), e, w, u));
+			}
+		}
+	}
+	let mut potential = vec![0.0; network.outbound.len()];
+	let (sent, _) = network.augment(exit(s), entry(t), i64::try_from(k).unwrap_or(i64::MAX), &mut potential);
+	// Edges carrying flow out of every node. Flow in both directions of an
+	// undirected edge cancels out.
+	let mut flow: Vec<Vec<(usize, usize)>> = vec![Vec::new(); n];
+	let used = |arc: usize| network.flow[arc] > 0;
+	for group in arcs.chunks(if G::directed() { 1 } else { 2 }) {
+		let carrying: Vec<_> = group.iter().filter(|(arc, ..)| used(*arc)).collect();
+		if let [(_, e, u, w)] = carrying[..] {
+			flow[*u].push((*e, *w));
+		}
+	}
+	let mut res = Vec::with_capacity(sent as usize);
+	for _ in 0..sent {
+		let mut nodes = vec![s];
+		let mut edges: Vec<usize> = Vec::new();
+		let mut v = s;
+		while v != t {
+			let (e, w) = match flow[v].pop() {
+				Some(next) => { next }
+				None => { break }
+			};
+			// Cut out cycles, which edge disjoint flows may contain.
+			match nodes.iter().position(|x| *x == w) {
+				Some(p) => {
+					nodes.truncate(p + 1);
+					edges.truncate(p);
+				}
+				None => {
+					nodes.push(w);
+					edges.push(e);
+				}
+			}
+			v = w;
+		}
+		res.push(edges.iter().map(|e| Arc::downgrade(&indexed.edges[*e])).collect());
+	}
+	res
+}
+
+//=============================================================================
+
+/// An arc of a residual graph, which follows its edge forward if there is
+/// capacity left on the edge or backward if the edge carries flow that can
+/// be cancelled.
+#[derive(Clone, Debug)]
+pub struct ResidualArc<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	pub edge: Arc<Edge<K, N, E>>,
+	pub forward: bool,
+	pub capacity: i64,
+}
+
+impl<K, N, E> ResidualArc<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// The node the arc enters.
+	pub fn head(&self) -> Arc<Node<K, N, E>> {
+		match self.forward {
+			true => { self.edge.target() }
+			false => { self.edge.source() }
+		}
+	}
+}
+
+/// # Residual Graph
+///
+/// View of a graph as a flow network where every edge has a capacity and
+/// a flow. The residual capacity of an edge is its capacity minus its flow
+/// forward and its flow backward. The flows are kept in the view, so the
+/// graph and its edge data are never mutated and no reverse edges are
+/// added to it. Flows follow edges from their source to their target, also
+/// in undirected graphs, and loops never carry flow.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::flow::*;
+///
+/// let mut g = Digraph::<u32, Empty, i64>::new();
+/// for i in 1..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, 3);
+/// g.add_edge(2, 3, 4);
+///
+/// let mut residual = Residual::new(&g, |e| e.load());
+/// assert!(residual.push(&1, &2, 2) && residual.push(&2, &3, 2));
+/// assert!(residual.forward(&1, &2) == Some(1) && residual.backward(&1, &2) == Some(2));
+/// // Only two more units fit from 2 to 3.
+/// assert!(!residual.push(&2, &3, 3));
+/// // One more unit fits on the path, and the edge data stays the same.
+/// assert!(residual.max_flow(&1, &3) == 1);
+/// assert!(residual.flow(&1, &2) == Some(3) && g.get_edge(1, 2).unwrap().load() == 3);
+/// ```
+pub struct Residual<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	indexed: Indexed<K, N, E>,
+	network: Network,
+}
+
+impl<K, N, E> Residual<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// View of the graph without flow where the `capacity` closure gives the
+	/// capacity of every edge. Negative capacities count as zero.
+	pub fn new<G, F>(graph: &G, capacity: F) -> Self
+	where
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> i64,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let mut network = Network::empty(indexed.nodes.len());
+		for ((u, w), edge) in ends(&indexed).into_iter().zip(indexed.edges.iter()) {
+			network.add_arc(u, w, capacity(edge).max(0), 0.0);
+		}
+		Residual { indexed, network }
+	}
+
+	/// Flow of the edge from the source to the target.
+	pub fn flow(&self, source: &K, target: &K) -> Option<i64> {
+		self.find(source, target).map(|e| self.network.flow[e])
+	}
+
+	/// Capacity left on the edge from the source to the target.
+	pub fn forward(&self, source: &K, target: &K) -> Option<i64> {
+		self.find(source, target).map(|e| self.network.upper[e] - self.network.flow[e])
+	}
+
+	/// Flow of the edge from the source to the target that can be sent back
+	/// from the target to the source.
+	pub fn backward(&self, source: &K, target: &K) -> Option<i64> {
+		self.flow(source, target)
+	}
+
+	/// Residual arcs with capacity left that leave a node.
+	pub fn arcs(&self, key: &K) -> Vec<ResidualArc<K, N, E>> {
+		let u = match self.indexed.index.get(key) {
+			Some(u) => { *u }
+			None => { return Vec::new() }
+		};
+		self.network
+			.residual(u)
+			.filter(|(_, _, w, _)| *w != u)
+			.map(|(e, forward, _, capacity)| ResidualArc { edge: self.indexed.edges[e].clone(), forward, capacity })
+			.collect()
+	}
+
+	/// Add an amount of flow to the edge from the source to the target, or
+	/// cancel flow if the amount is negative. Returns false and changes
+	/// nothing if there is no such edge or the flow wouldn't fit.
+	pub fn push(&mut self, source: &K, target: &K, amount: i64) -> bool {
+		let e = match self.find(source, target) {
+			Some(e) if source != target => { e }
+			_ => { return false }
+		};
+		match self.network.flow[e].checked_add(amount) {
+			Some(flow) if (0..=self.network.upper[e]).contains(&flow) => {
+				self.network.flow[e] = flow;
+				true
+			}
+			_ => { false }
+		}
+	}
+
+	/// Raise the flow to a maximum flow from the source to the target with
+	/// shortest augmenting paths and return the amount of flow added. Flow
+	/// already in the view is kept, so the view may be augmented further
+	/// after its capacities were pushed against by hand.
+	pub fn max_flow(&mut self, source: &K, target: &K) -> i64 {
+		let (s, t) = match (self.indexed.index.get(source), self.indexed.index.get(target)) {
+			(Some(&s), Some(&t)) if s != t => { (s, t) }
+			_ => { return 0 }
+		};
+		let mut sent: i64 = 0;
+		let _span = trace_span!("max_flow", nodes = self.indexed.nodes.len());
+		while let Some(path) = self.network.shortest_path(s, t) {
+			let amount = self.network.send(&path, i64::MAX);
+			sent = sent.saturating_add(amount);
+			trace_event!("augmenting path", amount = amount, sent = sent);
+		}
+		sent
+	}
+
+	/// Keys of the nodes reachable from a node over arcs with capacity left.
+	/// After a maximum flow from the node they are the source side of a
+	/// minimum cut.
+	pub fn reachable(&self, key: &K) -> Vec<K> {
+		let s = match self.indexed.index.get(key) {
+			Some(s) => { *s }
+			None => { return Vec::new() }
+		};
+		let mut seen = vec![false; self.indexed.nodes.len()];
+		seen[s] = true;
+		let mut stack = vec![s];
+		let mut res = Vec::new();
+		while let Some(u) = stack.pop() {
+			res.push(self.indexed.key(u));
+			for (_, _, w, _) in self.network.residual(u) {
+				if !seen[w] {
+					seen[w] = true;
+					stack.push(w);
+				}
+			}
+		}
+		res
+	}
+
+	/// Every edge paired with its flow.
+	pub fn edges(&self) -> EdgeFlows<K, N, E> {
+		self.indexed.edges.iter().cloned().zip(self.network.flow.iter().copied()).collect()
+	}
+
+	// Index of the edge from the source to the target.
+	fn find(&self, source: &K, target: &K) -> Option<usize> {
+		let u = *self.indexed.index.get(source)?;
+		let w = *self.indexed.index.get(target)?;
+		self.indexed.adjacency[u].iter().find(|(x, _)| *x == w).map(|(_, e)| *e)
+	}
+}
+
+/// # Maximum Flow
+///
+/// Find a maximum flow from the source node to the target node where the
+/// `capacity` closure gives the capacity of every edge, with shortest
+/// augmenting paths over a `Residual` view of the graph. The cost of the
+/// returned flow is zero. Returns None if either node can't be found.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::flow::*;
+///
+/// let mut g = Digraph::<u32, Empty, i64>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, 3);
+/// g.add_edge(1, 3, 2);
+/// g.add_edge(2, 3, 1);
+/// g.add_edge(2, 4, 1);
+/// g.add_edge(3, 4, 3);
+///
+/// let flow = max_flow(&g, &1, &4, |e| e.load()).unwrap();
+/// assert!(flow.value == 4);
+/// assert!(flow.edges.iter().all(|(e, f)| *f <= e.load()));
+/// ```
+pub fn max_flow<K, N, E, G, F>(graph: &G, source: &K, target: &K, capacity: F) -> Option<Flow<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> i64,
+{
+	graph.get_node(source.clone())?;
+	graph.get_node(target.clone())?;
+	let mut residual = Residual::new(graph, capacity);
+	let value = residual.max_flow(source, target);
+	Some(Flow { value, cost: 0.0, edges: residual.edges() })
+}
+
+//=============================================================================
+
+// Flow network whose arcs keep their flow between a lower and an upper
+// bound, so that residual arcs are implied rather than stored: an arc has
+// `upper - flow` capacity left forward and `flow - lower` backward. The arcs
+// of the edges of an indexed graph come first in the order of the edges.
+// `excess` holds the flow that the initial flow brings into every node minus
+// the flow it takes out.
+//
+// A `Residual` is a network with one arc per edge. The minimum cost flows
+// and `disjoint_paths` need arcs that aren't edges of the graph, such as the
+// arcs of the super source and sink, of split nodes and of both directions
+// of undirected edges, so they build a network of their own.
+struct Network {
+	tail: Vec<usize>,
+	head: Vec<usize>,
+	lower: Vec<i64>,
+	upper: Vec<i64>,
+	flow: Vec<i64>,
+	cost: Vec<f64>,
+	outbound: Vec<Vec<usize>>,
+	inbound: Vec<Vec<usize>>,
+	excess: Vec<i64>,
+}
+
+// A residual arc as the arc it follows and whether it follows it forward.
+type Step = (usize, bool);
+
+impl Network {
+	// Build the network of the edges and a super source and sink with every
+	// edge at its lower bound, or at its upper bound if it has a negative
+	// cost, so that no residual arc has a negative cost. Returns None if a
+	// lower bound exceeds its upper bound or the initial flow overflows.
+	fn new<K, N, E>(indexed: &Indexed<K, N, E>, capacities: &[Capacity]) -> Option<Self>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+	{
+		let mut network = Network::empty(indexed.nodes.len() + 2);
+		for ((u, w), c) in ends(indexed).into_iter().zip(capacities.iter()) {
+			if c.lower > c.upper {
+				return None;
+			}
+			c.upper.checked_sub(c.lower)?;
+			let flow = if c.cost < 0.0 { c.upper } else { c.lower };
+			let arc = network.add_arc(u, w, c.upper, c.cost);
+			network.lower[arc] = c.lower;
+			network.flow[arc] = flow;
+			network.excess[w] = network.excess[w].checked_add(flow)?;
+			network.excess[u] = network.excess[u].checked_sub(flow)?;
+		}
+		Some(network)
+	}
+
+	// Network of n nodes without arcs.
+	fn empty(n: usize) -> Self {
+		Network {
+			tail: Vec::new(),
+			head: Vec::new(),
+			lower: Vec::new(),
+			upper: Vec::new(),
+			flow: Vec::new(),
+			cost: Vec::new(),
+			outbound: vec![Vec::new(); n],
+			inbound: vec![Vec::new(); n],
+			excess: vec![0; n],
+		}
+	}
+
+	// Add an arc without flow from u to w.
+	fn add_arc(&mut self, u: usize, w: usize, upper: i64, cost: f64) -> usize {
+		let arc = self.head.len();
+		self.tail.push(u);
+		self.head.push(w);
+		self.lower.push(0);
+		self.upper.push(upper);
+		self.flow.push(0);
+		self.cost.push(cost);
+		self.outbound[u].push(arc);
+		self.inbound[w].push(arc);
+		arc
+	}
+
+	// Residual arcs with capacity left that leave a node, as the arc, whether
+	// it is followed forward, the node it enters and its capacity.
+	fn residual(&self, u: usize) -> impl Iterator<Item = (usize, bool, usize, i64)> + '_ {
+		let forward = self.outbound[u].iter().map(move |a| (*a, true, self.head[*a], self.upper[*a] - self.flow[*a]));
+		let backward = self.inbound[u].iter().map(move |a| (*a, false, self.tail[*a], self.flow[*a] - self.lower[*a]));
+		forward.chain(backward).filter(|(.., capacity)| *capacity > 0)
+	}
+
+	// Cost of a unit of flow along a residual arc.
+	fn step_cost(&self, (a, forward): Step) -> f64 {
+		if forward { self.cost[a] } else { -self.cost[a] }
+	}
+
+	// Route the excess of the initial flow from the super source to the super
+	// sink, which are the last two nodes. Returns false if not all of it can
+	// be routed or the total excess overflows.
+	fn satisfy_bounds(&mut self) -> bool {
+		let n = self.outbound.len();
+		let (source, sink) = (n - 2, n - 1);
+		let mut demand: i64 = 0;
+		for v in 0..n - 2 {
+			let excess = self.excess[v];
+			if excess > 0 {
+				self.add_arc(source, v, excess, 0.0);
+				demand = match demand.checked_add(excess) {
+					Some(demand) => { demand }
+					None => { return false }
+				};
+			} else if excess < 0 {
+				match excess.checked_neg() {
+					Some(deficit) => { self.add_arc(v, sink, deficit, 0.0); }
+					None => { return false }
+				}
+			}
+		}
+		let mut potential = vec![0.0; n];
+		let (routed, _) = self.augment(source, sink, demand, &mut potential);
+		routed == demand
+	}
+
+	// Shortest distances from the source over residual arcs, which may have
+	// negative costs but no negative cycles.
+	fn bellman_ford(&self, source: usize) -> Vec<f64> {
+		let n = self.outbound.len();
+		let mut dist = vec![f64::INFINITY; n];
+		dist[source] = 0.0;
+		for _ in 0..n {
+			let mut changed = false;
+			for u in 0..n {
+				if dist[u] == f64::INFINITY {
+					continue;
+				}
+				for (a, forward, w, _) in self.residual(u) {
+					let d = dist[u] + self.step_cost((a, forward));
+					if d < dist[w] {
+						dist[w] = d;
+						changed = true;
+					}
+				}
+			}
+			if !changed {
+				break;
+			}
+		}
+		dist.iter().map(|d| if d.is_finite() { *d } else { 0.0 }).collect()
+	}
+
+	// Path with the fewest residual arcs from the source to the sink as the
+	// steps from the sink back to the source, or None if there is none.
+	fn shortest_path(&self, source: usize, sink: usize) -> Option<Vec<Step>> {
+		let mut parent: Vec<Option<Step>> = vec![None; self.outbound.len()];
+		let mut queue = VecDeque::from(vec![source]);
+		while let Some(u) = queue.pop_front() {
+			if u == sink {
+				break;
+			}
+			for (a, forward, w, _) in self.residual(u) {
+				if w != source && parent[w].is_none() {
+					parent[w] = Some((a, forward));
+					queue.push_back(w);
+				}
+			}
+		}
+		parent[sink]?;
+		Some(self.path(&parent, sink))
+	}
+
+	// Steps of the path that the parents lead back from a node along.
+	fn path(&self, parent: &[Option<Step>], mut v: usize) -> Vec<Step> {
+		let mut res = Vec::new();
+		while let Some((a, forward)) = parent[v] {
+			res.push((a, forward));
+			v = if forward { self.tail[a] } else { self.head[a] };
+		}
+		res
+	}
+
+	// Send as much flow as fits along a path, up to the limit, and return the
+	// amount sent.
+	fn send(&mut self, path: &[Step], limit: i64) -> i64 {
+		let amount = path
+			.iter()
+			.map(|(a, forward)| if *forward { self.upper[*a] - self.flow[*a] } else { self.flow[*a] - self.lower[*a] })
+			.fold(limit, i64::min);
+		for (a, forward) in path.iter() {
+			self.flow[*a] += if *forward { amount } else { -amount };
+		}
+		amount
+	}
+
+	// Send up to `limit` units of flow along successive cheapest paths and
+	// return the amount sent and its cost.
+	fn augment(&mut self, source: usize, sink: usize, limit: i64, potential: &mut [f64]) -> (i64, f64) {
+		let n = self.outbound.len();
+		let (mut sent, mut total) = (0, 0.0);
+		let _span = trace_span!("augment", nodes = n);
+		while sent < limit {
+			let mut dist = vec![f64::INFINITY; n];
+			let mut parent: Vec<Option<Step>> = vec![None; n];
+			let mut heap = BinaryHeap::new();
+			dist[source] = 0.0;
+			heap.push(MinCost { cost: 0.0, item: source });
+			while let Some(MinCost { cost: d, item: u }) = heap.pop() {
+				if d > dist[u] {
+					continue;
+				}
+				for (a, forward, w, _) in self.residual(u) {
+					let reduced = (self.step_cost((a, forward)) + potential[u] - potential[w]).max(0.0);
+					if d + reduced < dist[w] {
+						dist[w] = d + reduced;
+						parent[w] = Some((a, forward));
+						heap.push(MinCost { cost: d + reduced, item: w });
+					}
+				}
+			}
+			if dist[sink] == f64::INFINITY {
+				break;
+			}
+			for v in 0..n {
+				if dist[v] < f64::INFINITY {
+					potential[v] += dist[v];
+				}
+			}
+			let path = self.path(&parent, sink);
+			let amount = self.send(&path, limit - sent);
+			total += path.iter().map(|step| amount as f64 * self.step_cost(*step)).sum::<f64>();
+			sent += amount;
+			trace_event!("augmenting path", amount = amount, sent = sent, cost = total);
+		}
+		(sent, total)
+	}
+
+	// Flow of every edge and the net flow out of the source.
+	fn flows<K, N, E>(&self, indexed: &Indexed<K, N, E>, capacities: &[Capacity], source: usize) -> Flow<K, N, E>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+	{
+		let mut res = Flow { value: 0, cost: 0.0, edges: Vec::with_capacity(capacities.len()) };
+		for (a, (edge, c)) in indexed.edges.iter().zip(capacities.iter()).enumerate() {
+			let flow = self.flow[a];
+			res.cost += flow as f64 * c.cost;
+			if self.tail[a] == source {
+				res.value = res.value.saturating_add(flow);
+			}
+			if self.head[a] == source {
+				res.value = res.value.saturating_sub(flow);
+			}
+			res.edges.push((edge.clone(), flow));
+		}
+		res
+	}
+}
+
+// Indices of the source and the target of every edge of an indexed graph
+// by the index of the edge.
+fn ends<K, N, E>(indexed: &Indexed<K, N, E>) -> Vec<(usize, usize)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut res = vec![(0, 0); indexed.edges.len()];
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for (w, e) in list.iter() {
+			res[*e] = (u, *w);
+		}
+	}
+	res
+}
+
+//=============================================================================
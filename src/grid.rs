@@ -0,0 +1,420 @@
+//=============================================================================
+// GRIDS
+//=============================================================================
+
+//! Square and hexagonal grid graphs keyed by the coordinates of their
+//! cells, as used for tile maps in games. Coordinates convert from and to
+//! `(i32, i32)` pairs. A cell of a square grid neighbors the four cells next
+//! to it, or eight with the diagonals. Hexagonal grids use axial
+//! coordinates, where every cell has six neighbors and `(x, y)` neighbors
+//! `(x + 1, y - 1)` and `(x - 1, y + 1)` besides the cells next to it on
+//! both axes. Cells that are blocked are left out of the graph, and cells
+//! can be blocked and opened again later. Keys are the coordinates, so the
+//! node of a cell is found with `get_node`, and points of a drawing of the
+//! grid convert to cells and back.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::core::Empty;
+//! use fastgraph::grid::*;
+//!
+//! // A 5 by 5 room with a wall that leaves one gap at the top.
+//! let map: Digraph<Coord, Empty, f64> = grid_graph(5, 5, Neighborhood::Four, |c| match c.x == 2 && c.y > 0 {
+//!     true => { None }
+//!     false => { Some(Empty) }
+//! }, |_, _| 1.0);
+//! assert!(map.node_count() == 21);
+//!
+//! let (cost, _) = map.shortest_path(Coord::new(0, 4), Coord::new(4, 4), |e| e.load()).unwrap();
+//! assert!(cost == 12.0);
+//! ```
+use crate::collections::Graph;
+use crate::layout::Point;
+use crate::shortest_path::MinCost;
+use std::{
+	collections::{BinaryHeap, HashMap, HashSet},
+	fmt::{Debug, Display},
+};
+
+/// Coordinates of a cell, displayed as `(x, y)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Coord {
+	pub x: i32,
+	pub y: i32,
+}
+
+impl Coord {
+	pub const fn new(x: i32, y: i32) -> Self {
+		Coord { x, y }
+	}
+}
+
+impl From<(i32, i32)> for Coord {
+	fn from((x, y): (i32, i32)) -> Self {
+		Coord { x, y }
+	}
+}
+
+impl From<Coord> for (i32, i32) {
+	fn from(c: Coord) -> Self {
+		(c.x, c.y)
+	}
+}
+
+impl Display for Coord {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "({}, {})", self.x, self.y)
+	}
+}
+
+/// Which cells neighbor a cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+	/// Square cells with the cells next to them horizontally and vertically.
+	Four,
+	/// Square cells with the diagonals too.
+	Eight,
+	/// Hexagonal cells in axial coordinates.
+	Hex,
+}
+
+const FOUR: [Coord; 4] = [Coord::new(1, 0), Coord::new(0, 1), Coord::new(-1, 0), Coord::new(0, -1)];
+const EIGHT: [Coord; 8] = [Coord::new(1, 0), Coord::new(1, 1), Coord::new(0, 1), Coord::new(-1, 1), Coord::new(-1, 0), Coord::new(-1, -1), Coord::new(0, -1), Coord::new(1, -1)];
+const HEX: [Coord; 6] = [Coord::new(1, 0), Coord::new(0, 1), Coord::new(-1, 1), Coord::new(-1, 0), Coord::new(0, -1), Coord::new(1, -1)];
+
+impl Neighborhood {
+	/// Offsets from a cell to its neighbors.
+	pub fn offsets(&self) -> &'static [Coord] {
+		match self {
+			Neighborhood::Four => { &FOUR }
+			Neighborhood::Eight => { &EIGHT }
+			Neighborhood::Hex => { &HEX }
+		}
+	}
+
+	/// Coordinates of the neighbors of a cell.
+	pub fn neighbors(&self, cell: Coord) -> Vec<Coord> {
+		self.offsets().iter().map(|d| Coord::new(cell.x + d.x, cell.y + d.y)).collect()
+	}
+
+	/// Least amount of steps between two cells on an open grid, which is
+	/// an admissible A* heuristic when every step costs at least one.
+	pub fn steps(&self, a: Coord, b: Coord) -> i32 {
+		let (dx, dy) = (b.x - a.x, b.y - a.y);
+		match self {
+			Neighborhood::Four => { dx.abs() + dy.abs() }
+			Neighborhood::Eight => { dx.abs().max(dy.abs()) }
+			Neighborhood::Hex => { (dx.abs() + dy.abs() + (dx + dy).abs()) / 2 }
+		}
+	}
+
+	/// Center of a cell in a drawing where neighboring cells are `size`
+	/// apart. Hexagonal cells are drawn pointy side up.
+	pub fn center(&self, cell: Coord, size: f64) -> Point {
+		let (x, y) = (cell.x as f64, cell.y as f64);
+		match self {
+			Neighborhood::Hex => { [size * (x + y / 2.0), size * y * 3f64.sqrt() / 2.0] }
+			_ => { [size * x, size * y] }
+		}
+	}
+
+	/// The cell a point of a drawing falls into, see `center`.
+	pub fn cell(&self, point: Point, size: f64) -> Coord {
+		match self {
+			Neighborhood::Hex => {
+				let y = point[1] / size * 2.0 / 3f64.sqrt();
+				let x = point[0] / size - y / 2.0;
+				// Round in cube coordinates and fix the component that
+				// rounded furthest.
+				let z = -x - y;
+				let (mut rx, mut ry, rz) = (x.round(), y.round(), z.round());
+				let (ex, ey, ez) = ((rx - x).abs(), (ry - y).abs(), (rz - z).abs());
+				if ex > ey && ex > ez {
+					rx = -ry - rz;
+				} else if ey > ez {
+					ry = -rx - rz;
+				}
+				Coord::new(rx as i32, ry as i32)
+			}
+			_ => { Coord::new((point[0] / size).round() as i32, (point[1] / size).round() as i32) }
+		}
+	}
+}
+
+//=============================================================================
+
+/// # Grid Graph
+///
+/// Build a grid of `width` by `height` cells. The `node` closure gives the
+/// data of every cell or None for a blocked cell, which is left out, and the
+/// `edge` closure the data of the edge between two neighboring cells, for
+/// example the cost of the step. Square grids span the cells from `(0, 0)`
+/// to `(width - 1, height - 1)`. Hexagonal grids span `height` rows of
+/// `width` cells, where every other row is shifted by half a cell so that
+/// the grid is a rectangle, and row `y` starts at `x = -(y / 2)`. Directed
+/// graphs get an edge both ways between neighbors.
+pub fn grid_graph<N, E, G, FN, FE>(width: i32, height: i32, neighborhood: Neighborhood, node: FN, edge: FE) -> G
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Coord, N, E>,
+	FN: Fn (Coord) -> Option<N>,
+	FE: Fn (Coord, Coord) -> E,
+{
+	let mut res = G::new();
+	for y in 0..height {
+		let start = match neighborhood {
+			Neighborhood::Hex => { -(y / 2) }
+			_ => { 0 }
+		};
+		for x in start..start + width {
+			if let Some(data) = node(Coord::new(x, y)) {
+				res.add_node(Coord::new(x, y), data);
+			}
+		}
+	}
+	for cell in res.nodes().iter().map(|n| *n.key()).collect::<Vec<_>>() {
+		connect_cell(&mut res, neighborhood, cell, &edge);
+	}
+	res
+}
+
+/// Block a cell by removing its node and edges. Returns false if the cell
+/// isn't in the graph.
+pub fn block<N, E, G>(graph: &mut G, cell: Coord) -> bool
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Coord, N, E>,
+{
+	graph.del_node(cell)
+}
+
+/// Open a blocked cell by adding its node with the given data and
+/// connecting it to the neighbors in the graph, with edge data given by the
+/// `edge` closure. Returns false if the cell is already in the graph.
+pub fn unblock<N, E, G, FE>(graph: &mut G, neighborhood: Neighborhood, cell: Coord, data: N, edge: FE) -> bool
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Coord, N, E>,
+	FE: Fn (Coord, Coord) -> E,
+{
+	if graph.get_node(cell).is_some() {
+		return false;
+	}
+	graph.add_node(cell, data);
+	for other in neighborhood.neighbors(cell) {
+		if graph.get_node(other).is_some() {
+			graph.add_edge(cell, other, edge(cell, other));
+			if G::directed() {
+				graph.add_edge(other, cell, edge(other, cell));
+			}
+		}
+	}
+	true
+}
+
+// Connect a cell to its neighbors in the graph that come after it, so that
+// connecting every cell adds every edge once.
+fn connect_cell<N, E, G, FE>(graph: &mut G, neighborhood: Neighborhood, cell: Coord, edge: &FE)
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Coord, N, E>,
+	FE: Fn (Coord, Coord) -> E,
+{
+	for other in neighborhood.neighbors(cell) {
+		if other > cell && graph.get_node(other).is_some() {
+			graph.add_edge(cell, other, edge(cell, other));
+			if G::directed() {
+				graph.add_edge(other, cell, edge(other, cell));
+			}
+		}
+	}
+}
+
+//=============================================================================
+
+/// # Jump Point Search
+///
+/// Shortest path between two cells of a grid built with
+/// `Neighborhood::Eight`, where straight steps cost one and diagonal steps
+/// the square root of two. Jump point search is A* that skips over the
+/// cells of open areas, jumping in straight and diagonal lines until a cell
+/// where a blocked cell forces a turn, which makes it far faster than A* on
+/// open maps. Only which cells are in the graph is read, so edges and their
+/// data are ignored and blocking cells takes effect at once. Diagonal steps
+/// may pass between two blocked cells like the edges of the grid do.
+/// Returns the cost and the cells along the path from the source to the
+/// target, or None if either cell is blocked or the target can't be
+/// reached.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::grid::*;
+///
+/// let mut map: Ungraph<Coord, Empty, Empty> = grid_graph(50, 50, Neighborhood::Eight, |_| Some(Empty), |_, _| Empty);
+/// for y in 0..49 {
+///     block(&mut map, Coord::new(25, y));
+/// }
+///
+/// // Around the wall through the gap at (25, 49).
+/// let (cost, path) = jump_point_search(&map, Coord::new(0, 0), Coord::new(49, 0)).unwrap();
+/// assert!(path.first() == Some(&Coord::new(0, 0)) && path.last() == Some(&Coord::new(49, 0)));
+/// assert!(path.contains(&Coord::new(25, 49)));
+/// assert!((cost - 49.0 * (1.0 + 2f64.sqrt())).abs() < 1e-9);
+/// ```
+pub fn jump_point_search<N, E, G>(graph: &G, source: Coord, target: Coord) -> Option<(f64, Vec<Coord>)>
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Coord, N, E>,
+{
+	let open = |c: Coord| graph.get_node(c).is_some();
+	if !open(source) || !open(target) {
+		return None;
+	}
+	let mut dist: HashMap<Coord, f64> = HashMap::new();
+	let mut parent: HashMap<Coord, Coord> = HashMap::new();
+	let mut closed: HashSet<Coord> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let _span = trace_span!("jump_point_search");
+	dist.insert(source, 0.0);
+	heap.push(MinCost { cost: octile(source, target), item: source });
+	while let Some(MinCost { item: cell, .. }) = heap.pop() {
+		if !closed.insert(cell) {
+			continue;
+		}
+		let d = dist[&cell];
+		if cell == target {
+			trace_event!("found", cost = d, closed = closed.len());
+			let mut jumps = vec![cell];
+			while let Some(p) = parent.get(jumps.last().unwrap()) {
+				jumps.push(*p);
+			}
+			jumps.reverse();
+			return Some((d, interpolate(&jumps)));
+		}
+		let from = parent.get(&cell).map(|p| Coord::new((cell.x - p.x).signum(), (cell.y - p.y).signum()));
+		for step in successors(&open, cell, from) {
+			let jump = match jump(&open, cell, step, target) {
+				Some(jump) => { jump }
+				None => { continue }
+			};
+			if closed.contains(&jump) {
+				continue;
+			}
+			let c = d + octile(cell, jump);
+			if dist.get(&jump).map_or(true, |old| c < *old) {
+				dist.insert(jump, c);
+				parent.insert(jump, cell);
+				heap.push(MinCost { cost: c + octile(jump, target), item: jump });
+			}
+		}
+	}
+	trace_event!("unreachable", closed = closed.len());
+	None
+}
+
+// Cost of the shortest path between two cells of an open grid with eight
+// neighbors.
+fn octile(a: Coord, b: Coord) -> f64 {
+	let (dx, dy) = ((b.x - a.x).abs(), (b.y - a.y).abs());
+	(dx.max(dy) - dx.min(dy)) as f64 + dx.min(dy) as f64 * 2f64.sqrt()
+}
+
+// Directions to search from a cell arrived at moving in the given
+// direction, the natural ones straight ahead and the forced ones around
+// blocked cells. Every direction is searched from the source.
+fn successors<F>(open: &F, cell: Coord, from: Option<Coord>) -> Vec<Coord>
+where
+	F: Fn(Coord) -> bool,
+{
+	let at = |dx: i32, dy: i32| Coord::new(cell.x + dx, cell.y + dy);
+	let d = match from {
+		Some(d) => { d }
+		None => { return EIGHT.to_vec() }
+	};
+	let mut res = Vec::new();
+	if d.x != 0 && d.y != 0 {
+		res.extend([Coord::new(d.x, 0), Coord::new(0, d.y), d]);
+		if !open(at(-d.x, 0)) {
+			res.push(Coord::new(-d.x, d.y));
+		}
+		if !open(at(0, -d.y)) {
+			res.push(Coord::new(d.x, -d.y));
+		}
+	} else {
+		// Sideways of the direction of the move.
+		let side = Coord::new(d.y, d.x);
+		res.push(d);
+		for s in [1, -1] {
+			if !open(at(side.x * s, side.y * s)) {
+				res.push(Coord::new(d.x + side.x * s, d.y + side.y * s));
+			}
+		}
+	}
+	res
+}
+
+// Move from a cell in a direction until a cell that must be expanded: the
+// target, a cell with a forced neighbor, or for diagonal moves a cell from
+// which a straight move finds one. None once the move runs into a blocked
+// cell.
+fn jump<F>(open: &F, cell: Coord, d: Coord, target: Coord) -> Option<Coord>
+where
+	F: Fn(Coord) -> bool,
+{
+	let mut current = cell;
+	loop {
+		let next = Coord::new(current.x + d.x, current.y + d.y);
+		if !open(next) {
+			return None;
+		}
+		if next == target {
+			return Some(next);
+		}
+		let at = |dx: i32, dy: i32| open(Coord::new(next.x + dx, next.y + dy));
+		if d.x != 0 && d.y != 0 {
+			if (!at(-d.x, 0) && at(-d.x, d.y)) || (!at(0, -d.y) && at(d.x, -d.y)) {
+				return Some(next);
+			}
+			if jump(open, next, Coord::new(d.x, 0), target).is_some() || jump(open, next, Coord::new(0, d.y), target).is_some() {
+				return Some(next);
+			}
+		} else {
+			let side = Coord::new(d.y, d.x);
+			for s in [1, -1] {
+				if !at(side.x * s, side.y * s) && at(d.x + side.x * s, d.y + side.y * s) {
+					return Some(next);
+				}
+			}
+		}
+		current = next;
+	}
+}
+
+// Every cell along straight and diagonal lines between jump points.
+fn interpolate(jumps: &[Coord]) -> Vec<Coord> {
+	let mut res = vec![jumps[0]];
+	for pair in jumps.windows(2) {
+		let (mut c, end) = (pair[0], pair[1]);
+		let d = Coord::new((end.x - c.x).signum(), (end.y - c.y).signum());
+		while c != end {
+			// Diagonal moves come first, the rest of the way is straight.
+			let dx = if c.x != end.x { d.x } else { 0 };
+			let dy = if c.y != end.y { d.y } else { 0 };
+			c = Coord::new(c.x + dx, c.y + dy);
+			res.push(c);
+		}
+	}
+	res
+}
+
+//=============================================================================
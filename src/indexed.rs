@@ -0,0 +1,69 @@
+//=============================================================================
+// INDEXED SNAPSHOT
+//=============================================================================
+
+//! Dense index based snapshot of a graph used internally by algorithms that
+//! visit every node and edge many times. Nodes and edges are numbered so that
+//! per-node and per-edge state can be kept in plain vectors.
+//!
+use crate::core::*;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Snapshot of a set of nodes and the edges between them. The adjacency
+/// list of each node holds pairs of neighbor index and edge index.
+pub(crate) struct Indexed<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	pub nodes: Vec<Arc<Node<K, N, E>>>,
+	pub index: HashMap<K, usize>,
+	pub edges: Vec<Arc<Edge<K, N, E>>>,
+	pub adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+impl<K, N, E> Indexed<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Index the given nodes and the edges between them. Edges are entered
+	/// into the adjacency lists according to the direction.
+	pub fn new(nodes: Vec<Arc<Node<K, N, E>>>, direction: Direction) -> Self {
+		let mut index = HashMap::with_capacity(nodes.len());
+		for (i, node) in nodes.iter().enumerate() {
+			index.insert(node.key().clone(), i);
+		}
+		let mut edges = Vec::new();
+		let mut adjacency = vec![Vec::new(); nodes.len()];
+		for (u, node) in nodes.iter().enumerate() {
+			for edge in node.outbound().iter() {
+				let w = match index.get(edge.target().key()) {
+					Some(w) => *w,
+					None => continue,
+				};
+				let e = edges.len();
+				edges.push(edge.clone());
+				if direction != Direction::Inbound {
+					adjacency[u].push((w, e));
+				}
+				if direction != Direction::Outbound && u != w {
+					adjacency[w].push((u, e));
+				}
+			}
+		}
+		Indexed { nodes, index, edges, adjacency }
+	}
+
+	/// Key of the node at the given index.
+	pub fn key(&self, i: usize) -> K {
+		self.nodes[i].key().clone()
+	}
+}
@@ -0,0 +1,541 @@
+//=============================================================================
+// ARROW AND PARQUET
+//=============================================================================
+
+//! Graphs stored as a pair of Arrow tables, one with a row per node and one
+//! with a row per edge. Rows are mapped into keys and data with closures as
+//! with CSV, but the values keep their column types. Tables can be built from
+//! record batches in memory, Arrow IPC files or, with the `parquet` feature,
+//! Parquet files. For record errors the line is the number of the row in the
+//! table, starting from one.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::io::arrow::*;
+//!
+//! let mut g = Digraph::<u32, String, f64>::new();
+//! g.add_node(1, "a".to_string());
+//! g.add_node(2, "b".to_string());
+//! g.add_edge(1, 2, 0.5);
+//!
+//! let (nodes, edges) = to_batches(
+//!     &g,
+//!     (
+//!         &[("id", DataType::UInt32), ("name", DataType::Utf8)],
+//!         &[("from", DataType::UInt32), ("to", DataType::UInt32), ("weight", DataType::Float64)],
+//!     ),
+//!     |k, n| vec![(*k).into(), n.clone().into()],
+//!     |s, t, e| vec![(*s).into(), (*t).into(), (*e).into()],
+//! ).unwrap();
+//!
+//! let h: Digraph<u32, String, f64> = from_batches(
+//!     &[nodes],
+//!     &[edges],
+//!     |r| Ok((r.get("id")?, r.get("name")?)),
+//!     |r| Ok((r.get("from")?, r.get("to")?, r.get("weight")?)),
+//! ).unwrap();
+//!
+//! assert!(h.get_edge(1, 2).unwrap().load() == 0.5);
+//! ```
+use crate::collections::Graph;
+use crate::io::Error;
+use arrow_array::{
+	builder::{
+		BooleanBuilder, Float32Builder, Float64Builder, Int32Builder, Int64Builder, StringBuilder,
+		UInt32Builder, UInt64Builder,
+	},
+	cast::AsArray,
+	types::*,
+	Array, ArrayRef,
+};
+use arrow_ipc::{reader::FileReader, writer::FileWriter};
+use arrow_schema::{ArrowError, Field, Schema};
+use std::{
+	convert::TryFrom,
+	fmt::{Debug, Display},
+	fs::File,
+	hash::Hash,
+	io::{Read, Seek, Write},
+	path::Path,
+	sync::Arc,
+};
+
+pub use arrow_array::RecordBatch;
+pub use arrow_schema::DataType;
+
+/// Names and types of the node columns and the edge columns of a table pair.
+pub type Columns<'a> = (&'a [(&'a str, DataType)], &'a [(&'a str, DataType)]);
+
+//=============================================================================
+
+/// A single value in a table. Integer columns of every width are read as
+/// `Int` or `UInt`, floating point columns as `Float` and string columns as
+/// `Str`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+	Null,
+	Bool(bool),
+	Int(i64),
+	UInt(u64),
+	Float(f64),
+	Str(String),
+}
+
+macro_rules! value_from {
+	($variant:ident, $as:ty, $($t:ty),*) => {
+		$(impl From<$t> for Value {
+			fn from(value: $t) -> Self {
+				Value::$variant(value as $as)
+			}
+		})*
+	};
+}
+
+value_from!(Int, i64, i8, i16, i32, i64);
+value_from!(UInt, u64, u8, u16, u32, u64, usize);
+value_from!(Float, f64, f32, f64);
+
+impl From<bool> for Value {
+	fn from(value: bool) -> Self {
+		Value::Bool(value)
+	}
+}
+
+impl From<String> for Value {
+	fn from(value: String) -> Self {
+		Value::Str(value)
+	}
+}
+
+impl From<&str> for Value {
+	fn from(value: &str) -> Self {
+		Value::Str(value.to_string())
+	}
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+	fn from(value: Option<T>) -> Self {
+		value.map_or(Value::Null, |v| v.into())
+	}
+}
+
+/// Types that can be read from a table value. Integers convert between
+/// widths when the value fits and into floating point.
+pub trait FromValue: Sized {
+	fn from_value(value: Value) -> Result<Self, String>;
+}
+
+macro_rules! integer_from_value {
+	($($t:ty),*) => {
+		$(impl FromValue for $t {
+			fn from_value(value: Value) -> Result<Self, String> {
+				let res = match value {
+					Value::Int(v) => <$t>::try_from(v).ok(),
+					Value::UInt(v) => <$t>::try_from(v).ok(),
+					_ => return Err(format!("expected an integer, found {:?}", value)),
+				};
+				res.ok_or_else(|| format!("integer out of range for {}", stringify!($t)))
+			}
+		})*
+	};
+}
+
+integer_from_value!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+macro_rules! float_from_value {
+	($($t:ty),*) => {
+		$(impl FromValue for $t {
+			fn from_value(value: Value) -> Result<Self, String> {
+				match value {
+					Value::Float(v) => Ok(v as $t),
+					Value::Int(v) => Ok(v as $t),
+					Value::UInt(v) => Ok(v as $t),
+					_ => Err(format!("expected a number, found {:?}", value)),
+				}
+			}
+		})*
+	};
+}
+
+float_from_value!(f32, f64);
+
+impl FromValue for bool {
+	fn from_value(value: Value) -> Result<Self, String> {
+		match value {
+			Value::Bool(v) => Ok(v),
+			_ => Err(format!("expected a boolean, found {:?}", value)),
+		}
+	}
+}
+
+impl FromValue for String {
+	fn from_value(value: Value) -> Result<Self, String> {
+		match value {
+			Value::Str(v) => Ok(v),
+			_ => Err(format!("expected a string, found {:?}", value)),
+		}
+	}
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+	fn from_value(value: Value) -> Result<Self, String> {
+		match value {
+			Value::Null => Ok(None),
+			value => T::from_value(value).map(Some),
+		}
+	}
+}
+
+//=============================================================================
+
+/// A row of a record batch with access to its values by column name.
+pub struct Record<'a> {
+	batch: &'a RecordBatch,
+	row: usize,
+}
+
+impl<'a> Record<'a> {
+	/// Value in the named column, if the column exists and has a supported
+	/// type.
+	pub fn value(&self, column: &str) -> Option<Value> {
+		read_value(self.batch.column_by_name(column)?, self.row)
+	}
+
+	/// Read the value in the named column. The error describes a missing
+	/// column or a value of the wrong type.
+	pub fn get<T: FromValue>(&self, column: &str) -> Result<T, String> {
+		let value = self.value(column).ok_or(format!("missing column {}", column))?;
+		T::from_value(value).map_err(|message| format!("{} in column {}", message, column))
+	}
+}
+
+//=============================================================================
+
+/// Build a graph from node record batches and edge record batches. Every node
+/// row is mapped into a key and node data, every edge row into the keys of
+/// its endpoints and edge data. Edges between unknown nodes are an error.
+pub fn from_batches<K, N, E, G, FN, FE>(nodes: &[RecordBatch], edges: &[RecordBatch], node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	read_batches(nodes.iter().cloned().map(Ok), edges.iter().cloned().map(Ok), node, edge)
+}
+
+/// Convert a graph into a node record batch and an edge record batch. The
+/// closures map nodes and edges into values matching the given columns.
+pub fn to_batches<K, N, E, G, FN, FE>(graph: &G, columns: Columns, node: FN, edge: FE) -> Result<(RecordBatch, RecordBatch), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn(&K, &N) -> Vec<Value>,
+	FE: Fn(&K, &K, &E) -> Vec<Value>,
+{
+	let mut nb = TableBuilder::new(columns.0)?;
+	let mut eb = TableBuilder::new(columns.1)?;
+	for n in graph.nodes() {
+		nb.push(node(n.key(), &n.load()))?;
+		for e in n.outbound().iter() {
+			eb.push(edge(n.key(), e.target().key(), &e.load()))?;
+		}
+	}
+	Ok((nb.finish()?, eb.finish()?))
+}
+
+/// Read a graph from a node Arrow IPC file stream and an edge Arrow IPC file
+/// stream. See `from_batches`.
+pub fn read_ipc<K, N, E, G, R, FN, FE>(nodes: R, edges: R, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	R: Read + Seek,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	let nodes = FileReader::try_new(nodes, None).map_err(format_error)?;
+	let edges = FileReader::try_new(edges, None).map_err(format_error)?;
+	read_batches(nodes, edges, node, edge)
+}
+
+/// Read a graph from a node Arrow IPC file and an edge Arrow IPC file. See
+/// `from_batches`.
+pub fn load_ipc<K, N, E, G, P, FN, FE>(nodes: P, edges: P, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	read_ipc(File::open(nodes)?, File::open(edges)?, node, edge)
+}
+
+/// Write a graph as a node Arrow IPC file stream and an edge Arrow IPC file
+/// stream. See `to_batches`.
+pub fn write_ipc<K, N, E, G, W, FN, FE>(graph: &G, nodes: W, edges: W, columns: Columns, node: FN, edge: FE) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	W: Write,
+	FN: Fn(&K, &N) -> Vec<Value>,
+	FE: Fn(&K, &K, &E) -> Vec<Value>,
+{
+	let (nb, eb) = to_batches(graph, columns, node, edge)?;
+	for (writer, batch) in [(nodes, nb), (edges, eb)] {
+		let mut writer = FileWriter::try_new(writer, &batch.schema()).map_err(format_error)?;
+		writer.write(&batch).map_err(format_error)?;
+		writer.finish().map_err(format_error)?;
+	}
+	Ok(())
+}
+
+/// Write a graph as a node Arrow IPC file and an edge Arrow IPC file. See
+/// `to_batches`.
+pub fn save_ipc<K, N, E, G, P, FN, FE>(graph: &G, nodes: P, edges: P, columns: Columns, node: FN, edge: FE) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&K, &N) -> Vec<Value>,
+	FE: Fn(&K, &K, &E) -> Vec<Value>,
+{
+	write_ipc(graph, File::create(nodes)?, File::create(edges)?, columns, node, edge)
+}
+
+/// Read a graph from a node Parquet file and an edge Parquet file. See
+/// `from_batches`.
+#[cfg(feature = "parquet")]
+pub fn load_parquet<K, N, E, G, P, FN, FE>(nodes: P, edges: P, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+	let open = |path: P| -> Result<_, Error> {
+		ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+			.and_then(|builder| builder.build())
+			.map_err(|err| Error::Format(err.to_string()))
+	};
+	read_batches(open(nodes)?, open(edges)?, node, edge)
+}
+
+/// Write a graph as a node Parquet file and an edge Parquet file. See
+/// `to_batches`.
+#[cfg(feature = "parquet")]
+pub fn save_parquet<K, N, E, G, P, FN, FE>(graph: &G, nodes: P, edges: P, columns: Columns, node: FN, edge: FE) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&K, &N) -> Vec<Value>,
+	FE: Fn(&K, &K, &E) -> Vec<Value>,
+{
+	use parquet::arrow::ArrowWriter;
+	let (nb, eb) = to_batches(graph, columns, node, edge)?;
+	for (path, batch) in [(nodes, nb), (edges, eb)] {
+		let mut writer = ArrowWriter::try_new(File::create(path)?, batch.schema(), None)
+			.map_err(|err| Error::Format(err.to_string()))?;
+		writer.write(&batch).map_err(|err| Error::Format(err.to_string()))?;
+		writer.close().map_err(|err| Error::Format(err.to_string()))?;
+	}
+	Ok(())
+}
+
+//=============================================================================
+
+// Feed every row of the node batches and then the edge batches to the
+// closures and build the graph.
+fn read_batches<K, N, E, G, IN, IE, FN, FE>(nodes: IN, edges: IE, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	IN: IntoIterator<Item = Result<RecordBatch, ArrowError>>,
+	IE: IntoIterator<Item = Result<RecordBatch, ArrowError>>,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	let mut g = G::new();
+	read_rows(nodes, |line, record| {
+		let (key, data) = node(record).map_err(|message| Error::Record { line, message })?;
+		g.add_node(key, data);
+		Ok(())
+	})?;
+	read_rows(edges, |line, record| {
+		let (source, target, data) = edge(record).map_err(|message| Error::Record { line, message })?;
+		for key in [&source, &target] {
+			if g.get_node(key.clone()).is_none() {
+				return Err(Error::UnknownNode { line, key: key.to_string() });
+			}
+		}
+		g.add_edge(source, target, data);
+		Ok(())
+	})?;
+	Ok(g)
+}
+
+fn read_rows<I, F>(batches: I, mut f: F) -> Result<(), Error>
+where
+	I: IntoIterator<Item = Result<RecordBatch, ArrowError>>,
+	F: FnMut(u64, &Record) -> Result<(), Error>,
+{
+	let mut line = 0;
+	for batch in batches {
+		let batch = batch.map_err(format_error)?;
+		for row in 0..batch.num_rows() {
+			line += 1;
+			f(line, &Record { batch: &batch, row })?;
+		}
+	}
+	Ok(())
+}
+
+fn read_value(array: &ArrayRef, row: usize) -> Option<Value> {
+	if array.is_null(row) {
+		return Some(Value::Null);
+	}
+	let value = match array.data_type() {
+		DataType::Boolean => Value::Bool(array.as_boolean().value(row)),
+		DataType::Int8 => Value::Int(array.as_primitive::<Int8Type>().value(row) as i64),
+		DataType::Int16 => Value::Int(array.as_primitive::<Int16Type>().value(row) as i64),
+		DataType::Int32 => Value::Int(array.as_primitive::<Int32Type>().value(row) as i64),
+		DataType::Int64 => Value::Int(array.as_primitive::<Int64Type>().value(row)),
+		DataType::UInt8 => Value::UInt(array.as_primitive::<UInt8Type>().value(row) as u64),
+		DataType::UInt16 => Value::UInt(array.as_primitive::<UInt16Type>().value(row) as u64),
+		DataType::UInt32 => Value::UInt(array.as_primitive::<UInt32Type>().value(row) as u64),
+		DataType::UInt64 => Value::UInt(array.as_primitive::<UInt64Type>().value(row)),
+		DataType::Float32 => Value::Float(array.as_primitive::<Float32Type>().value(row) as f64),
+		DataType::Float64 => Value::Float(array.as_primitive::<Float64Type>().value(row)),
+		DataType::Utf8 => Value::Str(array.as_string::<i32>().value(row).to_string()),
+		DataType::LargeUtf8 => Value::Str(array.as_string::<i64>().value(row).to_string()),
+		_ => return None,
+	};
+	Some(value)
+}
+
+// Builds a record batch row by row from values.
+struct TableBuilder {
+	schema: Arc<Schema>,
+	columns: Vec<ColumnBuilder>,
+}
+
+enum ColumnBuilder {
+	Bool(BooleanBuilder),
+	Int32(Int32Builder),
+	Int64(Int64Builder),
+	UInt32(UInt32Builder),
+	UInt64(UInt64Builder),
+	Float32(Float32Builder),
+	Float64(Float64Builder),
+	Str(StringBuilder),
+}
+
+impl TableBuilder {
+	fn new(columns: &[(&str, DataType)]) -> Result<Self, Error> {
+		let mut fields = Vec::with_capacity(columns.len());
+		let mut builders = Vec::with_capacity(columns.len());
+		for (name, data_type) in columns {
+			let builder = match data_type {
+				DataType::Boolean => ColumnBuilder::Bool(BooleanBuilder::new()),
+				DataType::Int32 => ColumnBuilder::Int32(Int32Builder::new()),
+				DataType::Int64 => ColumnBuilder::Int64(Int64Builder::new()),
+				DataType::UInt32 => ColumnBuilder::UInt32(UInt32Builder::new()),
+				DataType::UInt64 => ColumnBuilder::UInt64(UInt64Builder::new()),
+				DataType::Float32 => ColumnBuilder::Float32(Float32Builder::new()),
+				DataType::Float64 => ColumnBuilder::Float64(Float64Builder::new()),
+				DataType::Utf8 => ColumnBuilder::Str(StringBuilder::new()),
+				_ => return Err(Error::Format(format!("unsupported type {} for column {}", data_type, name))),
+			};
+			fields.push(Field::new(*name, data_type.clone(), true));
+			builders.push(builder);
+		}
+		Ok(TableBuilder { schema: Arc::new(Schema::new(fields)), columns: builders })
+	}
+
+	fn push(&mut self, row: Vec<Value>) -> Result<(), Error> {
+		if row.len() != self.columns.len() {
+			return Err(Error::Format(format!("expected {} values, found {}", self.columns.len(), row.len())));
+		}
+		for ((column, value), field) in self.columns.iter_mut().zip(row).zip(self.schema.fields()) {
+			column.push(value).map_err(|message| Error::Format(format!("{} in column {}", message, field.name())))?;
+		}
+		Ok(())
+	}
+
+	fn finish(mut self) -> Result<RecordBatch, Error> {
+		let columns = self.columns.iter_mut().map(|c| c.finish()).collect();
+		RecordBatch::try_new(self.schema, columns).map_err(format_error)
+	}
+}
+
+impl ColumnBuilder {
+	fn push(&mut self, value: Value) -> Result<(), String> {
+		if value == Value::Null {
+			match self {
+				ColumnBuilder::Bool(b) => b.append_null(),
+				ColumnBuilder::Int32(b) => b.append_null(),
+				ColumnBuilder::Int64(b) => b.append_null(),
+				ColumnBuilder::UInt32(b) => b.append_null(),
+				ColumnBuilder::UInt64(b) => b.append_null(),
+				ColumnBuilder::Float32(b) => b.append_null(),
+				ColumnBuilder::Float64(b) => b.append_null(),
+				ColumnBuilder::Str(b) => b.append_null(),
+			}
+			return Ok(());
+		}
+		match self {
+			ColumnBuilder::Bool(b) => b.append_value(bool::from_value(value)?),
+			ColumnBuilder::Int32(b) => b.append_value(i32::from_value(value)?),
+			ColumnBuilder::Int64(b) => b.append_value(i64::from_value(value)?),
+			ColumnBuilder::UInt32(b) => b.append_value(u32::from_value(value)?),
+			ColumnBuilder::UInt64(b) => b.append_value(u64::from_value(value)?),
+			ColumnBuilder::Float32(b) => b.append_value(f32::from_value(value)?),
+			ColumnBuilder::Float64(b) => b.append_value(f64::from_value(value)?),
+			ColumnBuilder::Str(b) => b.append_value(String::from_value(value)?),
+		}
+		Ok(())
+	}
+
+	fn finish(&mut self) -> ArrayRef {
+		match self {
+			ColumnBuilder::Bool(b) => Arc::new(b.finish()),
+			ColumnBuilder::Int32(b) => Arc::new(b.finish()),
+			ColumnBuilder::Int64(b) => Arc::new(b.finish()),
+			ColumnBuilder::UInt32(b) => Arc::new(b.finish()),
+			ColumnBuilder::UInt64(b) => Arc::new(b.finish()),
+			ColumnBuilder::Float32(b) => Arc::new(b.finish()),
+			ColumnBuilder::Float64(b) => Arc::new(b.finish()),
+			ColumnBuilder::Str(b) => Arc::new(b.finish()),
+		}
+	}
+}
+
+fn format_error(err: ArrowError) -> Error {
+	Error::Format(err.to_string())
+}
+
+//=============================================================================
@@ -0,0 +1,355 @@
+//=============================================================================
+// BINARY FORMAT
+//=============================================================================
+
+//! Compact binary snapshots of a graph designed for fast loading. A file
+//! starts with the magic bytes `FASTGRPH`, a little endian `u32` format
+//! version and a flags byte, followed by sections. Each section is a tag
+//! byte and a varint length followed by the payload and a little endian
+//! CRC-32 of the payload:
+//!
+//! - keys: node keys in bincode
+//! - nodes: node data in bincode, in the order of the keys
+//! - adjacency: varint node count, the varint degree of every node and the
+//!   varint index of the target of every edge, in compressed sparse row order
+//! - edges: edge data in bincode, in the order of the adjacency
+//!
+//! A zero tag ends the file. Sections with unknown tags are skipped. The
+//! lowest bit of the flags is set for directed graphs, and a snapshot is
+//! only read into a graph of the same kind. Reading fails if any checksum
+//! doesn't match, and `verify` checks a file without building the graph.
+//! Version 1 files have no checksums. They are still read, but corruption
+//! in them can't be detected, so `verify` rejects them.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::io::binary::*;
+//!
+//! let mut g = Digraph::<u32, u32, f64>::new();
+//! g.add_node(1, 10);
+//! g.add_node(2, 20);
+//! g.add_edge(1, 2, 0.5);
+//!
+//! let mut buffer = Vec::new();
+//! write_binary(&g, &mut buffer).unwrap();
+//! let h: Digraph<u32, u32, f64> = read_binary(buffer.as_slice()).unwrap();
+//!
+//! assert!(h.get_edge(1, 2).unwrap().load() == 0.5);
+//! ```
+use crate::core::*;
+use crate::collections::Graph;
+use crate::io::Error;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	fs::File,
+	hash::Hash,
+	io::{BufReader, BufWriter, Read, Write},
+	path::Path,
+	sync::Arc,
+};
+
+/// Magic bytes at the start of every file.
+pub const MAGIC: &[u8; 8] = b"FASTGRPH";
+
+/// Version of the format written by this library.
+pub const VERSION: u32 = 2;
+
+// Last version without section checksums.
+const VERSION_UNCHECKED: u32 = 1;
+
+const FLAG_DIRECTED: u8 = 1;
+
+const SECTION_END: u8 = 0;
+const SECTION_KEYS: u8 = 1;
+const SECTION_NODES: u8 = 2;
+const SECTION_ADJACENCY: u8 = 3;
+const SECTION_EDGES: u8 = 4;
+
+// Payloads of the sections of a file by tag.
+type Sections = HashMap<u8, Vec<u8>>;
+
+//=============================================================================
+
+/// Write a graph as a binary snapshot.
+pub fn write_binary<K, N, E, G, W>(graph: &G, writer: W) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + Serialize,
+	N: Clone + Debug + Display + Sync + Send + Serialize,
+	E: Clone + Debug + Display + Sync + Send + Serialize,
+	G: Graph<K, N, E>,
+	W: Write,
+{
+	let nodes = graph.nodes();
+	let mut index = HashMap::with_capacity(nodes.len());
+	for (i, node) in nodes.iter().enumerate() {
+		index.insert(node.key().clone(), i as u64);
+	}
+	let keys: Vec<K> = nodes.iter().map(|n| n.key().clone()).collect();
+	let data: Vec<N> = nodes.iter().map(|n| n.load()).collect();
+	let mut adjacency = Vec::new();
+	let mut edges = Vec::new();
+	write_varint(&mut adjacency, nodes.len() as u64)?;
+	for node in nodes.iter() {
+		write_varint(&mut adjacency, node.outbound().len() as u64)?;
+	}
+	for node in nodes.iter() {
+		for edge in node.outbound().iter() {
+			write_varint(&mut adjacency, index[edge.target().key()])?;
+			edges.push(edge.load());
+		}
+	}
+	let mut out = BufWriter::new(writer);
+	out.write_all(MAGIC)?;
+	out.write_all(&VERSION.to_le_bytes())?;
+	out.write_all(&[if G::directed() { FLAG_DIRECTED } else { 0 }])?;
+	write_section(&mut out, SECTION_KEYS, &encode(&keys)?)?;
+	write_section(&mut out, SECTION_NODES, &encode(&data)?)?;
+	write_section(&mut out, SECTION_ADJACENCY, &adjacency)?;
+	write_section(&mut out, SECTION_EDGES, &encode(&edges)?)?;
+	out.write_all(&[SECTION_END])?;
+	out.flush()?;
+	Ok(())
+}
+
+/// Read a graph from a binary snapshot.
+pub fn read_binary<K, N, E, G, R>(reader: R) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	N: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	E: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	G: Graph<K, N, E>,
+	R: Read,
+{
+	let (_, flags, mut sections) = read_sections(reader)?;
+	if (flags & FLAG_DIRECTED != 0) != G::directed() {
+		return Err(Error::Format(match G::directed() {
+			true => { "undirected snapshot read into a directed graph".to_string() }
+			false => { "directed snapshot read into an undirected graph".to_string() }
+		}));
+	}
+	let keys: Vec<K> = decode(&take_section(&mut sections, SECTION_KEYS)?)?;
+	let data: Vec<N> = decode(&take_section(&mut sections, SECTION_NODES)?)?;
+	let adjacency = take_section(&mut sections, SECTION_ADJACENCY)?;
+	let edges: Vec<E> = decode(&take_section(&mut sections, SECTION_EDGES)?)?;
+	let mut adjacency = adjacency.as_slice();
+	let n = read_varint(&mut adjacency)? as usize;
+	if keys.len() != n || data.len() != n {
+		return Err(Error::Format("node sections disagree on node count".to_string()));
+	}
+	let mut degrees = Vec::with_capacity(n);
+	for _ in 0..n {
+		degrees.push(read_varint(&mut adjacency)? as usize);
+	}
+	let total = degrees.iter().try_fold(0usize, |total, degree| total.checked_add(*degree));
+	if total != Some(edges.len()) {
+		return Err(Error::Format("edge sections disagree on edge count".to_string()));
+	}
+	let mut g = G::new();
+	let mut nodes: Vec<Arc<Node<K, N, E>>> = Vec::with_capacity(n);
+	for (key, data) in keys.into_iter().zip(data) {
+		if !g.add_node(key.clone(), data) {
+			return Err(Error::Format(format!("duplicate node {}", key)));
+		}
+		nodes.push(g.get_node(key).unwrap());
+	}
+	// Edges are linked directly instead of through the graph, checking that
+	// no source lists a target twice.
+	let mut edges = edges.into_iter();
+	let mut targets = HashSet::new();
+	for (source, degree) in nodes.iter().zip(degrees) {
+		let mut outbound = source.outbound_mut();
+		outbound.reserve(degree);
+		targets.clear();
+		for _ in 0..degree {
+			let t = read_varint(&mut adjacency)? as usize;
+			let target = nodes.get(t).ok_or_else(|| Error::Format(format!("edge to unknown node index {}", t)))?;
+			if !targets.insert(t) {
+				return Err(Error::Format(format!("duplicate edge {} -> {}", source.key(), target.key())));
+			}
+			let edge = Arc::new(Edge::new(source, target, edges.next().unwrap()));
+			target.inbound_mut().push(Arc::downgrade(&edge));
+			outbound.push(edge);
+		}
+	}
+	if !adjacency.is_empty() {
+		return Err(Error::Format(format!("{} bytes left over in adjacency section", adjacency.len())));
+	}
+	Ok(g)
+}
+
+/// Check that a stream is a complete binary snapshot whose sections all
+/// match their checksums, without decoding the graph. Version 1 files have
+/// no checksums to check and are an error.
+pub fn verify<R: Read>(reader: R) -> Result<(), Error> {
+	let (version, _, mut sections) = read_sections(reader)?;
+	if version == VERSION_UNCHECKED {
+		return Err(Error::Format(format!("version {} files have no checksums and can't be verified", version)));
+	}
+	for tag in [SECTION_KEYS, SECTION_NODES, SECTION_ADJACENCY, SECTION_EDGES] {
+		take_section(&mut sections, tag)?;
+	}
+	Ok(())
+}
+
+/// Check a binary snapshot file. See `verify`.
+pub fn verify_file<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+	verify(File::open(path)?)
+}
+
+/// Write a graph as a binary snapshot into a file.
+pub fn save<K, N, E, G, P>(graph: &G, path: P) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + Serialize,
+	N: Clone + Debug + Display + Sync + Send + Serialize,
+	E: Clone + Debug + Display + Sync + Send + Serialize,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+{
+	write_binary(graph, File::create(path)?)
+}
+
+/// Read a graph from a binary snapshot file.
+pub fn load<K, N, E, G, P>(path: P) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	N: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	E: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+{
+	read_binary(File::open(path)?)
+}
+
+//=============================================================================
+
+fn write_section<W: Write>(out: &mut W, tag: u8, payload: &[u8]) -> Result<(), Error> {
+	out.write_all(&[tag])?;
+	write_varint(out, payload.len() as u64)?;
+	out.write_all(payload)?;
+	out.write_all(&crc32(payload).to_le_bytes())?;
+	Ok(())
+}
+
+// Check the header and read the version, the flags and the payloads of all
+// sections by tag.
+fn read_sections<R: Read>(reader: R) -> Result<(u32, u8, Sections), Error> {
+	let mut input = BufReader::new(reader);
+	let mut magic = [0u8; 8];
+	input.read_exact(&mut magic)?;
+	if &magic != MAGIC {
+		return Err(Error::Format("not a fastgraph binary file".to_string()));
+	}
+	let mut version = [0u8; 4];
+	input.read_exact(&mut version)?;
+	let version = u32::from_le_bytes(version);
+	if version != VERSION && version != VERSION_UNCHECKED {
+		return Err(Error::Format(format!("unsupported format version {}", version)));
+	}
+	let mut flags = [0u8; 1];
+	input.read_exact(&mut flags)?;
+	let mut sections = HashMap::new();
+	loop {
+		let mut tag = [0u8; 1];
+		input.read_exact(&mut tag)?;
+		if tag[0] == SECTION_END {
+			break;
+		}
+		// A corrupted length must not allocate up front.
+		let len = read_varint(&mut input)?;
+		let mut payload = Vec::new();
+		(&mut input).take(len).read_to_end(&mut payload)?;
+		if (payload.len() as u64) < len {
+			return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+		}
+		if version != VERSION_UNCHECKED {
+			let mut checksum = [0u8; 4];
+			input.read_exact(&mut checksum)?;
+			if u32::from_le_bytes(checksum) != crc32(&payload) {
+				return Err(Error::Format(format!("checksum mismatch in {} section", section_name(tag[0]))));
+			}
+		}
+		sections.insert(tag[0], payload);
+	}
+	Ok((version, flags[0], sections))
+}
+
+fn take_section(sections: &mut Sections, tag: u8) -> Result<Vec<u8>, Error> {
+	sections.remove(&tag).ok_or_else(|| Error::Format(format!("missing {} section", section_name(tag))))
+}
+
+fn section_name(tag: u8) -> String {
+	match tag {
+		SECTION_KEYS => "keys".to_string(),
+		SECTION_NODES => "nodes".to_string(),
+		SECTION_ADJACENCY => "adjacency".to_string(),
+		SECTION_EDGES => "edges".to_string(),
+		_ => format!("unknown {}", tag),
+	}
+}
+
+// CRC-32 with the IEEE polynomial as used by zlib and PNG.
+fn crc32(bytes: &[u8]) -> u32 {
+	let mut crc = !0u32;
+	for byte in bytes {
+		crc = CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8);
+	}
+	!crc
+}
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+	let mut table = [0u32; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u32;
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 1 == 1 { 0xedb8_8320 ^ (crc >> 1) } else { crc >> 1 };
+			bit += 1;
+		}
+		table[i] = crc;
+		i += 1;
+	}
+	table
+}
+
+// Unsigned LEB128.
+fn write_varint<W: Write>(out: &mut W, mut value: u64) -> Result<(), Error> {
+	loop {
+		let byte = (value & 0x7f) as u8;
+		value >>= 7;
+		if value == 0 {
+			out.write_all(&[byte])?;
+			return Ok(());
+		}
+		out.write_all(&[byte | 0x80])?;
+	}
+}
+
+fn read_varint<R: Read>(input: &mut R) -> Result<u64, Error> {
+	let mut value = 0u64;
+	for shift in (0..64).step_by(7) {
+		let mut byte = [0u8; 1];
+		input.read_exact(&mut byte)?;
+		value |= ((byte[0] & 0x7f) as u64) << shift;
+		if byte[0] & 0x80 == 0 {
+			return Ok(value);
+		}
+	}
+	Err(Error::Format("varint is too long".to_string()))
+}
+
+fn encode<T: Serialize>(data: &T) -> Result<Vec<u8>, Error> {
+	bincode::serialize(data).map_err(|err| Error::Format(err.to_string()))
+}
+
+fn decode<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+	bincode::deserialize(bytes).map_err(|err| Error::Format(err.to_string()))
+}
+
+//=============================================================================
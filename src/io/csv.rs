@@ -0,0 +1,195 @@
+//=============================================================================
+// CSV
+//=============================================================================
+
+//! Graphs stored as a pair of CSV files, one with a record per node and one
+//! with a record per edge. Both files start with a header row. The user maps
+//! records into keys and data with closures, so any schema can be read.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::io::csv::*;
+//!
+//! let nodes = "id,name\n1,a\n2,b\n";
+//! let edges = "from,to,weight\n1,2,0.5\n";
+//!
+//! let g: Digraph<u32, String, f64> = read_csv(
+//!     nodes.as_bytes(),
+//!     edges.as_bytes(),
+//!     |r| Ok((r.parse("id")?, r.parse("name")?)),
+//!     |r| Ok((r.parse("from")?, r.parse("to")?, r.parse("weight")?)),
+//! ).unwrap();
+//!
+//! assert!(g.get_edge(1, 2).unwrap().load() == 0.5);
+//! ```
+use crate::collections::Graph;
+use crate::io::Error;
+use ::csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use std::{
+	fmt::{Debug, Display},
+	fs::File,
+	hash::Hash,
+	io::{Read, Write},
+	path::Path,
+	str::FromStr,
+};
+
+//=============================================================================
+
+/// A CSV record with access to its fields by column name.
+pub struct Record<'a> {
+	headers: &'a StringRecord,
+	record: &'a StringRecord,
+}
+
+impl<'a> Record<'a> {
+	/// Field in the named column, if the column exists.
+	pub fn get(&self, column: &str) -> Option<&'a str> {
+		let i = self.headers.iter().position(|h| h == column)?;
+		self.record.get(i)
+	}
+
+	/// Field at the given position.
+	pub fn field(&self, i: usize) -> Option<&'a str> {
+		self.record.get(i)
+	}
+
+	/// Parse the field in the named column. The error describes a missing
+	/// column or an invalid value.
+	pub fn parse<T: FromStr>(&self, column: &str) -> Result<T, String> {
+		let field = self.get(column).ok_or(format!("missing column {}", column))?;
+		field.parse().map_err(|_| format!("invalid value {:?} in column {}", field, column))
+	}
+}
+
+//=============================================================================
+
+/// Read a graph from a node CSV stream and an edge CSV stream. Every node
+/// record is mapped into a key and node data, every edge record into the
+/// keys of its endpoints and edge data. Edges between unknown nodes are an
+/// error.
+pub fn read_csv<K, N, E, G, RN, RE, FN, FE>(nodes: RN, edges: RE, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	RN: Read,
+	RE: Read,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	let mut g = G::new();
+	read_records(nodes, |line, record| {
+		let (key, data) = node(record).map_err(|message| Error::Record { line, message })?;
+		g.add_node(key, data);
+		Ok(())
+	})?;
+	read_records(edges, |line, record| {
+		let (source, target, data) = edge(record).map_err(|message| Error::Record { line, message })?;
+		for key in [&source, &target] {
+			if g.get_node(key.clone()).is_none() {
+				return Err(Error::UnknownNode { line, key: key.to_string() });
+			}
+		}
+		g.add_edge(source, target, data);
+		Ok(())
+	})?;
+	Ok(g)
+}
+
+/// Read a graph from a node CSV file and an edge CSV file. See `read_csv`.
+pub fn load_csv<K, N, E, G, P, FN, FE>(nodes: P, edges: P, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&Record) -> Result<(K, N), String>,
+	FE: Fn(&Record) -> Result<(K, K, E), String>,
+{
+	read_csv(File::open(nodes)?, File::open(edges)?, node, edge)
+}
+
+/// Write a graph as a node CSV stream and an edge CSV stream. The closures
+/// map nodes and edges into fields matching the given headers.
+pub fn write_csv<K, N, E, G, WN, WE, FN, FE>(
+	graph: &G,
+	nodes: WN,
+	edges: WE,
+	headers: (&[&str], &[&str]),
+	node: FN,
+	edge: FE,
+) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	WN: Write,
+	WE: Write,
+	FN: Fn(&K, &N) -> Vec<String>,
+	FE: Fn(&K, &K, &E) -> Vec<String>,
+{
+	let mut nw = WriterBuilder::new().from_writer(nodes);
+	let mut ew = WriterBuilder::new().from_writer(edges);
+	nw.write_record(headers.0).map_err(format_error)?;
+	ew.write_record(headers.1).map_err(format_error)?;
+	for n in graph.nodes() {
+		nw.write_record(node(n.key(), &n.load())).map_err(format_error)?;
+		for e in n.outbound().iter() {
+			ew.write_record(edge(n.key(), e.target().key(), &e.load())).map_err(format_error)?;
+		}
+	}
+	nw.flush()?;
+	ew.flush()?;
+	Ok(())
+}
+
+/// Write a graph as a node CSV file and an edge CSV file. See `write_csv`.
+pub fn save_csv<K, N, E, G, P, FN, FE>(
+	graph: &G,
+	nodes: P,
+	edges: P,
+	headers: (&[&str], &[&str]),
+	node: FN,
+	edge: FE,
+) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&K, &N) -> Vec<String>,
+	FE: Fn(&K, &K, &E) -> Vec<String>,
+{
+	write_csv(graph, File::create(nodes)?, File::create(edges)?, headers, node, edge)
+}
+
+//=============================================================================
+
+// Feed every record of a CSV stream with its line number to a closure.
+fn read_records<R, F>(input: R, mut f: F) -> Result<(), Error>
+where
+	R: Read,
+	F: FnMut(u64, &Record) -> Result<(), Error>,
+{
+	let mut reader = ReaderBuilder::new().trim(::csv::Trim::All).from_reader(input);
+	let headers = reader.headers().map_err(format_error)?.clone();
+	for result in reader.records() {
+		let record = result.map_err(format_error)?;
+		let line = record.position().map_or(0, |p| p.line());
+		f(line, &Record { headers: &headers, record: &record })?;
+	}
+	Ok(())
+}
+
+fn format_error(err: ::csv::Error) -> Error {
+	Error::Format(err.to_string())
+}
+
+//=============================================================================
@@ -0,0 +1,227 @@
+//=============================================================================
+// JSON NODE-LINK FORMAT
+//=============================================================================
+
+//! Graphs in the JSON node-link format used by D3 and networkx:
+//!
+//! ```json
+//! {
+//!     "directed": true,
+//!     "multigraph": false,
+//!     "graph": {},
+//!     "nodes": [{ "id": 1 }, { "id": 2 }],
+//!     "links": [{ "source": 1, "target": 2 }]
+//! }
+//! ```
+//!
+//! Node and edge data that serializes into an object is flattened into the
+//! node and link objects. Any other data is stored in a `value` field.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::io::json::*;
+//!
+//! let mut g = Digraph::<u32, u32, f64>::new();
+//! g.add_node(1, 10);
+//! g.add_node(2, 20);
+//! g.add_edge(1, 2, 0.5);
+//!
+//! let json = to_node_link(&g).unwrap();
+//! let h: Digraph<u32, u32, f64> = from_node_link(json).unwrap();
+//!
+//! assert!(h.get_edge(1, 2).unwrap().load() == 0.5);
+//! ```
+use crate::collections::Graph;
+use crate::io::Error;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+	fmt::{Debug, Display},
+	fs::File,
+	hash::Hash,
+	io::{BufReader, BufWriter, Read, Write},
+	path::Path,
+};
+
+type Object = Map<String, Value>;
+
+//=============================================================================
+
+/// Convert a graph into a node-link JSON value.
+pub fn to_node_link<K, N, E, G>(graph: &G) -> Result<Value, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + Serialize,
+	N: Clone + Debug + Display + Sync + Send + Serialize,
+	E: Clone + Debug + Display + Sync + Send + Serialize,
+	G: Graph<K, N, E>,
+{
+	let mut nodes = Vec::new();
+	let mut links = Vec::new();
+	for node in graph.nodes() {
+		let mut object = Map::new();
+		object.insert("id".to_string(), to_value(node.key())?);
+		flatten(&mut object, to_value(&node.load())?);
+		nodes.push(Value::Object(object));
+		for edge in node.outbound().iter() {
+			let mut object = Map::new();
+			object.insert("source".to_string(), to_value(node.key())?);
+			object.insert("target".to_string(), to_value(edge.target().key())?);
+			flatten(&mut object, to_value(&edge.load())?);
+			links.push(Value::Object(object));
+		}
+	}
+	Ok(json!({
+		"directed": G::directed(),
+		"multigraph": false,
+		"graph": {},
+		"nodes": nodes,
+		"links": links,
+	}))
+}
+
+/// Build a graph from a node-link JSON value. Links between unknown nodes
+/// are an error.
+pub fn from_node_link<K, N, E, G>(value: Value) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	N: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	E: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	G: Graph<K, N, E>,
+{
+	let mut root = match value {
+		Value::Object(root) => root,
+		_ => return Err(Error::Format("expected an object".to_string())),
+	};
+	let mut g = G::new();
+	for (line, mut object) in entries(&mut root, "nodes")? {
+		let key: K = take(&mut object, "id", line)?;
+		g.add_node(key, from_value(unflatten(object), line)?);
+	}
+	for (line, mut object) in entries(&mut root, "links")? {
+		let source: K = take(&mut object, "source", line)?;
+		let target: K = take(&mut object, "target", line)?;
+		for key in [&source, &target] {
+			if g.get_node(key.clone()).is_none() {
+				return Err(Error::UnknownNode { line, key: key.to_string() });
+			}
+		}
+		g.add_edge(source, target, from_value(unflatten(object), line)?);
+	}
+	Ok(g)
+}
+
+/// Write a graph as node-link JSON.
+pub fn write_json<K, N, E, G, W>(graph: &G, writer: W) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + Serialize,
+	N: Clone + Debug + Display + Sync + Send + Serialize,
+	E: Clone + Debug + Display + Sync + Send + Serialize,
+	G: Graph<K, N, E>,
+	W: Write,
+{
+	let mut writer = BufWriter::new(writer);
+	serde_json::to_writer(&mut writer, &to_node_link(graph)?).map_err(format_error)?;
+	writer.flush()?;
+	Ok(())
+}
+
+/// Read a graph from node-link JSON.
+pub fn read_json<K, N, E, G, R>(reader: R) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	N: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	E: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	G: Graph<K, N, E>,
+	R: Read,
+{
+	from_node_link(serde_json::from_reader(BufReader::new(reader)).map_err(format_error)?)
+}
+
+/// Write a graph as node-link JSON into a file.
+pub fn save_json<K, N, E, G, P>(graph: &G, path: P) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + Serialize,
+	N: Clone + Debug + Display + Sync + Send + Serialize,
+	E: Clone + Debug + Display + Sync + Send + Serialize,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+{
+	write_json(graph, File::create(path)?)
+}
+
+/// Read a graph from a node-link JSON file.
+pub fn load_json<K, N, E, G, P>(path: P) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	N: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	E: Clone + Debug + Display + Sync + Send + DeserializeOwned,
+	G: Graph<K, N, E>,
+	P: AsRef<Path>,
+{
+	read_json(File::open(path)?)
+}
+
+//=============================================================================
+
+// Add the fields of an object value to a node or link object. Other values
+// are stored in a value field and null is left out.
+fn flatten(object: &mut Map<String, Value>, data: Value) {
+	match data {
+		Value::Object(fields) => { object.extend(fields) }
+		Value::Null => {}
+		value => { object.insert("value".to_string(), value); }
+	}
+}
+
+// Reverse of flatten for the fields left after removing the keys.
+fn unflatten(mut object: Map<String, Value>) -> Value {
+	if object.is_empty() {
+		return Value::Null;
+	}
+	if object.len() == 1 {
+		if let Some(value) = object.remove("value") {
+			return value;
+		}
+	}
+	Value::Object(object)
+}
+
+// Entries of an array of objects in the root object, numbered from one.
+fn entries(root: &mut Map<String, Value>, name: &str) -> Result<Vec<(u64, Object)>, Error> {
+	let array = match root.remove(name) {
+		Some(Value::Array(array)) => array,
+		None => Vec::new(),
+		_ => return Err(Error::Format(format!("{} is not an array", name))),
+	};
+	let mut res = Vec::new();
+	for (i, value) in array.into_iter().enumerate() {
+		match value {
+			Value::Object(object) => { res.push((i as u64 + 1, object)) }
+			_ => return Err(Error::Record { line: i as u64 + 1, message: format!("{} entry is not an object", name) }),
+		}
+	}
+	Ok(res)
+}
+
+fn take<T: DeserializeOwned>(object: &mut Map<String, Value>, field: &str, line: u64) -> Result<T, Error> {
+	match object.remove(field) {
+		Some(value) => from_value(value, line),
+		None => Err(Error::Record { line, message: format!("missing field {}", field) }),
+	}
+}
+
+fn to_value<T: Serialize>(data: &T) -> Result<Value, Error> {
+	serde_json::to_value(data).map_err(format_error)
+}
+
+fn from_value<T: DeserializeOwned>(value: Value, line: u64) -> Result<T, Error> {
+	serde_json::from_value(value).map_err(|err| Error::Record { line, message: err.to_string() })
+}
+
+fn format_error(err: serde_json::Error) -> Error {
+	Error::Format(err.to_string())
+}
+
+//=============================================================================
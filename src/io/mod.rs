@@ -0,0 +1,61 @@
+//=============================================================================
+// IMPORT AND EXPORT
+//=============================================================================
+
+//! Reading and writing graphs in external formats. Each format is behind its
+//! own cargo feature.
+//!
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "binary")]
+pub mod binary;
+#[cfg(feature = "neo4j")]
+pub mod neo4j;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "tokio")]
+pub mod stream;
+#[cfg(feature = "rdf")]
+pub mod rdf;
+
+//=============================================================================
+
+/// Error produced when reading or writing a graph.
+#[derive(Debug)]
+pub enum Error {
+	/// Reading or writing the underlying stream failed.
+	Io(std::io::Error),
+	/// The input is not valid in the format being read.
+	Format(String),
+	/// A record could not be mapped into graph data. The line is the line
+	/// of the record in the input.
+	Record { line: u64, message: String },
+	/// An edge refers to a node that does not exist.
+	UnknownNode { line: u64, key: String },
+	/// A database query failed.
+	Database(String),
+}
+
+impl Display for Error {
+	fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::Io(err) => { write!(fmt, "io error: {}", err) }
+			Error::Format(message) => { write!(fmt, "format error: {}", message) }
+			Error::Record { line, message } => { write!(fmt, "line {}: {}", line, message) }
+			Error::UnknownNode { line, key } => { write!(fmt, "line {}: unknown node {}", line, key) }
+			Error::Database(message) => { write!(fmt, "database error: {}", message) }
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+	fn from(err: std::io::Error) -> Self {
+		Error::Io(err)
+	}
+}
@@ -0,0 +1,218 @@
+//=============================================================================
+// NEO4J
+//=============================================================================
+
+//! Pulling subgraphs from a Neo4j database and pushing results back over the
+//! bolt protocol. The user writes one Cypher query returning a row per node
+//! and another returning a row per edge, and maps the rows into keys and data
+//! with closures as with CSV. For record errors the line is the number of the
+//! row in the result, starting from one.
+//!
+//! The functions are async and run on the runtime of the `neo4rs` client.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use fastgraph::collections::*;
+//! use fastgraph::centrality::*;
+//! use fastgraph::io::neo4j::*;
+//!
+//! async fn rank(client: &Client) -> Result<(), fastgraph::io::Error> {
+//!     let g: Digraph<i64, String, f64> = pull(
+//!         client,
+//!         query("MATCH (p:Page) RETURN p.id AS id, p.title AS title"),
+//!         query("MATCH (a:Page)-[l:LINKS]->(b:Page) RETURN a.id AS from, b.id AS to, l.weight AS weight"),
+//!         |row| Ok((row.get("id")?, row.get("title")?)),
+//!         |row| Ok((row.get("from")?, row.get("to")?, row.get("weight")?)),
+//!     ).await?;
+//!
+//!     let scores = pagerank(&g, 0.85, 100, 1e-9);
+//!     let values = scores.iter().map(|(n, s)| (*n.key(), *s));
+//!     push(client, "Page", "id", "pagerank", values).await?;
+//!     Ok(())
+//! }
+//! ```
+use crate::collections::Graph;
+use crate::io::Error;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+pub use neo4rs::{query, BoltList, BoltType, Graph as Client, Query, Row};
+
+/// Number of values sent to the database in one query by `push`.
+pub const BATCH_SIZE: usize = 10_000;
+
+//=============================================================================
+
+/// Build a graph from the rows of a node query and an edge query. Every node
+/// row is mapped into a key and node data, every edge row into the keys of
+/// its endpoints and edge data. Edges between unknown nodes are an error.
+pub async fn pull<K, N, E, G, FN, FE>(client: &Client, nodes: Query, edges: Query, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn(&Row) -> Result<(K, N), neo4rs::DeError>,
+	FE: Fn(&Row) -> Result<(K, K, E), neo4rs::DeError>,
+{
+	let mut g = G::new();
+	let mut rows = client.execute(nodes).await.map_err(database_error)?;
+	let mut line = 0;
+	while let Some(row) = rows.next().await.map_err(database_error)? {
+		line += 1;
+		add_node_row(&mut g, line, &row, &node)?;
+	}
+	let mut rows = client.execute(edges).await.map_err(database_error)?;
+	let mut line = 0;
+	while let Some(row) = rows.next().await.map_err(database_error)? {
+		line += 1;
+		add_edge_row(&mut g, line, &row, &edge)?;
+	}
+	Ok(g)
+}
+
+/// Build a graph from rows already fetched from the database, mapping them
+/// the way `pull` does.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::io::neo4j::*;
+///
+/// let row = |fields: &[&str], values: Vec<BoltType>| {
+///     let fields: Vec<BoltType> = fields.iter().map(|f| BoltType::from(*f)).collect();
+///     Row::new(BoltList::from(fields), BoltList::from(values))
+/// };
+/// let nodes = vec![
+///     row(&["id"], vec![BoltType::from(1i64)]),
+///     row(&["id"], vec![BoltType::from(2i64)]),
+/// ];
+/// let edges = vec![row(&["from", "to"], vec![BoltType::from(1i64), BoltType::from(2i64)])];
+///
+/// let g: Digraph<i64, i64, i64> = from_rows(
+///     nodes,
+///     edges,
+///     |r| Ok((r.get("id")?, 0)),
+///     |r| Ok((r.get("from")?, r.get("to")?, 1)),
+/// ).unwrap();
+/// assert!(g.get_edge(1, 2).is_some());
+/// ```
+pub fn from_rows<K, N, E, G, IN, IE, FN, FE>(nodes: IN, edges: IE, node: FN, edge: FE) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	IN: IntoIterator<Item = Row>,
+	IE: IntoIterator<Item = Row>,
+	FN: Fn(&Row) -> Result<(K, N), neo4rs::DeError>,
+	FE: Fn(&Row) -> Result<(K, K, E), neo4rs::DeError>,
+{
+	let mut g = G::new();
+	for (line, row) in (1..).zip(nodes) {
+		add_node_row(&mut g, line, &row, &node)?;
+	}
+	for (line, row) in (1..).zip(edges) {
+		add_edge_row(&mut g, line, &row, &edge)?;
+	}
+	Ok(g)
+}
+
+/// Set a property on database nodes from pairs of key and value, such as
+/// centrality scores. The nodes are matched by label and the value of the
+/// key property. Values are sent in batches of `BATCH_SIZE`.
+pub async fn push<K, V, I>(client: &Client, label: &str, key: &str, property: &str, values: I) -> Result<(), Error>
+where
+	K: Into<BoltType>,
+	V: Into<BoltType>,
+	I: IntoIterator<Item = (K, V)>,
+{
+	let cypher = push_query(label, key, property);
+	let mut batch: Vec<BoltType> = Vec::with_capacity(BATCH_SIZE);
+	for (k, v) in values {
+		let mut row = HashMap::with_capacity(2);
+		row.insert("key", k.into());
+		row.insert("value", v.into());
+		batch.push(row.into());
+		if batch.len() == BATCH_SIZE {
+			client.run(query(&cypher).param("rows", std::mem::take(&mut batch))).await.map_err(database_error)?;
+		}
+	}
+	if !batch.is_empty() {
+		client.run(query(&cypher).param("rows", batch)).await.map_err(database_error)?;
+	}
+	Ok(())
+}
+
+/// Cypher query run by `push` for every batch. It sets the property on the
+/// nodes with the label whose key property matches the `key` of a row of the
+/// `$rows` parameter to the `value` of the row. Names are quoted, so they
+/// may contain any character.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::io::neo4j::*;
+///
+/// assert!(
+///     push_query("Page", "id", "rank")
+///         == "UNWIND $rows AS row MATCH (n:`Page` {`id`: row.key}) SET n.`rank` = row.value"
+/// );
+/// ```
+pub fn push_query(label: &str, key: &str, property: &str) -> String {
+	format!(
+		"UNWIND $rows AS row MATCH (n:{} {{{}: row.key}}) SET n.{} = row.value",
+		escape(label),
+		escape(key),
+		escape(property),
+	)
+}
+
+//=============================================================================
+
+fn add_node_row<K, N, E, G, F>(g: &mut G, line: u64, row: &Row, node: &F) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Row) -> Result<(K, N), neo4rs::DeError>,
+{
+	let (key, data) = node(row).map_err(|err| Error::Record { line, message: err.to_string() })?;
+	g.add_node(key, data);
+	Ok(())
+}
+
+fn add_edge_row<K, N, E, G, F>(g: &mut G, line: u64, row: &Row, edge: &F) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Row) -> Result<(K, K, E), neo4rs::DeError>,
+{
+	let (source, target, data) = edge(row).map_err(|err| Error::Record { line, message: err.to_string() })?;
+	for key in [&source, &target] {
+		if g.get_node(key.clone()).is_none() {
+			return Err(Error::UnknownNode { line, key: key.to_string() });
+		}
+	}
+	g.add_edge(source, target, data);
+	Ok(())
+}
+
+// Quote a label or property name for Cypher.
+fn escape(name: &str) -> String {
+	format!("`{}`", name.replace('`', "``"))
+}
+
+fn database_error(err: neo4rs::Error) -> Error {
+	Error::Database(err.to_string())
+}
+
+//=============================================================================
@@ -0,0 +1,627 @@
+//=============================================================================
+// RDF
+//=============================================================================
+
+//! Linked data in N-Triples and Turtle. Every triple becomes an edge from
+//! its subject to its object, so subjects and objects are the nodes, keyed
+//! by their terms, and the predicates are edge types. N-Triples is a subset
+//! of Turtle, so both are read by the same parser, which supports prefixes,
+//! base IRIs, predicate and object lists, blank node property lists,
+//! collections and the literal shorthands of Turtle. Relative IRIs are
+//! resolved by appending them to the base. Anonymous blank nodes are
+//! labeled `genid` followed by a number.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::core::Empty;
+//! use fastgraph::io::rdf::*;
+//! use fastgraph::typed::*;
+//!
+//! let turtle = r#"
+//!     @prefix foaf: <http://xmlns.com/foaf/0.1/> .
+//!     @prefix ex: <http://example.org/> .
+//!     ex:ada a foaf:Person ; foaf:name "Ada" ; foaf:knows ex:bob .
+//! "#;
+//!
+//! let g: Digraph<Term, Empty, MultiTyped<Term, Empty>> = read_rdf(
+//!     turtle.as_bytes(),
+//!     |_| Empty,
+//!     |predicates| MultiTyped::new(predicates.to_vec(), Empty),
+//! ).unwrap();
+//!
+//! let ada = Term::Iri("http://example.org/ada".to_string());
+//! let bob = Term::Iri("http://example.org/bob".to_string());
+//! assert!(g.node_count() == 4 && g.edge_count() == 3);
+//! assert!(g.get_edge(ada, bob).unwrap().load().tag() == &Term::Iri("http://xmlns.com/foaf/0.1/knows".to_string()));
+//! ```
+use crate::collections::Graph;
+use crate::io::Error;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display, Formatter},
+	fs::File,
+	hash::Hash,
+	io::Read,
+	path::Path,
+};
+
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const XSD: &str = "http://www.w3.org/2001/XMLSchema#";
+
+//=============================================================================
+
+/// An RDF term, displayed in N-Triples syntax.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+	Iri(String),
+	Blank(String),
+	/// A literal with the IRI of its datatype or its language tag. Simple
+	/// strings have neither.
+	Literal { value: String, datatype: Option<String>, language: Option<String> },
+}
+
+impl Term {
+	/// The IRI of the term, if it is one.
+	pub fn iri(&self) -> Option<&str> {
+		match self {
+			Term::Iri(iri) => { Some(iri) }
+			_ => { None }
+		}
+	}
+
+	/// The value of the term, if it is a literal.
+	pub fn value(&self) -> Option<&str> {
+		match self {
+			Term::Literal { value, .. } => { Some(value) }
+			_ => { None }
+		}
+	}
+
+	fn typed(value: &str, datatype: &str) -> Term {
+		Term::Literal { value: value.to_string(), datatype: Some(format!("{}{}", XSD, datatype)), language: None }
+	}
+}
+
+impl Display for Term {
+	fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Term::Iri(iri) => { write!(fmt, "<{}>", iri) }
+			Term::Blank(label) => { write!(fmt, "_:{}", label) }
+			Term::Literal { value, datatype, language } => {
+				write!(fmt, "\"")?;
+				for c in value.chars() {
+					match c {
+						'"' => { write!(fmt, "\\\"")? }
+						'\\' => { write!(fmt, "\\\\")? }
+						'\n' => { write!(fmt, "\\n")? }
+						'\r' => { write!(fmt, "\\r")? }
+						_ => { write!(fmt, "{}", c)? }
+					}
+				}
+				write!(fmt, "\"")?;
+				match (datatype, language) {
+					(_, Some(language)) => { write!(fmt, "@{}", language) }
+					(Some(datatype), None) => { write!(fmt, "^^<{}>", datatype) }
+					(None, None) => { Ok(()) }
+				}
+			}
+		}
+	}
+}
+
+/// A statement of a subject, a predicate and an object.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Triple {
+	pub subject: Term,
+	pub predicate: Term,
+	pub object: Term,
+}
+
+//=============================================================================
+
+/// Parse every triple of an N-Triples or Turtle stream in order.
+pub fn parse_rdf<R: Read>(mut input: R) -> Result<Vec<Triple>, Error> {
+	let mut text = String::new();
+	input.read_to_string(&mut text)?;
+	let mut parser = Parser {
+		chars: text.chars().collect(),
+		pos: 0,
+		line: 1,
+		base: String::new(),
+		prefixes: HashMap::new(),
+		blanks: 0,
+		triples: Vec::new(),
+	};
+	parser.document()?;
+	Ok(parser.triples)
+}
+
+/// Read a graph from an N-Triples or Turtle stream with an edge from the
+/// subject to the object of every triple. The closures map the terms of the
+/// subjects and objects into node data and the predicates into edge data.
+/// A graph keeps one edge from a subject to an object, so the `edge`
+/// closure gets every distinct predicate of the triples between them in
+/// the order they were read, which `MultiTyped` keeps as the tags of the
+/// edge. In an undirected graph triples in both directions between two
+/// nodes share the edge.
+pub fn read_rdf<N, E, G, R, FN, FE>(input: R, node: FN, edge: FE) -> Result<G, Error>
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Term, N, E>,
+	R: Read,
+	FN: Fn(&Term) -> N,
+	FE: Fn(&[Term]) -> E,
+{
+	let mut g = G::new();
+	let mut pairs: Vec<(Term, Term, Vec<Term>)> = Vec::new();
+	let mut index: HashMap<(Term, Term), usize> = HashMap::new();
+	for triple in parse_rdf(input)? {
+		for term in [&triple.subject, &triple.object] {
+			if g.get_node(term.clone()).is_none() {
+				g.add_node(term.clone(), node(term));
+			}
+		}
+		let key = match G::directed() || triple.subject <= triple.object {
+			true => { (triple.subject.clone(), triple.object.clone()) }
+			false => { (triple.object.clone(), triple.subject.clone()) }
+		};
+		match index.get(&key) {
+			Some(i) => {
+				if !pairs[*i].2.contains(&triple.predicate) {
+					pairs[*i].2.push(triple.predicate);
+				}
+			}
+			None => {
+				index.insert(key, pairs.len());
+				pairs.push((triple.subject, triple.object, vec![triple.predicate]));
+			}
+		}
+	}
+	for (subject, object, predicates) in pairs {
+		let data = edge(&predicates);
+		g.add_edge(subject, object, data);
+	}
+	Ok(g)
+}
+
+/// Read a graph from an N-Triples or Turtle file. See `read_rdf`.
+pub fn load_rdf<N, E, G, P, FN, FE>(path: P, node: FN, edge: FE) -> Result<G, Error>
+where
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<Term, N, E>,
+	P: AsRef<Path>,
+	FN: Fn(&Term) -> N,
+	FE: Fn(&[Term]) -> E,
+{
+	read_rdf(File::open(path)?, node, edge)
+}
+
+//=============================================================================
+
+// Recursive descent parser of Turtle.
+struct Parser {
+	chars: Vec<char>,
+	pos: usize,
+	line: u64,
+	base: String,
+	prefixes: HashMap<String, String>,
+	blanks: usize,
+	triples: Vec<Triple>,
+}
+
+impl Parser {
+	fn peek(&self) -> Option<char> {
+		self.chars.get(self.pos).copied()
+	}
+
+	fn peek_at(&self, offset: usize) -> Option<char> {
+		self.chars.get(self.pos + offset).copied()
+	}
+
+	fn bump(&mut self) -> Option<char> {
+		let c = self.peek()?;
+		self.pos += 1;
+		if c == '\n' {
+			self.line += 1;
+		}
+		Some(c)
+	}
+
+	fn error(&self, message: &str) -> Error {
+		Error::Record { line: self.line, message: message.to_string() }
+	}
+
+	fn expect(&mut self, c: char) -> Result<(), Error> {
+		self.skip();
+		match self.bump() {
+			Some(d) if d == c => { Ok(()) }
+			Some(d) => { Err(self.error(&format!("expected {:?} but found {:?}", c, d))) }
+			None => { Err(self.error(&format!("expected {:?} but found the end", c))) }
+		}
+	}
+
+	// Skip white space and comments.
+	fn skip(&mut self) {
+		while let Some(c) = self.peek() {
+			match c {
+				'#' => {
+					while self.peek().is_some_and(|c| c != '\n') {
+						self.bump();
+					}
+				}
+				_ if c.is_whitespace() => { self.bump(); }
+				_ => { break }
+			}
+		}
+	}
+
+	// True if the input continues with the keyword in any case followed by
+	// white space.
+	fn keyword(&self, keyword: &str) -> bool {
+		let n = keyword.chars().count();
+		keyword.chars().enumerate().all(|(i, k)| self.peek_at(i).is_some_and(|c| c.eq_ignore_ascii_case(&k)))
+			&& self.peek_at(n).is_some_and(|c| c.is_whitespace() || c == '<')
+	}
+
+	fn fresh(&mut self) -> Term {
+		self.blanks += 1;
+		Term::Blank(format!("genid{}", self.blanks))
+	}
+
+	fn push(&mut self, subject: Term, predicate: Term, object: Term) {
+		self.triples.push(Triple { subject, predicate, object });
+	}
+
+	fn document(&mut self) -> Result<(), Error> {
+		loop {
+			self.skip();
+			if self.peek().is_none() {
+				return Ok(());
+			}
+			if self.peek() == Some('@') {
+				self.bump();
+				match (self.keyword("prefix"), self.keyword("base")) {
+					(true, _) => { self.pos += 6; self.prefix()? }
+					(_, true) => { self.pos += 4; self.base()? }
+					_ => { return Err(self.error("unknown directive")) }
+				}
+				self.expect('.')?;
+			} else if self.keyword("prefix") {
+				self.pos += 6;
+				self.prefix()?;
+			} else if self.keyword("base") {
+				self.pos += 4;
+				self.base()?;
+			} else {
+				self.statement()?;
+			}
+		}
+	}
+
+	fn prefix(&mut self) -> Result<(), Error> {
+		self.skip();
+		let mut name = String::new();
+		while let Some(c) = self.peek().filter(|c| *c != ':') {
+			if !name_char(c) {
+				return Err(self.error("invalid prefix"));
+			}
+			name.push(c);
+			self.bump();
+		}
+		self.expect(':')?;
+		self.skip();
+		let iri = self.iri()?;
+		self.prefixes.insert(name, iri);
+		Ok(())
+	}
+
+	fn base(&mut self) -> Result<(), Error> {
+		self.skip();
+		self.base = self.iri()?;
+		Ok(())
+	}
+
+	fn statement(&mut self) -> Result<(), Error> {
+		let subject = match self.peek() {
+			Some('[') => {
+				let subject = self.blank_list()?;
+				self.skip();
+				// A blank node property list may be a statement on its own.
+				if self.peek() == Some('.') {
+					self.bump();
+					return Ok(());
+				}
+				subject
+			}
+			Some('(') => { self.collection()? }
+			_ => { self.subject()? }
+		};
+		self.predicate_objects(&subject)?;
+		self.expect('.')
+	}
+
+	fn subject(&mut self) -> Result<Term, Error> {
+		self.skip();
+		match self.peek() {
+			Some('<') => { Ok(Term::Iri(self.iri()?)) }
+			Some('_') if self.peek_at(1) == Some(':') => { self.blank() }
+			Some(_) => { self.prefixed() }
+			None => { Err(self.error("expected a subject")) }
+		}
+	}
+
+	fn predicate_objects(&mut self, subject: &Term) -> Result<(), Error> {
+		loop {
+			let predicate = self.predicate()?;
+			loop {
+				let object = self.object()?;
+				self.push(subject.clone(), predicate.clone(), object);
+				self.skip();
+				if self.peek() != Some(',') {
+					break;
+				}
+				self.bump();
+			}
+			if self.peek() != Some(';') {
+				return Ok(());
+			}
+			while self.peek() == Some(';') {
+				self.bump();
+				self.skip();
+			}
+			if matches!(self.peek(), Some('.') | Some(']') | None) {
+				return Ok(());
+			}
+		}
+	}
+
+	fn predicate(&mut self) -> Result<Term, Error> {
+		self.skip();
+		match self.peek() {
+			Some('a') if self.peek_at(1).map_or(true, |c| !name_char(c) && c != ':') => {
+				self.bump();
+				Ok(Term::Iri(format!("{}type", RDF)))
+			}
+			Some('<') => { Ok(Term::Iri(self.iri()?)) }
+			Some(_) => { self.prefixed() }
+			None => { Err(self.error("expected a predicate")) }
+		}
+	}
+
+	fn object(&mut self) -> Result<Term, Error> {
+		self.skip();
+		match self.peek() {
+			Some('<') => { Ok(Term::Iri(self.iri()?)) }
+			Some('_') if self.peek_at(1) == Some(':') => { self.blank() }
+			Some('[') => { self.blank_list() }
+			Some('(') => { self.collection() }
+			Some('"') | Some('\'') => { self.literal() }
+			Some(c) if c.is_ascii_digit() || c == '+' || c == '-' || c == '.' => { self.number() }
+			Some(_) if self.keyword_end("true") || self.keyword_end("false") => {
+				let value = if self.peek() == Some('t') { "true" } else { "false" };
+				self.pos += value.len();
+				Ok(Term::typed(value, "boolean"))
+			}
+			Some(_) => { self.prefixed() }
+			None => { Err(self.error("expected an object")) }
+		}
+	}
+
+	// True if the input continues with the word followed by something that
+	// can't continue a name.
+	fn keyword_end(&self, word: &str) -> bool {
+		word.chars().enumerate().all(|(i, w)| self.peek_at(i) == Some(w))
+			&& self.peek_at(word.len()).map_or(true, |c| !name_char(c) && c != ':')
+	}
+
+	fn iri(&mut self) -> Result<String, Error> {
+		self.expect('<')?;
+		let mut iri = String::new();
+		loop {
+			match self.bump() {
+				Some('>') => { break }
+				Some('\\') => { iri.push(self.escape()?) }
+				Some(c) if c.is_whitespace() => { return Err(self.error("white space in an IRI")) }
+				Some(c) => { iri.push(c) }
+				None => { return Err(self.error("unterminated IRI")) }
+			}
+		}
+		// IRIs without a scheme are relative.
+		let scheme = iri.find(':').is_some_and(|i| iri[..i].chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c)) && i > 0);
+		match scheme {
+			true => { Ok(iri) }
+			false => { Ok(format!("{}{}", self.base, iri)) }
+		}
+	}
+
+	fn name(&mut self) -> String {
+		let mut name = String::new();
+		while let Some(c) = self.peek().filter(|c| name_char(*c) || *c == ':' || *c == '%') {
+			name.push(c);
+			self.bump();
+		}
+		// A name can't end with a dot, which ends the statement instead.
+		while name.ends_with('.') {
+			name.pop();
+			self.pos -= 1;
+		}
+		name
+	}
+
+	fn prefixed(&mut self) -> Result<Term, Error> {
+		let name = self.name();
+		let (prefix, local) = match name.find(':') {
+			Some(i) => { (&name[..i], &name[i + 1..]) }
+			None => { return Err(self.error(&format!("expected a term but found {:?}", name))) }
+		};
+		match self.prefixes.get(prefix) {
+			Some(iri) => { Ok(Term::Iri(format!("{}{}", iri, local))) }
+			None => { Err(self.error(&format!("unknown prefix {:?}", prefix))) }
+		}
+	}
+
+	fn blank(&mut self) -> Result<Term, Error> {
+		self.pos += 2;
+		let label = self.name();
+		match label.is_empty() || label.contains(':') {
+			true => { Err(self.error("invalid blank node label")) }
+			false => { Ok(Term::Blank(label)) }
+		}
+	}
+
+	fn blank_list(&mut self) -> Result<Term, Error> {
+		self.expect('[')?;
+		let subject = self.fresh();
+		self.skip();
+		if self.peek() != Some(']') {
+			self.predicate_objects(&subject)?;
+		}
+		self.expect(']')?;
+		Ok(subject)
+	}
+
+	// A collection as a list of rdf:first and rdf:rest.
+	fn collection(&mut self) -> Result<Term, Error> {
+		self.expect('(')?;
+		let nil = Term::Iri(format!("{}nil", RDF));
+		self.skip();
+		if self.peek() == Some(')') {
+			self.bump();
+			return Ok(nil);
+		}
+		let head = self.fresh();
+		let mut node = head.clone();
+		loop {
+			let item = self.object()?;
+			self.push(node.clone(), Term::Iri(format!("{}first", RDF)), item);
+			self.skip();
+			let rest = match self.peek() {
+				Some(')') => { self.bump(); nil.clone() }
+				Some(_) => { self.fresh() }
+				None => { return Err(self.error("unterminated collection")) }
+			};
+			self.push(node, Term::Iri(format!("{}rest", RDF)), rest.clone());
+			if rest == nil {
+				return Ok(head);
+			}
+			node = rest;
+		}
+	}
+
+	fn literal(&mut self) -> Result<Term, Error> {
+		let quote = self.bump().unwrap_or('"');
+		let long = self.peek() == Some(quote) && self.peek_at(1) == Some(quote);
+		if long {
+			self.pos += 2;
+		}
+		let mut value = String::new();
+		loop {
+			match self.bump() {
+				Some(c) if c == quote && !long => { break }
+				Some(c) if c == quote && self.peek() == Some(quote) && self.peek_at(1) == Some(quote) => {
+					self.pos += 2;
+					break;
+				}
+				Some('\\') => { value.push(self.escape()?) }
+				Some('\n') if !long => { return Err(self.error("line break in a string")) }
+				Some(c) => { value.push(c) }
+				None => { return Err(self.error("unterminated string")) }
+			}
+		}
+		match self.peek() {
+			Some('@') => {
+				self.bump();
+				let mut language = String::new();
+				while let Some(c) = self.peek().filter(|c| c.is_ascii_alphanumeric() || *c == '-') {
+					language.push(c);
+					self.bump();
+				}
+				Ok(Term::Literal { value, datatype: None, language: Some(language) })
+			}
+			Some('^') if self.peek_at(1) == Some('^') => {
+				self.pos += 2;
+				let datatype = match self.peek() {
+					Some('<') => { self.iri()? }
+					_ => {
+						match self.prefixed()? {
+							Term::Iri(iri) => { iri }
+							_ => { return Err(self.error("invalid datatype")) }
+						}
+					}
+				};
+				Ok(Term::Literal { value, datatype: Some(datatype), language: None })
+			}
+			_ => { Ok(Term::Literal { value, datatype: None, language: None }) }
+		}
+	}
+
+	// The character of an escape sequence after the backslash.
+	fn escape(&mut self) -> Result<char, Error> {
+		let (digits, c) = match self.bump() {
+			Some('u') => { (4, None) }
+			Some('U') => { (8, None) }
+			Some('t') => { (0, Some('\t')) }
+			Some('b') => { (0, Some('\u{8}')) }
+			Some('n') => { (0, Some('\n')) }
+			Some('r') => { (0, Some('\r')) }
+			Some('f') => { (0, Some('\u{c}')) }
+			Some(c) if "\"'\\".contains(c) => { (0, Some(c)) }
+			_ => { return Err(self.error("invalid escape sequence")) }
+		};
+		if let Some(c) = c {
+			return Ok(c);
+		}
+		let hex: String = (0..digits).filter_map(|_| self.bump()).collect();
+		u32::from_str_radix(&hex, 16)
+			.ok()
+			.and_then(char::from_u32)
+			.ok_or_else(|| self.error("invalid unicode escape"))
+	}
+
+	fn number(&mut self) -> Result<Term, Error> {
+		let mut value = String::new();
+		let mut datatype = "integer";
+		if let Some(c) = self.peek().filter(|c| *c == '+' || *c == '-') {
+			value.push(c);
+			self.bump();
+		}
+		let digits = |p: &mut Parser, value: &mut String| {
+			while let Some(c) = p.peek().filter(|c| c.is_ascii_digit()) {
+				value.push(c);
+				p.bump();
+			}
+		};
+		digits(self, &mut value);
+		// A dot is only part of the number if a digit follows.
+		if self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+			value.push('.');
+			self.bump();
+			digits(self, &mut value);
+			datatype = "decimal";
+		}
+		if let Some(e) = self.peek().filter(|c| *c == 'e' || *c == 'E') {
+			value.push(e);
+			self.bump();
+			if let Some(c) = self.peek().filter(|c| *c == '+' || *c == '-') {
+				value.push(c);
+				self.bump();
+			}
+			digits(self, &mut value);
+			datatype = "double";
+		}
+		match value.chars().any(|c| c.is_ascii_digit()) {
+			true => { Ok(Term::typed(&value, datatype)) }
+			false => { Err(self.error("invalid number")) }
+		}
+	}
+}
+
+fn name_char(c: char) -> bool {
+	c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '\u{b7}'
+}
+
+//=============================================================================
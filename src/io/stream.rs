@@ -0,0 +1,154 @@
+//=============================================================================
+// ASYNC STREAMS
+//=============================================================================
+
+//! Building graphs incrementally from asynchronous sources such as network
+//! feeds or async file IO. The source is a stream of elements, each a node or
+//! an edge, and the graph is built while the elements arrive. Elements are
+//! pulled from the stream one at a time so a producer is never ahead of the
+//! graph by more than its own buffer. An edge must arrive after both of its
+//! endpoints. For errors the line is the number of the element in the
+//! stream, starting from one.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::io::stream::*;
+//!
+//! let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+//! let g: Digraph<u32, u32, f64> = rt.block_on(async {
+//!     let (sender, loader) = spawn(1024);
+//!     sender.send(Element::Node(1, 10)).await.unwrap();
+//!     sender.send(Element::Node(2, 20)).await.unwrap();
+//!     sender.send(Element::Edge(1, 2, 0.5)).await.unwrap();
+//!     drop(sender);
+//!     loader.await.unwrap()
+//! }).unwrap();
+//!
+//! assert!(g.get_edge(1, 2).unwrap().load() == 0.5);
+//! ```
+use crate::collections::Graph;
+use crate::io::Error;
+use futures_util::stream::{Stream, StreamExt};
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+use tokio::{
+	io::{AsyncBufRead, AsyncBufReadExt},
+	sync::mpsc,
+	task::JoinHandle,
+};
+
+/// A node with its key and data or an edge with the keys of its endpoints
+/// and its data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Element<K, N, E> {
+	Node(K, N),
+	Edge(K, K, E),
+}
+
+/// Sending half of the channel of a spawned loader.
+pub type Sender<K, N, E> = mpsc::Sender<Element<K, N, E>>;
+
+/// Task of a spawned loader finishing with the graph.
+pub type Loader<G> = JoinHandle<Result<G, Error>>;
+
+//=============================================================================
+
+/// Build a graph from a stream of elements. Stops at the first error in the
+/// stream or at the first edge between unknown nodes.
+pub async fn from_stream<K, N, E, G, S>(stream: S) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	S: Stream<Item = Result<Element<K, N, E>, Error>>,
+{
+	let mut g = G::new();
+	let mut stream = Box::pin(stream);
+	let mut line = 0;
+	while let Some(element) = stream.next().await {
+		line += 1;
+		insert(&mut g, line, element?)?;
+	}
+	Ok(g)
+}
+
+/// Build a graph from the lines of an asynchronous reader. Every line is
+/// mapped into an element. Empty lines are skipped.
+pub async fn from_lines<K, N, E, G, R, F>(reader: R, parse: F) -> Result<G, Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	R: AsyncBufRead + Unpin,
+	F: Fn(&str) -> Result<Element<K, N, E>, String>,
+{
+	let mut g = G::new();
+	let mut lines = reader.lines();
+	let mut line = 0;
+	while let Some(text) = lines.next_line().await? {
+		line += 1;
+		if text.trim().is_empty() {
+			continue;
+		}
+		let element = parse(&text).map_err(|message| Error::Record { line, message })?;
+		insert(&mut g, line, element)?;
+	}
+	Ok(g)
+}
+
+/// Spawn a task on the current tokio runtime that builds a graph from the
+/// elements sent into the returned channel. Sending waits while the channel
+/// holds `capacity` elements the task hasn't inserted yet. The task finishes
+/// with the graph once every sender has been dropped.
+pub fn spawn<K, N, E, G>(capacity: usize) -> (Sender<K, N, E>, Loader<G>)
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send + 'static,
+	N: Clone + Debug + Display + Sync + Send + 'static,
+	E: Clone + Debug + Display + Sync + Send + 'static,
+	G: Graph<K, N, E> + Send + 'static,
+{
+	let (sender, mut receiver) = mpsc::channel(capacity);
+	let handle = tokio::spawn(async move {
+		let mut g = G::new();
+		let mut line = 0;
+		while let Some(element) = receiver.recv().await {
+			line += 1;
+			insert(&mut g, line, element)?;
+		}
+		Ok(g)
+	});
+	(sender, handle)
+}
+
+//=============================================================================
+
+fn insert<K, N, E, G>(g: &mut G, line: u64, element: Element<K, N, E>) -> Result<(), Error>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	match element {
+		Element::Node(key, data) => {
+			g.add_node(key, data);
+		}
+		Element::Edge(source, target, data) => {
+			for key in [&source, &target] {
+				if g.get_node(key.clone()).is_none() {
+					return Err(Error::UnknownNode { line, key: key.to_string() });
+				}
+			}
+			g.add_edge(source, target, data);
+		}
+	}
+	Ok(())
+}
+
+//=============================================================================
@@ -0,0 +1,295 @@
+//=============================================================================
+// CANONICAL LABELING
+//=============================================================================
+
+//! Canonical labeling of graphs for isomorphism checks. Nodes are colored by
+//! their data, the coloring is refined by the colors of the neighbors until
+//! it is stable, and remaining ties are broken by trying every node of the
+//! first tied color class in turn. Of all the complete orderings reached, the
+//! one with the smallest certificate is canonical. Two graphs are isomorphic
+//! exactly when their certificates are equal, so certificates can be hashed
+//! to deduplicate large collections of small graphs. Branches that a known
+//! automorphism maps onto an explored branch are skipped, but the worst case
+//! is still exponential, so the labeling is meant for small graphs.
+//!
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+/// Certificate of a graph in its canonical ordering. Nodes are the colors of
+/// the nodes by position and edges the positions of the endpoints with the
+/// color of the edge, sorted. Endpoints of undirected edges are in ascending
+/// order.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Certificate<CN, CE> {
+	pub directed: bool,
+	pub nodes: Vec<CN>,
+	pub edges: Vec<(usize, usize, CE)>,
+}
+
+/// A canonical ordering of the keys of a graph and its certificate.
+#[derive(Clone, Debug)]
+pub struct Canonical<K, CN, CE> {
+	pub order: Vec<K>,
+	pub certificate: Certificate<CN, CE>,
+}
+
+// Neighbors of a node with the rank of the edge color and whether the edge
+// is inbound.
+type Adjacency = Vec<Vec<(usize, usize, bool)>>;
+
+// Color of a node with the sorted inbound flags, edge color ranks and colors
+// of its neighbors.
+type Signature = (usize, Vec<(bool, usize, usize)>);
+
+//=============================================================================
+
+/// # Canonical Form
+///
+/// Canonical labeling using the node and edge data as colors. See
+/// `canonical_form_by`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::isomorphism::*;
+///
+/// let mut a = Ungraph::<u32, char, u32>::new();
+/// let mut b = Ungraph::<u32, char, u32>::new();
+/// for (key, atom) in [(1, 'C'), (2, 'O'), (3, 'H')] {
+///     a.add_node(key, atom);
+///     b.add_node(key + 10, atom);
+/// }
+/// a.add_edge(1, 2, 2);
+/// a.add_edge(1, 3, 1);
+/// b.add_edge(13, 11, 1);
+/// b.add_edge(12, 11, 2);
+///
+/// assert!(canonical_form(&a).certificate == canonical_form(&b).certificate);
+/// ```
+pub fn canonical_form<K, N, E, G>(graph: &G) -> Canonical<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send + Ord,
+	E: Clone + Debug + Display + Sync + Send + Ord,
+	G: Graph<K, N, E>,
+{
+	canonical_form_by(graph, |n: &N| n.clone(), |e: &E| e.clone())
+}
+
+/// # Canonical Form By
+///
+/// Canonical labeling with node and edge colors computed from their data.
+/// Graphs whose colors are left out, for example with `|_| ()`, are labeled
+/// by structure alone.
+pub fn canonical_form_by<K, N, E, G, CN, CE, FN, FE>(graph: &G, node_color: FN, edge_color: FE) -> Canonical<K, CN, CE>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	CN: Clone + Ord,
+	CE: Clone + Ord,
+	FN: Fn(&N) -> CN,
+	FE: Fn(&E) -> CE,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let directed = G::directed();
+	let node_colors: Vec<CN> = indexed.nodes.iter().map(|n| node_color(&n.load())).collect();
+	let edge_colors: Vec<CE> = indexed.edges.iter().map(|e| edge_color(&e.load())).collect();
+	let edge_ranks = rank(&edge_colors);
+	let mut adjacency: Adjacency = vec![Vec::new(); indexed.nodes.len()];
+	let mut edges = Vec::with_capacity(indexed.edges.len());
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for (w, e) in list.iter() {
+			adjacency[u].push((*w, edge_ranks[*e], false));
+			if directed || u != *w {
+				adjacency[*w].push((u, edge_ranks[*e], directed));
+			}
+			edges.push((u, *w, *e));
+		}
+	}
+	let search = Search { adjacency, edges, directed, node_colors: &node_colors, edge_colors: &edge_colors };
+	let empty = Certificate { directed, nodes: Vec::new(), edges: Vec::new() };
+	let (certificate, positions) = search.run(rank(&node_colors)).unwrap_or((empty, Vec::new()));
+	let mut order = vec![None; positions.len()];
+	for (v, p) in positions.iter().enumerate() {
+		order[*p] = Some(indexed.key(v));
+	}
+	Canonical { order: order.into_iter().flatten().collect(), certificate }
+}
+
+/// True if the graphs are isomorphic including their node and edge data.
+pub fn is_isomorphic<K, N, E, G, H>(a: &G, b: &H) -> bool
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send + Ord,
+	E: Clone + Debug + Display + Sync + Send + Ord,
+	G: Graph<K, N, E>,
+	H: Graph<K, N, E>,
+{
+	a.node_count() == b.node_count()
+	&& a.edge_count() == b.edge_count()
+	&& canonical_form(a).certificate == canonical_form(b).certificate
+}
+
+//=============================================================================
+
+struct Search<'a, CN, CE> {
+	adjacency: Adjacency,
+	edges: Vec<(usize, usize, usize)>,
+	directed: bool,
+	node_colors: &'a [CN],
+	edge_colors: &'a [CE],
+}
+
+// Best certificate found so far with the positions of the nodes that led to
+// it, and the automorphisms found by reaching the same certificate again.
+struct State<CN, CE> {
+	best: Option<(Certificate<CN, CE>, Vec<usize>)>,
+	automorphisms: Vec<Vec<usize>>,
+}
+
+impl<'a, CN: Clone + Ord, CE: Clone + Ord> Search<'a, CN, CE> {
+	fn run(&self, colors: Vec<usize>) -> Option<(Certificate<CN, CE>, Vec<usize>)> {
+		let mut state = State { best: None, automorphisms: Vec::new() };
+		self.branch(colors, &mut Vec::new(), &mut state);
+		state.best
+	}
+
+	// Refine the coloring and either record the certificate of a discrete
+	// coloring or branch on every node of the first color class with more
+	// than one node. Nodes that an automorphism fixing the path maps onto an
+	// already explored node lead to the same certificates and are skipped.
+	fn branch(&self, colors: Vec<usize>, path: &mut Vec<usize>, state: &mut State<CN, CE>) {
+		let colors = self.refine(colors);
+		let n = colors.len();
+		let mut sizes = vec![0; n];
+		for c in colors.iter() {
+			sizes[*c] += 1;
+		}
+		let cell = match sizes.iter().position(|s| *s > 1) {
+			Some(cell) => cell,
+			None => {
+				self.leaf(colors, state);
+				return;
+			}
+		};
+		let mut explored: Vec<usize> = Vec::new();
+		for v in (0..n).filter(|v| colors[*v] == cell) {
+			if !explored.is_empty() && self.same_orbit(v, &explored, path, &state.automorphisms) {
+				continue;
+			}
+			explored.push(v);
+			// The chosen node is ordered first within its class.
+			let split: Vec<(usize, bool)> = (0..n).map(|u| (colors[u], u != v)).collect();
+			path.push(v);
+			self.branch(rank(&split), path, state);
+			path.pop();
+		}
+	}
+
+	fn leaf(&self, positions: Vec<usize>, state: &mut State<CN, CE>) {
+		let certificate = self.certificate(&positions);
+		match &state.best {
+			Some((best, best_positions)) if certificate == *best => {
+				// Map every node onto the node at the same position in the
+				// best ordering.
+				let mut at = vec![0; positions.len()];
+				for (v, p) in best_positions.iter().enumerate() {
+					at[*p] = v;
+				}
+				state.automorphisms.push(positions.iter().map(|p| at[*p]).collect());
+			}
+			Some((best, _)) if certificate > *best => {}
+			_ => { state.best = Some((certificate, positions)) }
+		}
+	}
+
+	// True if an automorphism that fixes every node on the path maps the
+	// node onto one of the explored nodes.
+	fn same_orbit(&self, v: usize, explored: &[usize], path: &[usize], automorphisms: &[Vec<usize>]) -> bool {
+		let mut parent: Vec<usize> = (0..self.adjacency.len()).collect();
+		fn find(parent: &mut [usize], mut x: usize) -> usize {
+			while parent[x] != x {
+				parent[x] = parent[parent[x]];
+				x = parent[x];
+			}
+			x
+		}
+		for gamma in automorphisms.iter().filter(|g| path.iter().all(|p| g[*p] == *p)) {
+			for (x, y) in gamma.iter().enumerate() {
+				let (a, b) = (find(&mut parent, x), find(&mut parent, *y));
+				parent[a] = b;
+			}
+		}
+		let root = find(&mut parent, v);
+		explored.iter().any(|u| find(&mut parent, *u) == root)
+	}
+
+	// Replace every color by the rank of the color together with the sorted
+	// colors of the neighbors until the amount of colors stops growing.
+	fn refine(&self, mut colors: Vec<usize>) -> Vec<usize> {
+		let mut count = distinct(&colors);
+		loop {
+			let signatures: Vec<Signature> = self.adjacency
+				.iter()
+				.enumerate()
+				.map(|(v, list)| {
+					let mut neighbors: Vec<_> = list.iter().map(|(w, e, inbound)| (*inbound, *e, colors[*w])).collect();
+					neighbors.sort_unstable();
+					(colors[v], neighbors)
+				})
+				.collect();
+			colors = rank(&signatures);
+			let new_count = distinct(&colors);
+			if new_count == count {
+				return colors;
+			}
+			count = new_count;
+		}
+	}
+
+	fn certificate(&self, positions: &[usize]) -> Certificate<CN, CE> {
+		let mut nodes = vec![None; positions.len()];
+		for (v, p) in positions.iter().enumerate() {
+			nodes[*p] = Some(self.node_colors[v].clone());
+		}
+		let mut edges: Vec<(usize, usize, CE)> = self.edges
+			.iter()
+			.map(|(u, w, e)| {
+				let (a, b) = (positions[*u], positions[*w]);
+				let (a, b) = if self.directed { (a, b) } else { (a.min(b), a.max(b)) };
+				(a, b, self.edge_colors[*e].clone())
+			})
+			.collect();
+		edges.sort();
+		Certificate { directed: self.directed, nodes: nodes.into_iter().flatten().collect(), edges }
+	}
+}
+
+// Dense ranks of the items in sorted order.
+fn rank<T: Ord>(items: &[T]) -> Vec<usize> {
+	let mut order: Vec<usize> = (0..items.len()).collect();
+	order.sort_by(|a, b| items[*a].cmp(&items[*b]));
+	let mut res = vec![0; items.len()];
+	let mut r = 0;
+	for i in 0..order.len() {
+		if i > 0 && items[order[i]] != items[order[i - 1]] {
+			r += 1;
+		}
+		res[order[i]] = r;
+	}
+	res
+}
+
+fn distinct(colors: &[usize]) -> usize {
+	colors.iter().max().map_or(0, |c| c + 1)
+}
+
+//=============================================================================
@@ -0,0 +1,185 @@
+//=============================================================================
+// WEISFEILER-LEHMAN
+//=============================================================================
+
+//! Weisfeiler-Lehman refinement of node labels. Every iteration replaces the
+//! label of a node by a hash of its label together with the labels of its
+//! neighbors and the edges to them, so that after k iterations a label
+//! describes the neighborhood of k hops around the node. Unlike the ranks of
+//! the canonical labeling the labels are hashes, which makes them comparable
+//! across graphs, and the hash function is fixed, so labels computed in
+//! different runs or builds of the program can be stored and compared too.
+//! The counts of the labels over all iterations are the
+//! features of the Weisfeiler-Lehman subtree kernel, and a hash of them is a
+//! hash of the graph. Isomorphic graphs always have the same hash, so graphs
+//! with different hashes can be ruled out before an exact isomorphism check,
+//! but some graphs that aren't isomorphic, such as regular graphs of the same
+//! degree and size, can't be told apart.
+//!
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use crate::sketch::hash;
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+// Neighbors of a node with the label of the edge and whether it's inbound.
+type Adjacency = Vec<Vec<(usize, u64, bool)>>;
+
+//=============================================================================
+
+/// # Weisfeiler-Lehman
+///
+/// The labels of the nodes of a graph in every iteration of the refinement.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::kernel::*;
+///
+/// // A path and a star on four nodes, with the path keyed differently.
+/// let mut path = Ungraph::<u32, Empty, Empty>::new();
+/// let mut other = Ungraph::<u32, Empty, Empty>::new();
+/// let mut star = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..4 {
+///     path.add_node(i, Empty);
+///     other.add_node(i, Empty);
+///     star.add_node(i, Empty);
+/// }
+/// for i in 0..3 {
+///     path.add_edge(i, i + 1, Empty);
+///     other.add_edge(3 - i, 2 - i, Empty);
+///     star.add_edge(0, i + 1, Empty);
+/// }
+///
+/// let (a, b, c) = (WeisfeilerLehman::new(&path, 2), WeisfeilerLehman::new(&other, 2), WeisfeilerLehman::new(&star, 2));
+/// assert!(a.hash() == b.hash() && a.hash() != c.hash());
+/// // The ends of the path look the same.
+/// assert!(a.label(&0) == a.label(&3) && a.label(&0) != a.label(&1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct WeisfeilerLehman<K> {
+	/// The labels of every node by iteration, starting with the initial
+	/// labels.
+	pub labels: Vec<HashMap<K, u64>>,
+}
+
+impl<K> WeisfeilerLehman<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Refine the labels for the amount of iterations by structure alone,
+	/// starting with the same label for every node.
+	pub fn new<N, E, G>(graph: &G, iterations: usize) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		Self::with_labels(graph, iterations, |_| (), |_| ())
+	}
+
+	/// Refine the labels for the amount of iterations, starting with labels
+	/// computed from the data of the nodes and including labels computed
+	/// from the data of the edges.
+	pub fn with_labels<N, E, G, LN, LE, FN, FE>(graph: &G, iterations: usize, node: FN, edge: FE) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		LN: Hash,
+		LE: Hash,
+		FN: Fn (&N) -> LN,
+		FE: Fn (&E) -> LE,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let directed = G::directed();
+		let mut adjacency: Adjacency = vec![Vec::new(); indexed.nodes.len()];
+		for (u, list) in indexed.adjacency.iter().enumerate() {
+			for (w, e) in list.iter() {
+				let label = hash(&edge(&indexed.edges[*e].load()));
+				adjacency[u].push((*w, label, false));
+				if directed || u != *w {
+					adjacency[*w].push((u, label, directed));
+				}
+			}
+		}
+		let mut current: Vec<u64> = indexed.nodes.iter().map(|n| hash(&node(&n.load()))).collect();
+		let keyed = |current: &[u64]| (0..current.len()).map(|v| (indexed.key(v), current[v])).collect();
+		let mut labels = vec![keyed(&current)];
+		let _span = trace_span!("weisfeiler_lehman", nodes = current.len(), iterations = iterations);
+		for _ in 0..iterations {
+			current = adjacency
+				.par_iter()
+				.enumerate()
+				.map(|(v, list)| {
+					let mut neighbors: Vec<(bool, u64, u64)> = list.iter().map(|(w, e, inbound)| (*inbound, *e, current[*w])).collect();
+					neighbors.sort_unstable();
+					hash(&(current[v], neighbors))
+				})
+				.collect();
+			labels.push(keyed(&current));
+		}
+		WeisfeilerLehman { labels }
+	}
+
+	/// The label of a node after the last iteration.
+	pub fn label(&self, key: &K) -> Option<u64> {
+		self.labels.last().and_then(|l| l.get(key).copied())
+	}
+
+	/// Amount of nodes with every label over all iterations. The dot product
+	/// of the features of two graphs is their Weisfeiler-Lehman subtree
+	/// kernel, see `kernel`.
+	pub fn features(&self) -> HashMap<u64, usize> {
+		let mut res = HashMap::new();
+		for l in self.labels.iter() {
+			for label in l.values() {
+				*res.entry(*label).or_insert(0) += 1;
+			}
+		}
+		res
+	}
+
+	/// The features folded into a vector of a fixed length by the label
+	/// modulo the length, for pipelines that expect vectors of the same
+	/// length for every graph. Labels that collide are summed.
+	pub fn feature_vector(&self, length: usize) -> Vec<f64> {
+		let mut res = vec![0.0; length];
+		if length == 0 {
+			return res;
+		}
+		for (label, count) in self.features() {
+			res[(label % length as u64) as usize] += count as f64;
+		}
+		res
+	}
+
+	/// Hash of the graph. Graphs refined for the same amount of iterations
+	/// have the same hash if they are isomorphic.
+	pub fn hash(&self) -> u64 {
+		let mut features: Vec<(u64, usize)> = self.features().into_iter().collect();
+		features.sort_unstable();
+		hash(&(self.labels.len(), features))
+	}
+
+	/// The Weisfeiler-Lehman subtree kernel of this graph and another, the
+	/// amount of pairs of nodes with equal labels in the same iteration.
+	pub fn kernel<L>(&self, other: &WeisfeilerLehman<L>) -> f64
+	where
+		L: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	{
+		let (a, b) = (self.features(), other.features());
+		a.iter()
+			.filter_map(|(label, x)| b.get(label).map(|y| (*x * *y) as f64))
+			.sum()
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,344 @@
+//=============================================================================
+// LANDMARKS
+//=============================================================================
+
+//! Landmark distance oracle for repeated shortest path queries over a static
+//! graph, also known as ALT. A few landmark nodes are picked and the
+//! distances from every node to each landmark and back are stored. By the
+//! triangle inequality these give lower and upper bounds of the distance
+//! between any two nodes without searching the graph. The lower bound guides
+//! A* towards the target and the upper bound is an approximate distance.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::shortest_path::{astar, MinCost};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::{BinaryHeap, HashMap},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// Distances between every node of a graph and a set of landmarks.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::landmarks::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..10 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..9 {
+///     g.add_edge(i, i + 1, 1.0);
+/// }
+///
+/// let landmarks = Landmarks::new(&g, 2, |edge| edge.load());
+/// assert!(landmarks.lower_bound(&2, &7) == 5.0);
+/// let mut keys = landmarks.landmarks().to_vec();
+/// keys.sort();
+/// assert!(keys == [0, 9]);
+/// assert!(landmarks.upper_bound(&2, &7) == Some(9.0));
+///
+/// let (cost, path) = landmarks.shortest_path(&g, 2, 7, |edge| edge.load()).unwrap();
+/// assert!(cost == 5.0 && path.len() == 5);
+/// ```
+pub struct Landmarks<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	index: HashMap<K, usize>,
+	keys: Vec<K>,
+	// Distances from each landmark to every node.
+	from: Vec<Vec<f64>>,
+	// Distances from every node to each landmark, empty in undirected graphs
+	// where they equal the distances from the landmarks.
+	to: Vec<Vec<f64>>,
+	revision: Option<u64>,
+}
+
+impl<K> Landmarks<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Pick `count` landmarks and compute their distances with edge costs
+	/// given by the `cost` closure. Costs must be non-negative. Landmarks are
+	/// picked one at a time as the node farthest from the landmarks picked so
+	/// far, which spreads them to the edges of the graph and gives every
+	/// component a landmark before any gets a second one.
+	pub fn new<N, E, G, F>(graph: &G, count: usize, cost: F) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let search = Search::new(graph, cost);
+		let n = search.forward.nodes.len();
+		let mut landmarks = Landmarks::empty(graph, &search.forward);
+		if n == 0 || count == 0 {
+			return landmarks;
+		}
+		// The first landmark is the node farthest from an arbitrary node.
+		let mut nearest = vec![f64::INFINITY; n];
+		let (from, to) = search.distances(0);
+		approach(&mut nearest, &from, to.as_deref());
+		let mut next = farthest(&nearest);
+		// The distance from every node to its nearest landmark.
+		let mut nearest = vec![f64::INFINITY; n];
+		while landmarks.keys.len() < count {
+			let (from, to) = search.distances(next);
+			approach(&mut nearest, &from, to.as_deref());
+			landmarks.push(search.forward.key(next), from, to);
+			next = farthest(&nearest);
+			// Every node is a landmark or as close as one.
+			if nearest[next] == 0.0 {
+				break;
+			}
+		}
+		landmarks
+	}
+
+	/// Compute the distances of the given landmarks, in parallel. Keys that
+	/// can't be found are skipped.
+	pub fn with_landmarks<N, E, G, F>(graph: &G, keys: &[K], cost: F) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let search = Search::new(graph, cost);
+		let mut landmarks = Landmarks::empty(graph, &search.forward);
+		let picked: Vec<usize> = keys.iter().filter_map(|k| search.forward.index.get(k).copied()).collect();
+		let distances: Vec<_> = picked.clone().into_par_iter().map(|l| search.distances(l)).collect();
+		for (l, (from, to)) in picked.into_iter().zip(distances) {
+			landmarks.push(search.forward.key(l), from, to);
+		}
+		landmarks
+	}
+
+	/// Keys of the landmarks.
+	pub fn landmarks(&self) -> &[K] {
+		&self.keys
+	}
+
+	/// True if the graph hasn't changed since the distances were computed.
+	/// Bounds computed from stale distances may be wrong.
+	pub fn is_fresh<N, E, G>(&self, graph: &G) -> bool
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		self.revision.is_some() && graph.revision() == self.revision
+	}
+
+	/// Lower bound of the distance from the source to the target. Infinite
+	/// if a landmark shows that the target can't be reached and zero for
+	/// keys that can't be found.
+	pub fn lower_bound(&self, source: &K, target: &K) -> f64 {
+		match (self.index.get(source), self.index.get(target)) {
+			(Some(&s), Some(&t)) => { self.bound(s, t) }
+			_ => { 0.0 }
+		}
+	}
+
+	/// Upper bound of the distance from the source to the target through the
+	/// best landmark, which is an approximation of the distance. None if no
+	/// landmark is reachable from the source and reaches the target.
+	pub fn upper_bound(&self, source: &K, target: &K) -> Option<f64> {
+		let s = *self.index.get(source)?;
+		let t = *self.index.get(target)?;
+		(0..self.keys.len())
+			.map(|l| self.to(l)[s] + self.from[l][t])
+			.filter(|d| d.is_finite())
+			.reduce(f64::min)
+	}
+
+	/// Shortest path between two nodes of the graph with A* guided by the
+	/// lower bounds. The costs must be the ones the landmarks were computed
+	/// with. Returns None if either node can't be found or the target can't
+	/// be reached.
+	pub fn shortest_path<N, E, G, F>(&self, graph: &G, source: K, target: K, cost: F) -> Option<(f64, Path<K, N, E>)>
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let s = graph.get_node(source)?;
+		let t = graph.get_node(target.clone())?;
+		let heuristic = match self.index.get(&target) {
+			Some(&t) => {
+				Box::new(move |node: &Arc<Node<K, N, E>>| {
+					self.index.get(node.key()).map_or(0.0, |&v| self.bound(v, t))
+				}) as Box<dyn Fn(&Arc<Node<K, N, E>>) -> f64 + '_>
+			}
+			None => { Box::new(|_: &Arc<Node<K, N, E>>| 0.0) }
+		};
+		astar(&s, &t, G::direction(), cost, heuristic)
+	}
+
+	fn empty<N, E, G>(graph: &G, indexed: &Indexed<K, N, E>) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		Landmarks {
+			index: indexed.index.clone(),
+			keys: Vec::new(),
+			from: Vec::new(),
+			to: Vec::new(),
+			revision: graph.revision(),
+		}
+	}
+
+	fn push(&mut self, key: K, from: Vec<f64>, to: Option<Vec<f64>>) {
+		self.keys.push(key);
+		self.from.push(from);
+		if let Some(to) = to {
+			self.to.push(to);
+		}
+	}
+
+	fn to(&self, l: usize) -> &[f64] {
+		match self.to.is_empty() {
+			true => { &self.from[l] }
+			false => { &self.to[l] }
+		}
+	}
+
+	// Best lower bound of the distance from s to t over the landmarks, from
+	// d(l, t) <= d(l, s) + d(s, t) and d(s, l) <= d(s, t) + d(t, l).
+	fn bound(&self, s: usize, t: usize) -> f64 {
+		let mut res: f64 = 0.0;
+		for l in 0..self.keys.len() {
+			let (from, to) = (&self.from[l], self.to(l));
+			if from[s].is_finite() {
+				res = res.max(from[t] - from[s]);
+			}
+			if to[t].is_finite() {
+				res = res.max(to[s] - to[t]);
+			}
+		}
+		res
+	}
+}
+
+// Lower the distances to the nearest landmark with the distances from and to
+// a new landmark.
+fn approach(nearest: &mut [f64], from: &[f64], to: Option<&[f64]>) {
+	for v in 0..nearest.len() {
+		nearest[v] = nearest[v].min(from[v]);
+		if let Some(to) = to {
+			nearest[v] = nearest[v].min(to[v]);
+		}
+	}
+}
+
+// Index of the largest finite distance, or of the first infinite one, which
+// belongs to a node no landmark reaches.
+fn farthest(distances: &[f64]) -> usize {
+	if let Some(v) = distances.iter().position(|d| d.is_infinite()) {
+		return v;
+	}
+	let mut res = 0;
+	for (v, d) in distances.iter().enumerate() {
+		if *d > distances[res] {
+			res = v;
+		}
+	}
+	res
+}
+
+//=============================================================================
+
+// Snapshot of a graph with edge costs for computing distances from and to a
+// landmark.
+struct Search<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	forward: Indexed<K, N, E>,
+	backward: Option<Vec<Vec<(usize, usize)>>>,
+	costs: Vec<f64>,
+}
+
+impl<K, N, E> Search<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn new<G, F>(graph: &G, cost: F) -> Self
+	where
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let forward = Indexed::new(graph.nodes(), G::direction());
+		let costs = forward.edges.iter().map(cost).collect();
+		// Reverse adjacency with the same edge numbering as the forward one.
+		let backward = match G::directed() {
+			true => {
+				let mut backward = vec![Vec::new(); forward.nodes.len()];
+				for (u, adjacent) in forward.adjacency.iter().enumerate() {
+					for &(w, e) in adjacent.iter() {
+						backward[w].push((u, e));
+					}
+				}
+				Some(backward)
+			}
+			false => { None }
+		};
+		Search { forward, backward, costs }
+	}
+
+	// Distances from and to a node. Distances to the node are only computed
+	// in directed graphs.
+	fn distances(&self, source: usize) -> (Vec<f64>, Option<Vec<f64>>) {
+		match &self.backward {
+			Some(backward) => {
+				let (from, to) = rayon::join(
+					|| dijkstra(&self.forward.adjacency, &self.costs, source),
+					|| dijkstra(backward, &self.costs, source),
+				);
+				(from, Some(to))
+			}
+			None => { (dijkstra(&self.forward.adjacency, &self.costs, source), None) }
+		}
+	}
+}
+
+// Distances from a source over an adjacency list with edge costs.
+fn dijkstra(adjacency: &[Vec<(usize, usize)>], costs: &[f64], source: usize) -> Vec<f64> {
+	let mut dist = vec![f64::INFINITY; adjacency.len()];
+	let mut heap = BinaryHeap::new();
+	dist[source] = 0.0;
+	heap.push(MinCost { cost: 0.0, item: source });
+	while let Some(MinCost { cost: d, item: v }) = heap.pop() {
+		if d > dist[v] {
+			continue;
+		}
+		for &(w, e) in adjacency[v].iter() {
+			let c = d + costs[e];
+			if c < dist[w] {
+				dist[w] = c;
+				heap.push(MinCost { cost: c, item: w });
+			}
+		}
+	}
+	dist
+}
+
+//=============================================================================
@@ -0,0 +1,293 @@
+//=============================================================================
+// LAYOUT
+//=============================================================================
+
+//! Graph drawing coordinates. Layouts read the positions they start from
+//! and write the positions they find through `Positions`, so positions can
+//! be kept in a side map keyed by node or inside the node data itself with
+//! `NodeData`, which reads and writes them with closures. A layout started
+//! from stored positions refines them, which keeps drawings stable when the
+//! graph changes between runs. Nodes without a position are placed next to
+//! their placed neighbors, or at random if there are none.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::layout::*;
+//! use std::fmt;
+//!
+//! #[derive(Clone, Debug)]
+//! struct City {
+//!     xy: Option<Point>,
+//! }
+//!
+//! impl fmt::Display for City {
+//!     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         write!(f, "{:?}", self.xy)
+//!     }
+//! }
+//!
+//! let mut g = Ungraph::<u32, City, f64>::new();
+//! for i in 0..3 {
+//!     g.add_node(i, City { xy: None });
+//! }
+//! g.add_edge(0, 1, 1.0);
+//! g.add_edge(1, 2, 1.0);
+//!
+//! let mut positions = NodeData::new(|c: &City| c.xy, |c: &mut City, xy| c.xy = Some(xy));
+//! ForceLayout::default().run(&g, &mut positions);
+//! assert!(g.nodes().iter().all(|n| n.load().xy.is_some()));
+//!
+//! // Add a node and refine the previous drawing.
+//! g.add_node(3, City { xy: None });
+//! g.add_edge(2, 3, 1.0);
+//! let refine = ForceLayout { temperature: 0.1, ..ForceLayout::default() };
+//! refine.run(&g, &mut positions);
+//! ```
+use crate::core::*;
+use crate::centrality::stable_order;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	marker::PhantomData,
+	sync::Arc,
+};
+
+/// Coordinates of a node in the plane.
+pub type Point = [f64; 2];
+
+/// Storage of the positions of the nodes of a graph.
+pub trait Positions<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Position of a node, None if it has none yet.
+	fn get(&self, node: &Arc<Node<K, N, E>>) -> Option<Point>;
+
+	/// Store the position of a node.
+	fn set(&mut self, node: &Arc<Node<K, N, E>>, point: Point);
+}
+
+impl<K, N, E> Positions<K, N, E> for HashMap<K, Point>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn get(&self, node: &Arc<Node<K, N, E>>) -> Option<Point> {
+		HashMap::get(self, node.key()).cloned()
+	}
+
+	fn set(&mut self, node: &Arc<Node<K, N, E>>, point: Point) {
+		self.insert(node.key().clone(), point);
+	}
+}
+
+/// Positions kept inside the node data, read by the `read` closure and
+/// written by the `write` closure.
+pub struct NodeData<N, R, W> {
+	read: R,
+	write: W,
+	data: PhantomData<N>,
+}
+
+impl<N, R, W> NodeData<N, R, W>
+where
+	R: Fn(&N) -> Option<Point>,
+	W: Fn(&mut N, Point),
+{
+	pub fn new(read: R, write: W) -> Self {
+		NodeData { read, write, data: PhantomData }
+	}
+}
+
+impl<K, N, E, R, W> Positions<K, N, E> for NodeData<N, R, W>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	R: Fn(&N) -> Option<Point>,
+	W: Fn(&mut N, Point),
+{
+	fn get(&self, node: &Arc<Node<K, N, E>>) -> Option<Point> {
+		(self.read)(&node.load())
+	}
+
+	fn set(&mut self, node: &Arc<Node<K, N, E>>, point: Point) {
+		let mut data = node.load();
+		(self.write)(&mut data, point);
+		node.store(data);
+	}
+}
+
+//=============================================================================
+
+/// # Force Layout
+///
+/// Force directed layout in the style of Fruchterman and Reingold. Every
+/// pair of nodes repels and the endpoints of every edge attract, so that
+/// neighbors end up about `distance` apart. In every iteration a node
+/// moves at most the current temperature, which starts at `temperature`
+/// times `distance` and cools down to zero. A low temperature refines
+/// stored positions without moving nodes far. Placing nodes without a
+/// position is reproducible for a given seed. Edge direction is ignored.
+#[derive(Clone, Debug)]
+pub struct ForceLayout {
+	pub iterations: usize,
+	pub distance: f64,
+	pub temperature: f64,
+	pub seed: u64,
+}
+
+impl Default for ForceLayout {
+	fn default() -> Self {
+		ForceLayout {
+			iterations: 100,
+			distance: 1.0,
+			temperature: 1.0,
+			seed: 0,
+		}
+	}
+}
+
+impl ForceLayout {
+	/// Lay out the nodes of the graph starting from their stored positions
+	/// and store the positions found. Repulsion between every pair of nodes
+	/// makes an iteration quadratic in the amount of nodes, and the nodes
+	/// are moved in parallel.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::Empty;
+	/// use fastgraph::layout::*;
+	/// use std::collections::HashMap;
+	///
+	/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+	/// for i in 0..4 {
+	///     g.add_node(i, Empty);
+	/// }
+	/// g.add_edge(0, 1, Empty);
+	/// g.add_edge(1, 2, Empty);
+	/// g.add_edge(2, 3, Empty);
+	///
+	/// let mut positions: HashMap<u32, Point> = HashMap::new();
+	/// ForceLayout::default().run(&g, &mut positions);
+	/// assert!(positions.len() == 4);
+	/// ```
+	pub fn run<K, N, E, G, P>(&self, graph: &G, positions: &mut P)
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		P: Positions<K, N, E>,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Both);
+		let n = indexed.nodes.len();
+		let adjacency: Vec<Vec<usize>> = indexed.adjacency
+			.iter()
+			.enumerate()
+			.map(|(v, adjacent)| adjacent.iter().map(|(w, _)| *w).filter(|w| *w != v).collect())
+			.collect();
+		let mut points = self.place(&indexed, &adjacency, positions);
+		let k = self.distance;
+		for i in 0..self.iterations {
+			let limit = self.temperature * k * (1.0 - i as f64 / self.iterations as f64);
+			let current = &points;
+			let moves: Vec<Point> = (0..n)
+				.into_par_iter()
+				.map(|v| {
+					let mut force = [0.0, 0.0];
+					for w in 0..n {
+						if w == v {
+							continue;
+						}
+						let (delta, d) = separation(current, v, w);
+						for c in 0..2 {
+							force[c] += delta[c] / d * k * k / d;
+						}
+					}
+					for w in adjacency[v].iter() {
+						let (delta, d) = separation(current, v, *w);
+						for c in 0..2 {
+							force[c] -= delta[c] / d * d * d / k;
+						}
+					}
+					let length = force[0].hypot(force[1]);
+					let step = length.min(limit);
+					match length > 0.0 {
+						true => { [force[0] / length * step, force[1] / length * step] }
+						false => { [0.0, 0.0] }
+					}
+				})
+				.collect();
+			for (point, m) in points.iter_mut().zip(moves) {
+				point[0] += m[0];
+				point[1] += m[1];
+			}
+		}
+		for (v, point) in points.into_iter().enumerate() {
+			positions.set(&indexed.nodes[v], point);
+		}
+	}
+
+	// Starting position of every node, the stored one if there is one.
+	// Other nodes are placed in a stable order near the mean of their
+	// placed neighbors, or at random in a square that fits every node.
+	fn place<K, N, E, P>(&self, indexed: &Indexed<K, N, E>, adjacency: &[Vec<usize>], positions: &P) -> Vec<Point>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		P: Positions<K, N, E>,
+	{
+		let n = indexed.nodes.len();
+		let mut rng = StdRng::seed_from_u64(self.seed);
+		let side = (n as f64).sqrt() * self.distance;
+		let mut points: Vec<Option<Point>> = indexed.nodes.iter().map(|node| positions.get(node)).collect();
+		for v in stable_order(indexed) {
+			if points[v].is_some() {
+				continue;
+			}
+			let placed: Vec<Point> = adjacency[v].iter().filter_map(|w| points[*w]).collect();
+			let jitter = [rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5)];
+			points[v] = Some(match placed.is_empty() {
+				true => { [jitter[0] * side, jitter[1] * side] }
+				false => {
+					let count = placed.len() as f64;
+					let x = placed.iter().map(|p| p[0]).sum::<f64>() / count;
+					let y = placed.iter().map(|p| p[1]).sum::<f64>() / count;
+					[x + jitter[0] * self.distance, y + jitter[1] * self.distance]
+				}
+			});
+		}
+		points.into_iter().map(|p| p.unwrap()).collect()
+	}
+}
+
+// Vector from one node to another and its length. Nodes at the same point
+// are pushed apart in a direction given by their indices.
+fn separation(points: &[Point], v: usize, w: usize) -> (Point, f64) {
+	let delta = [points[v][0] - points[w][0], points[v][1] - points[w][1]];
+	let d = delta[0].hypot(delta[1]);
+	match d > 1e-9 {
+		true => { (delta, d) }
+		false => {
+			let angle = (v.min(w) * 31 + v.max(w)) as f64;
+			let sign = if v < w { 1e-9 } else { -1e-9 };
+			([angle.cos() * sign, angle.sin() * sign], 1e-9)
+		}
+	}
+}
+
+//=============================================================================
@@ -9,48 +9,97 @@
 //! use fastgraph::collections::*;
 //!
 //! fn main() {
-//! 	let mut g = Digraph::<usize, Empty, Empty>::new();
-//!
-//! 	g.add_node(1, Empty);
-//! 	g.add_node(2, Empty);
-//! 	g.add_node(3, Empty);
-//! 	g.add_node(4, Empty);
-//! 	g.add_node(5, Empty);
-//! 	g.add_node(6, Empty);
-//!
-//! 	g.add_edge(1, 2, Empty);
-//! 	g.add_edge(1, 3, Empty);
-//! 	g.add_edge(2, 1, Empty);
-//! 	g.add_edge(2, 3, Empty);
-//! 	g.add_edge(3, 1, Empty);
-//! 	g.add_edge(3, 5, Empty);
-//! 	g.add_edge(5, 2, Empty);
-//! 	g.add_edge(5, 4, Empty);
-//! 	g.add_edge(5, 1, Empty);
-//! 	g.add_edge(4, 5, Empty);
-//! 	g.add_edge(4, 3, Empty);
-//! 	g.add_edge(4, 2, Empty);
-//! 	g.add_edge(4, 6, Empty);
-//!
-//! 	let sink = g.get_node(6).unwrap();
-//! 	let shortest_tree = g.par_breadth_first(1,
-//! 		|edge|{
-//! 			if edge.target() == sink {
-//! 				Traverse::Finish
-//! 			} else {
-//! 				Traverse::Include
-//! 			}
-//! 		}).unwrap();
-//!
-//! 	let shortest_path = fastgraph::core::backtrack_edges(&shortest_tree);
-//!
-//! 	for edge in shortest_path {
-//! 		println!("{}", edge.upgrade().unwrap())
-//! 	}
+//!     let mut g = Digraph::<usize, Empty, Empty>::new();
+//!
+//!     g.add_node(1, Empty);
+//!     g.add_node(2, Empty);
+//!     g.add_node(3, Empty);
+//!     g.add_node(4, Empty);
+//!     g.add_node(5, Empty);
+//!     g.add_node(6, Empty);
+//!
+//!     g.add_edge(1, 2, Empty);
+//!     g.add_edge(1, 3, Empty);
+//!     g.add_edge(2, 1, Empty);
+//!     g.add_edge(2, 3, Empty);
+//!     g.add_edge(3, 1, Empty);
+//!     g.add_edge(3, 5, Empty);
+//!     g.add_edge(5, 2, Empty);
+//!     g.add_edge(5, 4, Empty);
+//!     g.add_edge(5, 1, Empty);
+//!     g.add_edge(4, 5, Empty);
+//!     g.add_edge(4, 3, Empty);
+//!     g.add_edge(4, 2, Empty);
+//!     g.add_edge(4, 6, Empty);
+//!
+//!     let sink = g.get_node(6).unwrap();
+//!     let shortest_tree = g.par_breadth_first(1,
+//!         |edge|{
+//!             if edge.target() == sink {
+//!                 Traverse::Finish
+//!             } else {
+//!                 Traverse::Include
+//!             }
+//!         }).unwrap();
+//!
+//!     let shortest_path = fastgraph::core::backtrack_edges(&shortest_tree);
+//!
+//!     for edge in shortest_path {
+//!         println!("{}", edge.upgrade().unwrap())
+//!     }
 //! }
 //!
 //! ```
 
 
+#[macro_use]
+mod trace;
 pub mod core;
-pub mod collections;
\ No newline at end of file
+pub mod collections;
+pub mod events;
+pub mod cache;
+pub mod parallel;
+pub mod printer;
+pub mod csr;
+pub mod arena;
+pub mod algebra;
+pub mod window;
+pub mod pregel;
+pub mod machine;
+pub mod petri;
+pub mod scheduling;
+pub mod shortest_path;
+pub mod contraction;
+pub mod landmarks;
+pub mod temporal;
+pub mod flow;
+pub mod reliability;
+pub mod sparsify;
+pub mod anonymize;
+pub mod product;
+pub mod planarity;
+pub mod treewidth;
+pub mod components;
+pub mod sat;
+pub mod checkpoint;
+pub mod centrality;
+pub mod community;
+pub mod similarity;
+pub mod sketch;
+pub mod embedding;
+pub mod isomorphism;
+pub mod kernel;
+pub mod motifs;
+pub mod schema;
+pub mod typed;
+pub mod query;
+pub mod matching;
+pub mod layout;
+pub mod grid;
+pub mod multilevel;
+pub mod distributed;
+#[cfg(feature = "cluster")]
+pub mod cluster;
+pub mod io;
+
+mod indexed;
\ No newline at end of file
@@ -0,0 +1,167 @@
+//=============================================================================
+// STATE MACHINES
+//=============================================================================
+
+//! Interpretation of a graph as a state machine where nodes are states and
+//! edges are transitions. A guard closure decides whether a transition
+//! accepts an input, usually by comparing the input to an event stored in
+//! the edge, and a handler closure is called with the new state and the
+//! input every time a transition is taken, which lets handlers stored in the
+//! node data run. When several transitions accept an input the first one in
+//! the order of the outbound edges of the state is taken, so sorting the
+//! edges with `Node::sort_outbound_by` sets their priority.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// A state machine at a node of a graph.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::{Edge, Empty};
+/// use fastgraph::machine::*;
+/// use std::sync::Arc;
+///
+/// // A turnstile that unlocks with a coin and locks again when pushed.
+/// let mut g = Digraph::<&str, Empty, &str>::new();
+/// g.add_node("locked", Empty);
+/// g.add_node("unlocked", Empty);
+/// g.add_edge("locked", "unlocked", "coin");
+/// g.add_edge("unlocked", "locked", "push");
+///
+/// let guard = |edge: &Arc<Edge<&str, Empty, &str>>, input: &&str| edge.load() == *input;
+/// let mut turnstile = StateMachine::new(&g, "locked").unwrap();
+/// assert!(turnstile.step(&"push", guard).is_none());
+/// assert!(turnstile.step(&"coin", guard).is_some());
+/// assert!(turnstile.state().key() == &"unlocked");
+/// ```
+pub struct StateMachine<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	state: Arc<Node<K, N, E>>,
+}
+
+impl<K, N, E> StateMachine<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Machine at the start state, or None if the start state can't be found.
+	pub fn new<G: Graph<K, N, E>>(graph: &G, start: K) -> Option<Self> {
+		Some(StateMachine { state: graph.get_node(start)? })
+	}
+
+	/// Machine at the given state.
+	pub fn at(state: Arc<Node<K, N, E>>) -> Self {
+		StateMachine { state }
+	}
+
+	/// Current state.
+	pub fn state(&self) -> &Arc<Node<K, N, E>> {
+		&self.state
+	}
+
+	/// True if the current state has no transitions.
+	pub fn is_final(&self) -> bool {
+		self.state.is_leaf()
+	}
+
+	/// Take the first transition out of the current state that the guard
+	/// accepts the input for. Returns the transition taken, or None if no
+	/// transition accepts the input, in which case the state is unchanged.
+	pub fn step<I, F>(&mut self, input: &I, guard: F) -> Option<Arc<Edge<K, N, E>>>
+	where
+		F: Fn (&Arc<Edge<K, N, E>>, &I) -> bool,
+	{
+		let edge = self.state.outbound().iter().find(|edge| guard(edge, input)).cloned()?;
+		self.state = edge.target();
+		Some(edge)
+	}
+}
+
+//=============================================================================
+
+/// Outcome of running a state machine over a stream of inputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Execution<K> {
+	/// The states the machine was in, starting from the start state.
+	pub trace: Vec<K>,
+	/// Amount of inputs that were consumed by a transition.
+	pub consumed: usize,
+	/// True if the machine stopped at an input no transition accepted.
+	pub stuck: bool,
+}
+
+/// # Execute
+///
+/// Run the graph as a state machine from the start state over the inputs.
+/// Every input takes the first transition that the `guard` accepts it for,
+/// and the `handler` is called with the new state and the input. The run
+/// stops when the inputs run out or at the first input no transition
+/// accepts, leaving the rest of the inputs unread. Returns None if the start
+/// state can't be found.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::machine::*;
+///
+/// // An order workflow where the node data counts the orders in a state.
+/// let mut g = Digraph::<&str, u32, &str>::new();
+/// for state in ["new", "paid", "shipped", "cancelled"] {
+///     g.add_node(state, 0);
+/// }
+/// g.add_edge("new", "paid", "pay");
+/// g.add_edge("new", "cancelled", "cancel");
+/// g.add_edge("paid", "shipped", "ship");
+///
+/// let run = execute(
+///     &g,
+///     "new",
+///     vec!["pay", "ship", "cancel"],
+///     |edge, input| edge.load() == *input,
+///     |state, _| state.store(state.load() + 1),
+/// ).unwrap();
+///
+/// assert!(run.trace == ["new", "paid", "shipped"]);
+/// assert!(run.consumed == 2 && run.stuck);
+/// assert!(g.get_node("shipped").unwrap().load() == 1);
+/// ```
+pub fn execute<K, N, E, G, I, S, F, H>(graph: &G, start: K, inputs: S, guard: F, mut handler: H) -> Option<Execution<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	S: IntoIterator<Item = I>,
+	F: Fn (&Arc<Edge<K, N, E>>, &I) -> bool,
+	H: FnMut (&Arc<Node<K, N, E>>, &I),
+{
+	let mut machine = StateMachine::new(graph, start)?;
+	let mut res = Execution { trace: vec![machine.state().key().clone()], consumed: 0, stuck: false };
+	for input in inputs {
+		if machine.step(&input, &guard).is_none() {
+			res.stuck = true;
+			break;
+		}
+		handler(machine.state(), &input);
+		res.trace.push(machine.state().key().clone());
+		res.consumed += 1;
+	}
+	Some(res)
+}
+
+//=============================================================================
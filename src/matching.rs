@@ -0,0 +1,1255 @@
+//=============================================================================
+// MATCHING
+//=============================================================================
+
+//! Matchings are sets of edges no two of which share a node. The weighted
+//! matchings here look for a matching of large total weight over the
+//! undirected view of the graph, where the weight of every edge is given by
+//! a closure. Only the heaviest of parallel edges between two nodes counts,
+//! and loops are never matched.
+//!
+//! Heavy-edge matching visits every node once and matches it with the
+//! neighbor of its heaviest edge, which is what coarsening in the
+//! `multilevel` framework uses. Greedy matching takes the heaviest edges of
+//! the whole graph first and path growing matching grows paths along heavy
+//! edges in linear time. Both weigh at least half as much as a maximum
+//! weight matching. All three are maximal, no edge can be added to them,
+//! and never match edges that don't weigh more than zero.
+//!
+//! Maximum weight matchings are found exactly with Edmonds' blossom
+//! algorithm in cubic time. The algorithm grows alternating trees from the
+//! unmatched nodes, shrinks odd cycles into blossoms and adjusts a dual
+//! solution until every unmatched node has a dual of zero or no tree can
+//! grow further. Maximum cardinality matching finds the heaviest of the
+//! matchings with the most edges.
+//!
+//! Bipartite problems split the nodes into a given side and the rest and
+//! only pair nodes across the sides. Stable matching pairs the nodes so
+//! that no two nodes prefer each other over their partners, and the
+//! assignment problem pairs every node of the given side with the lowest
+//! total cost.
+//!
+use crate::core::*;
+use crate::centrality::stable_order;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// The matched edges, one for every matched pair of nodes.
+pub type Matching<K, N, E> = Vec<Arc<Edge<K, N, E>>>;
+
+type PairEdges<K, N, E> = HashMap<(usize, usize), Arc<Edge<K, N, E>>>;
+
+// Undirected view of a graph as adjacency lists of neighbor and weight that
+// hold only the heaviest edge between two nodes. Nodes are numbered in a
+// stable order and the matched edges are looked up by their endpoints.
+struct Pairs<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	adjacency: Vec<Vec<(usize, f64)>>,
+	edges: PairEdges<K, N, E>,
+}
+
+impl<K, N, E> Pairs<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	// Edges that don't weigh more than zero are left out if `positive` is
+	// set.
+	fn new<G, F>(graph: &G, positive: bool, weight: F) -> Self
+	where
+		G: Graph<K, N, E>,
+		F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let order = stable_order(&indexed);
+		let mut rank = vec![0; order.len()];
+		for (r, v) in order.iter().enumerate() {
+			rank[*v] = r;
+		}
+		let mut best: HashMap<(usize, usize), (f64, usize)> = HashMap::new();
+		for (u, adjacent) in indexed.adjacency.iter().enumerate() {
+			for (w, e) in adjacent.iter() {
+				let x = weight(&indexed.edges[*e]);
+				let (a, b) = (rank[u].min(rank[*w]), rank[u].max(rank[*w]));
+				if a == b || (positive && x <= 0.0) {
+					continue;
+				}
+				let entry = best.entry((a, b)).or_insert((x, *e));
+				if x > entry.0 {
+					*entry = (x, *e);
+				}
+			}
+		}
+		let mut adjacency = vec![Vec::new(); order.len()];
+		let mut edges = HashMap::with_capacity(best.len());
+		for ((a, b), (x, e)) in best.into_iter() {
+			adjacency[a].push((b, x));
+			adjacency[b].push((a, x));
+			edges.insert((a, b), indexed.edges[e].clone());
+		}
+		for adjacent in adjacency.iter_mut() {
+			adjacent.sort_by_key(|(w, _)| *w);
+		}
+		Pairs { adjacency, edges }
+	}
+
+	// Edges of the matched pairs.
+	fn matching(&self, mates: &[usize]) -> Matching<K, N, E> {
+		let mut res = Vec::new();
+		for (u, m) in mates.iter().enumerate() {
+			if *m != usize::MAX && u < *m {
+				res.push(self.edges[&(u, *m)].clone());
+			}
+		}
+		res
+	}
+}
+
+// Match every unmatched node in the given order with the unmatched neighbor
+// of its heaviest edge, the first one on ties. Mates of unmatched nodes are
+// `usize::MAX`.
+pub(crate) fn heavy_edge_mates(adjacency: &[Vec<(usize, f64)>], order: &[usize], mates: &mut [usize]) {
+	for u in order.iter() {
+		if mates[*u] != usize::MAX {
+			continue;
+		}
+		let mate = adjacency[*u]
+			.iter()
+			.filter(|(w, _)| *w != *u && mates[*w] == usize::MAX)
+			.fold(None, |best: Option<(usize, f64)>, (w, weight)| match best {
+				Some((_, b)) if b >= *weight => { best }
+				_ => { Some((*w, *weight)) }
+			});
+		if let Some((w, _)) = mate {
+			mates[*u] = w;
+			mates[w] = *u;
+		}
+	}
+}
+
+//=============================================================================
+
+/// # Heavy-Edge Matching
+///
+/// Visit every node once in an order that is the same in every run and
+/// match it with the unmatched neighbor of its heaviest edge. Fast and
+/// maximal, but without a bound on its weight. The `weight` closure gives
+/// the weight of every edge.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::matching::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 3.0);
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 3, 3.0);
+///
+/// // The heaviest edge of every node is one of the outer edges, so they are
+/// // matched whichever node is visited first.
+/// let mut matching: Vec<(u32, u32)> = heavy_edge_matching(&g, |e| e.load())
+///     .iter()
+///     .map(|e| (*e.source().key(), *e.target().key()))
+///     .collect();
+/// matching.sort();
+/// assert!(matching == vec![(0, 1), (2, 3)]);
+/// ```
+pub fn heavy_edge_matching<K, N, E, G, F>(graph: &G, weight: F) -> Matching<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let pairs = Pairs::new(graph, true, weight);
+	let n = pairs.adjacency.len();
+	let order: Vec<usize> = (0..n).collect();
+	let mut mates = vec![usize::MAX; n];
+	heavy_edge_mates(&pairs.adjacency, &order, &mut mates);
+	pairs.matching(&mates)
+}
+
+/// # Greedy Matching
+///
+/// Take the edges heaviest first and match the endpoints of every edge
+/// whose endpoints are both unmatched. Weighs at least half as much as a
+/// maximum weight matching. The `weight` closure gives the weight of every
+/// edge.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::matching::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 2.0);
+/// g.add_edge(1, 2, 3.0);
+/// g.add_edge(2, 3, 2.0);
+///
+/// // The heaviest edge blocks both of its neighbors.
+/// let matching = greedy_matching(&g, |e| e.load());
+/// assert!(matching.len() == 1);
+/// assert!(matching[0].load() == 3.0);
+/// ```
+pub fn greedy_matching<K, N, E, G, F>(graph: &G, weight: F) -> Matching<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let pairs = Pairs::new(graph, true, weight);
+	let mut order = Vec::new();
+	for (u, adjacent) in pairs.adjacency.iter().enumerate() {
+		for (w, x) in adjacent.iter() {
+			if u < *w {
+				order.push((u, *w, *x));
+			}
+		}
+	}
+	// Heaviest first, in the order of the endpoints on ties.
+	order.sort_by(|a, b| b.2.total_cmp(&a.2));
+	let mut mates = vec![usize::MAX; pairs.adjacency.len()];
+	for (u, w, _) in order {
+		if mates[u] == usize::MAX && mates[w] == usize::MAX {
+			mates[u] = w;
+			mates[w] = u;
+		}
+	}
+	pairs.matching(&mates)
+}
+
+/// # Path Growing Matching
+///
+/// Grow paths through the graph, every step following the heaviest edge
+/// from the end of the path to a node not yet on any path, and match the
+/// heaviest set of edges of every path that share no nodes. Edges left
+/// between unmatched nodes are then matched like in heavy-edge matching.
+/// Runs in linear time and weighs at least half as much as a maximum weight
+/// matching. The `weight` closure gives the weight of every edge.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::matching::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 2.0);
+/// g.add_edge(1, 2, 3.0);
+/// g.add_edge(2, 3, 2.0);
+///
+/// // Both outer edges together outweigh the middle one. A path that starts
+/// // in the middle matches the middle edge instead, which still weighs at
+/// // least half as much.
+/// let weight = |m: &Matching<u32, Empty, f64>| m.iter().map(|e| e.load()).sum::<f64>();
+/// let matching = path_growing_matching(&g, |e| e.load());
+/// let maximum = maximum_weight_matching(&g, |e| e.load());
+/// assert!(weight(&maximum) == 4.0);
+/// assert!(2.0 * weight(&matching) >= weight(&maximum));
+/// ```
+pub fn path_growing_matching<K, N, E, G, F>(graph: &G, weight: F) -> Matching<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let pairs = Pairs::new(graph, true, weight);
+	let n = pairs.adjacency.len();
+	let mut removed = vec![false; n];
+	let mut mates = vec![usize::MAX; n];
+	for start in 0..n {
+		if removed[start] {
+			continue;
+		}
+		let mut path = vec![start];
+		let mut weights = Vec::new();
+		let mut x = start;
+		loop {
+			removed[x] = true;
+			let next = pairs.adjacency[x]
+				.iter()
+				.filter(|(w, _)| !removed[*w])
+				.fold(None, |best: Option<(usize, f64)>, (w, weight)| match best {
+					Some((_, b)) if b >= *weight => { best }
+					_ => { Some((*w, *weight)) }
+				});
+			match next {
+				Some((w, weight)) => {
+					path.push(w);
+					weights.push(weight);
+					x = w;
+				}
+				None => { break }
+			}
+		}
+		// Heaviest set of edges of the path that share no nodes, where
+		// `best[i]` is the weight of the best set among the first `i` edges.
+		let mut best = vec![0.0; weights.len() + 1];
+		for i in 0..weights.len() {
+			let skip = best[i];
+			let take = weights[i] + if i > 0 { best[i - 1] } else { 0.0 };
+			best[i + 1] = skip.max(take);
+		}
+		let mut i = weights.len();
+		while i > 0 {
+			if best[i] == best[i - 1] {
+				i -= 1;
+			} else {
+				mates[path[i - 1]] = path[i];
+				mates[path[i]] = path[i - 1];
+				i = i.saturating_sub(2);
+			}
+		}
+	}
+	let order: Vec<usize> = (0..n).collect();
+	heavy_edge_mates(&pairs.adjacency, &order, &mut mates);
+	pairs.matching(&mates)
+}
+
+//=============================================================================
+
+/// # Maximum Weight Matching
+///
+/// Find a matching of the largest possible total weight with Edmonds'
+/// blossom algorithm. The `weight` closure gives the weight of every edge.
+/// Integral weights give exact results, other weights are subject to the
+/// rounding of floating point arithmetic.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::matching::*;
+///
+/// // A triangle with a pendant edge on each corner.
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..6 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 6.0);
+/// g.add_edge(1, 2, 6.0);
+/// g.add_edge(2, 0, 6.0);
+/// g.add_edge(0, 3, 4.0);
+/// g.add_edge(1, 4, 4.0);
+/// g.add_edge(2, 5, 3.0);
+///
+/// // The three pendant edges outweigh a triangle edge with the pendant
+/// // edge of the third corner.
+/// let matching = maximum_weight_matching(&g, |e| e.load());
+/// assert!(matching.len() == 3);
+/// assert!(matching.iter().map(|e| e.load()).sum::<f64>() == 11.0);
+/// ```
+pub fn maximum_weight_matching<K, N, E, G, F>(graph: &G, weight: F) -> Matching<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let pairs = Pairs::new(graph, true, weight);
+	let mates = Blossoms::new(&pairs.adjacency).solve(false);
+	pairs.matching(&mates)
+}
+
+/// # Maximum Cardinality Matching
+///
+/// Find the heaviest of the matchings with the most edges with Edmonds'
+/// blossom algorithm. Edges of any weight can be matched. The `weight`
+/// closure gives the weight of every edge, and a constant weight finds just
+/// some matching with the most edges.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::matching::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 2.0);
+/// g.add_edge(1, 2, 5.0);
+/// g.add_edge(2, 3, 2.0);
+///
+/// // Two edges beat the heavier middle edge.
+/// let matching = maximum_cardinality_matching(&g, |e| e.load());
+/// assert!(matching.len() == 2);
+/// ```
+pub fn maximum_cardinality_matching<K, N, E, G, F>(graph: &G, weight: F) -> Matching<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let pairs = Pairs::new(graph, false, weight);
+	let mates = Blossoms::new(&pairs.adjacency).solve(true);
+	pairs.matching(&mates)
+}
+
+// No node, edge, endpoint or blossom.
+const NONE: usize = usize::MAX;
+
+// State of the blossom algorithm. Nodes are numbered from zero and the
+// blossoms that are not single nodes follow them. The two endpoints of edge
+// `k` are numbered `2k` and `2k + 1`, so that `p ^ 1` is the other endpoint
+// of endpoint `p`. Labels are 0 for free blossoms, 1 for outer blossoms, 2
+// for inner blossoms and 5 for outer blossoms visited while looking for a
+// new blossom. The matched endpoint of a node is kept as its mate.
+struct Blossoms {
+	n: usize,
+	edges: Vec<(usize, usize, f64)>,
+	endpoint: Vec<usize>,
+	incident: Vec<Vec<usize>>,
+	mate: Vec<usize>,
+	label: Vec<u8>,
+	labelend: Vec<usize>,
+	inblossom: Vec<usize>,
+	parent: Vec<usize>,
+	children: Vec<Vec<usize>>,
+	base: Vec<usize>,
+	endpoints: Vec<Vec<usize>>,
+	bestedge: Vec<usize>,
+	bestedges: Vec<Option<Vec<usize>>>,
+	unused: Vec<usize>,
+	dual: Vec<f64>,
+	allowed: Vec<bool>,
+	queue: Vec<usize>,
+}
+
+impl Blossoms {
+	fn new(adjacency: &[Vec<(usize, f64)>]) -> Self {
+		let n = adjacency.len();
+		let mut edges = Vec::new();
+		for (u, adjacent) in adjacency.iter().enumerate() {
+			for (w, x) in adjacent.iter() {
+				if u < *w {
+					edges.push((u, *w, *x));
+				}
+			}
+		}
+		let mut endpoint = Vec::with_capacity(2 * edges.len());
+		let mut incident = vec![Vec::new(); n];
+		for (k, (u, w, _)) in edges.iter().enumerate() {
+			endpoint.push(*u);
+			endpoint.push(*w);
+			incident[*u].push(2 * k + 1);
+			incident[*w].push(2 * k);
+		}
+		let top = edges.iter().fold(0.0, |top: f64, (_, _, x)| top.max(*x));
+		let mut dual = vec![top; n];
+		dual.resize(2 * n, 0.0);
+		let mut base: Vec<usize> = (0..n).collect();
+		base.resize(2 * n, NONE);
+		Blossoms {
+			n,
+			allowed: vec![false; edges.len()],
+			edges,
+			endpoint,
+			incident,
+			mate: vec![NONE; n],
+			label: vec![0; 2 * n],
+			labelend: vec![NONE; 2 * n],
+			inblossom: (0..n).collect(),
+			parent: vec![NONE; 2 * n],
+			children: vec![Vec::new(); 2 * n],
+			base,
+			endpoints: vec![Vec::new(); 2 * n],
+			bestedge: vec![NONE; 2 * n],
+			bestedges: vec![None; 2 * n],
+			unused: (n..2 * n).collect(),
+			dual,
+			queue: Vec::new(),
+		}
+	}
+
+	// Twice the amount by which the duals of the endpoints of an edge
+	// exceed its weight.
+	fn slack(&self, k: usize) -> f64 {
+		let (u, w, x) = self.edges[k];
+		self.dual[u] + self.dual[w] - 2.0 * x
+	}
+
+	// The nodes inside a blossom.
+	fn leaves(&self, b: usize) -> Vec<usize> {
+		let mut res = Vec::new();
+		let mut stack = vec![b];
+		while let Some(b) = stack.pop() {
+			match b < self.n {
+				true => { res.push(b) }
+				false => { stack.extend(self.children[b].iter().rev()) }
+			}
+		}
+		res
+	}
+
+	// Child of a blossom by an index that wraps around in both directions,
+	// and the endpoint of the edge that follows it in the cycle.
+	fn child(&self, b: usize, j: isize) -> usize {
+		let len = self.children[b].len() as isize;
+		self.children[b][j.rem_euclid(len) as usize]
+	}
+
+	fn endpoint_at(&self, b: usize, j: isize) -> usize {
+		let len = self.endpoints[b].len() as isize;
+		self.endpoints[b][j.rem_euclid(len) as usize]
+	}
+
+	// Label the blossom of a node outer or inner, reached through the
+	// endpoint `p`. The mate of the base of an inner blossom becomes outer.
+	fn assign_label(&mut self, mut w: usize, mut t: u8, mut p: usize) {
+		loop {
+			let b = self.inblossom[w];
+			self.label[w] = t;
+			self.label[b] = t;
+			self.labelend[w] = p;
+			self.labelend[b] = p;
+			self.bestedge[w] = NONE;
+			self.bestedge[b] = NONE;
+			if t == 1 {
+				let leaves = self.leaves(b);
+				self.queue.extend(leaves);
+				return;
+			}
+			let m = self.mate[self.base[b]];
+			w = self.endpoint[m];
+			t = 1;
+			p = m ^ 1;
+		}
+	}
+
+	// Trace back from two outer nodes joined by an edge towards the roots
+	// of their trees. Returns the base of the new blossom if the paths meet
+	// and NONE if they reach two different roots.
+	fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+		let mut path = Vec::new();
+		let mut base = NONE;
+		while v != NONE || w != NONE {
+			let b = self.inblossom[v];
+			if self.label[b] & 4 != 0 {
+				base = self.base[b];
+				break;
+			}
+			path.push(b);
+			self.label[b] = 5;
+			match self.labelend[b] {
+				NONE => { v = NONE }
+				end => {
+					let t = self.inblossom[self.endpoint[end]];
+					v = self.endpoint[self.labelend[t]];
+				}
+			}
+			if w != NONE {
+				std::mem::swap(&mut v, &mut w);
+			}
+		}
+		for b in path {
+			self.label[b] = 1;
+		}
+		base
+	}
+
+	// Shrink the cycle closed by edge `k` through the given base into a new
+	// outer blossom.
+	fn add_blossom(&mut self, base: usize, k: usize) {
+		let (v, w, _) = self.edges[k];
+		let bb = self.inblossom[base];
+		let mut bv = self.inblossom[v];
+		let mut bw = self.inblossom[w];
+		let b = self.unused.pop().unwrap();
+		self.base[b] = base;
+		self.parent[b] = NONE;
+		self.parent[bb] = b;
+		let mut path = Vec::new();
+		let mut endps = Vec::new();
+		while bv != bb {
+			self.parent[bv] = b;
+			path.push(bv);
+			endps.push(self.labelend[bv]);
+			bv = self.inblossom[self.endpoint[self.labelend[bv]]];
+		}
+		path.push(bb);
+		path.reverse();
+		endps.reverse();
+		endps.push(2 * k);
+		while bw != bb {
+			self.parent[bw] = b;
+			path.push(bw);
+			endps.push(self.labelend[bw] ^ 1);
+			bw = self.inblossom[self.endpoint[self.labelend[bw]]];
+		}
+		self.children[b] = path.clone();
+		self.endpoints[b] = endps;
+		self.label[b] = 1;
+		self.labelend[b] = self.labelend[bb];
+		self.dual[b] = 0.0;
+		for v in self.leaves(b) {
+			if self.label[self.inblossom[v]] == 2 {
+				// Inner nodes become outer and get scanned.
+				self.queue.push(v);
+			}
+			self.inblossom[v] = b;
+		}
+		// Least slack edge from the new blossom to every other outer
+		// blossom.
+		let mut bestto = vec![NONE; 2 * self.n];
+		for bv in path {
+			let edges: Vec<usize> = match self.bestedges[bv].take() {
+				Some(edges) => { edges }
+				None => {
+					self.leaves(bv).into_iter().flat_map(|v| self.incident[v].iter().map(|p| p / 2)).collect()
+				}
+			};
+			for k in edges {
+				let (i, j, _) = self.edges[k];
+				let j = if self.inblossom[j] == b { i } else { j };
+				let bj = self.inblossom[j];
+				if bj != b && self.label[bj] == 1 && (bestto[bj] == NONE || self.slack(k) < self.slack(bestto[bj])) {
+					bestto[bj] = k;
+				}
+			}
+			self.bestedge[bv] = NONE;
+		}
+		let edges: Vec<usize> = bestto.into_iter().filter(|k| *k != NONE).collect();
+		self.bestedge[b] = NONE;
+		for k in edges.iter() {
+			if self.bestedge[b] == NONE || self.slack(*k) < self.slack(self.bestedge[b]) {
+				self.bestedge[b] = *k;
+			}
+		}
+		self.bestedges[b] = Some(edges);
+	}
+
+	// Undo a blossom. Between stages every sub-blossom with a zero dual is
+	// expanded too. During a stage the sub-blossoms of an inner blossom
+	// are relabeled along the path through it.
+	fn expand_blossom(&mut self, b: usize, endstage: bool) {
+		for s in self.children[b].clone() {
+			self.parent[s] = NONE;
+			if s < self.n {
+				self.inblossom[s] = s;
+			} else if endstage && self.dual[s] == 0.0 {
+				self.expand_blossom(s, endstage);
+			} else {
+				for v in self.leaves(s) {
+					self.inblossom[v] = s;
+				}
+			}
+		}
+		if !endstage && self.label[b] == 2 {
+			let entry = self.inblossom[self.endpoint[self.labelend[b] ^ 1]];
+			let mut j = self.children[b].iter().position(|c| *c == entry).unwrap() as isize;
+			let (step, trick) = match j & 1 {
+				1 => {
+					j -= self.children[b].len() as isize;
+					(1, 0)
+				}
+				_ => { (-1, 1) }
+			};
+			// Relabel the sub-blossoms on the even path from the entry
+			// child to the base.
+			let mut p = self.labelend[b];
+			while j != 0 {
+				self.label[self.endpoint[p ^ 1]] = 0;
+				let q = self.endpoint_at(b, j - trick as isize);
+				self.label[self.endpoint[q ^ trick ^ 1]] = 0;
+				self.assign_label(self.endpoint[p ^ 1], 2, p);
+				self.allowed[q / 2] = true;
+				j += step;
+				p = self.endpoint_at(b, j - trick as isize) ^ trick;
+				self.allowed[p / 2] = true;
+				j += step;
+			}
+			let bv = self.child(b, j);
+			let v = self.endpoint[p ^ 1];
+			self.label[v] = 2;
+			self.label[bv] = 2;
+			self.labelend[v] = p;
+			self.labelend[bv] = p;
+			self.bestedge[bv] = NONE;
+			j += step;
+			// Sub-blossoms on the odd path stay free unless one of their
+			// nodes was reached from outside.
+			while self.child(b, j) != entry {
+				let bv = self.child(b, j);
+				if self.label[bv] == 1 {
+					j += step;
+					continue;
+				}
+				let leaves = self.leaves(bv);
+				let v = *leaves.iter().find(|v| self.label[**v] != 0).unwrap_or(leaves.last().unwrap());
+				if self.label[v] != 0 {
+					self.label[v] = 0;
+					self.label[self.endpoint[self.mate[self.base[bv]]]] = 0;
+					self.assign_label(v, 2, self.labelend[v]);
+				}
+				j += step;
+			}
+		}
+		self.label[b] = 0;
+		self.labelend[b] = NONE;
+		self.children[b].clear();
+		self.endpoints[b].clear();
+		self.base[b] = NONE;
+		self.bestedges[b] = None;
+		self.bestedge[b] = NONE;
+		self.unused.push(b);
+	}
+
+	// Swap matched and unmatched edges on the path from node `v` to the
+	// base of the blossom, making `v` the new base.
+	fn augment_blossom(&mut self, b: usize, v: usize) {
+		let mut t = v;
+		while self.parent[t] != b {
+			t = self.parent[t];
+		}
+		if t >= self.n {
+			self.augment_blossom(t, v);
+		}
+		let i = self.children[b].iter().position(|c| *c == t).unwrap();
+		let mut j = i as isize;
+		let (step, trick) = match i & 1 {
+			1 => {
+				j -= self.children[b].len() as isize;
+				(1, 0)
+			}
+			_ => { (-1, 1) }
+		};
+		while j != 0 {
+			j += step;
+			let t = self.child(b, j);
+			let p = self.endpoint_at(b, j - trick as isize) ^ trick;
+			if t >= self.n {
+				self.augment_blossom(t, self.endpoint[p]);
+			}
+			j += step;
+			let t = self.child(b, j);
+			if t >= self.n {
+				self.augment_blossom(t, self.endpoint[p ^ 1]);
+			}
+			self.mate[self.endpoint[p]] = p ^ 1;
+			self.mate[self.endpoint[p ^ 1]] = p;
+		}
+		self.children[b].rotate_left(i);
+		self.endpoints[b].rotate_left(i);
+		self.base[b] = self.base[self.children[b][0]];
+	}
+
+	// Swap matched and unmatched edges along the augmenting path through
+	// edge `k` between the roots of two trees.
+	fn augment_matching(&mut self, k: usize) {
+		let (v, w, _) = self.edges[k];
+		for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+			loop {
+				let bs = self.inblossom[s];
+				if bs >= self.n {
+					self.augment_blossom(bs, s);
+				}
+				self.mate[s] = p;
+				if self.labelend[bs] == NONE {
+					// Reached the root.
+					break;
+				}
+				let t = self.endpoint[self.labelend[bs]];
+				let bt = self.inblossom[t];
+				s = self.endpoint[self.labelend[bt]];
+				let j = self.endpoint[self.labelend[bt] ^ 1];
+				if bt >= self.n {
+					self.augment_blossom(bt, j);
+				}
+				self.mate[j] = self.labelend[bt];
+				p = self.labelend[bt] ^ 1;
+			}
+		}
+	}
+
+	// Scan the outer nodes in the queue. Returns true once the matching was
+	// augmented.
+	fn scan(&mut self) -> bool {
+		while let Some(v) = self.queue.pop() {
+			for p in self.incident[v].clone() {
+				let k = p / 2;
+				let w = self.endpoint[p];
+				if self.inblossom[v] == self.inblossom[w] {
+					continue;
+				}
+				let mut kslack = 0.0;
+				if !self.allowed[k] {
+					kslack = self.slack(k);
+					if kslack <= 0.0 {
+						self.allowed[k] = true;
+					}
+				}
+				let bw = self.inblossom[w];
+				if self.allowed[k] {
+					if self.label[bw] == 0 {
+						// Grow the tree.
+						self.assign_label(w, 2, p ^ 1);
+					} else if self.label[bw] == 1 {
+						let base = self.scan_blossom(v, w);
+						if base != NONE {
+							self.add_blossom(base, k);
+						} else {
+							self.augment_matching(k);
+							return true;
+						}
+					} else if self.label[w] == 0 {
+						// Reached an inner blossom through another node.
+						self.label[w] = 2;
+						self.labelend[w] = p ^ 1;
+					}
+				} else if self.label[bw] == 1 {
+					let b = self.inblossom[v];
+					if self.bestedge[b] == NONE || kslack < self.slack(self.bestedge[b]) {
+						self.bestedge[b] = k;
+					}
+				} else if self.label[w] == 0 && (self.bestedge[w] == NONE || kslack < self.slack(self.bestedge[w])) {
+					self.bestedge[w] = k;
+				}
+			}
+		}
+		false
+	}
+
+	// Change the duals by the largest amount that keeps them feasible.
+	// Returns false once the stage is over without an augmenting path.
+	fn adjust(&mut self, cardinality: bool) -> bool {
+		let n = self.n;
+		// Kinds of changes: 1 ends the stage, 2 makes an edge to a free
+		// node tight, 3 makes an edge between outer blossoms tight and 4
+		// expands an inner blossom.
+		let mut kind = 0;
+		let mut delta = 0.0;
+		let mut edge = NONE;
+		let mut blossom = NONE;
+		if !cardinality {
+			kind = 1;
+			delta = self.dual[..n].iter().fold(f64::INFINITY, |a, b| a.min(*b));
+		}
+		for v in 0..n {
+			if self.label[self.inblossom[v]] == 0 && self.bestedge[v] != NONE {
+				let d = self.slack(self.bestedge[v]);
+				if kind == 0 || d < delta {
+					delta = d;
+					kind = 2;
+					edge = self.bestedge[v];
+				}
+			}
+		}
+		for b in 0..2 * n {
+			if self.parent[b] == NONE && self.label[b] == 1 && self.bestedge[b] != NONE {
+				let d = self.slack(self.bestedge[b]) / 2.0;
+				if kind == 0 || d < delta {
+					delta = d;
+					kind = 3;
+					edge = self.bestedge[b];
+				}
+			}
+		}
+		for b in n..2 * n {
+			if self.base[b] != NONE && self.parent[b] == NONE && self.label[b] == 2 && (kind == 0 || self.dual[b] < delta) {
+				delta = self.dual[b];
+				kind = 4;
+				blossom = b;
+			}
+		}
+		if kind == 0 {
+			// No further progress is possible with a maximum cardinality
+			// matching, so the duals are brought to optimum.
+			kind = 1;
+			delta = self.dual[..n].iter().fold(f64::INFINITY, |a, b| a.min(*b)).max(0.0);
+		}
+		for v in 0..n {
+			match self.label[self.inblossom[v]] {
+				1 => { self.dual[v] -= delta }
+				2 => { self.dual[v] += delta }
+				_ => {}
+			}
+		}
+		for b in n..2 * n {
+			if self.base[b] != NONE && self.parent[b] == NONE {
+				match self.label[b] {
+					1 => { self.dual[b] += delta }
+					2 => { self.dual[b] -= delta }
+					_ => {}
+				}
+			}
+		}
+		match kind {
+			2 => {
+				self.allowed[edge] = true;
+				let (i, j, _) = self.edges[edge];
+				let i = if self.label[self.inblossom[i]] == 0 { j } else { i };
+				self.queue.push(i);
+			}
+			3 => {
+				self.allowed[edge] = true;
+				self.queue.push(self.edges[edge].0);
+			}
+			4 => { self.expand_blossom(blossom, false) }
+			_ => { return false }
+		}
+		true
+	}
+
+	// Run stages until no augmenting path is left. Returns the mate of
+	// every node, `usize::MAX` for unmatched nodes.
+	fn solve(mut self, cardinality: bool) -> Vec<usize> {
+		let n = self.n;
+		for _ in 0..n {
+			self.label.iter_mut().for_each(|l| *l = 0);
+			self.bestedge.iter_mut().for_each(|e| *e = NONE);
+			self.bestedges[n..].iter_mut().for_each(|e| *e = None);
+			self.allowed.iter_mut().for_each(|a| *a = false);
+			self.queue.clear();
+			for v in 0..n {
+				if self.mate[v] == NONE && self.label[self.inblossom[v]] == 0 {
+					self.assign_label(v, 1, NONE);
+				}
+			}
+			let mut augmented = false;
+			loop {
+				if self.scan() {
+					augmented = true;
+					break;
+				}
+				if !self.adjust(cardinality) {
+					break;
+				}
+			}
+			if !augmented {
+				break;
+			}
+			for b in n..2 * n {
+				if self.parent[b] == NONE && self.base[b] != NONE && self.label[b] == 1 && self.dual[b] == 0.0 {
+					self.expand_blossom(b, true);
+				}
+			}
+		}
+		self.mate.iter().map(|p| if *p == NONE { NONE } else { self.endpoint[*p] }).collect()
+	}
+}
+
+//=============================================================================
+
+/// Edges of an assignment together with their total cost.
+#[derive(Clone, Debug)]
+pub struct Assignment<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// The edge that pairs every node with one of the keys with another node,
+	/// one per node in the order of the nodes in the graph.
+	pub edges: Matching<K, N, E>,
+	/// Sum of the costs of the assigned edges.
+	pub cost: f64,
+}
+
+/// # Stable Matching
+///
+/// Pair the nodes with the given keys, the proposers, with the other nodes
+/// along the edges between them with the Gale-Shapley algorithm. The
+/// `proposer` and `receiver` closures give the rank of an edge for its
+/// proposer and its receiver, lower ranks preferred. Proposers propose along
+/// their edges in order of rank and receivers keep the best proposal so far.
+/// No proposer and receiver prefer an edge between them over the edges they
+/// are matched by, and every proposer gets the best partner it has in any
+/// such matching. Edge direction is ignored and keys not found in the graph
+/// are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::{Edge, Empty};
+/// use fastgraph::matching::*;
+/// use std::{collections::HashMap, sync::Arc};
+///
+/// let mut g = Digraph::<&str, Empty, Empty>::new();
+/// for k in ["a", "b", "x", "y"] {
+///     g.add_node(k, Empty);
+/// }
+/// // Rank of every edge for its source and its target.
+/// let mut ranks = HashMap::new();
+/// ranks.insert(("a", "x"), (1, 2));
+/// ranks.insert(("a", "y"), (2, 1));
+/// ranks.insert(("b", "x"), (1, 1));
+/// ranks.insert(("b", "y"), (2, 2));
+/// for (s, t) in ranks.keys() {
+///     g.add_edge(*s, *t, Empty);
+/// }
+/// let rank = |e: &Arc<Edge<&str, Empty, Empty>>| ranks[&(*e.source().key(), *e.target().key())];
+///
+/// // Both proposers prefer x, which prefers b.
+/// let matching = stable_matching(&g, &["a", "b"], |e| rank(e).0 as f64, |e| rank(e).1 as f64);
+/// let mut pairs: Vec<_> = matching.iter().map(|e| (*e.source().key(), *e.target().key())).collect();
+/// pairs.sort();
+/// assert!(pairs == vec![("a", "y"), ("b", "x")]);
+/// ```
+pub fn stable_matching<K, N, E, G, F, R>(graph: &G, proposers: &[K], proposer: F, receiver: R) -> Matching<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	R: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let order = stable_order(&indexed);
+	let mut rank = vec![0; order.len()];
+	for (r, v) in order.iter().enumerate() {
+		rank[*v] = r;
+	}
+	let mut side = vec![false; indexed.nodes.len()];
+	for key in proposers.iter() {
+		if let Some(v) = indexed.index.get(key) {
+			side[*v] = true;
+		}
+	}
+	// Edges of every proposer as pairs of its rank and the edge, and the
+	// rank of every edge for its receiver.
+	let mut options: Vec<Vec<(f64, usize)>> = vec![Vec::new(); indexed.nodes.len()];
+	let mut ranks = vec![0.0; indexed.edges.len()];
+	let mut other = vec![0; indexed.edges.len()];
+	for (u, adjacent) in indexed.adjacency.iter().enumerate() {
+		for (w, e) in adjacent.iter() {
+			let (p, r) = match (side[u], side[*w]) {
+				(true, false) => { (u, *w) }
+				(false, true) => { (*w, u) }
+				_ => { continue }
+			};
+			let edge = &indexed.edges[*e];
+			options[p].push((proposer(edge), *e));
+			ranks[*e] = receiver(edge);
+			other[*e] = r;
+		}
+	}
+	for list in options.iter_mut() {
+		// Best first, by the order of the receivers and the edges on ties.
+		list.sort_by(|a, b| a.0.total_cmp(&b.0).then(rank[other[a.1]].cmp(&rank[other[b.1]])).then(a.1.cmp(&b.1)));
+	}
+	let mut next = vec![0; indexed.nodes.len()];
+	let mut held: Vec<Option<(usize, usize)>> = vec![None; indexed.nodes.len()];
+	let mut free: Vec<usize> = order.iter().rev().cloned().filter(|v| side[*v]).collect();
+	while let Some(p) = free.pop() {
+		let e = match options[p].get(next[p]) {
+			Some((_, e)) => { *e }
+			None => { continue }
+		};
+		next[p] += 1;
+		let r = other[e];
+		match held[r] {
+			Some((_, f)) if ranks[f] <= ranks[e] => { free.push(p) }
+			Some((q, _)) => {
+				held[r] = Some((p, e));
+				free.push(q);
+			}
+			None => { held[r] = Some((p, e)) }
+		}
+	}
+	let mut res = Vec::new();
+	for v in order {
+		if let Some((_, e)) = held[v] {
+			res.push(indexed.edges[e].clone());
+		}
+	}
+	res
+}
+
+/// # Assignment Problem
+///
+/// Pair every node with one of the given keys with a different node of the
+/// rest of the graph along an edge between them, with the lowest total cost
+/// given by the `cost` closure, using the Hungarian algorithm. Only the
+/// cheapest of parallel edges between two nodes counts and edge direction
+/// is ignored. For the heaviest assignment negate the weights. Returns None
+/// if a key can't be found or not every node can be assigned.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::matching::*;
+///
+/// let mut g = Ungraph::<&str, Empty, f64>::new();
+/// for k in ["ann", "bob", "sort", "ship"] {
+///     g.add_node(k, Empty);
+/// }
+/// g.add_edge("ann", "sort", 2.0);
+/// g.add_edge("ann", "ship", 3.0);
+/// g.add_edge("bob", "sort", 1.0);
+/// g.add_edge("bob", "ship", 4.0);
+///
+/// let assignment = min_cost_assignment(&g, &["ann", "bob"], |e| e.load()).unwrap();
+/// assert!(assignment.cost == 4.0);
+/// assert!(assignment.edges.len() == 2);
+/// ```
+pub fn min_cost_assignment<K, N, E, G, F>(graph: &G, keys: &[K], cost: F) -> Option<Assignment<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let mut row = vec![usize::MAX; indexed.nodes.len()];
+	for key in keys.iter() {
+		let v = *indexed.index.get(key)?;
+		if row[v] == usize::MAX {
+			row[v] = 0;
+		}
+	}
+	// Rows are the nodes with the keys and columns the rest, both in the
+	// order of the graph.
+	let mut rows = Vec::new();
+	let mut columns = Vec::new();
+	let mut column = vec![usize::MAX; indexed.nodes.len()];
+	for v in 0..indexed.nodes.len() {
+		match row[v] {
+			usize::MAX => {
+				column[v] = columns.len();
+				columns.push(v);
+			}
+			_ => {
+				row[v] = rows.len();
+				rows.push(v);
+			}
+		}
+	}
+	let (n, m) = (rows.len(), columns.len());
+	if n > m {
+		return None;
+	}
+	let mut costs = vec![f64::INFINITY; n * m];
+	let mut cheapest = vec![usize::MAX; n * m];
+	for (u, adjacent) in indexed.adjacency.iter().enumerate() {
+		for (w, e) in adjacent.iter() {
+			let (i, j) = match (column[u], column[*w]) {
+				(usize::MAX, c) if c != usize::MAX => { (row[u], c) }
+				(c, usize::MAX) if c != usize::MAX => { (row[*w], c) }
+				_ => { continue }
+			};
+			let x = cost(&indexed.edges[*e]);
+			if x < costs[i * m + j] {
+				costs[i * m + j] = x;
+				cheapest[i * m + j] = *e;
+			}
+		}
+	}
+	let assigned = hungarian(&costs, n, m)?;
+	let mut res = Assignment { edges: Vec::with_capacity(n), cost: 0.0 };
+	for (i, j) in assigned.into_iter().enumerate() {
+		res.cost += costs[i * m + j];
+		res.edges.push(indexed.edges[cheapest[i * m + j]].clone());
+	}
+	Some(res)
+}
+
+// Column assigned to every row of an `n` by `m` cost matrix with `n <= m`
+// and the lowest total cost, keeping a potential for every row and column
+// and adding the rows one by one along shortest augmenting paths. Missing
+// pairs cost infinity. Returns None if not every row can be assigned.
+fn hungarian(costs: &[f64], n: usize, m: usize) -> Option<Vec<usize>> {
+	// Rows and columns are numbered from one, zero is a virtual column that
+	// starts every augmenting path.
+	let mut u = vec![0.0; n + 1];
+	let mut v = vec![0.0; m + 1];
+	let mut assigned = vec![0; m + 1];
+	let mut way = vec![0; m + 1];
+	for i in 1..=n {
+		assigned[0] = i;
+		let mut j0 = 0;
+		let mut minv = vec![f64::INFINITY; m + 1];
+		let mut used = vec![false; m + 1];
+		loop {
+			used[j0] = true;
+			let i0 = assigned[j0];
+			let mut delta = f64::INFINITY;
+			let mut j1 = 0;
+			for j in 1..=m {
+				if used[j] {
+					continue;
+				}
+				let cur = costs[(i0 - 1) * m + j - 1] - u[i0] - v[j];
+				if cur < minv[j] {
+					minv[j] = cur;
+					way[j] = j0;
+				}
+				if minv[j] < delta {
+					delta = minv[j];
+					j1 = j;
+				}
+			}
+			if delta == f64::INFINITY {
+				return None;
+			}
+			for j in 0..=m {
+				if used[j] {
+					u[assigned[j]] += delta;
+					v[j] -= delta;
+				} else {
+					minv[j] -= delta;
+				}
+			}
+			j0 = j1;
+			if assigned[j0] == 0 {
+				break;
+			}
+		}
+		while j0 != 0 {
+			let j1 = way[j0];
+			assigned[j0] = assigned[j1];
+			j0 = j1;
+		}
+	}
+	let mut res = vec![0; n];
+	for j in 1..=m {
+		if assigned[j] != 0 {
+			res[assigned[j] - 1] = j - 1;
+		}
+	}
+	Some(res)
+}
+
+//=============================================================================
@@ -0,0 +1,380 @@
+//=============================================================================
+// MOTIFS
+//=============================================================================
+
+//! Counts of the small subgraphs a graph is made of. The triad census of a
+//! directed graph counts the triples of nodes by which of the 16 types of
+//! directed graphs on three nodes they induce, named by their amount of
+//! mutual, asymmetric and null dyads. Motif counts ignore the direction of
+//! the edges and count the connected induced subgraphs on three and four
+//! nodes by their shape. Both are counted in parallel over the nodes. A
+//! count is only meaningful compared to graphs with the same degrees, so
+//! `significance` compares statistics of a graph to those of graphs
+//! rewired with degree preserving edge swaps.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::HashSet,
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+/// Names of the triad types in the order of the counts of `triad_census`.
+pub const TRIADS: [&str; 16] = [
+	"003", "012", "102", "021D", "021U", "021C", "111D", "111U",
+	"030T", "030C", "201", "120D", "120U", "120C", "210", "300",
+];
+
+// Triad type of every combination of the six arcs between three nodes, see
+// `tricode`.
+const TRICODES: [usize; 64] = [
+	0, 1, 1, 2, 1, 3, 5, 7, 1, 5, 4, 6, 2, 7, 6, 10,
+	1, 5, 3, 7, 4, 8, 8, 12, 5, 9, 8, 13, 6, 13, 11, 14,
+	1, 4, 5, 6, 5, 8, 9, 13, 3, 8, 8, 11, 7, 12, 13, 14,
+	2, 6, 7, 10, 6, 11, 13, 14, 7, 13, 12, 14, 10, 14, 14, 15,
+];
+
+//=============================================================================
+
+/// Counts of the connected induced subgraphs on three and four nodes of a
+/// graph, ignoring the direction of the edges.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Motifs {
+	/// Paths of two edges.
+	pub wedges: u64,
+	/// Cycles of three nodes.
+	pub triangles: u64,
+	/// Paths of three edges.
+	pub paths: u64,
+	/// Nodes with three neighbors not adjacent to each other.
+	pub stars: u64,
+	/// Cycles of four nodes without chords.
+	pub cycles: u64,
+	/// Triangles with a pendant edge.
+	pub paws: u64,
+	/// Cycles of four nodes with one chord.
+	pub diamonds: u64,
+	/// Complete graphs on four nodes.
+	pub cliques: u64,
+}
+
+impl Motifs {
+	/// The counts in the order of the fields.
+	pub fn to_vec(&self) -> Vec<f64> {
+		[self.wedges, self.triangles, self.paths, self.stars, self.cycles, self.paws, self.diamonds, self.cliques]
+			.iter()
+			.map(|c| *c as f64)
+			.collect()
+	}
+
+	fn add(mut self, other: Motifs) -> Motifs {
+		self.wedges += other.wedges;
+		self.triangles += other.triangles;
+		self.paths += other.paths;
+		self.stars += other.stars;
+		self.cycles += other.cycles;
+		self.paws += other.paws;
+		self.diamonds += other.diamonds;
+		self.cliques += other.cliques;
+		self
+	}
+}
+
+/// Statistics of a graph compared to their distribution over null models.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Significance {
+	/// The statistics of the graph.
+	pub observed: Vec<f64>,
+	/// Mean of every statistic over the null models.
+	pub mean: Vec<f64>,
+	/// Standard deviation of every statistic over the null models.
+	pub deviation: Vec<f64>,
+	/// How many standard deviations every statistic is above the mean. Zero
+	/// for statistics equal to a mean without deviation, and infinite for
+	/// ones that differ from it.
+	pub z: Vec<f64>,
+}
+
+//=============================================================================
+
+/// # Triad Census
+///
+/// Count the triples of nodes of every triad type, in the order of
+/// `TRIADS`, with the algorithm of Batagelj and Mrvar that only visits the
+/// triples that have an edge. In an undirected graph every edge is mutual.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::motifs::*;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, Empty);
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 0, Empty);
+///
+/// let census = triad_census(&g);
+/// let count = |name| census[TRIADS.iter().position(|t| *t == name).unwrap()];
+/// assert!(count("030C") == 1 && count("012") == 3);
+/// assert!(census.iter().sum::<u64>() == 4);
+/// ```
+pub fn triad_census<K, N, E, G>(graph: &G) -> [u64; 16]
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let arcs = Arcs::new(graph);
+	let n = arcs.neighbors.len() as u64;
+	let mut res = (0..arcs.neighbors.len())
+		.into_par_iter()
+		.map(|v| {
+			let mut counts = [0u64; 16];
+			for &u in arcs.neighbors[v].iter().filter(|u| **u > v) {
+				let mut s: HashSet<usize> = arcs.neighbors[u].union(&arcs.neighbors[v]).copied().collect();
+				s.remove(&u);
+				s.remove(&v);
+				let dyad = match arcs.has(v, u) && arcs.has(u, v) {
+					true => { 2 }
+					false => { 1 }
+				};
+				counts[dyad] += n - s.len() as u64 - 2;
+				for &w in s.iter() {
+					if u < w || (v < w && w < u && !arcs.neighbors[v].contains(&w)) {
+						counts[TRICODES[arcs.tricode(v, u, w)]] += 1;
+					}
+				}
+			}
+			counts
+		})
+		.reduce(|| [0u64; 16], |mut a, b| {
+			for (x, y) in a.iter_mut().zip(b.iter()) {
+				*x += y;
+			}
+			a
+		});
+	let triples = match n {
+		0..=2 => { 0 }
+		_ => { n * (n - 1) * (n - 2) / 6 }
+	};
+	res[0] = triples - res[1..].iter().sum::<u64>();
+	res
+}
+
+/// # Motif Counts
+///
+/// Count the connected induced subgraphs on three and four nodes by their
+/// shape, ignoring the direction of the edges. Every subgraph is found once
+/// with the ESU algorithm of Wernicke, which grows the subgraphs from their
+/// smallest node.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::motifs::*;
+///
+/// // A square with one diagonal.
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0), (0, 2)] {
+///     g.add_edge(a, b, Empty);
+/// }
+///
+/// let motifs = motifs(&g);
+/// assert!(motifs.triangles == 2 && motifs.wedges == 2);
+/// assert!(motifs.diamonds == 1 && motifs.cycles == 0);
+/// ```
+pub fn motifs<K, N, E, G>(graph: &G) -> Motifs
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let arcs = Arcs::new(graph);
+	let _span = trace_span!("motifs", nodes = arcs.neighbors.len());
+	(0..arcs.neighbors.len())
+		.into_par_iter()
+		.map(|v| {
+			let mut res = Motifs::default();
+			let extension: Vec<usize> = arcs.neighbors[v].iter().copied().filter(|u| *u > v).collect();
+			arcs.extend(&mut vec![v], extension, v, &mut res);
+			res
+		})
+		.reduce(Motifs::default, Motifs::add)
+}
+
+/// # Significance
+///
+/// Compare the statistics the `statistic` closure computes of the graph to
+/// their distribution over `samples` null models. Every null model is a
+/// copy of the graph rewired with ten degree preserving edge swaps per edge,
+/// see `Graph::rewire`, with a generator seeded from `rng`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::motifs::*;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // A ring where every node is also connected to the node after the next,
+/// // which has many more triangles than random graphs with its degrees.
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..30 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..30 {
+///     g.add_edge(i, (i + 1) % 30, Empty);
+///     g.add_edge(i, (i + 2) % 30, Empty);
+/// }
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let s = significance(&g, 20, |g| vec![motifs(g).triangles as f64], &mut rng);
+/// assert!(s.observed[0] == 30.0);
+/// assert!(s.z[0] > 3.0);
+/// ```
+pub fn significance<K, N, E, G, F, R>(graph: &G, samples: usize, statistic: F, rng: &mut R) -> Significance
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn (&G) -> Vec<f64>,
+	R: Rng + ?Sized,
+{
+	let observed = statistic(graph);
+	let swaps = graph.edge_count() * 10;
+	let seed: u64 = rng.gen();
+	let mut sum = vec![0.0; observed.len()];
+	let mut squares = vec![0.0; observed.len()];
+	for i in 0..samples {
+		let mut null: G = graph.map(|_, n| n, |e| e);
+		let mut rng = StdRng::seed_from_u64(seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+		null.rewire(swaps, &mut rng);
+		for (j, x) in statistic(&null).into_iter().enumerate().take(observed.len()) {
+			sum[j] += x;
+			squares[j] += x * x;
+		}
+	}
+	let t = samples.max(1) as f64;
+	let mean: Vec<f64> = sum.iter().map(|s| s / t).collect();
+	let deviation: Vec<f64> = squares.iter().zip(mean.iter()).map(|(q, m)| (q / t - m * m).max(0.0).sqrt()).collect();
+	let z = observed
+		.iter()
+		.zip(mean.iter().zip(deviation.iter()))
+		.map(|(x, (m, d))| match *d > 0.0 {
+			true => { (x - m) / d }
+			false if x == m => { 0.0 }
+			false => { f64::INFINITY.copysign(x - m) }
+		})
+		.collect();
+	Significance { observed, mean, deviation, z }
+}
+
+//=============================================================================
+
+// The arcs of a graph by node index with the neighbors of every node in
+// either direction, without loops.
+struct Arcs {
+	outbound: Vec<HashSet<usize>>,
+	neighbors: Vec<HashSet<usize>>,
+}
+
+impl Arcs {
+	fn new<K, N, E, G>(graph: &G) -> Self
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let n = indexed.nodes.len();
+		let mut outbound = vec![HashSet::new(); n];
+		let mut neighbors = vec![HashSet::new(); n];
+		for (u, adjacent) in indexed.adjacency.iter().enumerate() {
+			for &(w, _) in adjacent.iter().filter(|(w, _)| *w != u) {
+				outbound[u].insert(w);
+				if !G::directed() {
+					outbound[w].insert(u);
+				}
+				neighbors[u].insert(w);
+				neighbors[w].insert(u);
+			}
+		}
+		Arcs { outbound, neighbors }
+	}
+
+	fn has(&self, u: usize, w: usize) -> bool {
+		self.outbound[u].contains(&w)
+	}
+
+	// Bits of the arcs between three nodes.
+	fn tricode(&self, v: usize, u: usize, w: usize) -> usize {
+		[(v, u, 1), (u, v, 2), (v, w, 4), (w, v, 8), (u, w, 16), (w, u, 32)]
+			.iter()
+			.filter(|(a, b, _)| self.has(*a, *b))
+			.map(|(_, _, bit)| bit)
+			.sum()
+	}
+
+	// Grow the subgraph from its smallest node `root` with the nodes of the
+	// extension, counting it once it has three and four nodes.
+	fn extend(&self, subgraph: &mut Vec<usize>, mut extension: Vec<usize>, root: usize, res: &mut Motifs) {
+		if subgraph.len() >= 3 {
+			self.count(subgraph, res);
+			if subgraph.len() == 4 {
+				return;
+			}
+		}
+		while let Some(w) = extension.pop() {
+			// Neighbors of w that aren't in or next to the subgraph.
+			let mut next = extension.clone();
+			for &u in self.neighbors[w].iter() {
+				if u > root && !subgraph.contains(&u) && !subgraph.iter().any(|s| self.neighbors[*s].contains(&u)) && !next.contains(&u) {
+					next.push(u);
+				}
+			}
+			subgraph.push(w);
+			self.extend(subgraph, next, root, res);
+			subgraph.pop();
+		}
+	}
+
+	fn count(&self, subgraph: &[usize], res: &mut Motifs) {
+		let degrees: Vec<usize> = subgraph
+			.iter()
+			.map(|a| subgraph.iter().filter(|b| self.neighbors[*a].contains(b)).count())
+			.collect();
+		let edges = degrees.iter().sum::<usize>() / 2;
+		let max = degrees.iter().copied().max().unwrap_or(0);
+		match (subgraph.len(), edges) {
+			(3, 2) => { res.wedges += 1 }
+			(3, _) => { res.triangles += 1 }
+			(_, 3) if max == 3 => { res.stars += 1 }
+			(_, 3) => { res.paths += 1 }
+			(_, 4) if max == 2 => { res.cycles += 1 }
+			(_, 4) => { res.paws += 1 }
+			(_, 5) => { res.diamonds += 1 }
+			_ => { res.cliques += 1 }
+		}
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,411 @@
+//=============================================================================
+// MULTILEVEL
+//=============================================================================
+
+//! Multilevel framework for problems that are easier to solve on a smaller
+//! graph, such as partitioning, layout and clustering. The graph is coarsened
+//! into a hierarchy of ever smaller weighted graphs by matching nodes along
+//! heavy edges and contracting every matched pair into one node. A solver
+//! solves the problem on the coarsest graph, and the solution is projected
+//! back through the levels, where every node takes the value of the node it
+//! was contracted into and the solver refines the values on every level.
+//! Solvers implement the `Multilevel` trait and can be run on any hierarchy.
+//! Edge direction is ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::core::Empty;
+//! use fastgraph::multilevel::*;
+//!
+//! // A cycle of 64 nodes.
+//! let mut g = Ungraph::<u32, Empty, Empty>::new();
+//! for i in 0..64 {
+//!     g.add_node(i, Empty);
+//! }
+//! for i in 0..64 {
+//!     g.add_edge(i, (i + 1) % 64, Empty);
+//! }
+//!
+//! let hierarchy = Hierarchy::new(&g, 8, |_| 1.0);
+//! assert!(hierarchy.levels.last().unwrap().len() <= 8);
+//!
+//! // The best cut of a cycle in two halves cuts two edges, and the
+//! // partition found comes close to it.
+//! let parts = hierarchy.run(&mut Partition::new(2, 0.05));
+//! let level = &hierarchy.levels[0];
+//! assert!(level.cut(&parts) >= 2.0 && level.cut(&parts) <= 4.0);
+//! ```
+use crate::core::*;
+use crate::centrality::stable_order;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::matching::heavy_edge_mates;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+// Coarsening stops once a matching contracts less than this share of the
+// nodes, since further levels would barely be smaller.
+const MIN_SHRINK: f64 = 0.05;
+
+//=============================================================================
+
+/// One level of a hierarchy, a weighted undirected graph without loops. The
+/// weight of a node is the total weight of the nodes contracted into it, and
+/// the adjacency list of every node holds pairs of neighbor and total weight
+/// of the edges between them. The weight of the edges inside a node is kept
+/// as its internal weight.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Level {
+	pub weights: Vec<f64>,
+	pub internal: Vec<f64>,
+	pub adjacency: Vec<Vec<(usize, f64)>>,
+}
+
+impl Level {
+	/// Amount of nodes.
+	pub fn len(&self) -> usize {
+		self.weights.len()
+	}
+
+	/// True if the level has no nodes.
+	pub fn is_empty(&self) -> bool {
+		self.weights.is_empty()
+	}
+
+	/// Total weight of the edges between nodes with different labels.
+	pub fn cut(&self, labels: &[usize]) -> f64 {
+		let mut res = 0.0;
+		for (u, adjacent) in self.adjacency.iter().enumerate() {
+			for (w, weight) in adjacent.iter() {
+				if u < *w && labels[u] != labels[*w] {
+					res += weight;
+				}
+			}
+		}
+		res
+	}
+
+	// Match every node with its unmatched neighbor of the heaviest edge,
+	// visiting lighter nodes first so that the weights of the coarse nodes
+	// stay even. Returns the coarse node of every node and the amount of
+	// coarse nodes.
+	fn matching(&self) -> (Vec<usize>, usize) {
+		let n = self.len();
+		let mut order: Vec<usize> = (0..n).collect();
+		order.sort_by(|a, b| self.weights[*a].total_cmp(&self.weights[*b]));
+		let mut mates = vec![usize::MAX; n];
+		heavy_edge_mates(&self.adjacency, &order, &mut mates);
+		let mut coarse = vec![usize::MAX; n];
+		let mut count = 0;
+		for u in order {
+			if coarse[u] != usize::MAX {
+				continue;
+			}
+			coarse[u] = count;
+			if mates[u] != usize::MAX {
+				coarse[mates[u]] = count;
+			}
+			count += 1;
+		}
+		(coarse, count)
+	}
+
+	// Contract the nodes into their coarse nodes.
+	fn contract(&self, coarse: &[usize], count: usize) -> Level {
+		let mut res = Level {
+			weights: vec![0.0; count],
+			internal: vec![0.0; count],
+			adjacency: vec![Vec::new(); count],
+		};
+		let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+		for (u, adjacent) in self.adjacency.iter().enumerate() {
+			let c = coarse[u];
+			res.weights[c] += self.weights[u];
+			res.internal[c] += self.internal[u];
+			for (w, weight) in adjacent.iter() {
+				let d = coarse[*w];
+				if c == d {
+					// Every edge is seen from both ends.
+					res.internal[c] += weight / 2.0;
+				} else {
+					*merged[c].entry(d).or_insert(0.0) += weight;
+				}
+			}
+		}
+		for (c, neighbors) in merged.into_iter().enumerate() {
+			let mut adjacent: Vec<(usize, f64)> = neighbors.into_iter().collect();
+			adjacent.sort_by_key(|(d, _)| *d);
+			res.adjacency[c] = adjacent;
+		}
+		res
+	}
+}
+
+/// A hierarchy of ever coarser levels of a graph. The first level is the
+/// graph itself with every node weighing one, and `coarse[i]` maps every node
+/// of level `i` to the node of level `i + 1` it was contracted into. Nodes of
+/// the first level are in the order of `keys`.
+#[derive(Clone, Debug)]
+pub struct Hierarchy<K> {
+	pub keys: Vec<K>,
+	pub levels: Vec<Level>,
+	pub coarse: Vec<Vec<usize>>,
+}
+
+impl<K> Hierarchy<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Coarsen the graph until a level has at most `min_nodes` nodes or a
+	/// matching barely shrinks it. The `weight` closure gives the weight of
+	/// every edge and parallel edges between two nodes add up.
+	pub fn new<N, E, G, F>(graph: &G, min_nodes: usize, weight: F) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		// Nodes are numbered in an order that is the same in every run.
+		let order = stable_order(&indexed);
+		let mut rank = vec![0; order.len()];
+		for (r, v) in order.iter().enumerate() {
+			rank[*v] = r;
+		}
+		let n = order.len();
+		let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); n];
+		let mut internal = vec![0.0; n];
+		for (u, adjacent) in indexed.adjacency.iter().enumerate() {
+			for (w, e) in adjacent.iter() {
+				let x = weight(&indexed.edges[*e]);
+				let (a, b) = (rank[u], rank[*w]);
+				if a == b {
+					internal[a] += x;
+					continue;
+				}
+				*merged[a].entry(b).or_insert(0.0) += x;
+				*merged[b].entry(a).or_insert(0.0) += x;
+			}
+		}
+		let adjacency = merged
+			.into_iter()
+			.map(|neighbors| {
+				let mut adjacent: Vec<(usize, f64)> = neighbors.into_iter().collect();
+				adjacent.sort_by_key(|(w, _)| *w);
+				adjacent
+			})
+			.collect();
+		let first = Level { weights: vec![1.0; n], internal, adjacency };
+		let keys = order.iter().map(|v| indexed.key(*v)).collect();
+		Self::coarsen(keys, first, min_nodes)
+	}
+
+	// Contract levels from the first until one is small enough.
+	fn coarsen(keys: Vec<K>, first: Level, min_nodes: usize) -> Self {
+		let mut res = Hierarchy { keys, levels: vec![first], coarse: Vec::new() };
+		let _span = trace_span!("coarsen", nodes = res.keys.len());
+		loop {
+			let level = res.levels.last().unwrap();
+			if level.len() <= min_nodes.max(1) {
+				break;
+			}
+			let (coarse, count) = level.matching();
+			if ((level.len() - count) as f64) < MIN_SHRINK * level.len() as f64 {
+				break;
+			}
+			let next = level.contract(&coarse, count);
+			trace_event!("level", nodes = count);
+			res.coarse.push(coarse);
+			res.levels.push(next);
+		}
+		res
+	}
+
+	/// Solve a problem with a solver on the coarsest level and project the
+	/// solution back to the first level, refining it on every level on the
+	/// way. Returns the value of every node of the first level in the order
+	/// of `keys`.
+	pub fn run<S: Multilevel>(&self, solver: &mut S) -> Vec<S::Value> {
+		let last = self.levels.len() - 1;
+		let mut values = solver.solve(&self.levels[last]);
+		solver.refine(&self.levels[last], &mut values);
+		for i in (0..last).rev() {
+			values = self.coarse[i].iter().map(|c| values[*c].clone()).collect();
+			solver.refine(&self.levels[i], &mut values);
+		}
+		values
+	}
+
+	/// Run a solver like `run` and pair the keys with their values.
+	pub fn run_keyed<S: Multilevel>(&self, solver: &mut S) -> HashMap<K, S::Value> {
+		self.keys.iter().cloned().zip(self.run(solver)).collect()
+	}
+}
+
+/// A problem solved on a hierarchy. The values of the nodes of a level are
+/// given by index. When a solution is projected to a finer level every node
+/// starts with the value of the node it was contracted into.
+pub trait Multilevel {
+	type Value: Clone;
+
+	/// Solve the problem on the coarsest level.
+	fn solve(&mut self, level: &Level) -> Vec<Self::Value>;
+
+	/// Improve the values of the nodes of a level.
+	fn refine(&mut self, level: &Level, values: &mut [Self::Value]);
+}
+
+//=============================================================================
+
+/// # Partition
+///
+/// Multilevel partitioning into `parts` parts of about the same weight that
+/// cut as little edge weight as possible. The coarsest level is partitioned
+/// greedily, heaviest nodes first, into the part each node is most strongly
+/// connected to that has room for it. Every level is refined by moving nodes
+/// to the part they are most strongly connected to as long as the move cuts
+/// less weight and keeps the part within `1 + imbalance` times the average
+/// weight of a part. Values are the indices of the parts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Partition {
+	pub parts: usize,
+	pub imbalance: f64,
+	pub passes: usize,
+}
+
+impl Partition {
+	/// Partition into the amount of parts with four refinement passes on
+	/// every level.
+	pub fn new(parts: usize, imbalance: f64) -> Self {
+		Partition { parts: parts.max(1), imbalance, passes: 4 }
+	}
+
+	// Largest weight allowed in a part of the level.
+	fn capacity(&self, level: &Level) -> f64 {
+		let total: f64 = level.weights.iter().sum();
+		(1.0 + self.imbalance) * total / self.parts as f64
+	}
+
+	// Weight of the edges from a node to every part.
+	fn connection(&self, level: &Level, v: usize, values: &[usize]) -> Vec<f64> {
+		let mut res = vec![0.0; self.parts];
+		for (w, weight) in level.adjacency[v].iter() {
+			if values[*w] < self.parts {
+				res[values[*w]] += weight;
+			}
+		}
+		res
+	}
+}
+
+impl Multilevel for Partition {
+	type Value = usize;
+
+	fn solve(&mut self, level: &Level) -> Vec<usize> {
+		let capacity = self.capacity(level);
+		let mut order: Vec<usize> = (0..level.len()).collect();
+		order.sort_by(|a, b| level.weights[*b].total_cmp(&level.weights[*a]));
+		let mut values = vec![usize::MAX; level.len()];
+		let mut loads: Vec<f64> = vec![0.0; self.parts];
+		for v in order {
+			let connection = self.connection(level, v, &values);
+			let lightest = (0..self.parts).min_by(|a, b| loads[*a].total_cmp(&loads[*b])).unwrap();
+			// Parts with room, most connected first and lighter on ties.
+			let best = (0..self.parts)
+				.filter(|p| loads[*p] + level.weights[v] <= capacity)
+				.max_by(|a, b| connection[*a].total_cmp(&connection[*b]).then(loads[*b].total_cmp(&loads[*a])))
+				.unwrap_or(lightest);
+			values[v] = best;
+			loads[best] += level.weights[v];
+		}
+		values
+	}
+
+	fn refine(&mut self, level: &Level, values: &mut [usize]) {
+		let capacity = self.capacity(level);
+		let mut loads: Vec<f64> = vec![0.0; self.parts];
+		for (v, p) in values.iter().enumerate() {
+			loads[*p] += level.weights[v];
+		}
+		for _ in 0..self.passes {
+			let mut moved = false;
+			for v in 0..level.len() {
+				let connection = self.connection(level, v, values);
+				let current = values[v];
+				let best = (0..self.parts)
+					.filter(|p| *p != current && loads[*p] + level.weights[v] <= capacity)
+					.max_by(|a, b| connection[*a].total_cmp(&connection[*b]));
+				if let Some(p) = best {
+					if connection[p] > connection[current] {
+						values[v] = p;
+						loads[current] -= level.weights[v];
+						loads[p] += level.weights[v];
+						moved = true;
+					}
+				}
+			}
+			if !moved {
+				break;
+			}
+		}
+	}
+}
+
+/// Partition the nodes of the graph into `parts` parts of about the same
+/// size with a multilevel `Partition`, where the `weight` closure gives the
+/// weight of every edge. Returns the keys of every part.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::multilevel::*;
+///
+/// // Two cliques of eight nodes joined by one edge.
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..16 {
+///     g.add_node(i, Empty);
+/// }
+/// for c in [0, 8] {
+///     for i in c..c + 8 {
+///         for j in i + 1..c + 8 {
+///             g.add_edge(i, j, Empty);
+///         }
+///     }
+/// }
+/// g.add_edge(0, 8, Empty);
+///
+/// let mut parts = multilevel_partition(&g, 2, 0.0, |_| 1.0);
+/// for part in parts.iter_mut() {
+///     part.sort_unstable();
+/// }
+/// parts.sort();
+/// assert!(parts == vec![(0..8).collect::<Vec<_>>(), (8..16).collect()]);
+/// ```
+pub fn multilevel_partition<K, N, E, G, F>(graph: &G, parts: usize, imbalance: f64, weight: F) -> Vec<Vec<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+{
+	let hierarchy = Hierarchy::new(graph, 8 * parts.max(1), weight);
+	let mut partition = Partition::new(parts, imbalance);
+	let values = hierarchy.run(&mut partition);
+	let mut res = vec![Vec::new(); partition.parts];
+	for (key, p) in hierarchy.keys.into_iter().zip(values) {
+		res[p].push(key);
+	}
+	res
+}
+
+//=============================================================================
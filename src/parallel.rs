@@ -0,0 +1,86 @@
+//=============================================================================
+// THREAD POOLS
+//=============================================================================
+
+//! Selection of the thread pool that parallel algorithms run on. Every
+//! parallel algorithm of the crate uses rayon and runs on the pool of the
+//! thread that calls it, which is the global pool unless the call is made
+//! through `Threads::run`. This lets an application that manages its own
+//! pools, such as a server, keep graph computations on a dedicated pool of a
+//! chosen size.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::centrality::pagerank;
+//! use fastgraph::collections::*;
+//! use fastgraph::core::Empty;
+//! use fastgraph::parallel::Threads;
+//!
+//! let mut g = Digraph::<u32, Empty, Empty>::new();
+//! g.add_node(1, Empty);
+//! g.add_node(2, Empty);
+//! g.add_edge(1, 2, Empty);
+//!
+//! let threads = Threads::count(2).unwrap();
+//! let ranks = threads.run(|| pagerank(&g, 0.85, 20, 1e-9));
+//! assert!(ranks.len() == 2);
+//! ```
+//!
+pub use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+use std::sync::Arc;
+
+/// Thread pool to run parallel algorithms on. Cloning shares the pool.
+#[derive(Clone, Debug, Default)]
+pub enum Threads {
+	/// The global rayon pool, or the pool the caller is already running on.
+	#[default]
+	Global,
+	/// A pool owned by the caller.
+	Pool(Arc<ThreadPool>),
+}
+
+impl Threads {
+	/// Build a new pool with the given amount of threads. Zero lets rayon
+	/// choose the amount of threads.
+	pub fn count(threads: usize) -> Result<Self, ThreadPoolBuildError> {
+		let pool = ThreadPoolBuilder::new().num_threads(threads).build()?;
+		Ok(Threads::Pool(Arc::new(pool)))
+	}
+
+	/// Run the closure on the pool. Parallel algorithms called from the
+	/// closure split their work among the threads of the pool, and the
+	/// calling thread blocks until the closure returns.
+	pub fn run<R, F>(&self, f: F) -> R
+	where
+		R: Send,
+		F: FnOnce () -> R + Send,
+	{
+		match self {
+			Threads::Global => { f() }
+			Threads::Pool(pool) => { pool.install(f) }
+		}
+	}
+
+	/// Amount of threads the parallel algorithms run with.
+	pub fn num_threads(&self) -> usize {
+		match self {
+			Threads::Global => { rayon::current_num_threads() }
+			Threads::Pool(pool) => { pool.current_num_threads() }
+		}
+	}
+}
+
+impl From<ThreadPool> for Threads {
+	fn from(pool: ThreadPool) -> Self {
+		Threads::Pool(Arc::new(pool))
+	}
+}
+
+impl From<Arc<ThreadPool>> for Threads {
+	fn from(pool: Arc<ThreadPool>) -> Self {
+		Threads::Pool(pool)
+	}
+}
+
+//=============================================================================
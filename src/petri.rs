@@ -0,0 +1,248 @@
+//=============================================================================
+// PETRI NETS
+//=============================================================================
+
+//! Token simulation of a graph interpreted as a Petri net. Nodes are either
+//! places, which hold tokens, or transitions, which consume tokens from the
+//! places on their inbound edges and produce tokens into the places on their
+//! outbound edges. The weight of an edge is the amount of tokens moved
+//! through it. A transition is enabled when each of its input places holds
+//! at least the weight of its edge, and a net in which no transition is
+//! enabled is deadlocked. The net is a snapshot of the graph, so the graph
+//! can be read while the simulation runs and is left unchanged.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+// Places and weights of the edges into and out of a transition.
+#[derive(Clone, Debug)]
+struct Transition {
+	node: usize,
+	inputs: Vec<(usize, u64)>,
+	outputs: Vec<(usize, u64)>,
+}
+
+/// Outcome of running a Petri net for a number of steps.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Run {
+	/// Amount of steps in which a transition fired.
+	pub steps: usize,
+	/// Amount of transitions fired.
+	pub fired: usize,
+	/// True if the run ended because the net deadlocked.
+	pub deadlock: bool,
+}
+
+/// A Petri net with its current marking, the amount of tokens in every
+/// place.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::petri::*;
+///
+/// // A producer that turns each unit of material into a product.
+/// let mut g = Digraph::<&str, Empty, u64>::new();
+/// for key in ["material", "produce", "product"] {
+///     g.add_node(key, Empty);
+/// }
+/// g.add_edge("material", "produce", 2);
+/// g.add_edge("produce", "product", 1);
+///
+/// let mut net = PetriNet::new(
+///     &g,
+///     |node| match *node.key() {
+///         "produce" => None,
+///         "material" => Some(5),
+///         _ => Some(0),
+///     },
+///     |edge| edge.load(),
+/// );
+///
+/// let run = net.run(10);
+/// assert!(run.fired == 2 && run.deadlock);
+/// assert!(net.tokens(&"material") == Some(1));
+/// assert!(net.tokens(&"product") == Some(2));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PetriNet<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	keys: Vec<K>,
+	index: HashMap<K, usize>,
+	// Tokens of every node by index, None for transitions.
+	marking: Vec<Option<u64>>,
+	transitions: Vec<Transition>,
+}
+
+impl<K> PetriNet<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Build a net from the graph. The `tokens` closure gives the initial
+	/// amount of tokens of a place, or None if the node is a transition, and
+	/// the `weight` closure the weight of an edge. Edges between two places
+	/// or two transitions are ignored.
+	pub fn new<N, E, G, FT, FW>(graph: &G, tokens: FT, weight: FW) -> Self
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+		FT: Fn (&Arc<Node<K, N, E>>) -> Option<u64>,
+		FW: Fn (&Arc<Edge<K, N, E>>) -> u64,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let marking: Vec<Option<u64>> = indexed.nodes.iter().map(tokens).collect();
+		let mut transitions = Vec::new();
+		let mut of: Vec<Option<usize>> = vec![None; marking.len()];
+		for (v, m) in marking.iter().enumerate() {
+			if m.is_none() {
+				of[v] = Some(transitions.len());
+				transitions.push(Transition { node: v, inputs: Vec::new(), outputs: Vec::new() });
+			}
+		}
+		for (u, list) in indexed.adjacency.iter().enumerate() {
+			for &(w, e) in list.iter() {
+				match (of[u], of[w]) {
+					(None, Some(t)) => { transitions[t].inputs.push((u, weight(&indexed.edges[e]))) }
+					(Some(t), None) => { transitions[t].outputs.push((w, weight(&indexed.edges[e]))) }
+					_ => {}
+				}
+			}
+		}
+		let keys: Vec<K> = indexed.nodes.iter().map(|n| n.key().clone()).collect();
+		PetriNet { keys, index: indexed.index, marking, transitions }
+	}
+
+	/// Tokens in a place, or None if the key isn't a place of the net.
+	pub fn tokens(&self, place: &K) -> Option<u64> {
+		self.marking[*self.index.get(place)?]
+	}
+
+	/// Set the tokens of a place. Returns false if the key isn't a place of
+	/// the net.
+	pub fn set_tokens(&mut self, place: &K, tokens: u64) -> bool {
+		let v = match self.index.get(place) {
+			Some(v) => { *v }
+			None => { return false }
+		};
+		match self.marking[v].as_mut() {
+			Some(m) => {
+				*m = tokens;
+				true
+			}
+			None => { false }
+		}
+	}
+
+	/// Every place paired with its tokens.
+	pub fn marking(&self) -> Vec<(K, u64)> {
+		self.keys
+			.iter()
+			.zip(self.marking.iter())
+			.filter_map(|(k, m)| m.map(|m| (k.clone(), m)))
+			.collect()
+	}
+
+	/// Keys of the enabled transitions.
+	pub fn enabled(&self) -> Vec<K> {
+		(0..self.transitions.len())
+			.filter(|t| self.is_enabled(*t))
+			.map(|t| self.keys[self.transitions[t].node].clone())
+			.collect()
+	}
+
+	/// True if no transition is enabled.
+	pub fn is_deadlocked(&self) -> bool {
+		!(0..self.transitions.len()).any(|t| self.is_enabled(t))
+	}
+
+	/// Fire a transition, moving tokens from its input places to its output
+	/// places. Returns false if the key isn't a transition of the net or the
+	/// transition isn't enabled.
+	pub fn fire(&mut self, transition: &K) -> bool {
+		match self.transitions.iter().position(|t| self.keys[t.node] == *transition) {
+			Some(t) if self.is_enabled(t) => {
+				self.fire_at(t);
+				true
+			}
+			_ => { false }
+		}
+	}
+
+	/// Advance the marking by one step, in which every transition fires once
+	/// if it's enabled, in a fixed order. Tokens produced in a step can only
+	/// be consumed in the next step, while a transition may be disabled by
+	/// the transitions that consumed tokens before it in the same step.
+	/// Returns the amount of transitions fired, zero if the net is
+	/// deadlocked.
+	pub fn step(&mut self) -> usize {
+		let mut produced = vec![0; self.marking.len()];
+		let mut fired = 0;
+		for t in 0..self.transitions.len() {
+			if self.is_enabled(t) {
+				let transition = &self.transitions[t];
+				for (p, w) in transition.inputs.iter() {
+					if let Some(m) = self.marking[*p].as_mut() {
+						*m -= w;
+					}
+				}
+				for (p, w) in transition.outputs.iter() {
+					produced[*p] += w;
+				}
+				fired += 1;
+			}
+		}
+		for (m, p) in self.marking.iter_mut().zip(produced) {
+			if let Some(m) = m.as_mut() {
+				*m += p;
+			}
+		}
+		fired
+	}
+
+	/// Take up to `steps` steps, stopping early if the net deadlocks.
+	pub fn run(&mut self, steps: usize) -> Run {
+		let mut res = Run { steps: 0, fired: 0, deadlock: false };
+		while res.steps < steps {
+			let fired = self.step();
+			if fired == 0 {
+				res.deadlock = true;
+				break;
+			}
+			res.steps += 1;
+			res.fired += fired;
+		}
+		res
+	}
+
+	fn is_enabled(&self, t: usize) -> bool {
+		self.transitions[t].inputs.iter().all(|(p, w)| self.marking[*p].unwrap_or(0) >= *w)
+	}
+
+	fn fire_at(&mut self, t: usize) {
+		let transition = &self.transitions[t];
+		for (p, w) in transition.inputs.iter() {
+			if let Some(m) = self.marking[*p].as_mut() {
+				*m -= w;
+			}
+		}
+		for (p, w) in transition.outputs.iter() {
+			if let Some(m) = self.marking[*p].as_mut() {
+				*m += w;
+			}
+		}
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,633 @@
+//=============================================================================
+// PLANARITY
+//=============================================================================
+
+//! Planarity testing with the left-right algorithm. A depth first search
+//! orients the graph and every back edge is assigned to the left or the right
+//! side of the tree path it returns to, subject to constraints between the
+//! back edges that would otherwise cross. The graph is planar exactly when
+//! the constraints can be satisfied, in which case the sides give a
+//! combinatorial embedding: the clockwise order of the neighbors around every
+//! node. Non-planar graphs are reported with a Kuratowski subgraph, a
+//! subdivision of K5 or K3,3, found by deleting every edge whose removal
+//! keeps the rest non-planar.
+//!
+//! The direction of edges, loops and parallel edges don't affect planarity
+//! and are ignored.
+//!
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+/// Result of a planarity test.
+#[derive(Clone, Debug)]
+pub enum Planarity<K: Hash + Eq> {
+	/// A planar embedding of the graph.
+	Planar(Embedding<K>),
+	/// The edges of a subdivision of K5 or K3,3 contained in the graph.
+	NonPlanar(Vec<(K, K)>),
+}
+
+/// Combinatorial embedding of a planar graph given by the clockwise order of
+/// the neighbors around every node.
+#[derive(Clone, Debug)]
+pub struct Embedding<K: Hash + Eq> {
+	rotations: HashMap<K, Vec<K>>,
+}
+
+impl<K: Hash + Eq + Clone> Embedding<K> {
+	/// Neighbors of the node in clockwise order, or None if the node isn't in
+	/// the embedding.
+	pub fn rotation(&self, key: &K) -> Option<&[K]> {
+		self.rotations.get(key).map(|r| r.as_slice())
+	}
+
+	/// Faces of the embedding as the cyclic sequences of nodes around them.
+	/// Every edge is on the boundary of a face once from each side. A
+	/// connected graph with `n` nodes and `m` edges has `m - n + 2` faces.
+	pub fn faces(&self) -> Vec<Vec<K>> {
+		let mut position: HashMap<(&K, &K), usize> = HashMap::new();
+		for (v, rotation) in self.rotations.iter() {
+			for (i, u) in rotation.iter().enumerate() {
+				position.insert((v, u), i);
+			}
+		}
+		let mut visited: HashSet<(&K, &K)> = HashSet::new();
+		let mut res = Vec::new();
+		for (u, rotation) in self.rotations.iter() {
+			for v in rotation.iter() {
+				if visited.contains(&(u, v)) {
+					continue;
+				}
+				let mut face = Vec::new();
+				let (mut a, mut b) = (u, v);
+				while visited.insert((a, b)) {
+					face.push(a.clone());
+					// Continue along the edge that follows the one arrived
+					// through in the rotation of the next node.
+					let around = &self.rotations[b];
+					let next = &around[(position[&(b, a)] + 1) % around.len()];
+					a = b;
+					b = next;
+				}
+				res.push(face);
+			}
+		}
+		res
+	}
+}
+
+//=============================================================================
+
+/// # Planarity
+///
+/// Test whether the graph can be drawn in the plane without crossing edges.
+/// Returns an embedding of a planar graph or the edges of a Kuratowski
+/// subgraph of a non-planar graph. The test takes linear time, while finding
+/// the Kuratowski subgraph takes a test per edge.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::planarity::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..5 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..5 {
+///     for j in i + 1..5 {
+///         g.add_edge(i, j, Empty);
+///     }
+/// }
+///
+/// // K5 minus an edge is planar but K5 is not.
+/// g.del_edge(0, 1);
+/// assert!(is_planar(&g));
+/// g.add_edge(0, 1, Empty);
+/// match planarity(&g) {
+///     Planarity::NonPlanar(witness) => { assert!(witness.len() == 10) }
+///     Planarity::Planar(_) => { unreachable!() }
+/// }
+/// ```
+pub fn planarity<K, N, E, G>(graph: &G) -> Planarity<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let n = indexed.nodes.len();
+	let edges = simple_edges(&indexed);
+	match LeftRight::new(n, &edges).embed() {
+		Some(rotations) => {
+			let rotations = rotations
+				.into_iter()
+				.enumerate()
+				.map(|(v, r)| (indexed.key(v), r.into_iter().map(|w| indexed.key(w)).collect()))
+				.collect();
+			Planarity::Planar(Embedding { rotations })
+		}
+		None => {
+			let mut witness = edges;
+			let mut i = 0;
+			while i < witness.len() {
+				let removed = witness.remove(i);
+				if LeftRight::new(n, &witness).test() {
+					witness.insert(i, removed);
+					i += 1;
+				}
+			}
+			Planarity::NonPlanar(witness.into_iter().map(|(u, w)| (indexed.key(u), indexed.key(w))).collect())
+		}
+	}
+}
+
+/// True if the graph is planar. Doesn't build an embedding or a Kuratowski
+/// subgraph.
+pub fn is_planar<K, N, E, G>(graph: &G) -> bool
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	LeftRight::new(indexed.nodes.len(), &simple_edges(&indexed)).test()
+}
+
+//=============================================================================
+
+const NONE: usize = usize::MAX;
+
+// Edges between distinct nodes with every pair of nodes once.
+fn simple_edges<K, N, E>(indexed: &Indexed<K, N, E>) -> Vec<(usize, usize)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut seen = HashSet::new();
+	let mut res = Vec::new();
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for (w, _) in list.iter() {
+			if u != *w && seen.insert((u.min(*w), u.max(*w))) {
+				res.push((u, *w));
+			}
+		}
+	}
+	res
+}
+
+// Interval of return edges on one side, from the lowest to the highest.
+#[derive(Clone, Copy, Default)]
+struct Interval {
+	low: Option<usize>,
+	high: Option<usize>,
+}
+
+impl Interval {
+	fn empty(&self) -> bool {
+		self.low.is_none() && self.high.is_none()
+	}
+}
+
+#[derive(Clone, Copy, Default)]
+struct ConflictPair {
+	left: Interval,
+	right: Interval,
+}
+
+impl ConflictPair {
+	fn swap(&mut self) {
+		std::mem::swap(&mut self.left, &mut self.right);
+	}
+}
+
+// State of the left-right test. Every undirected edge is numbered and gets
+// the orientation it is first traversed in by the orienting search.
+struct LeftRight {
+	adjacency: Vec<Vec<(usize, usize)>>,
+	source: Vec<usize>,
+	target: Vec<usize>,
+	oriented: Vec<bool>,
+	out: Vec<Vec<usize>>,
+	height: Vec<usize>,
+	parent_edge: Vec<usize>,
+	roots: Vec<usize>,
+	lowpt: Vec<usize>,
+	lowpt2: Vec<usize>,
+	nesting_depth: Vec<i64>,
+	reference: Vec<usize>,
+	side: Vec<i64>,
+	lowpt_edge: Vec<usize>,
+	stack_bottom: Vec<usize>,
+	stack: Vec<ConflictPair>,
+}
+
+impl LeftRight {
+	fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+		let m = edges.len();
+		let mut adjacency = vec![Vec::new(); n];
+		for (e, (u, w)) in edges.iter().enumerate() {
+			adjacency[*u].push((*w, e));
+			adjacency[*w].push((*u, e));
+		}
+		LeftRight {
+			adjacency,
+			source: vec![NONE; m],
+			target: vec![NONE; m],
+			oriented: vec![false; m],
+			out: vec![Vec::new(); n],
+			height: vec![NONE; n],
+			parent_edge: vec![NONE; n],
+			roots: Vec::new(),
+			lowpt: vec![0; m],
+			lowpt2: vec![0; m],
+			nesting_depth: vec![0; m],
+			reference: vec![NONE; m],
+			side: vec![1; m],
+			lowpt_edge: vec![NONE; m],
+			stack_bottom: vec![0; m],
+			stack: Vec::new(),
+		}
+	}
+
+	// True if the graph is planar.
+	fn test(&mut self) -> bool {
+		let n = self.adjacency.len();
+		let m = self.source.len();
+		if n > 2 && m > 3 * n - 6 {
+			return false;
+		}
+		for v in 0..n {
+			if self.height[v] == NONE {
+				self.height[v] = 0;
+				self.roots.push(v);
+				self.orient(v);
+			}
+		}
+		for v in 0..n {
+			let depth = &self.nesting_depth;
+			self.out[v].sort_by_key(|e| depth[*e]);
+		}
+		for r in 0..self.roots.len() {
+			if !self.constrain(self.roots[r]) {
+				return false;
+			}
+		}
+		true
+	}
+
+	// Clockwise rotations of the neighbors of every node, or None if the
+	// graph isn't planar.
+	fn embed(mut self) -> Option<Vec<Vec<usize>>> {
+		if !self.test() {
+			return None;
+		}
+		let n = self.adjacency.len();
+		for e in 0..self.source.len() {
+			self.nesting_depth[e] *= self.sign(e);
+		}
+		let mut rotation = Rotation::new(n);
+		for v in 0..n {
+			let depth = &self.nesting_depth;
+			self.out[v].sort_by_key(|e| depth[*e]);
+			let mut previous = None;
+			for e in self.out[v].iter() {
+				rotation.insert_cw(v, self.target[*e], previous);
+				previous = Some(self.target[*e]);
+			}
+		}
+		let mut left_ref = vec![NONE; n];
+		let mut right_ref = vec![NONE; n];
+		let mut next = vec![0; n];
+		for root in self.roots.iter() {
+			let mut stack = vec![*root];
+			while let Some(v) = stack.pop() {
+				while next[v] < self.out[v].len() {
+					let e = self.out[v][next[v]];
+					let w = self.target[e];
+					next[v] += 1;
+					if self.parent_edge[w] == e {
+						rotation.insert_first(w, v);
+						left_ref[v] = w;
+						right_ref[v] = w;
+						stack.push(v);
+						stack.push(w);
+						break;
+					} else if self.side[e] == 1 {
+						rotation.insert_cw(w, v, Some(right_ref[w]));
+					} else {
+						rotation.insert_ccw(w, v, Some(left_ref[w]));
+						left_ref[w] = v;
+					}
+				}
+			}
+		}
+		Some((0..n).map(|v| rotation.order(v)).collect())
+	}
+
+	// Orient the edges with a depth first search and compute the lowpoints
+	// and nesting depths of the edges.
+	fn orient(&mut self, root: usize) {
+		let mut stack = vec![root];
+		let mut next = vec![0; self.adjacency.len()];
+		let mut resume = vec![false; self.source.len()];
+		while let Some(v) = stack.pop() {
+			let parent = self.parent_edge[v];
+			while next[v] < self.adjacency[v].len() {
+				let (w, e) = self.adjacency[v][next[v]];
+				if !resume[e] {
+					if self.oriented[e] {
+						next[v] += 1;
+						continue;
+					}
+					self.oriented[e] = true;
+					self.source[e] = v;
+					self.target[e] = w;
+					self.out[v].push(e);
+					self.lowpt[e] = self.height[v];
+					self.lowpt2[e] = self.height[v];
+					if self.height[w] == NONE {
+						self.parent_edge[w] = e;
+						self.height[w] = self.height[v] + 1;
+						resume[e] = true;
+						stack.push(v);
+						stack.push(w);
+						break;
+					}
+					self.lowpt[e] = self.height[w];
+				}
+				self.nesting_depth[e] = 2 * self.lowpt[e] as i64;
+				if self.lowpt2[e] < self.height[v] {
+					self.nesting_depth[e] += 1;
+				}
+				if parent != NONE {
+					if self.lowpt[e] < self.lowpt[parent] {
+						self.lowpt2[parent] = self.lowpt[parent].min(self.lowpt2[e]);
+						self.lowpt[parent] = self.lowpt[e];
+					} else if self.lowpt[e] > self.lowpt[parent] {
+						self.lowpt2[parent] = self.lowpt2[parent].min(self.lowpt[e]);
+					} else {
+						self.lowpt2[parent] = self.lowpt2[parent].min(self.lowpt2[e]);
+					}
+				}
+				next[v] += 1;
+			}
+		}
+	}
+
+	// Second depth first search that collects the constraints between the
+	// return edges in a stack of conflict pairs. Returns false if they can't
+	// be satisfied.
+	fn constrain(&mut self, root: usize) -> bool {
+		let mut stack = vec![root];
+		let mut next = vec![0; self.adjacency.len()];
+		let mut resume = vec![false; self.source.len()];
+		while let Some(v) = stack.pop() {
+			let parent = self.parent_edge[v];
+			let mut descended = false;
+			while next[v] < self.out[v].len() {
+				let e = self.out[v][next[v]];
+				let w = self.target[e];
+				if !resume[e] {
+					self.stack_bottom[e] = self.stack.len();
+					if self.parent_edge[w] == e {
+						resume[e] = true;
+						descended = true;
+						stack.push(v);
+						stack.push(w);
+						break;
+					}
+					self.lowpt_edge[e] = e;
+					self.stack.push(ConflictPair { left: Interval::default(), right: Interval { low: Some(e), high: Some(e) } });
+				}
+				if self.lowpt[e] < self.height[v] {
+					if e == self.out[v][0] {
+						self.lowpt_edge[parent] = self.lowpt_edge[e];
+					} else if !self.add_constraints(e, parent) {
+						return false;
+					}
+				}
+				next[v] += 1;
+			}
+			if !descended && parent != NONE {
+				self.remove_back_edges(parent);
+			}
+		}
+		true
+	}
+
+	fn conflicting(&self, interval: &Interval, e: usize) -> bool {
+		match interval.high {
+			Some(high) => { self.lowpt[high] > self.lowpt[e] }
+			None => { false }
+		}
+	}
+
+	fn lowest(&self, pair: &ConflictPair) -> usize {
+		match (pair.left.low, pair.right.low) {
+			(Some(l), Some(r)) => { self.lowpt[l].min(self.lowpt[r]) }
+			(Some(l), None) => { self.lowpt[l] }
+			(None, Some(r)) => { self.lowpt[r] }
+			(None, None) => { NONE }
+		}
+	}
+
+	fn add_constraints(&mut self, e: usize, parent: usize) -> bool {
+		let mut p = ConflictPair::default();
+		// Merge the return edges of e into the right interval.
+		while let Some(mut q) = self.stack.pop() {
+			if !q.left.empty() {
+				q.swap();
+			}
+			if !q.left.empty() {
+				return false;
+			}
+			let low = q.right.low.unwrap_or(NONE);
+			if low != NONE && self.lowpt[low] > self.lowpt[parent] {
+				if p.right.empty() {
+					p.right = q.right;
+				} else if let Some(l) = p.right.low {
+					self.reference[l] = q.right.high.unwrap_or(NONE);
+				}
+				p.right.low = q.right.low;
+			} else if low != NONE {
+				self.reference[low] = self.lowpt_edge[parent];
+			}
+			if self.stack.len() == self.stack_bottom[e] {
+				break;
+			}
+		}
+		// Merge the conflicting return edges of the earlier siblings of e into
+		// the left interval.
+		while let Some(top) = self.stack.last() {
+			if !self.conflicting(&top.left, e) && !self.conflicting(&top.right, e) {
+				break;
+			}
+			let mut q = self.stack.pop().unwrap_or_default();
+			if self.conflicting(&q.right, e) {
+				q.swap();
+			}
+			if self.conflicting(&q.right, e) {
+				return false;
+			}
+			if let Some(l) = p.right.low {
+				self.reference[l] = q.right.high.unwrap_or(NONE);
+			}
+			if q.right.low.is_some() {
+				p.right.low = q.right.low;
+			}
+			if p.left.empty() {
+				p.left = q.left;
+			} else if let Some(l) = p.left.low {
+				self.reference[l] = q.left.high.unwrap_or(NONE);
+			}
+			p.left.low = q.left.low;
+		}
+		if !(p.left.empty() && p.right.empty()) {
+			self.stack.push(p);
+		}
+		true
+	}
+
+	// Remove the back edges that return to the source of the tree edge once
+	// its subtree has been searched.
+	fn remove_back_edges(&mut self, e: usize) {
+		let u = self.source[e];
+		while let Some(top) = self.stack.last() {
+			if self.lowest(top) != self.height[u] {
+				break;
+			}
+			if let Some(l) = top.left.low {
+				self.side[l] = -1;
+			}
+			self.stack.pop();
+		}
+		if let Some(mut p) = self.stack.pop() {
+			while let Some(high) = p.left.high.filter(|h| self.target[*h] == u) {
+				p.left.high = Some(self.reference[high]).filter(|r| *r != NONE);
+			}
+			if let (None, Some(low)) = (p.left.high, p.left.low) {
+				self.reference[low] = p.right.low.unwrap_or(NONE);
+				self.side[low] = -1;
+				p.left.low = None;
+			}
+			while let Some(high) = p.right.high.filter(|h| self.target[*h] == u) {
+				p.right.high = Some(self.reference[high]).filter(|r| *r != NONE);
+			}
+			if let (None, Some(low)) = (p.right.high, p.right.low) {
+				self.reference[low] = p.left.low.unwrap_or(NONE);
+				self.side[low] = -1;
+				p.right.low = None;
+			}
+			self.stack.push(p);
+		}
+		if self.lowpt[e] < self.height[u] {
+			if let Some(top) = self.stack.last() {
+				let (hl, hr) = (top.left.high, top.right.high);
+				self.reference[e] = match (hl, hr) {
+					(Some(l), Some(r)) if self.lowpt[l] > self.lowpt[r] => { l }
+					(Some(l), None) => { l }
+					(_, r) => { r.unwrap_or(NONE) }
+				};
+			}
+		}
+	}
+
+	// Resolve the side of an edge relative to the edges it refers to.
+	fn sign(&mut self, e: usize) -> i64 {
+		let mut stack = vec![e];
+		let mut old = HashMap::new();
+		while let Some(f) = stack.pop() {
+			let r = self.reference[f];
+			if r != NONE {
+				stack.push(f);
+				stack.push(r);
+				old.insert(f, r);
+				self.reference[f] = NONE;
+			} else if let Some(r) = old.get(&f) {
+				self.side[f] *= self.side[*r];
+			}
+		}
+		self.side[e]
+	}
+}
+
+// Cyclic order of half edges around every node as clockwise and counter
+// clockwise successors, with the first neighbor of every node.
+struct Rotation {
+	cw: HashMap<(usize, usize), usize>,
+	ccw: HashMap<(usize, usize), usize>,
+	first: Vec<Option<usize>>,
+}
+
+impl Rotation {
+	fn new(n: usize) -> Self {
+		Rotation { cw: HashMap::new(), ccw: HashMap::new(), first: vec![None; n] }
+	}
+
+	// Insert w clockwise after the reference in the rotation of v.
+	fn insert_cw(&mut self, v: usize, w: usize, reference: Option<usize>) {
+		match reference {
+			None => {
+				self.cw.insert((v, w), w);
+				self.ccw.insert((v, w), w);
+				self.first[v] = Some(w);
+			}
+			Some(r) => {
+				let after = self.cw[&(v, r)];
+				self.cw.insert((v, r), w);
+				self.cw.insert((v, w), after);
+				self.ccw.insert((v, after), w);
+				self.ccw.insert((v, w), r);
+			}
+		}
+	}
+
+	// Insert w counter clockwise before the reference in the rotation of v.
+	fn insert_ccw(&mut self, v: usize, w: usize, reference: Option<usize>) {
+		match reference {
+			None => { self.insert_cw(v, w, None) }
+			Some(r) => {
+				let before = self.ccw[&(v, r)];
+				self.insert_cw(v, w, Some(before));
+				if self.first[v] == Some(r) {
+					self.first[v] = Some(w);
+				}
+			}
+		}
+	}
+
+	// Insert w as the first neighbor of v.
+	fn insert_first(&mut self, v: usize, w: usize) {
+		let first = self.first[v];
+		self.insert_ccw(v, w, first);
+	}
+
+	fn order(&self, v: usize) -> Vec<usize> {
+		let mut res = Vec::new();
+		if let Some(first) = self.first[v] {
+			let mut w = first;
+			loop {
+				res.push(w);
+				w = self.cw[&(v, w)];
+				if w == first {
+					break;
+				}
+			}
+		}
+		res
+	}
+}
+
+//=============================================================================
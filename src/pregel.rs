@@ -0,0 +1,99 @@
+//=============================================================================
+// PREGEL
+//=============================================================================
+
+//! Vertex centric computations in bulk synchronous supersteps. In every
+//! superstep the `compute` closure runs in parallel for every node that
+//! received messages in the previous superstep, gets the messages and
+//! returns messages addressed to other nodes by key. The state of the
+//! computation is kept in the node data, which `compute` is free to load and
+//! store. Messages sent in a superstep are delivered at the start of the next
+//! one, so the result doesn't depend on the order in which nodes are
+//! computed. Every node is computed in the first superstep, and the
+//! computation ends once no messages are sent.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// # Pregel
+///
+/// Run supersteps until no messages are sent or `max_supersteps` supersteps
+/// have been run. The closure gets the number of the superstep starting from
+/// zero, the node and the messages sent to it. Messages to keys that can't
+/// be found are dropped. Returns the amount of supersteps run.
+///
+/// # Examples
+///
+/// Single source shortest paths where the node data is the distance from
+/// node 1.
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::pregel::*;
+///
+/// let mut g = Digraph::<u32, f64, f64>::new();
+/// for i in 1..5 {
+///     g.add_node(i, f64::INFINITY);
+/// }
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 3, 1.0);
+/// g.add_edge(1, 3, 5.0);
+/// g.add_edge(3, 4, 1.0);
+///
+/// pregel(&g, 100, |superstep, node, messages: Vec<f64>| {
+///     let start = if superstep == 0 && *node.key() == 1 { 0.0 } else { f64::INFINITY };
+///     let best = messages.into_iter().fold(start, f64::min);
+///     if best >= node.load() {
+///         return Vec::new();
+///     }
+///     node.store(best);
+///     node.outbound().iter().map(|e| (e.target().key().clone(), best + e.load())).collect()
+/// });
+///
+/// assert!(g.get_node(4).unwrap().load() == 3.0);
+/// ```
+pub fn pregel<K, N, E, G, M, F>(graph: &G, max_supersteps: usize, compute: F) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	M: Send,
+	F: Fn(usize, &Arc<Node<K, N, E>>, Vec<M>) -> Vec<(K, M)> + Sync + Send,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let mut inboxes: Vec<Vec<M>> = indexed.nodes.iter().map(|_| Vec::new()).collect();
+	let mut superstep = 0;
+	let _span = trace_span!("pregel", nodes = indexed.nodes.len());
+	while superstep < max_supersteps {
+		let outgoing: Vec<(K, M)> = std::mem::take(&mut inboxes)
+			.into_par_iter()
+			.enumerate()
+			.filter(|(_, messages)| superstep == 0 || !messages.is_empty())
+			.flat_map_iter(|(i, messages)| compute(superstep, &indexed.nodes[i], messages))
+			.collect();
+		trace_event!("superstep", superstep = superstep, messages = outgoing.len());
+		superstep += 1;
+		if outgoing.is_empty() {
+			break;
+		}
+		inboxes = indexed.nodes.iter().map(|_| Vec::new()).collect();
+		for (key, message) in outgoing {
+			if let Some(i) = indexed.index.get(&key) {
+				inboxes[*i].push(message);
+			}
+		}
+	}
+	superstep
+}
+
+//=============================================================================
@@ -0,0 +1,208 @@
+//=============================================================================
+// PRINTER
+//=============================================================================
+
+//! Configurable formatting of whole graphs. A `Printer` borrows the graph and
+//! implements `Display`, so it can be printed, logged or turned into a string
+//! with `to_string`. Large graphs can be truncated to their first nodes and
+//! edges, and the data of nodes and edges can be left out.
+//!
+use crate::collections::Graph;
+use std::{
+	fmt::{Debug, Display, Formatter},
+	hash::Hash,
+	marker::PhantomData,
+};
+
+/// Layout of the printed graph.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+	/// Graphviz dot with one statement per node and per edge.
+	Dot,
+	/// One line per node listing the node and its outbound neighbors, meant
+	/// for logs.
+	Lines,
+}
+
+/// Formatter for a graph created with `Graph::display`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::printer::Layout;
+///
+/// let mut g = Digraph::<u32, &str, f64>::new();
+/// g.add_node(1, "a");
+/// g.add_node(2, "b");
+/// g.add_edge(1, 2, 0.5);
+///
+/// let one = g.display().with_data(false).max_nodes(1).to_string();
+/// assert!(one.starts_with("digraph {\n\t"));
+/// assert!(one.contains("// 1 more nodes"));
+///
+/// let line = g.display().layout(Layout::Lines).with_edges(false).to_string();
+/// assert!(line.lines().count() == 2 && line.contains("2: b"));
+/// ```
+pub struct Printer<'a, K, N, E, G> {
+	graph: &'a G,
+	data: bool,
+	edges: bool,
+	max_nodes: usize,
+	max_edges: usize,
+	layout: Layout,
+	types: PhantomData<(K, N, E)>,
+}
+
+impl<'a, K, N, E, G> Printer<'a, K, N, E, G>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	/// Print every node and edge with their data as dot.
+	pub fn new(graph: &'a G) -> Self {
+		Printer {
+			graph,
+			data: true,
+			edges: true,
+			max_nodes: usize::MAX,
+			max_edges: usize::MAX,
+			layout: Layout::Dot,
+			types: PhantomData,
+		}
+	}
+
+	/// Print the data of nodes and edges.
+	pub fn with_data(mut self, data: bool) -> Self {
+		self.data = data;
+		self
+	}
+
+	/// Print edges.
+	pub fn with_edges(mut self, edges: bool) -> Self {
+		self.edges = edges;
+		self
+	}
+
+	/// Print at most this many nodes and only the edges leaving them.
+	pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+		self.max_nodes = max_nodes;
+		self
+	}
+
+	/// Print at most this many edges.
+	pub fn max_edges(mut self, max_edges: usize) -> Self {
+		self.max_edges = max_edges;
+		self
+	}
+
+	/// Layout of the output.
+	pub fn layout(mut self, layout: Layout) -> Self {
+		self.layout = layout;
+		self
+	}
+
+	fn dot(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let (name, sign) = match G::directed() {
+			true => { ("digraph", "->") }
+			false => { ("graph", "--") }
+		};
+		let nodes = self.graph.nodes();
+		let shown = &nodes[..nodes.len().min(self.max_nodes)];
+		writeln!(f, "{} {{", name)?;
+		for node in shown.iter() {
+			match self.data {
+				true => { writeln!(f, "\t{}", node)? }
+				false => { writeln!(f, "\t{}", node.key())? }
+			}
+		}
+		if shown.len() < nodes.len() {
+			writeln!(f, "\t// {} more nodes", nodes.len() - shown.len())?;
+		}
+		if self.edges {
+			let mut count = 0;
+			let mut hidden = 0;
+			for node in shown.iter() {
+				for edge in node.outbound().iter() {
+					if count == self.max_edges {
+						hidden += 1;
+						continue;
+					}
+					count += 1;
+					match self.data {
+						true => { writeln!(f, "\t{} {} {} [label = \"{}\"]", node.key(), sign, edge.target().key(), edge.load())? }
+						false => { writeln!(f, "\t{} {} {}", node.key(), sign, edge.target().key())? }
+					}
+				}
+			}
+			if hidden > 0 {
+				writeln!(f, "\t// {} more edges", hidden)?;
+			}
+		}
+		write!(f, "}}")
+	}
+
+	fn lines(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let sign = match G::directed() {
+			true => { "->" }
+			false => { "--" }
+		};
+		let nodes = self.graph.nodes();
+		let shown = &nodes[..nodes.len().min(self.max_nodes)];
+		let mut count = 0;
+		for (i, node) in shown.iter().enumerate() {
+			if i > 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}", node.key())?;
+			if self.data {
+				write!(f, ": {}", node.load())?;
+			}
+			if !self.edges {
+				continue;
+			}
+			let outbound = node.outbound();
+			let take = outbound.len().min(self.max_edges - count);
+			count += take;
+			if take > 0 {
+				write!(f, " {}", sign)?;
+			}
+			for (j, edge) in outbound[..take].iter().enumerate() {
+				let separator = if j == 0 { " " } else { ", " };
+				match self.data {
+					true => { write!(f, "{}{} ({})", separator, edge.target().key(), edge.load())? }
+					false => { write!(f, "{}{}", separator, edge.target().key())? }
+				}
+			}
+			if take < outbound.len() {
+				write!(f, " ... {} more", outbound.len() - take)?;
+			}
+		}
+		if shown.len() < nodes.len() {
+			if !shown.is_empty() {
+				writeln!(f)?;
+			}
+			write!(f, "... {} more nodes", nodes.len() - shown.len())?;
+		}
+		Ok(())
+	}
+}
+
+impl<'a, K, N, E, G> Display for Printer<'a, K, N, E, G>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self.layout {
+			Layout::Dot => { self.dot(f) }
+			Layout::Lines => { self.lines(f) }
+		}
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,246 @@
+//=============================================================================
+// GRAPH PRODUCTS
+//=============================================================================
+
+//! Products combine two graphs into a graph with a node for every pair of a
+//! node of the first graph and a node of the second. In the Cartesian
+//! product a pair is connected to the pairs that differ from it by an edge of
+//! one graph while the other node stays the same, so the product of two paths
+//! is a grid. In the tensor product both nodes move along an edge at once,
+//! which models two systems stepping in lockstep. The strong product has the
+//! edges of both. The `node` closure gives the key and the data of the node
+//! of a pair and the `edge` closure the data of an edge from the edges it
+//! follows in the first and the second graph, at least one of which is
+//! given. Both graphs should have the same direction, and the type of the
+//! product is chosen by the caller.
+//!
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use std::{
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// # Cartesian Product
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::product::*;
+///
+/// let mut path = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..3 {
+///     path.add_node(i, Empty);
+/// }
+/// path.add_edge(0, 1, Empty);
+/// path.add_edge(1, 2, Empty);
+///
+/// // A 3 by 3 grid keyed by row * 3 + column.
+/// let grid: Ungraph<u32, Empty, Empty> = cartesian_product(
+///     &path,
+///     &path,
+///     |a, b| (a.key() * 3 + b.key(), Empty),
+///     |_, _| Empty,
+/// );
+/// assert!(grid.node_count() == 9);
+/// assert!(grid.edge_count() == 12);
+/// ```
+pub fn cartesian_product<K1, N1, E1, G1, K2, N2, E2, G2, K, N, E, G, FN, FE>(a: &G1, b: &G2, node: FN, edge: FE) -> G
+where
+	K1: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N1: Clone + Debug + Display + Sync + Send,
+	E1: Clone + Debug + Display + Sync + Send,
+	G1: Graph<K1, N1, E1>,
+	K2: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N2: Clone + Debug + Display + Sync + Send,
+	E2: Clone + Debug + Display + Sync + Send,
+	G2: Graph<K2, N2, E2>,
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn (&Arc<Node<K1, N1, E1>>, &Arc<Node<K2, N2, E2>>) -> (K, N),
+	FE: Fn (Option<&Arc<Edge<K1, N1, E1>>>, Option<&Arc<Edge<K2, N2, E2>>>) -> E,
+{
+	product(a, b, Kind::Cartesian, node, edge)
+}
+
+/// # Tensor Product
+///
+/// Also known as the direct or categorical product.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::product::*;
+///
+/// let mut cycle = Digraph::<u32, Empty, Empty>::new();
+/// for i in 0..3 {
+///     cycle.add_node(i, Empty);
+/// }
+/// for i in 0..3 {
+///     cycle.add_edge(i, (i + 1) % 3, Empty);
+/// }
+///
+/// // Two counters stepping in lockstep never leave the diagonal they start on.
+/// let g: Digraph<u32, Empty, Empty> = tensor_product(
+///     &cycle,
+///     &cycle,
+///     |a, b| (a.key() * 3 + b.key(), Empty),
+///     |_, _| Empty,
+/// );
+/// assert!(g.edge_count() == 9);
+/// assert!(g.get_edge(0, 4).is_some() && g.get_edge(0, 1).is_none());
+/// ```
+pub fn tensor_product<K1, N1, E1, G1, K2, N2, E2, G2, K, N, E, G, FN, FE>(a: &G1, b: &G2, node: FN, edge: FE) -> G
+where
+	K1: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N1: Clone + Debug + Display + Sync + Send,
+	E1: Clone + Debug + Display + Sync + Send,
+	G1: Graph<K1, N1, E1>,
+	K2: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N2: Clone + Debug + Display + Sync + Send,
+	E2: Clone + Debug + Display + Sync + Send,
+	G2: Graph<K2, N2, E2>,
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn (&Arc<Node<K1, N1, E1>>, &Arc<Node<K2, N2, E2>>) -> (K, N),
+	FE: Fn (Option<&Arc<Edge<K1, N1, E1>>>, Option<&Arc<Edge<K2, N2, E2>>>) -> E,
+{
+	product(a, b, Kind::Tensor, node, edge)
+}
+
+/// # Strong Product
+///
+/// The union of the Cartesian and the tensor product.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::product::*;
+///
+/// let mut path = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..3 {
+///     path.add_node(i, Empty);
+/// }
+/// path.add_edge(0, 1, Empty);
+/// path.add_edge(1, 2, Empty);
+///
+/// // The moves of a king on a 3 by 3 board.
+/// let board: Ungraph<u32, Empty, Empty> = strong_product(
+///     &path,
+///     &path,
+///     |a, b| (a.key() * 3 + b.key(), Empty),
+///     |_, _| Empty,
+/// );
+/// assert!(board.edge_count() == 12 + 8);
+/// ```
+pub fn strong_product<K1, N1, E1, G1, K2, N2, E2, G2, K, N, E, G, FN, FE>(a: &G1, b: &G2, node: FN, edge: FE) -> G
+where
+	K1: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N1: Clone + Debug + Display + Sync + Send,
+	E1: Clone + Debug + Display + Sync + Send,
+	G1: Graph<K1, N1, E1>,
+	K2: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N2: Clone + Debug + Display + Sync + Send,
+	E2: Clone + Debug + Display + Sync + Send,
+	G2: Graph<K2, N2, E2>,
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn (&Arc<Node<K1, N1, E1>>, &Arc<Node<K2, N2, E2>>) -> (K, N),
+	FE: Fn (Option<&Arc<Edge<K1, N1, E1>>>, Option<&Arc<Edge<K2, N2, E2>>>) -> E,
+{
+	product(a, b, Kind::Strong, node, edge)
+}
+
+//=============================================================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+	Cartesian,
+	Tensor,
+	Strong,
+}
+
+fn product<K1, N1, E1, G1, K2, N2, E2, G2, K, N, E, G, FN, FE>(a: &G1, b: &G2, kind: Kind, node: FN, edge: FE) -> G
+where
+	K1: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N1: Clone + Debug + Display + Sync + Send,
+	E1: Clone + Debug + Display + Sync + Send,
+	G1: Graph<K1, N1, E1>,
+	K2: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N2: Clone + Debug + Display + Sync + Send,
+	E2: Clone + Debug + Display + Sync + Send,
+	G2: Graph<K2, N2, E2>,
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn (&Arc<Node<K1, N1, E1>>, &Arc<Node<K2, N2, E2>>) -> (K, N),
+	FE: Fn (Option<&Arc<Edge<K1, N1, E1>>>, Option<&Arc<Edge<K2, N2, E2>>>) -> E,
+{
+	// Every edge appears once in the outbound adjacency of its source.
+	let a = Indexed::new(a.nodes(), Direction::Outbound);
+	let b = Indexed::new(b.nodes(), Direction::Outbound);
+	let m = b.nodes.len();
+	let mut g = G::new();
+	let mut keys = Vec::with_capacity(a.nodes.len() * m);
+	for x in a.nodes.iter() {
+		for y in b.nodes.iter() {
+			let (key, data) = node(x, y);
+			g.add_node(key.clone(), data);
+			keys.push(key);
+		}
+	}
+	let pair = |u: usize, v: usize| keys[u * m + v].clone();
+	if kind != Kind::Tensor {
+		for (u, adjacent) in a.adjacency.iter().enumerate() {
+			for &(w, e) in adjacent.iter() {
+				for v in 0..m {
+					g.add_edge(pair(u, v), pair(w, v), edge(Some(&a.edges[e]), None));
+				}
+			}
+		}
+		for (v, adjacent) in b.adjacency.iter().enumerate() {
+			for &(x, f) in adjacent.iter() {
+				for u in 0..a.nodes.len() {
+					g.add_edge(pair(u, v), pair(u, x), edge(None, Some(&b.edges[f])));
+				}
+			}
+		}
+	}
+	if kind != Kind::Cartesian {
+		for (u, adjacent) in a.adjacency.iter().enumerate() {
+			for &(w, e) in adjacent.iter() {
+				for (v, other) in b.adjacency.iter().enumerate() {
+					for &(x, f) in other.iter() {
+						let data = edge(Some(&a.edges[e]), Some(&b.edges[f]));
+						// Undirected edges can also be followed in opposite
+						// orientations, unless either is a loop.
+						if !G1::directed() && u != w && v != x {
+							g.add_edge(pair(u, x), pair(w, v), data.clone());
+						}
+						g.add_edge(pair(u, v), pair(w, x), data);
+					}
+				}
+			}
+		}
+	}
+	g
+}
+
+//=============================================================================
@@ -0,0 +1,323 @@
+//=============================================================================
+// TRIPLE PATTERNS
+//=============================================================================
+
+//! Queries over typed graphs in the style of the basic graph patterns of
+//! SPARQL. A query is a list of triple patterns of a subject, an edge tag
+//! and an object, each either a fixed value or a variable. An edge matches
+//! a pattern if it goes from the subject to the object and has the tag
+//! among its tags, once for every tag a variable binds to, and a binding of
+//! the variables is a result if every pattern has a matching edge.
+//! Variables shared by patterns join them. Patterns are matched in the
+//! order that binds the most of every next pattern first, so that every
+//! pattern after the first usually starts from a known node. In undirected
+//! graphs an edge matches in either orientation. Nodes and tags are bound
+//! separately, so a variable used for both binds two values.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::core::Empty;
+//! use fastgraph::query::*;
+//! use fastgraph::typed::*;
+//!
+//! let mut g = Digraph::<&str, Empty, Typed<&str, Empty>>::new();
+//! for key in ["ada", "bob", "cy", "acme"] {
+//!     g.add_node(key, Empty);
+//! }
+//! g.add_edge("ada", "bob", Typed::new("knows", Empty));
+//! g.add_edge("bob", "cy", Typed::new("knows", Empty));
+//! g.add_edge("cy", "acme", Typed::new("works_at", Empty));
+//!
+//! // Who knows someone who knows someone working somewhere?
+//! let query = Query::parse("?a knows ?b . ?b knows ?c . ?c works_at ?d", |k| g.get_node(k).map(|_| k), Some).unwrap();
+//! let results = query.run(&g);
+//! assert!(results.len() == 1);
+//! assert!(results[0].get("a") == Some(&"ada") && results[0].get("d") == Some(&"acme"));
+//! ```
+use crate::collections::Graph;
+use crate::core::*;
+use crate::typed::Tagged;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// A fixed value or a variable of a triple pattern.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Slot<T> {
+	Var(String),
+	Value(T),
+}
+
+impl<T> Slot<T> {
+	/// A variable with a name.
+	pub fn var(name: &str) -> Self {
+		Slot::Var(name.to_string())
+	}
+
+	fn name(&self) -> Option<&str> {
+		match self {
+			Slot::Var(var) => { Some(var) }
+			Slot::Value(_) => { None }
+		}
+	}
+}
+
+/// Values of the variables in one result of a query.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binding<K, T> {
+	pub nodes: HashMap<String, K>,
+	pub tags: HashMap<String, T>,
+}
+
+impl<K, T> Binding<K, T> {
+	/// The node bound to a variable.
+	pub fn get(&self, var: &str) -> Option<&K> {
+		self.nodes.get(var)
+	}
+
+	/// The tag bound to a variable.
+	pub fn tag(&self, var: &str) -> Option<&T> {
+		self.tags.get(var)
+	}
+}
+
+/// A pattern of a subject, an edge tag and an object.
+pub type Pattern<K, T> = (Slot<K>, Slot<T>, Slot<K>);
+
+/// A list of triple patterns joined by their shared variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Query<K, T> {
+	pub patterns: Vec<Pattern<K, T>>,
+}
+
+impl<K, T> Default for Query<K, T> {
+	fn default() -> Self {
+		Query { patterns: Vec::new() }
+	}
+}
+
+impl<K, T> Query<K, T>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	T: PartialEq + Clone,
+{
+	/// A query without patterns, which has one empty result.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add a pattern.
+	pub fn pattern(mut self, subject: Slot<K>, tag: Slot<T>, object: Slot<K>) -> Self {
+		self.patterns.push((subject, tag, object));
+		self
+	}
+
+	/// Parse patterns of three terms separated by white space, with a dot
+	/// between patterns. Terms starting with `?` are variables and the
+	/// closures map other terms into nodes and tags, returning None for
+	/// unknown terms.
+	pub fn parse<'a, FK, FT>(text: &'a str, node: FK, tag: FT) -> Result<Self, String>
+	where
+		FK: Fn (&'a str) -> Option<K>,
+		FT: Fn (&'a str) -> Option<T>,
+	{
+		let mut query = Query::new();
+		let mut terms: Vec<&str> = Vec::new();
+		for token in text.split_whitespace().chain(std::iter::once(".")) {
+			let end = token == "." || (terms.len() == 2 && token.len() > 1 && token.ends_with('.'));
+			let token = token.strip_suffix('.').filter(|_| token.len() > 1 && terms.len() == 2).unwrap_or(token);
+			if token != "." {
+				terms.push(token);
+			}
+			if !end {
+				continue;
+			}
+			match terms.len() {
+				0 => { continue }
+				3 => {}
+				_ => { return Err(format!("expected three terms in pattern {}", query.patterns.len() + 1)) }
+			}
+			let slot = |term: &'a str| match term.strip_prefix('?') {
+				Some(var) => { Ok(Slot::var(var)) }
+				None => { node(term).map(Slot::Value).ok_or(format!("unknown term {}", term)) }
+			};
+			let subject = slot(terms[0])?;
+			let predicate = match terms[1].strip_prefix('?') {
+				Some(var) => { Slot::var(var) }
+				None => { Slot::Value(tag(terms[1]).ok_or(format!("unknown term {}", terms[1]))?) }
+			};
+			let object = slot(terms[2])?;
+			query.patterns.push((subject, predicate, object));
+			terms.clear();
+		}
+		Ok(query)
+	}
+
+	/// Every binding of the variables for which all the patterns match.
+	pub fn run<N, E, G>(&self, graph: &G) -> Vec<Binding<K, T>>
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send + Tagged<T>,
+		G: Graph<K, N, E>,
+	{
+		let mut res = vec![Binding { nodes: HashMap::new(), tags: HashMap::new() }];
+		for pattern in self.order() {
+			let mut next = Vec::new();
+			for binding in res.iter() {
+				self.extend(graph, pattern, binding, &mut next);
+			}
+			res = next;
+			if res.is_empty() {
+				break;
+			}
+		}
+		res
+	}
+
+	// The patterns in an order where every next pattern has the most fixed
+	// values and variables bound by earlier patterns.
+	fn order(&self) -> Vec<&Pattern<K, T>> {
+		let mut bound: Vec<&str> = Vec::new();
+		let mut remaining: Vec<&Pattern<K, T>> = self.patterns.iter().collect();
+		let mut res = Vec::new();
+		while !remaining.is_empty() {
+			let known = |var: Option<&str>, weight: usize| match var {
+				Some(var) if !bound.contains(&var) => { 0 }
+				_ => { weight }
+			};
+			// Known endpoints narrow the edges down more than known tags.
+			let score = |(s, t, o): &Pattern<K, T>| known(s.name(), 2) + known(o.name(), 2) + known(t.name(), 1);
+			let best = (0..remaining.len()).rev().max_by_key(|i| score(remaining[*i])).unwrap();
+			let pattern = remaining.remove(best);
+			bound.extend(pattern.0.name().into_iter().chain(pattern.1.name()).chain(pattern.2.name()));
+			res.push(pattern);
+		}
+		res
+	}
+
+	// Extend a binding with every match of a pattern.
+	fn extend<N, E, G>(&self, graph: &G, pattern: &Pattern<K, T>, binding: &Binding<K, T>, res: &mut Vec<Binding<K, T>>)
+	where
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send + Tagged<T>,
+		G: Graph<K, N, E>,
+	{
+		let (subject, tag, object) = pattern;
+		let value = |slot: &Slot<K>| match slot {
+			Slot::Var(var) => { binding.nodes.get(var).cloned() }
+			Slot::Value(key) => { Some(key.clone()) }
+		};
+		// Candidate edges as subject, edge and object.
+		let mut candidates: Vec<Candidate<K, N, E>> = Vec::new();
+		match (value(subject), value(object)) {
+			(Some(key), _) => {
+				if let Some(node) = graph.get_node(key) {
+					candidates = incident(&node, G::directed());
+				}
+			}
+			(None, Some(key)) => {
+				if let Some(node) = graph.get_node(key) {
+					candidates = match G::directed() {
+						true => {
+							node.inbound()
+								.iter()
+								.filter_map(|e| e.upgrade())
+								.map(|e| (e.source(), e, node.clone()))
+								.collect()
+						}
+						false => {
+							incident(&node, false)
+								.into_iter()
+								.map(|(s, e, o)| (o, e, s))
+								.collect()
+						}
+					};
+				}
+			}
+			(None, None) => {
+				for node in graph.nodes() {
+					candidates.extend(incident(&node, G::directed()));
+				}
+			}
+		}
+		for (s, edge, o) in candidates {
+			let mut nodes = binding.nodes.clone();
+			if !bind(&mut nodes, subject, s.key()) || !bind(&mut nodes, object, o.key()) {
+				continue;
+			}
+			edge.with(|data| {
+				for t in data.tags() {
+					let mut tags = binding.tags.clone();
+					if bind_tag(&mut tags, tag, t) {
+						res.push(Binding { nodes: nodes.clone(), tags });
+					}
+				}
+			});
+		}
+	}
+}
+
+//=============================================================================
+
+// An edge with the nodes it matches as the subject and the object.
+type Candidate<K, N, E> = (Arc<Node<K, N, E>>, Arc<Edge<K, N, E>>, Arc<Node<K, N, E>>);
+
+// The edges of a node as the subject, which in an undirected graph are all
+// its edges with loops once.
+fn incident<K, N, E>(node: &Arc<Node<K, N, E>>, directed: bool) -> Vec<Candidate<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut res: Vec<Candidate<K, N, E>> = node.outbound().iter().map(|e| (node.clone(), e.clone(), e.target())).collect();
+	if !directed {
+		for e in node.inbound().iter().filter_map(|e| e.upgrade()) {
+			let source = e.source();
+			if source.key() != node.key() {
+				res.push((node.clone(), e, source));
+			}
+		}
+	}
+	res
+}
+
+// Bind a node to a slot, or check that it matches the value of the slot.
+fn bind<K: Eq + Clone>(nodes: &mut HashMap<String, K>, slot: &Slot<K>, key: &K) -> bool {
+	match slot {
+		Slot::Value(value) => { value == key }
+		Slot::Var(var) => {
+			match nodes.get(var) {
+				Some(value) => { value == key }
+				None => {
+					nodes.insert(var.clone(), key.clone());
+					true
+				}
+			}
+		}
+	}
+}
+
+fn bind_tag<T: PartialEq + Clone>(tags: &mut HashMap<String, T>, slot: &Slot<T>, tag: &T) -> bool {
+	match slot {
+		Slot::Value(value) => { value == tag }
+		Slot::Var(var) => {
+			match tags.get(var) {
+				Some(value) => { value == tag }
+				None => {
+					tags.insert(var.clone(), tag.clone());
+					true
+				}
+			}
+		}
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,261 @@
+//=============================================================================
+// RELIABILITY
+//=============================================================================
+
+//! Monte Carlo estimates of how a network holds up when its edges fail at
+//! random. Every trial keeps each edge with the probability that it doesn't
+//! fail, given by a closure over the edge, and measures the surviving graph.
+//! Trials run in parallel, each with its own generator seeded from the
+//! generator of the caller, so the estimates only depend on the state of
+//! that generator. The standard error of an estimate shrinks with the square
+//! root of the amount of trials.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::VecDeque,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Component sizes of the surviving graph averaged over the trials.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Percolation {
+	/// Amount of connected components.
+	pub components: f64,
+	/// Size of the largest component.
+	pub largest: f64,
+	/// Size of the component of a node picked uniformly at random.
+	pub mean_size: f64,
+}
+
+//=============================================================================
+
+/// # Reliability
+///
+/// Estimate the probability that the terminals stay connected when every
+/// edge fails with the probability given by the `failure` closure. In an
+/// undirected graph the terminals must be in the same component and in a
+/// directed graph every terminal must be reachable from the first one.
+/// Returns None if any of the terminals can't be found.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::reliability::*;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // Two parallel routes of two edges that each fail half of the time.
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 0.5);
+/// g.add_edge(1, 3, 0.5);
+/// g.add_edge(0, 2, 0.5);
+/// g.add_edge(2, 3, 0.5);
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let p = reliability(&g, &[0, 3], 20_000, |e| e.load(), &mut rng).unwrap();
+/// assert!((p - 0.4375).abs() < 0.02);
+/// ```
+pub fn reliability<K, N, E, G, F, R>(graph: &G, terminals: &[K], trials: usize, failure: F, rng: &mut R) -> Option<f64>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	R: Rng + ?Sized,
+{
+	let trial = Trial::new(graph, failure);
+	let terminals = terminals
+		.iter()
+		.map(|k| trial.indexed.index.get(k).copied())
+		.collect::<Option<Vec<usize>>>()?;
+	if terminals.len() < 2 || trials == 0 {
+		return Some(1.0);
+	}
+	let seed: u64 = rng.gen();
+	let connected = (0..trials)
+		.into_par_iter()
+		.filter(|i| {
+			let alive = trial.sample(seed, *i);
+			match G::directed() {
+				true => {
+					let reached = trial.reachable(&alive, terminals[0]);
+					terminals.iter().all(|t| reached[*t])
+				}
+				false => {
+					let mut parent = trial.components(&alive);
+					let root = find(&mut parent, terminals[0]);
+					terminals.iter().all(|t| find(&mut parent, *t) == root)
+				}
+			}
+		})
+		.count();
+	Some(connected as f64 / trials as f64)
+}
+
+/// # Percolation
+///
+/// Estimate the component sizes of the graph when every edge fails with the
+/// probability given by the `failure` closure. Components are connected
+/// components ignoring the direction of the edges. Sweeping a uniform
+/// failure probability shows the threshold at which the graph falls apart.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::reliability::*;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..100 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 1..100 {
+///     g.add_edge(i - 1, i, Empty);
+/// }
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// assert!(percolation(&g, 10, |_| 0.0, &mut rng).largest == 100.0);
+/// let broken = percolation(&g, 1000, |_| 0.5, &mut rng);
+/// // Half of the 99 edges fail on average.
+/// assert!((broken.components - 50.5).abs() < 1.0);
+/// ```
+pub fn percolation<K, N, E, G, F, R>(graph: &G, trials: usize, failure: F, rng: &mut R) -> Percolation
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	R: Rng + ?Sized,
+{
+	let trial = Trial::new(graph, failure);
+	let n = trial.indexed.nodes.len();
+	if n == 0 || trials == 0 {
+		return Percolation::default();
+	}
+	let seed: u64 = rng.gen();
+	let total = (0..trials)
+		.into_par_iter()
+		.map(|i| {
+			let mut parent = trial.components(&trial.sample(seed, i));
+			let mut sizes = vec![0usize; n];
+			for v in 0..n {
+				sizes[find(&mut parent, v)] += 1;
+			}
+			let sizes = sizes.into_iter().filter(|s| *s > 0);
+			let (mut count, mut largest, mut squares) = (0.0, 0.0, 0.0);
+			for s in sizes.map(|s| s as f64) {
+				count += 1.0;
+				largest = f64::max(largest, s);
+				squares += s * s;
+			}
+			Percolation { components: count, largest, mean_size: squares / n as f64 }
+		})
+		.reduce(Percolation::default, |a, b| Percolation {
+			components: a.components + b.components,
+			largest: a.largest + b.largest,
+			mean_size: a.mean_size + b.mean_size,
+		});
+	let t = trials as f64;
+	Percolation {
+		components: total.components / t,
+		largest: total.largest / t,
+		mean_size: total.mean_size / t,
+	}
+}
+
+//=============================================================================
+
+// Snapshot of a graph with the failure probability of every edge.
+struct Trial<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	indexed: Indexed<K, N, E>,
+	// Endpoints of every edge by index.
+	endpoints: Vec<(usize, usize)>,
+	failure: Vec<f64>,
+}
+
+impl<K, N, E> Trial<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn new<G, F>(graph: &G, failure: F) -> Self
+	where
+		G: Graph<K, N, E>,
+		F: Fn (&Arc<Edge<K, N, E>>) -> f64,
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let failure = indexed.edges.iter().map(failure).collect();
+		let mut endpoints = vec![(0, 0); indexed.edges.len()];
+		for (u, list) in indexed.adjacency.iter().enumerate() {
+			for (w, e) in list.iter() {
+				endpoints[*e] = (u, *w);
+			}
+		}
+		Trial { indexed, endpoints, failure }
+	}
+
+	// Surviving edges of the i:th trial.
+	fn sample(&self, seed: u64, i: usize) -> Vec<bool> {
+		let mut rng = StdRng::seed_from_u64(seed ^ (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+		self.failure.iter().map(|p| rng.gen::<f64>() >= *p).collect()
+	}
+
+	// Union-find forest of the components of the surviving edges.
+	fn components(&self, alive: &[bool]) -> Vec<usize> {
+		let mut parent: Vec<usize> = (0..self.indexed.nodes.len()).collect();
+		for (e, (u, w)) in self.endpoints.iter().enumerate() {
+			if alive[e] {
+				let (a, b) = (find(&mut parent, *u), find(&mut parent, *w));
+				parent[a] = b;
+			}
+		}
+		parent
+	}
+
+	// Nodes reachable from the source over surviving edges.
+	fn reachable(&self, alive: &[bool], source: usize) -> Vec<bool> {
+		let mut reached = vec![false; self.indexed.nodes.len()];
+		let mut queue = VecDeque::from(vec![source]);
+		reached[source] = true;
+		while let Some(v) = queue.pop_front() {
+			for (w, e) in self.indexed.adjacency[v].iter() {
+				if alive[*e] && !reached[*w] {
+					reached[*w] = true;
+					queue.push_back(*w);
+				}
+			}
+		}
+		reached
+	}
+}
+
+// Root of the node with path halving.
+fn find(parent: &mut [usize], mut x: usize) -> usize {
+	while parent[x] != x {
+		parent[x] = parent[parent[x]];
+		x = parent[x];
+	}
+	x
+}
+
+//=============================================================================
@@ -0,0 +1,163 @@
+//=============================================================================
+// 2-SAT
+//=============================================================================
+
+//! Satisfiability of boolean formulas in conjunctive normal form where every
+//! clause has at most two literals. Each clause `a or b` is entered into an
+//! implication graph as the edges `!a -> b` and `!b -> a`. The formula is
+//! unsatisfiable exactly when a variable and its negation are in the same
+//! strongly connected component. Otherwise setting every literal whose
+//! component comes later in topological order than the component of its
+//! negation gives a satisfying assignment.
+//!
+use crate::collections::{Digraph, Graph};
+use crate::components::strongly_connected_components;
+use crate::core::Empty;
+use std::{
+	collections::HashMap,
+	fmt::{Display, Formatter},
+	ops::Not,
+};
+
+/// A variable or its negation. Variables are numbered from zero.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Literal {
+	pub variable: usize,
+	pub value: bool,
+}
+
+impl Literal {
+	/// The literal that is true when the variable is true.
+	pub fn pos(variable: usize) -> Self {
+		Literal { variable, value: true }
+	}
+
+	/// The literal that is true when the variable is false.
+	pub fn neg(variable: usize) -> Self {
+		Literal { variable, value: false }
+	}
+}
+
+impl Not for Literal {
+	type Output = Literal;
+
+	fn not(self) -> Literal {
+		Literal { variable: self.variable, value: !self.value }
+	}
+}
+
+impl Display for Literal {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		if self.value {
+			write!(f, "x{}", self.variable)
+		} else {
+			write!(f, "!x{}", self.variable)
+		}
+	}
+}
+
+//=============================================================================
+
+/// # 2-SAT
+///
+/// A formula built clause by clause. Variables are added as they appear in
+/// clauses.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::sat::*;
+///
+/// // (x0 or x1) and (!x0 or x1) and (!x1 or !x2)
+/// let mut formula = TwoSat::new();
+/// formula.clause(Literal::pos(0), Literal::pos(1));
+/// formula.clause(Literal::neg(0), Literal::pos(1));
+/// formula.clause(Literal::neg(1), Literal::neg(2));
+///
+/// let assignment = formula.solve().unwrap();
+/// assert!(assignment[1] && !assignment[2]);
+///
+/// // Forcing x2 makes the formula unsatisfiable.
+/// formula.unit(Literal::pos(2));
+/// assert!(formula.solve().is_none());
+/// ```
+pub struct TwoSat {
+	variables: usize,
+	graph: Digraph<Literal, Empty, Empty>,
+}
+
+impl TwoSat {
+	/// Empty formula, which is satisfied by any assignment.
+	pub fn new() -> Self {
+		TwoSat { variables: 0, graph: Digraph::new() }
+	}
+
+	/// Amount of variables in the formula.
+	pub fn variables(&self) -> usize {
+		self.variables
+	}
+
+	/// The implication graph of the formula with an edge `a -> b` whenever
+	/// `a` being true forces `b` to be true.
+	pub fn implication_graph(&self) -> &Digraph<Literal, Empty, Empty> {
+		&self.graph
+	}
+
+	/// Add the clause `a or b`.
+	pub fn clause(&mut self, a: Literal, b: Literal) {
+		self.grow(a.variable.max(b.variable) + 1);
+		self.graph.add_edge(!a, b, Empty);
+		self.graph.add_edge(!b, a, Empty);
+	}
+
+	/// Add a clause forcing the literal to be true.
+	pub fn unit(&mut self, a: Literal) {
+		self.clause(a, a);
+	}
+
+	/// Add the clause `a implies b`.
+	pub fn implication(&mut self, a: Literal, b: Literal) {
+		self.clause(!a, b);
+	}
+
+	/// Add clauses requiring exactly one of the literals to be true.
+	pub fn exclusive(&mut self, a: Literal, b: Literal) {
+		self.clause(a, b);
+		self.clause(!a, !b);
+	}
+
+	/// Value of every variable in a satisfying assignment, or None if the
+	/// formula is unsatisfiable.
+	pub fn solve(&self) -> Option<Vec<bool>> {
+		let mut component = HashMap::new();
+		for (i, c) in strongly_connected_components(&self.graph).into_iter().enumerate() {
+			for literal in c {
+				component.insert(literal, i);
+			}
+		}
+		(0..self.variables)
+			.map(|v| {
+				let pos = component[&Literal::pos(v)];
+				let neg = component[&Literal::neg(v)];
+				// Components are numbered in reverse topological order.
+				if pos == neg { None } else { Some(pos < neg) }
+			})
+			.collect()
+	}
+
+	fn grow(&mut self, variables: usize) {
+		while self.variables < variables {
+			self.graph.add_node(Literal::pos(self.variables), Empty);
+			self.graph.add_node(Literal::neg(self.variables), Empty);
+			self.variables += 1;
+		}
+	}
+}
+
+impl Default for TwoSat {
+	fn default() -> Self {
+		TwoSat::new()
+	}
+}
+
+//=============================================================================
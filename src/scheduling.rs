@@ -0,0 +1,170 @@
+//=============================================================================
+// SCHEDULING
+//=============================================================================
+
+//! Critical path analysis of a graph of dependent activities. Every node
+//! and every edge has a duration given by a closure, which covers both
+//! activities on nodes with the edges as dependencies and activities on
+//! edges with the nodes as events. The critical path is the longest path
+//! through the graph and its duration is the shortest time in which all of
+//! the activities can be finished. Nodes off the critical path have slack,
+//! the time their start can be delayed without delaying the whole project.
+//! Edges are followed from their source to their target, also in an
+//! undirected graph, and durations should be non-negative.
+//!
+use crate::core::*;
+use crate::collections::{cycle, Graph};
+use crate::indexed::Indexed;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+/// Critical path of a graph together with the earliest and the latest start
+/// of every node.
+#[derive(Clone, Debug)]
+pub struct CriticalPath<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Keys of the nodes on the critical path in order.
+	pub path: Vec<K>,
+	/// Total duration of the critical path.
+	pub duration: f64,
+	/// The earliest time every node can start once all of its predecessors
+	/// have finished.
+	pub earliest: HashMap<K, f64>,
+	/// The latest time every node can start without delaying the total
+	/// duration.
+	pub latest: HashMap<K, f64>,
+}
+
+impl<K> CriticalPath<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Time the start of a node can be delayed without delaying the total
+	/// duration, or None if the key can't be found.
+	pub fn slack(&self, key: &K) -> Option<f64> {
+		Some(self.latest.get(key)? - self.earliest.get(key)?)
+	}
+
+	/// True if the node can't be delayed at all.
+	pub fn is_critical(&self, key: &K) -> bool {
+		self.slack(key).is_some_and(|s| s <= 0.0)
+	}
+}
+
+//=============================================================================
+
+/// # Critical Path
+///
+/// Find the longest path through the graph with node durations given by the
+/// `node` closure and edge durations given by the `edge` closure, and the
+/// earliest and latest start of every node. A node starts once every
+/// predecessor has finished and the duration of the edge from it has passed.
+/// If the graph has a cycle, the keys of the nodes on a cycle are returned
+/// in order as the error.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::scheduling::*;
+///
+/// // Tasks with their durations in days.
+/// let mut g = Digraph::<&str, f64, Empty>::new();
+/// g.add_node("design", 3.0);
+/// g.add_node("backend", 5.0);
+/// g.add_node("frontend", 2.0);
+/// g.add_node("launch", 1.0);
+/// g.add_edge("design", "backend", Empty);
+/// g.add_edge("design", "frontend", Empty);
+/// g.add_edge("backend", "launch", Empty);
+/// g.add_edge("frontend", "launch", Empty);
+///
+/// let plan = critical_path(&g, |node| node.load(), |_| 0.0).unwrap();
+/// assert!(plan.path == ["design", "backend", "launch"]);
+/// assert!(plan.duration == 9.0);
+/// assert!(plan.earliest[&"frontend"] == 3.0 && plan.latest[&"frontend"] == 6.0);
+/// assert!(plan.slack(&"frontend") == Some(3.0));
+///
+/// g.add_edge("launch", "design", Empty);
+/// let cycle = critical_path(&g, |node| node.load(), |_| 0.0).unwrap_err();
+/// assert!(cycle.contains(&"design") && cycle.contains(&"launch"));
+/// ```
+pub fn critical_path<K, N, E, G, FN, FE>(graph: &G, node: FN, edge: FE) -> Result<CriticalPath<K>, Vec<K>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	FN: Fn (&Arc<Node<K, N, E>>) -> f64,
+	FE: Fn (&Arc<Edge<K, N, E>>) -> f64,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let n = indexed.nodes.len();
+	let durations: Vec<f64> = indexed.nodes.iter().map(node).collect();
+	let delays: Vec<f64> = indexed.edges.iter().map(edge).collect();
+	let mut remaining = vec![0; n];
+	for adjacent in indexed.adjacency.iter() {
+		for &(w, _) in adjacent.iter() {
+			remaining[w] += 1;
+		}
+	}
+	// Topological order by Kahn's algorithm, relaxing the earliest starts of
+	// the successors of every node as it's visited.
+	let mut order = Vec::with_capacity(n);
+	let mut ready: Vec<usize> = (0..n).filter(|v| remaining[*v] == 0).collect();
+	let mut earliest = vec![0.0; n];
+	let mut parent: Vec<Option<usize>> = vec![None; n];
+	while let Some(v) = ready.pop() {
+		order.push(v);
+		let finish = earliest[v] + durations[v];
+		for &(w, e) in indexed.adjacency[v].iter() {
+			if parent[w].is_none() || finish + delays[e] > earliest[w] {
+				earliest[w] = finish + delays[e];
+				parent[w] = Some(v);
+			}
+			remaining[w] -= 1;
+			if remaining[w] == 0 {
+				ready.push(w);
+			}
+		}
+	}
+	if order.len() < n {
+		return Err(cycle(&indexed.adjacency, &remaining).into_iter().map(|v| indexed.key(v)).collect());
+	}
+	// The critical path ends at the node that finishes the last.
+	let mut end = None;
+	let mut duration = 0.0;
+	for v in 0..n {
+		let finish = earliest[v] + durations[v];
+		if end.is_none() || finish > duration {
+			end = Some(v);
+			duration = finish;
+		}
+	}
+	let mut latest = vec![0.0; n];
+	for &v in order.iter().rev() {
+		let mut finish = duration;
+		for &(w, e) in indexed.adjacency[v].iter() {
+			finish = f64::min(finish, latest[w] - delays[e]);
+		}
+		latest[v] = finish - durations[v];
+	}
+	let mut path = Vec::new();
+	let mut current = end;
+	while let Some(v) = current {
+		path.push(indexed.key(v));
+		current = parent[v];
+	}
+	path.reverse();
+	let keys = |times: Vec<f64>| (0..n).map(|v| indexed.key(v)).zip(times).collect();
+	Ok(CriticalPath { path, duration, earliest: keys(earliest), latest: keys(latest) })
+}
+
+//=============================================================================
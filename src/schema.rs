@@ -0,0 +1,152 @@
+//=============================================================================
+// SCHEMA
+//=============================================================================
+
+//! Constraints for typed graphs such as knowledge graphs. A schema derives a
+//! type for every node and a label for every edge from their data, declares
+//! the node types that are allowed, the relations that may connect them and
+//! how many edges of a relation a node may have. Checking a graph against a
+//! schema lists every violation found.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::schema::*;
+//!
+//! let schema = Schema::new(|n: &String| n.split(':').next().unwrap().to_string(), |e: &String| e.clone())
+//!     .node_type("person".to_string())
+//!     .node_type("company".to_string())
+//!     .relation("person".to_string(), "works_at".to_string(), "company".to_string())
+//!     .cardinality("person".to_string(), "works_at".to_string(), "company".to_string(), 0, Some(1));
+//!
+//! let mut g = Digraph::<u32, String, String>::new();
+//! g.add_node(1, "person:ada".to_string());
+//! g.add_node(2, "company:acme".to_string());
+//! g.add_node(3, "company:initech".to_string());
+//! g.add_edge(1, 2, "works_at".to_string());
+//! assert!(g.check_schema(&schema).is_empty());
+//!
+//! g.add_edge(1, 3, "works_at".to_string());
+//! assert!(g.check_schema(&schema).len() == 1);
+//! ```
+use crate::collections::Graph;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+/// A relation from nodes of a source type to nodes of a target type through
+/// edges with a label.
+pub type Relation<T, R> = (T, R, T);
+
+type TypeOf<N, T> = Box<dyn Fn(&N) -> T + Sync + Send>;
+type LabelOf<E, R> = Box<dyn Fn(&E) -> R + Sync + Send>;
+
+/// A violation of a schema found in a graph.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation<K, T, R> {
+	/// The type of the node is not declared.
+	NodeType { node: K, node_type: T },
+	/// The edge doesn't belong to any declared relation.
+	Edge { source: K, target: K, relation: Relation<T, R> },
+	/// The node has too few or too many edges of a relation.
+	Cardinality { node: K, relation: Relation<T, R>, count: usize },
+}
+
+//=============================================================================
+
+/// Declared node types, relations and cardinalities.
+pub struct Schema<N, E, T, R> {
+	type_of: TypeOf<N, T>,
+	label_of: LabelOf<E, R>,
+	types: HashSet<T>,
+	relations: HashSet<Relation<T, R>>,
+	cardinalities: HashMap<Relation<T, R>, (usize, Option<usize>)>,
+}
+
+impl<N, E, T, R> Schema<N, E, T, R>
+where
+	T: Hash + Eq + Clone + Debug,
+	R: Hash + Eq + Clone + Debug,
+{
+	/// Create an empty schema that derives node types and edge labels with
+	/// the given closures. Graphs without edge labels can use `|_| ()`.
+	pub fn new<FN, FE>(type_of: FN, label_of: FE) -> Self
+	where
+		FN: Fn(&N) -> T + Sync + Send + 'static,
+		FE: Fn(&E) -> R + Sync + Send + 'static,
+	{
+		Schema {
+			type_of: Box::new(type_of),
+			label_of: Box::new(label_of),
+			types: HashSet::new(),
+			relations: HashSet::new(),
+			cardinalities: HashMap::new(),
+		}
+	}
+
+	/// Allow nodes of a type.
+	pub fn node_type(mut self, node_type: T) -> Self {
+		self.types.insert(node_type);
+		self
+	}
+
+	/// Allow edges with a label from nodes of the source type to nodes of
+	/// the target type. In undirected graphs the edge may be stored in either
+	/// orientation.
+	pub fn relation(mut self, source: T, label: R, target: T) -> Self {
+		self.relations.insert((source, label, target));
+		self
+	}
+
+	/// Require every node of the source type to have at least `min` and at
+	/// most `max` edges of the relation. Declares the relation if it isn't
+	/// declared yet.
+	pub fn cardinality(mut self, source: T, label: R, target: T, min: usize, max: Option<usize>) -> Self {
+		let relation = (source, label, target);
+		self.relations.insert(relation.clone());
+		self.cardinalities.insert(relation, (min, max));
+		self
+	}
+
+	/// Check a graph against the schema and return every violation.
+	pub fn check<K, G>(&self, graph: &G) -> Vec<Violation<K, T, R>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send,
+		E: Clone + Debug + Display + Sync + Send,
+		G: Graph<K, N, E>,
+	{
+		let mut res = Vec::new();
+		for node in graph.nodes() {
+			let node_type = (self.type_of)(&node.load());
+			if !self.types.contains(&node_type) {
+				res.push(Violation::NodeType { node: node.key().clone(), node_type: node_type.clone() });
+			}
+			for edge in node.outbound().iter() {
+				let target = edge.target();
+				let relation = (node_type.clone(), (self.label_of)(&edge.load()), (self.type_of)(&target.load()));
+				let reverse = (relation.2.clone(), relation.1.clone(), relation.0.clone());
+				if !self.relations.contains(&relation) && (G::directed() || !self.relations.contains(&reverse)) {
+					res.push(Violation::Edge { source: node.key().clone(), target: target.key().clone(), relation });
+				}
+			}
+			let mut counts: HashMap<Relation<T, R>, usize> = HashMap::new();
+			for (edge, other) in node.incident(G::direction()) {
+				let relation = (node_type.clone(), (self.label_of)(&edge.load()), (self.type_of)(&other.load()));
+				*counts.entry(relation).or_insert(0) += 1;
+			}
+			for (relation, (min, max)) in self.cardinalities.iter().filter(|(r, _)| r.0 == node_type) {
+				let count = counts.get(relation).copied().unwrap_or(0);
+				if count < *min || max.is_some_and(|max| count > max) {
+					res.push(Violation::Cardinality { node: node.key().clone(), relation: relation.clone(), count });
+				}
+			}
+		}
+		res
+	}
+}
+
+//=============================================================================
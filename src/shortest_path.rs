@@ -0,0 +1,1088 @@
+//=============================================================================
+// SHORTEST PATHS
+//=============================================================================
+
+//! Weighted shortest path searches. Edge costs are provided by the user as a
+//! closure over the edge, which allows the cost to be derived from any data
+//! stored in the edge.
+//!
+use crate::centrality::stable_order;
+use crate::checkpoint::Checkpoint;
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	cmp::Ordering,
+	collections::{BinaryHeap, HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// Nodes paired with their distance from a source node.
+pub type Distances<K, N, E> = Vec<(Arc<Node<K, N, E>>, f64)>;
+
+/// Costs and paths answering a batch of shortest path queries, None where
+/// the target can't be reached.
+pub type Routes<K, N, E> = Vec<Option<(f64, Path<K, N, E>)>>;
+
+/// Maps every node that can reach a target to its cost to the nearest target
+/// and the edge to follow toward it paired with the node on the other end of
+/// the edge, None at the targets.
+pub type FlowField<K, N, E> = HashMap<K, (f64, Option<(Arc<Edge<K, N, E>>, Arc<Node<K, N, E>>)>)>;
+
+// Maps each reached node to the edge it was reached through and the node on
+// the other end of that edge.
+pub(crate) type Parents<K, N, E> = HashMap<K, (Arc<Edge<K, N, E>>, Arc<Node<K, N, E>>)>;
+
+/// An item in a priority queue ordered by cost. The ordering is reversed so
+/// that a `BinaryHeap` pops the item with the lowest cost first.
+pub(crate) struct MinCost<T> {
+	pub cost: f64,
+	pub item: T,
+}
+
+// A node together with the source and target keys of the edge it was arrived
+// at through, or None at the source of a search.
+type Arrival<K> = (K, Option<(K, K)>);
+
+// A node of the current path of a depth first search with its incident edges
+// and the position of the next edge to follow.
+type Branch<K, N, E> = (Arc<Node<K, N, E>>, Incident<K, N, E>, usize);
+
+// Maps each arrival to the edge it was reached through and the previous
+// arrival.
+type ArrivalParents<K, N, E> = HashMap<Arrival<K>, (Arc<Edge<K, N, E>>, Arrival<K>)>;
+
+// Costs of a partial path in a multi-criteria search, the node it ends at, the
+// last edge with the index of the previous label and whether the label has
+// been dominated.
+type Label<K, N, E, const D: usize> = ([f64; D], Arc<Node<K, N, E>>, Option<(Arc<Edge<K, N, E>>, usize)>, bool);
+
+impl<T> PartialEq for MinCost<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cost == other.cost
+	}
+}
+
+impl<T> Eq for MinCost<T> {}
+
+impl<T> PartialOrd for MinCost<T> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T> Ord for MinCost<T> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.cost.partial_cmp(&self.cost).unwrap_or(Ordering::Equal)
+	}
+}
+
+//=============================================================================
+
+/// # Dijkstra Ball
+///
+/// Find all nodes reachable from the source node with a total edge cost of
+/// at most `max_cost`. Edges are followed in the given direction and their
+/// cost is determined by the `cost` closure. Returns the reached nodes paired
+/// with their distance from the source, in order of increasing distance.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let n1 = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let n2 = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+/// let n3 = Arc::new(Node::<u32, Empty, f64>::new(3, Empty));
+///
+/// connect(&n1, &n2, 1.0);
+/// connect(&n2, &n3, 2.0);
+///
+/// let ball = dijkstra_ball(&n1, 2.0, Direction::Outbound, |edge| edge.load());
+///
+/// assert!(ball.len() == 2);
+/// ```
+pub fn dijkstra_ball<K, N, E, F>(
+	source: &Arc<Node<K, N, E>>,
+	max_cost: f64,
+	direction: Direction,
+	cost: F,
+) -> Distances<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let mut res = Vec::new();
+	let mut dist: HashMap<K, f64> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let _span = trace_span!("dijkstra_ball", max_cost = max_cost);
+	dist.insert(source.key().clone(), 0.0);
+	heap.push(MinCost { cost: 0.0, item: source.clone() });
+	while let Some(MinCost { cost: d, item: node }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		for (edge, next) in node.incident(direction) {
+			let c = d + cost(&edge);
+			if c > max_cost || settled.contains(next.key()) {
+				continue;
+			}
+			let shorter = match dist.get(next.key()) {
+				Some(old) => c < *old,
+				None => true,
+			};
+			if shorter {
+				dist.insert(next.key().clone(), c);
+				heap.push(MinCost { cost: c, item: next });
+			}
+		}
+		res.push((node, d));
+	}
+	trace_event!("settled", nodes = res.len());
+	res
+}
+
+/// # Dijkstra
+///
+/// Find the shortest path from the source node to the target node. Edges are
+/// followed in the given direction and their cost is determined by the `cost`
+/// closure. Returns the total cost of the path and the edges along the path
+/// in order, or None if the target can't be reached.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let n1 = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let n2 = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+/// let n3 = Arc::new(Node::<u32, Empty, f64>::new(3, Empty));
+///
+/// connect(&n1, &n2, 1.0);
+/// connect(&n2, &n3, 2.0);
+/// connect(&n1, &n3, 4.0);
+///
+/// let (cost, path) = dijkstra(&n1, &n3, Direction::Outbound, |edge| edge.load()).unwrap();
+///
+/// assert!(cost == 3.0);
+/// assert!(path.len() == 2);
+/// ```
+pub fn dijkstra<K, N, E, F>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	cost: F,
+) -> Option<(f64, Path<K, N, E>)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let mut dist: HashMap<K, f64> = HashMap::new();
+	let mut parent: Parents<K, N, E> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let mut relaxations = 0;
+	let _span = trace_span!("dijkstra");
+	dist.insert(source.key().clone(), 0.0);
+	heap.push(MinCost { cost: 0.0, item: source.clone() });
+	while let Some(MinCost { cost: d, item: node }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		if node == *target {
+			trace_event!("found", cost = d, settled = settled.len(), relaxations = relaxations);
+			return Some((d, backtrack_parents(&parent, target)));
+		}
+		for (edge, next) in node.incident(direction) {
+			if settled.contains(next.key()) {
+				continue;
+			}
+			let c = d + cost(&edge);
+			let shorter = match dist.get(next.key()) {
+				Some(old) => c < *old,
+				None => true,
+			};
+			if shorter {
+				relaxations += 1;
+				dist.insert(next.key().clone(), c);
+				parent.insert(next.key().clone(), (edge, node.clone()));
+				heap.push(MinCost { cost: c, item: next });
+			}
+		}
+	}
+	trace_event!("unreachable", settled = settled.len(), relaxations = relaxations);
+	None
+}
+
+/// # A*
+///
+/// Find the shortest path from the source node to the target node like
+/// `dijkstra`, guided by a `heuristic` giving a lower bound of the cost from
+/// a node to the target. Nodes are settled in order of their distance plus
+/// the heuristic, so a good bound settles far fewer nodes than Dijkstra. The
+/// heuristic must never overestimate the remaining cost and obey the
+/// triangle inequality over every edge, otherwise the path found may not be
+/// the shortest. A heuristic of zero gives Dijkstra.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let nodes: Vec<_> = (0..10).map(|i| Arc::new(Node::<i32, Empty, f64>::new(i, Empty))).collect();
+/// for w in nodes.windows(2) {
+///     connect(&w[0], &w[1], 1.0);
+///     connect(&w[1], &w[0], 1.0);
+/// }
+///
+/// // Distance on a line is bounded by the difference of the keys.
+/// let target = nodes[7].clone();
+/// let (cost, path) = astar(&nodes[2], &target, Direction::Outbound, |edge| edge.load(), |node| {
+///     (node.key() - target.key()).abs() as f64
+/// }).unwrap();
+///
+/// assert!(cost == 5.0);
+/// assert!(path.len() == 5);
+/// ```
+pub fn astar<K, N, E, F, H>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	cost: F,
+	heuristic: H,
+) -> Option<(f64, Path<K, N, E>)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	H: Fn(&Arc<Node<K, N, E>>) -> f64,
+{
+	let mut dist: HashMap<K, f64> = HashMap::new();
+	let mut parent: Parents<K, N, E> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let _span = trace_span!("astar");
+	dist.insert(source.key().clone(), 0.0);
+	heap.push(MinCost { cost: heuristic(source), item: source.clone() });
+	while let Some(MinCost { item: node, .. }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		let d = dist[node.key()];
+		if node == *target {
+			trace_event!("found", cost = d, settled = settled.len());
+			return Some((d, backtrack_parents(&parent, target)));
+		}
+		for (edge, next) in node.incident(direction) {
+			if settled.contains(next.key()) {
+				continue;
+			}
+			let c = d + cost(&edge);
+			let shorter = match dist.get(next.key()) {
+				Some(old) => c < *old,
+				None => true,
+			};
+			if shorter {
+				dist.insert(next.key().clone(), c);
+				parent.insert(next.key().clone(), (edge, node.clone()));
+				let estimate = c + heuristic(&next);
+				heap.push(MinCost { cost: estimate, item: next });
+			}
+		}
+	}
+	trace_event!("unreachable", settled = settled.len());
+	None
+}
+
+/// # Dijkstra Turns
+///
+/// Find the shortest path from the source node to the target node when the
+/// cost of passing through a node depends on the edge used to arrive and the
+/// edge used to leave, such as turns at road intersections. The `turn`
+/// closure gets the incoming and the outgoing edge and returns the penalty
+/// of the turn, or None if the turn is forbidden. The search runs over edges
+/// rather than nodes, so a node may be passed several times if it's arrived
+/// at through different edges. Returns the total cost of the path and the
+/// edges along the path in order, or None if the target can't be reached.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let n1 = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let n2 = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+/// let n3 = Arc::new(Node::<u32, Empty, f64>::new(3, Empty));
+///
+/// connect(&n1, &n2, 1.0);
+/// connect(&n2, &n3, 1.0);
+/// connect(&n1, &n3, 5.0);
+///
+/// // Turning from 1 -> 2 onto 2 -> 3 costs 10.
+/// let (cost, path) = dijkstra_turns(&n1, &n3, Direction::Outbound, |edge| edge.load(), |_, _| Some(10.0)).unwrap();
+///
+/// assert!(cost == 5.0);
+/// assert!(path.len() == 1);
+/// ```
+pub fn dijkstra_turns<K, N, E, F, T>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	cost: F,
+	turn: T,
+) -> Option<(f64, Path<K, N, E>)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	T: Fn(&Arc<Edge<K, N, E>>, &Arc<Edge<K, N, E>>) -> Option<f64>,
+{
+	let mut dist: HashMap<Arrival<K>, f64> = HashMap::new();
+	let mut parent: ArrivalParents<K, N, E> = HashMap::new();
+	let mut settled: HashSet<Arrival<K>> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let start = (source.key().clone(), None);
+	dist.insert(start.clone(), 0.0);
+	heap.push(MinCost { cost: 0.0, item: (source.clone(), None, start) });
+	while let Some(MinCost { cost: d, item: (node, incoming, state) }) = heap.pop() {
+		if !settled.insert(state.clone()) {
+			continue;
+		}
+		if node == *target {
+			let mut res = Vec::new();
+			let mut current = &state;
+			while let Some((edge, prev)) = parent.get(current) {
+				res.push(Arc::downgrade(edge));
+				current = prev;
+			}
+			res.reverse();
+			return Some((d, res));
+		}
+		for (edge, next) in node.incident(direction) {
+			let penalty = match &incoming {
+				Some(incoming) => {
+					match turn(incoming, &edge) {
+						Some(penalty) => penalty,
+						None => continue,
+					}
+				}
+				None => 0.0,
+			};
+			let next_state = (next.key().clone(), Some((edge.source().key().clone(), edge.target().key().clone())));
+			if settled.contains(&next_state) {
+				continue;
+			}
+			let c = d + penalty + cost(&edge);
+			let shorter = match dist.get(&next_state) {
+				Some(old) => c < *old,
+				None => true,
+			};
+			if shorter {
+				dist.insert(next_state.clone(), c);
+				parent.insert(next_state.clone(), (edge.clone(), state.clone()));
+				heap.push(MinCost { cost: c, item: (next, Some(edge), next_state) });
+			}
+		}
+	}
+	None
+}
+
+/// # Dijkstra Regions
+///
+/// Find the shortest path from the source node to the target node when
+/// passing through a node costs as well as following an edge, such as
+/// crossing terrain or a region of a navigation mesh. The `region` closure
+/// gives the cost of a node, which is paid on entering it, so every node of
+/// the path but the source adds its cost. Nodes with an infinite cost can't
+/// be entered. Edges are followed in the given direction and their cost is
+/// determined by the `cost` closure. Costs must not be negative. Returns the
+/// total cost of the path and the edges along the path in order, or None if
+/// the target can't be reached.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// // Node data is the cost of entering the node, with a swamp at 2.
+/// let n1 = Arc::new(Node::<u32, f64, f64>::new(1, 0.0));
+/// let n2 = Arc::new(Node::<u32, f64, f64>::new(2, 5.0));
+/// let n3 = Arc::new(Node::<u32, f64, f64>::new(3, 0.0));
+/// let n4 = Arc::new(Node::<u32, f64, f64>::new(4, 1.0));
+///
+/// connect(&n1, &n2, 1.0);
+/// connect(&n2, &n4, 1.0);
+/// connect(&n1, &n3, 2.0);
+/// connect(&n3, &n4, 2.0);
+///
+/// let (cost, path) = dijkstra_regions(&n1, &n4, Direction::Outbound, |edge| edge.load(), |node| node.load()).unwrap();
+///
+/// assert!(cost == 5.0);
+/// assert!(path[0].upgrade().unwrap().target() == n3);
+/// ```
+pub fn dijkstra_regions<K, N, E, F, R>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	cost: F,
+	region: R,
+) -> Option<(f64, Path<K, N, E>)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	R: Fn(&Arc<Node<K, N, E>>) -> f64,
+{
+	let mut dist: HashMap<K, f64> = HashMap::new();
+	let mut parent: Parents<K, N, E> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let _span = trace_span!("dijkstra_regions");
+	dist.insert(source.key().clone(), 0.0);
+	heap.push(MinCost { cost: 0.0, item: source.clone() });
+	while let Some(MinCost { cost: d, item: node }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		if node == *target {
+			trace_event!("found", cost = d, settled = settled.len());
+			return Some((d, backtrack_parents(&parent, target)));
+		}
+		for (edge, next) in node.incident(direction) {
+			if settled.contains(next.key()) {
+				continue;
+			}
+			let entry = region(&next);
+			if entry == f64::INFINITY {
+				continue;
+			}
+			let c = d + cost(&edge) + entry;
+			let shorter = match dist.get(next.key()) {
+				Some(old) => c < *old,
+				None => true,
+			};
+			if shorter {
+				dist.insert(next.key().clone(), c);
+				parent.insert(next.key().clone(), (edge, node.clone()));
+				heap.push(MinCost { cost: c, item: next });
+			}
+		}
+	}
+	trace_event!("unreachable", settled = settled.len());
+	None
+}
+
+/// # Flow Field
+///
+/// Find for every node the edge to follow toward the nearest of the target
+/// nodes, also known as a Dijkstra map. A single search from all targets at
+/// once serves any amount of agents moving toward them, each of which only
+/// looks up the edge at the node it stands on. Edges are followed in the
+/// given direction from the nodes toward the targets, so the search runs
+/// against it, and their costs, which must not be negative, are determined
+/// by the `cost` closure. Nodes that can't reach any target are left out.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let n1 = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let n2 = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+/// let n3 = Arc::new(Node::<u32, Empty, f64>::new(3, Empty));
+/// let n4 = Arc::new(Node::<u32, Empty, f64>::new(4, Empty));
+///
+/// connect(&n1, &n2, 1.0);
+/// connect(&n2, &n3, 5.0);
+/// connect(&n2, &n4, 1.0);
+///
+/// let field = flow_field(&[n3.clone(), n4.clone()], Direction::Outbound, |edge| edge.load());
+///
+/// // From 1 the nearest target is 4, two steps away through 2.
+/// let (cost, next) = &field[&1];
+/// assert!(*cost == 2.0);
+/// assert!(next.as_ref().unwrap().1 == n2);
+/// assert!(field[&2].1.as_ref().unwrap().1 == n4);
+/// assert!(field[&3].1.is_none());
+/// ```
+pub fn flow_field<K, N, E, F>(
+	targets: &[Arc<Node<K, N, E>>],
+	direction: Direction,
+	cost: F,
+) -> FlowField<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let against = match direction {
+		Direction::Outbound => { Direction::Inbound }
+		Direction::Inbound => { Direction::Outbound }
+		Direction::Both => { Direction::Both }
+	};
+	let mut field: FlowField<K, N, E> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	let _span = trace_span!("flow_field", targets = targets.len());
+	for target in targets.iter() {
+		field.insert(target.key().clone(), (0.0, None));
+		heap.push(MinCost { cost: 0.0, item: target.clone() });
+	}
+	while let Some(MinCost { cost: d, item: node }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		for (edge, prev) in node.incident(against) {
+			if settled.contains(prev.key()) {
+				continue;
+			}
+			let c = d + cost(&edge);
+			let shorter = match field.get(prev.key()) {
+				Some((old, _)) => c < *old,
+				None => true,
+			};
+			if shorter {
+				field.insert(prev.key().clone(), (c, Some((edge, node.clone()))));
+				heap.push(MinCost { cost: c, item: prev });
+			}
+		}
+	}
+	trace_event!("settled", settled = settled.len());
+	field
+}
+
+/// # All Pairs Shortest Paths
+///
+/// Find the cost of the shortest path between every pair of nodes in the
+/// graph with a search from every node. Edges follow the direction of the
+/// graph and their costs, which must not be negative, are determined by the
+/// `cost` closure. Returns the keys of the source and the target of every
+/// pair where the target can be reached, including every node paired with
+/// itself, which takes memory quadratic in the amount of nodes for a
+/// connected graph. Rows of the sources are in an order that is the same in
+/// every run and the targets of a row in order of increasing cost.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::shortest_path::*;
+///
+/// let mut g = Digraph::<u32, Empty, f64>::new();
+/// for i in 1..=3 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 3, 2.0);
+/// g.add_edge(1, 3, 5.0);
+///
+/// let distances = all_pairs(&g, |edge| edge.load());
+/// assert!(distances.len() == 6);
+/// assert!(distances.contains(&(1, 3, 3.0)));
+/// ```
+pub fn all_pairs<K, N, E, G, F>(graph: &G, cost: F) -> Vec<(K, K, f64)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	all_pairs_resume(graph, &mut Checkpoint::new(), usize::MAX, cost).unwrap_or_default()
+}
+
+/// Find the costs of `all_pairs` a batch of at most `batch` source nodes at
+/// a time, recording the rows found so far in the checkpoint. Returns the
+/// costs once every source has been searched from and None before that.
+/// Sources are processed in an order that is the same in every run, so a
+/// checkpoint saved to disk can be resumed by another process as long as
+/// the graph hasn't changed. A checkpoint of a graph with a different amount
+/// of nodes is started over. A batch of zero is taken as one.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::checkpoint::Checkpoint;
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::shortest_path::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..4 {
+///     g.add_edge(i, (i + 1) % 4, 1.0);
+/// }
+///
+/// let mut checkpoint = Checkpoint::new();
+/// assert!(all_pairs_resume(&g, &mut checkpoint, 3, |edge| edge.load()).is_none());
+/// let distances = all_pairs_resume(&g, &mut checkpoint, 3, |edge| edge.load()).unwrap();
+/// assert!(checkpoint.finished() && distances.len() == 16);
+/// ```
+pub fn all_pairs_resume<K, N, E, G, F>(
+	graph: &G,
+	checkpoint: &mut Checkpoint<(K, K, f64)>,
+	batch: usize,
+	cost: F,
+) -> Option<Vec<(K, K, f64)>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let n = indexed.nodes.len();
+	let range = checkpoint.next(n, batch);
+	let costs: Vec<f64> = indexed.edges.iter().map(cost).collect();
+	let order = stable_order(&indexed);
+	let _span = trace_span!("all_pairs_resume", from = range.start, to = range.end);
+	let rows: Vec<Vec<(usize, f64)>> = order[range.clone()]
+		.into_par_iter()
+		.map(|s| costs_from(&indexed.adjacency, &costs, *s))
+		.collect();
+	for (s, row) in order[range.clone()].iter().zip(rows) {
+		for (t, c) in row {
+			checkpoint.values.push((indexed.key(*s), indexed.key(t), c));
+		}
+	}
+	checkpoint.done = range.end;
+	match range.end < n {
+		true => { None }
+		false => { Some(std::mem::take(&mut checkpoint.values)) }
+	}
+}
+
+// Dijkstra over an adjacency list of pairs of neighbor and edge index.
+// Returns the reached nodes with their costs in order of increasing cost.
+fn costs_from(adjacency: &[Vec<(usize, usize)>], costs: &[f64], source: usize) -> Vec<(usize, f64)> {
+	let mut best = vec![f64::INFINITY; adjacency.len()];
+	let mut settled = vec![false; adjacency.len()];
+	let mut res = Vec::new();
+	let mut heap = BinaryHeap::new();
+	best[source] = 0.0;
+	heap.push(MinCost { cost: 0.0, item: source });
+	while let Some(MinCost { cost: d, item: v }) = heap.pop() {
+		if settled[v] {
+			continue;
+		}
+		settled[v] = true;
+		res.push((v, d));
+		for (w, e) in adjacency[v].iter() {
+			let c = d + costs[*e];
+			if !settled[*w] && c < best[*w] {
+				best[*w] = c;
+				heap.push(MinCost { cost: c, item: *w });
+			}
+		}
+	}
+	res
+}
+
+/// # Pareto Paths
+///
+/// Find every Pareto optimal path from the source node to the target node
+/// when the cost of an edge has several criteria, such as travel time and
+/// price. A path is Pareto optimal if no other path is at least as good in
+/// every criterion. Edges are followed in the given direction and their
+/// costs, which must not be negative, are determined by the `cost` closure.
+/// Returns the costs and edges of the optimal paths in lexicographic order of
+/// their costs. Paths with equal costs are reported only once. The amount of
+/// optimal paths can grow exponentially with the length of the paths.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let n1 = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let n2 = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+/// let n3 = Arc::new(Node::<u32, Empty, f64>::new(3, Empty));
+///
+/// // An expensive direct route and a cheap route with a transfer at 2.
+/// connect(&n1, &n3, 10.0);
+/// connect(&n1, &n2, 1.0);
+/// connect(&n2, &n3, 1.0);
+///
+/// // Minimize both the amount of edges and their total price.
+/// let paths = pareto_paths(&n1, &n3, Direction::Outbound, |edge| [1.0, edge.load()]);
+///
+/// assert!(paths.len() == 2);
+/// assert!(paths[0].0 == [1.0, 10.0]);
+/// assert!(paths[1].0 == [2.0, 2.0]);
+/// ```
+pub fn pareto_paths<K, N, E, F, const D: usize>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	cost: F,
+) -> Vec<([f64; D], Path<K, N, E>)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>) -> [f64; D],
+{
+	fn dominates<const D: usize>(a: &[f64; D], b: &[f64; D]) -> bool {
+		a.iter().zip(b.iter()).all(|(x, y)| x <= y)
+	}
+	// Every label holds its costs, the node it's at, the edge it was reached
+	// through with the index of the previous label, and whether it has been
+	// dominated since it was queued.
+	let mut labels: Vec<Label<K, N, E, D>> = vec![([0.0; D], source.clone(), None, false)];
+	let mut at: HashMap<K, Vec<usize>> = HashMap::new();
+	let mut found: Vec<usize> = Vec::new();
+	let mut heap = BinaryHeap::new();
+	at.insert(source.key().clone(), vec![0]);
+	heap.push(MinCost { cost: 0.0, item: 0 });
+	while let Some(MinCost { item: l, .. }) = heap.pop() {
+		if labels[l].3 {
+			continue;
+		}
+		let (costs, node) = (labels[l].0, labels[l].1.clone());
+		if node == *target {
+			found.push(l);
+			continue;
+		}
+		for (edge, next) in node.incident(direction) {
+			let mut c = costs;
+			for (x, y) in c.iter_mut().zip(cost(&edge).iter()) {
+				*x += y;
+			}
+			if found.iter().any(|f| dominates(&labels[*f].0, &c)) {
+				continue;
+			}
+			let existing = at.entry(next.key().clone()).or_default();
+			if existing.iter().any(|e| dominates(&labels[*e].0, &c)) {
+				continue;
+			}
+			existing.retain(|e| {
+				let dominated = dominates(&c, &labels[*e].0);
+				labels[*e].3 |= dominated;
+				!dominated
+			});
+			existing.push(labels.len());
+			heap.push(MinCost { cost: c.iter().sum(), item: labels.len() });
+			labels.push((c, next, Some((edge, l)), false));
+		}
+	}
+	let mut res: Vec<([f64; D], Path<K, N, E>)> = found
+		.into_iter()
+		.filter(|f| !labels[*f].3)
+		.map(|f| {
+			let mut path = Vec::new();
+			let mut current = f;
+			while let Some((edge, prev)) = &labels[current].2 {
+				path.push(Arc::downgrade(edge));
+				current = *prev;
+			}
+			path.reverse();
+			(labels[f].0, path)
+		})
+		.collect();
+	res.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+	res
+}
+
+/// # Simple Paths
+///
+/// Lazily enumerate the simple paths from the source node to the target
+/// node, which are the paths that don't visit any node twice, in depth first
+/// order. Paths longer than `max_len` edges are skipped and the enumeration
+/// ends after `max_count` paths. The amount of simple paths grows
+/// exponentially with the size of the graph, so the limits keep the search
+/// tractable. The neighbors of a node are collected when the search reaches
+/// it and no locks are held between paths.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let nodes: Vec<_> = (0..4).map(|i| Arc::new(Node::<u32, Empty, Empty>::new(i, Empty))).collect();
+/// connect(&nodes[0], &nodes[1], Empty);
+/// connect(&nodes[0], &nodes[2], Empty);
+/// connect(&nodes[1], &nodes[3], Empty);
+/// connect(&nodes[2], &nodes[3], Empty);
+/// connect(&nodes[1], &nodes[2], Empty);
+///
+/// let paths: Vec<_> = simple_paths(&nodes[0], &nodes[3], Direction::Outbound, usize::MAX, usize::MAX).collect();
+/// assert!(paths.len() == 3);
+/// assert!(simple_paths(&nodes[0], &nodes[3], Direction::Outbound, 2, usize::MAX).count() == 2);
+/// assert!(simple_paths(&nodes[0], &nodes[3], Direction::Outbound, 2, 1).count() == 1);
+/// ```
+pub fn simple_paths<K, N, E>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	max_len: usize,
+	max_count: usize,
+) -> SimplePaths<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut paths = SimplePaths::empty(target.key().clone(), direction, max_len, max_count);
+	if max_len > 0 {
+		paths.visited.insert(source.key().clone());
+		paths.stack.push((source.clone(), source.incident(direction), 0));
+	}
+	paths
+}
+
+/// Iterator over simple paths returned by `simple_paths`.
+pub struct SimplePaths<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	target: K,
+	direction: Direction,
+	max_len: usize,
+	remaining: usize,
+	stack: Vec<Branch<K, N, E>>,
+	path: Vec<Arc<Edge<K, N, E>>>,
+	visited: HashSet<K>,
+}
+
+impl<K, N, E> SimplePaths<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// An iterator that yields no paths.
+	pub(crate) fn empty(target: K, direction: Direction, max_len: usize, max_count: usize) -> Self {
+		SimplePaths {
+			target,
+			direction,
+			max_len,
+			remaining: max_count,
+			stack: Vec::new(),
+			path: Vec::new(),
+			visited: HashSet::new(),
+		}
+	}
+}
+
+impl<K, N, E> Iterator for SimplePaths<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	type Item = Path<K, N, E>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		while let Some((node, incident, i)) = self.stack.last_mut() {
+			if *i == incident.len() {
+				self.visited.remove(node.key());
+				self.stack.pop();
+				self.path.pop();
+				continue;
+			}
+			let (edge, next) = incident[*i].clone();
+			*i += 1;
+			if self.visited.contains(next.key()) {
+				continue;
+			}
+			if next.key() == &self.target {
+				self.remaining -= 1;
+				let mut res: Path<K, N, E> = self.path.iter().map(Arc::downgrade).collect();
+				res.push(Arc::downgrade(&edge));
+				return Some(res);
+			}
+			if self.path.len() + 1 < self.max_len {
+				self.visited.insert(next.key().clone());
+				let incident = next.incident(self.direction);
+				self.stack.push((next, incident, 0));
+				self.path.push(edge);
+			}
+		}
+		None
+	}
+}
+
+/// # Earliest Arrivals
+///
+/// Find the earliest arrival time at every node reachable from the source
+/// node when leaving at the `departure` time. The travel time of an edge
+/// depends on the time it's entered and is determined by the `duration`
+/// closure, which gets the edge and the entry time. The network must be FIFO,
+/// meaning that entering an edge later never results in an earlier arrival,
+/// so waiting at a node is never useful. Returns the reached nodes paired with
+/// their arrival time, in order of increasing arrival time.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::core::*;
+/// use fastgraph::shortest_path::*;
+/// use std::sync::Arc;
+///
+/// let n1 = Arc::new(Node::<u32, Empty, f64>::new(1, Empty));
+/// let n2 = Arc::new(Node::<u32, Empty, f64>::new(2, Empty));
+///
+/// connect(&n1, &n2, 1.0);
+///
+/// // Travel takes twice as long during the rush hour from 8 to 9.
+/// let duration = |edge: &Arc<Edge<u32, Empty, f64>>, time: f64| {
+///     if (8.0..9.0).contains(&time) { edge.load() * 2.0 } else { edge.load() }
+/// };
+///
+/// assert!(earliest_arrivals(&n1, Direction::Outbound, 7.0, duration)[1].1 == 8.0);
+/// assert!(earliest_arrivals(&n1, Direction::Outbound, 8.0, duration)[1].1 == 10.0);
+/// ```
+pub fn earliest_arrivals<K, N, E, F>(
+	source: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	departure: f64,
+	duration: F,
+) -> Distances<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>, f64) -> f64,
+{
+	let mut res = Vec::new();
+	let mut arrival: HashMap<K, f64> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	arrival.insert(source.key().clone(), departure);
+	heap.push(MinCost { cost: departure, item: source.clone() });
+	while let Some(MinCost { cost: t, item: node }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		for (edge, next) in node.incident(direction) {
+			if settled.contains(next.key()) {
+				continue;
+			}
+			let a = t + duration(&edge, t);
+			let earlier = match arrival.get(next.key()) {
+				Some(old) => a < *old,
+				None => true,
+			};
+			if earlier {
+				arrival.insert(next.key().clone(), a);
+				heap.push(MinCost { cost: a, item: next });
+			}
+		}
+		res.push((node, t));
+	}
+	res
+}
+
+/// # Earliest Arrival
+///
+/// Find the path from the source node to the target node that arrives the
+/// earliest when leaving at the `departure` time, with travel times that
+/// depend on the time an edge is entered. See `earliest_arrivals`. Returns
+/// the arrival time and the edges along the path in order, or None if the
+/// target can't be reached.
+pub fn earliest_arrival<K, N, E, F>(
+	source: &Arc<Node<K, N, E>>,
+	target: &Arc<Node<K, N, E>>,
+	direction: Direction,
+	departure: f64,
+	duration: F,
+) -> Option<(f64, Path<K, N, E>)>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	F: Fn(&Arc<Edge<K, N, E>>, f64) -> f64,
+{
+	let mut arrival: HashMap<K, f64> = HashMap::new();
+	let mut parent: Parents<K, N, E> = HashMap::new();
+	let mut settled: HashSet<K> = HashSet::new();
+	let mut heap = BinaryHeap::new();
+	arrival.insert(source.key().clone(), departure);
+	heap.push(MinCost { cost: departure, item: source.clone() });
+	while let Some(MinCost { cost: t, item: node }) = heap.pop() {
+		if !settled.insert(node.key().clone()) {
+			continue;
+		}
+		if node == *target {
+			return Some((t, backtrack_parents(&parent, target)));
+		}
+		for (edge, next) in node.incident(direction) {
+			if settled.contains(next.key()) {
+				continue;
+			}
+			let a = t + duration(&edge, t);
+			let earlier = match arrival.get(next.key()) {
+				Some(old) => a < *old,
+				None => true,
+			};
+			if earlier {
+				arrival.insert(next.key().clone(), a);
+				parent.insert(next.key().clone(), (edge, node.clone()));
+				heap.push(MinCost { cost: a, item: next });
+			}
+		}
+	}
+	None
+}
+
+// Walks a map of parent edges back from the target and returns the edges
+// leading to it in order.
+pub(crate) fn backtrack_parents<K, N, E>(
+	parent: &Parents<K, N, E>,
+	target: &Arc<Node<K, N, E>>,
+) -> Path<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let mut res = Vec::new();
+	let mut current = target.key();
+	while let Some((edge, prev)) = parent.get(current) {
+		res.push(Arc::downgrade(edge));
+		current = prev.key();
+	}
+	res.reverse();
+	res
+}
+
+//=============================================================================
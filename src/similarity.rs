@@ -0,0 +1,321 @@
+//=============================================================================
+// SIMILARITY
+//=============================================================================
+
+//! Measures of how similar two nodes are based on the structure of the graph
+//! around them. Neighborhood based measures are computed on demand for a pair
+//! of nodes, SimRank is computed for all pairs at once.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::centrality::NodeScores;
+use crate::indexed::Indexed;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+// Keys of the neighbors of a node excluding the node itself.
+fn neighbor_keys<K, N, E>(node: &Arc<Node<K, N, E>>, direction: Direction) -> HashSet<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	node.neighbors(direction)
+		.iter()
+		.filter(|n| *n != node)
+		.map(|n| n.key().clone())
+		.collect()
+}
+
+/// Amount of neighbors shared by two nodes.
+pub fn common_neighbors<K, N, E>(a: &Arc<Node<K, N, E>>, b: &Arc<Node<K, N, E>>, direction: Direction) -> usize
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let na = neighbor_keys(a, direction);
+	let nb = neighbor_keys(b, direction);
+	na.intersection(&nb).count()
+}
+
+/// Jaccard similarity of two nodes ie. the amount of shared neighbors divided
+/// by the amount of neighbors of either node.
+pub fn jaccard<K, N, E>(a: &Arc<Node<K, N, E>>, b: &Arc<Node<K, N, E>>, direction: Direction) -> f64
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let na = neighbor_keys(a, direction);
+	let nb = neighbor_keys(b, direction);
+	let union = na.union(&nb).count();
+	match union {
+		0 => { 0.0 }
+		_ => { na.intersection(&nb).count() as f64 / union as f64 }
+	}
+}
+
+/// Adamic-Adar index of two nodes. Shared neighbors are weighted by the
+/// inverse logarithm of their degree so that rare neighbors count more.
+pub fn adamic_adar<K, N, E>(a: &Arc<Node<K, N, E>>, b: &Arc<Node<K, N, E>>, direction: Direction) -> f64
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	let nb = neighbor_keys(b, direction);
+	let mut res = 0.0;
+	let mut seen = HashSet::new();
+	for z in a.neighbors(direction) {
+		if z == *a || !nb.contains(z.key()) || !seen.insert(z.key().clone()) {
+			continue;
+		}
+		let degree = neighbor_keys(&z, direction).len() as f64;
+		if degree > 1.0 {
+			res += 1.0 / degree.ln();
+		}
+	}
+	res
+}
+
+/// # Top K Similar
+///
+/// Find the `k` nodes most similar to the node with the given key according
+/// to the `scorer` closure. Every other node in the graph is scored in
+/// parallel. Returns the nodes in order of decreasing score, or None if the
+/// node can't be found.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::*;
+/// use fastgraph::similarity::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 1..5 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 3, Empty);
+/// g.add_edge(2, 3, Empty);
+/// g.add_edge(1, 4, Empty);
+///
+/// let similar = top_k_similar(&g, 2, 1, |a, b| jaccard(a, b, Direction::Both)).unwrap();
+///
+/// assert!(similar[0].0.key() == &1);
+/// ```
+pub fn top_k_similar<K, N, E, G, F>(graph: &G, key: K, k: usize, scorer: F) -> Option<NodeScores<K, N, E>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Node<K, N, E>>, &Arc<Node<K, N, E>>) -> f64 + Sync + Send,
+{
+	let node = graph.get_node(key)?;
+	let mut res: NodeScores<K, N, E> = graph.nodes()
+		.into_par_iter()
+		.filter(|other| *other != node)
+		.map(|other| {
+			let score = scorer(&node, &other);
+			(other, score)
+		})
+		.collect();
+	sort_scores(&mut res);
+	res.truncate(k);
+	Some(res)
+}
+
+// Sort scores in decreasing order.
+pub(crate) fn sort_scores<T>(scores: &mut [(T, f64)]) {
+	scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+}
+
+//=============================================================================
+
+/// SimRank similarity of all pairs of nodes. Two nodes are similar if their
+/// inbound neighbors are similar. In an undirected graph all neighbors are
+/// considered. Requires memory quadratic in the amount of nodes.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::*;
+/// use fastgraph::similarity::*;
+///
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 1..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(1, 3, Empty);
+///
+/// let simrank = SimRank::new(&g, 0.8, 10);
+///
+/// assert!(simrank.score(&2, &3).unwrap() == 0.8);
+/// ```
+pub struct SimRank<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	nodes: Vec<Arc<Node<K, N, E>>>,
+	index: HashMap<K, usize>,
+	scores: Vec<Vec<f64>>,
+}
+
+impl<K, N, E> SimRank<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Compute SimRank with the given `decay` factor for a fixed amount of
+	/// iterations. Rows of the score matrix are updated in parallel.
+	pub fn new<G>(graph: &G, decay: f64, iterations: usize) -> Self
+	where
+		G: Graph<K, N, E>,
+	{
+		let direction = match G::directed() {
+			true => { Direction::Inbound }
+			false => { Direction::Both }
+		};
+		let indexed = Indexed::new(graph.nodes(), direction);
+		let n = indexed.nodes.len();
+		let inbound: Vec<Vec<usize>> = indexed.adjacency
+			.iter()
+			.map(|adjacent| adjacent.iter().map(|(w, _)| *w).collect())
+			.collect();
+		let mut scores: Vec<Vec<f64>> = (0..n)
+			.map(|a| (0..n).map(|b| if a == b { 1.0 } else { 0.0 }).collect())
+			.collect();
+		for _ in 0..iterations {
+			scores = (0..n)
+				.into_par_iter()
+				.map(|a| {
+					(0..n)
+						.map(|b| {
+							if a == b {
+								return 1.0;
+							}
+							if inbound[a].is_empty() || inbound[b].is_empty() {
+								return 0.0;
+							}
+							let mut sum = 0.0;
+							for i in inbound[a].iter() {
+								for j in inbound[b].iter() {
+									sum += scores[*i][*j];
+								}
+							}
+							decay * sum / (inbound[a].len() * inbound[b].len()) as f64
+						})
+						.collect()
+				})
+				.collect();
+		}
+		SimRank { nodes: indexed.nodes, index: indexed.index, scores }
+	}
+
+	/// SimRank score of two nodes, or None if either node is unknown.
+	pub fn score(&self, a: &K, b: &K) -> Option<f64> {
+		Some(self.scores[*self.index.get(a)?][*self.index.get(b)?])
+	}
+
+	/// The `k` nodes most similar to the given node in order of decreasing
+	/// score, or None if the node is unknown.
+	pub fn top_k(&self, key: &K, k: usize) -> Option<NodeScores<K, N, E>> {
+		let a = *self.index.get(key)?;
+		let mut res: NodeScores<K, N, E> = self.scores[a]
+			.iter()
+			.enumerate()
+			.filter(|(b, _)| *b != a)
+			.map(|(b, score)| (self.nodes[b].clone(), *score))
+			.collect();
+		sort_scores(&mut res);
+		res.truncate(k);
+		Some(res)
+	}
+}
+
+//=============================================================================
+
+/// Pairs of nodes paired with a score.
+pub type PairScores<K, N, E> = Vec<(Arc<Node<K, N, E>>, Arc<Node<K, N, E>>, f64)>;
+
+/// # Link Prediction
+///
+/// Suggest the `top_k` most likely missing edges of the graph according to
+/// the `scorer` closure. Candidates are the pairs of nodes that are two hops
+/// apart but not adjacent, which covers every pair with a nonzero score for
+/// neighborhood based measures. Candidates are scored in parallel. In an
+/// undirected graph each pair is suggested once. Returns the pairs in order
+/// of decreasing score.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::*;
+/// use fastgraph::similarity::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 1..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(1, 2, Empty);
+/// g.add_edge(2, 3, Empty);
+///
+/// let links = predict_links(&g, 1, |a, b| common_neighbors(a, b, Direction::Both) as f64);
+///
+/// assert!(links.len() == 1);
+/// assert!(links[0].2 == 1.0);
+/// ```
+pub fn predict_links<K, N, E, G, F>(graph: &G, top_k: usize, scorer: F) -> PairScores<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Node<K, N, E>>, &Arc<Node<K, N, E>>) -> f64 + Sync + Send,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let mut res: Vec<(usize, usize, f64)> = (0..indexed.nodes.len())
+		.into_par_iter()
+		.map(|u| {
+			let adjacent: HashSet<usize> = indexed.adjacency[u].iter().map(|(w, _)| *w).collect();
+			let mut candidates = HashSet::new();
+			for v in adjacent.iter() {
+				for (w, _) in indexed.adjacency[*v].iter() {
+					if *w != u && !adjacent.contains(w) && (G::directed() || u < *w) {
+						candidates.insert(*w);
+					}
+				}
+			}
+			let mut scores: Vec<(usize, usize, f64)> = candidates
+				.into_iter()
+				.map(|w| (u, w, scorer(&indexed.nodes[u], &indexed.nodes[w])))
+				.collect();
+			scores.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+			scores.truncate(top_k);
+			scores
+		})
+		.flatten()
+		.collect();
+	res.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+	res.truncate(top_k);
+	res.into_iter()
+		.map(|(u, w, score)| (indexed.nodes[u].clone(), indexed.nodes[w].clone(), score))
+		.collect()
+}
+
+//=============================================================================
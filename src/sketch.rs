@@ -0,0 +1,450 @@
+//=============================================================================
+// SKETCHES
+//=============================================================================
+
+//! Probabilistic sketches that trade exactness for memory and time on large
+//! graphs. A HyperLogLog counter estimates the amount of distinct items it
+//! has seen in a fixed amount of registers, with a relative standard error
+//! of about `1.04 / sqrt(2^precision)`, and two counters combine into the
+//! counter of the union of their items. HyperANF keeps a counter for the
+//! ball around every node and grows the balls by one hop at a time by taking
+//! the union of the counters of the neighbors, which estimates the
+//! neighborhood function of the graph in a few passes over the edges.
+//!
+//! MinHash signatures summarize the neighbor set of every node so that the
+//! fraction of equal entries in two signatures estimates the Jaccard
+//! similarity of the sets. Locality sensitive hashing splits the signatures
+//! into bands and only compares the nodes that share a whole band with some
+//! other node, which finds the similar pairs without comparing all of them.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::similarity::PairScores;
+use rayon::prelude::*;
+use std::{
+	collections::{HashMap, HashSet},
+	fmt::{Debug, Display},
+	hash::{Hash, Hasher},
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// A HyperLogLog counter of distinct items.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::sketch::*;
+///
+/// let mut a = HyperLogLog::new(12);
+/// let mut b = HyperLogLog::new(12);
+/// for i in 0..10_000 {
+///     a.insert(&i);
+///     b.insert(&(i + 5_000));
+/// }
+/// assert!((a.estimate() - 10_000.0).abs() < 500.0);
+/// a.union(&b);
+/// assert!((a.estimate() - 15_000.0).abs() < 750.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct HyperLogLog {
+	precision: u8,
+	registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+	/// Create an empty counter with `2^precision` registers. The precision
+	/// is clamped between 4 and 16.
+	pub fn new(precision: u8) -> Self {
+		let precision = precision.clamp(4, 16);
+		HyperLogLog { precision, registers: vec![0; 1 << precision] }
+	}
+
+	/// Count an item.
+	pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+		let (register, rank) = position(item, self.precision);
+		self.registers[register] = self.registers[register].max(rank);
+	}
+
+	/// Merge the items of another counter of the same precision into this
+	/// one. Returns true if the counter changed. Panics if the precisions
+	/// differ.
+	pub fn union(&mut self, other: &HyperLogLog) -> bool {
+		assert!(
+			self.precision == other.precision,
+			"union of counters of precision {} and {}",
+			self.precision,
+			other.precision
+		);
+		merge(&mut self.registers, &other.registers)
+	}
+
+	/// Estimated amount of distinct items counted.
+	pub fn estimate(&self) -> f64 {
+		estimate(&self.registers)
+	}
+}
+
+//=============================================================================
+
+/// Estimates of the neighborhood function of a graph.
+#[derive(Clone, Debug)]
+pub struct Neighborhoods<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// Estimated amount of pairs of nodes within `t` hops of each other, for
+	/// every `t` from zero, including every node paired with itself.
+	pub function: Vec<f64>,
+	/// Estimated amount of nodes within the last amount of hops of every
+	/// node, including the node itself.
+	pub sizes: HashMap<K, f64>,
+}
+
+impl<K> Neighborhoods<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+{
+	/// The amount of hops, interpolated between whole hops, within which the
+	/// given fraction of the pairs of nodes within the last amount of hops
+	/// are. With a fraction of 0.9 this is the effective diameter.
+	pub fn effective_diameter(&self, fraction: f64) -> f64 {
+		let last = match self.function.last() {
+			Some(last) => { *last }
+			None => { return 0.0 }
+		};
+		let goal = fraction * last;
+		match self.function.iter().position(|pairs| *pairs >= goal) {
+			Some(0) | None => { 0.0 }
+			Some(t) => {
+				let (below, above) = (self.function[t - 1], self.function[t]);
+				(t - 1) as f64 + (goal - below) / (above - below)
+			}
+		}
+	}
+}
+
+/// # HyperANF
+///
+/// Estimate how many nodes are within up to `max_hops` hops of every node
+/// along edges in the direction of the graph with HyperLogLog counters of
+/// the given precision. Every hop is a parallel pass over the edges and the
+/// passes stop early once no ball grows. The memory used is `2^precision`
+/// bytes per node.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::sketch::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..100 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 1..100 {
+///     g.add_edge(i - 1, i, Empty);
+/// }
+///
+/// let anf = hyper_anf(&g, 10, 10);
+/// assert!(anf.function.len() == 11);
+/// // 21 nodes are within 10 hops of the middle of the path.
+/// assert!((anf.sizes[&50] - 21.0).abs() < 2.0);
+/// ```
+pub fn hyper_anf<K, N, E, G>(graph: &G, precision: u8, max_hops: usize) -> Neighborhoods<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), G::direction());
+	let precision = precision.clamp(4, 16);
+	let m = 1 << precision;
+	let mut counters = vec![0u8; indexed.nodes.len() * m];
+	for (v, counter) in counters.chunks_mut(m).enumerate() {
+		let (register, rank) = position(indexed.nodes[v].key(), precision);
+		counter[register] = rank;
+	}
+	let sum = |counters: &[u8]| counters.par_chunks(m).map(estimate).sum::<f64>();
+	let mut function = vec![sum(&counters)];
+	let _span = trace_span!("hyper_anf", nodes = indexed.nodes.len(), precision = precision);
+	while function.len() <= max_hops {
+		let mut next = counters.clone();
+		let changed = next
+			.par_chunks_mut(m)
+			.enumerate()
+			.map(|(v, counter)| {
+				let mut changed = false;
+				for &(w, _) in indexed.adjacency[v].iter() {
+					changed |= merge(counter, &counters[w * m..(w + 1) * m]);
+				}
+				changed
+			})
+			.reduce(|| false, |a, b| a || b);
+		counters = next;
+		trace_event!("hop", hop = function.len(), changed = changed);
+		if !changed {
+			break;
+		}
+		function.push(sum(&counters));
+	}
+	// The balls stopped growing, so the function stays the same.
+	let last = function[function.len() - 1];
+	function.resize(max_hops + 1, last);
+	let sizes = counters
+		.chunks(m)
+		.enumerate()
+		.map(|(v, counter)| (indexed.key(v), estimate(counter)))
+		.collect();
+	Neighborhoods { function, sizes }
+}
+
+//=============================================================================
+
+/// MinHash signatures of the neighbor sets of the nodes of a graph. The
+/// neighbors of a node don't include the node itself, as in
+/// `similarity::jaccard`.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::{Direction, Empty};
+/// use fastgraph::sketch::*;
+///
+/// // Users 1 and 2 follow the same accounts, user 3 follows others.
+/// let mut g = Digraph::<u32, Empty, Empty>::new();
+/// for i in 1..40 {
+///     g.add_node(i, Empty);
+/// }
+/// for account in 10..30 {
+///     g.add_edge(1, account, Empty);
+///     g.add_edge(2, account, Empty);
+/// }
+/// for account in 30..40 {
+///     g.add_edge(3, account, Empty);
+/// }
+///
+/// let minhash = MinHash::new(&g, 128, Direction::Outbound);
+/// assert!(minhash.similarity(&1, &2) == Some(1.0));
+/// assert!(minhash.similarity(&1, &3) == Some(0.0));
+///
+/// let pairs = minhash.similar_pairs(32, 0.5);
+/// assert!(pairs.len() == 1 && pairs[0].2 == 1.0);
+/// ```
+pub struct MinHash<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	nodes: Vec<Arc<Node<K, N, E>>>,
+	index: HashMap<K, usize>,
+	// Signatures of the nodes by index, empty for nodes without neighbors.
+	signatures: Vec<Vec<u64>>,
+}
+
+impl<K, N, E> MinHash<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Compute signatures of `hashes` entries over the neighbors of every
+	/// node in the given direction, in parallel. The standard error of an
+	/// estimated similarity is at most `0.5 / sqrt(hashes)`.
+	pub fn new<G: Graph<K, N, E>>(graph: &G, hashes: usize, direction: Direction) -> Self {
+		let indexed = Indexed::new(graph.nodes(), direction);
+		let signatures = (0..indexed.nodes.len())
+			.into_par_iter()
+			.map(|v| {
+				let mut signature = Vec::new();
+				for &(w, _) in indexed.adjacency[v].iter().filter(|(w, _)| *w != v) {
+					let x = hash(indexed.nodes[w].key());
+					if signature.is_empty() {
+						signature = vec![u64::MAX; hashes];
+					}
+					for (i, min) in signature.iter_mut().enumerate() {
+						*min = (*min).min(mix(x ^ (i as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)));
+					}
+				}
+				signature
+			})
+			.collect();
+		MinHash { nodes: indexed.nodes, index: indexed.index, signatures }
+	}
+
+	/// Signature of a node, empty if the node has no neighbors, or None if
+	/// the node can't be found.
+	pub fn signature(&self, key: &K) -> Option<&[u64]> {
+		Some(&self.signatures[*self.index.get(key)?])
+	}
+
+	/// Estimated Jaccard similarity of the neighbors of two nodes, zero if
+	/// either has no neighbors, or None if either node can't be found.
+	pub fn similarity(&self, a: &K, b: &K) -> Option<f64> {
+		Some(self.estimate(*self.index.get(a)?, *self.index.get(b)?))
+	}
+
+	/// Pairs of nodes with an estimated similarity of at least the threshold,
+	/// most similar first. Only pairs of nodes whose signatures are equal in
+	/// at least one of the `bands` bands are compared, so a pair with a
+	/// similarity of `s` is found with a probability of `1 - (1 - s^r)^b`
+	/// where `r` is the amount of hashes in a band. More bands find more of
+	/// the pairs with a low similarity at the cost of more comparisons.
+	pub fn similar_pairs(&self, bands: usize, threshold: f64) -> PairScores<K, N, E> {
+		let candidates = self.candidates(bands);
+		let mut res: Vec<(usize, usize, f64)> = candidates
+			.into_par_iter()
+			.map(|(u, w)| (u, w, self.estimate(u, w)))
+			.filter(|(_, _, s)| *s >= threshold)
+			.collect();
+		res.sort_by(|a, b| b.2.total_cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+		res.into_iter()
+			.map(|(u, w, s)| (self.nodes[u].clone(), self.nodes[w].clone(), s))
+			.collect()
+	}
+
+	// Pairs of nodes sharing a band, with the smaller index first.
+	fn candidates(&self, bands: usize) -> Vec<(usize, usize)> {
+		let hashes = self.signatures.iter().map(|s| s.len()).max().unwrap_or(0);
+		let rows = match bands {
+			0 => { return Vec::new() }
+			_ => { (hashes / bands).max(1) }
+		};
+		let mut res = HashSet::new();
+		for band in 0..bands.min(hashes) {
+			let range = band * rows..((band + 1) * rows).min(hashes);
+			let mut buckets: HashMap<&[u64], Vec<usize>> = HashMap::new();
+			for (v, signature) in self.signatures.iter().enumerate() {
+				if !signature.is_empty() && !range.is_empty() {
+					buckets.entry(&signature[range.clone()]).or_default().push(v);
+				}
+			}
+			for bucket in buckets.values() {
+				for (i, u) in bucket.iter().enumerate() {
+					for w in bucket[i + 1..].iter() {
+						res.insert((*u, *w));
+					}
+				}
+			}
+		}
+		res.into_iter().collect()
+	}
+
+	fn estimate(&self, u: usize, w: usize) -> f64 {
+		let (a, b) = (&self.signatures[u], &self.signatures[w]);
+		match a.is_empty() || b.is_empty() {
+			true => { 0.0 }
+			false => { a.iter().zip(b.iter()).filter(|(x, y)| x == y).count() as f64 / a.len() as f64 }
+		}
+	}
+}
+
+//=============================================================================
+
+// Hash of an item that is the same in every run of the program and in every
+// build of it, whatever the version of Rust or the platform.
+pub(crate) fn hash<T: Hash + ?Sized>(item: &T) -> u64 {
+	let mut hasher = FixedHasher(0xCBF2_9CE4_8422_2325);
+	item.hash(&mut hasher);
+	hasher.finish()
+}
+
+// FNV-1a over the written bytes, finished with the SplitMix64 finalizer.
+// Unlike the hasher of the standard library its algorithm is fixed, and
+// integers are written in little endian and widened to 64 bits so that the
+// hash doesn't depend on the platform either.
+struct FixedHasher(u64);
+
+impl Hasher for FixedHasher {
+	fn write(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01B3);
+		}
+	}
+
+	fn write_u8(&mut self, i: u8) {
+		self.write(&[i]);
+	}
+
+	fn write_u16(&mut self, i: u16) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u32(&mut self, i: u32) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u64(&mut self, i: u64) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u128(&mut self, i: u128) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_usize(&mut self, i: usize) {
+		self.write_u64(i as u64);
+	}
+
+	fn write_isize(&mut self, i: isize) {
+		self.write_u64(i as i64 as u64);
+	}
+
+	fn finish(&self) -> u64 {
+		mix(self.0)
+	}
+}
+
+// Finalizer of SplitMix64, which turns a hash into one of a family of
+// independent hashes when it's salted differently for every member.
+fn mix(mut x: u64) -> u64 {
+	x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	x ^ (x >> 31)
+}
+
+// The register of an item and the position of the first set bit of the rest
+// of its hash.
+fn position<T: Hash + ?Sized>(item: &T, precision: u8) -> (usize, u8) {
+	let hash = hash(item);
+	let register = (hash >> (64 - precision)) as usize;
+	let rank = ((hash << precision) | (1 << (precision - 1))).leading_zeros() + 1;
+	(register, rank as u8)
+}
+
+fn merge(registers: &mut [u8], other: &[u8]) -> bool {
+	let mut changed = false;
+	for (r, o) in registers.iter_mut().zip(other.iter()) {
+		if *o > *r {
+			*r = *o;
+			changed = true;
+		}
+	}
+	changed
+}
+
+fn estimate(registers: &[u8]) -> f64 {
+	let m = registers.len() as f64;
+	let alpha = match registers.len() {
+		16 => { 0.673 }
+		32 => { 0.697 }
+		64 => { 0.709 }
+		_ => { 0.7213 / (1.0 + 1.079 / m) }
+	};
+	let sum: f64 = registers.iter().map(|r| (-(*r as f64)).exp2()).sum();
+	let raw = alpha * m * m / sum;
+	let zeros = registers.iter().filter(|r| **r == 0).count();
+	// Linear counting is more accurate while many registers are empty.
+	match raw <= 2.5 * m && zeros > 0 {
+		true => { m * (m / zeros as f64).ln() }
+		false => { raw }
+	}
+}
+
+//=============================================================================
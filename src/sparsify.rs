@@ -0,0 +1,333 @@
+//=============================================================================
+// SPARSIFICATION
+//=============================================================================
+
+//! Sparsification builds a new graph with far fewer edges that approximately
+//! preserves a property of the original. A spanner keeps every distance
+//! within a factor of the original distance, which preserves routing quality.
+//! Sparsifiers sample edges at random and scale the weights of the kept
+//! edges up so that cut sizes are preserved in expectation. Sampling by
+//! effective resistance keeps the edges that matter for connectivity, like
+//! bridges, while uniform sampling treats every edge alike. The sparsified
+//! graphs have the same nodes as the original and receive copies of the
+//! node and edge data.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::shortest_path::MinCost;
+use crate::sketch::hash;
+use rand::Rng;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::{
+	collections::BinaryHeap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// # Greedy Spanner
+///
+/// Build a t-spanner of the graph, a subgraph in which the distance between
+/// any two nodes is at most `stretch` times their distance in the graph.
+/// Edges are considered in order of increasing weight given by the `weight`
+/// closure and kept only if the spanner built so far doesn't already connect
+/// their endpoints within `stretch` times their weight. Distances follow the
+/// direction of the graph. Weights must not be negative.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::sparsify::*;
+///
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..4 {
+///     g.add_node(i, Empty);
+/// }
+/// g.add_edge(0, 1, 1.0);
+/// g.add_edge(1, 2, 1.0);
+/// g.add_edge(2, 3, 1.0);
+/// g.add_edge(0, 3, 2.5);
+///
+/// // The path 0 - 1 - 2 - 3 is within 1.5 times the direct edge.
+/// let spanner = greedy_spanner(&g, 1.5, |e| e.load());
+/// assert!(spanner.edge_count() == 3);
+/// ```
+pub fn greedy_spanner<K, N, E, G, F>(graph: &G, stretch: f64, weight: F) -> G
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let weights: Vec<f64> = indexed.edges.iter().map(weight).collect();
+	let mut order: Vec<(usize, usize, usize)> = Vec::with_capacity(weights.len());
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for (w, e) in list.iter() {
+			order.push((u, *w, *e));
+		}
+	}
+	order.sort_by(|a, b| weights[a.2].partial_cmp(&weights[b.2]).unwrap_or(std::cmp::Ordering::Equal));
+	let mut spanner: Vec<Vec<(usize, f64)>> = vec![Vec::new(); indexed.nodes.len()];
+	let mut kept = Vec::new();
+	for (u, w, e) in order {
+		let limit = stretch * weights[e];
+		if u != w && !within(&spanner, u, w, limit) {
+			spanner[u].push((w, weights[e]));
+			if !G::directed() {
+				spanner[w].push((u, weights[e]));
+			}
+			kept.push(e);
+		}
+	}
+	subgraph(&indexed, kept.into_iter().map(|e| (e, None)), |data, _| data)
+}
+
+/// # Random Sparsifier
+///
+/// Keep every edge independently with probability `p`. The weights of the
+/// kept edges are divided by `p` with the `reweight` closure, which gets the
+/// edge data and the new weight and returns the new data, so that the total
+/// weight of every cut is preserved in expectation. Edges are sampled in an
+/// order that doesn't depend on the order of the nodes in the graph, so the
+/// same random number generator keeps the same edges in every run.
+pub fn random_sparsifier<K, N, E, G, F, W, R>(graph: &G, p: f64, weight: F, reweight: W, rng: &mut R) -> G
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	W: Fn(E, f64) -> E,
+	R: Rng + ?Sized,
+{
+	let indexed = Indexed::new(stable_nodes(graph), Direction::Outbound);
+	let kept: Vec<(usize, Option<f64>)> = indexed.edges
+		.iter()
+		.enumerate()
+		.filter(|_| rng.gen::<f64>() < p)
+		.map(|(e, edge)| (e, Some(weight(edge) / p)))
+		.collect();
+	subgraph(&indexed, kept.into_iter(), reweight)
+}
+
+/// # Spectral Sparsifier
+///
+/// Sample about `edges` edges with probabilities proportional to their
+/// weight times their effective resistance, the resistance between their
+/// endpoints when every edge is a resistor with a conductance equal to its
+/// weight. Edges that are the only connection between parts of the graph
+/// have a high resistance and are almost always kept. The weights of the
+/// kept edges are divided by their probability with the `reweight` closure,
+/// which preserves the Laplacian of the graph and so every cut in
+/// expectation. Edges are treated as undirected and weights must be
+/// positive.
+///
+/// Resistances are estimated from random projections that are solved with
+/// the conjugate gradient method in parallel, so they are rough but
+/// sufficient for sampling. Like `random_sparsifier` the same random number
+/// generator keeps the same edges in every run.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::sparsify::*;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // Two cliques joined by a bridge.
+/// let mut g = Ungraph::<u32, Empty, f64>::new();
+/// for i in 0..40 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..40 {
+///     for j in i + 1..40 {
+///         if (i < 20) == (j < 20) {
+///             g.add_edge(i, j, 1.0);
+///         }
+///     }
+/// }
+/// g.add_edge(0, 20, 1.0);
+///
+/// let sparse = spectral_sparsifier(&g, 150, |e| e.load(), |_, w| w, &mut StdRng::seed_from_u64(1));
+/// assert!(sparse.edge_count() < g.edge_count() / 2);
+/// assert!(sparse.get_edge(0, 20).is_some());
+/// ```
+pub fn spectral_sparsifier<K, N, E, G, F, W, R>(graph: &G, edges: usize, weight: F, reweight: W, rng: &mut R) -> G
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	F: Fn(&Arc<Edge<K, N, E>>) -> f64,
+	W: Fn(E, f64) -> E,
+	R: Rng + ?Sized,
+{
+	let indexed = Indexed::new(stable_nodes(graph), Direction::Outbound);
+	let n = indexed.nodes.len();
+	let mut endpoints = vec![(0, 0); indexed.edges.len()];
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for (w, e) in list.iter() {
+			endpoints[*e] = (u, *w);
+		}
+	}
+	let weights: Vec<f64> = indexed.edges.iter().map(weight).collect();
+	let resistances = effective_resistances(n, &endpoints, &weights, rng);
+	// Weights times resistances sum to the amount of nodes minus the amount
+	// of components, which normalizes them into probabilities.
+	let total: f64 = weights.iter().zip(resistances.iter()).map(|(w, r)| w * r).sum();
+	let kept: Vec<(usize, Option<f64>)> = (0..endpoints.len())
+		.filter_map(|e| {
+			let p = (edges as f64 * weights[e] * resistances[e] / total).min(1.0);
+			match rng.gen::<f64>() < p {
+				true => { Some((e, Some(weights[e] / p))) }
+				false => { None }
+			}
+		})
+		.collect();
+	subgraph(&indexed, kept.into_iter(), reweight)
+}
+
+//=============================================================================
+
+// True if the spanner connects the nodes with a path of at most the given
+// length.
+fn within(spanner: &[Vec<(usize, f64)>], source: usize, target: usize, limit: f64) -> bool {
+	let mut dist = vec![f64::INFINITY; spanner.len()];
+	let mut heap = BinaryHeap::new();
+	dist[source] = 0.0;
+	heap.push(MinCost { cost: 0.0, item: source });
+	while let Some(MinCost { cost: d, item: u }) = heap.pop() {
+		if u == target {
+			return true;
+		}
+		if d > dist[u] {
+			continue;
+		}
+		for (w, c) in spanner[u].iter() {
+			let next = d + c;
+			if next <= limit && next < dist[*w] {
+				dist[*w] = next;
+				heap.push(MinCost { cost: next, item: *w });
+			}
+		}
+	}
+	false
+}
+
+// Nodes of the graph ordered by a hash of their keys that is the same in
+// every run, rather than by the order of the nodes in the graph.
+fn stable_nodes<K, N, E, G>(graph: &G) -> Vec<Arc<Node<K, N, E>>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let mut nodes = graph.nodes();
+	nodes.sort_by_cached_key(|n| hash(n.key()));
+	nodes
+}
+
+// Copy the nodes and the given edges into a new graph, reweighting the
+// edges that have a new weight.
+fn subgraph<K, N, E, G, W, I>(indexed: &Indexed<K, N, E>, edges: I, reweight: W) -> G
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	W: Fn(E, f64) -> E,
+	I: Iterator<Item = (usize, Option<f64>)>,
+{
+	let mut res = G::new();
+	for node in indexed.nodes.iter() {
+		res.add_node(node.key().clone(), node.load());
+	}
+	for (e, scaled) in edges {
+		let edge = &indexed.edges[e];
+		let data = match scaled {
+			Some(w) => { reweight(edge.load(), w) }
+			None => { edge.load() }
+		};
+		res.add_edge(edge.source().key().clone(), edge.target().key().clone(), data);
+	}
+	res
+}
+
+// Estimate the effective resistance of every edge from the differences of
+// its endpoints in random projections of the pseudoinverse of the Laplacian.
+fn effective_resistances<R: Rng + ?Sized>(n: usize, endpoints: &[(usize, usize)], weights: &[f64], rng: &mut R) -> Vec<f64> {
+	let mut laplacian: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+	for ((u, w), c) in endpoints.iter().zip(weights.iter()) {
+		if u != w {
+			laplacian[*u].push((*w, *c));
+			laplacian[*w].push((*u, *c));
+		}
+	}
+	let projections = ((8.0 * (n.max(2) as f64).ln()).ceil() as usize).max(8);
+	let signs: Vec<Vec<bool>> = (0..projections)
+		.map(|_| (0..endpoints.len()).map(|_| rng.gen()).collect())
+		.collect();
+	let solutions: Vec<Vec<f64>> = signs
+		.into_par_iter()
+		.map(|signs| {
+			let mut b = vec![0.0; n];
+			for (((u, w), c), positive) in endpoints.iter().zip(weights.iter()).zip(signs.iter()) {
+				let s = if *positive { c.sqrt() } else { -c.sqrt() };
+				b[*u] += s;
+				b[*w] -= s;
+			}
+			conjugate_gradient(&laplacian, &b)
+		})
+		.collect();
+	endpoints
+		.iter()
+		.map(|(u, w)| solutions.iter().map(|x| (x[*u] - x[*w]).powi(2)).sum::<f64>() / projections as f64)
+		.collect()
+}
+
+// Solve `L x = b` for a Laplacian given as weighted adjacency lists where the
+// entries of `b` sum to zero within every component.
+fn conjugate_gradient(laplacian: &[Vec<(usize, f64)>], b: &[f64]) -> Vec<f64> {
+	let n = b.len();
+	let multiply = |x: &[f64]| -> Vec<f64> {
+		laplacian
+			.iter()
+			.enumerate()
+			.map(|(u, list)| list.iter().map(|(w, c)| c * (x[u] - x[*w])).sum())
+			.collect()
+	};
+	let dot = |a: &[f64], b: &[f64]| a.iter().zip(b.iter()).map(|(x, y)| x * y).sum::<f64>();
+	let mut x = vec![0.0; n];
+	let mut r = b.to_vec();
+	let mut p = r.clone();
+	let mut rr = dot(&r, &r);
+	let tolerance = 1e-10 * rr.max(f64::MIN_POSITIVE);
+	for _ in 0..n.max(1) * 2 {
+		if rr <= tolerance {
+			break;
+		}
+		let lp = multiply(&p);
+		let alpha = rr / dot(&p, &lp);
+		for i in 0..n {
+			x[i] += alpha * p[i];
+			r[i] -= alpha * lp[i];
+		}
+		let next = dot(&r, &r);
+		for i in 0..n {
+			p[i] = r[i] + next / rr * p[i];
+		}
+		rr = next;
+	}
+	x
+}
+
+//=============================================================================
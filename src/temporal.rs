@@ -0,0 +1,360 @@
+//=============================================================================
+// TEMPORAL GRAPHS
+//=============================================================================
+
+//! Queries over graphs whose edges are active during an interval of time,
+//! such as contacts, transactions or scheduled connections. The interval of
+//! an edge is given by a closure over the edge, which allows the times to be
+//! stored in any form in the edge data. The index keeps the intervals sorted
+//! by their start in implicit interval trees, one over all of the edges and
+//! one over the edges into and out of every node, so a window query only
+//! visits the edges that overlap it and a logarithmic amount of others.
+//!
+//! The same intervals also serve as the departure and arrival times of the
+//! edges for time-respecting paths, where every edge must depart no earlier
+//! than the previous edge arrived. Reachability along such paths isn't
+//! transitive, so it can't be answered by a static search of the graph.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use crate::indexed::Indexed;
+use crate::shortest_path::{Distances, MinCost};
+use std::{
+	collections::{BinaryHeap, VecDeque},
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+//=============================================================================
+
+/// Index of the intervals during which the edges of a graph are active.
+/// Intervals are closed, and an edge with an equal start and end is active
+/// at a single instant.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::{Direction, Empty};
+/// use fastgraph::temporal::*;
+///
+/// // Contacts of an hour between people by the hour they started.
+/// let mut g = Ungraph::<&str, Empty, f64>::new();
+/// for person in ["ann", "bob", "cid", "dan"] {
+///     g.add_node(person, Empty);
+/// }
+/// g.add_edge("ann", "bob", 9.0);
+/// g.add_edge("bob", "cid", 12.0);
+/// g.add_edge("cid", "dan", 9.5);
+///
+/// let index = TemporalIndex::new(&g, |edge| (edge.load(), edge.load() + 1.0));
+/// assert!(index.edges_between(9.5, 11.0).len() == 2);
+/// assert!(index.edges_between(10.75, 11.5).is_empty());
+///
+/// // Everyone within two contacts of bob in the morning.
+/// let morning: Ungraph<&str, Empty, f64> = index.ego("bob", 2, Direction::Both, 8.0, 11.0).unwrap();
+/// assert!(morning.node_count() == 2);
+/// ```
+pub struct TemporalIndex<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	indexed: Indexed<K, N, E>,
+	all: Intervals,
+	outbound: Vec<Intervals>,
+	inbound: Vec<Intervals>,
+	revision: Option<u64>,
+}
+
+impl<K, N, E> TemporalIndex<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Index the edges of the graph with the start and end of their intervals
+	/// given by the `interval` closure. The start of an interval must not be
+	/// after its end.
+	pub fn new<G, F>(graph: &G, interval: F) -> Self
+	where
+		G: Graph<K, N, E>,
+		F: Fn (&Arc<Edge<K, N, E>>) -> (f64, f64),
+	{
+		let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+		let times: Vec<(f64, f64)> = indexed.edges.iter().map(interval).collect();
+		let mut outbound = vec![Vec::new(); indexed.nodes.len()];
+		let mut inbound = vec![Vec::new(); indexed.nodes.len()];
+		for (u, adjacent) in indexed.adjacency.iter().enumerate() {
+			for &(w, e) in adjacent.iter() {
+				outbound[u].push(Span { start: times[e].0, end: times[e].1, edge: e, node: w });
+				inbound[w].push(Span { start: times[e].0, end: times[e].1, edge: e, node: u });
+			}
+		}
+		let all = times
+			.iter()
+			.zip(indexed.edges.iter())
+			.enumerate()
+			.map(|(e, (t, edge))| Span { start: t.0, end: t.1, edge: e, node: indexed.index[edge.source().key()] })
+			.collect();
+		TemporalIndex {
+			all: Intervals::new(all),
+			outbound: outbound.into_iter().map(Intervals::new).collect(),
+			inbound: inbound.into_iter().map(Intervals::new).collect(),
+			revision: graph.revision(),
+			indexed,
+		}
+	}
+
+	/// True if the graph hasn't changed since the index was built. Queries
+	/// over a stale index may miss edges or return deleted ones.
+	pub fn is_fresh<G: Graph<K, N, E>>(&self, graph: &G) -> bool {
+		self.revision.is_some() && graph.revision() == self.revision
+	}
+
+	/// Edges active at some time between `from` and `to`, in order of their
+	/// start.
+	pub fn edges_between(&self, from: f64, to: f64) -> Vec<Arc<Edge<K, N, E>>> {
+		let mut res = Vec::new();
+		self.all.overlapping(from, to, &mut |s| res.push(self.indexed.edges[s.edge].clone()));
+		res
+	}
+
+	/// Edges of a node in the given direction active at some time between
+	/// `from` and `to`. Returns None if the node can't be found.
+	pub fn edges_of(&self, key: &K, direction: Direction, from: f64, to: f64) -> Option<Vec<Arc<Edge<K, N, E>>>> {
+		let v = *self.indexed.index.get(key)?;
+		let mut res = Vec::new();
+		self.incident(v, direction, from, to, &mut |s| res.push(self.indexed.edges[s.edge].clone()));
+		Some(res)
+	}
+
+	/// Temporal ego network, the subgraph of the nodes within `k` hops of the
+	/// `center` node along edges in the given direction that are active at
+	/// some time between `from` and `to`, together with those edges of the
+	/// window between them. The type of the new graph is chosen by the
+	/// caller. Returns None if the center node can't be found.
+	pub fn ego<G: Graph<K, N, E>>(&self, center: K, k: usize, direction: Direction, from: f64, to: f64) -> Option<G> {
+		let center = *self.indexed.index.get(&center)?;
+		let mut depth: Vec<Option<usize>> = vec![None; self.indexed.nodes.len()];
+		let mut members = Vec::new();
+		let mut queue = VecDeque::new();
+		depth[center] = Some(0);
+		queue.push_back(center);
+		while let Some(v) = queue.pop_front() {
+			members.push(v);
+			let d = depth[v].unwrap_or(0);
+			if d < k {
+				self.incident(v, direction, from, to, &mut |s| {
+					if depth[s.node].is_none() {
+						depth[s.node] = Some(d + 1);
+						queue.push_back(s.node);
+					}
+				});
+			}
+		}
+		let mut g = G::new();
+		for v in members.iter() {
+			g.add_node(self.indexed.key(*v), self.indexed.nodes[*v].load());
+		}
+		for v in members.iter() {
+			self.outbound[*v].overlapping(from, to, &mut |s| {
+				if depth[s.node].is_some() {
+					g.add_edge(self.indexed.key(*v), self.indexed.key(s.node), self.indexed.edges[s.edge].load());
+				}
+			});
+		}
+		Some(g)
+	}
+
+	/// # Earliest Arrival
+	///
+	/// Time-respecting path from the source to the target that arrives the
+	/// earliest when leaving no earlier than the `departure` time. Every edge
+	/// departs at the start of its interval and arrives at its end, and can
+	/// only be taken if it departs no earlier than the previous edge on the
+	/// path arrived. Edges are followed in the given direction, always
+	/// forward in time. Returns the arrival time and the edges along the path
+	/// in order, or None if either node can't be found or the target can't
+	/// be reached.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// use fastgraph::collections::*;
+	/// use fastgraph::core::{Direction, Empty};
+	/// use fastgraph::temporal::*;
+	///
+	/// // Trains between stations with their departure and arrival times.
+	/// let mut g = Digraph::<&str, Empty, f64>::new();
+	/// for station in ["a", "b", "c", "d"] {
+	///     g.add_node(station, Empty);
+	/// }
+	/// g.add_edge("a", "b", 8.0);
+	/// g.add_edge("b", "c", 8.5);
+	/// g.add_edge("a", "d", 8.0);
+	/// g.add_edge("d", "c", 9.0);
+	///
+	/// // Every train takes an hour, so the train from b to c is missed.
+	/// let index = TemporalIndex::new(&g, |edge| (edge.load(), edge.load() + 1.0));
+	/// let (arrival, path) = index.earliest_arrival(&"a", &"c", Direction::Outbound, 7.0).unwrap();
+	/// assert!(arrival == 10.0 && path.len() == 2);
+	/// assert!(path[0].upgrade().unwrap().target().key() == &"d");
+	/// assert!(index.earliest_arrival(&"a", &"c", Direction::Outbound, 8.5).is_none());
+	/// ```
+	pub fn earliest_arrival(&self, source: &K, target: &K, direction: Direction, departure: f64) -> Option<(f64, Path<K, N, E>)> {
+		let s = *self.indexed.index.get(source)?;
+		let t = *self.indexed.index.get(target)?;
+		let (arrival, parent) = self.search(s, Some(t), direction, departure, f64::INFINITY);
+		if arrival[t].is_infinite() {
+			return None;
+		}
+		let mut path = Vec::new();
+		let mut v = t;
+		while let Some((e, u)) = parent[v] {
+			path.push(Arc::downgrade(&self.indexed.edges[e]));
+			v = u;
+		}
+		path.reverse();
+		Some((arrival[t], path))
+	}
+
+	/// # Temporal Reachability
+	///
+	/// Nodes reachable from the source along time-respecting paths that leave
+	/// no earlier than `from` and arrive no later than `to`, see
+	/// `earliest_arrival`. Returns the reached nodes paired with their
+	/// earliest arrival time in order of increasing arrival, starting from the
+	/// source itself, or None if the source can't be found.
+	pub fn reachable(&self, source: &K, direction: Direction, from: f64, to: f64) -> Option<Distances<K, N, E>> {
+		let s = *self.indexed.index.get(source)?;
+		let (arrival, _) = self.search(s, None, direction, from, to);
+		let mut res: Distances<K, N, E> = arrival
+			.into_iter()
+			.enumerate()
+			.filter(|(_, a)| a.is_finite())
+			.map(|(v, a)| (self.indexed.nodes[v].clone(), a))
+			.collect();
+		res.sort_by(|a, b| a.1.total_cmp(&b.1));
+		Some(res)
+	}
+
+	// Earliest arrival times from the source with the edge and the node every
+	// node was reached through. Arriving earlier at a node never leaves fewer
+	// edges to depart along, so labels are settled in order of arrival.
+	fn search(&self, source: usize, target: Option<usize>, direction: Direction, from: f64, to: f64) -> (Vec<f64>, Vec<Option<(usize, usize)>>) {
+		let n = self.indexed.nodes.len();
+		let mut arrival = vec![f64::INFINITY; n];
+		let mut parent = vec![None; n];
+		let mut settled = vec![false; n];
+		let mut heap = BinaryHeap::new();
+		arrival[source] = from;
+		heap.push(MinCost { cost: from, item: source });
+		while let Some(MinCost { cost: t, item: v }) = heap.pop() {
+			if settled[v] {
+				continue;
+			}
+			settled[v] = true;
+			if Some(v) == target {
+				break;
+			}
+			let mut relax = |spans: &[Span]| {
+				for s in spans.iter().take_while(|s| s.start <= to) {
+					if s.end <= to && s.end < arrival[s.node] && !settled[s.node] {
+						arrival[s.node] = s.end;
+						parent[s.node] = Some((s.edge, v));
+						heap.push(MinCost { cost: s.end, item: s.node });
+					}
+				}
+			};
+			if direction != Direction::Inbound {
+				relax(self.outbound[v].departing(t));
+			}
+			if direction != Direction::Outbound {
+				relax(self.inbound[v].departing(t));
+			}
+		}
+		(arrival, parent)
+	}
+
+	fn incident<F: FnMut (&Span)>(&self, v: usize, direction: Direction, from: f64, to: f64, visit: &mut F) {
+		if direction != Direction::Inbound {
+			self.outbound[v].overlapping(from, to, visit);
+		}
+		if direction != Direction::Outbound {
+			self.inbound[v].overlapping(from, to, visit);
+		}
+	}
+}
+
+//=============================================================================
+
+// Interval of an edge with the index of the node on its other end.
+#[derive(Clone, Copy)]
+struct Span {
+	start: f64,
+	end: f64,
+	edge: usize,
+	node: usize,
+}
+
+// Intervals sorted by their start. The middle of every range of the sorted
+// intervals is the root of an implicit binary tree with the two halves as
+// its subtrees, and `max_end` holds the latest end in the tree of each root.
+struct Intervals {
+	spans: Vec<Span>,
+	max_end: Vec<f64>,
+}
+
+impl Intervals {
+	fn new(mut spans: Vec<Span>) -> Self {
+		spans.sort_by(|a, b| a.start.total_cmp(&b.start));
+		let mut max_end = vec![f64::NEG_INFINITY; spans.len()];
+		Intervals::build(&spans, &mut max_end, 0, spans.len());
+		Intervals { spans, max_end }
+	}
+
+	fn build(spans: &[Span], max_end: &mut [f64], lo: usize, hi: usize) -> f64 {
+		if lo >= hi {
+			return f64::NEG_INFINITY;
+		}
+		let mid = lo + (hi - lo) / 2;
+		let left = Intervals::build(spans, max_end, lo, mid);
+		let right = Intervals::build(spans, max_end, mid + 1, hi);
+		max_end[mid] = spans[mid].end.max(left).max(right);
+		max_end[mid]
+	}
+
+	// Intervals that start no earlier than the given time.
+	fn departing(&self, time: f64) -> &[Span] {
+		&self.spans[self.spans.partition_point(|s| s.start < time)..]
+	}
+
+	// Visit the intervals overlapping the window in order of their start.
+	fn overlapping<F: FnMut (&Span)>(&self, from: f64, to: f64, visit: &mut F) {
+		self.search(0, self.spans.len(), from, to, visit);
+	}
+
+	fn search<F: FnMut (&Span)>(&self, lo: usize, hi: usize, from: f64, to: f64, visit: &mut F) {
+		if lo >= hi {
+			return;
+		}
+		let mid = lo + (hi - lo) / 2;
+		if self.max_end[mid] < from {
+			return;
+		}
+		self.search(lo, mid, from, to, visit);
+		// Intervals after the root start no earlier than it.
+		if self.spans[mid].start > to {
+			return;
+		}
+		if self.spans[mid].end >= from {
+			visit(&self.spans[mid]);
+		}
+		self.search(mid + 1, hi, from, to, visit);
+	}
+}
+
+//=============================================================================
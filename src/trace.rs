@@ -0,0 +1,51 @@
+//=============================================================================
+// TRACING
+//=============================================================================
+
+//! Optional instrumentation. With the `tracing` feature algorithms enter a
+//! debug level span for every run and emit debug events for their phases,
+//! such as the size of every breadth first frontier, the amount of edge
+//! relaxations of a shortest path search or the change of the scores in
+//! every iteration of PageRank. Without the feature the macros expand to
+//! nothing and their field values aren't computed.
+//!
+//! Both macros take a message or span name followed by `field = value`
+//! pairs.
+//!
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_span {
+	($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+		tracing::debug_span!($name $(, $field = $value)*).entered()
+	};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_span {
+	($name:literal $(, $field:ident = $value:expr)* $(,)?) => {
+		{
+			$(let _ = || { let _ = &$value; };)*
+			crate::trace::Span
+		}
+	};
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+	($message:literal $(, $field:ident = $value:expr)* $(,)?) => {
+		tracing::debug!($($field = $value,)* $message)
+	};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+	($message:literal $(, $field:ident = $value:expr)* $(,)?) => {
+		{
+			$(let _ = || { let _ = &$value; };)*
+		}
+	};
+}
+
+/// Stands in for an entered span when the `tracing` feature is off.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct Span;
@@ -0,0 +1,179 @@
+//=============================================================================
+// TREEWIDTH
+//=============================================================================
+
+//! Tree decompositions from elimination orderings. Nodes are eliminated one
+//! at a time by connecting all of their remaining neighbors to each other and
+//! removing the node. Each node forms a bag together with the neighbors it
+//! had when it was eliminated, and the bag is attached to the bag of the
+//! neighbor eliminated next. The width of the decomposition, the size of the
+//! largest bag minus one, is an upper bound for the treewidth of the graph.
+//! Dynamic programs over the bags then run in time exponential only in the
+//! width.
+//!
+//! The direction of edges, loops and parallel edges don't affect the
+//! decomposition and are ignored.
+//!
+use crate::collections::Graph;
+use crate::core::*;
+use crate::indexed::Indexed;
+use std::{
+	collections::{BinaryHeap, HashSet},
+	cmp::Reverse,
+	fmt::{Debug, Display},
+	hash::Hash,
+};
+
+/// Rule for choosing the node to eliminate next.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Heuristic {
+	/// The node with the fewest remaining neighbors.
+	MinDegree,
+	/// The node whose elimination adds the fewest edges between its
+	/// neighbors. Slower than `MinDegree` but usually gives a smaller width.
+	MinFill,
+}
+
+/// A tree decomposition given as the bags of nodes and the edges of the tree
+/// between the bags by index. Every node and every edge of the graph is
+/// contained in some bag, and the bags containing a node form a subtree.
+#[derive(Clone, Debug)]
+pub struct TreeDecomposition<K> {
+	pub bags: Vec<Vec<K>>,
+	pub tree: Vec<(usize, usize)>,
+}
+
+impl<K> TreeDecomposition<K> {
+	/// Size of the largest bag minus one, or zero if there are no bags.
+	pub fn width(&self) -> usize {
+		self.bags.iter().map(|b| b.len()).max().unwrap_or(1).max(1) - 1
+	}
+}
+
+//=============================================================================
+
+/// # Tree Decomposition
+///
+/// Find a tree decomposition of the graph by eliminating nodes in the order
+/// given by the heuristic. Disconnected graphs give a single tree where the
+/// decompositions of the components are joined at arbitrary bags.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::treewidth::*;
+///
+/// let mut g = Ungraph::<u32, Empty, Empty>::new();
+/// for i in 0..6 {
+///     g.add_node(i, Empty);
+/// }
+/// for i in 0..6 {
+///     g.add_edge(i, (i + 1) % 6, Empty);
+/// }
+///
+/// let decomposition = tree_decomposition(&g, Heuristic::MinFill);
+/// assert!(decomposition.width() == 2);
+/// assert!(decomposition.tree.len() == decomposition.bags.len() - 1);
+/// ```
+pub fn tree_decomposition<K, N, E, G>(graph: &G, heuristic: Heuristic) -> TreeDecomposition<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+{
+	let indexed = Indexed::new(graph.nodes(), Direction::Outbound);
+	let n = indexed.nodes.len();
+	let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); n];
+	for (u, list) in indexed.adjacency.iter().enumerate() {
+		for (w, _) in list.iter() {
+			if u != *w {
+				neighbors[u].insert(*w);
+				neighbors[*w].insert(u);
+			}
+		}
+	}
+	let score = |neighbors: &[HashSet<usize>], v: usize| -> usize {
+		match heuristic {
+			Heuristic::MinDegree => { neighbors[v].len() }
+			Heuristic::MinFill => { fill(neighbors, v) }
+		}
+	};
+
+	// Scores change only for the neighbors of an eliminated node, and with
+	// fill in also for their neighbors, so outdated heap entries are skipped.
+	let mut current: Vec<usize> = (0..n).map(|v| score(&neighbors, v)).collect();
+	let mut heap: BinaryHeap<Reverse<(usize, usize)>> = (0..n).map(|v| Reverse((current[v], v))).collect();
+	let mut position = vec![usize::MAX; n];
+	let mut bags: Vec<Vec<usize>> = Vec::with_capacity(n);
+	while let Some(Reverse((s, v))) = heap.pop() {
+		if position[v] != usize::MAX || s != current[v] {
+			continue;
+		}
+		position[v] = bags.len();
+		let adjacent: Vec<usize> = neighbors[v].iter().cloned().collect();
+		for (i, a) in adjacent.iter().enumerate() {
+			neighbors[*a].remove(&v);
+			for b in adjacent[i + 1..].iter() {
+				neighbors[*a].insert(*b);
+				neighbors[*b].insert(*a);
+			}
+		}
+		neighbors[v].clear();
+		let mut affected: HashSet<usize> = adjacent.iter().cloned().collect();
+		if heuristic == Heuristic::MinFill {
+			for a in adjacent.iter() {
+				affected.extend(neighbors[*a].iter().cloned());
+			}
+		}
+		for u in affected {
+			let s = score(&neighbors, u);
+			if s != current[u] {
+				current[u] = s;
+				heap.push(Reverse((s, u)));
+			}
+		}
+		let mut bag = vec![v];
+		bag.extend(adjacent);
+		bags.push(bag);
+	}
+
+	let mut tree = Vec::new();
+	let mut root: Option<usize> = None;
+	for (i, bag) in bags.iter().enumerate() {
+		match bag[1..].iter().map(|u| position[*u]).min() {
+			Some(parent) => { tree.push((i, parent)) }
+			None => {
+				if let Some(r) = root {
+					tree.push((r, i));
+				}
+				root = Some(i);
+			}
+		}
+	}
+	TreeDecomposition {
+		bags: bags
+			.into_iter()
+			.map(|bag| bag.into_iter().map(|v| indexed.key(v)).collect())
+			.collect(),
+		tree,
+	}
+}
+
+// Amount of edges missing between the neighbors of the node.
+fn fill(neighbors: &[HashSet<usize>], v: usize) -> usize {
+	let adjacent: Vec<&usize> = neighbors[v].iter().collect();
+	let mut res = 0;
+	for (i, a) in adjacent.iter().enumerate() {
+		for b in adjacent[i + 1..].iter() {
+			if !neighbors[**a].contains(*b) {
+				res += 1;
+			}
+		}
+	}
+	res
+}
+
+//=============================================================================
@@ -0,0 +1,292 @@
+//=============================================================================
+// HETEROGENEOUS GRAPHS
+//=============================================================================
+
+//! Graphs with a type tag on every node and edge, such as knowledge graphs
+//! where authors write papers that cite papers. Any graph becomes typed by
+//! storing data that implements `Tagged`, for example `Typed`, which pairs a
+//! tag with the data, or `MultiTyped` for data with several tags, which
+//! matches a pattern if any of its tags does. A metapath is a pattern of tags along a path, like
+//! author, writes, paper, written by, author, where every step also says in
+//! which direction its edges are followed and a missing tag matches any tag.
+//! Following a metapath from a node finds the nodes it leads to with the
+//! amount of paths that match, or the matching paths themselves. In
+//! undirected graphs the direction of the steps is ignored.
+//!
+//! # Examples
+//!
+//! ```
+//! use fastgraph::collections::*;
+//! use fastgraph::core::{Direction, Empty};
+//! use fastgraph::typed::*;
+//!
+//! let mut g = Digraph::<&str, Typed<&str, Empty>, Typed<&str, Empty>>::new();
+//! for author in ["ada", "bob", "cy"] {
+//!     g.add_node(author, Typed::new("author", Empty));
+//! }
+//! g.add_node("p1", Typed::new("paper", Empty));
+//! g.add_node("p2", Typed::new("paper", Empty));
+//! g.add_edge("ada", "p1", Typed::new("writes", Empty));
+//! g.add_edge("bob", "p1", Typed::new("writes", Empty));
+//! g.add_edge("bob", "p2", Typed::new("writes", Empty));
+//! g.add_edge("cy", "p2", Typed::new("writes", Empty));
+//! g.add_edge("p2", "p1", Typed::new("cites", Empty));
+//!
+//! // Coauthors of bob, including bob through both papers.
+//! let coauthors = Metapath::new(Some("author"))
+//!     .step(Direction::Outbound, Some("writes"), Some("paper"))
+//!     .step(Direction::Inbound, Some("writes"), Some("author"));
+//! let ends = g.follow_metapath("bob", &coauthors).unwrap();
+//! assert!(ends.len() == 3 && ends["bob"] == 2 && ends["cy"] == 1);
+//!
+//! // Papers cited by papers of cy.
+//! let cited = Metapath::new(None)
+//!     .step(Direction::Outbound, Some("writes"), None)
+//!     .step(Direction::Outbound, Some("cites"), None);
+//! assert!(cited.instances(&g, "cy", 10).unwrap() == vec![vec!["cy", "p2", "p1"]]);
+//! ```
+use crate::collections::Graph;
+use crate::core::*;
+use std::{
+	collections::HashMap,
+	fmt::{Debug, Display},
+	hash::Hash,
+	sync::Arc,
+};
+
+// Nodes reached by a metapath with the amount of paths to every one of them.
+type Frontier<K, N, E> = HashMap<K, (Arc<Node<K, N, E>>, usize)>;
+
+/// Data with a type tag.
+pub trait Tagged<T> {
+	/// The type tag of the data.
+	fn tag(&self) -> &T;
+
+	/// Every tag of the data, by default only its tag. Patterns match data
+	/// that has the tag they look for among its tags.
+	fn tags(&self) -> &[T] {
+		std::slice::from_ref(self.tag())
+	}
+}
+
+/// Data paired with a type tag, displayed as `tag:data`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Typed<T, D> {
+	/// Type tag of the data.
+	pub tag: T,
+	/// The data itself.
+	pub data: D,
+}
+
+impl<T, D> Typed<T, D> {
+	/// Pair data with a tag.
+	pub fn new(tag: T, data: D) -> Self {
+		Typed { tag, data }
+	}
+}
+
+impl<T, D> Tagged<T> for Typed<T, D> {
+	fn tag(&self) -> &T {
+		&self.tag
+	}
+}
+
+impl<T: Display, D: Display> Display for Typed<T, D> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.tag, self.data)
+	}
+}
+
+/// Data with one or more type tags, such as an edge standing for every
+/// relation between two nodes, displayed as `tag,tag:data`. Its tag is the
+/// first of its tags.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MultiTyped<T, D> {
+	tags: Vec<T>,
+	/// The data itself.
+	pub data: D,
+}
+
+impl<T, D> MultiTyped<T, D> {
+	/// Data with the tags, of which there must be at least one.
+	pub fn new(tags: Vec<T>, data: D) -> Self {
+		assert!(!tags.is_empty(), "MultiTyped needs at least one tag");
+		MultiTyped { tags, data }
+	}
+
+	/// Add a tag.
+	pub fn push(&mut self, tag: T) {
+		self.tags.push(tag);
+	}
+}
+
+impl<T, D> Tagged<T> for MultiTyped<T, D> {
+	fn tag(&self) -> &T {
+		&self.tags[0]
+	}
+
+	fn tags(&self) -> &[T] {
+		&self.tags
+	}
+}
+
+impl<T: Display, D: Display> Display for MultiTyped<T, D> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for (i, tag) in self.tags.iter().enumerate() {
+			if i > 0 {
+				write!(f, ",")?;
+			}
+			write!(f, "{}", tag)?;
+		}
+		write!(f, ":{}", self.data)
+	}
+}
+
+//=============================================================================
+
+/// A pattern of node tags `TN` and edge tags `TE` along a path. `None`
+/// matches any tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metapath<TN, TE> {
+	/// Tag of the node a path starts from.
+	pub start: Option<TN>,
+	/// Every step of a path, which follows an edge in the direction with the
+	/// edge tag to a node with the node tag.
+	pub steps: Vec<(Direction, Option<TE>, Option<TN>)>,
+}
+
+impl<TN, TE> Metapath<TN, TE>
+where
+	TN: PartialEq,
+	TE: PartialEq,
+{
+	/// A metapath without steps from nodes with a tag.
+	pub fn new(start: Option<TN>) -> Self {
+		Metapath { start, steps: Vec::new() }
+	}
+
+	/// Follow edges with a tag in a direction to nodes with a tag.
+	pub fn step(mut self, direction: Direction, edge: Option<TE>, node: Option<TN>) -> Self {
+		self.steps.push((direction, edge, node));
+		self
+	}
+
+	/// The nodes the metapath leads to from a node with the amount of paths
+	/// that match to every one of them, which is zero paths for a start that
+	/// doesn't match. None if the node isn't in the graph.
+	pub fn ends<K, N, E, G>(&self, graph: &G, start: K) -> Option<HashMap<K, usize>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+		G: Graph<K, N, E>,
+	{
+		let node = graph.get_node(start)?;
+		let mut frontier: Frontier<K, N, E> = HashMap::new();
+		if node.with(|data| matches(&self.start, data.tags())) {
+			frontier.insert(node.key().clone(), (node, 1));
+		}
+		for step in self.steps.iter() {
+			let mut next: Frontier<K, N, E> = HashMap::new();
+			for (node, count) in frontier.values() {
+				for other in follow(node, step, G::directed()) {
+					next.entry(other.key().clone()).or_insert((other, 0)).1 += count;
+				}
+			}
+			frontier = next;
+		}
+		Some(frontier.into_iter().map(|(k, (_, count))| (k, count)).collect())
+	}
+
+	/// The keys along the first `max_count` paths from a node that match the
+	/// metapath, found depth first. A path may visit a node more than once.
+	/// None if the node isn't in the graph.
+	pub fn instances<K, N, E, G>(&self, graph: &G, start: K, max_count: usize) -> Option<Vec<Vec<K>>>
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+		G: Graph<K, N, E>,
+	{
+		let node = graph.get_node(start)?;
+		let mut res = Vec::new();
+		if max_count > 0 && node.with(|data| matches(&self.start, data.tags())) {
+			let mut path = vec![node.key().clone()];
+			self.walk(&node, G::directed(), &mut path, max_count, &mut res);
+		}
+		Some(res)
+	}
+
+	fn walk<K, N, E>(&self, node: &Arc<Node<K, N, E>>, directed: bool, path: &mut Vec<K>, max_count: usize, res: &mut Vec<Vec<K>>)
+	where
+		K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+		N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+		E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+	{
+		let step = match self.steps.get(path.len() - 1) {
+			Some(step) => { step }
+			None => {
+				res.push(path.clone());
+				return;
+			}
+		};
+		for other in follow(node, step, directed) {
+			path.push(other.key().clone());
+			self.walk(&other, directed, path, max_count, res);
+			path.pop();
+			if res.len() == max_count {
+				return;
+			}
+		}
+	}
+}
+
+//=============================================================================
+
+// Whether a pattern matches any of the tags.
+pub(crate) fn matches<T: PartialEq>(pattern: &Option<T>, tags: &[T]) -> bool {
+	match pattern {
+		Some(p) => { tags.contains(p) }
+		None => { true }
+	}
+}
+
+// The nodes a step leads to from a node, once for every matching edge.
+pub(crate) fn follow<K, N, E, TN, TE>(node: &Arc<Node<K, N, E>>, step: &(Direction, Option<TE>, Option<TN>), directed: bool) -> Vec<Arc<Node<K, N, E>>>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send + Tagged<TN>,
+	E: Clone + Debug + Display + Sync + Send + Tagged<TE>,
+	TN: PartialEq,
+	TE: PartialEq,
+{
+	let (direction, edge, other) = step;
+	let direction = match directed {
+		true => { *direction }
+		false => { Direction::Both }
+	};
+	let mut res = Vec::new();
+	if direction != Direction::Inbound {
+		for e in node.outbound().iter() {
+			let target = e.target();
+			if e.with(|data| matches(edge, data.tags())) && target.with(|data| matches(other, data.tags())) {
+				res.push(target);
+			}
+		}
+	}
+	if direction != Direction::Outbound {
+		for e in node.inbound().iter().filter_map(|e| e.upgrade()) {
+			let source = e.source();
+			// Loops were already followed as outbound edges.
+			if direction == Direction::Both && source.key() == node.key() {
+				continue;
+			}
+			if e.with(|data| matches(edge, data.tags())) && source.with(|data| matches(other, data.tags())) {
+				res.push(source);
+			}
+		}
+	}
+	res
+}
+
+//=============================================================================
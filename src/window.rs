@@ -0,0 +1,421 @@
+//=============================================================================
+// SLIDING WINDOWS
+//=============================================================================
+
+//! A graph over the most recent edges of a stream. Edges are pushed in the
+//! order they arrive and expire once they fall out of the window, which
+//! holds either a fixed amount of the latest edges or the edges of a span of
+//! time. Expired edges are evicted from the front of a queue, so keeping the
+//! window costs constant amortized time per edge. Nodes are added when an
+//! edge first touches them and deleted when their last edge is evicted.
+//! Metrics that are kept up to date incrementally implement `WindowMetric`
+//! and are told about every edge as it enters and leaves the window.
+//!
+use crate::core::*;
+use crate::collections::Graph;
+use std::{
+	collections::{HashMap, VecDeque},
+	fmt::{Debug, Display},
+	hash::Hash,
+	marker::PhantomData,
+	sync::Arc,
+};
+
+/// When the edges of a window expire.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expiry {
+	/// Keep the given amount of the latest edges.
+	Count(usize),
+	/// Keep the edges pushed within the given span of the latest time.
+	Time(f64),
+}
+
+/// A metric kept up to date as edges enter and leave a window. Metrics can
+/// be combined in pairs and `()` is the empty metric.
+pub trait WindowMetric<K, N, E>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	/// Called after a new edge has been added to the graph.
+	fn insert(&mut self, edge: &Arc<Edge<K, N, E>>);
+	/// Called before an expired edge is deleted from the graph.
+	fn evict(&mut self, edge: &Arc<Edge<K, N, E>>);
+}
+
+impl<K, N, E> WindowMetric<K, N, E> for ()
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn insert(&mut self, _: &Arc<Edge<K, N, E>>) {}
+	fn evict(&mut self, _: &Arc<Edge<K, N, E>>) {}
+}
+
+impl<K, N, E, A, B> WindowMetric<K, N, E> for (A, B)
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	A: WindowMetric<K, N, E>,
+	B: WindowMetric<K, N, E>,
+{
+	fn insert(&mut self, edge: &Arc<Edge<K, N, E>>) {
+		self.0.insert(edge);
+		self.1.insert(edge);
+	}
+
+	fn evict(&mut self, edge: &Arc<Edge<K, N, E>>) {
+		self.0.evict(edge);
+		self.1.evict(edge);
+	}
+}
+
+//=============================================================================
+
+// An edge in the queue of a window. Pushing an edge that is already in the
+// window renews it, leaving its older entry stale.
+struct Entry<K> {
+	time: f64,
+	seq: u64,
+	source: K,
+	target: K,
+}
+
+/// A graph of the edges of a stream that haven't expired.
+///
+/// # Examples
+///
+/// ```
+/// use fastgraph::collections::*;
+/// use fastgraph::core::Empty;
+/// use fastgraph::window::*;
+///
+/// // Messages between users within the last minute.
+/// let mut window = SlidingWindow::<&str, Empty, u32, Digraph<&str, Empty, u32>, Degrees<&str>>::new(Expiry::Time(60.0));
+/// window.push("ann", "bob", 1, 0.0);
+/// window.push("bob", "cid", 2, 30.0);
+/// window.push("cid", "ann", 3, 70.0);
+///
+/// assert!(window.len() == 2);
+/// assert!(window.graph().get_node("ann").is_some());
+/// assert!(window.graph().get_edge("ann", "bob").is_none());
+/// assert!(window.metric().degree(&"cid") == 2);
+///
+/// // Nothing has happened for another minute.
+/// assert!(window.advance(130.0) == 2);
+/// assert!(window.graph().node_count() == 0);
+/// ```
+pub struct SlidingWindow<K, N, E, G, M = ()>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	M: WindowMetric<K, N, E>,
+{
+	graph: G,
+	expiry: Expiry,
+	metric: M,
+	queue: VecDeque<Entry<K>>,
+	// The sequence number of the latest entry of every edge in the window.
+	live: HashMap<(K, K), u64>,
+	seq: u64,
+	time: f64,
+	data: PhantomData<(N, E)>,
+}
+
+impl<K, N, E, G, M> SlidingWindow<K, N, E, G, M>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send + Default,
+	E: Clone + Debug + Display + Sync + Send,
+	G: Graph<K, N, E>,
+	M: WindowMetric<K, N, E>,
+{
+	/// Create an empty window with a default metric.
+	pub fn new(expiry: Expiry) -> Self
+	where
+		M: Default,
+	{
+		SlidingWindow::with_metric(expiry, M::default())
+	}
+
+	/// Create an empty window that keeps the given metric up to date.
+	pub fn with_metric(expiry: Expiry, metric: M) -> Self {
+		SlidingWindow {
+			graph: G::new(),
+			expiry,
+			metric,
+			queue: VecDeque::new(),
+			live: HashMap::new(),
+			seq: 0,
+			time: f64::NEG_INFINITY,
+			data: PhantomData,
+		}
+	}
+
+	/// The graph of the edges in the window.
+	pub fn graph(&self) -> &G {
+		&self.graph
+	}
+
+	/// The metric of the window.
+	pub fn metric(&self) -> &M {
+		&self.metric
+	}
+
+	/// The metric of the window, for metrics that update lazily when read.
+	pub fn metric_mut(&mut self) -> &mut M {
+		&mut self.metric
+	}
+
+	/// Amount of edges in the window.
+	pub fn len(&self) -> usize {
+		self.live.len()
+	}
+
+	/// True if the window has no edges.
+	pub fn is_empty(&self) -> bool {
+		self.live.is_empty()
+	}
+
+	/// The latest time pushed or advanced to.
+	pub fn time(&self) -> f64 {
+		self.time
+	}
+
+	/// Push an edge that arrived at the given time, adding its nodes with
+	/// default data if they aren't in the window. An edge that is already in
+	/// the window has its data replaced and expires as if it had just been
+	/// added. Times should not decrease. Returns the amount of edges evicted.
+	pub fn push(&mut self, source: K, target: K, data: E, time: f64) -> usize {
+		self.time = self.time.max(time);
+		let existing = self.graph.get_edge(source.clone(), target.clone()).or_else(|| match G::directed() {
+			true => { None }
+			false => { self.graph.get_edge(target.clone(), source.clone()) }
+		});
+		let key = match existing {
+			Some(edge) => {
+				edge.store(data);
+				(edge.source().key().clone(), edge.target().key().clone())
+			}
+			None => {
+				self.graph.add_node(source.clone(), N::default());
+				self.graph.add_node(target.clone(), N::default());
+				self.graph.add_edge(source.clone(), target.clone(), data);
+				if let Some(edge) = self.graph.get_edge(source.clone(), target.clone()) {
+					self.metric.insert(&edge);
+				}
+				(source, target)
+			}
+		};
+		self.seq += 1;
+		self.live.insert(key.clone(), self.seq);
+		self.queue.push_back(Entry { time, seq: self.seq, source: key.0, target: key.1 });
+		self.evict()
+	}
+
+	/// Move the time of the window forward without pushing an edge. Returns
+	/// the amount of edges evicted.
+	pub fn advance(&mut self, time: f64) -> usize {
+		self.time = self.time.max(time);
+		self.evict()
+	}
+
+	/// Take the graph out of the window.
+	pub fn into_graph(self) -> G {
+		self.graph
+	}
+
+	fn expired(&self, entry: &Entry<K>) -> bool {
+		match self.expiry {
+			Expiry::Count(count) => { self.live.len() > count }
+			Expiry::Time(span) => { entry.time <= self.time - span }
+		}
+	}
+
+	fn evict(&mut self) -> usize {
+		let mut res = 0;
+		while let Some(entry) = self.queue.front() {
+			let key = (entry.source.clone(), entry.target.clone());
+			if self.live.get(&key) != Some(&entry.seq) {
+				self.queue.pop_front();
+				continue;
+			}
+			if !self.expired(entry) {
+				break;
+			}
+			self.queue.pop_front();
+			self.live.remove(&key);
+			if let Some(edge) = self.graph.get_edge(key.0.clone(), key.1.clone()) {
+				self.metric.evict(&edge);
+			}
+			self.graph.del_edge(key.0.clone(), key.1.clone());
+			for k in [key.0, key.1] {
+				if self.graph.get_node(k.clone()).is_some_and(|n| n.is_leaf() && n.inbound().is_empty()) {
+					self.graph.del_node(k);
+				}
+			}
+			res += 1;
+		}
+		res
+	}
+}
+
+//=============================================================================
+
+/// The degree of every node in a window, counting a loop twice.
+#[derive(Clone, Debug)]
+pub struct Degrees<K: Hash + Eq> {
+	degrees: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> Degrees<K> {
+	/// Degree of a node, zero if it isn't in the window.
+	pub fn degree(&self, key: &K) -> usize {
+		self.degrees.get(key).copied().unwrap_or(0)
+	}
+
+	/// Largest degree in the window.
+	pub fn max_degree(&self) -> usize {
+		self.degrees.values().copied().max().unwrap_or(0)
+	}
+}
+
+impl<K: Hash + Eq> Default for Degrees<K> {
+	fn default() -> Self {
+		Degrees { degrees: HashMap::new() }
+	}
+}
+
+impl<K, N, E> WindowMetric<K, N, E> for Degrees<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn insert(&mut self, edge: &Arc<Edge<K, N, E>>) {
+		for k in [edge.source().key().clone(), edge.target().key().clone()] {
+			*self.degrees.entry(k).or_insert(0) += 1;
+		}
+	}
+
+	fn evict(&mut self, edge: &Arc<Edge<K, N, E>>) {
+		for k in [edge.source().key().clone(), edge.target().key().clone()] {
+			if let Some(d) = self.degrees.get_mut(&k) {
+				*d -= 1;
+				if *d == 0 {
+					self.degrees.remove(&k);
+				}
+			}
+		}
+	}
+}
+
+/// Connected components of a window, ignoring the direction of the edges.
+/// Inserted edges are merged into a union-find forest as they arrive. An
+/// eviction may split a component, so it marks the forest stale and the
+/// forest is rebuilt from the edges in the window when it's next read.
+#[derive(Clone, Debug)]
+pub struct Components<K: Hash + Eq> {
+	edges: HashMap<(K, K), usize>,
+	index: HashMap<K, usize>,
+	parent: Vec<usize>,
+	count: usize,
+	stale: bool,
+}
+
+impl<K: Hash + Eq + Clone> Components<K> {
+	/// Amount of components in the window.
+	pub fn count(&mut self) -> usize {
+		self.refresh();
+		self.count
+	}
+
+	/// True if both nodes are in the window and in the same component.
+	pub fn connected(&mut self, a: &K, b: &K) -> bool {
+		self.refresh();
+		match (self.index.get(a).copied(), self.index.get(b).copied()) {
+			(Some(a), Some(b)) => { self.find(a) == self.find(b) }
+			_ => { false }
+		}
+	}
+
+	fn refresh(&mut self) {
+		if !self.stale {
+			return;
+		}
+		self.index.clear();
+		self.parent.clear();
+		self.count = 0;
+		self.stale = false;
+		let edges: Vec<(K, K)> = self.edges.keys().cloned().collect();
+		for (a, b) in edges {
+			self.union(a, b);
+		}
+	}
+
+	fn add(&mut self, key: K) -> usize {
+		let next = self.parent.len();
+		let i = *self.index.entry(key).or_insert(next);
+		if i == next {
+			self.parent.push(i);
+			self.count += 1;
+		}
+		i
+	}
+
+	fn union(&mut self, a: K, b: K) {
+		let (a, b) = (self.add(a), self.add(b));
+		let (a, b) = (self.find(a), self.find(b));
+		if a != b {
+			self.parent[a] = b;
+			self.count -= 1;
+		}
+	}
+
+	fn find(&mut self, mut x: usize) -> usize {
+		while self.parent[x] != x {
+			self.parent[x] = self.parent[self.parent[x]];
+			x = self.parent[x];
+		}
+		x
+	}
+}
+
+impl<K: Hash + Eq> Default for Components<K> {
+	fn default() -> Self {
+		Components { edges: HashMap::new(), index: HashMap::new(), parent: Vec::new(), count: 0, stale: false }
+	}
+}
+
+impl<K, N, E> WindowMetric<K, N, E> for Components<K>
+where
+	K: Hash + Eq + Clone + Debug + Display + Sync + Send,
+	N: Clone + Debug + Display + Sync + Send,
+	E: Clone + Debug + Display + Sync + Send,
+{
+	fn insert(&mut self, edge: &Arc<Edge<K, N, E>>) {
+		let (a, b) = (edge.source().key().clone(), edge.target().key().clone());
+		*self.edges.entry((a.clone(), b.clone())).or_insert(0) += 1;
+		if !self.stale {
+			self.union(a, b);
+		}
+	}
+
+	fn evict(&mut self, edge: &Arc<Edge<K, N, E>>) {
+		let key = (edge.source().key().clone(), edge.target().key().clone());
+		if let Some(c) = self.edges.get_mut(&key) {
+			*c -= 1;
+			if *c == 0 {
+				self.edges.remove(&key);
+			}
+		}
+		self.stale = true;
+	}
+}
+
+//=============================================================================
@@ -0,0 +1,153 @@
+use fastgraph::algebra::*;
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use rand::Rng;
+
+fn random_ungraph(size: usize, edges: usize) -> Ungraph<usize, Empty, f64> {
+	let mut rng = rand::thread_rng();
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..size {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..edges {
+		let (s, t) = (rng.gen_range(0..size), rng.gen_range(0..size));
+		if s != t && g.get_edge(t, s).is_none() {
+			g.add_edge(s, t, rng.gen_range(1..10) as f64);
+		}
+	}
+	g
+}
+
+#[test]
+fn matrix_test_triplets() {
+	let m = Matrix::from_triplets(2, 3, &PlusTimes, vec![(1, 2, 1.0), (0, 1, 2.0), (1, 2, 3.0), (1, 0, 5.0)]);
+	assert!(m.nnz() == 3);
+	assert!(m.get(1, 2) == Some(&4.0));
+	assert!(m.get(0, 0).is_none());
+	assert!(m.row(1).0 == [0, 2]);
+	let t = m.transpose();
+	assert!(t.rows() == 3 && t.cols() == 2);
+	assert!(t.get(2, 1) == Some(&4.0));
+	assert!(t.transpose() == m);
+	assert!(m.reduce(&PlusTimes) == 11.0);
+	assert!(m.mxv(&PlusTimes, &[1.0, 1.0, 2.0]) == vec![2.0, 13.0]);
+	assert!(m.vxm(&PlusTimes, &[1.0, 2.0]) == vec![10.0, 2.0, 8.0]);
+	// Operands of the wrong shape are rejected rather than read past or
+	// ignored.
+	let wrong = |f: &dyn Fn()| std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err();
+	assert!(wrong(&|| { m.mxv(&PlusTimes, &[1.0, 1.0, 2.0, 3.0]); }));
+	assert!(wrong(&|| { m.vxm(&PlusTimes, &[1.0, 2.0, 3.0]); }));
+	assert!(wrong(&|| { m.mxm(&PlusTimes, &m); }));
+	assert!(wrong(&|| { m.mxm_masked(&PlusTimes, &t, &m); }));
+	assert!(m.mxm(&PlusTimes, &t).rows() == 2 && m.mxm_masked(&PlusTimes, &t, &m.mxm(&PlusTimes, &t)).cols() == 2);
+}
+
+#[test]
+fn ungraph_test_bfs_levels() {
+	let g = random_ungraph(300, 400);
+	let csr = g.freeze();
+	let a = Matrix::adjacency(&csr, &OrAnd, |_| true);
+	let n = csr.node_count();
+	let root = csr.index(&0).unwrap();
+	// Expand the frontier one level at a time, masking out visited nodes.
+	let mut levels = vec![usize::MAX; n];
+	let mut frontier = vec![false; n];
+	frontier[root] = true;
+	levels[root] = 0;
+	let mut depth = 0;
+	while frontier.iter().any(|f| *f) {
+		depth += 1;
+		frontier = a.vxm(&OrAnd, &frontier);
+		for (i, f) in frontier.iter_mut().enumerate() {
+			*f = *f && levels[i] == usize::MAX;
+			if *f {
+				levels[i] = depth;
+			}
+		}
+	}
+	for (i, level) in levels.iter().enumerate() {
+		let expected = g.shortest_path(0, *csr.key(i), |_| 1.0).map_or(usize::MAX, |(_, p)| p.len());
+		assert!(*level == expected);
+	}
+}
+
+#[test]
+fn ungraph_test_min_plus_distances() {
+	let g = random_ungraph(200, 400);
+	let csr = g.freeze();
+	let a = Matrix::adjacency(&csr, &MinPlus, |e| e.load());
+	let n = csr.node_count();
+	let mut dist = vec![f64::INFINITY; n];
+	dist[csr.index(&0).unwrap()] = 0.0;
+	for _ in 0..n {
+		let relaxed = a.vxm(&MinPlus, &dist);
+		let next: Vec<f64> = dist.iter().zip(relaxed.iter()).map(|(d, r)| d.min(*r)).collect();
+		if next == dist {
+			break;
+		}
+		dist = next;
+	}
+	for (i, d) in dist.iter().enumerate() {
+		let expected = g.shortest_path(0, *csr.key(i), |e| e.load()).map_or(f64::INFINITY, |(cost, _)| cost);
+		assert!(*d == expected);
+	}
+}
+
+#[test]
+fn ungraph_test_triangle_count() {
+	let g = random_ungraph(60, 400);
+	let csr = g.freeze();
+	let a = Matrix::adjacency(&csr, &PlusTimes, |_| 1.0);
+	let triangles = a.mxm_masked(&PlusTimes, &a, &a).reduce(&PlusTimes) / 6.0;
+	let n = csr.node_count();
+	let mut expected = 0;
+	for i in 0..n {
+		for j in i + 1..n {
+			for k in j + 1..n {
+				if a.get(i, j).is_some() && a.get(j, k).is_some() && a.get(i, k).is_some() {
+					expected += 1;
+				}
+			}
+		}
+	}
+	assert!(triangles == expected as f64);
+	// The unmasked square counts walks of length two.
+	let square = a.mxm(&PlusTimes, &a);
+	for i in 0..n {
+		assert!(square.get(i, i).copied().unwrap_or(0.0) == csr.neighbors(i).len() as f64);
+	}
+}
+
+#[test]
+fn digraph_test_reachability() {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..5 {
+		g.add_node(i, Empty);
+	}
+	for (s, t) in [(0, 1), (1, 2), (2, 0), (3, 4)] {
+		g.add_edge(s, t, Empty);
+	}
+	let csr = g.freeze();
+	let a = Matrix::adjacency(&csr, &OrAnd, |_| true);
+	// Square the closure until it stops growing.
+	let mut closure = a.clone();
+	loop {
+		let next = closure.mxm(&OrAnd, &closure);
+		let merged: Vec<(usize, usize, bool)> = (0..5)
+			.flat_map(|i| {
+				let (columns, _) = closure.row(i);
+				let (more, _) = next.row(i);
+				columns.iter().chain(more.iter()).map(move |j| (i, *j, true)).collect::<Vec<_>>()
+			})
+			.collect();
+		let merged = Matrix::from_triplets(5, 5, &OrAnd, merged);
+		if merged == closure {
+			break;
+		}
+		closure = merged;
+	}
+	let reaches = |s: usize, t: usize| closure.get(csr.index(&s).unwrap(), csr.index(&t).unwrap()).is_some();
+	assert!(reaches(0, 0) && reaches(2, 1) && reaches(3, 4));
+	assert!(!reaches(4, 3) && !reaches(0, 3));
+	assert!(closure.nnz() == 10);
+}
@@ -0,0 +1,106 @@
+use fastgraph::anonymize::*;
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+// Inbound and outbound degree of every node.
+fn degrees(g: &Digraph<u32, Empty, u32>) -> HashMap<u32, (usize, usize)> {
+	g.nodes().iter().map(|n| (*n.key(), (n.inbound().len(), n.outbound().len()))).collect()
+}
+
+fn is_simple<G: Graph<u32, Empty, u32>>(g: &G) -> bool {
+	let mut seen = std::collections::HashSet::new();
+	g.nodes().iter().all(|n| {
+		n.outbound().iter().all(|e| {
+			let (a, b) = (*e.source().key(), *e.target().key());
+			let pair = match G::directed() {
+				true => { (a, b) }
+				false => { (a.min(b), a.max(b)) }
+			};
+			a != b && seen.insert(pair)
+		})
+	})
+}
+
+#[test]
+fn digraph_test_swap_edges() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let mut g = Digraph::<u32, Empty, u32>::new();
+	for i in 0..50 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..200 {
+		let (a, b) = (rng.gen_range(0..50), rng.gen_range(0..50));
+		if a != b {
+			g.add_edge(a, b, i);
+		}
+	}
+	let before = degrees(&g);
+	let edges = g.edge_count();
+	let mut sum: u32 = 0;
+	for n in g.nodes() {
+		sum += n.outbound().iter().map(|e| e.load()).sum::<u32>();
+	}
+
+	assert!(swap_edges(&mut g, 500, &mut rng) == 500);
+	assert!(degrees(&g) == before);
+	assert!(g.edge_count() == edges && is_simple(&g));
+	// Edge data is carried along with the edges.
+	let after: u32 = g.nodes().iter().map(|n| n.outbound().iter().map(|e| e.load()).sum::<u32>()).sum();
+	assert!(after == sum);
+
+	assert!(inject_edges(&mut g, 30, |_, _| 0, &mut rng) == 30);
+	assert!(g.edge_count() == edges + 30 && is_simple(&g));
+	assert!(drop_edges(&mut g, 40, &mut rng) == 40);
+	assert!(g.edge_count() == edges - 10);
+	assert!(drop_edges(&mut g, 1000, &mut rng) == edges - 10);
+	assert!(g.edge_count() == 0);
+}
+
+#[test]
+fn ungraph_test_anonymize() {
+	let mut rng = StdRng::seed_from_u64(6);
+	// In a complete graph every swap would create a parallel edge.
+	let mut complete = Ungraph::<u32, Empty, u32>::new();
+	for i in 0..4 {
+		complete.add_node(i, Empty);
+	}
+	for a in 0..4 {
+		for b in a + 1..4 {
+			complete.add_edge(a, b, a * 10 + b);
+		}
+	}
+	assert!(swap_edges(&mut complete, 10, &mut rng) == 0);
+	assert!(inject_edges(&mut complete, 10, |_, _| 0, &mut rng) == 0);
+	assert!(is_simple(&complete) && complete.edge_count() == 6);
+
+	// Complete bipartite graph between 0..3 and 3..6, which swaps can turn
+	// into a graph with edges within either side.
+	let mut g = Ungraph::<u32, Empty, u32>::new();
+	for i in 0..6 {
+		g.add_node(i, Empty);
+	}
+	for a in 0..3 {
+		for b in 3..6 {
+			g.add_edge(a, b, a * 10 + b);
+		}
+	}
+	assert!(degree_anonymity(&g) == 6);
+	assert!(swap_edges(&mut g, 5, &mut rng) == 5);
+	assert!(is_simple(&g) && g.edge_count() == 9);
+	assert!(degree_anonymity(&g) == 6);
+	// Only the 6 pairs that aren't adjacent can be added.
+	assert!(inject_edges(&mut g, 100, |_, _| 0, &mut rng) == 6);
+	assert!(is_simple(&g) && g.edge_count() == 15);
+
+	g.add_node(6, Empty);
+	g.add_edge(6, 0, 0);
+	assert!(degree_anonymity(&g) == 1);
+	let h: Ungraph<usize, Empty, Empty> = strip_attributes(&g, |_| Empty, |_| Empty, &mut rng);
+	assert!(h.node_count() == 7 && h.edge_count() == 16);
+	let mut ids: Vec<usize> = h.nodes().iter().map(|n| *n.key()).collect();
+	ids.sort();
+	assert!(ids == [0, 1, 2, 3, 4, 5, 6]);
+	assert!(degree_anonymity(&h) == 1);
+}
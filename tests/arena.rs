@@ -0,0 +1,76 @@
+use fastgraph::arena::*;
+use fastgraph::collections::*;
+use fastgraph::core::Direction;
+
+#[test]
+fn arena_ids_and_reuse() {
+	let mut a = Arena::<usize, usize, usize>::with_capacity(true, 10, 20);
+	let ids: Vec<NodeId> = (0..10).map(|i| a.add_node(i, i * 10).unwrap()).collect();
+	assert!(a.add_node(3, 0).is_none());
+	let edges: Vec<EdgeId> = (0..10).map(|i| a.add_edge(ids[i], ids[(i + 1) % 10], i).unwrap()).collect();
+	assert!(a.node_count() == 10 && a.edge_count() == 10);
+	assert!(a.edge(edges[4]) == Some((ids[4], ids[5], &4)));
+	assert!(a.find_edge(ids[4], ids[5]) == Some(edges[4]) && a.find_edge(ids[5], ids[4]).is_none());
+
+	// Deleting a node deletes its edges and leaves its id stale even after
+	// the slot is reused.
+	assert!(a.del_node(ids[5]) == Some(50));
+	assert!(a.node_count() == 9 && a.edge_count() == 8);
+	assert!(a.edge(edges[4]).is_none() && a.edge(edges[5]).is_none());
+	assert!(a.outbound(ids[4]).is_empty() && a.inbound(ids[6]).is_empty());
+	let again = a.add_node(5, 55).unwrap();
+	assert!(again.index() == ids[5].index() && again != ids[5]);
+	assert!(a.node(ids[5]).is_none() && a.node(again) == Some(&55));
+	assert!(a.del_node(ids[5]).is_none() && a.add_edge(ids[5], ids[0], 0).is_none());
+	assert!(a.node_id(&5) == Some(again) && a.key(again) == Some(&5));
+
+	*a.edge_mut(edges[0]).unwrap() += 100;
+	assert!(a.del_edge(edges[0]) == Some(100) && a.del_edge(edges[0]).is_none());
+	assert!(a.node_ids().count() == 10);
+}
+
+#[test]
+fn arena_undirected_neighbors() {
+	let mut a = Arena::<u32, (), ()>::new(false);
+	let x = a.add_node(1, ()).unwrap();
+	let y = a.add_node(2, ()).unwrap();
+	let z = a.add_node(3, ()).unwrap();
+	a.add_edge(x, y, ());
+	a.add_edge(z, x, ());
+	let mut n: Vec<NodeId> = a.neighbors(x, a.direction()).collect();
+	n.sort();
+	assert!(n == vec![y, z]);
+	assert!(a.neighbors(x, Direction::Outbound).collect::<Vec<_>>() == vec![y]);
+	assert!(a.find_edge(x, z).is_some());
+	// Self loops are listed once when deleting.
+	a.add_edge(y, y, ());
+	assert!(a.del_node(y).is_some() && a.edge_count() == 1);
+}
+
+#[test]
+fn arena_graph_conversion() {
+	let mut g = Digraph::<usize, usize, usize>::new();
+	for i in 0..50 {
+		g.add_node(i, i);
+	}
+	for i in 0..50 {
+		g.add_edge(i, (i * 7 + 3) % 50, i);
+		g.add_edge(i, (i + 1) % 50, i + 50);
+	}
+	let a = Arena::from_graph(&g);
+	assert!(a.directed() && a.node_count() == 50 && a.edge_count() == g.edge_count());
+	let back: Digraph<usize, usize, usize> = a.to_graph();
+	assert!(g.structural_eq(&back));
+
+	let mut u = Ungraph::<usize, usize, usize>::new();
+	for i in 0..5 {
+		u.add_node(i, i);
+	}
+	for i in 0..5 {
+		u.add_edge(i, (i + 1) % 5, i);
+	}
+	let a = Arena::from_graph(&u);
+	assert!(!a.directed() && a.edge_count() == 5);
+	let back: Ungraph<usize, usize, usize> = a.to_graph();
+	assert!(u.structural_eq(&back));
+}
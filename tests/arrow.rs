@@ -0,0 +1,99 @@
+#![cfg(feature = "arrow")]
+use fastgraph::collections::*;
+use fastgraph::io::{arrow::*, Error};
+
+const COLUMNS: Columns = (
+	&[("id", DataType::UInt32), ("name", DataType::Utf8)],
+	&[("from", DataType::UInt32), ("to", DataType::UInt32), ("weight", DataType::Float64)],
+);
+
+fn test_digraph() -> Digraph<u32, String, f64> {
+	let mut g = Digraph::<u32, String, f64>::new();
+	for i in 1..4 {
+		g.add_node(i, format!("node {}", i));
+	}
+	g.add_edge(1, 2, 0.5);
+	g.add_edge(2, 3, 1.5);
+	g.add_edge(3, 1, 2.0);
+	g
+}
+
+#[test]
+fn digraph_test_arrow_ipc_round_trip() {
+	let g = test_digraph();
+	let mut node_out = Vec::new();
+	let mut edge_out = Vec::new();
+	write_ipc(
+		&g,
+		&mut node_out,
+		&mut edge_out,
+		COLUMNS,
+		|k, n| vec![(*k).into(), n.clone().into()],
+		|s, t, e| vec![(*s).into(), (*t).into(), (*e).into()],
+	).unwrap();
+	// Keys are read into a wider type than they were written with.
+	let h: Digraph<u64, String, f64> = read_ipc(
+		std::io::Cursor::new(node_out),
+		std::io::Cursor::new(edge_out),
+		|r| Ok((r.get("id")?, r.get("name")?)),
+		|r| Ok((r.get("from")?, r.get("to")?, r.get("weight")?)),
+	).unwrap();
+	assert!(h.node_count() == 3);
+	assert!(h.edge_count() == 3);
+	assert!(h.get_node(2).unwrap().load() == "node 2");
+	assert!(h.get_edge(3, 1).unwrap().load() == 2.0);
+}
+
+#[test]
+fn digraph_test_arrow_errors() {
+	let g = test_digraph();
+	let (nodes, edges) = to_batches(
+		&g,
+		COLUMNS,
+		|k, n| vec![(*k).into(), n.clone().into()],
+		|s, t, e| vec![(*s).into(), (*t).into(), (*e).into()],
+	).unwrap();
+	let res: Result<Digraph<u32, u32, f64>, Error> = from_batches(
+		std::slice::from_ref(&nodes),
+		std::slice::from_ref(&edges),
+		|r| Ok((r.get("id")?, r.get("name")?)),
+		|r| Ok((r.get("from")?, r.get("to")?, r.get("weight")?)),
+	);
+	assert!(matches!(res, Err(Error::Record { line: 1, .. })));
+	let res: Result<Digraph<u32, String, f64>, Error> = from_batches(
+		&[nodes.slice(0, 2)],
+		&[edges],
+		|r| Ok((r.get("id")?, r.get("name")?)),
+		|r| Ok((r.get("from")?, r.get("to")?, r.get("weight")?)),
+	);
+	assert!(matches!(res, Err(Error::UnknownNode { .. })));
+	let res = to_batches(&g, COLUMNS, |k, _| vec![(*k).into()], |_, _, _| vec![]);
+	assert!(matches!(res, Err(Error::Format(_))));
+}
+
+#[cfg(feature = "parquet")]
+#[test]
+fn digraph_test_parquet_round_trip() {
+	let g = test_digraph();
+	let dir = std::env::temp_dir();
+	let nodes = dir.join("fastgraph_parquet_nodes.parquet");
+	let edges = dir.join("fastgraph_parquet_edges.parquet");
+	save_parquet(
+		&g,
+		&nodes,
+		&edges,
+		COLUMNS,
+		|k, n| vec![(*k).into(), n.clone().into()],
+		|s, t, e| vec![(*s).into(), (*t).into(), (*e).into()],
+	).unwrap();
+	let h: Digraph<u32, String, f64> = load_parquet(
+		&nodes,
+		&edges,
+		|r| Ok((r.get("id")?, r.get("name")?)),
+		|r| Ok((r.get("from")?, r.get("to")?, r.get("weight")?)),
+	).unwrap();
+	std::fs::remove_file(&nodes).unwrap();
+	std::fs::remove_file(&edges).unwrap();
+	assert!(h.node_count() == 3);
+	assert!(h.get_edge(1, 2).unwrap().load() == 0.5);
+}
@@ -1,6 +1,7 @@
 use fastgraph::core::*;
 use fastgraph::collections::*;
 use fastgraph::core::Empty;
+use rayon::prelude::*;
 type SimpleDigraph = Digraph<usize, Empty, Empty>;
 type SimpleUngraph = Ungraph<usize, Empty, Empty>;
 
@@ -191,3 +192,544 @@ fn digraph_test_par_breadth_traversal() {
 	assert!(path2[2].upgrade().unwrap().target().key() == &4);
 	assert!(path2[3].upgrade().unwrap().target().key() == &6);
 }
+
+#[test]
+fn digraph_test_disconnect() {
+	let mut g = SimpleDigraph::new();
+	g.add_node(1, Empty);
+	g.add_node(2, Empty);
+	g.add_node(3, Empty);
+	g.add_edge(1, 2, Empty);
+	g.add_edge(3, 1, Empty);
+	assert!(g.del_edge(1, 2));
+	let a = g.get_node(1).unwrap();
+	let b = g.get_node(2).unwrap();
+	assert!(a.outbound().is_empty());
+	assert!(a.inbound().len() == 1);
+	assert!(b.inbound().is_empty());
+}
+
+#[test]
+fn digraph_test_compact() {
+	let mut g = SimpleDigraph::new();
+	for i in 0..100 {
+		g.add_node(i, Empty);
+	}
+	for i in 1..100 {
+		g.add_edge(0, i, Empty);
+	}
+	for i in 10..100 {
+		g.del_edge(0, i);
+	}
+	assert!(g.compact() > 0);
+	assert!(g.edge_count() == 9);
+	assert!(g.get_node(50).unwrap().inbound().is_empty());
+	assert!(g.get_node(5).unwrap().find_inbound(&g.get_node(0).unwrap()).is_some());
+	assert!(g.compact() == 0);
+}
+
+#[test]
+fn digraph_test_fold_traversal() {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 1..8 {
+		g.add_node(i, Empty);
+	}
+	// A binary tree rooted at 1 with an extra edge back to the root.
+	for i in 2..8 {
+		g.add_edge(i / 2, i, Empty);
+	}
+	g.add_edge(7, 1, Empty);
+	let push = |mut acc: Vec<usize>, node: &std::sync::Arc<Node<usize, Empty, Empty>>, _: Option<&std::sync::Arc<Edge<usize, Empty, Empty>>>| {
+		acc.push(*node.key());
+		acc
+	};
+	// Neighbors are visited in the order their edges were added.
+	assert!(g.fold_bfs(1, Vec::new(), push).unwrap() == vec![1, 2, 3, 4, 5, 6, 7]);
+	assert!(g.fold_dfs(1, Vec::new(), push).unwrap() == vec![1, 2, 4, 5, 3, 6, 7]);
+	// The edge each node was reached through ends at the node.
+	assert!(g.fold_bfs(1, true, |acc, node, edge| acc && edge.map_or(true, |e| e.target() == *node)).unwrap());
+	assert!(g.fold_dfs(6, 0, |acc, _, _| acc + 1) == Some(1));
+	assert!(g.fold_bfs(42, 0, |acc, _, _| acc + 1).is_none());
+}
+
+#[test]
+fn ungraph_test_par_fold_traversal() {
+	let g = test_digraph_1();
+	let count = g.par_fold_bfs(1, || 0, |acc, _, _| acc + 1, |a, b| a + b);
+	assert!(count == Some(6));
+	let keys = g.par_fold_bfs(6, || 0, |acc, node, _| acc + node.key(), |a, b| a + b);
+	assert!(keys == g.fold_dfs(6, 0, |acc, node, _| acc + node.key()));
+	assert!(g.par_fold_bfs(42, || 0, |acc, _, _| acc + 1, |a, b| a + b).is_none());
+}
+
+#[test]
+fn digraph_test_gather_scatter_node_data() {
+	let mut g = Digraph::<usize, usize, Empty>::new();
+	for i in 0..1000 {
+		g.add_node(i, i);
+	}
+	let gathered = g.gather_node_data();
+	assert!(gathered.len() == 1000);
+	assert!(gathered.iter().all(|(k, n)| k == n));
+	// The order is stable while the nodes stay the same.
+	let keys: Vec<usize> = gathered.iter().map(|(k, _)| *k).collect();
+	g.add_edge(1, 2, Empty);
+	assert!(g.gather_node_data().iter().map(|(k, _)| *k).collect::<Vec<_>>() == keys);
+	let updates: Vec<(usize, usize)> = gathered.into_iter().map(|(k, n)| (k, n * 3)).chain(std::iter::once((5000, 0))).collect();
+	assert!(g.scatter_node_data(updates) == 1000);
+	for i in 0..1000 {
+		assert!(g.get_node(i).unwrap().load() == i * 3);
+	}
+}
+
+#[test]
+fn digraph_test_deferred_mutations() {
+	let mut g = test_ungraph_1();
+	// Cut every edge out of 4 and add a new node for every node while
+	// iterating.
+	let applied = g.deferred(|g, queue| {
+		g.iter_nodes(&|node| {
+			for edge in node.outbound().iter() {
+				if edge.source().key() == &4 {
+					let target = *edge.target().key();
+					queue.defer(move |g| { g.del_edge(4, target); });
+				}
+			}
+		});
+		g.iter_nodes(&|node| {
+			let key = *node.key() + 100;
+			queue.defer(move |g| { g.add_node(key, Empty); });
+		});
+		assert!(queue.len() == 10);
+	});
+	assert!(applied == 10);
+	assert!(g.node_count() == 12);
+	assert!(g.get_node(4).unwrap().outbound().is_empty());
+	// Mutations are applied in order.
+	let queue = Deferred::new();
+	queue.defer(|g: &mut SimpleDigraph| { g.add_node(1000, Empty); });
+	queue.defer(|g: &mut SimpleDigraph| { g.del_node(1000); });
+	assert!(queue.apply(&mut g) == 2);
+	assert!(g.get_node(1000).is_none());
+}
+
+#[test]
+fn digraph_test_retain() {
+	let mut g = Digraph::<usize, usize, usize>::new();
+	for i in 0..100 {
+		g.add_node(i, i);
+	}
+	for i in 0..100 {
+		for j in 1..4 {
+			g.add_edge(i, (i + j) % 100, i + j);
+		}
+	}
+	let feed = g.subscribe();
+	assert!(g.retain_nodes(|n| n.load() % 10 != 0) == 10);
+	assert!(g.node_count() == 90);
+	assert!(g.validate().is_ok());
+	// Every removed node had three outbound and three inbound edges.
+	assert!(g.edge_count() == 300 - 60);
+	assert!(g.retain_nodes(|_| true) == 0);
+	let odd = g.nodes().iter().map(|n| n.outbound().iter().filter(|e| e.load() % 2 == 1).count()).sum::<usize>();
+	assert!(g.retain_edges(|e| e.load() % 2 == 0) == odd);
+	assert!(g.edge_count() == 240 - odd);
+	assert!(g.validate().is_ok());
+	assert!(g.nodes().iter().all(|n| n.inbound().len() == g.nodes().iter().filter(|m| m.find_outbound(n).is_some()).count()));
+	let events: Vec<_> = feed.try_iter().collect();
+	assert!(events.iter().filter(|e| matches!(e, fastgraph::events::Event::DelNode { .. })).count() == 10);
+	assert!(events.iter().filter(|e| matches!(e, fastgraph::events::Event::DelEdge { .. })).count() == 60 + odd);
+	// The edges of a removed node are announced before the node itself.
+	let edge = events.iter().position(|e| *e == fastgraph::events::Event::DelEdge { source: 10, target: 11 }).unwrap();
+	let node = events.iter().position(|e| *e == fastgraph::events::Event::DelNode { key: 10 }).unwrap();
+	assert!(edge < node);
+}
+
+#[test]
+fn ungraph_test_retain() {
+	let mut g = test_digraph_1();
+	assert!(g.retain_nodes(|n| n.key() != &4) == 1);
+	assert!(g.get_node(6).unwrap().degree() == 0 && g.get_node(6).unwrap().inbound().is_empty());
+	assert!(g.get_node(5).unwrap().neighbors(Direction::Both).iter().all(|n| n.key() != &4));
+	assert!(g.validate().is_ok());
+}
+
+#[test]
+fn digraph_test_map() {
+	let mut g = Digraph::<usize, String, String>::new();
+	for i in 0..10 {
+		g.add_node(i, format!("{}", i * 2));
+	}
+	for i in 0..10 {
+		g.add_edge(i, (i + 1) % 10, format!("{}", i));
+		g.add_edge(i, (i + 3) % 10, format!("{}", i + 100));
+	}
+	let h: Digraph<usize, usize, usize> = g.map(|_, n| n.parse().unwrap(), |e| e.parse().unwrap());
+	assert!(h.node_count() == 10 && h.edge_count() == 20);
+	assert!(h.nodes().iter().all(|n| n.load() == n.key() * 2));
+	assert!(h.get_edge(4, 7).unwrap().load() == 104);
+
+	// Every edge has an odd end, so dropping the odd nodes drops all edges.
+	let f: Ungraph<usize, usize, usize> = g.filter_map(|k, n| if k % 2 == 0 { n.parse().ok() } else { None }, |e| e.parse().ok());
+	assert!(f.node_count() == 5 && f.edge_count() == 0);
+	let f: Ungraph<usize, usize, usize> = g.filter_map(|k, _| Some(*k), |e| e.parse::<usize>().ok().filter(|e| *e >= 100));
+	assert!(f.edge_count() == 10 && f.get_edge(9, 2).is_some());
+	assert!(f.validate().is_ok());
+}
+
+#[test]
+fn ungraph_test_map_keys() {
+	let mut g = Ungraph::<String, usize, usize>::new();
+	for i in 0..10 {
+		g.add_node(format!("n{}", i), i);
+	}
+	for i in 0..10 {
+		g.add_edge(format!("n{}", i), format!("n{}", (i + 1) % 10), i);
+	}
+	let h: Ungraph<usize, usize, usize> = g.map_keys(|k| k[1..].parse().unwrap()).unwrap();
+	assert!(h.node_count() == 10 && h.edge_count() == 10);
+	assert!(h.nodes().iter().all(|n| n.load() == *n.key()));
+	assert!(h.get_edge(9, 0).unwrap().load() == 9 || h.get_edge(0, 9).unwrap().load() == 9);
+	assert!(h.validate().is_ok());
+
+	// And back again.
+	let back: Ungraph<String, usize, usize> = h.map_keys(|k| format!("n{}", k)).unwrap();
+	assert!(g.structural_eq(&back));
+
+	let collision: Result<Ungraph<usize, usize, usize>, _> = g.map_keys(|k| k.len());
+	let (a, b) = collision.err().unwrap();
+	assert!(a != b && a.len() == b.len());
+}
+
+#[test]
+fn digraph_test_adjacency() {
+	let g = test_digraph_1();
+	let node = g.get_node(1).unwrap();
+	// The list has the same type with or without the `smallvec` feature.
+	let outbound: &fastgraph::core::Adjacency<_> = &node.outbound();
+	let edges: &[_] = outbound;
+	assert!(edges.len() == outbound.iter().count() && outbound.spilled() != (edges.len() <= 4 && cfg!(feature = "smallvec")));
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn digraph_test_inline_adjacency() {
+	let mut g = test_digraph_1();
+	let node = g.get_node(1).unwrap();
+	assert!(!node.outbound().spilled() && !node.inbound().spilled());
+	for i in 10..20 {
+		g.add_node(i, Empty);
+		g.add_edge(1, i, Empty);
+	}
+	assert!(node.outbound().spilled() && node.outbound().len() == 12);
+	for i in 10..20 {
+		g.del_edge(1, i);
+	}
+	assert!(g.compact() > 0 && !node.outbound().spilled());
+	assert!(g.validate().is_ok());
+}
+
+type Handles = (Vec<std::sync::Weak<Node<usize, Empty, Empty>>>, Vec<std::sync::Weak<Edge<usize, Empty, Empty>>>);
+
+// Weak handles to every node and edge of a complete graph.
+fn complete_handles<G: Graph<usize, Empty, Empty>>(g: &mut G) -> Handles {
+	for i in 0..20 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..20 {
+		for j in 0..20 {
+			g.add_edge(i, j, Empty);
+		}
+	}
+	let nodes = g.nodes().iter().map(std::sync::Arc::downgrade).collect();
+	let edges = g.nodes().iter().flat_map(|n| n.outbound().iter().map(std::sync::Arc::downgrade).collect::<Vec<_>>()).collect();
+	(nodes, edges)
+}
+
+#[test]
+fn digraph_test_drop_and_clear() {
+	let mut g = SimpleDigraph::new();
+	let (nodes, edges) = complete_handles(&mut g);
+	assert!(edges.len() == 400);
+	drop(g);
+	assert!(nodes.iter().all(|n| n.upgrade().is_none()) && edges.iter().all(|e| e.upgrade().is_none()));
+
+	let mut g = SimpleUngraph::new();
+	let (nodes, edges) = complete_handles(&mut g);
+	let feed = g.subscribe();
+	let kept = g.get_node(3).unwrap();
+	assert!(g.clear() == 20 && g.node_count() == 0 && g.edge_count() == 0);
+	assert!(feed.try_iter().count() == 20 + edges.len());
+	assert!(kept.outbound().is_empty() && kept.inbound().is_empty());
+	assert!(nodes.iter().filter(|n| n.upgrade().is_some()).count() == 1);
+	assert!(edges.iter().all(|e| e.upgrade().is_none()));
+	assert!(g.add_node(1, Empty) && g.node_count() == 1);
+}
+
+#[test]
+fn ungraph_test_build() {
+	// Cells of a 5 by 5 grid as 10 * x + y with steps right and down.
+	let cells = (0..5).flat_map(|x| (0..5).map(move |y| 10 * x + y));
+	let all = SimpleDigraph::build(cells, 25, |k| vec![(k + 10, Empty), (k + 1, Empty)]);
+	assert!(all.node_count() == 25 && all.edge_count() == 40);
+
+	let mut calls = 0;
+	let g = SimpleUngraph::build([0, 0, 1], 6, |k| {
+		calls += 1;
+		vec![(k + 2, Empty), (k * 3, Empty)]
+	});
+	assert!(g.node_count() == 6 && calls == 6);
+	assert!(g.get_edge(0, 2).is_some() && g.get_edge(1, 3).is_some());
+	assert!(g.get_node(0).unwrap().find_outbound(&g.get_node(0).unwrap()).is_some());
+	let none = SimpleDigraph::build(0..10, 0, |k| vec![(k + 1, Empty)]);
+	assert!(none.node_count() == 0);
+}
+
+#[test]
+fn digraph_test_hub_index() {
+	let mut g = SimpleDigraph::new();
+	let n = 50_000;
+	for i in 0..n {
+		g.add_node(i, Empty);
+	}
+	for i in 1..n {
+		assert!(g.add_edge(0, i, Empty));
+		assert!(g.add_edge(i, 0, Empty));
+	}
+	// Duplicates are still rejected once the hub is indexed.
+	assert!(!connect(&g.get_node(0).unwrap(), &g.get_node(7).unwrap(), Empty));
+	assert!(g.edge_count() == 2 * (n - 1));
+	let hub = g.get_node(0).unwrap();
+	for i in (1..n).step_by(97) {
+		let node = g.get_node(i).unwrap();
+		assert!(hub.find_outbound(&node).unwrap().target().key() == &i);
+		assert!(hub.find_inbound(&node).unwrap().upgrade().unwrap().source().key() == &i);
+	}
+	assert!(hub.find_outbound(&hub).is_none());
+
+	// Disconnect and isolate keep the indexes up to date.
+	assert!(disconnect(&hub, &g.get_node(5).unwrap()));
+	assert!(hub.find_outbound(&g.get_node(5).unwrap()).is_none());
+	assert!(hub.find_inbound(&g.get_node(5).unwrap()).is_some());
+	isolate(&g.get_node(6).unwrap());
+	assert!(hub.find_outbound(&g.get_node(6).unwrap()).is_none());
+	assert!(hub.find_inbound(&g.get_node(6).unwrap()).is_none());
+	assert!(g.add_edge(0, 5, Empty) && g.get_edge(0, 5).is_some());
+
+	// Direct mutation of the lists drops the indexes.
+	hub.outbound_mut().retain(|e| e.target().key() % 2 == 0);
+	assert!(hub.find_outbound(&g.get_node(3).unwrap()).is_none());
+	assert!(hub.find_outbound(&g.get_node(4).unwrap()).is_some());
+	g.retain_edges(|e| e.source().key() % 3 != 0 || e.target().key() != &0);
+	assert!(hub.find_inbound(&g.get_node(9).unwrap()).is_none());
+	assert!(hub.find_inbound(&g.get_node(10).unwrap()).is_some());
+}
+
+#[test]
+fn ungraph_test_sort_edges() {
+	let mut g = Ungraph::<usize, Empty, usize>::new();
+	for i in 0..100 {
+		g.add_node(i, Empty);
+	}
+	// Weights are a permutation of the keys so that the order is unique.
+	for i in 1..100 {
+		g.add_edge(0, i, (i * 37) % 100);
+		g.add_edge(i, 0, 0);
+	}
+	for i in 1..99 {
+		g.add_edge(i + 1, i, i);
+	}
+	g.sort_all_edges_by_weight(|e| e.load() as f64);
+	for node in g.nodes() {
+		let outbound: Vec<usize> = node.outbound().iter().map(|e| e.load()).collect();
+		let inbound: Vec<usize> = node.inbound().iter().map(|e| e.upgrade().unwrap().load()).collect();
+		assert!(outbound.windows(2).all(|w| w[0] <= w[1]));
+		assert!(inbound.windows(2).all(|w| w[0] <= w[1]));
+	}
+	// The order of breadth first search follows the weights of the hub.
+	let hub = g.get_node(0).unwrap();
+	let order = g.fold_bfs(0, Vec::new(), |mut acc, node, _| {
+		acc.push(*node.key());
+		acc
+	}).unwrap();
+	let mut expected: Vec<usize> = (1..100).collect();
+	expected.sort_by_key(|i| (i * 37) % 100);
+	assert!(order[1..] == expected[..]);
+	// Lookups through the index of the hub still work.
+	for i in 1..100 {
+		assert!(hub.find_outbound(&g.get_node(i).unwrap()).unwrap().load() == (i * 37) % 100);
+	}
+	hub.sort_outbound_by(|a, b| b.target().key().cmp(a.target().key()));
+	assert!(hub.outbound()[0].target().key() == &99);
+}
+
+#[test]
+fn digraph_test_merge_edges() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..100 {
+		g.add_node(i, Empty);
+	}
+	// Repeated interactions with a hub, merged through its index.
+	for round in 0..3 {
+		for i in 1..100 {
+			assert!(g.add_edge_with(0, i, i as f64, |w, x| *w += x));
+		}
+		assert!(g.edge_count() == 99);
+		let revision = g.revision();
+		g.add_edge_with(0, 1, 0.0, |w, x| *w += x);
+		assert!(g.revision() != revision);
+		assert!(g.get_edge(0, 50).unwrap().load() == 50.0 * (round + 1) as f64);
+	}
+	assert!(!g.add_edge_with(0, 100, 1.0, |w, x| *w += x));
+
+	// Concurrent merges into the same edge are all applied.
+	let (a, b) = (g.get_node(1).unwrap(), g.get_node(2).unwrap());
+	let added: usize = (0..1000)
+		.into_par_iter()
+		.map(|_| connect_or_merge(&a, &b, 1.0, |w, x| *w += x) as usize)
+		.sum();
+	assert!(added == 1);
+	assert!(g.get_edge(1, 2).unwrap().load() == 1000.0);
+}
+
+#[test]
+fn ungraph_test_prune_degree() {
+	// A 4-clique with a path hanging from node 0 and an isolated node.
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..10 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..4 {
+		for j in i + 1..4 {
+			g.add_edge(i, j, Empty);
+		}
+	}
+	g.add_edge(0, 4, Empty);
+	for i in 4..8 {
+		g.add_edge(i, i + 1, Empty);
+	}
+	let mut rounds = g.prune_degree_lt(2);
+	rounds[0].sort();
+	assert!(rounds[0] == vec![8, 9]);
+	assert!(rounds[1..] == [vec![7], vec![6], vec![5], vec![4]]);
+	assert!(g.node_count() == 4);
+	assert!(g.nodes().iter().all(|n| n.outbound().len() + n.inbound().len() == 3));
+	assert!(g.prune_degree_lt(3).is_empty());
+	assert!(g.trim_leaves(usize::MAX).is_empty());
+	assert!(g.prune_degree_lt(4).len() == 1 && g.node_count() == 0);
+}
+
+#[test]
+fn digraph_test_complement_and_line_graph() {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	// The cycle 0 -> 1 -> 2 -> 0 and the edge 2 -> 3.
+	g.add_edge(0, 1, Empty);
+	g.add_edge(1, 2, Empty);
+	g.add_edge(2, 0, Empty);
+	g.add_edge(2, 3, Empty);
+	let h: Digraph<usize, usize, usize> = g.complement(|k, _| *k, |s, t| s * 10 + t);
+	assert!(h.edge_count() == 4 * 3 - 4);
+	assert!(h.get_edge(1, 0).unwrap().load() == 10);
+	assert!(h.get_edge(0, 1).is_none() && h.get_edge(0, 0).is_none());
+	// The complement of the complement is the graph.
+	assert!(g.structural_eq(&h.complement::<Empty, Empty, Digraph<usize, Empty, Empty>, _, _>(|_, _| Empty, |_, _| Empty)));
+
+	let l: Digraph<usize, Empty, usize> = g.line_graph(
+		|e| (e.source().key() * 10 + e.target().key(), Empty),
+		|shared| *shared.key(),
+	);
+	assert!(l.node_count() == 4);
+	assert!(l.edge_count() == 4);
+	assert!(l.get_edge(12, 20).unwrap().load() == 2);
+	assert!(l.get_edge(12, 23).is_some() && l.get_edge(23, 12).is_none());
+	assert!(l.get_node(23).unwrap().outbound().is_empty());
+}
+
+#[test]
+fn digraph_test_rewire() {
+	use rand::{rngs::StdRng, SeedableRng};
+	// Disjoint directed triangles, which rewiring mostly breaks up.
+	let mut g = SimpleDigraph::new();
+	for i in 0..60 {
+		g.add_node(i, Empty);
+	}
+	for t in 0..20 {
+		let (a, b, c) = (t * 3, t * 3 + 1, t * 3 + 2);
+		g.add_edge(a, b, Empty);
+		g.add_edge(b, c, Empty);
+		g.add_edge(c, a, Empty);
+	}
+	let triangles = |g: &SimpleDigraph| {
+		let mut count = 0;
+		for node in g.nodes() {
+			for e in node.outbound().iter() {
+				for f in e.target().outbound().iter() {
+					if g.get_edge(*f.target().key(), *node.key()).is_some() {
+						count += 1;
+					}
+				}
+			}
+		}
+		count / 3
+	};
+	let degrees = |g: &SimpleDigraph| {
+		let mut d: Vec<(usize, usize, usize)> = g.nodes().iter().map(|n| (*n.key(), n.inbound().len(), n.outbound().len())).collect();
+		d.sort();
+		d
+	};
+	let before = degrees(&g);
+	assert!(triangles(&g) == 20);
+
+	let mut rng = StdRng::seed_from_u64(12);
+	assert!(g.rewire(600, &mut rng) == 600);
+	assert!(degrees(&g) == before && g.edge_count() == 60);
+	assert!(triangles(&g) < 10);
+	for node in g.nodes() {
+		assert!(g.get_edge(*node.key(), *node.key()).is_none());
+	}
+}
+
+#[test]
+fn digraph_test_reverse_edges() {
+	let mut g = Digraph::<usize, Empty, usize>::new();
+	for i in 0..6 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..6 {
+		g.add_edge(i, (i + 1) % 6, i);
+	}
+	g.add_edge(1, 0, 10);
+	g.add_edge(3, 3, 30);
+	let events = g.subscribe();
+
+	// A stale edge can't be reversed again.
+	let edge = g.get_edge(4, 5).unwrap();
+	assert!(edge.reverse() && !edge.reverse());
+	assert!(g.get_edge(5, 4).unwrap().load() == 4);
+	assert!(!g.reverse_edge(1, 0) && !g.reverse_edge(4, 5) && !g.reverse_edge(0, 9));
+	assert!(g.reverse_edge(3, 3) && g.get_edge(3, 3).unwrap().load() == 30);
+
+	// Both edges between 0 and 1 are selected and swap their data.
+	assert!(g.reverse_edges_where(|e| e.load() % 2 == 0) == 5);
+	assert!(g.get_edge(0, 1).unwrap().load() == 10 && g.get_edge(1, 0).unwrap().load() == 0);
+	assert!(g.get_edge(3, 2).unwrap().load() == 2);
+	assert!(g.get_edge(4, 5).unwrap().load() == 4 && g.get_edge(3, 3).is_some());
+	assert!(g.get_edge(3, 4).unwrap().load() == 3);
+	assert!(g.edge_count() == 8);
+	assert!(g.validate().is_ok());
+
+	// Edges reversed through the graph are a deleted and an added edge,
+	// swapped data deletes both edges before adding them back and loops
+	// send nothing.
+	let received: Vec<_> = events.try_iter().collect();
+	assert!(received.len() == 8);
+	use fastgraph::events::Event;
+	let swap = received.iter().position(|e| matches!(e, Event::DelEdge { source: 0, .. } | Event::DelEdge { source: 1, target: 0 })).unwrap();
+	assert!(received[swap..swap + 2].contains(&Event::DelEdge { source: 0, target: 1 }));
+	assert!(received[swap..swap + 2].contains(&Event::DelEdge { source: 1, target: 0 }));
+	assert!(received[swap + 2..swap + 4].contains(&Event::AddEdge { source: 0, target: 1, data: 10 }));
+	assert!(received[swap + 2..swap + 4].contains(&Event::AddEdge { source: 1, target: 0, data: 0 }));
+}
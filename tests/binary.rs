@@ -0,0 +1,149 @@
+#![cfg(feature = "binary")]
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::io::{binary::*, Error};
+
+fn test_digraph_weighted() -> Digraph<u32, String, f64> {
+	let mut g = Digraph::<u32, String, f64>::new();
+	for i in 0..300 {
+		g.add_node(i, format!("node {}", i));
+	}
+	for i in 0..300 {
+		g.add_edge(i, (i * 7 + 1) % 300, i as f64 / 2.0);
+		g.add_edge(i, (i * 13 + 5) % 300, i as f64);
+	}
+	g
+}
+
+#[test]
+fn digraph_test_binary_round_trip() {
+	let g = test_digraph_weighted();
+	let path = std::env::temp_dir().join("fastgraph_binary_round_trip.bin");
+	save(&g, &path).unwrap();
+	let h: Digraph<u32, String, f64> = load(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert!(h.node_count() == g.node_count());
+	assert!(h.edge_count() == g.edge_count());
+	assert!(h.get_node(42).unwrap().load() == "node 42");
+	assert!(h.get_edge(10, 71).unwrap().load() == 5.0);
+	let node = h.get_node(71).unwrap();
+	assert!(node.find_inbound(&h.get_node(10).unwrap()).is_some());
+}
+
+#[test]
+fn ungraph_test_binary_rejects_invalid_input() {
+	let mut g = Ungraph::<u32, Empty, Empty>::new();
+	g.add_node(1, Empty);
+	g.add_node(2, Empty);
+	g.add_edge(1, 2, Empty);
+	let mut buffer = Vec::new();
+	write_binary(&g, &mut buffer).unwrap();
+
+	let mut wrong_magic = buffer.clone();
+	wrong_magic[0] = b'X';
+	let res: Result<Ungraph<u32, Empty, Empty>, Error> = read_binary(wrong_magic.as_slice());
+	assert!(matches!(res, Err(Error::Format(_))));
+
+	let mut wrong_version = buffer.clone();
+	wrong_version[8] = 99;
+	let res: Result<Ungraph<u32, Empty, Empty>, Error> = read_binary(wrong_version.as_slice());
+	assert!(matches!(res, Err(Error::Format(_))));
+
+	let res: Result<Ungraph<u32, Empty, Empty>, Error> = read_binary(&buffer[..buffer.len() - 3]);
+	assert!(matches!(res, Err(Error::Io(_))));
+}
+
+#[test]
+fn digraph_test_binary_detects_corruption() {
+	let g = test_digraph_weighted();
+	let mut buffer = Vec::new();
+	write_binary(&g, &mut buffer).unwrap();
+	assert!(verify(buffer.as_slice()).is_ok());
+
+	// Flip a bit in bytes spread over the sections. Every corruption must be
+	// reported rather than yield a graph.
+	for i in (13..buffer.len()).step_by(97) {
+		let mut corrupted = buffer.clone();
+		corrupted[i] ^= 0x10;
+		assert!(verify(corrupted.as_slice()).is_err());
+		let res: Result<Digraph<u32, String, f64>, Error> = read_binary(corrupted.as_slice());
+		assert!(res.is_err());
+	}
+}
+
+// A version 1 file, which has no checksums, of nodes with the keys 1 to
+// `nodes` and the given adjacency section and amount of edges.
+fn version_1(nodes: u32, adjacency: &[u8], edges: u64) -> Vec<u8> {
+	let section = |tag: u8, payload: &[u8]| {
+		let mut res = vec![tag, payload.len() as u8];
+		res.extend_from_slice(payload);
+		res
+	};
+	let mut keys = (nodes as u64).to_le_bytes().to_vec();
+	for key in 1..=nodes {
+		keys.extend_from_slice(&key.to_le_bytes());
+	}
+	let mut buffer = b"FASTGRPH".to_vec();
+	buffer.extend_from_slice(&1u32.to_le_bytes());
+	buffer.push(1);
+	buffer.extend(section(1, &keys));
+	buffer.extend(section(2, &(nodes as u64).to_le_bytes()));
+	buffer.extend(section(3, adjacency));
+	buffer.extend(section(4, &edges.to_le_bytes()));
+	buffer.push(0);
+	buffer
+}
+
+#[test]
+fn digraph_test_binary_rejects_overflowing_degrees() {
+	// Degrees that wrap around to the amount of edges when summed.
+	let mut adjacency = vec![2];
+	adjacency.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+	adjacency.extend_from_slice(&[2, 1]);
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_binary(version_1(2, &adjacency, 1).as_slice());
+	assert!(matches!(res, Err(Error::Format(_))));
+}
+
+#[test]
+fn digraph_test_binary_rejects_malformed_adjacency() {
+	// Node 1 has two edges to node 2.
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_binary(version_1(2, &[2, 2, 0, 1, 1], 2).as_slice());
+	assert!(matches!(res, Err(Error::Format(message)) if message == "duplicate edge 1 -> 2"));
+	// Bytes after the last target.
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_binary(version_1(2, &[2, 1, 0, 1, 7, 7, 7], 1).as_slice());
+	assert!(matches!(res, Err(Error::Format(message)) if message.contains("left over")));
+	let g: Digraph<u32, Empty, Empty> = read_binary(version_1(2, &[2, 1, 1, 1, 0], 2).as_slice()).unwrap();
+	assert!(g.validate().is_ok() && g.get_edge(1, 2).is_some() && g.get_edge(2, 1).is_some());
+}
+
+#[test]
+fn digraph_test_binary_version_1_is_not_verified() {
+	// Without checksums corruption can't be detected, so verifying fails
+	// even though the file can be read.
+	let buffer = version_1(2, &[2, 1, 0, 1], 1);
+	assert!(matches!(verify(buffer.as_slice()), Err(Error::Format(_))));
+	let g: Digraph<u32, Empty, Empty> = read_binary(buffer.as_slice()).unwrap();
+	assert!(g.get_edge(1, 2).is_some());
+}
+
+#[test]
+fn digraph_test_binary_rejects_other_kind() {
+	let mut g = Digraph::<u32, Empty, Empty>::new();
+	g.add_node(0, Empty);
+	g.add_node(1, Empty);
+	g.add_edge(0, 1, Empty);
+	g.add_edge(1, 0, Empty);
+	let mut buffer = Vec::new();
+	write_binary(&g, &mut buffer).unwrap();
+	let res: Result<Ungraph<u32, Empty, Empty>, Error> = read_binary(buffer.as_slice());
+	assert!(matches!(res, Err(Error::Format(_))));
+	let h: Digraph<u32, Empty, Empty> = read_binary(buffer.as_slice()).unwrap();
+	assert!(h.edge_count() == 2);
+
+	let mut u = Ungraph::<u32, Empty, Empty>::new();
+	u.add_node(0, Empty);
+	let mut buffer = Vec::new();
+	write_binary(&u, &mut buffer).unwrap();
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_binary(buffer.as_slice());
+	assert!(matches!(res, Err(Error::Format(_))));
+}
@@ -0,0 +1,125 @@
+use fastgraph::cache::*;
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use std::sync::{
+	atomic::{AtomicUsize, Ordering},
+	Arc,
+};
+
+fn cycle(n: u32) -> Digraph<u32, Empty, Empty> {
+	let mut g = Digraph::new();
+	for i in 0..n {
+		g.add_node(i, Empty);
+	}
+	for i in 0..n {
+		g.add_edge(i, (i + 1) % n, Empty);
+	}
+	g
+}
+
+#[test]
+fn cache_test_revision() {
+	let mut g = cycle(3);
+	let r0 = g.revision().unwrap();
+	g.edge_count();
+	g.get_node(1).unwrap().store(Empty);
+	assert!(g.revision() == Some(r0));
+	g.add_edge(0, 2, Empty);
+	let r1 = g.revision().unwrap();
+	assert!(r1 != r0);
+	// Failed mutations don't change the revision.
+	g.add_edge(0, 7, Empty);
+	g.del_node(7);
+	assert!(g.revision() == Some(r1));
+	g.del_edge(0, 2);
+	assert!(g.revision() != Some(r1));
+	// Revisions of different graphs never collide.
+	let h = cycle(3);
+	assert!(h.revision() != g.revision());
+	let mut u = Ungraph::<u32, Empty, Empty>::new();
+	let r = u.revision();
+	u.add_node(1, Empty);
+	assert!(u.revision() != r);
+}
+
+#[test]
+fn cache_test_memo() {
+	let mut g = cycle(4);
+	let memo = Memo::new();
+	let calls = AtomicUsize::new(0);
+	let count = |g: &Digraph<u32, Empty, Empty>| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		g.edge_count()
+	};
+	assert!(!memo.is_fresh(&g));
+	assert!(*memo.get(&g, count) == 4);
+	assert!(*memo.get(&g, count) == 4);
+	assert!(memo.is_fresh(&g));
+	assert!(calls.load(Ordering::Relaxed) == 1);
+	g.retain_edges(|e| *e.source().key() != 0);
+	assert!(!memo.is_fresh(&g));
+	assert!(*memo.get(&g, count) == 3);
+	assert!(calls.load(Ordering::Relaxed) == 2);
+	// A memo used with another graph recomputes.
+	let h = cycle(5);
+	assert!(*memo.get(&h, count) == 5);
+	memo.clear();
+	assert!(*memo.get(&h, count) == 5);
+	assert!(calls.load(Ordering::Relaxed) == 4);
+}
+
+#[test]
+fn cache_test_scatter() {
+	let mut g = Digraph::<u32, u32, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, i);
+	}
+	let memo = Memo::new();
+	let sum = |g: &Digraph<u32, u32, Empty>| g.gather_node_data().iter().map(|(_, v)| v).sum::<u32>();
+	assert!(*memo.get(&g, sum) == 6);
+	// Updates to missing nodes change nothing.
+	assert!(g.scatter_node_data(vec![(9, 1)]) == 0);
+	assert!(memo.is_fresh(&g));
+	assert!(g.scatter_node_data(vec![(0, 10), (9, 1)]) == 1);
+	assert!(!memo.is_fresh(&g));
+	assert!(*memo.get(&g, sum) == 16);
+}
+
+#[test]
+fn cache_test_algorithms() {
+	let mut g = cycle(6);
+	g.add_node(6, Empty);
+	let cache = Cache::new();
+	let scc = cache.strongly_connected_components(&g);
+	assert!(scc.len() == 2);
+	assert!(Arc::ptr_eq(&scc, &cache.strongly_connected_components(&g)));
+	let ranks = cache.pagerank(&g, 0.85, 50, 1e-9);
+	assert!(Arc::ptr_eq(&ranks, &cache.pagerank(&g, 0.85, 50, 1e-9)));
+	assert!(!Arc::ptr_eq(&ranks, &cache.pagerank(&g, 0.5, 50, 1e-9)));
+	g.del_edge(2, 3);
+	assert!(cache.strongly_connected_components(&g).len() == 7);
+	assert!(cache.components(&g).len() == 2);
+	g.clear();
+	assert!(cache.components(&g).is_empty());
+	assert!(cache.pagerank(&g, 0.5, 50, 1e-9).is_empty());
+}
+
+#[test]
+fn cache_test_shared() {
+	let g = Shared::new(cycle(100));
+	let cache = Cache::new();
+	let calls = AtomicUsize::new(0);
+	let memo = Memo::new();
+	std::thread::scope(|s| {
+		for _ in 0..8 {
+			s.spawn(|| {
+				let g = g.read();
+				assert!(cache.strongly_connected_components(&*g).len() == 1);
+				memo.get(&*g, |_| calls.fetch_add(1, Ordering::Relaxed));
+			});
+		}
+	});
+	assert!(calls.load(Ordering::Relaxed) == 1);
+	g.write().del_edge(0, 1);
+	assert!(cache.strongly_connected_components(&*g.read()).len() == 100);
+}
@@ -0,0 +1,328 @@
+use fastgraph::collections::*;
+use fastgraph::centrality::*;
+use fastgraph::community::*;
+use fastgraph::core::Empty;
+use std::collections::HashMap;
+type SimpleUngraph = Ungraph<usize, Empty, Empty>;
+
+// Two triangles 1-2-3 and 4-5-6 joined by the bridge 3-4.
+fn test_ungraph_bridge() -> SimpleUngraph {
+	let mut g = SimpleUngraph::new();
+	for i in 1..7 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, Empty);
+	g.add_edge(2, 3, Empty);
+	g.add_edge(3, 1, Empty);
+	g.add_edge(4, 5, Empty);
+	g.add_edge(5, 6, Empty);
+	g.add_edge(6, 4, Empty);
+	g.add_edge(3, 4, Empty);
+	g
+}
+
+#[test]
+fn ungraph_test_edge_betweenness() {
+	let g = test_ungraph_bridge();
+	let scores = edge_betweenness(&g);
+	assert!(scores.len() == 7);
+	for (edge, score) in scores {
+		let (s, t) = (*edge.source().key(), *edge.target().key());
+		if (s, t) == (3, 4) {
+			assert!(score == 9.0);
+		} else if s == 3 || t == 3 || s == 4 || t == 4 {
+			assert!(score == 4.0);
+		} else {
+			assert!(score == 1.0);
+		}
+	}
+}
+
+#[test]
+fn digraph_test_edge_betweenness_resume() {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..40 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..40 {
+		g.add_edge(i, (i * 7 + 3) % 40, Empty);
+		g.add_edge(i, (i * i + 1) % 40, Empty);
+	}
+	let expected: HashMap<(usize, usize), f64> = edge_betweenness(&g)
+		.into_iter()
+		.map(|(e, score)| ((*e.source().key(), *e.target().key()), score))
+		.collect();
+	let mut checkpoint = BetweennessCheckpoint::new();
+	let mut runs = 1;
+	let scores = loop {
+		// Every run starts from a checkpoint as if saved by another process.
+		#[cfg(feature = "serde")]
+		{
+			let saved = serde_json::to_string(&checkpoint).unwrap();
+			checkpoint = serde_json::from_str(&saved).unwrap();
+		}
+		match edge_betweenness_resume(&g, &mut checkpoint, 6) {
+			Some(scores) => { break scores }
+			None => { runs += 1 }
+		}
+	};
+	assert!(runs == 7 && checkpoint.finished());
+	assert!(scores.len() == expected.len());
+	for (e, score) in scores {
+		assert!((expected[&(*e.source().key(), *e.target().key())] - score).abs() < 1e-9);
+	}
+	// A checkpoint of another graph is started over.
+	g.add_node(40, Empty);
+	assert!(edge_betweenness_resume(&g, &mut checkpoint, 100).unwrap().len() == expected.len());
+	// A batch of zero still makes progress.
+	let mut checkpoint = BetweennessCheckpoint::new();
+	assert!(edge_betweenness_resume(&g, &mut checkpoint, 0).is_none() && checkpoint.done == 1);
+	// An empty graph is finished after the first run, but not before it.
+	let empty = Digraph::<usize, Empty, Empty>::new();
+	let mut checkpoint = BetweennessCheckpoint::new();
+	assert!(!checkpoint.finished());
+	assert!(edge_betweenness_resume(&empty, &mut checkpoint, 1).unwrap().is_empty() && checkpoint.finished());
+}
+
+#[test]
+fn ungraph_test_girvan_newman() {
+	let g = test_ungraph_bridge();
+	let dendrogram = girvan_newman(&g);
+	assert!(dendrogram[0].len() == 1);
+	let mut split = dendrogram[1].clone();
+	for community in split.iter_mut() {
+		community.sort();
+	}
+	split.sort();
+	assert!(split == vec![vec![1, 2, 3], vec![4, 5, 6]]);
+	assert!(dendrogram.last().unwrap().len() == 6);
+}
+
+#[test]
+fn digraph_test_hits() {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 1..6 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 3, Empty);
+	g.add_edge(1, 4, Empty);
+	g.add_edge(2, 3, Empty);
+	g.add_edge(2, 4, Empty);
+	g.add_edge(5, 3, Empty);
+	let hits = hits(&g, 100, 1e-12);
+	assert!(hits.iterations < 100);
+	let score = |scores: &NodeScores<usize, Empty, Empty>, k: usize| {
+		scores.iter().find(|(n, _)| n.key() == &k).unwrap().1
+	};
+	assert!(score(&hits.authorities, 3) > score(&hits.authorities, 4));
+	assert!(score(&hits.authorities, 1) == 0.0);
+	assert!(score(&hits.hubs, 1) == score(&hits.hubs, 2));
+	assert!(score(&hits.hubs, 1) > score(&hits.hubs, 5));
+	assert!(score(&hits.hubs, 3) == 0.0);
+	let total: f64 = hits.hubs.iter().map(|(_, s)| s).sum();
+	assert!((total - 1.0).abs() < 1e-9);
+}
+
+fn test_digraph_ring() -> Digraph<usize, Empty, Empty> {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..10 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..10 {
+		g.add_edge(i, (i + 1) % 10, Empty);
+	}
+	g.add_edge(0, 5, Empty);
+	g.add_edge(7, 3, Empty);
+	g
+}
+
+#[test]
+fn digraph_test_pagerank() {
+	let g = test_digraph_ring();
+	let scores = pagerank(&g, 0.85, 1000, 1e-12);
+	let total: f64 = scores.iter().map(|(_, s)| s).sum();
+	assert!((total - 1.0).abs() < 1e-9);
+	let score = |k: usize| scores.iter().find(|(n, _)| n.key() == &k).unwrap().1;
+	assert!(score(5) > score(4));
+	assert!(score(3) > score(2));
+}
+
+#[test]
+fn digraph_test_personalized_pagerank() {
+	let g = test_digraph_ring();
+	let exact = personalized_pagerank(&g, &[0, 1], 0.85, 1000, 1e-12).unwrap();
+	let approximate = approximate_personalized_pagerank(&g, &[0, 1], 0.85, 1e-10).unwrap();
+	let total: f64 = exact.iter().map(|(_, s)| s).sum();
+	assert!((total - 1.0).abs() < 1e-9);
+	for (node, score) in exact.iter() {
+		let other = approximate.iter().find(|(n, _)| n == node).map_or(0.0, |(_, s)| *s);
+		assert!((score - other).abs() < 1e-6);
+	}
+	let score = |k: usize| exact.iter().find(|(n, _)| n.key() == &k).unwrap().1;
+	assert!(score(1) > score(9));
+	assert!(personalized_pagerank(&g, &[42], 0.85, 10, 1e-6).is_none());
+	assert!(approximate_personalized_pagerank(&g, &[42], 0.85, 1e-6).is_none());
+}
+
+#[test]
+fn ungraph_test_summarize() {
+	let g = test_ungraph_bridge();
+	let dendrogram = girvan_newman(&g);
+	let summary: Summary<usize, usize, Ungraph<usize, Supernode, f64>> = summarize_partition(&g, &dendrogram[1], |_| 1.0);
+	assert!(summary.graph.node_count() == 2);
+	assert!(summary.graph.edge_count() == 1);
+	for c in 0..2 {
+		let supernode = summary.graph.get_node(c).unwrap().load();
+		assert!(supernode == Supernode { size: 3, internal: 3.0 });
+	}
+	assert!(summary.groups[&1] == summary.groups[&3]);
+	assert!(summary.groups[&1] != summary.groups[&4]);
+	let summary: Summary<usize, bool, Ungraph<bool, Supernode, f64>> = summarize(&g, |n| *n.key() <= 3, |_| 2.0);
+	assert!(summary.graph.get_node(true).unwrap().load().internal == 6.0);
+	let edge = summary.graph.get_edge(true, false).or_else(|| summary.graph.get_edge(false, true));
+	assert!(edge.unwrap().load() == 2.0);
+	// Leaving a node out of the partition leaves out its edges.
+	let summary: Summary<usize, usize, Ungraph<usize, Supernode, f64>> = summarize_partition(&g, &[vec![1, 2, 3], vec![5, 6]], |_| 1.0);
+	assert!(summary.groups.len() == 5);
+	assert!(summary.graph.edge_count() == 0);
+	assert!(summary.graph.get_node(1).unwrap().load().internal == 1.0);
+}
+
+#[test]
+fn ungraph_test_group_by_nodes() {
+	let mut g = test_ungraph_bridge();
+	g.add_edge(6, 6, Empty);
+	let groups = g.group_by_nodes(|n| *n.key() <= 3);
+	let left = groups.groups[&true];
+	let right = groups.groups[&false];
+	assert!(left == GroupStats { nodes: 3, internal: 3, external: 1, min_degree: 2, max_degree: 3 });
+	assert!(right == GroupStats { nodes: 3, internal: 4, external: 1, min_degree: 2, max_degree: 4 });
+	assert!(left.density(false) == 1.0 && left.mean_degree() == 7.0 / 3.0);
+	assert!(groups.between[&(true, false)] == 1 && groups.between[&(false, true)] == 1);
+	assert!(groups.between.len() == 2);
+
+	// Every node in its own group has no internal edges but its loop.
+	let groups = g.group_by_nodes(|n| *n.key());
+	assert!(groups.groups.len() == 6);
+	assert!(groups.groups.values().map(|s| s.internal).sum::<usize>() == 1);
+	assert!(groups.between.values().sum::<usize>() == 2 * 7);
+}
+
+#[test]
+fn digraph_test_cuts() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..20 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..20 {
+		for j in [1, 3, 7] {
+			g.add_edge(i, (i * j + 5) % 20, (i + j) as f64);
+		}
+	}
+	let a: Vec<usize> = (0..8).collect();
+	let b: Vec<usize> = (5..14).collect();
+	let edges: Vec<(usize, usize, f64)> = g
+		.nodes()
+		.iter()
+		.flat_map(|n| n.outbound().iter().map(|e| (*n.key(), *e.target().key(), e.load())).collect::<Vec<_>>())
+		.collect();
+	// Edges between the overlapping sets are only returned once.
+	let mut cut: Vec<(usize, usize)> = g.cut_edges(&a, &b).iter().map(|e| (*e.source().key(), *e.target().key())).collect();
+	cut.sort_unstable();
+	let mut expected: Vec<(usize, usize)> = edges
+		.iter()
+		.filter(|(s, t, _)| (a.contains(s) && b.contains(t)) || (b.contains(s) && a.contains(t)))
+		.map(|(s, t, _)| (*s, *t))
+		.collect();
+	expected.sort_unstable();
+	assert!(cut == expected);
+
+	let crosses = |s: &usize, t: &usize| a.contains(s) != a.contains(t);
+	assert!(g.boundary(&a).len() == edges.iter().filter(|(s, t, _)| crosses(s, t)).count());
+	let size: f64 = edges.iter().filter(|(s, t, _)| crosses(s, t)).map(|e| e.2).sum();
+	assert!(g.cut_size(&a, |e| e.load()) == size);
+	let volume: f64 = edges.iter().map(|(s, t, w)| if a.contains(s) { *w } else { 0.0 } + if a.contains(t) { *w } else { 0.0 }).sum();
+	let total: f64 = 2.0 * edges.iter().map(|e| e.2).sum::<f64>();
+	let conductance = g.conductance(&a, |e| e.load()).unwrap();
+	assert!((conductance - size / volume.min(total - volume)).abs() < 1e-12);
+	assert!(g.conductance(&[], |e| e.load()).is_none());
+}
+
+#[test]
+fn digraph_test_pagerank_update() {
+	let build = || {
+		let mut g = Digraph::<usize, Empty, Empty>::new();
+		for i in 0..100 {
+			g.add_node(i, Empty);
+		}
+		for i in 0..100 {
+			for j in [1, 7, 31] {
+				g.add_edge(i, (i * j + 3) % 100, Empty);
+			}
+		}
+		g
+	};
+	let g = build();
+	let (expected, synchronous) = pagerank_with(&g, 0.85, 500, 1e-12, Update::Synchronous);
+	assert!(synchronous.converged);
+	for seed in [None, Some(1), Some(2)] {
+		let (scores, convergence) = pagerank_with(&g, 0.85, 500, 1e-12, Update::Asynchronous { seed });
+		assert!(convergence.converged && convergence.iterations() <= synchronous.iterations());
+		for ((a, x), (b, y)) in expected.iter().zip(scores.iter()) {
+			assert!(a.key() == b.key() && (x - y).abs() < 1e-9);
+		}
+		// The same seed gives the same iterations, also in a copy of the
+		// graph with its nodes in another order.
+		let (_, again) = pagerank_with(&build(), 0.85, 500, 1e-12, Update::Asynchronous { seed });
+		assert!(again == convergence);
+	}
+}
+
+#[test]
+fn ungraph_test_label_propagation() {
+	// Four cliques of eight nodes joined in a ring by single edges.
+	let mut g = SimpleUngraph::new();
+	for i in 0..32 {
+		g.add_node(i, Empty);
+	}
+	for c in 0..4 {
+		for i in 0..8 {
+			for j in i + 1..8 {
+				g.add_edge(8 * c + i, 8 * c + j, Empty);
+			}
+		}
+		g.add_edge(8 * c, (8 * c + 9) % 32, Empty);
+	}
+	let sorted = |mut communities: Vec<Vec<usize>>| {
+		for c in communities.iter_mut() {
+			c.sort_unstable();
+		}
+		communities.sort();
+		communities
+	};
+	let cliques: Vec<Vec<usize>> = (0..4).map(|c| (8 * c..8 * c + 8).collect()).collect();
+	// Random ties find the cliques, and without them a clique may be taken
+	// over by its neighbor but never split.
+	for seed in [Some(3), Some(4), Some(5)] {
+		let (communities, convergence) = label_propagation(&g, 50, Update::Asynchronous { seed });
+		assert!(convergence.converged);
+		assert!(sorted(communities) == cliques);
+		let (again, _) = label_propagation(&g, 50, Update::Asynchronous { seed });
+		assert!(sorted(again) == cliques);
+	}
+	let (communities, convergence) = label_propagation(&g, 50, Update::Asynchronous { seed: None });
+	assert!(convergence.converged);
+	for clique in cliques.iter() {
+		assert!(communities.iter().any(|c| clique.iter().all(|k| c.contains(k))));
+	}
+	// Two nodes swap their labels forever with synchronous updates.
+	let mut pair = SimpleUngraph::new();
+	pair.add_node(0, Empty);
+	pair.add_node(1, Empty);
+	pair.add_edge(0, 1, Empty);
+	let (communities, convergence) = label_propagation(&pair, 10, Update::Synchronous);
+	assert!(!convergence.converged && convergence.residuals == vec![2.0; 10]);
+	assert!(communities.len() == 2);
+	let (communities, _) = label_propagation(&pair, 10, Update::Asynchronous { seed: None });
+	assert!(communities.len() == 1);
+}
@@ -0,0 +1,137 @@
+#![cfg(feature = "cluster")]
+use fastgraph::cluster::*;
+use fastgraph::collections::*;
+use fastgraph::distributed::*;
+use fastgraph::core::Node;
+use fastgraph::pregel::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use fastgraph::io::Error;
+use std::{
+	io::Write,
+	net::{SocketAddr, TcpListener, TcpStream},
+	sync::Arc,
+	time::Duration,
+};
+
+type SimpleDigraph = Digraph<usize, f64, f64>;
+
+fn random_digraph(size: usize, edges: usize, seed: u64) -> SimpleDigraph {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let mut g = SimpleDigraph::new();
+	for i in 0..size {
+		g.add_node(i, f64::INFINITY);
+	}
+	for _ in 0..edges {
+		g.add_edge(rng.gen_range(0..size), rng.gen_range(0..size), rng.gen_range(1..10) as f64);
+	}
+	g
+}
+
+// Single source shortest paths from node 0 with the distance in the node data.
+fn relax(superstep: usize, node: &Arc<Node<usize, f64, f64>>, messages: Vec<f64>) -> Vec<(usize, f64)> {
+	let start = if superstep == 0 && *node.key() == 0 { 0.0 } else { f64::INFINITY };
+	let best = messages.into_iter().fold(start, f64::min);
+	if best >= node.load() {
+		return Vec::new();
+	}
+	node.store(best);
+	node.outbound().iter().map(|e| (*e.target().key(), best + e.load())).collect()
+}
+
+// Run a worker for every shard on its own thread and gather the results.
+fn run<T>(g: &SimpleDigraph, shards: Vec<Shard<usize, f64, f64>>, transports: Vec<T>, max_supersteps: usize) -> Vec<usize>
+where
+	T: Transport<usize, f64> + Send + 'static,
+{
+	let workers: Vec<_> = shards
+		.into_iter()
+		.zip(transports)
+		.map(|(shard, mut transport)| {
+			std::thread::spawn(move || {
+				let local: SimpleDigraph = shard.graph();
+				let supersteps = pregel_worker(&shard, &local, &mut transport, max_supersteps, relax).unwrap();
+				(shard, local, supersteps)
+			})
+		})
+		.collect();
+	let mut res = Vec::new();
+	for worker in workers {
+		let (shard, local, supersteps) = worker.join().unwrap();
+		gather(g, &shard, &local);
+		res.push(supersteps);
+	}
+	res
+}
+
+fn distances(g: &SimpleDigraph) -> Vec<f64> {
+	(0..g.node_count()).map(|i| g.get_node(i).unwrap().load()).collect()
+}
+
+#[test]
+fn digraph_test_pregel_worker_channels() {
+	let expected = random_digraph(300, 1200, 15);
+	let supersteps = pregel(&expected, 1000, relax);
+	for count in [1, 2, 5] {
+		let g = random_digraph(300, 1200, 15);
+		let shards = split_into(&g, count);
+		let steps = run(&g, shards, channels(count), 1000);
+		assert!(steps.iter().all(|s| *s == supersteps));
+		assert!(distances(&g) == distances(&expected));
+	}
+	// Every worker stops after the same superstep.
+	let g = random_digraph(300, 1200, 15);
+	let steps = run(&g, split_into(&g, 3), channels(3), 2);
+	assert!(steps == vec![2, 2, 2]);
+}
+
+#[test]
+fn digraph_test_pregel_worker_tcp() {
+	let expected = random_digraph(200, 800, 16);
+	pregel(&expected, 1000, relax);
+	let g = random_digraph(200, 800, 16);
+	let count = 3;
+	let listeners: Vec<TcpListener> = (0..count).map(|_| TcpListener::bind("127.0.0.1:0").unwrap()).collect();
+	let addresses: Vec<SocketAddr> = listeners.iter().map(|l| l.local_addr().unwrap()).collect();
+	let connecting: Vec<_> = listeners
+		.into_iter()
+		.enumerate()
+		.map(|(i, listener)| {
+			let addresses = addresses.clone();
+			std::thread::spawn(move || Tcp::connect(i, listener, &addresses, Duration::from_secs(10)).unwrap())
+		})
+		.collect();
+	let transports: Vec<Tcp<usize, f64>> = connecting.into_iter().map(|t| t.join().unwrap()).collect();
+	run(&g, split_into(&g, count), transports, 1000);
+	assert!(distances(&g) == distances(&expected));
+}
+
+#[test]
+fn digraph_test_pregel_worker_disconnected() {
+	// A worker whose peer is gone fails instead of waiting forever.
+	let g = random_digraph(20, 40, 17);
+	let shards = split_into(&g, 2);
+	let mut transports = channels::<usize, f64>(2);
+	drop(transports.pop());
+	let local: SimpleDigraph = shards[0].graph();
+	assert!(pregel_worker(&shards[0], &local, &mut transports[0], 100, relax).is_err());
+}
+
+#[test]
+fn digraph_test_tcp_rejects_large_frames() {
+	// A peer announcing a batch larger than the limit is an error, before
+	// anything is allocated for it.
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+	let peer = std::thread::spawn(move || {
+		let mut stream = TcpStream::connect(address).unwrap();
+		stream.write_all(&1u32.to_le_bytes()).unwrap();
+		stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+		stream
+	});
+	let mut transport: Tcp<usize, f64> = Tcp::connect(0, listener, &[address, address], Duration::from_secs(10)).unwrap();
+	let _stream = peer.join().unwrap();
+	match transport.receive() {
+		Err(Error::Format(message)) => { assert!(message.contains("larger than")) }
+		other => { panic!("{:?}", other) }
+	}
+}
@@ -0,0 +1,90 @@
+use fastgraph::collections::*;
+use fastgraph::core::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::Arc;
+
+#[test]
+fn digraph_test_concurrent_connect() {
+	let mut g = Digraph::<u32, Empty, u32>::new();
+	for i in 0..8 {
+		g.add_node(i, Empty);
+	}
+	let nodes: Arc<Vec<_>> = Arc::new((0..8).map(|i| g.get_node(i).unwrap()).collect());
+
+	// Threads connect and disconnect random pairs of a few nodes, so the
+	// same pairs, reversed pairs and self loops are mutated concurrently.
+	let threads: Vec<_> = (0..8).map(|t| {
+		let nodes = nodes.clone();
+		std::thread::spawn(move || {
+			let mut rng = StdRng::seed_from_u64(t);
+			for i in 0..20_000 {
+				let a = &nodes[rng.gen_range(0..8)];
+				let b = &nodes[rng.gen_range(0..8)];
+				if rng.gen_bool(0.6) {
+					connect(a, b, i);
+				} else {
+					disconnect(a, b);
+				}
+			}
+		})
+	}).collect();
+	for thread in threads {
+		thread.join().unwrap();
+	}
+
+	check(&nodes);
+	assert!(g.edge_count() == nodes.iter().map(|n| n.outbound().len()).sum::<usize>());
+}
+
+#[test]
+fn digraph_test_concurrent_reverse() {
+	let mut g = Digraph::<u32, Empty, u32>::new();
+	for i in 0..8 {
+		g.add_node(i, Empty);
+	}
+	let nodes: Arc<Vec<_>> = Arc::new((0..8).map(|i| g.get_node(i).unwrap()).collect());
+
+	// Reversals take the outbound locks of both nodes, so they race with
+	// connects and disconnects of the same pairs both ways.
+	let threads: Vec<_> = (0..8).map(|t| {
+		let nodes = nodes.clone();
+		std::thread::spawn(move || {
+			let mut rng = StdRng::seed_from_u64(t);
+			for i in 0..20_000 {
+				let a = &nodes[rng.gen_range(0..8)];
+				let b = &nodes[rng.gen_range(0..8)];
+				match rng.gen_range(0..4) {
+					0 => { connect(a, b, i); }
+					1 => { disconnect(a, b); }
+					2 => { reverse(a, b); }
+					_ => {
+						if let Some(edge) = a.find_outbound(b) {
+							edge.reverse();
+						}
+					}
+				}
+			}
+		})
+	}).collect();
+	for thread in threads {
+		thread.join().unwrap();
+	}
+	check(&nodes);
+}
+
+// Every outbound edge is unique and has exactly one inbound reference.
+fn check(nodes: &[Arc<Node<u32, Empty, u32>>]) {
+	let mut edges = 0;
+	for node in nodes.iter() {
+		let outbound = node.outbound();
+		for (i, edge) in outbound.iter().enumerate() {
+			assert!(outbound[..i].iter().all(|e| e.target() != edge.target()));
+			let target = edge.target();
+			let refs = target.inbound().iter().filter(|e| e.as_ptr() == Arc::as_ptr(edge)).count();
+			assert!(refs == 1);
+		}
+		edges += outbound.len();
+	}
+	let inbound: usize = nodes.iter().map(|n| n.inbound().len()).sum();
+	assert!(inbound == edges);
+}
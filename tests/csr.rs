@@ -0,0 +1,139 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use rand::Rng;
+use std::collections::HashMap;
+
+// Component labels by sequential search from every unlabeled node.
+fn components(csr: &fastgraph::csr::Csr<usize, Empty, Empty>) -> Vec<usize> {
+	let mut undirected = vec![Vec::new(); csr.node_count()];
+	for u in 0..csr.node_count() {
+		for w in csr.neighbors(u) {
+			undirected[u].push(*w);
+			undirected[*w].push(u);
+		}
+	}
+	let mut labels = vec![usize::MAX; csr.node_count()];
+	for s in 0..csr.node_count() {
+		if labels[s] != usize::MAX {
+			continue;
+		}
+		let mut stack = vec![s];
+		labels[s] = s;
+		while let Some(v) = stack.pop() {
+			for w in undirected[v].iter() {
+				if labels[*w] == usize::MAX {
+					labels[*w] = s;
+					stack.push(*w);
+				}
+			}
+		}
+	}
+	labels
+}
+
+// True if both labelings put the same nodes together.
+fn same_partition(a: &[usize], b: &[usize]) -> bool {
+	let mut map = HashMap::new();
+	a.iter().zip(b.iter()).all(|(x, y)| *map.entry(*x).or_insert(*y) == *y)
+		&& map.len() == b.iter().collect::<std::collections::HashSet<_>>().len()
+}
+
+#[test]
+fn ungraph_test_freeze() {
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, Empty);
+	g.add_edge(1, 2, Empty);
+	g.add_edge(2, 2, Empty);
+	let csr = g.freeze();
+	assert!(!csr.directed());
+	assert!(csr.node_count() == 4);
+	assert!(csr.edge_count() == 3);
+	let one = csr.index(&1).unwrap();
+	assert!(csr.neighbors(one).len() == 2);
+	assert!(csr.neighbors(csr.index(&2).unwrap()).len() == 2);
+	assert!(csr.neighbors(csr.index(&3).unwrap()).is_empty());
+	for (w, e) in csr.neighbors(one).iter().zip(csr.edge_ids(one).iter()) {
+		let edge = csr.edge(*e);
+		assert!(edge.source() == *csr.node(*w) || edge.target() == *csr.node(*w));
+	}
+	assert!(csr.index(&42).is_none());
+}
+
+#[test]
+fn ungraph_test_par_connected_components() {
+	let mut rng = rand::thread_rng();
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..2000 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..1500 {
+		g.add_edge(rng.gen_range(0..2000), rng.gen_range(0..2000), Empty);
+	}
+	let csr = g.freeze();
+	let labels = csr.par_connected_components();
+	assert!(same_partition(&labels, &components(&csr)));
+	// Every label is the smallest index in its component.
+	for (i, label) in labels.iter().enumerate() {
+		assert!(*label <= i);
+		assert!(labels[*label] == *label);
+	}
+}
+
+#[test]
+fn digraph_test_par_connected_components() {
+	let mut rng = rand::thread_rng();
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..2000 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..1500 {
+		g.add_edge(rng.gen_range(0..2000), rng.gen_range(0..2000), Empty);
+	}
+	let csr = g.freeze();
+	assert!(csr.directed());
+	assert!(same_partition(&csr.par_connected_components(), &components(&csr)));
+}
+
+#[test]
+fn digraph_test_par_pagerank() {
+	let mut rng = rand::thread_rng();
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..300 {
+		g.add_node(i, Empty);
+	}
+	// Nodes from 250 on have no outbound edges.
+	for _ in 0..2000 {
+		g.add_edge(rng.gen_range(0..250), rng.gen_range(0..300), Empty);
+	}
+	let csr = g.freeze();
+	let (scores, convergence) = csr.par_pagerank(0.85, 200, 1e-12);
+	assert!(convergence.converged && convergence.iterations() < 200);
+	assert!(*convergence.residuals.last().unwrap() < 1e-12);
+	assert!(convergence.residuals.windows(2).all(|w| w[1] <= w[0] * 1.01));
+	assert!((scores.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+	for (node, score) in fastgraph::centrality::pagerank(&g, 0.85, 200, 1e-12) {
+		assert!((scores[csr.index(node.key()).unwrap()] - score).abs() < 1e-9);
+	}
+	let (_, convergence) = csr.par_pagerank(0.85, 3, 0.0);
+	assert!(!convergence.converged && convergence.iterations() == 3);
+}
+
+#[test]
+fn ungraph_test_par_pagerank() {
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..5 {
+		g.add_node(i, Empty);
+	}
+	for i in 1..5 {
+		g.add_edge(0, i, Empty);
+	}
+	let csr = g.freeze();
+	let (scores, _) = csr.par_pagerank(0.85, 100, 1e-12);
+	for (node, score) in fastgraph::centrality::pagerank(&g, 0.85, 100, 1e-12) {
+		assert!((scores[csr.index(node.key()).unwrap()] - score).abs() < 1e-9);
+	}
+	assert!(scores[csr.index(&0).unwrap()] > scores[csr.index(&1).unwrap()]);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "csv")]
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::io::{csv::*, Error};
+
+#[test]
+fn digraph_test_csv_round_trip() {
+	let nodes = "id,name\n1,a\n2,b\n3,c\n";
+	let edges = "from,to,weight\n1,2,0.5\n2,3,1.5\n3,1,2\n";
+	let g: Digraph<u32, String, f64> = read_csv(
+		nodes.as_bytes(),
+		edges.as_bytes(),
+		|r| Ok((r.parse("id")?, r.parse("name")?)),
+		|r| Ok((r.parse("from")?, r.parse("to")?, r.parse("weight")?)),
+	).unwrap();
+	assert!(g.node_count() == 3);
+	assert!(g.edge_count() == 3);
+	assert!(g.get_node(2).unwrap().load() == "b");
+	assert!(g.get_edge(3, 1).unwrap().load() == 2.0);
+
+	let mut node_out = Vec::new();
+	let mut edge_out = Vec::new();
+	write_csv(
+		&g,
+		&mut node_out,
+		&mut edge_out,
+		(&["id", "name"], &["from", "to", "weight"]),
+		|k, n| vec![k.to_string(), n.clone()],
+		|s, t, e| vec![s.to_string(), t.to_string(), e.to_string()],
+	).unwrap();
+	let h: Digraph<u32, String, f64> = read_csv(
+		node_out.as_slice(),
+		edge_out.as_slice(),
+		|r| Ok((r.parse("id")?, r.parse("name")?)),
+		|r| Ok((r.parse("from")?, r.parse("to")?, r.parse("weight")?)),
+	).unwrap();
+	assert!(h.node_count() == 3);
+	assert!(h.get_edge(1, 2).unwrap().load() == 0.5);
+
+	let bad = "from,to\n1,4\n";
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_csv(
+		"id\n1\n".as_bytes(),
+		bad.as_bytes(),
+		|r| Ok((r.parse("id")?, Empty)),
+		|r| Ok((r.parse("from")?, r.parse("to")?, Empty)),
+	);
+	assert!(matches!(res, Err(Error::UnknownNode { line: 2, .. })));
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_csv(
+		"id\nx\n".as_bytes(),
+		"from,to\n".as_bytes(),
+		|r| Ok((r.parse("id")?, Empty)),
+		|r| Ok((r.parse("from")?, r.parse("to")?, Empty)),
+	);
+	assert!(matches!(res, Err(Error::Record { line: 2, .. })));
+}
@@ -0,0 +1,105 @@
+use fastgraph::collections::*;
+use fastgraph::core::Direction;
+use fastgraph::distributed::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashSet;
+
+fn random_digraph(size: usize, edges: usize, rng: &mut StdRng) -> Digraph<usize, usize, f64> {
+	let mut g = Digraph::<usize, usize, f64>::new();
+	for i in 0..size {
+		g.add_node(i, i);
+	}
+	for _ in 0..edges {
+		g.add_edge(rng.gen_range(0..size), rng.gen_range(0..size), rng.gen_range(1..10) as f64);
+	}
+	g
+}
+
+// Source, target and data of every edge of the graph.
+fn edges<G: Graph<usize, usize, f64>>(g: &G) -> HashSet<(usize, usize, u64)> {
+	let mut res = HashSet::new();
+	for n in g.nodes() {
+		for e in n.outbound().iter() {
+			res.insert((*e.source().key(), *e.target().key(), e.load().to_bits()));
+		}
+	}
+	res
+}
+
+#[test]
+fn digraph_test_split_and_merge() {
+	let mut rng = StdRng::seed_from_u64(13);
+	let g = random_digraph(200, 800, &mut rng);
+	let shards = split_into(&g, 4);
+	assert!(shards.len() == 4);
+	let mut owned = HashSet::new();
+	for shard in shards.iter() {
+		assert!(shard.count == 4 && shard.directed);
+		let owners = shard.owners();
+		for (key, data) in shard.nodes.iter() {
+			assert!(owned.insert(*key) && key == data);
+		}
+		// Every edge has an owned endpoint and every ghost is owned elsewhere.
+		for (s, t, _) in shard.edges.iter() {
+			assert!(owners[s] == shard.index || owners[t] == shard.index);
+		}
+		for (key, _, owner) in shard.ghosts.iter() {
+			assert!(*owner != shard.index && shards[*owner].nodes.iter().any(|(k, _)| k == key));
+		}
+		// The local graph has every edge of its owned nodes.
+		let local: Digraph<usize, usize, f64> = shard.graph();
+		for (key, _) in shard.nodes.iter() {
+			let node = g.get_node(*key).unwrap();
+			let copy = local.get_node(*key).unwrap();
+			assert!(node.outbound().len() == copy.outbound().len());
+			assert!(node.inbound().len() == copy.inbound().len());
+		}
+	}
+	assert!(owned.len() == 200);
+	let h: Digraph<usize, usize, f64> = merge(&shards);
+	assert!(h.node_count() == 200);
+	assert!(edges(&h) == edges(&g));
+}
+
+#[test]
+fn ungraph_test_split_and_gather() {
+	let mut g = Ungraph::<usize, usize, f64>::new();
+	for i in 0..10 {
+		g.add_node(i, 0);
+	}
+	for i in 0..10 {
+		g.add_edge(i, (i + 1) % 10, 1.0);
+	}
+	// Odd and even nodes, so that every edge crosses between the shards.
+	let shards = split(&g, &[(0..10).step_by(2).collect(), (1..10).step_by(2).chain([0, 42]).collect()]);
+	assert!(shards[1].nodes.len() == 5);
+	for shard in shards.iter() {
+		assert!(shard.ghosts.len() == 5 && shard.edges.len() == 10);
+		let local: Ungraph<usize, usize, f64> = shard.graph();
+		for node in local.nodes() {
+			node.store(node.neighbors(Direction::Both).len());
+		}
+		assert!(gather(&g, shard, &local) == 5);
+	}
+	assert!(g.nodes().iter().all(|n| n.load() == 2));
+	let h: Ungraph<usize, usize, f64> = merge(&shards);
+	assert!(edges(&h) == edges(&g));
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn digraph_test_shard_serialization() {
+	let mut rng = StdRng::seed_from_u64(14);
+	let g = random_digraph(50, 150, &mut rng);
+	let mut shards = Vec::new();
+	for shard in split_into(&g, 3) {
+		let mut buffer = Vec::new();
+		shard.write(&mut buffer).unwrap();
+		let read = Shard::<usize, usize, f64>::read(buffer.as_slice()).unwrap();
+		assert!(read == shard);
+		shards.push(read);
+	}
+	let h: Digraph<usize, usize, f64> = merge(&shards);
+	assert!(edges(&h) == edges(&g));
+	assert!(Shard::<usize, usize, f64>::read("{".as_bytes()).is_err());
+}
@@ -0,0 +1,138 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Direction, Empty};
+use fastgraph::embedding::*;
+use fastgraph::typed::*;
+
+fn test_ungraph_grid() -> Ungraph<usize, Empty, Empty> {
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..16 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..16 {
+		if i % 4 != 3 {
+			g.add_edge(i, i + 1, Empty);
+		}
+		if i < 12 {
+			g.add_edge(i, i + 4, Empty);
+		}
+	}
+	g
+}
+
+#[test]
+fn ungraph_test_node2vec_walks() {
+	let g = test_ungraph_grid();
+	let config = Node2Vec { walk_length: 20, walks_per_node: 3, p: 0.5, q: 2.0, seed: 7 };
+	let walks = config.walks(&g);
+	assert!(walks.len() == 48);
+	for walk in walks.iter() {
+		assert!(walk.len() == 20);
+		for pair in walk.windows(2) {
+			assert!(g.get_edge(pair[0], pair[1]).is_some() || g.get_edge(pair[1], pair[0]).is_some());
+		}
+	}
+	assert!(walks == config.walks(&g));
+	// The walks don't depend on the order the nodes were added in.
+	let mut h = Ungraph::<usize, Empty, Empty>::new();
+	for i in (0..16).rev() {
+		h.add_node(i, Empty);
+	}
+	for node in g.nodes() {
+		for edge in node.outbound().iter() {
+			h.add_edge(*edge.source().key(), *edge.target().key(), Empty);
+		}
+	}
+	assert!(walks == config.walks(&h));
+	// A walk has at least its start node.
+	let config = Node2Vec { walk_length: 0, walks_per_node: 1, ..config };
+	assert!(config.walks(&g).iter().all(|walk| walk.len() == 1));
+	let config = Node2Vec { walk_length: 20, walks_per_node: 3, ..config };
+
+	let path = std::env::temp_dir().join("fastgraph_node2vec_walks.txt");
+	config.write_walks(&g, &path).unwrap();
+	let text = std::fs::read_to_string(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert!(text.lines().count() == 48);
+	assert!(text.lines().next().unwrap().split(' ').count() == 20);
+}
+
+#[test]
+fn ungraph_test_node2vec_walks_resume() {
+	let g = test_ungraph_grid();
+	let config = Node2Vec { walk_length: 10, walks_per_node: 2, p: 2.0, q: 0.5, seed: 11 };
+	let mut checkpoint = fastgraph::checkpoint::Checkpoint::new();
+	let mut runs = 1;
+	let walks = loop {
+		#[cfg(feature = "serde")]
+		{
+			let saved = serde_json::to_string(&checkpoint).unwrap();
+			checkpoint = serde_json::from_str(&saved).unwrap();
+		}
+		match config.walks_resume(&g, &mut checkpoint, 5) {
+			Some(walks) => { break walks }
+			None => { runs += 1 }
+		}
+	};
+	assert!(runs == 7 && checkpoint.finished());
+	assert!(walks == config.walks(&g));
+	// A finished checkpoint is started over.
+	assert!(config.walks_resume(&g, &mut checkpoint, 0).is_none() && checkpoint.values.len() == 1);
+}
+
+#[test]
+fn digraph_test_metapath2vec_walks() {
+	// Authors writing papers in venues.
+	let mut g = Digraph::<usize, Typed<char, Empty>, Typed<char, Empty>>::new();
+	for i in 0..30 {
+		let tag = match i % 3 {
+			0 => { 'a' }
+			1 => { 'p' }
+			_ => { 'v' }
+		};
+		g.add_node(i, Typed::new(tag, Empty));
+	}
+	for i in 0..10 {
+		let paper = i * 3 + 1;
+		g.add_edge(i * 3, paper, Typed::new('w', Empty));
+		g.add_edge((i * 3 + 6) % 30, paper, Typed::new('w', Empty));
+		g.add_edge(paper, (i % 2) * 3 + 2, Typed::new('i', Empty));
+	}
+	// Author, paper, venue, paper, author.
+	let metapath = Metapath::new(Some('a'))
+		.step(Direction::Outbound, Some('w'), Some('p'))
+		.step(Direction::Outbound, Some('i'), Some('v'))
+		.step(Direction::Inbound, Some('i'), Some('p'))
+		.step(Direction::Inbound, Some('w'), Some('a'));
+	let config = Metapath2Vec { walk_length: 17, walks_per_node: 4, seed: 3, ..Metapath2Vec::new(metapath) };
+	let walks = config.walks(&g);
+	assert!(walks.len() == 40);
+	let tags = ['a', 'p', 'v', 'p'];
+	for walk in walks.iter() {
+		assert!(walk.len() == 17);
+		for (i, pair) in walk.windows(2).enumerate() {
+			let tag = |k: usize| g.get_node(k).unwrap().load().tag;
+			assert!(tag(pair[0]) == tags[i % 4] && tag(pair[1]) == tags[(i + 1) % 4]);
+			assert!(g.get_edge(pair[0], pair[1]).is_some() || g.get_edge(pair[1], pair[0]).is_some());
+		}
+	}
+	assert!(walks == config.walks(&g));
+	// The walks don't depend on the order the nodes were added in.
+	let mut h = Digraph::<usize, Typed<char, Empty>, Typed<char, Empty>>::new();
+	for i in (0..30).rev() {
+		h.add_node(i, g.get_node(i).unwrap().load());
+	}
+	for i in 0..30 {
+		for edge in g.get_node(i).unwrap().outbound().iter() {
+			h.add_edge(i, *edge.target().key(), edge.load());
+		}
+	}
+	assert!(walks == config.walks(&h));
+	let mut checkpoint = fastgraph::checkpoint::Checkpoint::new();
+	assert!(config.walks_resume(&g, &mut checkpoint, 25).is_none());
+	assert!(config.walks_resume(&g, &mut checkpoint, 25).unwrap() == walks);
+	let short = Metapath2Vec { walk_length: 0, ..config.clone() };
+	assert!(short.walks(&g).iter().all(|walk| walk.len() == 1));
+	// Walks from a node without a matching edge end where they start.
+	g.add_node(30, Typed::new('a', Empty));
+	assert!(config.walks(&g).iter().filter(|walk| walk.len() == 1).count() == 4);
+}
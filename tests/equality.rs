@@ -0,0 +1,52 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+
+fn cycle<G: Graph<u32, u32, u32>>(order: &[u32]) -> G {
+	let mut g = G::new();
+	for i in order {
+		g.add_node(*i, i * 10);
+	}
+	for i in order {
+		g.add_edge(*i, (i + 1) % 5, *i);
+	}
+	g
+}
+
+#[test]
+fn digraph_test_structural_eq() {
+	let a: Digraph<u32, u32, u32> = cycle(&[0, 1, 2, 3, 4]);
+	let b: Digraph<u32, u32, u32> = cycle(&[4, 2, 0, 3, 1]);
+	assert!(a.structural_eq(&b));
+	assert!(a.structural_hash() == b.structural_hash());
+
+	b.get_edge(3, 4).unwrap().store(99);
+	assert!(!a.structural_eq(&b));
+	assert!(a.structural_hash() != b.structural_hash());
+	b.get_edge(3, 4).unwrap().store(3);
+	b.get_node(2).unwrap().store(0);
+	assert!(!a.structural_eq(&b));
+	assert!(a.structural_hash() != b.structural_hash());
+
+	// Reversing an edge changes a directed graph but not an undirected one.
+	let mut c = Digraph::<u32, Empty, Empty>::new();
+	let mut d = Digraph::<u32, Empty, Empty>::new();
+	let mut e = Ungraph::<u32, Empty, Empty>::new();
+	let mut f = Ungraph::<u32, Empty, Empty>::new();
+	c.add_node(1, Empty);
+	c.add_node(2, Empty);
+	d.add_node(1, Empty);
+	d.add_node(2, Empty);
+	e.add_node(1, Empty);
+	e.add_node(2, Empty);
+	f.add_node(1, Empty);
+	f.add_node(2, Empty);
+	c.add_edge(1, 2, Empty);
+	d.add_edge(2, 1, Empty);
+	e.add_edge(1, 2, Empty);
+	f.add_edge(2, 1, Empty);
+	assert!(!c.structural_eq(&d));
+	assert!(c.structural_hash() != d.structural_hash());
+	assert!(e.structural_eq(&f));
+	assert!(e.structural_hash() == f.structural_hash());
+	assert!(!c.structural_eq(&e));
+}
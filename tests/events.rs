@@ -0,0 +1,101 @@
+use fastgraph::collections::*;
+use fastgraph::events::Event;
+
+#[test]
+fn digraph_test_subscribe() {
+	let mut g = Digraph::<u32, u32, f64>::new();
+	g.add_node(0, 0);
+	let feed = g.subscribe();
+
+	// Mirror the graph from the feed on another thread.
+	let mirror = std::thread::spawn(move || {
+		let mut h = Digraph::<u32, u32, f64>::new();
+		h.add_node(0, 0);
+		for event in feed.iter() {
+			match event {
+				Event::AddNode { key, data } => { h.add_node(key, data); }
+				Event::AddEdge { source, target, data } => { h.add_edge(source, target, data); }
+				Event::DelEdge { source, target } => { h.del_edge(source, target); }
+				Event::DelNode { key } => { h.del_node(key); }
+			}
+		}
+		h
+	});
+	for i in 1..100 {
+		g.add_node(i, i * 10);
+		g.add_edge(i - 1, i, i as f64);
+	}
+	g.del_edge(10, 11);
+	drop(g.subscribe());
+	let events = g.subscribe();
+	// Nothing is sent for mutations that don't change the graph.
+	g.add_node(5, 0);
+	g.add_edge(1, 2, 0.0);
+	g.del_edge(10, 11);
+	assert!(events.try_recv().is_err());
+	g.add_edge(10, 11, 11.0);
+	assert!(events.try_recv().unwrap() == Event::AddEdge { source: 10, target: 11, data: 11.0 });
+	let g_edges = g.edge_count();
+	drop(g);
+
+	let h = mirror.join().unwrap();
+	assert!(h.node_count() == 100);
+	assert!(h.edge_count() == g_edges);
+	assert!(h.get_node(42).unwrap().load() == 420);
+	assert!(h.get_edge(10, 11).unwrap().load() == 11.0);
+}
+
+#[test]
+fn digraph_test_del_node_events() {
+	let mut g = Digraph::<u32, u32, f64>::new();
+	for i in 0..4 {
+		g.add_node(i, i);
+	}
+	g.add_edge(0, 1, 0.0);
+	g.add_edge(2, 0, 0.0);
+	g.add_edge(0, 0, 0.0);
+	g.add_edge(2, 3, 0.0);
+	let events = g.subscribe();
+	assert!(g.del_node(0));
+	let sent: Vec<_> = events.try_iter().collect();
+	assert!(sent.len() == 4);
+	assert!(sent.contains(&Event::DelEdge { source: 0, target: 1 }));
+	assert!(sent.contains(&Event::DelEdge { source: 2, target: 0 }));
+	assert!(sent.contains(&Event::DelEdge { source: 0, target: 0 }));
+	assert!(sent[3] == Event::DelNode { key: 0 });
+	assert!(g.clear() == 3);
+	let sent: Vec<_> = events.try_iter().collect();
+	assert!(sent[0] == Event::DelEdge { source: 2, target: 3 });
+	assert!(sent[1..].iter().all(|e| matches!(e, Event::DelNode { .. })));
+}
+
+#[test]
+fn ungraph_test_del_node_events() {
+	let mut g = Ungraph::<u32, u32, f64>::new();
+	for i in 0..3 {
+		g.add_node(i, i);
+	}
+	g.add_edge(0, 1, 0.0);
+	g.add_edge(2, 0, 0.0);
+	let feed = g.subscribe();
+	let mut h = Ungraph::<u32, u32, f64>::new();
+	for i in 0..3 {
+		h.add_node(i, i);
+	}
+	h.add_edge(0, 1, 0.0);
+	h.add_edge(2, 0, 0.0);
+	g.retain_nodes(|n| n.load() != 0);
+	for event in feed.try_iter() {
+		match event {
+			Event::DelEdge { source, target } => { assert!(h.del_edge(source, target)); }
+			Event::DelNode { key } => {
+				assert!(h.get_node(key).unwrap().outbound().is_empty());
+				assert!(h.get_node(key).unwrap().inbound().is_empty());
+				h.del_node(key);
+			}
+			_ => {}
+		}
+	}
+	assert!(h.node_count() == 2);
+	assert!(h.edge_count() == 0);
+}
@@ -0,0 +1,268 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Empty, Path};
+use fastgraph::flow::*;
+use rand::Rng;
+
+// Assign every worker 0..n to a job n..2n through a source 2n and a target
+// 2n + 1.
+fn assignment(costs: &[Vec<f64>]) -> Digraph<usize, Empty, f64> {
+	let n = costs.len();
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..2 * n + 2 {
+		g.add_node(i, Empty);
+	}
+	for (worker, row) in costs.iter().enumerate() {
+		g.add_edge(2 * n, worker, 0.0);
+		g.add_edge(n + worker, 2 * n + 1, 0.0);
+		for (job, cost) in row.iter().enumerate() {
+			g.add_edge(worker, n + job, *cost);
+		}
+	}
+	g
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+	if n == 0 {
+		return vec![Vec::new()];
+	}
+	let mut res = Vec::new();
+	for p in permutations(n - 1) {
+		for i in 0..n {
+			let mut q = p.clone();
+			q.insert(i, n - 1);
+			res.push(q);
+		}
+	}
+	res
+}
+
+#[test]
+fn digraph_test_min_cost_assignment() {
+	let mut rng = rand::thread_rng();
+	for _ in 0..20 {
+		let n = 5;
+		let costs: Vec<Vec<f64>> = (0..n).map(|_| (0..n).map(|_| rng.gen_range(-5..20) as f64).collect()).collect();
+		let g = assignment(&costs);
+		let flow = min_cost_max_flow(&g, &(2 * n), &(2 * n + 1), |e| Capacity::new(1, e.load())).unwrap();
+		let best = permutations(n)
+			.iter()
+			.map(|p| p.iter().enumerate().map(|(w, j)| costs[w][*j]).sum::<f64>())
+			.fold(f64::INFINITY, f64::min);
+		assert!(flow.value == n as i64);
+		assert!(flow.cost == best);
+		for (edge, f) in flow.edges.iter() {
+			assert!(*f == 0 || *f == 1);
+			if *edge.source().key() < n {
+				assert!(flow.edges.iter().filter(|(e, f)| e.source() == edge.source() && *f == 1).count() == 1);
+			}
+		}
+	}
+}
+
+#[test]
+fn digraph_test_min_cost_flow_lower_bounds() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..5 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 4, 1.0);
+	g.add_edge(1, 3, 5.0);
+	g.add_edge(3, 4, 5.0);
+	g.add_edge(2, 3, 0.0);
+	// At least two units have to pass through 2 -> 3 and at most three fit
+	// through 1 -> 2.
+	let capacity = |e: &std::sync::Arc<fastgraph::core::Edge<usize, Empty, f64>>| {
+		match (*e.source().key(), *e.target().key()) {
+			(2, 3) => Capacity { lower: 2, upper: 2, cost: 0.0 },
+			(1, 2) => Capacity::new(3, e.load()),
+			_ => Capacity::new(10, e.load()),
+		}
+	};
+	let flow = min_cost_max_flow(&g, &1, &4, capacity).unwrap();
+	assert!(flow.value == 11);
+	for (edge, f) in flow.edges.iter() {
+		match (*edge.source().key(), *edge.target().key()) {
+			(2, 3) => { assert!(*f == 2) }
+			(2, 4) => { assert!(*f == 1) }
+			(3, 4) => { assert!(*f == 10) }
+			(1, 3) => { assert!(*f == 8) }
+			_ => { assert!(*f == 3) }
+		}
+	}
+	assert!(flow.cost == 3.0 + 1.0 + 40.0 + 50.0);
+
+	// The forced flow can't leave node 3.
+	let infeasible = |e: &std::sync::Arc<fastgraph::core::Edge<usize, Empty, f64>>| {
+		match (*e.source().key(), *e.target().key()) {
+			(2, 3) => Capacity { lower: 2, upper: 2, cost: 0.0 },
+			(3, 4) => Capacity::new(1, e.load()),
+			_ => Capacity::new(10, e.load()),
+		}
+	};
+	assert!(min_cost_max_flow(&g, &1, &4, infeasible).is_none());
+	assert!(min_cost_max_flow(&g, &1, &42, |e| Capacity::new(1, e.load())).is_none());
+}
+
+#[test]
+fn digraph_test_min_cost_flow_overflow() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..5 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 4, 1.0);
+	g.add_edge(1, 3, 2.0);
+	g.add_edge(3, 4, 2.0);
+	// Capacities that stand for no limit saturate the value of the flow.
+	let flow = min_cost_max_flow(&g, &1, &4, |e| Capacity::new(i64::MAX, e.load())).unwrap();
+	assert!(flow.value == i64::MAX);
+	let forced = |e: &std::sync::Arc<fastgraph::core::Edge<usize, Empty, f64>>| {
+		match (*e.source().key(), *e.target().key()) {
+			(1, 2) => Capacity { lower: i64::MAX, upper: i64::MAX, cost: 1.0 },
+			_ => Capacity::new(i64::MAX, e.load()),
+		}
+	};
+	assert!(min_cost_max_flow(&g, &1, &4, forced).unwrap().value == i64::MAX);
+	// Negative costs force more flow into 4 than an i64 holds.
+	let negative = |_: &std::sync::Arc<fastgraph::core::Edge<usize, Empty, f64>>| Capacity::new(i64::MAX, -1.0);
+	assert!(min_cost_max_flow(&g, &1, &4, negative).is_none());
+	assert!(min_cost_circulation(&g, negative).is_none());
+}
+
+#[test]
+fn digraph_test_min_cost_circulation() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..5 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 3, 1.0);
+	g.add_edge(3, 1, 1.0);
+	g.add_edge(3, 4, 2.0);
+	g.add_edge(4, 1, 2.0);
+	// Four units are forced through 1 -> 2 and return the cheapest way as
+	// far as 3 -> 1 allows.
+	let flow = min_cost_circulation(&g, |e| {
+		match (*e.source().key(), *e.target().key()) {
+			(1, 2) => Capacity { lower: 4, upper: 4, cost: e.load() },
+			(3, 1) => Capacity::new(3, e.load()),
+			_ => Capacity::new(10, e.load()),
+		}
+	}).unwrap();
+	assert!(flow.value == 0);
+	assert!(flow.cost == 4.0 + 4.0 + 3.0 + 2.0 + 2.0);
+	assert!(min_cost_circulation(&g, |e| Capacity { lower: 1, upper: 1, cost: e.load() }).is_none());
+}
+
+// Check that the paths lead from the source to the target and share no edges
+// and, unless allowed, no inner nodes.
+fn check_disjoint(paths: &[Path<usize, Empty, f64>], s: usize, t: usize, nodes: bool) {
+	let mut edges = std::collections::HashSet::new();
+	let mut inner = std::collections::HashSet::new();
+	for path in paths.iter() {
+		let mut v = s;
+		for edge in path.iter().map(|e| e.upgrade().unwrap()) {
+			let (a, b) = (*edge.source().key(), *edge.target().key());
+			assert!(v == a || v == b);
+			v = if v == a { b } else { a };
+			assert!(edges.insert((a, b)));
+			if v != t && nodes {
+				assert!(inner.insert(v));
+			}
+		}
+		assert!(v == t);
+	}
+}
+
+#[test]
+fn ungraph_test_disjoint_paths() {
+	// A 5 by 5 grid where the corner 0 has two neighbors and the center 12
+	// has four.
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..25 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..25 {
+		if i % 5 < 4 {
+			g.add_edge(i, i + 1, 1.0);
+		}
+		if i < 20 {
+			g.add_edge(i, i + 5, 1.0);
+		}
+	}
+	for mode in [Disjoint::Edges, Disjoint::Nodes] {
+		let paths = disjoint_paths(&g, &0, &12, 10, mode);
+		assert!(paths.len() == 2);
+		check_disjoint(&paths, 0, 12, mode == Disjoint::Nodes);
+		let paths = disjoint_paths(&g, &6, &18, 10, mode);
+		assert!(paths.len() == 4);
+		check_disjoint(&paths, 6, 18, mode == Disjoint::Nodes);
+		// No limit finds as many paths as there are.
+		let paths = disjoint_paths(&g, &6, &18, usize::MAX, mode);
+		assert!(paths.len() == 4);
+		check_disjoint(&paths, 6, 18, mode == Disjoint::Nodes);
+	}
+	// A node where two halves of a bowtie meet is a cut node but not a cut
+	// edge.
+	let mut bowtie = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..5 {
+		bowtie.add_node(i, Empty);
+	}
+	for (s, t) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)] {
+		bowtie.add_edge(s, t, 1.0);
+	}
+	assert!(disjoint_paths(&bowtie, &0, &4, 10, Disjoint::Edges).len() == 2);
+	assert!(disjoint_paths(&bowtie, &0, &4, 10, Disjoint::Nodes).len() == 1);
+	assert!(disjoint_paths(&bowtie, &0, &0, 10, Disjoint::Edges).is_empty());
+	assert!(disjoint_paths(&bowtie, &0, &9, 10, Disjoint::Edges).is_empty());
+}
+
+#[test]
+fn digraph_test_residual_max_flow() {
+	let mut rng = rand::thread_rng();
+	for _ in 0..20 {
+		let mut g = Digraph::<usize, Empty, i64>::new();
+		for i in 0..12 {
+			g.add_node(i, Empty);
+		}
+		for _ in 0..40 {
+			g.add_edge(rng.gen_range(0..12), rng.gen_range(0..12), rng.gen_range(0..10));
+		}
+		let edges = g.edge_count();
+		let flow = max_flow(&g, &0, &11, |e| e.load()).unwrap();
+		let expected = min_cost_max_flow(&g, &0, &11, |e| Capacity::new(e.load(), 0.0)).unwrap();
+		assert!(flow.value == expected.value);
+		// The flow respects the capacities and is conserved at every node
+		// other than the source and the target.
+		let mut net = [0; 12];
+		for (e, f) in flow.edges.iter() {
+			assert!(*f >= 0 && *f <= e.load());
+			net[*e.source().key()] -= f;
+			net[*e.target().key()] += f;
+		}
+		assert!(net[11] == flow.value && net[0] == -flow.value);
+		assert!(net[1..11].iter().all(|x| *x == 0));
+
+		// The nodes reachable in the residual graph are a cut as large as
+		// the flow, and the graph is left untouched.
+		let mut residual = Residual::new(&g, |e| e.load());
+		assert!(residual.max_flow(&0, &11) == flow.value);
+		assert!(residual.max_flow(&0, &11) == 0);
+		let side = residual.reachable(&0);
+		assert!(side.contains(&0) && !side.contains(&11));
+		let mut cut = 0;
+		for node in g.nodes() {
+			for e in node.outbound().iter() {
+				if side.contains(node.key()) && !side.contains(e.target().key()) {
+					cut += e.load();
+				}
+			}
+		}
+		assert!(cut == flow.value);
+		assert!(g.edge_count() == edges);
+		for arc in residual.arcs(&0) {
+			assert!(arc.capacity > 0 && side.contains(arc.head().key()));
+		}
+	}
+}
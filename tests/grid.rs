@@ -0,0 +1,107 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Direction, Empty};
+use fastgraph::grid::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashSet;
+
+fn open(kind: Neighborhood) -> Ungraph<Coord, Empty, f64> {
+	grid_graph(6, 5, kind, |_| Some(Empty), |_, _| 1.0)
+}
+
+#[test]
+fn ungraph_test_grid_graph() {
+	let four = open(Neighborhood::Four);
+	assert!(four.node_count() == 30 && four.edge_count() == 5 * 5 + 6 * 4);
+	let eight = open(Neighborhood::Eight);
+	assert!(eight.node_count() == 30 && eight.edge_count() == 5 * 5 + 6 * 4 + 2 * 5 * 4);
+	let hex = open(Neighborhood::Hex);
+	assert!(hex.node_count() == 30);
+	// Cells are connected to every neighbor in the grid and points near
+	// their center fall into them.
+	for (g, kind) in [(&four, Neighborhood::Four), (&eight, Neighborhood::Eight), (&hex, Neighborhood::Hex)] {
+		for node in g.nodes() {
+			let cell = *node.key();
+			let present = kind.neighbors(cell).into_iter().filter(|c| g.get_node(*c).is_some()).count();
+			assert!(node.neighbors(Direction::Both).len() == present);
+			let point = kind.center(cell, 2.0);
+			assert!(kind.cell(point, 2.0) == cell);
+			assert!(kind.cell([point[0] + 0.4, point[1] - 0.4], 2.0) == cell);
+		}
+		// Steps on an open grid are shortest path lengths.
+		let start = Coord::new(0, 2);
+		for node in g.nodes() {
+			let (cost, _) = g.shortest_path(start, *node.key(), |e| e.load()).unwrap_or((0.0, Vec::new()));
+			assert!(cost == kind.steps(start, *node.key()) as f64);
+		}
+	}
+	assert!(Coord::from((3, -1)) == Coord::new(3, -1));
+	assert!(<(i32, i32)>::from(Coord::new(3, -1)) == (3, -1));
+	assert!(Coord::new(3, -1).to_string() == "(3, -1)");
+}
+
+#[test]
+fn digraph_test_grid_obstacles() {
+	// A wall across the middle column.
+	let wall = |c: Coord| c.x == 3;
+	let mut g: Digraph<Coord, Empty, f64> = grid_graph(7, 7, Neighborhood::Eight, |c| match wall(c) {
+		true => { None }
+		false => { Some(Empty) }
+	}, |a, b| if a.x != b.x && a.y != b.y { 1.5 } else { 1.0 });
+	assert!(g.node_count() == 42);
+	assert!(g.get_edge(Coord::new(0, 0), Coord::new(1, 1)).unwrap().load() == 1.5);
+	assert!(g.get_edge(Coord::new(1, 1), Coord::new(0, 0)).is_some());
+	assert!(g.shortest_path(Coord::new(0, 0), Coord::new(6, 6), |e| e.load()).is_none());
+	// Opening a gap connects both sides through it.
+	assert!(unblock(&mut g, Neighborhood::Eight, Coord::new(3, 6), Empty, |_, _| 1.0));
+	assert!(!unblock(&mut g, Neighborhood::Eight, Coord::new(3, 6), Empty, |_, _| 1.0));
+	assert!(g.get_edge(Coord::new(2, 5), Coord::new(3, 6)).is_some());
+	assert!(g.get_edge(Coord::new(3, 6), Coord::new(4, 5)).is_some());
+	assert!(g.shortest_path(Coord::new(0, 0), Coord::new(6, 6), |e| e.load()).is_some());
+	assert!(block(&mut g, Coord::new(3, 6)));
+	assert!(!block(&mut g, Coord::new(3, 6)));
+	assert!(g.shortest_path(Coord::new(0, 0), Coord::new(6, 6), |e| e.load()).is_none());
+}
+
+#[test]
+fn ungraph_test_jump_point_search() {
+	let mut rng = StdRng::seed_from_u64(13);
+	for round in 0..20 {
+		// Random obstacles, denser in later rounds.
+		let density = 0.05 + 0.02 * round as f64;
+		let mut blocked = HashSet::new();
+		for x in 0..30 {
+			for y in 0..20 {
+				if rng.gen_bool(density) {
+					blocked.insert(Coord::new(x, y));
+				}
+			}
+		}
+		let g: Ungraph<Coord, Empty, f64> = grid_graph(30, 20, Neighborhood::Eight, |c| match blocked.contains(&c) {
+			true => { None }
+			false => { Some(Empty) }
+		}, |a, b| if a.x != b.x && a.y != b.y { 2f64.sqrt() } else { 1.0 });
+		for _ in 0..20 {
+			let s = Coord::new(rng.gen_range(0..30), rng.gen_range(0..20));
+			let t = Coord::new(rng.gen_range(0..30), rng.gen_range(0..20));
+			let expected = match s == t {
+				true => { g.get_node(s).map(|_| 0.0) }
+				false => { g.shortest_path(s, t, |e| e.load()).map(|(cost, _)| cost) }
+			};
+			match jump_point_search(&g, s, t) {
+				Some((cost, path)) => {
+					assert!((cost - expected.unwrap()).abs() < 1e-9);
+					assert!(path[0] == s && *path.last().unwrap() == t);
+					// Consecutive cells are neighbors and the steps add up
+					// to the cost.
+					let mut total = 0.0;
+					for pair in path.windows(2) {
+						let edge = g.get_edge(pair[0], pair[1]).or_else(|| g.get_edge(pair[1], pair[0])).unwrap();
+						total += edge.load();
+					}
+					assert!((total - cost).abs() < 1e-9);
+				}
+				None => { assert!(expected.is_none()) }
+			}
+		}
+	}
+}
@@ -0,0 +1,40 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+
+#[test]
+fn interned_graph() {
+	let mut g = InternedGraph::<usize, usize>::new();
+	for i in 0..100 {
+		assert!(g.add_node(&format!("node-{}", i), i));
+	}
+	for i in 0..100 {
+		assert!(g.add_edge(&format!("node-{}", i), &format!("node-{}", (i + 7) % 100), i));
+	}
+	assert!(!g.add_edge("node-1", "missing", 0));
+	assert!(g.node_count() == 100 && g.edge_count() == 100);
+	assert!(g.get_edge("node-95", "node-2").unwrap().load() == 95);
+	assert!(g.graph().get_edge(g.id("node-95").unwrap(), g.id("node-2").unwrap()).is_some());
+
+	// Ids are dense and survive deletion.
+	let id = g.id("node-42").unwrap();
+	assert!(id < 100 && g.name(id) == Some("node-42"));
+	assert!(g.del_node("node-42"));
+	assert!(g.get_node("node-42").is_none() && !g.del_node("node-42"));
+	assert!(g.add_node("node-42", 0));
+	assert!(g.id("node-42") == Some(id));
+	assert!(g.del_edge("node-0", "node-7") && g.get_edge("node-0", "node-7").is_none());
+
+	let s: Digraph<String, usize, usize> = g.to_string_keyed();
+	assert!(s.node_count() == 100 && s.edge_count() == 97);
+	assert!(s.get_edge("node-1".to_string(), "node-8".to_string()).unwrap().load() == 1);
+}
+
+#[test]
+fn interned_ungraph() {
+	let mut g = InternedGraph::<Empty, Empty, Ungraph<u32, Empty, Empty>>::default();
+	g.add_node("a", Empty);
+	g.add_node("b", Empty);
+	g.add_edge("a", "b", Empty);
+	assert!(g.get_edge("b", "a").is_some() || g.get_edge("a", "b").is_some());
+	assert!(g.intern("c") == 2 && g.get_node("c").is_none());
+}
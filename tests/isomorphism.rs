@@ -0,0 +1,105 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::isomorphism::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+// Build a graph from an edge list over n nodes with keys permuted.
+fn build<G: Graph<u32, Empty, u8>>(n: u32, edges: &[(u32, u32, u8)], perm: &[u32]) -> G {
+	let mut g = G::new();
+	for i in 0..n {
+		g.add_node(perm[i as usize], Empty);
+	}
+	for (s, t, e) in edges {
+		g.add_edge(perm[*s as usize], perm[*t as usize], *e);
+	}
+	g
+}
+
+fn check_relabeling<G: Graph<u32, Empty, u8>>(seed: u64) {
+	let mut rng = StdRng::seed_from_u64(seed);
+	let n = rng.gen_range(1..12);
+	let mut edges = Vec::new();
+	for s in 0..n {
+		for t in 0..n {
+			if s != t && (G::directed() || s < t) && rng.gen_bool(0.3) {
+				edges.push((s, t, rng.gen_range(0..2)));
+			}
+		}
+	}
+	let identity: Vec<u32> = (0..n).collect();
+	let mut perm = identity.clone();
+	perm.shuffle(&mut rng);
+	let a: G = build(n, &edges, &identity);
+	let b: G = build(n, &edges, &perm);
+	let ca = canonical_form(&a);
+	let cb = canonical_form(&b);
+	assert!(ca.certificate == cb.certificate);
+	// Nodes at the same canonical position correspond to each other.
+	let edge = |g: &G, s: u32, t: u32| {
+		let e = g.get_edge(s, t).or_else(|| if G::directed() { None } else { g.get_edge(t, s) });
+		e.map(|e| e.load())
+	};
+	for (i, key) in ca.order.iter().enumerate() {
+		for (j, other) in ca.order.iter().enumerate() {
+			assert!(edge(&a, *key, *other) == edge(&b, cb.order[i], cb.order[j]));
+		}
+	}
+}
+
+#[test]
+fn digraph_test_canonical_relabeling() {
+	for seed in 0..50 {
+		check_relabeling::<Digraph<u32, Empty, u8>>(seed);
+	}
+}
+
+#[test]
+fn ungraph_test_canonical_relabeling() {
+	for seed in 0..50 {
+		check_relabeling::<Ungraph<u32, Empty, u8>>(seed);
+	}
+}
+
+#[test]
+fn ungraph_test_regular_graphs() {
+	// A hexagon and two triangles can't be told apart by refinement alone.
+	let identity: Vec<u32> = (0..6).collect();
+	let hexagon: Ungraph<u32, Empty, u8> = build(6, &[(0, 1, 0), (1, 2, 0), (2, 3, 0), (3, 4, 0), (4, 5, 0), (5, 0, 0)], &identity);
+	let triangles: Ungraph<u32, Empty, u8> = build(6, &[(0, 1, 0), (1, 2, 0), (2, 0, 0), (3, 4, 0), (4, 5, 0), (5, 3, 0)], &identity);
+	assert!(!is_isomorphic(&hexagon, &triangles));
+
+	// The Petersen graph is highly symmetric.
+	let petersen = [
+		(0, 1, 0), (1, 2, 0), (2, 3, 0), (3, 4, 0), (4, 0, 0),
+		(0, 5, 0), (1, 6, 0), (2, 7, 0), (3, 8, 0), (4, 9, 0),
+		(5, 7, 0), (7, 9, 0), (9, 6, 0), (6, 8, 0), (8, 5, 0),
+	];
+	let mut perm: Vec<u32> = (0..10).collect();
+	perm.shuffle(&mut StdRng::seed_from_u64(7));
+	let a: Ungraph<u32, Empty, u8> = build(10, &petersen, &(0..10).collect::<Vec<u32>>());
+	let b: Ungraph<u32, Empty, u8> = build(10, &petersen, &perm);
+	assert!(is_isomorphic(&a, &b));
+}
+
+#[test]
+fn ungraph_test_labeled_molecules() {
+	// Ethanol and dimethyl ether have the same atoms but different bonds.
+	let molecule = |atoms: &[char], bonds: &[(u32, u32)]| {
+		let mut g = Ungraph::<u32, char, u8>::new();
+		for (i, atom) in atoms.iter().enumerate() {
+			g.add_node(i as u32, *atom);
+		}
+		for (s, t) in bonds {
+			g.add_edge(*s, *t, 1);
+		}
+		g
+	};
+	let atoms = ['C', 'C', 'O', 'H', 'H', 'H', 'H', 'H', 'H'];
+	let ethanol = molecule(&atoms, &[(0, 1), (1, 2), (0, 3), (0, 4), (0, 5), (1, 6), (1, 7), (2, 8)]);
+	let ether = molecule(&atoms, &[(0, 2), (1, 2), (0, 3), (0, 4), (0, 5), (1, 6), (1, 7), (1, 8)]);
+	let shuffled = molecule(&['H', 'O', 'C', 'H', 'C', 'H', 'H', 'H', 'H'], &[(2, 4), (4, 1), (2, 0), (2, 3), (2, 5), (4, 6), (4, 7), (1, 8)]);
+	assert!(!is_isomorphic(&ethanol, &ether));
+	assert!(is_isomorphic(&ethanol, &shuffled));
+	let structure = |g: &Ungraph<u32, char, u8>| canonical_form_by(g, |_| (), |_| ()).certificate;
+	assert!(structure(&ethanol) != structure(&ether));
+}
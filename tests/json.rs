@@ -0,0 +1,51 @@
+#![cfg(feature = "serde")]
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::io::{json::*, Error};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct City {
+	name: String,
+	population: u32,
+}
+
+impl std::fmt::Display for City {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(fmt, "{}", self.name)
+	}
+}
+
+#[test]
+fn ungraph_test_json_node_link() {
+	let mut g = Ungraph::<String, City, f64>::new();
+	g.add_node("hel".to_string(), City { name: "Helsinki".to_string(), population: 650000 });
+	g.add_node("tku".to_string(), City { name: "Turku".to_string(), population: 190000 });
+	g.add_edge("hel".to_string(), "tku".to_string(), 166.0);
+
+	let json = to_node_link(&g).unwrap();
+	assert!(json["directed"] == false);
+	assert!(json["links"][0]["value"] == 166.0);
+	let hel = json["nodes"].as_array().unwrap().iter().find(|n| n["id"] == "hel").unwrap();
+	assert!(hel["population"] == 650000);
+
+	let mut buffer = Vec::new();
+	write_json(&g, &mut buffer).unwrap();
+	let h: Ungraph<String, City, f64> = read_json(buffer.as_slice()).unwrap();
+	assert!(h.get_node("tku".to_string()).unwrap().load().population == 190000);
+	assert!(h.get_edge("hel".to_string(), "tku".to_string()).unwrap().load() == 166.0);
+}
+
+#[test]
+fn digraph_test_json_from_networkx() {
+	let text = r#"{"directed": true, "multigraph": false, "graph": {},
+		"nodes": [{"id": 1}, {"id": 2}, {"id": 3}],
+		"links": [{"source": 1, "target": 2}, {"source": 2, "target": 3}]}"#;
+	let g: Digraph<u32, Empty, Empty> = read_json(text.as_bytes()).unwrap();
+	assert!(g.node_count() == 3);
+	assert!(g.get_edge(2, 3).is_some());
+
+	let text = r#"{"nodes": [{"id": 1}], "links": [{"source": 1, "target": 5}]}"#;
+	let res: Result<Digraph<u32, Empty, Empty>, Error> = read_json(text.as_bytes());
+	assert!(matches!(res, Err(Error::UnknownNode { line: 1, .. })));
+}
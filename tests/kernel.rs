@@ -0,0 +1,100 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::kernel::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+#[test]
+fn digraph_test_weisfeiler_lehman() {
+	let mut rng = StdRng::seed_from_u64(6);
+	let mut g = Digraph::<usize, u32, u32>::new();
+	for i in 0..50 {
+		g.add_node(i, (i % 3) as u32);
+	}
+	for _ in 0..150 {
+		g.add_edge(rng.gen_range(0..50), rng.gen_range(0..50), rng.gen_range(0..2));
+	}
+	// The same graph with shuffled keys and insertion order.
+	let mut keys: Vec<usize> = (0..50).collect();
+	keys.shuffle(&mut rng);
+	let mut h = Digraph::<usize, u32, u32>::new();
+	for v in (0..50).rev() {
+		h.add_node(keys[v], (v % 3) as u32);
+	}
+	for node in g.nodes() {
+		for edge in node.outbound().iter() {
+			h.add_edge(keys[*node.key()], keys[*edge.target().key()], edge.load());
+		}
+	}
+	let wl = |g: &Digraph<usize, u32, u32>| WeisfeilerLehman::with_labels(g, 3, |n| *n, |e| *e);
+	let (a, b) = (wl(&g), wl(&h));
+	assert!(a.labels.len() == 4);
+	assert!(a.hash() == b.hash());
+	assert!(a.feature_vector(64) == b.feature_vector(64));
+	assert!(a.kernel(&b) == a.kernel(&a));
+	for (v, key) in keys.iter().enumerate() {
+		assert!(a.label(&v) == b.label(key));
+	}
+	// Labels only depend on the neighborhood, so their amount grows.
+	let distinct = |i: usize| a.labels[i].values().collect::<std::collections::HashSet<_>>().len();
+	assert!(distinct(0) == 3 && distinct(1) >= distinct(0) && distinct(3) >= distinct(2));
+	// Changing the data of an edge only changes the hash with edge labels.
+	let node = g.nodes().into_iter().find(|n| !n.outbound().is_empty()).unwrap();
+	let edge = node.outbound()[0].clone();
+	let (s, t) = (*node.key(), *edge.target().key());
+	let structure = WeisfeilerLehman::new(&g, 3).hash();
+	g.del_edge(s, t);
+	g.add_edge(s, t, 1 - edge.load());
+	assert!(wl(&g).hash() != a.hash());
+	assert!(WeisfeilerLehman::new(&g, 3).hash() == structure);
+}
+
+#[test]
+fn ungraph_test_weisfeiler_lehman_kernel() {
+	// Two triangles and a hexagon can't be told apart, unlike a triangle
+	// with a tail.
+	let mut triangles = Ungraph::<u32, Empty, Empty>::new();
+	let mut hexagon = Ungraph::<u32, Empty, Empty>::new();
+	let mut paw = Ungraph::<u32, Empty, Empty>::new();
+	for i in 0..6 {
+		triangles.add_node(i, Empty);
+		hexagon.add_node(i, Empty);
+	}
+	for i in 0..4 {
+		paw.add_node(i, Empty);
+	}
+	for i in 0..3 {
+		triangles.add_edge(i, (i + 1) % 3, Empty);
+		triangles.add_edge(i + 3, (i + 1) % 3 + 3, Empty);
+	}
+	for i in 0..6 {
+		hexagon.add_edge(i, (i + 1) % 6, Empty);
+	}
+	for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+		paw.add_edge(a, b, Empty);
+	}
+	let (t, h, p) = (WeisfeilerLehman::new(&triangles, 3), WeisfeilerLehman::new(&hexagon, 3), WeisfeilerLehman::new(&paw, 3));
+	assert!(t.hash() == h.hash());
+	assert!(t.hash() != p.hash());
+	// Every pair of nodes matches in every iteration.
+	assert!(t.kernel(&h) == 4.0 * 36.0);
+	// Only the initial labels of the paw match, except for the degree of
+	// the nodes of degree two after the first iteration.
+	assert!(t.kernel(&p) == 6.0 * 4.0 + 6.0 * 2.0);
+	assert!(t.kernel(&p) == p.kernel(&t));
+}
+
+#[test]
+fn ungraph_test_weisfeiler_lehman_fixed_hash() {
+	// Hashes don't depend on the build, so they can be stored and compared
+	// later. The values pin the hash function.
+	let mut g = Ungraph::<u32, Empty, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, Empty);
+	g.add_edge(1, 2, Empty);
+	g.add_edge(2, 3, Empty);
+	let wl = WeisfeilerLehman::new(&g, 2);
+	assert!(wl.hash() == 18191751994223243212);
+	assert!(wl.label(&0) == Some(16460808434325277539));
+}
@@ -0,0 +1,83 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::layout::*;
+use std::{collections::HashMap, fmt};
+
+#[derive(Clone, Debug)]
+struct Place {
+	xy: Option<Point>,
+}
+
+impl fmt::Display for Place {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{:?}", self.xy)
+	}
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+	(a[0] - b[0]).hypot(a[1] - b[1])
+}
+
+#[test]
+fn ungraph_test_force_layout() {
+	// Two cycles of ten nodes joined by one edge.
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..20 {
+		g.add_node(i, Empty);
+	}
+	for c in [0, 10] {
+		for i in 0..10 {
+			g.add_edge(c + i, c + (i + 1) % 10, Empty);
+		}
+	}
+	g.add_edge(0, 10, Empty);
+	let mut positions: HashMap<usize, Point> = HashMap::new();
+	ForceLayout::default().run(&g, &mut positions);
+	assert!(positions.len() == 20);
+	// Neighbors are drawn closer than nodes of different cycles.
+	let mut near = 0.0;
+	for i in 0..10 {
+		near += distance(positions[&i], positions[&((i + 1) % 10)]);
+	}
+	let mut far = 0.0;
+	for i in 0..10 {
+		far += distance(positions[&i], positions[&(10 + i)]);
+	}
+	assert!(near < far);
+	// The same seed gives the same drawing.
+	let mut again: HashMap<usize, Point> = HashMap::new();
+	ForceLayout::default().run(&g, &mut again);
+	assert!(again == positions);
+	// Without iterations stored positions stay and missing ones are placed.
+	positions.remove(&5);
+	let before = positions.clone();
+	ForceLayout { iterations: 0, ..ForceLayout::default() }.run(&g, &mut positions);
+	assert!(positions.len() == 20);
+	assert!(before.iter().all(|(k, p)| positions[k] == *p));
+	assert!(distance(positions[&5], positions[&4]) < 5.0);
+}
+
+#[test]
+fn digraph_test_node_data_positions() {
+	let mut g = Digraph::<usize, Place, Empty>::new();
+	for i in 0..30 {
+		g.add_node(i, Place { xy: None });
+	}
+	for i in 0..29 {
+		g.add_edge(i, i + 1, Empty);
+	}
+	let mut positions = NodeData::new(|p: &Place| p.xy, |p: &mut Place, xy| p.xy = Some(xy));
+	ForceLayout::default().run(&g, &mut positions);
+	let first: Vec<Point> = (0..30).map(|i| g.get_node(i).unwrap().load().xy.unwrap()).collect();
+	// A new node joins next to its neighbor, and refining at a low
+	// temperature keeps the rest of the drawing in place.
+	g.add_node(30, Place { xy: None });
+	g.add_edge(29, 30, Empty);
+	let refine = ForceLayout { iterations: 10, temperature: 0.01, ..ForceLayout::default() };
+	refine.run(&g, &mut positions);
+	let second: Vec<Point> = (0..31).map(|i| g.get_node(i).unwrap().load().xy.unwrap()).collect();
+	for i in 0..30 {
+		assert!(distance(first[i], second[i]) <= 0.1);
+	}
+	assert!(distance(second[29], second[30]) < 2.0);
+}
@@ -0,0 +1,51 @@
+use fastgraph::collections::*;
+use fastgraph::core::*;
+use fastgraph::machine::*;
+
+// Recognizes binary numbers divisible by three, with the remainder as the
+// state and the next bit as the event of each transition.
+fn divisible_by_three() -> Digraph<usize, Empty, u8> {
+	let mut g = Digraph::<usize, Empty, u8>::new();
+	for r in 0..3 {
+		g.add_node(r, Empty);
+	}
+	for r in 0..3 {
+		for bit in 0..2 {
+			g.add_edge(r, (2 * r + bit as usize) % 3, bit);
+		}
+	}
+	g
+}
+
+#[test]
+fn digraph_test_state_machine() {
+	let g = divisible_by_three();
+	for n in 0..64usize {
+		let bits: Vec<u8> = format!("{:b}", n).bytes().map(|b| b - b'0').collect();
+		let mut entered = 0;
+		let run = execute(&g, 0, bits.iter().copied(), |e, bit| e.load() == *bit, |_, _| entered += 1).unwrap();
+		assert!(!run.stuck && run.consumed == bits.len() && entered == bits.len());
+		assert!(*run.trace.last().unwrap() == n % 3);
+	}
+	// An input no transition accepts stops the run.
+	let run = execute(&g, 1, vec![1, 7, 0], |e, bit| e.load() == *bit, |_, _| {}).unwrap();
+	assert!(run == Execution { trace: vec![1, 0], consumed: 1, stuck: true });
+	assert!(execute(&g, 3, vec![0], |e, bit| e.load() == *bit, |_, _| {}).is_none());
+
+	// The first accepting transition in edge order wins.
+	let mut h = Digraph::<usize, Empty, u8>::new();
+	for i in 0..3 {
+		h.add_node(i, Empty);
+	}
+	h.add_edge(0, 1, 0);
+	h.add_edge(0, 2, 1);
+	let mut machine = StateMachine::new(&h, 0).unwrap();
+	assert!(!machine.is_final());
+	machine.step(&(), |_, _| true);
+	assert!(machine.state().key() == &1 && machine.is_final());
+	let start = h.get_node(0).unwrap();
+	start.sort_outbound_by(|a, b| b.load().cmp(&a.load()));
+	let mut machine = StateMachine::at(start);
+	let edge = machine.step(&(), |_, _| true).unwrap();
+	assert!(edge.load() == 1 && machine.state().key() == &2);
+}
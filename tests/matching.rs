@@ -0,0 +1,291 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Edge, Empty};
+use fastgraph::matching::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
+
+fn random_ungraph(size: usize, edges: usize, rng: &mut StdRng) -> Ungraph<usize, Empty, f64> {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..size {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..edges {
+		g.add_edge(rng.gen_range(0..size), rng.gen_range(0..size), rng.gen_range(1..20) as f64);
+	}
+	g
+}
+
+// Weight of a maximum weight matching over the given edges by trying every
+// subset of them.
+fn maximum(edges: &[(usize, usize, f64)], used: &mut HashSet<usize>) -> f64 {
+	match edges.split_first() {
+		Some(((s, t, w), rest)) => {
+			let mut best = maximum(rest, used);
+			if s != t && !used.contains(s) && !used.contains(t) {
+				used.insert(*s);
+				used.insert(*t);
+				best = best.max(w + maximum(rest, used));
+				used.remove(s);
+				used.remove(t);
+			}
+			best
+		}
+		None => { 0.0 }
+	}
+}
+
+// Checks that the matching shares no nodes, can't be extended and returns
+// its weight.
+fn check(g: &Ungraph<usize, Empty, f64>, matching: &Matching<usize, Empty, f64>) -> f64 {
+	let mut matched = HashSet::new();
+	for e in matching.iter() {
+		assert!(e.source().key() != e.target().key());
+		assert!(matched.insert(*e.source().key()) && matched.insert(*e.target().key()));
+	}
+	for n in g.nodes() {
+		for e in n.outbound().iter() {
+			let (s, t) = (*e.source().key(), *e.target().key());
+			assert!(s == t || matched.contains(&s) || matched.contains(&t));
+		}
+	}
+	matching.iter().map(|e| e.load()).sum()
+}
+
+#[test]
+fn ungraph_test_matching_approximations() {
+	let mut rng = StdRng::seed_from_u64(7);
+	for _ in 0..30 {
+		let g = random_ungraph(10, 18, &mut rng);
+		let mut edges = Vec::new();
+		for n in g.nodes() {
+			for e in n.outbound().iter() {
+				edges.push((*e.source().key(), *e.target().key(), e.load()));
+			}
+		}
+		let best = maximum(&edges, &mut HashSet::new());
+		check(&g, &heavy_edge_matching(&g, |e| e.load()));
+		assert!(check(&g, &greedy_matching(&g, |e| e.load())) * 2.0 >= best);
+		assert!(check(&g, &path_growing_matching(&g, |e| e.load())) * 2.0 >= best);
+	}
+}
+
+#[test]
+fn digraph_test_matching() {
+	// Parallel edges count once with their heaviest weight, and edges that
+	// weigh nothing are never matched.
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, 1.0);
+	g.add_edge(1, 0, 5.0);
+	g.add_edge(1, 2, 4.0);
+	g.add_edge(2, 2, 9.0);
+	g.add_edge(2, 3, 0.0);
+	for matching in [greedy_matching(&g, |e| e.load()), path_growing_matching(&g, |e| e.load())] {
+		assert!(matching.len() == 1);
+		assert!(matching[0].load() == 5.0);
+	}
+	let matching = heavy_edge_matching(&g, |e| e.load());
+	assert!(matching.len() == 1 && matching[0].load() != 0.0);
+	// A large graph is matched the same way every time.
+	let mut rng = StdRng::seed_from_u64(8);
+	let g = random_ungraph(2000, 8000, &mut rng);
+	let keys = |m: Matching<usize, Empty, f64>| m.iter().map(|e| (*e.source().key(), *e.target().key())).collect::<Vec<_>>();
+	assert!(keys(path_growing_matching(&g, |e| e.load())) == keys(path_growing_matching(&g, |e| e.load())));
+	let heavy = check(&g, &heavy_edge_matching(&g, |e| e.load()));
+	let greedy = check(&g, &greedy_matching(&g, |e| e.load()));
+	let path = check(&g, &path_growing_matching(&g, |e| e.load()));
+	assert!(heavy > 0.0 && greedy > 0.0 && path > 0.0);
+}
+
+// Largest amount of edges and the largest weight among matchings of that
+// size by trying every subset of the edges.
+fn maximum_cardinality(edges: &[(usize, usize, f64)], used: &mut HashSet<usize>) -> (usize, f64) {
+	match edges.split_first() {
+		Some(((s, t, w), rest)) => {
+			let mut best = maximum_cardinality(rest, used);
+			if s != t && !used.contains(s) && !used.contains(t) {
+				used.insert(*s);
+				used.insert(*t);
+				let (count, weight) = maximum_cardinality(rest, used);
+				if count + 1 > best.0 || (count + 1 == best.0 && weight + w > best.1) {
+					best = (count + 1, weight + w);
+				}
+				used.remove(s);
+				used.remove(t);
+			}
+			best
+		}
+		None => { (0, 0.0) }
+	}
+}
+
+#[test]
+fn ungraph_test_maximum_weight_matching() {
+	let mut rng = StdRng::seed_from_u64(9);
+	for round in 0..200 {
+		let size = rng.gen_range(2..11);
+		let mut g = Ungraph::<usize, Empty, f64>::new();
+		for i in 0..size {
+			g.add_node(i, Empty);
+		}
+		for _ in 0..rng.gen_range(1..20) {
+			let weight = match round % 2 {
+				0 => { rng.gen_range(-3..10) as f64 }
+				_ => { rng.gen_range(1..4) as f64 }
+			};
+			g.add_edge(rng.gen_range(0..size), rng.gen_range(0..size), weight);
+		}
+		let mut edges = Vec::new();
+		for n in g.nodes() {
+			for e in n.outbound().iter() {
+				edges.push((*e.source().key(), *e.target().key(), e.load()));
+			}
+		}
+		let positive: Vec<_> = edges.iter().cloned().filter(|e| e.2 > 0.0).collect();
+		let matching = maximum_weight_matching(&g, |e| e.load());
+		let weight: f64 = matching.iter().map(|e| e.load()).sum();
+		assert!(weight == maximum(&positive, &mut HashSet::new()));
+		let matching = maximum_cardinality_matching(&g, |e| e.load());
+		let weight: f64 = matching.iter().map(|e| e.load()).sum();
+		assert!(check(&g, &matching) == weight);
+		assert!((matching.len(), weight) == maximum_cardinality(&edges, &mut HashSet::new()));
+	}
+}
+
+#[test]
+fn digraph_test_maximum_weight_matching() {
+	// Fractional weights on a larger graph are at least as heavy as the
+	// approximations.
+	let mut rng = StdRng::seed_from_u64(10);
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..300 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..1500 {
+		g.add_edge(rng.gen_range(0..300), rng.gen_range(0..300), rng.gen_range(0.0..1.0));
+	}
+	let weight = |m: &Matching<usize, Empty, f64>| m.iter().map(|e| e.load()).sum::<f64>();
+	let exact = weight(&maximum_weight_matching(&g, |e| e.load()));
+	assert!(exact >= weight(&greedy_matching(&g, |e| e.load())));
+	assert!(exact >= weight(&path_growing_matching(&g, |e| e.load())));
+	// A constant weight gives a maximum matching, which has at least as
+	// many edges as any maximal one.
+	let maximum = maximum_cardinality_matching(&g, |_| 1.0);
+	assert!(maximum.len() >= heavy_edge_matching(&g, |_| 1.0).len());
+	assert!(maximum.len() >= maximum_weight_matching(&g, |e| e.load()).len());
+}
+
+// Lowest total cost of assigning every row a different column by trying
+// every permutation, None if some row has no column left.
+fn cheapest(costs: &[Vec<f64>], row: usize, used: &mut Vec<bool>) -> Option<f64> {
+	if row == costs.len() {
+		return Some(0.0);
+	}
+	let mut best: Option<f64> = None;
+	for j in 0..used.len() {
+		if used[j] || costs[row][j] == f64::INFINITY {
+			continue;
+		}
+		used[j] = true;
+		if let Some(rest) = cheapest(costs, row + 1, used) {
+			let total = costs[row][j] + rest;
+			best = Some(best.map_or(total, |b| b.min(total)));
+		}
+		used[j] = false;
+	}
+	best
+}
+
+#[test]
+fn digraph_test_min_cost_assignment() {
+	let mut rng = StdRng::seed_from_u64(11);
+	for _ in 0..200 {
+		let (n, m) = (rng.gen_range(1..6), rng.gen_range(1..7));
+		let mut g = Digraph::<usize, Empty, f64>::new();
+		for i in 0..n + m {
+			g.add_node(i, Empty);
+		}
+		let mut costs = vec![vec![f64::INFINITY; m]; n];
+		for _ in 0..rng.gen_range(0..2 * n * m + 1) {
+			let (i, j) = (rng.gen_range(0..n), rng.gen_range(0..m));
+			let cost = rng.gen_range(-5..20) as f64;
+			// Edges run both ways and between nodes of one side too.
+			let (s, t) = match rng.gen_range(0..3) {
+				0 => { (i, n + j) }
+				1 => { (n + j, i) }
+				_ => { (i, (i + 1) % n) }
+			};
+			if g.get_edge(s, t).is_none() {
+				g.add_edge(s, t, cost);
+				if s != t && (s < n) != (t < n) {
+					costs[i][j] = costs[i][j].min(cost);
+				}
+			}
+		}
+		let keys: Vec<usize> = (0..n).collect();
+		let assignment = min_cost_assignment(&g, &keys, |e| e.load());
+		match cheapest(&costs, 0, &mut vec![false; m]) {
+			Some(best) => {
+				let assignment = assignment.unwrap();
+				assert!(assignment.cost == best);
+				assert!(assignment.edges.iter().map(|e| e.load()).sum::<f64>() == best);
+				let mut rows = HashSet::new();
+				let mut columns = HashSet::new();
+				for e in assignment.edges.iter() {
+					let (s, t) = (*e.source().key(), *e.target().key());
+					let (r, c) = if s < n { (s, t) } else { (t, s) };
+					assert!(r < n && c >= n && rows.insert(r) && columns.insert(c));
+				}
+				assert!(rows.len() == n);
+			}
+			None => { assert!(assignment.is_none()) }
+		}
+	}
+	let g = Digraph::<usize, Empty, f64>::new();
+	assert!(min_cost_assignment(&g, &[1], |e| e.load()).is_none());
+}
+
+#[test]
+fn ungraph_test_stable_matching() {
+	let mut rng = StdRng::seed_from_u64(12);
+	for _ in 0..100 {
+		let n = rng.gen_range(1..12);
+		let mut g = Ungraph::<usize, Empty, Empty>::new();
+		for i in 0..2 * n {
+			g.add_node(i, Empty);
+		}
+		// Rank of every edge for the proposer and the receiver.
+		let mut ranks = HashMap::new();
+		for p in 0..n {
+			for r in n..2 * n {
+				if rng.gen_bool(0.7) {
+					g.add_edge(p, r, Empty);
+					ranks.insert((p, r), (rng.gen_range(0..100), rng.gen_range(0..100)));
+				}
+			}
+		}
+		let proposers: Vec<usize> = (0..n).collect();
+		let rank = |e: &Arc<Edge<usize, Empty, Empty>>| ranks[&(*e.source().key(), *e.target().key())];
+		let matching = stable_matching(&g, &proposers, |e| rank(e).0 as f64, |e| rank(e).1 as f64);
+		let mut partner = HashMap::new();
+		for e in matching.iter() {
+			let (p, r, rank) = (*e.source().key(), *e.target().key(), rank(e));
+			assert!(p < n && r >= n);
+			assert!(partner.insert(p, rank.0).is_none() && partner.insert(r, rank.1).is_none());
+		}
+		// No edge is preferred by both of its endpoints.
+		for node in g.nodes() {
+			for e in node.outbound().iter() {
+				let (p, r, (a, b)) = (*e.source().key(), *e.target().key(), rank(e));
+				let proposer = partner.get(&p).map_or(true, |x| a < *x);
+				let receiver = partner.get(&r).map_or(true, |x| b < *x);
+				assert!(!(proposer && receiver));
+			}
+		}
+	}
+}
@@ -0,0 +1,99 @@
+use fastgraph::collections::*;
+use proptest::prelude::*;
+use std::collections::BTreeSet;
+
+// Operations on a graph with a handful of keys so that operations collide
+// often.
+#[derive(Clone, Debug)]
+enum Op {
+	AddNode(u8),
+	DelNode(u8),
+	AddEdge(u8, u8),
+	DelEdge(u8, u8),
+}
+
+fn op() -> impl Strategy<Value = Op> {
+	let key = 0u8..12;
+	prop_oneof![
+		key.clone().prop_map(Op::AddNode),
+		key.clone().prop_map(Op::DelNode),
+		(key.clone(), key.clone()).prop_map(|(s, t)| Op::AddEdge(s, t)),
+		(key.clone(), key).prop_map(|(s, t)| Op::DelEdge(s, t)),
+	]
+}
+
+// Reference model of the graph as sets of keys and key pairs.
+#[derive(Default)]
+struct Model {
+	nodes: BTreeSet<u8>,
+	edges: BTreeSet<(u8, u8)>,
+}
+
+impl Model {
+	fn apply(&mut self, op: &Op) -> bool {
+		match *op {
+			Op::AddNode(k) => self.nodes.insert(k),
+			Op::DelNode(k) => {
+				self.edges.retain(|(s, t)| *s != k && *t != k);
+				self.nodes.remove(&k)
+			}
+			Op::AddEdge(s, t) => {
+				if self.nodes.contains(&s) && self.nodes.contains(&t) {
+					self.edges.insert((s, t));
+					true
+				} else {
+					false
+				}
+			}
+			Op::DelEdge(s, t) => {
+				self.edges.remove(&(s, t));
+				self.nodes.contains(&s) && self.nodes.contains(&t)
+			}
+		}
+	}
+}
+
+fn apply<G: Graph<u8, u32, u32>>(g: &mut G, op: &Op) -> bool {
+	match *op {
+		Op::AddNode(k) => g.add_node(k, k as u32),
+		Op::DelNode(k) => g.del_node(k),
+		Op::AddEdge(s, t) => g.add_edge(s, t, s as u32 * 100 + t as u32),
+		Op::DelEdge(s, t) => g.del_edge(s, t),
+	}
+}
+
+fn check<G: Graph<u8, u32, u32>>(ops: &[Op]) -> Result<(), TestCaseError> {
+	let mut g = G::new();
+	let mut model = Model::default();
+	for op in ops {
+		prop_assert_eq!(apply(&mut g, op), model.apply(op), "{:?}", op);
+		if let Err(violation) = g.validate() {
+			return Err(TestCaseError::fail(format!("after {:?}: {}", op, violation)));
+		}
+	}
+	prop_assert_eq!(g.node_count(), model.nodes.len());
+	prop_assert_eq!(g.edge_count(), model.edges.len());
+	for k in 0..12 {
+		prop_assert_eq!(g.get_node(k).is_some(), model.nodes.contains(&k));
+		for t in 0..12 {
+			let edge = g.get_edge(k, t);
+			prop_assert_eq!(edge.is_some(), model.edges.contains(&(k, t)));
+			if let Some(edge) = edge {
+				prop_assert_eq!(edge.load(), k as u32 * 100 + t as u32);
+			}
+		}
+	}
+	Ok(())
+}
+
+proptest! {
+	#[test]
+	fn digraph_test_model(ops in prop::collection::vec(op(), 0..200)) {
+		check::<Digraph<u8, u32, u32>>(&ops)?;
+	}
+
+	#[test]
+	fn ungraph_test_model(ops in prop::collection::vec(op(), 0..200)) {
+		check::<Ungraph<u8, u32, u32>>(&ops)?;
+	}
+}
@@ -0,0 +1,155 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::motifs::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+#[test]
+fn digraph_test_triad_census() {
+	let mut rng = StdRng::seed_from_u64(2);
+	let n = 25;
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..n {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..120 {
+		g.add_edge(rng.gen_range(0..n), rng.gen_range(0..n), Empty);
+	}
+	let arc = |a: usize, b: usize| a != b && g.get_edge(a, b).is_some();
+	let census = triad_census(&g);
+	assert!(census.iter().sum::<u64>() == (n * (n - 1) * (n - 2) / 6) as u64);
+	// The amounts of mutual, asymmetric and null dyads of every triple
+	// match the name of its type.
+	let mut dyads: HashMap<String, u64> = HashMap::new();
+	for a in 0..n {
+		for b in a + 1..n {
+			for c in b + 1..n {
+				let mut man = [0; 3];
+				for (x, y) in [(a, b), (a, c), (b, c)] {
+					match (arc(x, y), arc(y, x)) {
+						(true, true) => { man[0] += 1 }
+						(false, false) => { man[2] += 1 }
+						_ => { man[1] += 1 }
+					}
+				}
+				*dyads.entry(format!("{}{}{}", man[0], man[1], man[2])).or_insert(0) += 1;
+			}
+		}
+	}
+	for name in TRIADS.iter() {
+		let same: u64 = TRIADS
+			.iter()
+			.zip(census.iter())
+			.filter(|(t, _)| t[..3] == name[..3])
+			.map(|(_, c)| c)
+			.sum();
+		assert!(same == dyads.get(&name[..3]).copied().unwrap_or(0), "{}", name);
+	}
+}
+
+#[test]
+fn digraph_test_triad_types() {
+	// Every triad type on its own three nodes.
+	let types: [&[(u32, u32)]; 16] = [
+		&[],
+		&[(0, 1)],
+		&[(0, 1), (1, 0)],
+		&[(0, 1), (0, 2)],
+		&[(1, 0), (2, 0)],
+		&[(0, 1), (1, 2)],
+		&[(0, 1), (1, 0), (2, 0)],
+		&[(0, 1), (1, 0), (0, 2)],
+		&[(0, 1), (1, 2), (0, 2)],
+		&[(0, 1), (1, 2), (2, 0)],
+		&[(0, 1), (1, 0), (0, 2), (2, 0)],
+		&[(1, 0), (1, 2), (0, 2), (2, 0)],
+		&[(0, 1), (2, 1), (0, 2), (2, 0)],
+		&[(0, 1), (1, 2), (0, 2), (2, 0)],
+		&[(0, 1), (1, 2), (2, 1), (0, 2), (2, 0)],
+		&[(0, 1), (1, 0), (1, 2), (2, 1), (0, 2), (2, 0)],
+	];
+	for (i, edges) in types.iter().enumerate() {
+		for shift in 0..3 {
+			let mut g = Digraph::<u32, Empty, Empty>::new();
+			for v in 0..3 {
+				g.add_node(v, Empty);
+			}
+			for (a, b) in edges.iter() {
+				g.add_edge((a + shift) % 3, (b + shift) % 3, Empty);
+			}
+			let census = triad_census(&g);
+			assert!(census[i] == 1, "{}", TRIADS[i]);
+		}
+	}
+}
+
+#[test]
+fn ungraph_test_motifs() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let n = 16;
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..n {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..40 {
+		let (a, b) = (rng.gen_range(0..n), rng.gen_range(0..n));
+		if a != b && g.get_edge(b, a).is_none() {
+			g.add_edge(a, b, Empty);
+		}
+	}
+	let adjacent = |a: usize, b: usize| g.get_edge(a, b).is_some() || g.get_edge(b, a).is_some();
+	// Classify every connected subset of three and four nodes.
+	let mut expected = Motifs::default();
+	let subsets = (0..1usize << n).filter(|s| s.count_ones() == 3 || s.count_ones() == 4);
+	for set in subsets {
+		let nodes: Vec<usize> = (0..n).filter(|v| set & 1 << v != 0).collect();
+		let degrees: Vec<usize> = nodes.iter().map(|a| nodes.iter().filter(|b| adjacent(*a, **b)).count()).collect();
+		let edges = degrees.iter().sum::<usize>() / 2;
+		let mut reached = vec![nodes[0]];
+		while let Some(v) = reached.iter().flat_map(|a| nodes.iter().filter(move |b| adjacent(*a, **b))).find(|b| !reached.contains(b)) {
+			reached.push(*v);
+		}
+		if reached.len() < nodes.len() {
+			continue;
+		}
+		let max = *degrees.iter().max().unwrap();
+		match (nodes.len(), edges) {
+			(3, 2) => { expected.wedges += 1 }
+			(3, _) => { expected.triangles += 1 }
+			(_, 3) if max == 3 => { expected.stars += 1 }
+			(_, 3) => { expected.paths += 1 }
+			(_, 4) if max == 2 => { expected.cycles += 1 }
+			(_, 4) => { expected.paws += 1 }
+			(_, 5) => { expected.diamonds += 1 }
+			_ => { expected.cliques += 1 }
+		}
+	}
+	assert!(motifs(&g) == expected);
+	// Direction is ignored.
+	let d: Digraph<usize, Empty, Empty> = g.map(|_, n| n, |e| e);
+	assert!(motifs(&d) == expected);
+}
+
+#[test]
+fn ungraph_test_significance() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..40 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..80 {
+		let (a, b) = (rng.gen_range(0..40), rng.gen_range(0..40));
+		if a != b && g.get_edge(b, a).is_none() {
+			g.add_edge(a, b, Empty);
+		}
+	}
+	// The amount of edges and wedges only depends on the degrees.
+	let s = significance(&g, 10, |g| {
+		let m = motifs(g);
+		vec![g.edge_count() as f64, (m.wedges + 3 * m.triangles) as f64]
+	}, &mut rng);
+	assert!(s.observed[0] == g.edge_count() as f64);
+	assert!(s.deviation == vec![0.0, 0.0]);
+	assert!(s.z == vec![0.0, 0.0]);
+	assert!(s.observed == s.mean);
+}
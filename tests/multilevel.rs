@@ -0,0 +1,96 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::multilevel::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// Total weight of the edges of a level, inside nodes or between them.
+fn total(level: &Level) -> f64 {
+	let between: f64 = level.adjacency.iter().flatten().map(|(_, w)| w).sum();
+	between / 2.0 + level.internal.iter().sum::<f64>()
+}
+
+// Records the levels it refines and labels every node with its index on the
+// coarsest level.
+struct Levels {
+	sizes: Vec<usize>,
+}
+
+impl Multilevel for Levels {
+	type Value = usize;
+
+	fn solve(&mut self, level: &Level) -> Vec<usize> {
+		(0..level.len()).collect()
+	}
+
+	fn refine(&mut self, level: &Level, values: &mut [usize]) {
+		assert!(values.len() == level.len());
+		self.sizes.push(level.len());
+	}
+}
+
+#[test]
+fn digraph_test_hierarchy() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..500 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..2000 {
+		g.add_edge(rng.gen_range(0..500), rng.gen_range(0..500), rng.gen_range(1..4) as f64);
+	}
+	let weight: f64 = g.nodes().iter().flat_map(|n| n.outbound().iter().map(|e| e.load()).collect::<Vec<_>>()).sum();
+	let hierarchy = Hierarchy::new(&g, 20, |e| e.load());
+	assert!(hierarchy.levels.len() > 3 && hierarchy.coarse.len() == hierarchy.levels.len() - 1);
+	assert!(hierarchy.keys.len() == 500);
+	// Contraction keeps the node and edge weights, and matched nodes are
+	// contracted at most in pairs.
+	for (i, level) in hierarchy.levels.iter().enumerate() {
+		assert!(level.weights.iter().sum::<f64>() == 500.0);
+		assert!((total(level) - weight).abs() < 1e-9);
+		if i > 0 {
+			let coarse = &hierarchy.coarse[i - 1];
+			assert!(coarse.iter().all(|c| *c < level.len()));
+			assert!(level.len() * 2 >= hierarchy.levels[i - 1].len());
+		}
+	}
+	// Every node of the first level ends up labeled with the coarse node it
+	// was contracted into.
+	let mut solver = Levels { sizes: Vec::new() };
+	let values = hierarchy.run(&mut solver);
+	let sizes: Vec<usize> = hierarchy.levels.iter().rev().map(|l| l.len()).collect();
+	assert!(solver.sizes == sizes);
+	for (v, value) in values.iter().enumerate() {
+		let mut c = v;
+		for coarse in hierarchy.coarse.iter() {
+			c = coarse[c];
+		}
+		assert!(*value == c);
+	}
+	// The hierarchy is the same for a copy of the graph.
+	let again = Hierarchy::new(&g, 20, |e| e.load());
+	assert!(again.keys == hierarchy.keys && again.levels == hierarchy.levels);
+}
+
+#[test]
+fn ungraph_test_multilevel_partition() {
+	// Four dense groups of 50 nodes with few edges between them.
+	let mut rng = StdRng::seed_from_u64(6);
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..200 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..3000 {
+		let group = rng.gen_range(0..4) * 50;
+		g.add_edge(group + rng.gen_range(0..50), group + rng.gen_range(0..50), Empty);
+	}
+	for _ in 0..20 {
+		g.add_edge(rng.gen_range(0..200), rng.gen_range(0..200), Empty);
+	}
+	let parts = multilevel_partition(&g, 4, 0.1, |_| 1.0);
+	assert!(parts.len() == 4);
+	assert!(parts.iter().map(|p| p.len()).sum::<usize>() == 200);
+	assert!(parts.iter().all(|p| p.len() as f64 <= 1.1 * 50.0));
+	let part: Vec<usize> = (0..200).map(|k| parts.iter().position(|p| p.contains(&k)).unwrap()).collect();
+	let cut = g.nodes().iter().map(|n| n.outbound().iter().filter(|e| part[*n.key()] != part[*e.target().key()]).count()).sum::<usize>();
+	assert!(cut <= 20);
+}
@@ -0,0 +1,120 @@
+use fastgraph::core::*;
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+type SimpleDigraph = Digraph<usize, Empty, Empty>;
+
+// A path 1 -> 2 -> 3 -> 4 -> 5 with a shortcut 1 -> 3.
+fn test_digraph_path() -> SimpleDigraph {
+	let mut g = SimpleDigraph::new();
+	for i in 1..6 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, Empty);
+	g.add_edge(2, 3, Empty);
+	g.add_edge(3, 4, Empty);
+	g.add_edge(4, 5, Empty);
+	g.add_edge(1, 3, Empty);
+	g
+}
+
+#[test]
+fn digraph_test_ego() {
+	let g = test_digraph_path();
+
+	let ego = g.ego(1, 1, Direction::Outbound).unwrap();
+	assert!(ego.node_count() == 3);
+	assert!(ego.edge_count() == 3);
+	assert!(ego.get_edge(2, 3).is_some());
+
+	let ego = g.ego(3, 1, Direction::Inbound).unwrap();
+	assert!(ego.node_count() == 3);
+	assert!(ego.get_node(4).is_none());
+
+	let ego = g.ego(3, 1, Direction::Both).unwrap();
+	assert!(ego.node_count() == 4);
+	assert!(ego.get_node(5).is_none());
+
+	let ego = g.ego(3, 0, Direction::Both).unwrap();
+	assert!(ego.node_count() == 1);
+	assert!(ego.edge_count() == 0);
+
+	assert!(g.ego(42, 1, Direction::Both).is_none());
+}
+
+#[test]
+fn ungraph_test_ball() {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 1..6 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 3, 1.5);
+	g.add_edge(3, 4, 4.0);
+	g.add_edge(5, 1, 2.0);
+
+	let ball = g.ball(1, 2.5, |edge| edge.load()).unwrap();
+	let keys: Vec<usize> = ball.iter().map(|(node, _)| *node.key()).collect();
+	assert!(keys == vec![1, 2, 5, 3]);
+	assert!(ball[3].1 == 2.5);
+
+	let ball = g.ball(3, 0.5, |edge| edge.load()).unwrap();
+	assert!(ball.len() == 1);
+	assert!(g.ball(42, 1.0, |edge| edge.load()).is_none());
+}
+
+#[test]
+fn digraph_test_aggregate_edges() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..5 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 4.0);
+	g.add_edge(1, 3, 2.0);
+	g.add_edge(2, 3, 1.0);
+	g.add_edge(3, 1, 6.0);
+	let one = g.get_node(1).unwrap();
+	assert!(one.aggregate_out(Aggregate::Sum, |e| e.load()) == Some(6.0));
+	assert!(one.aggregate_out(Aggregate::Min, |e| e.load()) == Some(2.0));
+	assert!(one.aggregate_out(Aggregate::Max, |e| e.load()) == Some(4.0));
+	assert!(one.aggregate_out(Aggregate::Mean, |e| e.load()) == Some(3.0));
+	assert!(one.aggregate_in(Aggregate::Count, |e| e.load()) == Some(1.0));
+	assert!(one.aggregate(Direction::Both, Aggregate::Sum, |e| e.load()) == Some(12.0));
+	let four = g.get_node(4).unwrap();
+	assert!(four.aggregate_out(Aggregate::Sum, |e| e.load()) == Some(0.0));
+	assert!(four.aggregate_out(Aggregate::Count, |e| e.load()) == Some(0.0));
+	assert!(four.aggregate_out(Aggregate::Mean, |e| e.load()).is_none());
+	assert!(four.aggregate_in(Aggregate::Min, |e| e.load()).is_none());
+	// Inflow minus outflow of every node sums to zero.
+	let balance: f64 = g.nodes()
+		.iter()
+		.map(|n| n.aggregate_in(Aggregate::Sum, |e| e.load()).unwrap() - n.aggregate_out(Aggregate::Sum, |e| e.load()).unwrap())
+		.sum();
+	assert!(balance == 0.0);
+	let maxima = g.aggregate_edges(Direction::Inbound, Aggregate::Max, |e| e.load());
+	assert!(maxima.len() == 4);
+	for (node, max) in maxima {
+		match node.key() {
+			1 => { assert!(max == Some(6.0)) }
+			2 => { assert!(max == Some(4.0)) }
+			3 => { assert!(max == Some(2.0)) }
+			_ => { assert!(max.is_none()) }
+		}
+	}
+}
+
+#[test]
+fn ungraph_test_aggregate_edges() {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 1..4 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(3, 2, 2.0);
+	for (node, degree) in g.aggregate_edges(Direction::Both, Aggregate::Sum, |e| e.load()) {
+		match node.key() {
+			2 => { assert!(degree == Some(3.0)) }
+			1 => { assert!(degree == Some(1.0)) }
+			_ => { assert!(degree == Some(2.0)) }
+		}
+	}
+}
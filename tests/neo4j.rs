@@ -0,0 +1,67 @@
+#![cfg(feature = "neo4j")]
+use fastgraph::collections::*;
+use fastgraph::io::{neo4j::*, Error};
+
+fn row(fields: &[&str], values: Vec<BoltType>) -> Row {
+	let fields: Vec<BoltType> = fields.iter().map(|f| BoltType::from(*f)).collect();
+	Row::new(BoltList::from(fields), BoltList::from(values))
+}
+
+fn node(id: i64, title: &str) -> Row {
+	row(&["id", "title"], vec![BoltType::from(id), BoltType::from(title)])
+}
+
+fn edge(from: i64, to: i64, weight: f64) -> Row {
+	row(&["from", "to", "weight"], vec![BoltType::from(from), BoltType::from(to), BoltType::from(weight)])
+}
+
+fn build(nodes: Vec<Row>, edges: Vec<Row>) -> Result<Digraph<i64, String, f64>, Error> {
+	from_rows(
+		nodes,
+		edges,
+		|r| Ok((r.get("id")?, r.get("title")?)),
+		|r| Ok((r.get("from")?, r.get("to")?, r.get("weight")?)),
+	)
+}
+
+#[test]
+fn digraph_test_neo4j_rows() {
+	let g = build(
+		vec![node(1, "a"), node(2, "b"), node(3, "c")],
+		vec![edge(1, 2, 0.5), edge(2, 3, 1.5), edge(3, 1, 2.0)],
+	).unwrap();
+	assert!(g.node_count() == 3);
+	assert!(g.edge_count() == 3);
+	assert!(g.get_node(2).unwrap().load() == "b");
+	assert!(g.get_edge(3, 1).unwrap().load() == 2.0);
+}
+
+#[test]
+fn digraph_test_neo4j_row_errors() {
+	let missing = row(&["id"], vec![BoltType::from(2i64)]);
+	match build(vec![node(1, "a"), missing], vec![]) {
+		Err(Error::Record { line, .. }) => { assert!(line == 2) }
+		other => { panic!("{:?}", other.map(|g| g.node_count())) }
+	}
+	let wrong = row(&["from", "to", "weight"], vec![BoltType::from(1i64), BoltType::from("b"), BoltType::from(1.0)]);
+	match build(vec![node(1, "a"), node(2, "b")], vec![edge(1, 2, 1.0), wrong]) {
+		Err(Error::Record { line, .. }) => { assert!(line == 2) }
+		other => { panic!("{:?}", other.map(|g| g.node_count())) }
+	}
+	match build(vec![node(1, "a")], vec![edge(1, 7, 1.0)]) {
+		Err(Error::UnknownNode { line, key }) => { assert!(line == 1 && key == "7") }
+		other => { panic!("{:?}", other.map(|g| g.node_count())) }
+	}
+}
+
+#[test]
+fn digraph_test_neo4j_push_query() {
+	assert!(
+		push_query("Page", "id", "pagerank")
+			== "UNWIND $rows AS row MATCH (n:`Page` {`id`: row.key}) SET n.`pagerank` = row.value"
+	);
+	assert!(
+		push_query("A`) DETACH DELETE n //", "k`ey", "p")
+			== "UNWIND $rows AS row MATCH (n:`A``) DETACH DELETE n //` {`k``ey`: row.key}) SET n.`p` = row.value"
+	);
+}
@@ -0,0 +1,53 @@
+use fastgraph::centrality::pagerank;
+use fastgraph::collections::*;
+use fastgraph::core::{Empty, Traverse};
+use fastgraph::parallel::*;
+use fastgraph::pregel::pregel;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+
+fn names(threads: &Threads, g: &Digraph<u32, f64, Empty>) -> HashSet<String> {
+	let seen = Mutex::new(HashSet::new());
+	threads.run(|| {
+		g.par_breadth_first(0, |_| {
+			seen.lock().insert(std::thread::current().name().unwrap_or("").to_string());
+			Traverse::Include
+		});
+		pregel(g, 3, |_, _, _: Vec<f64>| {
+			seen.lock().insert(std::thread::current().name().unwrap_or("").to_string());
+			Vec::new()
+		});
+	});
+	seen.into_inner()
+}
+
+#[test]
+fn parallel_test_pool() {
+	let mut g = Digraph::<u32, f64, Empty>::new();
+	for i in 0..2000 {
+		g.add_node(i, 0.0);
+	}
+	for i in 1..2000 {
+		g.add_edge(0, i, Empty);
+		g.add_edge(i, 0, Empty);
+	}
+	let pool = ThreadPoolBuilder::new()
+		.num_threads(3)
+		.thread_name(|i| format!("graph-{}", i))
+		.build()
+		.unwrap();
+	let threads = Threads::from(pool);
+	assert!(threads.num_threads() == 3);
+	let seen = names(&threads, &g);
+	assert!(!seen.is_empty());
+	assert!(seen.iter().all(|name| name.starts_with("graph-")));
+	// A single thread gives the same result as the global pool.
+	let single = Threads::count(1).unwrap();
+	let a = single.run(|| pagerank(&g, 0.85, 30, 0.0));
+	let b = Threads::default().run(|| pagerank(&g, 0.85, 30, 0.0));
+	let a: HashMap<u32, f64> = a.iter().map(|(n, s)| (*n.key(), *s)).collect();
+	for (node, score) in b.iter() {
+		assert!((a[node.key()] - score).abs() < 1e-12);
+	}
+	assert!(!names(&Threads::Global, &g).iter().any(|name| name.starts_with("graph-")));
+}
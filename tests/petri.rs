@@ -0,0 +1,79 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::petri::*;
+
+// Build a net from transitions given as the places they consume from and
+// produce into, with places named by lowercase and transitions by uppercase
+// keys.
+fn net(transitions: &[(&'static str, &[&'static str], &[&'static str])], marking: &[(&str, u64)]) -> PetriNet<&'static str> {
+	let mut g = Digraph::<&'static str, Empty, Empty>::new();
+	for (t, inputs, outputs) in transitions.iter() {
+		g.add_node(t, Empty);
+		for p in inputs.iter().chain(outputs.iter()) {
+			g.add_node(p, Empty);
+		}
+		for p in inputs.iter() {
+			g.add_edge(p, t, Empty);
+		}
+		for p in outputs.iter() {
+			g.add_edge(t, p, Empty);
+		}
+	}
+	PetriNet::new(
+		&g,
+		|node| match node.key().chars().next().unwrap().is_uppercase() {
+			true => { None }
+			false => { Some(marking.iter().find(|(p, _)| p == node.key()).map_or(0, |(_, m)| *m)) }
+		},
+		|_| 1,
+	)
+}
+
+#[test]
+fn digraph_test_petri_mutex() {
+	let mut net = net(
+		&[
+			("ENTER_A", &["idle_a", "mutex"], &["crit_a"]),
+			("LEAVE_A", &["crit_a"], &["idle_a", "mutex"]),
+			("ENTER_B", &["idle_b", "mutex"], &["crit_b"]),
+			("LEAVE_B", &["crit_b"], &["idle_b", "mutex"]),
+		],
+		&[("idle_a", 1), ("idle_b", 1), ("mutex", 1)],
+	);
+	assert!(net.enabled().len() == 2);
+	for _ in 0..100 {
+		assert!(net.step() > 0);
+		let tokens = |p| net.tokens(&p).unwrap();
+		assert!(tokens("mutex") + tokens("crit_a") + tokens("crit_b") == 1);
+	}
+	assert!(net.marking().len() == 5);
+	assert!(net.tokens(&"ENTER_A").is_none() && net.tokens(&"nowhere").is_none());
+}
+
+#[test]
+fn digraph_test_petri_deadlock() {
+	// Two processes that take two locks in opposite orders.
+	let mut net = net(
+		&[
+			("A1", &["a0", "lock1"], &["a1"]),
+			("A2", &["a1", "lock2"], &["a2", "lock1", "lock2"]),
+			("B1", &["b0", "lock2"], &["b1"]),
+			("B2", &["b1", "lock1"], &["b2", "lock1", "lock2"]),
+		],
+		&[("a0", 1), ("b0", 1), ("lock1", 1), ("lock2", 1)],
+	);
+	let mut sequential = net.clone();
+	// Both take their first lock in the same step and wait forever.
+	let run = net.run(10);
+	assert!(run == Run { steps: 1, fired: 2, deadlock: true });
+	assert!(net.is_deadlocked() && net.enabled().is_empty());
+	// One after the other both finish.
+	assert!(!sequential.fire(&"A2"));
+	for t in ["A1", "A2", "B1", "B2"] {
+		assert!(sequential.fire(&t));
+	}
+	assert!(sequential.tokens(&"a2") == Some(1) && sequential.tokens(&"b2") == Some(1));
+	assert!(sequential.is_deadlocked());
+	assert!(sequential.set_tokens(&"a0", 1) && !sequential.set_tokens(&"A1", 1));
+	assert!(sequential.enabled() == ["A1"]);
+}
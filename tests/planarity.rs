@@ -0,0 +1,192 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::planarity::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+fn graph(size: usize, edges: &[(usize, usize)]) -> Ungraph<usize, Empty, Empty> {
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..size {
+		g.add_node(i, Empty);
+	}
+	for (s, t) in edges.iter() {
+		g.add_edge(*s, *t, Empty);
+	}
+	g
+}
+
+fn complete(size: usize) -> Vec<(usize, usize)> {
+	(0..size).flat_map(|i| (i + 1..size).map(move |j| (i, j))).collect()
+}
+
+fn petersen() -> Vec<(usize, usize)> {
+	(0..5).flat_map(|i| vec![(i, (i + 1) % 5), (i, i + 5), (i + 5, (i + 2) % 5 + 5)]).collect()
+}
+
+// Planar triangulation built by repeatedly splitting a random face with a
+// new node.
+fn triangulation(size: usize, rng: &mut StdRng) -> Vec<(usize, usize)> {
+	let mut edges = vec![(0, 1), (1, 2), (2, 0)];
+	let mut faces = vec![(0, 1, 2), (0, 2, 1)];
+	for v in 3..size {
+		let (a, b, c) = faces.swap_remove(rng.gen_range(0..faces.len()));
+		edges.extend(vec![(a, v), (b, v), (c, v)]);
+		faces.extend(vec![(a, b, v), (b, c, v), (c, a, v)]);
+	}
+	edges
+}
+
+fn check_embedding(g: &Ungraph<usize, Empty, Empty>, edges: usize) {
+	let embedding = match planarity(g) {
+		Planarity::Planar(embedding) => embedding,
+		Planarity::NonPlanar(_) => panic!("expected a planar graph"),
+	};
+	for node in g.nodes() {
+		let mut rotation = embedding.rotation(node.key()).unwrap().to_vec();
+		let mut neighbors: Vec<usize> = node
+			.outbound()
+			.iter()
+			.map(|e| *e.target().key())
+			.chain(node.inbound().iter().map(|e| *e.upgrade().unwrap().source().key()))
+			.collect();
+		rotation.sort();
+		neighbors.sort();
+		assert_eq!(rotation, neighbors);
+	}
+	// Euler's formula holds for connected graphs only if the embedding is
+	// planar.
+	assert_eq!(embedding.faces().len() + g.node_count(), edges + 2);
+}
+
+fn check_witness(g: &Ungraph<usize, Empty, Empty>) -> Vec<(usize, usize)> {
+	let witness = match planarity(g) {
+		Planarity::NonPlanar(witness) => witness,
+		Planarity::Planar(_) => panic!("expected a non-planar graph"),
+	};
+	for (s, t) in witness.iter() {
+		assert!(g.get_edge(*s, *t).is_some() || g.get_edge(*t, *s).is_some());
+	}
+	let mut degree = HashMap::new();
+	for (s, t) in witness.iter() {
+		*degree.entry(*s).or_insert(0) += 1;
+		*degree.entry(*t).or_insert(0) += 1;
+	}
+	let branches: Vec<usize> = degree.values().cloned().filter(|d| *d > 2).collect();
+	assert!(
+		(branches.len() == 5 && branches.iter().all(|d| *d == 4))
+			|| (branches.len() == 6 && branches.iter().all(|d| *d == 3))
+	);
+	assert!(!is_planar(&graph(g.node_count(), &witness)));
+	witness
+}
+
+#[test]
+fn planarity_small() {
+	assert!(is_planar(&graph(0, &[])));
+	assert!(is_planar(&graph(4, &complete(4))));
+	assert!(!is_planar(&graph(5, &complete(5))));
+	let k33: Vec<(usize, usize)> = (0..3).flat_map(|i| (3..6).map(move |j| (i, j))).collect();
+	assert!(!is_planar(&graph(6, &k33)));
+	assert!(is_planar(&graph(6, &k33[1..])));
+	check_embedding(&graph(4, &complete(4)), 6);
+	assert_eq!(check_witness(&graph(5, &complete(5))).len(), 10);
+	assert_eq!(check_witness(&graph(6, &k33)).len(), 9);
+}
+
+#[test]
+fn planarity_petersen() {
+	let g = graph(10, &petersen());
+	assert!(!is_planar(&g));
+	// The Petersen graph contains a subdivision of K3,3 but not of K5.
+	let witness = check_witness(&g);
+	assert!(witness.len() < 15);
+}
+
+#[test]
+fn planarity_grid() {
+	let size = 20;
+	let mut edges = Vec::new();
+	for i in 0..size {
+		for j in 0..size {
+			if i + 1 < size {
+				edges.push((i * size + j, (i + 1) * size + j));
+			}
+			if j + 1 < size {
+				edges.push((i * size + j, i * size + j + 1));
+			}
+		}
+	}
+	check_embedding(&graph(size * size, &edges), edges.len());
+	// A long path searches deep without overflowing the stack.
+	let path: Vec<(usize, usize)> = (1..100_000).map(|i| (i - 1, i)).collect();
+	check_embedding(&graph(100_000, &path), path.len());
+}
+
+#[test]
+fn planarity_triangulation() {
+	let mut rng = StdRng::seed_from_u64(7);
+	for size in [6, 20, 100, 500] {
+		let edges = triangulation(size, &mut rng);
+		let mut g = graph(size, &edges);
+		check_embedding(&g, edges.len());
+		// A triangulation is maximal, so any new edge breaks planarity.
+		loop {
+			let (s, t) = (rng.gen_range(0..size), rng.gen_range(0..size));
+			if s != t && g.get_edge(s, t).is_none() && g.get_edge(t, s).is_none() {
+				g.add_edge(s, t, Empty);
+				break;
+			}
+		}
+		assert!(!is_planar(&g));
+		if size <= 100 {
+			check_witness(&g);
+		}
+	}
+}
+
+#[test]
+fn planarity_parallel_edges_and_loops() {
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	for (s, t) in complete(4) {
+		g.add_edge(s, t, Empty);
+		g.add_edge(t, s, Empty);
+	}
+	g.add_edge(0, 0, Empty);
+	assert!(is_planar(&g));
+	g.add_node(4, Empty);
+	for i in 0..4 {
+		g.add_edge(4, i, Empty);
+	}
+	assert!(!is_planar(&g));
+}
+
+#[test]
+fn planarity_random() {
+	// Sparse random graphs are planar or not about equally often. Planar
+	// results are checked with Euler's formula and non-planar ones by their
+	// Kuratowski subgraph.
+	let mut rng = StdRng::seed_from_u64(3);
+	let (mut planar, mut non_planar) = (0, 0);
+	for _ in 0..200 {
+		let size = rng.gen_range(6..14);
+		let mut edges: Vec<(usize, usize)> = (1..size).map(|i| (rng.gen_range(0..i), i)).collect();
+		for _ in 0..size {
+			let (s, t) = (rng.gen_range(0..size), rng.gen_range(0..size));
+			if s != t && !edges.contains(&(s, t)) && !edges.contains(&(t, s)) {
+				edges.push((s, t));
+			}
+		}
+		let g = graph(size, &edges);
+		if is_planar(&g) {
+			check_embedding(&g, edges.len());
+			planar += 1;
+		} else {
+			check_witness(&g);
+			non_planar += 1;
+		}
+	}
+	assert!(planar > 20 && non_planar > 20);
+}
@@ -0,0 +1,144 @@
+use fastgraph::collections::*;
+use fastgraph::core::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn postorder_test_tree_sizes() {
+	// A wide and uneven hierarchy where every node weighs its own key.
+	let mut g = Digraph::<u64, u64, Empty>::new();
+	let n = 100_000;
+	for i in 0..n {
+		g.add_node(i, i);
+	}
+	for i in 1..n {
+		let parent = if i % 7 == 0 { i - 1 } else { i / 10 };
+		g.add_edge(parent, i, Empty);
+	}
+	let calls = AtomicUsize::new(0);
+	let total = g.par_fold_postorder(0, |node, children: Vec<u64>| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		node.load() + children.iter().sum::<u64>()
+	});
+	assert!(total == Some(n * (n - 1) / 2));
+	assert!(calls.load(Ordering::Relaxed) == n as usize);
+	let subtree = g.par_fold_postorder(3, |_, children: Vec<u64>| 1 + children.iter().sum::<u64>());
+	let reachable = g.fold_dfs(3, 0, |acc, _, _| acc + 1);
+	assert!(subtree == reachable);
+	assert!(g.par_fold_postorder(n, |_, _: Vec<u64>| 0).is_none());
+}
+
+#[test]
+fn postorder_test_deep_path() {
+	let mut g = Digraph::<u32, Empty, Empty>::new();
+	for i in 0..200_000 {
+		g.add_node(i, Empty);
+	}
+	for i in 1..200_000 {
+		g.add_edge(i - 1, i, Empty);
+	}
+	let depth = g.par_fold_postorder(0, |_, children: Vec<u32>| 1 + children.into_iter().max().unwrap_or(0));
+	assert!(depth == Some(200_000));
+}
+
+#[test]
+fn postorder_test_children_order() {
+	let mut g = Digraph::<u32, Empty, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, Empty);
+	g.add_edge(0, 3, Empty);
+	g.add_edge(0, 2, Empty);
+	let order = g.par_fold_postorder(0, |node, children: Vec<String>| {
+		format!("{}{}", node.key(), children.concat())
+	});
+	assert!(order == Some("0132".to_string()));
+}
+
+#[test]
+fn postorder_test_dag_and_cycles() {
+	// Count the paths from 0 to 5 in a lattice of diamonds, where shared
+	// nodes must be folded once and reused.
+	let mut g = Digraph::<u32, Empty, Empty>::new();
+	for i in 0..6 {
+		g.add_node(i, Empty);
+	}
+	for (s, t) in [(0, 1), (0, 2), (1, 3), (2, 3), (1, 4), (3, 5), (4, 5), (3, 4)] {
+		g.add_edge(s, t, Empty);
+	}
+	let calls = AtomicUsize::new(0);
+	let paths = g.par_fold_postorder(0, |_, children: Vec<u64>| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		match children.is_empty() {
+			true => { 1 }
+			false => { children.iter().sum() }
+		}
+	});
+	assert!(paths == Some(5));
+	assert!(calls.load(Ordering::Relaxed) == 6);
+	// Edges closing a cycle are left out.
+	g.add_edge(5, 0, Empty);
+	g.add_edge(4, 1, Empty);
+	let count = g.par_fold_postorder(0, |_, children: Vec<u64>| {
+		match children.is_empty() {
+			true => { 1 }
+			false => { children.iter().sum() }
+		}
+	});
+	assert!(count.is_some() && count.unwrap() <= 5);
+}
+
+#[test]
+fn postorder_test_undirected_spanning_tree() {
+	let mut g = Ungraph::<u32, Empty, Empty>::new();
+	for i in 0..100 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..100 {
+		g.add_edge(i, (i + 1) % 100, Empty);
+		g.add_edge(i, (i + 7) % 100, Empty);
+	}
+	g.add_node(100, Empty);
+	let size = g.par_fold_postorder(42, |_, children: Vec<usize>| 1 + children.iter().sum::<usize>());
+	assert!(size == Some(100));
+}
+
+#[test]
+fn digraph_test_execute_topological() {
+	// A layered DAG with every node depending on a few nodes of the layer
+	// above it.
+	let mut g = Digraph::<usize, usize, Empty>::new();
+	let (layers, width) = (50, 200);
+	for i in 0..layers * width {
+		g.add_node(i, i % width);
+	}
+	for layer in 1..layers {
+		for j in 0..width {
+			for k in 0..3 {
+				g.add_edge((layer - 1) * width + (j * 7 + k * 13) % width, layer * width + j, Empty);
+			}
+		}
+	}
+	// Every task takes a ticket, which must come after the tickets of its
+	// predecessors.
+	let clock = AtomicUsize::new(0);
+	let tickets = g.execute_topological(|_, data| (data, clock.fetch_add(1, Ordering::SeqCst))).unwrap();
+	assert!(tickets.len() == layers * width);
+	for node in g.nodes() {
+		let (data, ticket) = tickets[node.key()];
+		assert!(data == node.load());
+		for edge in node.outbound().iter() {
+			assert!(tickets[edge.target().key()].1 > ticket);
+		}
+	}
+	// A cycle deep in the graph is found before any task runs.
+	g.add_edge(40 * width + 5, 3, Empty);
+	let ran = AtomicUsize::new(0);
+	let cycle = g.execute_topological(|_, _| ran.fetch_add(1, Ordering::SeqCst)).unwrap_err();
+	assert!(ran.load(Ordering::SeqCst) == 0);
+	for (i, k) in cycle.iter().enumerate() {
+		let next = cycle[(i + 1) % cycle.len()];
+		assert!(g.get_edge(*k, next).is_some());
+	}
+	assert!(cycle.contains(&3));
+}
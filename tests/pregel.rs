@@ -0,0 +1,82 @@
+use fastgraph::centrality::*;
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::pregel::*;
+use rand::Rng;
+
+#[test]
+fn digraph_test_pregel_shortest_paths() {
+	let mut rng = rand::thread_rng();
+	let mut g = Digraph::<usize, f64, f64>::new();
+	for i in 0..300 {
+		g.add_node(i, f64::INFINITY);
+	}
+	for i in 0..300 {
+		for _ in 0..3 {
+			g.add_edge(i, rng.gen_range(0..300), rng.gen_range(1..10) as f64);
+		}
+	}
+	pregel(&g, 1000, |superstep, node, messages: Vec<f64>| {
+		let start = if superstep == 0 && *node.key() == 0 { 0.0 } else { f64::INFINITY };
+		let best = messages.into_iter().fold(start, f64::min);
+		if best >= node.load() {
+			return Vec::new();
+		}
+		node.store(best);
+		node.outbound().iter().map(|e| (*e.target().key(), best + e.load())).collect()
+	});
+	for i in 0..300 {
+		let expected = g.shortest_path(0, i, |e| e.load()).map_or(f64::INFINITY, |(cost, _)| cost);
+		assert!(g.get_node(i).unwrap().load() == expected);
+	}
+}
+
+#[test]
+fn ungraph_test_pregel_components() {
+	let mut g = Ungraph::<usize, usize, Empty>::new();
+	for i in 0..10 {
+		g.add_node(i, i);
+	}
+	for (s, t) in [(0, 1), (1, 2), (2, 3), (5, 6), (7, 6), (8, 9)] {
+		g.add_edge(s, t, Empty);
+	}
+	// Every node adopts the smallest key it hears of.
+	let supersteps = pregel(&g, 100, |superstep, node, messages: Vec<usize>| {
+		let smallest = messages.into_iter().fold(node.load(), usize::min);
+		if superstep > 0 && smallest == node.load() {
+			return Vec::new();
+		}
+		node.store(smallest);
+		node.neighbors(fastgraph::core::Direction::Both).iter().map(|n| (*n.key(), smallest)).collect()
+	});
+	assert!(supersteps > 1 && supersteps < 100);
+	let labels: Vec<usize> = (0..10).map(|i| g.get_node(i).unwrap().load()).collect();
+	assert!(labels == vec![0, 0, 0, 0, 4, 5, 5, 5, 8, 8]);
+}
+
+#[test]
+fn digraph_test_pregel_pagerank() {
+	let mut g = Digraph::<usize, f64, Empty>::new();
+	for i in 0..4 {
+		g.add_node(i, 0.25);
+	}
+	for (s, t) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 0)] {
+		g.add_edge(s, t, Empty);
+	}
+	let damping = 0.85;
+	// Every superstep sends the rank of each node split over its outbound
+	// edges and sums the incoming shares.
+	let supersteps = pregel(&g, 101, |superstep, node, messages: Vec<f64>| {
+		if superstep > 0 {
+			node.store((1.0 - damping) / 4.0 + damping * messages.iter().sum::<f64>());
+		}
+		let out = node.outbound();
+		out.iter().map(|e| (*e.target().key(), node.load() / out.len() as f64)).collect()
+	});
+	assert!(supersteps == 101);
+	for (node, score) in pagerank(&g, damping, 1000, 1e-12) {
+		assert!((node.load() - score).abs() < 1e-9);
+	}
+	// Stops early once nothing is sent.
+	assert!(pregel(&g, 10, |_, _, _: Vec<f64>| Vec::new()) == 1);
+}
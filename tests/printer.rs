@@ -0,0 +1,54 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::printer::Layout;
+
+#[test]
+fn printer_dot() {
+	let mut g = Digraph::<u32, u32, f64>::new();
+	g.add_node(1, 10);
+	g.add_node(2, 20);
+	g.add_edge(1, 2, 0.5);
+	g.add_edge(2, 1, 1.5);
+	let full = g.display().to_string();
+	assert!(full.starts_with("digraph {\n") && full.ends_with("\n}"));
+	assert!(full.contains("\t1 [label = \"1 : 10\"]\n") && full.contains("\t2 -> 1 [label = \"1.5\"]\n"));
+	assert!(full.lines().count() == 6);
+
+	let bare = g.display().with_data(false).to_string();
+	assert!(bare.contains("\t1\n") && bare.contains("\t1 -> 2\n") && !bare.contains("label"));
+	let nodes = g.display().with_edges(false).to_string();
+	assert!(nodes.lines().count() == 4 && !nodes.contains("->"));
+	let one = g.display().max_edges(1).to_string();
+	assert!(one.matches(" -> ").count() == 1 && one.contains("\t// 1 more edges\n"));
+
+	let mut u = Ungraph::<u32, Empty, Empty>::new();
+	u.add_node(1, Empty);
+	u.add_node(2, Empty);
+	u.add_edge(1, 2, Empty);
+	let undirected = u.display().to_string();
+	assert!(undirected.starts_with("graph {\n") && undirected.contains(" -- "));
+}
+
+#[test]
+fn printer_truncated_lines() {
+	let mut g = Digraph::<u32, Empty, u32>::new();
+	for i in 0..1000 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..1000 {
+		for j in 1..4 {
+			g.add_edge(i, (i + j) % 1000, j);
+		}
+	}
+	let text = g.display().layout(Layout::Lines).max_nodes(10).max_edges(29).to_string();
+	let lines: Vec<&str> = text.lines().collect();
+	assert!(lines.len() == 11 && lines[10] == "... 990 more nodes");
+	assert!(lines[..9].iter().all(|l| l.matches(", ").count() == 2 && l.contains(": _ -> ")));
+	assert!(lines[9].ends_with(" ... 1 more"));
+
+	let bare = g.display().layout(Layout::Lines).with_data(false).max_nodes(1).to_string();
+	let first = bare.lines().next().unwrap();
+	assert!(!first.contains('(') && first.split(" -> ").count() == 2);
+	assert!(g.display().layout(Layout::Lines).max_nodes(0).to_string() == "... 1000 more nodes");
+	assert!(g.display().max_nodes(5).to_string().lines().count() == 5 + 15 + 3);
+}
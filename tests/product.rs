@@ -0,0 +1,81 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::product::*;
+
+fn path(n: usize) -> Ungraph<usize, Empty, usize> {
+	let mut g = Ungraph::<usize, Empty, usize>::new();
+	for i in 0..n {
+		g.add_node(i, Empty);
+	}
+	for i in 1..n {
+		g.add_edge(i - 1, i, i);
+	}
+	g
+}
+
+#[test]
+fn ungraph_test_products() {
+	let (a, b) = (path(4), path(3));
+	// Edges of the Cartesian product carry the weight of the edge they follow.
+	let grid: Ungraph<usize, Empty, usize> = cartesian_product(
+		&a,
+		&b,
+		|x, y| (x.key() * 10 + y.key(), Empty),
+		|e, f| e.or(f).unwrap().load(),
+	);
+	assert!(grid.node_count() == 12);
+	assert!(grid.edge_count() == 3 * 3 + 4 * 2);
+	assert!(grid.get_edge(11, 21).unwrap().load() == 2);
+	assert!(grid.get_edge(21, 22).unwrap().load() == 2);
+	assert!(grid.get_edge(11, 22).is_none());
+	let corner = grid.get_node(0).unwrap();
+	assert!(corner.outbound().len() + corner.inbound().len() == 2);
+
+	// Tensor edges follow an edge of both graphs in both orientations.
+	let tensor: Ungraph<usize, Empty, usize> = tensor_product(
+		&a,
+		&b,
+		|x, y| (x.key() * 10 + y.key(), Empty),
+		|e, f| e.unwrap().load() * 10 + f.unwrap().load(),
+	);
+	assert!(tensor.edge_count() == 2 * 3 * 2);
+	assert!(tensor.get_edge(11, 22).unwrap().load() == 22);
+	assert!(tensor.get_edge(12, 21).unwrap().load() == 22);
+	assert!(tensor.get_edge(11, 21).is_none());
+
+	let strong: Ungraph<usize, Empty, usize> = strong_product(
+		&a,
+		&b,
+		|x, y| (x.key() * 10 + y.key(), Empty),
+		|_, _| 0,
+	);
+	assert!(strong.edge_count() == grid.edge_count() + tensor.edge_count());
+	let center = strong.get_node(11).unwrap();
+	assert!(center.outbound().len() + center.inbound().len() == 8);
+}
+
+#[test]
+fn digraph_test_tensor_product() {
+	// Product of two cycles of coprime lengths is a single cycle.
+	let mut a = Digraph::<usize, Empty, Empty>::new();
+	let mut b = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..2 {
+		a.add_node(i, Empty);
+	}
+	for i in 0..2 {
+		a.add_edge(i, (i + 1) % 2, Empty);
+	}
+	for i in 0..3 {
+		b.add_node(i, Empty);
+	}
+	for i in 0..3 {
+		b.add_edge(i, (i + 1) % 3, Empty);
+	}
+	let g: Digraph<usize, Empty, Empty> = tensor_product(&a, &b, |x, y| (x.key() * 3 + y.key(), Empty), |_, _| Empty);
+	assert!(g.edge_count() == 6);
+	let order = g.fold_bfs(0, Vec::new(), |mut acc, node, _| {
+		acc.push(*node.key());
+		acc
+	}).unwrap();
+	assert!(order.len() == 6);
+}
@@ -0,0 +1,87 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::query::*;
+use fastgraph::typed::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type Tag = Typed<u8, Empty>;
+
+fn sorted(mut rows: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+	rows.sort();
+	rows
+}
+
+#[test]
+fn digraph_test_query() {
+	let mut rng = StdRng::seed_from_u64(8);
+	let mut g = Digraph::<usize, Empty, Tag>::new();
+	for i in 0..30 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..120 {
+		g.add_edge(rng.gen_range(0..30), rng.gen_range(0..30), Typed::new(rng.gen_range(0..3), Empty));
+	}
+	let tag = |a: usize, b: usize| g.get_edge(a, b).map(|e| e.load().tag);
+	// A path of two edges tagged 0 and 1 that closes with any edge back.
+	let query = Query::new()
+		.pattern(Slot::var("a"), Slot::Value(0), Slot::var("b"))
+		.pattern(Slot::var("b"), Slot::Value(1), Slot::var("c"))
+		.pattern(Slot::var("c"), Slot::var("t"), Slot::var("a"));
+	let rows: Vec<Vec<usize>> = query
+		.run(&g)
+		.iter()
+		.map(|b| vec![b.nodes["a"], b.nodes["b"], b.nodes["c"], *b.tag("t").unwrap() as usize])
+		.collect();
+	let mut expected = Vec::new();
+	for a in 0..30 {
+		for b in 0..30 {
+			for c in 0..30 {
+				if let (Some(0), Some(1), Some(t)) = (tag(a, b), tag(b, c), tag(c, a)) {
+					expected.push(vec![a, b, c, t as usize]);
+				}
+			}
+		}
+	}
+	assert!(!expected.is_empty());
+	assert!(sorted(rows) == sorted(expected));
+	// A fixed subject and a repeated variable.
+	let node = (0..30).find(|a| (0..30).any(|b| tag(*a, b) == Some(0))).unwrap();
+	let query = Query::new()
+		.pattern(Slot::Value(node), Slot::Value(0), Slot::var("x"))
+		.pattern(Slot::var("x"), Slot::var("t"), Slot::var("x"));
+	for b in query.run(&g) {
+		let x = b.nodes["x"];
+		assert!(tag(node, x) == Some(0) && tag(x, x).is_some());
+	}
+	let loops = (0..30).filter(|x| tag(node, *x) == Some(0) && tag(*x, *x).is_some()).count();
+	assert!(query.run(&g).len() == loops);
+	// Queries that can't match have no results, and no patterns one.
+	let none = Query::new().pattern(Slot::var("a"), Slot::Value(7), Slot::var("b"));
+	assert!(none.run(&g).is_empty());
+	assert!(Query::<usize, u8>::new().run(&g).len() == 1);
+}
+
+#[test]
+fn ungraph_test_query() {
+	let mut g = Ungraph::<&str, Empty, Typed<&str, Empty>>::new();
+	for key in ["ada", "bob", "cy"] {
+		g.add_node(key, Empty);
+	}
+	g.add_edge("ada", "bob", Typed::new("knows", Empty));
+	g.add_edge("cy", "bob", Typed::new("knows", Empty));
+	g.add_edge("cy", "cy", Typed::new("knows", Empty));
+	let parse = |text| Query::parse(text, |k| g.get_node(k).map(|_| k), Some);
+	// Edges match in either orientation and loops once.
+	let query = parse("?a knows bob.").unwrap();
+	let mut found: Vec<&str> = query.run(&g).iter().map(|b| b.nodes["a"]).collect();
+	found.sort_unstable();
+	assert!(found == vec!["ada", "cy"]);
+	assert!(parse("?a knows ?b").unwrap().run(&g).len() == 5);
+	assert!(parse("?a knows ?a").unwrap().run(&g).len() == 1);
+	let query = parse("?a knows ?b . ?b knows ?c . ?c ?t ada").unwrap();
+	assert!(query.patterns.len() == 3);
+	assert!(query.run(&g).len() == 3);
+	assert!(parse("?a knows").is_err());
+	assert!(parse("?a knows ?b ?c knows ?d").is_err());
+	assert!(parse("?a knows dan").is_err());
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "rdf")]
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::io::{rdf::*, Error};
+use fastgraph::query::*;
+use fastgraph::typed::*;
+
+fn iri(s: &str) -> Term {
+	Term::Iri(s.to_string())
+}
+
+fn literal(value: &str, datatype: Option<&str>, language: Option<&str>) -> Term {
+	Term::Literal {
+		value: value.to_string(),
+		datatype: datatype.map(|d| format!("http://www.w3.org/2001/XMLSchema#{}", d)),
+		language: language.map(|l| l.to_string()),
+	}
+}
+
+#[test]
+fn test_parse_ntriples() {
+	let text = r#"
+		# A comment.
+		<http://ex.org/a> <http://ex.org/p> <http://ex.org/b> .
+		<http://ex.org/a> <http://ex.org/name> "A \"quoted\"\tnameé" .
+		_:x <http://ex.org/p> "chat"@fr .
+		_:x <http://ex.org/age> "7"^^<http://www.w3.org/2001/XMLSchema#integer> . # Trailing.
+	"#;
+	let triples = parse_rdf(text.as_bytes()).unwrap();
+	assert!(triples.len() == 4);
+	assert!(triples[0] == Triple { subject: iri("http://ex.org/a"), predicate: iri("http://ex.org/p"), object: iri("http://ex.org/b") });
+	assert!(triples[1].object == literal("A \"quoted\"\tnameé", None, None));
+	assert!(triples[2].subject == Term::Blank("x".to_string()));
+	assert!(triples[2].object == literal("chat", None, Some("fr")));
+	assert!(triples[3].object == literal("7", Some("integer"), None));
+	// Terms are displayed in N-Triples syntax, so the triples can be
+	// written back and read again.
+	let written: String = triples
+		.iter()
+		.map(|t| format!("{} {} {} .\n", t.subject, t.predicate, t.object))
+		.collect();
+	assert!(parse_rdf(written.as_bytes()).unwrap() == triples);
+}
+
+#[test]
+fn test_parse_turtle() {
+	let text = r#"
+		@base <http://ex.org/> .
+		@prefix : <http://ex.org/> .
+		PREFIX foaf: <http://xmlns.com/foaf/0.1/>
+
+		:ada a foaf:Person ;
+			foaf:knows :bob, <carl> ;
+			foaf:age 36 ;
+			:ratio -1.5 ;
+			:mass 2e3 ;
+			:active true ;
+			:bio """Lines
+and "quotes".""" ;
+			:address [ :city 'Paris' ; :zip "75001" ] ;
+			:tags ( :a :b ) ;
+			:empty () .
+		[] foaf:name "nobody" .
+		:bob foaf:knows :ada.
+	"#;
+	let triples = parse_rdf(text.as_bytes()).unwrap();
+	let object = |subject: &Term, predicate: &str| -> Vec<Term> {
+		triples
+			.iter()
+			.filter(|t| &t.subject == subject && t.predicate == iri(predicate))
+			.map(|t| t.object.clone())
+			.collect()
+	};
+	let ada = iri("http://ex.org/ada");
+	let foaf = |name: &str| format!("http://xmlns.com/foaf/0.1/{}", name);
+	assert!(object(&ada, "http://www.w3.org/1999/02/22-rdf-syntax-ns#type") == vec![iri(&foaf("Person"))]);
+	assert!(object(&ada, &foaf("knows")) == vec![iri("http://ex.org/bob"), iri("http://ex.org/carl")]);
+	assert!(object(&ada, &foaf("age")) == vec![literal("36", Some("integer"), None)]);
+	assert!(object(&ada, "http://ex.org/ratio") == vec![literal("-1.5", Some("decimal"), None)]);
+	assert!(object(&ada, "http://ex.org/mass") == vec![literal("2e3", Some("double"), None)]);
+	assert!(object(&ada, "http://ex.org/active") == vec![literal("true", Some("boolean"), None)]);
+	assert!(object(&ada, "http://ex.org/bio") == vec![literal("Lines\nand \"quotes\".", None, None)]);
+	let address = object(&ada, "http://ex.org/address");
+	assert!(matches!(address[..], [Term::Blank(_)]));
+	assert!(object(&address[0], "http://ex.org/city") == vec![literal("Paris", None, None)]);
+	// The collection is a list of two cells.
+	let rdf = |name: &str| format!("http://www.w3.org/1999/02/22-rdf-syntax-ns#{}", name);
+	let head = object(&ada, "http://ex.org/tags");
+	assert!(object(&head[0], &rdf("first")) == vec![iri("http://ex.org/a")]);
+	let rest = object(&head[0], &rdf("rest"));
+	assert!(object(&rest[0], &rdf("first")) == vec![iri("http://ex.org/b")]);
+	assert!(object(&rest[0], &rdf("rest")) == vec![iri(&rdf("nil"))]);
+	assert!(object(&ada, "http://ex.org/empty") == vec![iri(&rdf("nil"))]);
+	assert!(triples.iter().any(|t| t.object == literal("nobody", None, None) && matches!(t.subject, Term::Blank(_))));
+	assert!(object(&iri("http://ex.org/bob"), &foaf("knows")) == vec![ada.clone()]);
+	assert!(triples.len() == 19);
+}
+
+#[test]
+fn test_parse_rdf_errors() {
+	let line = |text: &str| match parse_rdf(text.as_bytes()) {
+		Err(Error::Record { line, .. }) => { line }
+		_ => { 0 }
+	};
+	assert!(line("<a> <b> <c> .\n<a> <b> .\n") == 2);
+	assert!(line("<a> <b> <c>\n") == 2);
+	assert!(line("\n\nfoo:a <b> <c> .") == 3);
+	assert!(line("<a> <b> \"open .\n") == 2);
+	assert!(line("<a> <b> <c d> .") == 1);
+}
+
+#[test]
+fn digraph_test_read_rdf() {
+	let text = r#"
+		@prefix : <http://ex.org/> .
+		:ada :knows :bob ; :likes :bob ; :name "Ada" .
+		:bob :knows :ada ; :name "Bob" .
+		:carl :name "Ada" .
+	"#;
+	let g: Digraph<Term, Empty, MultiTyped<Term, Empty>> = read_rdf(text.as_bytes(), |_| Empty, |p| MultiTyped::new(p.to_vec(), Empty)).unwrap();
+	// Literals are shared nodes and the predicates between two nodes are the
+	// tags of one edge.
+	assert!(g.node_count() == 5);
+	assert!(g.edge_count() == 5);
+	let edge = g.get_edge(iri("http://ex.org/ada"), iri("http://ex.org/bob")).unwrap();
+	assert!(edge.load().tags() == [iri("http://ex.org/knows"), iri("http://ex.org/likes")]);
+	assert!(g.get_node(literal("Ada", None, None)).unwrap().inbound().len() == 2);
+
+	let path = std::env::temp_dir().join("fastgraph_rdf.ttl");
+	std::fs::write(&path, text).unwrap();
+	let h: Digraph<Term, Empty, MultiTyped<Term, Empty>> = load_rdf(&path, |_| Empty, |p| MultiTyped::new(p.to_vec(), Empty)).unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert!(h.node_count() == 5 && h.edge_count() == 5);
+}
+
+#[test]
+fn digraph_test_query_rdf() {
+	let text = r#"
+		@prefix : <http://ex.org/> .
+		:ada :knows :bob ; :name "Ada" .
+		:bob :knows :cy ; :name "Bob" .
+		:cy :name "Cy" .
+	"#;
+	let g: Digraph<Term, Empty, MultiTyped<Term, Empty>> = read_rdf(text.as_bytes(), |_| Empty, |p| MultiTyped::new(p.to_vec(), Empty)).unwrap();
+	let term = |name: &str| Some(iri(&name.replace(':', "http://ex.org/")));
+	let query = Query::parse("?a :knows ?b . ?b :knows ?c . ?c :name ?name", term, term).unwrap();
+	let results = query.run(&g);
+	assert!(results.len() == 1);
+	assert!(results[0].get("a") == Some(&iri("http://ex.org/ada")));
+	assert!(results[0].get("name") == Some(&literal("Cy", None, None)));
+}
+
+#[test]
+fn ungraph_test_read_rdf_predicates() {
+	// Every predicate between two nodes is kept once, in both directions of
+	// an undirected graph.
+	let text = r#"
+		@prefix : <http://ex.org/> .
+		:ada :knows :bob ; :likes :bob ; :knows :bob .
+		:bob :likes :ada .
+	"#;
+	let g: Ungraph<Term, Empty, MultiTyped<Term, Empty>> = read_rdf(text.as_bytes(), |_| Empty, |p| MultiTyped::new(p.to_vec(), Empty)).unwrap();
+	assert!(g.edge_count() == 1);
+	let edge = g.get_edge(iri("http://ex.org/ada"), iri("http://ex.org/bob")).unwrap();
+	assert!(edge.load().tags().len() == 2);
+	let term = |name: &str| Some(iri(&name.replace(':', "http://ex.org/")));
+	let query = Query::parse("?a :likes ?b", term, term).unwrap();
+	assert!(query.run(&g).len() == 2);
+	let query = Query::parse(":ada ?p :bob", term, term).unwrap();
+	let mut tags: Vec<Term> = query.run(&g).into_iter().map(|b| b.tag("p").unwrap().clone()).collect();
+	tags.sort();
+	assert!(tags == vec![iri("http://ex.org/knows"), iri("http://ex.org/likes")]);
+}
@@ -0,0 +1,49 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::reliability::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn digraph_test_reliability() {
+	// A chain 0 -> 1 -> 2 of edges that fail a fifth of the time.
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..3 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, 0.2);
+	g.add_edge(1, 2, 0.2);
+	let mut rng = StdRng::seed_from_u64(5);
+	let p = reliability(&g, &[0, 2], 20_000, |e| e.load(), &mut rng).unwrap();
+	assert!((p - 0.64).abs() < 0.02);
+	// Direction matters: nothing reaches 0.
+	assert!(reliability(&g, &[2, 0], 100, |e| e.load(), &mut rng).unwrap() == 0.0);
+	assert!(reliability(&g, &[0, 1, 2], 100, |_| 0.0, &mut rng).unwrap() == 1.0);
+	assert!(reliability(&g, &[0, 9], 100, |_| 0.0, &mut rng).is_none());
+
+	// The estimate only depends on the state of the generator.
+	let a = reliability(&g, &[0, 2], 1000, |e| e.load(), &mut StdRng::seed_from_u64(9));
+	let b = reliability(&g, &[0, 2], 1000, |e| e.load(), &mut StdRng::seed_from_u64(9));
+	assert!(a == b);
+}
+
+#[test]
+fn ungraph_test_percolation() {
+	// A complete graph stays in one piece until most edges fail.
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..30 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..30 {
+		for j in i + 1..30 {
+			g.add_edge(i, j, Empty);
+		}
+	}
+	let mut rng = StdRng::seed_from_u64(3);
+	let intact = percolation(&g, 200, |_| 0.5, &mut rng);
+	assert!(intact.largest > 29.0);
+	let shattered = percolation(&g, 200, |_| 1.0, &mut rng);
+	assert!(shattered == Percolation { components: 30.0, largest: 1.0, mean_size: 1.0 });
+	let partial = percolation(&g, 200, |_| 0.97, &mut rng);
+	assert!(partial.components > 1.0 && partial.components < 30.0);
+	assert!(partial.mean_size >= 1.0 && partial.mean_size <= partial.largest);
+}
@@ -0,0 +1,477 @@
+use fastgraph::core::*;
+use fastgraph::collections::*;
+use fastgraph::contraction::*;
+use fastgraph::landmarks::*;
+use fastgraph::core::Empty;
+use rand::Rng;
+
+fn random_digraph(size: usize, degree: usize) -> Digraph<usize, Empty, f64> {
+	let mut rng = rand::thread_rng();
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..size {
+		g.add_node(i, Empty);
+	}
+	for i in 0..size {
+		for _ in 0..degree {
+			g.add_edge(i, rng.gen_range(0..size), rng.gen_range(1..10) as f64);
+		}
+	}
+	g
+}
+
+fn path_cost<N, E>(path: &Path<usize, N, E>, cost: impl Fn(&E) -> f64) -> f64
+where
+	N: Clone + std::fmt::Debug + std::fmt::Display + Sync + Send,
+	E: Clone + std::fmt::Debug + std::fmt::Display + Sync + Send,
+{
+	path.iter().map(|e| cost(&e.upgrade().unwrap().load())).sum()
+}
+
+#[test]
+fn digraph_test_shortest_path() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..5 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 3, 1.0);
+	g.add_edge(3, 4, 1.0);
+	g.add_edge(1, 4, 5.0);
+	let (cost, path) = g.shortest_path(1, 4, |e| e.load()).unwrap();
+	assert!(cost == 3.0);
+	assert!(path.len() == 3);
+	assert!(path[2].upgrade().unwrap().target().key() == &4);
+	assert!(g.shortest_path(4, 1, |e| e.load()).is_none());
+	assert!(g.shortest_path(1, 1, |e| e.load()).unwrap().1.is_empty());
+}
+
+#[test]
+fn digraph_test_contraction_hierarchy() {
+	let g = random_digraph(200, 3);
+	let ch = ContractionHierarchy::new(&g, |e| e.load());
+	let mut rng = rand::thread_rng();
+	for _ in 0..200 {
+		let s = rng.gen_range(0..200);
+		let t = rng.gen_range(0..200);
+		let expected = g.shortest_path(s, t, |e| e.load());
+		let result = ch.shortest_path(&s, &t);
+		match expected {
+			Some((cost, _)) => {
+				let (ch_cost, path) = result.unwrap();
+				assert!(ch_cost == cost);
+				assert!(path_cost(&path, |e| *e) == cost);
+				if !path.is_empty() {
+					assert!(path[0].upgrade().unwrap().source().key() == &s);
+					assert!(path[path.len() - 1].upgrade().unwrap().target().key() == &t);
+				}
+			}
+			None => { assert!(result.is_none()) }
+		}
+	}
+}
+
+#[test]
+fn ungraph_test_contraction_hierarchy() {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..10 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..9 {
+		g.add_edge(i, i + 1, 1.0);
+	}
+	g.add_edge(0, 9, 20.0);
+	let ch = ContractionHierarchy::new(&g, |e| e.load());
+	assert!(ch.shortest_path(&9, &0).unwrap().0 == 9.0);
+	assert!(ch.shortest_path(&3, &7).unwrap().1.len() == 4);
+	assert!(ch.shortest_path(&3, &42).is_none());
+}
+
+#[test]
+fn digraph_test_shortest_path_turns() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..6 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 3, 1.0);
+	g.add_edge(2, 4, 1.0);
+	g.add_edge(4, 5, 1.0);
+	g.add_edge(5, 2, 1.0);
+	// Turning from 1 -> 2 onto 2 -> 3 is forbidden, so the path has to loop
+	// through 4 and 5 to arrive at 2 again.
+	let forbidden = |a: &std::sync::Arc<Edge<usize, Empty, f64>>, b: &std::sync::Arc<Edge<usize, Empty, f64>>| {
+		match (a.source().key(), b.target().key()) {
+			(1, 3) => None,
+			_ => Some(0.0),
+		}
+	};
+	let (cost, path) = g.shortest_path_turns(1, 3, |e| e.load(), forbidden).unwrap();
+	assert!(cost == 5.0);
+	let keys: Vec<usize> = path.iter().map(|e| *e.upgrade().unwrap().target().key()).collect();
+	assert!(keys == vec![2, 4, 5, 2, 3]);
+	assert!(g.shortest_path_turns(3, 1, |e| e.load(), forbidden).is_none());
+}
+
+#[test]
+fn digraph_test_shortest_path_turns_without_penalties() {
+	let g = random_digraph(200, 3);
+	let mut rng = rand::thread_rng();
+	for _ in 0..100 {
+		let s = rng.gen_range(0..200);
+		let t = rng.gen_range(0..200);
+		let expected = g.shortest_path(s, t, |e| e.load()).map(|(cost, _)| cost);
+		let result = g.shortest_path_turns(s, t, |e| e.load(), |_, _| Some(0.0));
+		assert!(result.map(|(cost, _)| cost) == expected);
+	}
+}
+
+#[test]
+fn ungraph_test_shortest_path_turns() {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 1..5 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 3, 1.0);
+	g.add_edge(3, 4, 1.0);
+	g.add_edge(1, 4, 1.0);
+	// Every turn at 2 costs 10, so going around the other way is cheaper.
+	let (cost, path) = g.shortest_path_turns(1, 3, |e| e.load(), |a, b| {
+		let at = if a.target() == b.source() || a.target() == b.target() { a.target() } else { a.source() };
+		if at.key() == &2 { Some(10.0) } else { Some(0.0) }
+	}).unwrap();
+	assert!(cost == 2.0);
+	assert!(path.len() == 2);
+	assert!(path.iter().any(|e| e.upgrade().unwrap().source().key() == &1 && e.upgrade().unwrap().target().key() == &4));
+}
+
+#[test]
+fn digraph_test_shortest_path_regions() {
+	// A constant cost for every node adds to the cost of every edge.
+	let g = random_digraph(200, 3);
+	let mut rng = rand::thread_rng();
+	for _ in 0..100 {
+		let s = rng.gen_range(0..200);
+		let t = rng.gen_range(0..200);
+		let expected = g.shortest_path(s, t, |e| e.load() + 2.0).map(|(cost, _)| cost);
+		let result = g.shortest_path_regions(s, t, |e| e.load(), |_| 2.0);
+		assert!(result.map(|(cost, _)| cost) == expected);
+	}
+}
+
+#[test]
+fn ungraph_test_shortest_path_regions() {
+	// A row of cheap road around a field of mud, with a wall of rock.
+	let mut g = Ungraph::<usize, f64, Empty>::new();
+	let terrain = [
+		0.0, 0.0, 0.0, 0.0, 0.0,
+		0.0, 5.0, 5.0, 5.0, 0.0,
+		0.0, f64::INFINITY, 5.0, 5.0, 0.0,
+	];
+	for (i, t) in terrain.iter().enumerate() {
+		g.add_node(i, *t);
+	}
+	for i in 0..15 {
+		if i % 5 < 4 {
+			g.add_edge(i, i + 1, Empty);
+		}
+		if i + 5 < 15 {
+			g.add_edge(i, i + 5, Empty);
+		}
+	}
+	let (cost, path) = g.shortest_path_regions(10, 14, |_| 1.0, |n| n.load()).unwrap();
+	// Up the left side, along the road and down the right side.
+	assert!(cost == 8.0);
+	assert!(path.len() == 8);
+	// Rock can't be entered, but a path can start on it.
+	assert!(g.shortest_path_regions(5, 11, |_| 1.0, |n| n.load()).is_none());
+	assert!(g.shortest_path_regions(11, 10, |_| 1.0, |n| n.load()).unwrap().0 == 1.0);
+}
+
+#[test]
+fn digraph_test_flow_field() {
+	let g = random_digraph(200, 3);
+	let targets = [0, 1, 2];
+	let field = g.flow_field(&targets, |e| e.load());
+	for s in 0..200 {
+		let expected = targets
+			.iter()
+			.filter_map(|t| g.shortest_path(s, *t, |e| e.load()).map(|(cost, _)| cost))
+			.reduce(f64::min);
+		assert!(field.get(&s).map(|(cost, _)| *cost) == expected);
+		// Following the field leads to a target at the same cost.
+		let mut at = s;
+		let mut total = 0.0;
+		while let Some((_, Some((edge, next)))) = field.get(&at) {
+			assert!(edge.source().key() == &at && edge.target() == *next);
+			total += edge.load();
+			at = *next.key();
+		}
+		if let Some(cost) = expected {
+			assert!(targets.contains(&at) && total == cost);
+		}
+	}
+}
+
+#[test]
+fn ungraph_test_flow_field() {
+	// Agents on a grid with a wall in the middle head for the nearest exit.
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..25 {
+		if i % 5 != 2 || i == 22 {
+			g.add_node(i, Empty);
+		}
+	}
+	for i in 0..25 {
+		if i % 5 < 4 {
+			g.add_edge(i, i + 1, 1.0);
+		}
+		if i + 5 < 25 {
+			g.add_edge(i, i + 5, 1.0);
+		}
+	}
+	let field = g.flow_field(&[0, 99], |e| e.load());
+	assert!(field.len() == 21);
+	assert!(field[&0].1.is_none());
+	// Around the bottom of the wall and up the left side.
+	assert!(field[&4].0 == 12.0);
+	let step = field[&4].1.as_ref().unwrap();
+	assert!(field[step.1.key()].0 == 11.0);
+	assert!(step.0.load() == 1.0);
+}
+
+#[test]
+fn digraph_test_all_pairs() {
+	let g = random_digraph(60, 2);
+	let expected = fastgraph::shortest_path::all_pairs(&g, |e| e.load());
+	for (s, t, cost) in expected.iter() {
+		match g.shortest_path(*s, *t, |e| e.load()) {
+			Some((c, _)) => { assert!((c - cost).abs() < 1e-9) }
+			None => { assert!(s == t && *cost == 0.0) }
+		}
+	}
+	let reachable = (0..60)
+		.flat_map(|s| (0..60).map(move |t| (s, t)))
+		.filter(|(s, t)| s == t || g.shortest_path(*s, *t, |e| e.load()).is_some())
+		.count();
+	assert!(expected.len() == reachable);
+	// Resumed in batches of seven sources the rows come in the same order.
+	let mut checkpoint = fastgraph::checkpoint::Checkpoint::new();
+	let mut runs = 1;
+	let distances = loop {
+		#[cfg(feature = "serde")]
+		{
+			let saved = serde_json::to_string(&checkpoint).unwrap();
+			checkpoint = serde_json::from_str(&saved).unwrap();
+		}
+		match fastgraph::shortest_path::all_pairs_resume(&g, &mut checkpoint, 7, |e| e.load()) {
+			Some(distances) => { break distances }
+			None => { runs += 1 }
+		}
+	};
+	assert!(runs == 9 && checkpoint.finished());
+	assert!(distances == expected);
+}
+
+#[test]
+fn digraph_test_pareto_paths() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..6 {
+		g.add_node(i, Empty);
+	}
+	// Criteria are the price stored in the edge and the amount of edges.
+	g.add_edge(1, 5, 9.0);
+	g.add_edge(1, 2, 3.0);
+	g.add_edge(2, 5, 3.0);
+	g.add_edge(1, 3, 1.0);
+	g.add_edge(3, 4, 1.0);
+	g.add_edge(4, 5, 1.0);
+	// Dominated by 1 -> 3 -> 4 -> 5 in both criteria.
+	g.add_edge(2, 3, 5.0);
+	let paths = g.pareto_paths(1, 5, |e| [e.load(), 1.0]);
+	let costs: Vec<[f64; 2]> = paths.iter().map(|(c, _)| *c).collect();
+	assert!(costs == vec![[3.0, 3.0], [6.0, 2.0], [9.0, 1.0]]);
+	for (c, path) in paths.iter() {
+		assert!(path.len() as f64 == c[1]);
+		assert!(path_cost(path, |e| *e) == c[0]);
+	}
+	assert!(g.pareto_paths(5, 1, |e| [e.load(), 1.0]).is_empty());
+}
+
+#[test]
+fn digraph_test_pareto_paths_frontier() {
+	let g = random_digraph(100, 3);
+	let mut rng = rand::thread_rng();
+	for _ in 0..50 {
+		let s = rng.gen_range(0..100);
+		let t = rng.gen_range(0..100);
+		let paths = g.pareto_paths(s, t, |e| [e.load(), 1.0]);
+		match g.shortest_path(s, t, |e| e.load()) {
+			Some((cost, _)) => {
+				// The cheapest path is on the frontier and no path dominates
+				// another.
+				assert!(paths[0].0[0] == cost);
+				for (i, (a, _)) in paths.iter().enumerate() {
+					for (b, _) in paths[i + 1..].iter() {
+						assert!(a[0] < b[0] && a[1] > b[1]);
+					}
+				}
+			}
+			None => { assert!(paths.is_empty()) }
+		}
+	}
+}
+
+#[test]
+fn digraph_test_earliest_arrival() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 1..4 {
+		g.add_node(i, Empty);
+	}
+	// A highway through 2 that is congested between 8 and 10 and a side road
+	// that always takes 4.5.
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(2, 3, 1.0);
+	g.add_edge(1, 3, 4.5);
+	let duration = |e: &std::sync::Arc<Edge<usize, Empty, f64>>, time: f64| {
+		if e.load() == 1.0 && (8.0..10.0).contains(&time) { 4.0 } else { e.load() }
+	};
+	let (arrival, path) = g.earliest_arrival(1, 3, 6.0, duration).unwrap();
+	assert!(arrival == 8.0);
+	assert!(path.len() == 2);
+	let (arrival, path) = g.earliest_arrival(1, 3, 8.0, duration).unwrap();
+	assert!(arrival == 12.5);
+	assert!(path.len() == 1);
+	assert!(g.earliest_arrival(3, 1, 0.0, duration).is_none());
+}
+
+#[test]
+fn digraph_test_earliest_arrival_constant() {
+	let g = random_digraph(200, 3);
+	let mut rng = rand::thread_rng();
+	for _ in 0..100 {
+		let s = rng.gen_range(0..200);
+		let t = rng.gen_range(0..200);
+		let expected = g.shortest_path(s, t, |e| e.load()).map(|(cost, _)| cost + 100.0);
+		let result = g.earliest_arrival(s, t, 100.0, |e, _| e.load()).map(|(arrival, _)| arrival);
+		assert!(result == expected);
+	}
+}
+
+#[test]
+fn digraph_test_par_shortest_paths() {
+	let g = random_digraph(1000, 4);
+	let mut rng = rand::thread_rng();
+	let pairs: Vec<(usize, usize)> = (0..200).map(|_| (rng.gen_range(0..1000), rng.gen_range(0..1000))).collect();
+	let cost = |e: &std::sync::Arc<Edge<usize, Empty, f64>>| e.load();
+	// Traversals that lock nodes run at the same time as the queries.
+	let (batch, _) = rayon::join(
+		|| g.par_shortest_paths(&pairs, cost),
+		|| (0..20).for_each(|i| { g.breadth_first(i, |_| Traverse::Include); }),
+	);
+	assert!(batch.len() == pairs.len());
+	for ((s, t), res) in pairs.iter().zip(batch) {
+		let expected = g.shortest_path(*s, *t, cost).map(|(c, _)| c);
+		assert!(res.as_ref().map(|(c, _)| *c) == expected);
+		if let Some((c, path)) = res {
+			assert!(path_cost(&path, |w| *w) == c);
+			match path.first() {
+				Some(e) => { assert!(e.upgrade().unwrap().source().key() == s) }
+				None => { assert!(s == t) }
+			}
+		}
+	}
+}
+
+#[test]
+fn digraph_test_landmarks() {
+	let g = random_digraph(300, 3);
+	let landmarks = Landmarks::new(&g, 8, |e| e.load());
+	assert!(landmarks.landmarks().len() == 8);
+	assert!(landmarks.is_fresh(&g));
+	let mut rng = rand::thread_rng();
+	for _ in 0..200 {
+		let s = rng.gen_range(0..300);
+		let t = rng.gen_range(0..300);
+		let expected = g.shortest_path(s, t, |e| e.load());
+		let result = landmarks.shortest_path(&g, s, t, |e| e.load());
+		match expected {
+			Some((cost, _)) => {
+				let (alt_cost, path) = result.unwrap();
+				assert!(alt_cost == cost);
+				assert!(path_cost(&path, |e| *e) == cost);
+				assert!(landmarks.lower_bound(&s, &t) <= cost);
+				assert!(landmarks.upper_bound(&s, &t).map_or(true, |d| d >= cost));
+			}
+			None => {
+				assert!(result.is_none());
+			}
+		}
+	}
+}
+
+#[test]
+fn ungraph_test_landmarks() {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..100 {
+		g.add_node(i, Empty);
+	}
+	// A 10 by 10 grid and an isolated pair of nodes.
+	for i in 0..100 {
+		if i % 10 < 9 {
+			g.add_edge(i, i + 1, 1.0);
+		}
+		if i < 90 {
+			g.add_edge(i, i + 10, 1.0);
+		}
+	}
+	g.add_node(100, Empty);
+	g.add_node(101, Empty);
+	g.add_edge(100, 101, 1.0);
+	let landmarks = Landmarks::new(&g, 4, |e| e.load());
+	assert!(landmarks.landmarks().iter().any(|k| *k >= 100));
+	assert!(landmarks.lower_bound(&0, &100) == f64::INFINITY);
+	assert!(landmarks.upper_bound(&0, &100).is_none());
+	assert!(landmarks.shortest_path(&g, 0, 100, |e| e.load()).is_none());
+	for (s, t) in [(0, 99), (5, 50), (23, 77), (100, 101)] {
+		let expected = g.shortest_path(s, t, |e| e.load()).unwrap().0;
+		assert!(landmarks.lower_bound(&s, &t) <= expected);
+		assert!(landmarks.upper_bound(&s, &t).unwrap() >= expected);
+		assert!(landmarks.shortest_path(&g, s, t, |e| e.load()).unwrap().0 == expected);
+	}
+	let chosen = Landmarks::with_landmarks(&g, &[0, 99, 1000], |e| e.load());
+	assert!(chosen.landmarks() == [0, 99]);
+	assert!(chosen.lower_bound(&0, &99) == 18.0);
+	g.add_edge(0, 99, 1.0);
+	assert!(!landmarks.is_fresh(&g));
+}
+
+#[test]
+fn digraph_test_all_simple_paths() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..5 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..5 {
+		for j in 0..5 {
+			g.add_edge(i, j, 1.0);
+		}
+	}
+	// Paths through any ordered subset of the three other nodes.
+	let paths: Vec<_> = g.all_simple_paths(0, 4, usize::MAX, usize::MAX).collect();
+	assert!(paths.len() == 1 + 3 + 6 + 6);
+	for path in paths.iter() {
+		let edges: Vec<_> = path.iter().map(|e| e.upgrade().unwrap()).collect();
+		assert!(edges[0].source().key() == &0);
+		assert!(edges.last().unwrap().target().key() == &4);
+		assert!(edges.windows(2).all(|w| w[0].target() == w[1].source()));
+		let mut keys: Vec<usize> = edges.iter().map(|e| *e.target().key()).collect();
+		keys.sort();
+		keys.dedup();
+		assert!(keys.len() == edges.len() && !keys.contains(&0));
+	}
+	assert!(g.all_simple_paths(0, 4, 2, usize::MAX).count() == 1 + 3);
+	assert!(g.all_simple_paths(0, 4, usize::MAX, 5).count() == 5);
+	assert!(g.all_simple_paths(0, 0, usize::MAX, usize::MAX).count() == 0);
+	assert!(g.all_simple_paths(0, 4, 0, usize::MAX).count() == 0);
+}
@@ -0,0 +1,44 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn digraph_test_sample_edges() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..3000 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..3000 {
+		g.add_edge(i, (i + 1) % 3000, if i == 0 { 0.0 } else { 1.0 });
+	}
+	let mut rng = StdRng::seed_from_u64(1);
+	let sample = g.sample_edges(100, |e| e.load(), &mut rng);
+	assert!(sample.len() == 100);
+	let mut keys: Vec<usize> = sample.iter().map(|e| *e.source().key()).collect();
+	keys.sort_unstable();
+	keys.dedup();
+	assert!(keys.len() == 100);
+	assert!(!keys.contains(&0));
+	// The sample only depends on the state of the generator.
+	let again = g.sample_edges(100, |e| e.load(), &mut StdRng::seed_from_u64(1));
+	let first = g.sample_edges(100, |e| e.load(), &mut StdRng::seed_from_u64(1));
+	assert!(again.iter().zip(first.iter()).all(|(a, b)| a.source() == b.source()));
+	// Asking for more than there is returns every edge with a positive weight.
+	assert!(g.sample_edges(5000, |e| e.load(), &mut rng).len() == 2999);
+	assert!(g.sample_edges(0, |e| e.load(), &mut rng).is_empty());
+}
+
+#[test]
+fn digraph_test_sample_edges_weighted() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..3 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, 9.0);
+	g.add_edge(0, 2, 1.0);
+	let mut rng = StdRng::seed_from_u64(42);
+	let heavy = (0..10000)
+		.filter(|_| g.sample_edges(1, |e| e.load(), &mut rng)[0].load() == 9.0)
+		.count();
+	assert!((8800..9200).contains(&heavy));
+}
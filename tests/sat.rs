@@ -0,0 +1,100 @@
+use fastgraph::collections::*;
+use fastgraph::components::*;
+use fastgraph::core::Empty;
+use fastgraph::sat::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[test]
+fn strongly_connected_components_order() {
+	// Three cycles chained together with a tail.
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..10 {
+		g.add_node(i, Empty);
+	}
+	for (s, t) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 5), (5, 6), (6, 7), (7, 5), (7, 8), (8, 9)] {
+		g.add_edge(s, t, Empty);
+	}
+	let mut components = strongly_connected_components(&g);
+	for c in components.iter_mut() {
+		c.sort();
+	}
+	assert_eq!(components, vec![vec![9], vec![8], vec![5, 6, 7], vec![3, 4], vec![0, 1, 2]]);
+
+	// Undirected graphs give the connected components.
+	let mut u = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..5 {
+		u.add_node(i, Empty);
+	}
+	u.add_edge(0, 1, Empty);
+	u.add_edge(2, 1, Empty);
+	u.add_edge(3, 4, Empty);
+	let mut components: Vec<Vec<usize>> = strongly_connected_components(&u)
+		.into_iter()
+		.map(|mut c| {
+			c.sort();
+			c
+		})
+		.collect();
+	components.sort();
+	assert_eq!(components, vec![vec![0, 1, 2], vec![3, 4]]);
+
+	// A long cycle doesn't overflow the stack.
+	let mut c = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..100_000 {
+		c.add_node(i, Empty);
+	}
+	for i in 0..100_000 {
+		c.add_edge(i, (i + 1) % 100_000, Empty);
+	}
+	assert_eq!(strongly_connected_components(&c).len(), 1);
+}
+
+#[test]
+fn two_sat_random() {
+	let mut rng = StdRng::seed_from_u64(11);
+	let (mut satisfiable, mut unsatisfiable) = (0, 0);
+	for _ in 0..300 {
+		let variables = rng.gen_range(1..9);
+		let clauses: Vec<(Literal, Literal)> = (0..rng.gen_range(1..3 * variables + 2))
+			.map(|_| {
+				let a = Literal { variable: rng.gen_range(0..variables), value: rng.gen() };
+				let b = Literal { variable: rng.gen_range(0..variables), value: rng.gen() };
+				(a, b)
+			})
+			.collect();
+		let mut formula = TwoSat::new();
+		for (a, b) in clauses.iter() {
+			formula.clause(*a, *b);
+		}
+		let holds = |assignment: &[bool]| {
+			let value = |l: &Literal| assignment[l.variable] == l.value;
+			clauses.iter().all(|(a, b)| value(a) || value(b))
+		};
+		let n = formula.variables();
+		let exists = (0..1u32 << n).any(|m| holds(&(0..n).map(|v| m >> v & 1 == 1).collect::<Vec<bool>>()));
+		match formula.solve() {
+			Some(assignment) => {
+				assert!(holds(&assignment));
+				satisfiable += 1;
+			}
+			None => {
+				assert!(!exists);
+				unsatisfiable += 1;
+			}
+		}
+	}
+	assert!(satisfiable > 50 && unsatisfiable > 50);
+}
+
+#[test]
+fn two_sat_helpers() {
+	let mut formula = TwoSat::new();
+	formula.exclusive(Literal::pos(0), Literal::pos(1));
+	formula.implication(Literal::pos(1), Literal::pos(2));
+	formula.unit(Literal::neg(2));
+	assert_eq!(formula.solve(), Some(vec![true, false, false]));
+	assert_eq!(formula.implication_graph().node_count(), 6);
+	assert_eq!(format!("{}", !Literal::pos(3)), "!x3");
+	formula.unit(Literal::neg(0));
+	assert_eq!(formula.solve(), None);
+}
@@ -0,0 +1,58 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::scheduling::*;
+
+#[test]
+fn digraph_test_critical_path_on_edges() {
+	// Activities on the edges between numbered events.
+	let mut g = Digraph::<u32, Empty, f64>::new();
+	for i in 1..7 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 2, 4.0);
+	g.add_edge(1, 3, 2.0);
+	g.add_edge(2, 4, 3.0);
+	g.add_edge(3, 4, 1.0);
+	g.add_edge(3, 5, 6.0);
+	g.add_edge(4, 6, 2.0);
+	g.add_edge(5, 6, 2.0);
+
+	let plan = critical_path(&g, |_| 0.0, |edge| edge.load()).unwrap();
+	assert!(plan.path == [1, 3, 5, 6]);
+	assert!(plan.duration == 10.0);
+	assert!(plan.earliest[&4] == 7.0 && plan.latest[&4] == 8.0);
+	assert!(plan.earliest[&2] == 4.0 && plan.latest[&2] == 5.0);
+	assert!(plan.slack(&4) == Some(1.0));
+	for key in [1, 3, 5, 6] {
+		assert!(plan.is_critical(&key));
+	}
+	assert!(plan.slack(&7).is_none());
+}
+
+#[test]
+fn digraph_test_critical_path_on_nodes() {
+	// Two independent chains, the shorter one can slip.
+	let mut g = Digraph::<&str, f64, f64>::new();
+	g.add_node("a", 2.0);
+	g.add_node("b", 3.0);
+	g.add_node("x", 1.0);
+	g.add_node("y", 1.0);
+	g.add_edge("a", "b", 1.0);
+	g.add_edge("x", "y", 0.0);
+
+	let plan = critical_path(&g, |node| node.load(), |edge| edge.load()).unwrap();
+	assert!(plan.path == ["a", "b"]);
+	assert!(plan.duration == 6.0);
+	assert!(plan.earliest[&"b"] == 3.0);
+	assert!(plan.latest[&"x"] == 4.0 && plan.latest[&"y"] == 5.0);
+	assert!(plan.slack(&"x") == Some(4.0));
+	assert!(!plan.is_critical(&"y"));
+
+	let empty = Digraph::<&str, f64, f64>::new();
+	let plan = critical_path(&empty, |node| node.load(), |edge| edge.load()).unwrap();
+	assert!(plan.path.is_empty() && plan.duration == 0.0);
+
+	g.add_edge("b", "a", 0.0);
+	let cycle = critical_path(&g, |node| node.load(), |edge| edge.load()).unwrap_err();
+	assert!(cycle.len() == 2 && cycle.contains(&"a") && cycle.contains(&"b"));
+}
@@ -0,0 +1,78 @@
+use fastgraph::collections::*;
+use fastgraph::schema::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Kind {
+	Person,
+	Company,
+	City,
+	Planet,
+}
+
+#[allow(clippy::ptr_arg)]
+fn kind(data: &String) -> Kind {
+	match data.split(':').next().unwrap() {
+		"person" => Kind::Person,
+		"company" => Kind::Company,
+		"city" => Kind::City,
+		_ => Kind::Planet,
+	}
+}
+
+fn schema() -> Schema<String, &'static str, Kind, &'static str> {
+	Schema::new(kind, |e: &&str| *e)
+		.node_type(Kind::Person)
+		.node_type(Kind::Company)
+		.node_type(Kind::City)
+		.relation(Kind::Person, "knows", Kind::Person)
+		.cardinality(Kind::Person, "works_at", Kind::Company, 0, Some(1))
+		.cardinality(Kind::Company, "located_in", Kind::City, 1, Some(1))
+}
+
+#[test]
+fn digraph_test_check_schema() {
+	let mut g = Digraph::<u32, String, &str>::new();
+	for (key, data) in [(1, "person:ada"), (2, "person:alan"), (3, "company:acme"), (4, "city:espoo")] {
+		g.add_node(key, data.to_string());
+	}
+	g.add_edge(1, 2, "knows");
+	g.add_edge(1, 3, "works_at");
+	g.add_edge(3, 4, "located_in");
+	let schema = schema();
+	assert!(g.check_schema(&schema).is_empty());
+
+	g.add_node(5, "planet:earth".to_string());
+	g.add_node(6, "company:initech".to_string());
+	g.add_edge(3, 1, "knows");
+	g.add_edge(1, 6, "works_at");
+	let violations = g.check_schema(&schema);
+	assert!(violations.len() == 4);
+	assert!(violations.contains(&Violation::NodeType { node: 5, node_type: Kind::Planet }));
+	assert!(violations.contains(&Violation::Edge {
+		source: 3,
+		target: 1,
+		relation: (Kind::Company, "knows", Kind::Person),
+	}));
+	assert!(violations.contains(&Violation::Cardinality {
+		node: 1,
+		relation: (Kind::Person, "works_at", Kind::Company),
+		count: 2,
+	}));
+	assert!(violations.contains(&Violation::Cardinality {
+		node: 6,
+		relation: (Kind::Company, "located_in", Kind::City),
+		count: 0,
+	}));
+}
+
+#[test]
+fn ungraph_test_check_schema() {
+	let mut g = Ungraph::<u32, String, &str>::new();
+	g.add_node(1, "person:ada".to_string());
+	g.add_node(2, "company:acme".to_string());
+	g.add_node(3, "city:espoo".to_string());
+	// Relations of undirected graphs match in either orientation.
+	g.add_edge(2, 1, "works_at");
+	g.add_edge(3, 2, "located_in");
+	assert!(g.check_schema(&schema()).is_empty());
+}
@@ -0,0 +1,39 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use std::sync::Arc;
+
+#[test]
+fn digraph_test_shared_consistent_reads() {
+	let shared = Arc::new(Shared::new(Digraph::<u32, Empty, Empty>::new()));
+	shared.write().add_node(0, Empty);
+	let writer = {
+		let shared = shared.clone();
+		std::thread::spawn(move || {
+			for i in 1..500 {
+				let mut g = shared.write();
+				g.add_node(i, Empty);
+				g.add_edge(i - 1, i, Empty);
+			}
+		})
+	};
+	let readers: Vec<_> = (0..4).map(|_| {
+		let shared = shared.clone();
+		std::thread::spawn(move || {
+			for _ in 0..200 {
+				// Every write adds a node and an edge together, so the counts
+				// must always match under one guard.
+				let g = shared.read();
+				let nodes = g.node_count();
+				let edges = g.edge_count();
+				assert!(nodes == edges + 1);
+			}
+		})
+	}).collect();
+	writer.join().unwrap();
+	for reader in readers {
+		reader.join().unwrap();
+	}
+	let g = Arc::try_unwrap(shared).ok().unwrap().into_inner();
+	assert!(g.node_count() == 500);
+	assert!(g.edge_count() == 499);
+}
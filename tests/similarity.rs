@@ -0,0 +1,65 @@
+use fastgraph::collections::*;
+use fastgraph::core::*;
+use fastgraph::similarity::*;
+use fastgraph::core::Empty;
+type SimpleUngraph = Ungraph<usize, Empty, Empty>;
+
+// Nodes 1 and 2 share the neighbors 3 and 4, node 5 hangs off 4.
+fn test_ungraph_shared() -> SimpleUngraph {
+	let mut g = SimpleUngraph::new();
+	for i in 1..6 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(1, 3, Empty);
+	g.add_edge(1, 4, Empty);
+	g.add_edge(2, 3, Empty);
+	g.add_edge(2, 4, Empty);
+	g.add_edge(4, 5, Empty);
+	g
+}
+
+#[test]
+fn ungraph_test_neighborhood_similarity() {
+	let g = test_ungraph_shared();
+	let n = |k: usize| g.get_node(k).unwrap();
+	assert!(common_neighbors(&n(1), &n(2), Direction::Both) == 2);
+	assert!(jaccard(&n(1), &n(2), Direction::Both) == 1.0);
+	assert!(jaccard(&n(1), &n(5), Direction::Both) == 0.5);
+	let expected = 1.0 / 2f64.ln() + 1.0 / 3f64.ln();
+	assert!((adamic_adar(&n(1), &n(2), Direction::Both) - expected).abs() < 1e-12);
+	let similar = top_k_similar(&g, 1, 2, |a, b| jaccard(a, b, Direction::Both)).unwrap();
+	assert!(similar.len() == 2);
+	assert!(similar[0].0.key() == &2);
+	assert!(similar[1].0.key() == &5);
+	assert!(top_k_similar(&g, 42, 2, |a, b| jaccard(a, b, Direction::Both)).is_none());
+}
+
+#[test]
+fn ungraph_test_simrank() {
+	let g = test_ungraph_shared();
+	let simrank = SimRank::new(&g, 0.8, 10);
+	assert!(simrank.score(&1, &1).unwrap() == 1.0);
+	assert!(simrank.score(&1, &2).unwrap() == simrank.score(&2, &1).unwrap());
+	assert!(simrank.score(&1, &2).unwrap() > simrank.score(&1, &3).unwrap());
+	let top = simrank.top_k(&3, 1).unwrap();
+	assert!(top[0].0.key() == &4);
+	assert!(simrank.score(&1, &42).is_none());
+}
+
+#[test]
+fn ungraph_test_predict_links() {
+	let g = test_ungraph_shared();
+	let links = predict_links(&g, 3, |a, b| jaccard(a, b, Direction::Both));
+	assert!(links.len() == 3);
+	let mut first = [*links[0].0.key(), *links[0].1.key()];
+	first.sort();
+	assert!(first == [1, 2]);
+	let mut second = [*links[1].0.key(), *links[1].1.key()];
+	second.sort();
+	assert!(second == [3, 4]);
+	assert!(links[2].2 == 0.5);
+	for (a, b, _) in links.iter() {
+		assert!(g.get_edge(*a.key(), *b.key()).is_none());
+		assert!(g.get_edge(*b.key(), *a.key()).is_none());
+	}
+}
@@ -0,0 +1,141 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Direction, Empty};
+use fastgraph::similarity::jaccard;
+use fastgraph::sketch::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+
+#[test]
+fn digraph_test_hyper_anf() {
+	let mut rng = StdRng::seed_from_u64(4);
+	let mut g = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..300 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..600 {
+		g.add_edge(rng.gen_range(0..300), rng.gen_range(0..300), Empty);
+	}
+	let hops = 6;
+	// Exact hop distances from every node by breadth first search.
+	let mut function = vec![0.0; hops + 1];
+	let mut sizes = HashMap::new();
+	for source in 0..300 {
+		let mut depth = HashMap::new();
+		let mut queue = VecDeque::from(vec![g.get_node(source).unwrap()]);
+		depth.insert(source, 0);
+		while let Some(node) = queue.pop_front() {
+			let d = depth[node.key()];
+			if d == hops {
+				continue;
+			}
+			for edge in node.outbound().iter() {
+				let next = edge.target();
+				if !depth.contains_key(next.key()) {
+					depth.insert(*next.key(), d + 1);
+					queue.push_back(next);
+				}
+			}
+		}
+		for d in depth.values() {
+			for pairs in function[*d..].iter_mut() {
+				*pairs += 1.0;
+			}
+		}
+		sizes.insert(source, depth.len() as f64);
+	}
+
+	let anf = hyper_anf(&g, 12, hops);
+	assert!(anf.function.len() == hops + 1);
+	for (estimate, exact) in anf.function.iter().zip(function.iter()) {
+		assert!((estimate - exact).abs() <= 0.05 * exact);
+	}
+	for (key, exact) in sizes.iter() {
+		assert!((anf.sizes[key] - exact).abs() <= 0.1 * exact + 1.0);
+	}
+	let exact = Neighborhoods { function, sizes };
+	assert!((anf.effective_diameter(0.9) - exact.effective_diameter(0.9)).abs() < 0.25);
+
+	// Balls that stop growing keep their size for the rest of the hops.
+	let mut path = Digraph::<usize, Empty, Empty>::new();
+	for i in 0..3 {
+		path.add_node(i, Empty);
+	}
+	path.add_edge(0, 1, Empty);
+	path.add_edge(1, 2, Empty);
+	let anf = hyper_anf(&path, 8, 5);
+	assert!(anf.function.len() == 6);
+	assert!((anf.function[5] - 6.0).abs() < 0.5);
+	assert!((anf.sizes[&0] - 3.0).abs() < 0.5 && (anf.sizes[&2] - 1.0).abs() < 0.5);
+	assert!(anf.effective_diameter(1.0) <= 2.0);
+}
+
+#[test]
+fn ungraph_test_minhash() {
+	// Groups of nodes sharing most of their neighbors among random noise.
+	let mut rng = StdRng::seed_from_u64(9);
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..500 {
+		g.add_node(i, Empty);
+	}
+	for group in 0..10 {
+		let hubs: Vec<usize> = (0..20).map(|i| 100 + group * 20 + i).collect();
+		for member in group * 10..group * 10 + 3 {
+			for hub in hubs.iter().filter(|_| rng.gen_bool(0.95)) {
+				g.add_edge(member, *hub, Empty);
+			}
+		}
+	}
+	for _ in 0..300 {
+		g.add_edge(rng.gen_range(300..500), rng.gen_range(0..500), Empty);
+	}
+
+	let minhash = MinHash::new(&g, 256, Direction::Both);
+	let node = |k: usize| g.get_node(k).unwrap();
+	for (a, b) in [(0, 1), (0, 2), (0, 10), (5, 300), (100, 101)] {
+		let exact = jaccard(&node(a), &node(b), Direction::Both);
+		let estimate = minhash.similarity(&a, &b).unwrap();
+		assert!((estimate - exact).abs() < 0.1);
+	}
+	assert!(minhash.signature(&0).unwrap().len() == 256);
+	assert!(minhash.similarity(&0, &500).is_none());
+
+	let pairs = minhash.similar_pairs(64, 0.5);
+	let found: Vec<(usize, usize)> = pairs
+		.iter()
+		.map(|(a, b, _)| (*a.key().min(b.key()), *a.key().max(b.key())))
+		.collect();
+	let mut checked = 0;
+	for group in 0..10 {
+		let first = group * 10;
+		for (a, b) in [(first, first + 1), (first, first + 2), (first + 1, first + 2)] {
+			if jaccard(&node(a), &node(b), Direction::Both) >= 0.7 {
+				assert!(found.contains(&(a, b)));
+				checked += 1;
+			}
+		}
+	}
+	assert!(checked >= 20);
+	assert!(pairs.windows(2).all(|w| w[0].2 >= w[1].2));
+	for (a, b, s) in pairs.iter() {
+		assert!(*s >= 0.5 && a != b);
+	}
+}
+
+#[test]
+fn digraph_test_hyper_log_log_union() {
+	let mut a = HyperLogLog::new(8);
+	let mut b = HyperLogLog::new(8);
+	for i in 0..100 {
+		a.insert(&i);
+	}
+	assert!(b.union(&a) && !b.union(&a));
+	assert!(a == b);
+	// Counters of different precisions have registers for different bits of
+	// the hash and can't be merged.
+	let mut c = HyperLogLog::new(10);
+	let wrong = |f: &mut dyn FnMut()| std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).is_err();
+	assert!(wrong(&mut || { c.union(&a); }));
+	assert!(wrong(&mut || { a.union(&HyperLogLog::new(4)); }));
+	// Precisions are clamped before they are compared.
+	assert!(!HyperLogLog::new(2).union(&HyperLogLog::new(4)));
+}
@@ -0,0 +1,121 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::sparsify::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+fn random_ungraph(size: usize, edges: usize, rng: &mut StdRng) -> Ungraph<usize, Empty, f64> {
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..size {
+		g.add_node(i, Empty);
+	}
+	for i in 1..size {
+		g.add_edge(rng.gen_range(0..i), i, rng.gen_range(1..10) as f64);
+	}
+	for _ in size..edges {
+		let (s, t) = (rng.gen_range(0..size), rng.gen_range(0..size));
+		if s != t && g.get_edge(t, s).is_none() {
+			g.add_edge(s, t, rng.gen_range(1..10) as f64);
+		}
+	}
+	g
+}
+
+// Total weight of the edges crossing between the nodes below and above the
+// split.
+fn cut(g: &Ungraph<usize, Empty, f64>, split: usize) -> f64 {
+	g.nodes()
+		.iter()
+		.flat_map(|n| n.outbound().iter().cloned().collect::<Vec<_>>())
+		.filter(|e| (*e.source().key() < split) != (*e.target().key() < split))
+		.map(|e| e.load())
+		.sum()
+}
+
+#[test]
+fn ungraph_test_greedy_spanner() {
+	let mut rng = StdRng::seed_from_u64(3);
+	let g = random_ungraph(150, 1200, &mut rng);
+	let spanner = greedy_spanner(&g, 3.0, |e| e.load());
+	assert!(spanner.node_count() == g.node_count());
+	assert!(spanner.edge_count() < g.edge_count() / 2);
+	for _ in 0..200 {
+		let (s, t) = (rng.gen_range(0..150), rng.gen_range(0..150));
+		let original = g.shortest_path(s, t, |e| e.load()).unwrap().0;
+		let stretched = spanner.shortest_path(s, t, |e| e.load()).unwrap().0;
+		assert!(stretched >= original && stretched <= 3.0 * original);
+	}
+	// A stretch of one keeps every shortest path.
+	let exact = greedy_spanner(&g, 1.0, |e| e.load());
+	for _ in 0..50 {
+		let (s, t) = (rng.gen_range(0..150), rng.gen_range(0..150));
+		assert!(g.shortest_path(s, t, |e| e.load()).unwrap().0 == exact.shortest_path(s, t, |e| e.load()).unwrap().0);
+	}
+}
+
+#[test]
+fn digraph_test_greedy_spanner() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..3 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, 1.0);
+	g.add_edge(1, 2, 1.0);
+	g.add_edge(0, 2, 2.0);
+	g.add_edge(2, 0, 2.0);
+	let spanner = greedy_spanner(&g, 1.0, |e| e.load());
+	// The path 0 -> 1 -> 2 replaces 0 -> 2 but 2 -> 0 has no replacement.
+	assert!(spanner.edge_count() == 3);
+	assert!(spanner.get_edge(0, 2).is_none() && spanner.get_edge(2, 0).is_some());
+}
+
+#[test]
+fn ungraph_test_random_sparsifier() {
+	let mut rng = StdRng::seed_from_u64(5);
+	let g = random_ungraph(200, 4000, &mut rng);
+	let sparse = random_sparsifier(&g, 0.25, |e| e.load(), |_, w| w, &mut rng);
+	let ratio = sparse.edge_count() as f64 / g.edge_count() as f64;
+	assert!((0.2..0.3).contains(&ratio));
+	let (a, b) = (cut(&g, 100), cut(&sparse, 100));
+	assert!((b / a - 1.0).abs() < 0.15);
+}
+
+#[test]
+fn ungraph_test_spectral_sparsifier() {
+	let mut rng = StdRng::seed_from_u64(11);
+	let mut g = Ungraph::<usize, Empty, f64>::new();
+	for i in 0..120 {
+		g.add_node(i, Empty);
+	}
+	// Two dense halves joined by two bridges.
+	for i in 0..120 {
+		for j in i + 1..120 {
+			if (i < 60) == (j < 60) && rng.gen::<f64>() < 0.5 {
+				g.add_edge(i, j, 1.0);
+			}
+		}
+	}
+	g.add_edge(0, 60, 1.0);
+	g.add_edge(1, 61, 1.0);
+	let sparse = spectral_sparsifier(&g, 600, |e| e.load(), |_, w| w, &mut rng.clone());
+	assert!(sparse.edge_count() < g.edge_count() / 2);
+	// The bridges carry the whole cut between the halves.
+	assert!(sparse.get_edge(0, 60).is_some() && sparse.get_edge(1, 61).is_some());
+	assert!((cut(&sparse, 60) - 2.0).abs() < 1.0);
+	for split in [20, 40, 80, 100] {
+		let (a, b) = (cut(&g, split), cut(&sparse, split));
+		assert!((b / a - 1.0).abs() < 0.2);
+	}
+	// The same generator keeps the same edges whatever the order of the nodes.
+	let mut h = Ungraph::<usize, Empty, f64>::new();
+	for i in (0..120).rev() {
+		h.add_node(i, Empty);
+	}
+	for node in g.nodes() {
+		for edge in node.outbound().iter() {
+			h.add_edge(*edge.source().key(), *edge.target().key(), edge.load());
+		}
+	}
+	let other = spectral_sparsifier(&h, 600, |e| e.load(), |_, w| w, &mut rng);
+	assert!(other.edge_count() == sparse.edge_count());
+	assert!(sparse.nodes().iter().all(|n| n.outbound().iter().all(|e| other.get_edge(*n.key(), *e.target().key()).is_some())));
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "tokio")]
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::io::{stream::*, Error};
+
+fn runtime() -> tokio::runtime::Runtime {
+	tokio::runtime::Builder::new_current_thread().build().unwrap()
+}
+
+fn parse(line: &str) -> Result<Element<u32, Empty, f64>, String> {
+	let fields: Vec<&str> = line.split_whitespace().collect();
+	let key = |i: usize| fields[i].parse::<u32>().map_err(|e| e.to_string());
+	match fields[0] {
+		"n" => Ok(Element::Node(key(1)?, Empty)),
+		"e" => Ok(Element::Edge(key(1)?, key(2)?, fields[3].parse().map_err(|_| "bad weight")?)),
+		_ => Err(format!("unknown element {}", fields[0])),
+	}
+}
+
+#[test]
+fn digraph_test_from_stream() {
+	let mut elements: Vec<Result<Element<u32, Empty, f64>, Error>> = (0..100).map(|i| Ok(Element::Node(i, Empty))).collect();
+	for i in 0..99 {
+		elements.push(Ok(Element::Edge(i, i + 1, i as f64)));
+	}
+	let g: Digraph<u32, Empty, f64> = runtime().block_on(from_stream(futures_util::stream::iter(elements))).unwrap();
+	assert!(g.node_count() == 100);
+	assert!(g.edge_count() == 99);
+	assert!(g.get_edge(41, 42).unwrap().load() == 41.0);
+
+	let elements = vec![Ok(Element::Node(1, Empty)), Ok(Element::Edge(1, 2, 1.0))];
+	let res: Result<Digraph<u32, Empty, f64>, Error> = runtime().block_on(from_stream(futures_util::stream::iter(elements)));
+	assert!(matches!(res, Err(Error::UnknownNode { line: 2, .. })));
+}
+
+#[test]
+fn digraph_test_from_lines() {
+	let input = "n 1\nn 2\n\nn 3\ne 1 2 0.5\ne 2 3 1.5\n";
+	let g: Digraph<u32, Empty, f64> = runtime().block_on(from_lines(input.as_bytes(), parse)).unwrap();
+	assert!(g.edge_count() == 2);
+	assert!(g.get_edge(2, 3).unwrap().load() == 1.5);
+
+	let res: Result<Digraph<u32, Empty, f64>, Error> = runtime().block_on(from_lines("n 1\nx 2\n".as_bytes(), parse));
+	assert!(matches!(res, Err(Error::Record { line: 2, .. })));
+}
+
+#[test]
+fn ungraph_test_spawn() {
+	let rt = runtime();
+	let g: Ungraph<u32, Empty, Empty> = rt.block_on(async {
+		// A small capacity makes the producer wait for the loader.
+		let (sender, loader) = spawn(4);
+		for i in 0..1000 {
+			sender.send(Element::Node(i, Empty)).await.unwrap();
+		}
+		for i in 0..1000 {
+			sender.send(Element::Edge(i, (i + 1) % 1000, Empty)).await.unwrap();
+		}
+		drop(sender);
+		loader.await.unwrap()
+	}).unwrap();
+	assert!(g.node_count() == 1000);
+	assert!(g.edge_count() == 1000);
+}
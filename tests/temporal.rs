@@ -0,0 +1,157 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Direction, Empty};
+use fastgraph::temporal::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+#[test]
+fn digraph_test_temporal_index() {
+	// Random intervals compared against a scan over every edge.
+	let mut rng = StdRng::seed_from_u64(11);
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..50 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..400 {
+		let (u, v) = (rng.gen_range(0..50), rng.gen_range(0..50));
+		g.add_edge(u, v, rng.gen_range(0.0..100.0));
+	}
+	let interval = |start: f64| (start, start + (start * 7.0) % 5.0);
+	let index = TemporalIndex::new(&g, |edge| interval(edge.load()));
+	assert!(index.is_fresh(&g));
+	for _ in 0..100 {
+		let from = rng.gen_range(-5.0..105.0);
+		let to = from + rng.gen_range(0.0..10.0);
+		let active = |start: f64| {
+			let (s, e) = interval(start);
+			s <= to && e >= from
+		};
+		let expected = g.nodes().iter().map(|n| n.outbound().iter().filter(|e| active(e.load())).count()).sum::<usize>();
+		let edges = index.edges_between(from, to);
+		assert!(edges.len() == expected);
+		assert!(edges.iter().all(|e| active(e.load())));
+		assert!(edges.windows(2).all(|w| w[0].load() <= w[1].load()));
+
+		let node = g.get_node(7).unwrap();
+		let outbound = index.edges_of(&7, Direction::Outbound, from, to).unwrap();
+		assert!(outbound.len() == node.outbound().iter().filter(|e| active(e.load())).count());
+		let inbound = index.edges_of(&7, Direction::Inbound, from, to).unwrap();
+		assert!(inbound.iter().all(|e| e.target() == node));
+		let both = index.edges_of(&7, Direction::Both, from, to).unwrap();
+		assert!(both.len() == outbound.len() + inbound.len());
+	}
+	assert!(index.edges_of(&50, Direction::Both, 0.0, 1.0).is_none());
+	g.add_edge(0, 1, 1.0);
+	assert!(!index.is_fresh(&g));
+}
+
+#[test]
+fn digraph_test_temporal_ego() {
+	// A chain 0 -> 1 -> 2 -> 3 where each edge is active for a unit of time
+	// starting from its data.
+	let mut g = Digraph::<u32, Empty, f64>::new();
+	for i in 0..4 {
+		g.add_node(i, Empty);
+	}
+	g.add_edge(0, 1, 0.0);
+	g.add_edge(1, 2, 1.5);
+	g.add_edge(2, 3, 5.0);
+	g.add_edge(3, 0, 1.0);
+	let index = TemporalIndex::new(&g, |edge| (edge.load(), edge.load() + 1.0));
+
+	let early: Digraph<u32, Empty, f64> = index.ego(0, 3, Direction::Outbound, 0.0, 2.0).unwrap();
+	assert!(early.node_count() == 3 && early.edge_count() == 2);
+	assert!(early.get_edge(1, 2).is_some());
+
+	// The edge back to 0 is active but 3 isn't reached within the window.
+	let both: Digraph<u32, Empty, f64> = index.ego(0, 1, Direction::Both, 0.0, 2.0).unwrap();
+	assert!(both.node_count() == 3 && both.edge_count() == 2);
+	assert!(both.get_edge(3, 0).is_some() && both.get_edge(1, 2).is_none());
+
+	let late: Digraph<u32, Empty, f64> = index.ego(0, 3, Direction::Outbound, 3.0, 4.0).unwrap();
+	assert!(late.node_count() == 1);
+	assert!(index.ego::<Digraph<u32, Empty, f64>>(9, 1, Direction::Both, 0.0, 1.0).is_none());
+}
+
+#[test]
+fn ungraph_test_temporal_reachability() {
+	// Contacts at single instants. Reaching b from a happens after b met c,
+	// so c can't be reached from a while a can be reached from c.
+	let mut g = Ungraph::<&str, Empty, f64>::new();
+	for person in ["a", "b", "c", "d"] {
+		g.add_node(person, Empty);
+	}
+	g.add_edge("a", "b", 5.0);
+	g.add_edge("b", "c", 3.0);
+	g.add_edge("c", "d", 1.0);
+	let index = TemporalIndex::new(&g, |edge| (edge.load(), edge.load()));
+
+	let from_a = index.reachable(&"a", Direction::Both, 0.0, 10.0).unwrap();
+	let keys: Vec<&str> = from_a.iter().map(|(n, _)| *n.key()).collect();
+	assert!(keys == ["a", "b"]);
+	assert!(from_a[1].1 == 5.0);
+
+	let from_d = index.reachable(&"d", Direction::Both, 0.0, 10.0).unwrap();
+	let keys: Vec<&str> = from_d.iter().map(|(n, _)| *n.key()).collect();
+	assert!(keys == ["d", "c", "b", "a"]);
+	assert!(index.reachable(&"d", Direction::Both, 0.0, 4.0).unwrap().len() == 3);
+	assert!(index.reachable(&"d", Direction::Both, 2.0, 10.0).unwrap().len() == 1);
+
+	let (arrival, path) = index.earliest_arrival(&"d", &"a", Direction::Both, 0.0).unwrap();
+	assert!(arrival == 5.0 && path.len() == 3);
+	assert!(index.earliest_arrival(&"a", &"d", Direction::Both, 0.0).is_none());
+	assert!(index.reachable(&"e", Direction::Both, 0.0, 1.0).is_none());
+}
+
+#[test]
+fn digraph_test_earliest_arrival() {
+	// Random connections compared against relaxing every edge until no
+	// arrival improves.
+	let mut rng = StdRng::seed_from_u64(3);
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..30 {
+		g.add_node(i, Empty);
+	}
+	for _ in 0..300 {
+		let (u, v) = (rng.gen_range(0..30), rng.gen_range(0..30));
+		g.add_edge(u, v, rng.gen_range(0.0..50.0));
+	}
+	let interval = |start: f64| (start, start + 1.0 + start % 3.0);
+	let index = TemporalIndex::new(&g, |edge| interval(edge.load()));
+
+	let mut arrival = vec![f64::INFINITY; 30];
+	arrival[0] = 0.0;
+	let mut changed = true;
+	while changed {
+		changed = false;
+		for node in g.nodes() {
+			for edge in node.outbound().iter() {
+				let (start, end) = interval(edge.load());
+				let (u, v) = (*edge.source().key(), *edge.target().key());
+				if start >= arrival[u] && end < arrival[v] {
+					arrival[v] = end;
+					changed = true;
+				}
+			}
+		}
+	}
+	let reached = index.reachable(&0, Direction::Outbound, 0.0, f64::INFINITY).unwrap();
+	assert!(reached.len() == arrival.iter().filter(|a| a.is_finite()).count());
+	for (node, a) in reached.iter() {
+		assert!(arrival[*node.key()] == *a);
+	}
+	for (target, expected) in arrival.iter().enumerate().skip(1) {
+		match index.earliest_arrival(&0, &target, Direction::Outbound, 0.0) {
+			Some((a, path)) => {
+				assert!(a == *expected);
+				let mut time = 0.0;
+				for edge in path.iter().map(|e| e.upgrade().unwrap()) {
+					let (start, end) = interval(edge.load());
+					assert!(start >= time);
+					time = end;
+				}
+				assert!(time == a);
+			}
+			None => { assert!(expected.is_infinite()) }
+		}
+	}
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "tracing")]
+
+use fastgraph::centrality::pagerank;
+use fastgraph::collections::*;
+use fastgraph::core::{Empty, Traverse};
+use std::sync::{Arc, Mutex};
+use tracing::{
+	field::{Field, Visit},
+	span::{Attributes, Id, Record},
+	Event, Metadata, Subscriber,
+};
+
+type Values = Vec<(String, String)>;
+
+// Records the names of the spans and the fields of the events.
+#[derive(Clone, Default)]
+struct Recorder {
+	spans: Arc<Mutex<Vec<String>>>,
+	events: Arc<Mutex<Vec<Values>>>,
+}
+
+struct Fields(Vec<(String, String)>);
+
+impl Visit for Fields {
+	fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+		self.0.push((field.name().to_string(), format!("{:?}", value)));
+	}
+}
+
+impl Subscriber for Recorder {
+	fn enabled(&self, _: &Metadata<'_>) -> bool {
+		true
+	}
+
+	fn new_span(&self, span: &Attributes<'_>) -> Id {
+		let mut spans = self.spans.lock().unwrap();
+		spans.push(span.metadata().name().to_string());
+		Id::from_u64(spans.len() as u64)
+	}
+
+	fn record(&self, _: &Id, _: &Record<'_>) {}
+
+	fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+	fn event(&self, event: &Event<'_>) {
+		let mut fields = Fields(Vec::new());
+		event.record(&mut fields);
+		self.events.lock().unwrap().push(fields.0);
+	}
+
+	fn enter(&self, _: &Id) {}
+
+	fn exit(&self, _: &Id) {}
+}
+
+fn message(fields: &[(String, String)]) -> &str {
+	fields.iter().find(|(name, _)| name == "message").map(|(_, value)| value.as_str()).unwrap_or("")
+}
+
+#[test]
+fn tracing_algorithm_phases() {
+	let mut g = Digraph::<usize, Empty, f64>::new();
+	for i in 0..10 {
+		g.add_node(i, Empty);
+	}
+	for i in 0..9 {
+		g.add_edge(i, i + 1, 1.0);
+	}
+	let recorder = Recorder::default();
+	tracing::subscriber::with_default(recorder.clone(), || {
+		g.shortest_path(0, 9, |e| e.load());
+		g.breadth_first(0, |_| Traverse::Include);
+		pagerank(&g, 0.85, 20, 0.0);
+	});
+	let spans = recorder.spans.lock().unwrap();
+	assert!(spans.contains(&"dijkstra".to_string()));
+	assert!(spans.contains(&"breadth_first".to_string()));
+	assert!(spans.contains(&"power_iteration".to_string()));
+	let events = recorder.events.lock().unwrap();
+	let found = events.iter().find(|e| message(e) == "found").unwrap();
+	assert!(found.contains(&("relaxations".to_string(), "9".to_string())));
+	// The path has a frontier of one edge per level.
+	assert_eq!(events.iter().filter(|e| message(e) == "frontier").count(), 9);
+	assert_eq!(events.iter().filter(|e| message(e) == "iteration").count(), 20);
+	assert!(events.iter().filter(|e| message(e) == "iteration").all(|e| e.iter().any(|(name, _)| name == "delta")));
+}
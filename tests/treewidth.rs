@@ -0,0 +1,112 @@
+use fastgraph::collections::*;
+use fastgraph::core::Empty;
+use fastgraph::treewidth::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashSet;
+
+fn graph(size: usize, edges: &[(usize, usize)]) -> Ungraph<usize, Empty, Empty> {
+	let mut g = Ungraph::<usize, Empty, Empty>::new();
+	for i in 0..size {
+		g.add_node(i, Empty);
+	}
+	for (s, t) in edges.iter() {
+		g.add_edge(*s, *t, Empty);
+	}
+	g
+}
+
+fn grid(size: usize) -> Vec<(usize, usize)> {
+	let mut edges = Vec::new();
+	for i in 0..size {
+		for j in 0..size {
+			if i + 1 < size {
+				edges.push((i * size + j, (i + 1) * size + j));
+			}
+			if j + 1 < size {
+				edges.push((i * size + j, i * size + j + 1));
+			}
+		}
+	}
+	edges
+}
+
+// Check the properties of a tree decomposition and return its width.
+fn check(size: usize, edges: &[(usize, usize)], heuristic: Heuristic) -> usize {
+	let d = tree_decomposition(&graph(size, edges), heuristic);
+	let bags: Vec<HashSet<usize>> = d.bags.iter().map(|b| b.iter().cloned().collect()).collect();
+	if size > 0 {
+		assert_eq!(d.tree.len(), bags.len() - 1);
+	}
+	for v in 0..size {
+		assert!(bags.iter().any(|b| b.contains(&v)));
+	}
+	for (s, t) in edges.iter() {
+		assert!(bags.iter().any(|b| b.contains(s) && b.contains(t)));
+	}
+	// The bags containing a node are connected by the tree edges between
+	// them, and the tree itself is connected.
+	let connected = |contains: &dyn Fn(usize) -> bool| -> bool {
+		let members: Vec<usize> = (0..bags.len()).filter(|i| contains(*i)).collect();
+		let mut seen: HashSet<usize> = members.iter().take(1).cloned().collect();
+		let mut changed = true;
+		while changed {
+			changed = false;
+			for (a, b) in d.tree.iter() {
+				if contains(*a) && contains(*b) && seen.contains(a) != seen.contains(b) {
+					seen.insert(*a);
+					seen.insert(*b);
+					changed = true;
+				}
+			}
+		}
+		seen.len() == members.len()
+	};
+	assert!(connected(&|_| true));
+	for v in 0..size {
+		assert!(connected(&|i| bags[i].contains(&v)));
+	}
+	d.width()
+}
+
+#[test]
+fn treewidth_known_graphs() {
+	for heuristic in [Heuristic::MinDegree, Heuristic::MinFill] {
+		assert_eq!(check(0, &[], heuristic), 0);
+		assert_eq!(check(3, &[], heuristic), 0);
+		let path: Vec<(usize, usize)> = (1..50).map(|i| (i - 1, i)).collect();
+		assert_eq!(check(50, &path, heuristic), 1);
+		let tree: Vec<(usize, usize)> = (1..100).map(|i| ((i - 1) / 3, i)).collect();
+		assert_eq!(check(100, &tree, heuristic), 1);
+		let mut cycle = path.clone();
+		cycle.push((49, 0));
+		assert_eq!(check(50, &cycle, heuristic), 2);
+		let complete: Vec<(usize, usize)> = (0..8).flat_map(|i| (i + 1..8).map(move |j| (i, j))).collect();
+		assert_eq!(check(8, &complete, heuristic), 7);
+		// Two disjoint triangles and an isolated node.
+		assert_eq!(check(7, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)], heuristic), 2);
+	}
+}
+
+#[test]
+fn treewidth_grid() {
+	// The treewidth of a grid is its side length.
+	let size = 8;
+	let width = check(size * size, &grid(size), Heuristic::MinFill);
+	assert!(width >= size && width <= 2 * size);
+	let width = check(size * size, &grid(size), Heuristic::MinDegree);
+	assert!(width >= size && width <= 2 * size);
+}
+
+#[test]
+fn treewidth_random() {
+	let mut rng = StdRng::seed_from_u64(5);
+	for _ in 0..20 {
+		let size = rng.gen_range(10..60);
+		let edges: Vec<(usize, usize)> = (0..size * 2)
+			.map(|_| (rng.gen_range(0..size), rng.gen_range(0..size)))
+			.filter(|(s, t)| s != t)
+			.collect();
+		check(size, &edges, Heuristic::MinDegree);
+		check(size, &edges, Heuristic::MinFill);
+	}
+}
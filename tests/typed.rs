@@ -0,0 +1,85 @@
+use fastgraph::collections::*;
+use fastgraph::core::{Direction, Empty};
+use fastgraph::typed::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::HashMap;
+
+type Tag = Typed<u8, Empty>;
+
+#[test]
+fn digraph_test_metapath() {
+	let mut rng = StdRng::seed_from_u64(7);
+	let mut g = Digraph::<usize, Tag, Tag>::new();
+	for i in 0..40 {
+		g.add_node(i, Typed::new(rng.gen_range(0..3), Empty));
+	}
+	for _ in 0..160 {
+		g.add_edge(rng.gen_range(0..40), rng.gen_range(0..40), Typed::new(rng.gen_range(0..2), Empty));
+	}
+	let metapath = Metapath::new(Some(0))
+		.step(Direction::Outbound, Some(0), None)
+		.step(Direction::Inbound, None, Some(1))
+		.step(Direction::Both, Some(1), Some(0));
+	let tag = |k: usize| g.get_node(k).unwrap().load().tag;
+	let mut found = 0;
+	for v in 0..40 {
+		let ends = g.follow_metapath(v, &metapath).unwrap();
+		let instances = metapath.instances(&g, v, usize::MAX).unwrap();
+		let mut counts: HashMap<usize, usize> = HashMap::new();
+		for path in instances.iter() {
+			assert!(path.len() == 4 && path[0] == v);
+			assert!(tag(path[0]) == 0 && tag(path[2]) == 1 && tag(path[3]) == 0);
+			assert!(g.get_edge(path[0], path[1]).unwrap().load().tag == 0);
+			assert!(g.get_edge(path[2], path[1]).is_some());
+			let last = g.get_edge(path[2], path[3]).or_else(|| g.get_edge(path[3], path[2])).unwrap();
+			assert!(last.load().tag == 1);
+			*counts.entry(path[3]).or_insert(0) += 1;
+		}
+		assert!(ends == counts);
+		if tag(v) != 0 {
+			assert!(ends.is_empty());
+		}
+		found += instances.len();
+		if instances.len() > 1 {
+			assert!(metapath.instances(&g, v, 1).unwrap() == instances[..1].to_vec());
+		}
+	}
+	assert!(found > 0);
+	assert!(g.follow_metapath(40, &metapath).is_none());
+	// Without steps a metapath ends where it starts.
+	let start: Metapath<u8, u8> = Metapath::new(None);
+	assert!(g.follow_metapath(3, &start).unwrap() == vec![(3, 1)].into_iter().collect());
+}
+
+#[test]
+fn ungraph_test_metapath() {
+	// A chain of alternating tags where the direction of the steps doesn't
+	// matter.
+	let mut g = Ungraph::<usize, Typed<&str, usize>, Typed<&str, Empty>>::new();
+	for i in 0..6 {
+		g.add_node(i, Typed::new(if i % 2 == 0 { "even" } else { "odd" }, i));
+	}
+	for i in 0..5 {
+		g.add_edge(i, i + 1, Typed::new("next", Empty));
+	}
+	let metapath = Metapath::new(Some("even"))
+		.step(Direction::Outbound, Some("next"), Some("odd"))
+		.step(Direction::Inbound, Some("next"), Some("even"));
+	let ends = g.follow_metapath(2, &metapath).unwrap();
+	assert!(ends == vec![(0, 1), (2, 2), (4, 1)].into_iter().collect());
+	assert!(g.get_node(1).unwrap().load().to_string() == "odd:1");
+}
+
+#[test]
+fn digraph_test_tag_under_lock() {
+	// Tags are read under the lock of the data instead of from a copy of it.
+	let mut g = Digraph::<&str, Typed<&str, String>, Typed<&str, String>>::new();
+	g.add_node("a", Typed::new("x", "a".repeat(1000)));
+	g.add_node("b", Typed::new("y", "b".repeat(1000)));
+	g.add_edge("a", "b", Typed::new("to", "e".repeat(1000)));
+	let a = g.get_node("a").unwrap();
+	assert!(a.with(|data| *data.tag()) == "x");
+	assert!(g.get_edge("a", "b").unwrap().with(|data| data.data.len()) == 1000);
+	let path = Metapath::new(Some("x")).step(Direction::Outbound, Some("to"), Some("y"));
+	assert!(path.instances(&g, "a", 10).unwrap() == vec![vec!["a", "b"]]);
+}
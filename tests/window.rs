@@ -0,0 +1,55 @@
+use fastgraph::collections::*;
+use fastgraph::components::connected_components;
+use fastgraph::core::Empty;
+use fastgraph::window::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+type Window = SlidingWindow<u32, Empty, u32, Ungraph<u32, Empty, u32>, (Degrees<u32>, Components<u32>)>;
+
+#[test]
+fn ungraph_test_count_window() {
+	// Random edges with the metrics compared against the graph.
+	let mut rng = StdRng::seed_from_u64(8);
+	let mut window = Window::new(Expiry::Count(30));
+	for i in 0..500 {
+		let (u, v) = (rng.gen_range(0..40), rng.gen_range(0..40));
+		window.push(u, v, i, i as f64);
+		assert!(window.len() <= 30);
+		if i % 50 == 49 {
+			let g = window.graph();
+			assert!(window.len() == 30 && g.edge_count() == 30);
+			for node in g.nodes() {
+				let degree = node.outbound().len() + node.inbound().len();
+				assert!(degree > 0);
+				assert!(window.metric().0.degree(node.key()) == degree);
+			}
+			let components = connected_components(g).len();
+			assert!(window.metric_mut().1.count() == components);
+		}
+	}
+	assert!(window.metric().0.degree(&40) == 0);
+}
+
+#[test]
+fn ungraph_test_time_window() {
+	let mut window = Window::new(Expiry::Time(10.0));
+	window.push(1, 2, 0, 0.0);
+	window.push(2, 3, 0, 4.0);
+	window.push(4, 5, 0, 5.0);
+	assert!(window.metric_mut().1.count() == 2);
+	assert!(window.metric_mut().1.connected(&1, &3));
+
+	// Pushing the edge again in the other orientation renews it.
+	assert!(window.push(2, 1, 7, 8.0) == 0);
+	assert!(window.len() == 3 && window.graph().edge_count() == 3);
+	assert!(window.advance(15.0) == 2);
+	assert!(window.graph().get_node(3).is_none() && window.graph().get_node(4).is_none());
+	assert!(window.metric_mut().1.count() == 1);
+	assert!(!window.metric_mut().1.connected(&1, &3));
+	assert!(window.graph().get_edge(1, 2).unwrap().load() == 7);
+	assert!(window.metric().0.max_degree() == 1);
+
+	assert!(window.advance(18.0) == 1);
+	assert!(window.is_empty() && window.graph().node_count() == 0);
+	assert!(window.metric_mut().1.count() == 0);
+}